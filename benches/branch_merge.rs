@@ -0,0 +1,48 @@
+//! Measures the symbolic execution of a circuit whose branches depend on its inputs.
+//!
+//! Run with `cargo bench --bench branch_merge`.
+
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+use num_bigint_dig::BigInt;
+
+use zkfuzz::executor::symbolic_execution::SymbolicExecutor;
+use zkfuzz::executor::symbolic_setting::get_default_setting_for_symbolic_execution;
+
+#[path = "../tests/utils.rs"]
+#[allow(dead_code)]
+mod utils;
+
+use crate::utils::{execute, prepare_symbolic_library};
+
+const NUM_RUNS: u32 = 10;
+
+fn main() {
+    let path = "./tests/sample/test_branch_heavy.circom".to_string();
+    let prime = BigInt::from_str(
+        "21888242871839275222246405745257275088548364400416034343698204186575808495617",
+    )
+    .unwrap();
+
+    let setting = get_default_setting_for_symbolic_execution(prime.clone(), false);
+
+    let mut total = Duration::ZERO;
+    let mut num_constraints = 0;
+    for _ in 0..NUM_RUNS {
+        let (mut symbolic_library, program_archive) =
+            prepare_symbolic_library(path.clone(), prime.clone());
+        let mut sexe = SymbolicExecutor::new(&mut symbolic_library, &setting);
+        let start = Instant::now();
+        execute(&mut sexe, &program_archive);
+        total += start.elapsed();
+        num_constraints = sexe.cur_state.symbolic_trace.len();
+    }
+
+    println!(
+        "branch_merge: {:?} per run ({} runs, {} trace constraints)",
+        total / NUM_RUNS,
+        NUM_RUNS,
+        num_constraints
+    );
+}
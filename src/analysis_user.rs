@@ -0,0 +1,175 @@
+use std::path::PathBuf;
+use std::rc::Rc;
+
+use colored::Colorize;
+use num_bigint_dig::BigInt;
+use rustc_hash::FxHashSet;
+
+use program_structure::ast::Expression;
+use program_structure::constants::UsefulConstants;
+
+use crate::executor::symbolic_execution::SymbolicExecutor;
+use crate::executor::symbolic_interner::reset_interner;
+use crate::executor::symbolic_setting::get_default_setting_for_symbolic_execution;
+use crate::executor::symbolic_value::{OwnerName, SymbolicLibrary};
+use crate::executor::template_semantics::LessThanSemantics;
+use crate::mutator::detector::{DetectorRegistry, Finding, DEFAULT_DETECTORS};
+use crate::mutator::input_spec::InputSpec;
+use crate::mutator::utils::BaseVerificationConfig;
+use crate::parser_user::parse_source;
+use crate::type_analysis_user::{analyse_project, gather_input_shapes};
+
+/// The options of `analyze_source`.
+pub struct SourceAnalysisOptions {
+    /// Name of the curve whose prime is used (e.g. `bn128`).
+    pub prime: String,
+    /// Directories against which the includes that the resolver does not provide are resolved.
+    pub link_libraries: Vec<PathBuf>,
+    /// Comma-separated detectors to run, in the format accepted by `DetectorRegistry::select`.
+    pub detectors: String,
+}
+
+impl Default for SourceAnalysisOptions {
+    fn default() -> Self {
+        SourceAnalysisOptions {
+            prime: String::from("bn128"),
+            link_libraries: Vec::new(),
+            detectors: String::from(DEFAULT_DETECTORS),
+        }
+    }
+}
+
+/// Analyzes a circuit given as a string and returns the findings of the detectors, so that
+/// tooling such as a language server or a web playground can check an unsaved buffer without
+/// writing it to the files of the project.
+///
+/// The circuit is parsed with `parse_source`, type-checked, and its main template is executed
+/// symbolically before the detectors selected by `options.detectors` run. The errors of the
+/// parser and of the type checker, and the components that are never executed, are printed to
+/// stderr.
+///
+/// # Parameters
+/// - `source`: A circuit with a main component.
+/// - `resolver`: Returns the contents of the file of an `include` directive, given its path as
+///   written, or `None` to resolve it against `options.link_libraries`.
+/// - `registry`: The available detectors, including the ones registered by the caller.
+/// - `options`: The options of the analysis.
+///
+/// # Returns
+/// The findings of the selected detectors, in the order they are reported.
+pub fn analyze_source<F>(
+    source: &str,
+    resolver: F,
+    registry: &DetectorRegistry,
+    options: &SourceAnalysisOptions,
+) -> Result<Vec<Finding>, ()>
+where
+    F: Fn(&str) -> Option<String>,
+{
+    let detectors = registry
+        .select(&options.detectors)
+        .map_err(|name| eoutln!("{} {}", "Unknown detector:".red(), name))?;
+    let mut program_archive =
+        parse_source(source, resolver, &options.prime, &options.link_libraries)?;
+    analyse_project(&mut program_archive)?;
+
+    // A language server analyses a buffer on every edit, so the names of the previous analyses
+    // are released
+    reset_interner();
+    let mut symbolic_library = SymbolicLibrary::default();
+    symbolic_library.register_template_semantics(Rc::new(LessThanSemantics));
+    let whitelist = FxHashSet::default();
+    let mut template_names = program_archive
+        .templates
+        .keys()
+        .cloned()
+        .collect::<Vec<_>>();
+    template_names.sort();
+    for name in template_names {
+        let template = &program_archive.templates[&name];
+        symbolic_library.register_template(
+            name.clone(),
+            template.get_body(),
+            template.get_name_of_params(),
+            &whitelist,
+            template.is_custom_gate(),
+        );
+    }
+    let mut function_names = program_archive
+        .functions
+        .keys()
+        .cloned()
+        .collect::<Vec<_>>();
+    function_names.sort();
+    for name in function_names {
+        let function = &program_archive.functions[&name];
+        symbolic_library.register_function(
+            name.clone(),
+            function.get_body().clone(),
+            function.get_name_of_params(),
+        );
+    }
+
+    let (id, args) = match &program_archive.initial_template_call {
+        Expression::Call { id, args, .. } => (id, args),
+        _ => return Result::Err(eoutln!("{}", "Cannot Find Main Call".red())),
+    };
+    let template_param_names = program_archive.templates[id].get_name_of_params().clone();
+    let prime = UsefulConstants::new(&options.prime).get_p().clone();
+    let setting = get_default_setting_for_symbolic_execution(prime.clone(), false);
+    let mut sexe = SymbolicExecutor::new(&mut symbolic_library, &setting);
+
+    sexe.symbolic_library
+        .name2id
+        .insert("main".to_string(), sexe.symbolic_library.name2id.len());
+    sexe.symbolic_library
+        .id2name
+        .insert(sexe.symbolic_library.name2id["main"], "main".to_string());
+    sexe.cur_state.add_owner(&OwnerName {
+        id: sexe.symbolic_library.name2id["main"],
+        counter: 0,
+        access: None,
+    });
+    sexe.cur_state
+        .set_template_id(sexe.symbolic_library.name2id[id]);
+    sexe.feed_arguments(&template_param_names, args);
+    let body = sexe.symbolic_library.template_library[&sexe.symbolic_library.name2id[id]]
+        .body
+        .clone();
+    sexe.execute(&body, 0);
+    sexe.record_incomplete_components();
+    if !sexe.incomplete_components.is_empty() {
+        eoutln!(
+            "{} {}",
+            "🧩 Incomplete Components:".red(),
+            sexe.incomplete_components.len()
+        );
+        for component in &sexe.incomplete_components {
+            eoutln!("  ├─ {}", component.message());
+        }
+        return Result::Err(());
+    }
+
+    let base_config = BaseVerificationConfig {
+        target_template_name: id.to_string(),
+        prime: prime,
+        range: BigInt::from(100),
+        quick_mode: false,
+        heuristics_mode: false,
+        progress_interval: 10000,
+        template_param_names: template_param_names,
+        template_param_values: args.clone(),
+        check_output_tags: false,
+        max_counterexamples: 1,
+        input_shapes: gather_input_shapes(sexe.symbolic_library, id, &sexe.id2dimensions),
+        input_spec: InputSpec::default(),
+    };
+    let symbolic_trace = sexe.cur_state.symbolic_trace.clone();
+    let side_constraints = sexe.cur_state.side_constraints.clone();
+    Ok(detectors
+        .iter()
+        .flat_map(|detector| {
+            detector.run(&mut sexe, &symbolic_trace, &side_constraints, &base_config)
+        })
+        .collect())
+}
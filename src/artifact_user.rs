@@ -0,0 +1,279 @@
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use colored::Colorize;
+use log::warn;
+use num_bigint_dig::BigInt;
+use rustc_hash::FxHashMap;
+use serde_json::{json, Value};
+
+use crate::executor::constraint_store::ConstraintStore;
+use crate::executor::symbolic_value::SymbolicLibrary;
+use crate::mutator::formal_export::{collect_constraint_system, ExportFormat};
+use crate::mutator::utils::{BaseVerificationConfig, CounterExample};
+use crate::mutator::witness_export::{save_witness_generator_files, AssignmentFormat};
+use crate::stats::symbolic_stats::{component_statistics_csv, ConstraintStatistics};
+
+/// A file written by a run, listed in the artifacts manifest.
+pub struct Artifact {
+    /// What the file contains, e.g. `counterexample` or `summary`.
+    pub kind: &'static str,
+    pub path: String,
+}
+
+/// Names the files written by a run and lists them in the artifacts manifest.
+///
+/// The files of the `index`-th counterexample found by `source` (a detector, or the search mode
+/// if the search found it) are named `<out_dir>/<circuit>_<source>_<index>_<suffix>`, where
+/// `circuit` is the file stem of the analysed circuit. Running the same analysis again therefore
+/// overwrites its files instead of adding new ones, and pipelines can collect them by name or
+/// from `<out_dir>/<circuit>_manifest.json`.
+pub struct ArtifactWriter {
+    out_dir: PathBuf,
+    circuit: String,
+    /// Whether `--out_dir` was given, in which case the manifest is written.
+    write_manifest: bool,
+    pub artifacts: Vec<Artifact>,
+}
+
+impl ArtifactWriter {
+    /// Creates a writer for the analysis of `input_file`, whose files are written to `out_dir`,
+    /// or next to `input_file` if `out_dir` is `none`. `out_dir` is created if it does not exist.
+    pub fn new(out_dir: &str, input_file: &Path) -> io::Result<Self> {
+        let write_manifest = out_dir != "none";
+        let out_dir = if write_manifest {
+            fs::create_dir_all(out_dir)?;
+            PathBuf::from(out_dir)
+        } else {
+            input_file
+                .parent()
+                .map(Path::to_path_buf)
+                .unwrap_or_default()
+        };
+        let circuit = input_file
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_else(|| String::from("circuit"));
+        Ok(ArtifactWriter {
+            out_dir,
+            circuit,
+            write_manifest,
+            artifacts: Vec::new(),
+        })
+    }
+
+    /// Returns the common prefix of the files of the `index`-th counterexample found by `source`,
+    /// e.g. `out/test_vuln_iszero_ga_1`. The characters of `source` other than alphanumerics,
+    /// `-`, and `_` (e.g. the `:` of `custom:<name>`) are replaced with `_`.
+    pub fn prefix(&self, source: &str, index: usize) -> String {
+        let source: String = source
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+        self.out_dir
+            .join(format!("{}_{}_{}", self.circuit, source, index))
+            .to_string_lossy()
+            .to_string()
+    }
+
+    /// Records a file written by the run.
+    pub fn record(&mut self, kind: &'static str, path: &str) {
+        self.artifacts.push(Artifact {
+            kind,
+            path: path.to_string(),
+        });
+    }
+
+    /// Returns the path of the manifest, `<out_dir>/<circuit>_manifest.json`.
+    pub fn manifest_path(&self) -> String {
+        self.out_dir
+            .join(format!("{}_manifest.json", self.circuit))
+            .to_string_lossy()
+            .to_string()
+    }
+
+    /// Returns the manifest of the run, listing the recorded files in the order they were
+    /// written.
+    pub fn to_json(&self, target_path: &str, verdict: &str) -> Value {
+        json!({
+            "target_path": target_path,
+            "out_dir": self.out_dir.to_string_lossy(),
+            "verdict": verdict,
+            "artifacts": self
+                .artifacts
+                .iter()
+                .map(|artifact| json!({"kind": artifact.kind, "path": artifact.path}))
+                .collect::<Vec<_>>(),
+        })
+    }
+
+    /// Writes the trace and side constraints to `path` (`--path_to_constraints`) as JSON.
+    pub fn save_constraints(
+        &mut self,
+        path: &str,
+        trace_store: &dyn ConstraintStore,
+        side_store: &dyn ConstraintStore,
+        id2name: &FxHashMap<usize, String>,
+    ) {
+        let constraints = json!({
+            "trace_constraints": trace_store
+                .iter()
+                .map(|c| c.lookup_fmt(id2name))
+                .collect::<Vec<_>>(),
+            "side_constraints": side_store
+                .iter()
+                .map(|c| c.lookup_fmt(id2name))
+                .collect::<Vec<_>>(),
+        });
+        let mut file = File::create(path).expect("Unable to create file");
+        file.write_all(constraints.to_string().as_bytes())
+            .expect("Unable to write data");
+        self.record("constraints", path);
+    }
+
+    /// Exports the side constraints of `main_template_name` to `path` (`--export_constraints`), in
+    /// the format given by its extension (see `ExportFormat`).
+    pub fn export_constraints(
+        &mut self,
+        path: &str,
+        side_store: &dyn ConstraintStore,
+        symbolic_library: &SymbolicLibrary,
+        main_template_name: &str,
+        prime: &BigInt,
+    ) {
+        let system =
+            collect_constraint_system(side_store, symbolic_library, main_template_name, prime);
+        let content = match ExportFormat::from_path(path) {
+            ExportFormat::Smt2 => system.to_smt2(),
+            ExportFormat::Json => system.to_json().to_string(),
+        };
+        eoutln!(
+            "{} {}",
+            "💾 Saving the exported constraints to:",
+            path.cyan()
+        );
+        let mut file = File::create(path).expect("Unable to create file");
+        file.write_all(content.as_bytes())
+            .expect("Unable to write data");
+        self.record("exported_constraints", path);
+        if !system.unsupported.is_empty() {
+            eoutln!(
+                "{} {} constraint(s) outside the field arithmetic are left out of the export",
+                "⚠️ Unsupported Constraints:".yellow(),
+                system.unsupported.len()
+            );
+        }
+    }
+
+    /// Saves the counterexamples found by `source` as JSON, together with the input and the
+    /// script of the witness generator of the first one. The file of the first counterexample
+    /// also includes `auxiliary_result`, the details of the search that found it.
+    ///
+    /// # Parameters
+    /// - `meta`: The metadata of the run, included in the file of every counterexample.
+    ///
+    /// # Returns
+    /// The path of the file of the first counterexample.
+    pub fn save_counter_examples(
+        &mut self,
+        source: &str,
+        counter_examples: &[CounterExample],
+        meta: &FxHashMap<String, String>,
+        auxiliary_result: Value,
+        symbolic_library: &SymbolicLibrary,
+        base_config: &BaseVerificationConfig,
+        id2dimensions: &FxHashMap<usize, Vec<usize>>,
+        assignment_format: &AssignmentFormat,
+    ) -> String {
+        let ce = &counter_examples[0];
+        let mut json_output =
+            ce.to_json_with_meta(&symbolic_library.id2name, meta, assignment_format);
+        json_output["8_auxiliary_result"] = auxiliary_result;
+
+        let prefix = self.prefix(source, 1);
+        let file_path = format!("{}_counterexample.json", prefix);
+        eoutln!("{} {}", "💾 Saving the output to:", file_path.cyan(),);
+
+        let mut file = File::create(&file_path).expect("Unable to create file");
+        let json_string = serde_json::to_string_pretty(&json_output).unwrap();
+        file.write_all(json_string.as_bytes())
+            .expect("Unable to write data");
+        self.record("counterexample", &file_path);
+
+        match save_witness_generator_files(
+            &prefix,
+            ce,
+            symbolic_library,
+            base_config,
+            id2dimensions,
+        ) {
+            Ok((input_json_path, script_path)) => {
+                eoutln!(
+                    "{} {} {}",
+                    "💾 Saving the witness generator input to:",
+                    input_json_path.cyan(),
+                    script_path.cyan()
+                );
+                self.record("witness_input", &input_json_path);
+                self.record("witness_script", &script_path);
+            }
+            Err(e) => warn!("Unable to save the witness generator input: {}", e),
+        }
+
+        for (i, extra_ce) in counter_examples.iter().enumerate().skip(1) {
+            let extra_file_path = format!("{}_counterexample.json", self.prefix(source, i + 1));
+            eoutln!("{} {}", "💾 Saving the output to:", extra_file_path.cyan(),);
+            let extra_json_output =
+                extra_ce.to_json_with_meta(&symbolic_library.id2name, meta, assignment_format);
+            let mut file = File::create(&extra_file_path).expect("Unable to create file");
+            let json_string = serde_json::to_string_pretty(&extra_json_output).unwrap();
+            file.write_all(json_string.as_bytes())
+                .expect("Unable to write data");
+            self.record("counterexample", &extra_file_path);
+        }
+        file_path
+    }
+
+    /// Writes the statistics of each component to `path` (`--component_stats_csv`) as CSV.
+    pub fn save_component_stats(
+        &mut self,
+        path: &str,
+        trace_stats: &ConstraintStatistics,
+        side_stats: &ConstraintStatistics,
+        id2name: &FxHashMap<usize, String>,
+        sort_by: &str,
+    ) {
+        eoutln!(
+            "{} {}",
+            "💾 Saving the per-component stats to:",
+            path.cyan()
+        );
+        let mut file = File::create(path).expect("Unable to create file");
+        file.write_all(
+            component_statistics_csv(trace_stats, side_stats, id2name, sort_by).as_bytes(),
+        )
+        .expect("Unable to write data");
+        self.record("component_stats", path);
+    }
+
+    /// Writes the manifest if `--out_dir` was given.
+    ///
+    /// # Returns
+    /// The path of the manifest, or `None` if it is not written.
+    pub fn write_manifest(&self, target_path: &str, verdict: &str) -> io::Result<Option<String>> {
+        if !self.write_manifest {
+            return Ok(None);
+        }
+        let path = self.manifest_path();
+        let manifest = serde_json::to_string_pretty(&self.to_json(target_path, verdict))?;
+        fs::write(&path, manifest)?;
+        Ok(Some(path))
+    }
+}
@@ -0,0 +1,555 @@
+use std::env;
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time;
+
+use colored::Colorize;
+use serde_json::{json, Value};
+
+use crate::input_user::Input;
+use crate::mutator::clustering::{cluster_findings, FindingSignature};
+use crate::mutator::severity::{format_severity_counts, Severity};
+use crate::parser_user::collect_circom_files;
+
+/// Summary of the analysis of a single circuit in batch mode.
+pub struct BatchResult {
+    pub target_path: String,
+    pub status: String,
+    pub verdict: String,
+    pub execution_time_ms: u128,
+    pub num_trace_constraints: String,
+    pub num_side_constraints: String,
+    pub counterexample_path: String,
+    /// The signature of each counterexample reported for the circuit.
+    pub finding_signatures: Vec<FindingSignature>,
+}
+
+impl BatchResult {
+    pub fn csv_header() -> &'static str {
+        "target_path,status,verdict,execution_time_ms,num_trace_constraints,num_side_constraints,counterexample_path"
+    }
+
+    pub fn to_csv_row(&self) -> String {
+        [
+            escape_csv_field(&self.target_path),
+            escape_csv_field(&self.status),
+            escape_csv_field(&self.verdict),
+            self.execution_time_ms.to_string(),
+            escape_csv_field(&self.num_trace_constraints),
+            escape_csv_field(&self.num_side_constraints),
+            escape_csv_field(&self.counterexample_path),
+        ]
+        .join(",")
+    }
+}
+
+/// Summary of the analysis of a single entry given to `--entry`.
+pub struct EntryResult {
+    pub entry_path: String,
+    pub main_template_name: String,
+    pub status: String,
+    pub verdict: String,
+    pub execution_time_ms: u128,
+    pub num_trace_constraints: usize,
+    pub num_side_constraints: usize,
+    /// The findings of the detectors without a counterexample, as `(severity, category, message)`.
+    pub findings: Vec<(Severity, &'static str, String)>,
+    pub counter_example: Option<Value>,
+    /// The signature of the counterexample reported for the entry, if any.
+    pub finding_signatures: Vec<FindingSignature>,
+    /// The most severe finding of the entry, including its counterexample.
+    pub max_severity: Option<Severity>,
+}
+
+impl EntryResult {
+    /// Returns the result of an entry whose analysis failed, e.g. because some of its components
+    /// are incomplete.
+    pub fn failed(entry_path: &Path, main_template_name: &str) -> Self {
+        EntryResult {
+            entry_path: entry_path.to_string_lossy().to_string(),
+            main_template_name: main_template_name.to_string(),
+            status: "error".to_string(),
+            verdict: "".to_string(),
+            execution_time_ms: 0,
+            num_trace_constraints: 0,
+            num_side_constraints: 0,
+            findings: Vec::new(),
+            counter_example: None,
+            finding_signatures: Vec::new(),
+            max_severity: None,
+        }
+    }
+
+    pub fn to_json(&self, min_severity: Severity) -> Value {
+        json!({
+            "entry_path": self.entry_path,
+            "main_template_name": self.main_template_name,
+            "status": self.status,
+            "verdict": self.verdict,
+            "execution_time_ms": self.execution_time_ms as u64,
+            "num_trace_constraints": self.num_trace_constraints,
+            "num_side_constraints": self.num_side_constraints,
+            "findings": self
+                .findings
+                .iter()
+                .filter(|(severity, _, _)| *severity >= min_severity)
+                .map(|(severity, category, message)| json!({
+                    "severity": severity.to_string(),
+                    "category": category,
+                    "message": message,
+                }))
+                .collect::<Vec<_>>(),
+            "counter_example": self.counter_example,
+            "max_severity": self.max_severity.map(|severity| severity.to_string()),
+            "finding_signatures": self.finding_signatures,
+        })
+    }
+}
+
+fn escape_csv_field(field: &str) -> String {
+    if field.contains(',') || field.contains('"') || field.contains('\n') {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// Builds the command-line arguments that are forwarded to each per-file run.
+fn forwarded_args(user_input: &Input) -> Vec<String> {
+    let mut args = vec![
+        "--prime".to_string(),
+        user_input.prime(),
+        "--debug_prime".to_string(),
+        user_input.debug_prime(),
+        "--search_mode".to_string(),
+        user_input.search_mode(),
+        "--heuristics_range".to_string(),
+        user_input.heuristics_range(),
+        "--heuristics_stages".to_string(),
+        user_input.heuristics_stages(),
+        "--path_to_mutation_setting".to_string(),
+        user_input.path_to_mutation_setting(),
+        "--path_to_whitelist".to_string(),
+        user_input.path_to_whitelist(),
+        "--input_spec".to_string(),
+        user_input.input_spec(),
+        "--corpus".to_string(),
+        user_input.corpus(),
+        "--target_signal".to_string(),
+        user_input.target_signal(),
+        "--max_index_case_splits".to_string(),
+        user_input.max_index_case_splits(),
+        "--function_summary_capacity".to_string(),
+        user_input.function_summary_capacity(),
+        "--max_call_depth".to_string(),
+        user_input.max_call_depth(),
+        "--constraint_store".to_string(),
+        user_input.constraint_store(),
+        "--simplify".to_string(),
+        user_input.simplify(),
+        "--assert_search_iterations".to_string(),
+        user_input.assert_search_iterations(),
+        "--concolic_max_paths".to_string(),
+        user_input.concolic_max_paths(),
+        "--validate_with_circom".to_string(),
+        user_input.validate_with_circom(),
+        "--inspect_with_circom".to_string(),
+        user_input.inspect_with_circom(),
+        "--groth16_zkey".to_string(),
+        user_input.groth16_zkey(),
+        "--snarkjs".to_string(),
+        user_input.snarkjs(),
+        "--baseline".to_string(),
+        user_input.baseline(),
+        "--max_counterexamples".to_string(),
+        user_input.max_counterexamples(),
+        "--auto_time_budget".to_string(),
+        user_input.auto_time_budget(),
+        "--template_timeouts".to_string(),
+        user_input.template_timeouts(),
+        "--compare_prime".to_string(),
+        user_input.compare_prime(),
+        "--prime_samples".to_string(),
+        user_input.prime_samples(),
+        "--cache_dir".to_string(),
+        user_input.cache_dir(),
+        "--detectors".to_string(),
+        user_input.detectors(),
+        "--log_level".to_string(),
+        user_input.log_level(),
+        "--seed".to_string(),
+        user_input.seed(),
+        "--save_output".to_string(),
+        "--ce_format".to_string(),
+        user_input.ce_format(),
+        "--out_dir".to_string(),
+        user_input.out_dir(),
+    ];
+    if user_input.link() != "none" {
+        args.push("--link".to_string());
+        args.push(user_input.link());
+    }
+    for lib in user_input.get_link_libraries() {
+        args.push("-l".to_string());
+        args.push(lib.to_string_lossy().to_string());
+    }
+    if user_input.constraint_assert_dissabled_flag {
+        args.push("--constraint_assert_dissabled".to_string());
+    }
+    if user_input.lessthan_dissabled_flag {
+        args.push("--lessthan_dissabled".to_string());
+    }
+    if user_input.flag_symbolic_template_params {
+        args.push("--symbolic_template_params".to_string());
+    }
+    if user_input.flag_check_output_tags {
+        args.push("--check_output_tags".to_string());
+    }
+    if user_input.flag_check_division_by_zero {
+        args.push("--check_division_by_zero".to_string());
+    }
+    if user_input.flag_overflow_audit {
+        args.push("--overflow_audit".to_string());
+    }
+    if user_input.flag_free_witnesses {
+        args.push("--free_witnesses".to_string());
+    }
+    if user_input.flag_suggest_repair {
+        args.push("--suggest_repair".to_string());
+    }
+    if user_input.flag_replay_logs {
+        args.push("--replay_logs".to_string());
+    }
+    if user_input.flag_no_cache {
+        args.push("--no-cache".to_string());
+    }
+    if user_input.flag_plain {
+        args.push("--plain".to_string());
+    }
+    args
+}
+
+/// Analyses a single circuit in a child process, killing it once `timeout` is exceeded.
+///
+/// # Parameters
+/// - `exe`: Path to the zkFuzz executable.
+/// - `target`: Path to the circuit to analyse.
+/// - `args`: Additional arguments forwarded to the child process.
+/// - `summary_path`: Path where the child process writes the JSON summary of its run.
+/// - `timeout`: Maximum wall-clock time for the run. `None` means no limit.
+///
+/// # Returns
+/// A `BatchResult` describing the outcome. The status is one of `ok`, `timeout`, or `error`.
+fn run_single_file(
+    exe: &Path,
+    target: &Path,
+    args: &[String],
+    summary_path: &Path,
+    timeout: Option<time::Duration>,
+) -> BatchResult {
+    let _ = fs::remove_file(summary_path);
+    let start_time = time::Instant::now();
+    let mut result = BatchResult {
+        target_path: target.to_string_lossy().to_string(),
+        status: "error".to_string(),
+        verdict: "".to_string(),
+        execution_time_ms: 0,
+        num_trace_constraints: "".to_string(),
+        num_side_constraints: "".to_string(),
+        counterexample_path: "".to_string(),
+        finding_signatures: Vec::new(),
+    };
+
+    let child = Command::new(exe)
+        .arg(target)
+        .args(args)
+        .arg("--path_to_summary")
+        .arg(summary_path)
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn();
+    let mut child = match child {
+        Ok(child) => child,
+        Err(_) => return result,
+    };
+
+    let exit_status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break Some(status),
+            Ok(None) => {
+                if timeout.map_or(false, |t| start_time.elapsed() > t) {
+                    let _ = child.kill();
+                    let _ = child.wait();
+                    break None;
+                }
+                thread::sleep(time::Duration::from_millis(100));
+            }
+            Err(_) => break None,
+        }
+    };
+    result.execution_time_ms = start_time.elapsed().as_millis();
+
+    match exit_status {
+        None => {
+            result.status = "timeout".to_string();
+        }
+        Some(status) if status.success() => {
+            result.status = "ok".to_string();
+            if let Ok(summary) = fs::read_to_string(summary_path) {
+                if let Ok(summary) = serde_json::from_str::<Value>(&summary) {
+                    let field = |key: &str| match &summary[key] {
+                        Value::String(s) => s.clone(),
+                        Value::Null => "".to_string(),
+                        v => v.to_string(),
+                    };
+                    result.verdict = field("verdict");
+                    result.num_trace_constraints = field("num_trace_constraints");
+                    result.num_side_constraints = field("num_side_constraints");
+                    result.counterexample_path = field("counterexample_path");
+                    result.finding_signatures =
+                        serde_json::from_value(summary["finding_signatures"].clone())
+                            .unwrap_or_default();
+                }
+            }
+        }
+        Some(_) => {}
+    }
+    let _ = fs::remove_file(summary_path);
+    result
+}
+
+/// Runs the configured analysis on every `.circom` file under `--input_dir` and writes one CSV row
+/// per circuit to `--output_csv`.
+///
+/// Each circuit is analysed in a separate child process so that a per-file timeout can be enforced
+/// and a panic in one circuit does not abort the whole batch.
+pub fn run_batch(user_input: &Input) -> Result<(), ()> {
+    let exe = env::current_exe().map_err(|e| eoutln!("{} {}", "Cannot locate zkfuzz:".red(), e))?;
+    let files = collect_circom_files(Path::new(&user_input.input_dir()));
+    let timeout_secs: u64 = user_input.timeout_per_file().parse().unwrap_or(0);
+    let timeout = if timeout_secs == 0 {
+        None
+    } else {
+        Some(time::Duration::from_secs(timeout_secs))
+    };
+    let args = forwarded_args(user_input);
+    let summary_path =
+        env::temp_dir().join(format!("zkfuzz_batch_summary_{}.json", std::process::id()));
+
+    let mut findings = Vec::new();
+    let mut csv_file = File::create(user_input.output_csv())
+        .map_err(|e| eoutln!("{} {}", "Unable to create the output CSV:".red(), e))?;
+    writeln!(csv_file, "{}", BatchResult::csv_header()).map_err(|_| ())?;
+
+    eoutln!(
+        "{} {} circuits found in {}",
+        "📂 Batch Mode:".green(),
+        files.len(),
+        user_input.input_dir().cyan()
+    );
+    for (i, target) in files.iter().enumerate() {
+        eout!(
+            "{} [{}/{}] {} ... ",
+            "🔎".green(),
+            i + 1,
+            files.len(),
+            target.display()
+        );
+        let result = run_single_file(&exe, target, &args, &summary_path, timeout);
+        eoutln!(
+            "{} ({} ms)",
+            if result.status == "ok" {
+                result.verdict.normal()
+            } else {
+                result.status.yellow()
+            },
+            result.execution_time_ms
+        );
+        writeln!(csv_file, "{}", result.to_csv_row()).map_err(|_| ())?;
+        csv_file.flush().map_err(|_| ())?;
+        for signature in &result.finding_signatures {
+            findings.push((
+                signature.clone(),
+                json!({
+                    "target_path": result.target_path,
+                    "counterexample_path": result.counterexample_path,
+                }),
+            ));
+        }
+    }
+
+    let num_findings = findings.len();
+    let clusters = cluster_findings(findings);
+    eoutln!(
+        "{} {} counterexamples in {} clusters",
+        "🗂️ Root Causes:".yellow(),
+        num_findings,
+        clusters.len()
+    );
+    for cluster in &clusters {
+        eoutln!(
+            "  ├─ [{}] {} {} {} : {} (e.g. {})",
+            cluster.signature.structural_hash(),
+            cluster.signature.kind,
+            cluster.signature.signal,
+            cluster.signature.constraint,
+            cluster.count,
+            cluster.representative["target_path"]
+                .as_str()
+                .unwrap_or_default()
+        );
+    }
+    if user_input.output_clusters() != "none" {
+        eoutln!(
+            "{} {}",
+            "💾 Saving the clusters of counterexamples to:",
+            user_input.output_clusters().cyan()
+        );
+        let report = json!(clusters.iter().map(|c| c.to_json()).collect::<Vec<_>>());
+        fs::write(user_input.output_clusters(), report.to_string())
+            .map_err(|e| eoutln!("{} {}", "Unable to write the clusters:".red(), e))?;
+    }
+
+    eoutln!(
+        "{} {}",
+        "💾 Saving the batch results to:",
+        user_input.output_csv().cyan()
+    );
+    Ok(())
+}
+
+/// Prints the results of the entries given to `--entry` and writes them to `--output_entries`,
+/// together with their counterexamples grouped by root cause as in the batch mode.
+///
+/// Only the findings of at least `min_severity` are reported.
+pub fn report_entries(
+    user_input: &Input,
+    results: &[EntryResult],
+    min_severity: Severity,
+) -> Result<(), ()> {
+    eoutln!("{}", "══════════════════════════════════".green());
+    eoutln!("{} {}", "🚪 Entries:".green(), results.len());
+    let mut findings = Vec::new();
+    for result in results {
+        let severities: Vec<Severity> = result
+            .findings
+            .iter()
+            .map(|(severity, _, _)| *severity)
+            .filter(|severity| *severity >= min_severity)
+            .collect();
+        eoutln!(
+            "  ├─ {} ({}): {} ({} ms){}",
+            result.entry_path,
+            result.main_template_name.cyan(),
+            if result.status == "ok" {
+                result.verdict.normal()
+            } else {
+                result.status.yellow()
+            },
+            result.execution_time_ms,
+            if severities.is_empty() {
+                "".to_string()
+            } else {
+                format!(
+                    ", {} findings ({})",
+                    severities.len(),
+                    format_severity_counts(severities)
+                )
+            }
+        );
+        for signature in &result.finding_signatures {
+            findings.push((
+                signature.clone(),
+                json!({
+                    "target_path": result.entry_path,
+                    "main_template_name": result.main_template_name,
+                }),
+            ));
+        }
+    }
+
+    // The entries share their templates, so a bug in a shared template is reported once
+    let clusters = cluster_findings(findings);
+    if !clusters.is_empty() {
+        eoutln!("{} {} clusters", "🗂️ Root Causes:".yellow(), clusters.len());
+        for cluster in &clusters {
+            eoutln!(
+                "  ├─ [{}] {} {} {} : {} (e.g. {})",
+                cluster.signature.structural_hash(),
+                cluster.signature.kind,
+                cluster.signature.signal,
+                cluster.signature.constraint,
+                cluster.count,
+                cluster.representative["target_path"]
+                    .as_str()
+                    .unwrap_or_default()
+            );
+        }
+    }
+
+    let report = json!({
+        "entries": results
+            .iter()
+            .map(|result| result.to_json(min_severity))
+            .collect::<Vec<_>>(),
+        "clusters": clusters.iter().map(|c| c.to_json()).collect::<Vec<_>>(),
+    });
+    eoutln!(
+        "{} {}",
+        "💾 Saving the results of the entries to:",
+        user_input.output_entries().cyan()
+    );
+    fs::write(user_input.output_entries(), report.to_string())
+        .map_err(|e| eoutln!("{} {}", "Unable to write the entries:".red(), e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_escape_csv_field() {
+        assert_eq!(escape_csv_field("main.circom"), "main.circom");
+        assert_eq!(escape_csv_field(""), "");
+        assert_eq!(escape_csv_field("a,b.circom"), "\"a,b.circom\"");
+        assert_eq!(
+            escape_csv_field("say \"hi\".circom"),
+            "\"say \"\"hi\"\".circom\""
+        );
+        assert_eq!(escape_csv_field("line\nbreak"), "\"line\nbreak\"");
+        assert_eq!(escape_csv_field("a,\"b\"\nc"), "\"a,\"\"b\"\"\nc\"");
+    }
+
+    #[test]
+    fn test_to_csv_row() {
+        let result = BatchResult {
+            target_path: "circuits/a,b.circom".to_string(),
+            status: "ok".to_string(),
+            verdict: "UNDER-CONSTRAINED".to_string(),
+            execution_time_ms: 42,
+            num_trace_constraints: "12".to_string(),
+            num_side_constraints: "3".to_string(),
+            counterexample_path: "ce \"1\".json".to_string(),
+            finding_signatures: Vec::new(),
+        };
+        assert_eq!(
+            BatchResult::csv_header().split(',').collect::<Vec<_>>(),
+            vec![
+                "target_path",
+                "status",
+                "verdict",
+                "execution_time_ms",
+                "num_trace_constraints",
+                "num_side_constraints",
+                "counterexample_path",
+            ]
+        );
+        assert_eq!(
+            result.to_csv_row(),
+            "\"circuits/a,b.circom\",ok,UNDER-CONSTRAINED,42,12,3,\"ce \"\"1\"\".json\""
+        );
+    }
+}
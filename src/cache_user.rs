@@ -0,0 +1,104 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::executor::structural_hash::StableHasher;
+use crate::parser_user::read_included_sources;
+use crate::VERSION;
+
+/// The directory of the result cache, relative to the current directory (`--cache_dir`).
+pub const DEFAULT_CACHE_DIR: &str = ".zkfuzz_cache";
+
+/// The options whose values do not affect the result of the analysis, i.e., where the cache and
+/// the reports are written.
+const UNKEYED_OPTIONS: [&str; 3] = ["--cache_dir", "--path_to_summary", "--report_html"];
+
+/// The result of an analysis, stored in the cache so that it can be reported again without
+/// executing the circuit.
+#[derive(Serialize, Deserialize)]
+pub struct CachedResult {
+    pub verdict: String,
+    /// The lines of the report, without its execution time.
+    pub report: Vec<String>,
+    pub execution_time_ms: u64,
+    /// The symbolic trace, formatted with `lookup_fmt`.
+    pub symbolic_trace: Vec<String>,
+    /// The summary of `--path_to_summary`, including the findings.
+    pub summary: Value,
+    /// The report of `--report_html`, if it was requested.
+    pub report_html: Option<String>,
+}
+
+/// An entry of the result cache, keyed by the preprocessed program and the analysis options.
+pub struct ResultCache {
+    path: PathBuf,
+    pub key: String,
+}
+
+impl ResultCache {
+    /// Locates the entry of the analysis of `input_file` in the cache directory `dir`.
+    ///
+    /// The key hashes the version of zkFuzz, the sources of `input_file` and of the files it
+    /// includes, and the command-line arguments `args` other than `--no-cache` and
+    /// `UNKEYED_OPTIONS`. The contents of the files named by the arguments (e.g. `--input_spec`) are
+    /// hashed as well, so that editing them invalidates the entry, while editing a file the
+    /// circuit does not include does not. The key is computed with `StableHasher`, so that an entry
+    /// written by one build is found by another.
+    pub fn new(
+        dir: &Path,
+        input_file: &Path,
+        link_libraries: &[PathBuf],
+        args: &[String],
+    ) -> io::Result<Self> {
+        let mut hasher = StableHasher::default();
+        hasher.write_str(VERSION);
+        for (path, source) in read_included_sources(input_file, link_libraries)? {
+            hasher.write_str(&path.to_string_lossy());
+            hasher.write_str(&source);
+        }
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            let option = arg.split('=').next().unwrap_or(arg);
+            if UNKEYED_OPTIONS.contains(&option) {
+                if !arg.contains('=') {
+                    args.next();
+                }
+                continue;
+            }
+            if option == "--no-cache" {
+                continue;
+            }
+            hasher.write_str(arg);
+            if Path::new(arg).is_file() {
+                let content = fs::read(arg)?;
+                hasher.write_u64(content.len() as u64);
+                hasher.write_bytes(&content);
+            }
+        }
+        let key = format!("{:016x}", hasher.finish());
+        Ok(ResultCache {
+            path: dir.join(format!("{}.json", key)),
+            key: key,
+        })
+    }
+
+    /// Returns the cached result, or `None` if there is none or it cannot be read.
+    pub fn load(&self) -> Option<CachedResult> {
+        let content = fs::read_to_string(&self.path).ok()?;
+        serde_json::from_str(&content).ok()
+    }
+
+    pub fn store(&self, result: &CachedResult) -> io::Result<()> {
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(&self.path, serde_json::to_string(result)?)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
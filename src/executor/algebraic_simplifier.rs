@@ -0,0 +1,508 @@
+use std::hash::{Hash, Hasher};
+use std::rc::Rc;
+use std::str::FromStr;
+
+use num_bigint_dig::BigInt;
+use num_traits::{One, Zero};
+use program_structure::ast::{ExpressionInfixOpcode, ExpressionPrefixOpcode};
+use rustc_hash::FxHasher;
+
+use crate::executor::debug_ast::{
+    DebuggableExpressionInfixOpcode, DebuggableExpressionPrefixOpcode,
+};
+use crate::executor::symbolic_value::{
+    evaluate_binary_op, QuadraticPoly, SymbolicValue, SymbolicValueRef,
+};
+use crate::executor::utils::modreduce;
+
+/// How much the constraints are rewritten by `simplify_constraints`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SimplificationLevel {
+    /// The constraints are kept as they are.
+    Off,
+    /// Local rewrites: operations on constants are folded, and `x * 1`, `1 * x`, `x + 0`,
+    /// `0 + x`, `x - 0`, and `-(-x)` are replaced by `x`.
+    Basic,
+    /// On top of the basic rewrites, the chains of `+`, `-`, and `*` are flattened across
+    /// associativity, so that all their constants are folded together, the like terms of a sum
+    /// are combined modulo the prime, and a product with a zero factor is replaced by `0`.
+    Aggressive,
+}
+
+impl SimplificationLevel {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SimplificationLevel::Off => "off",
+            SimplificationLevel::Basic => "basic",
+            SimplificationLevel::Aggressive => "aggressive",
+        }
+    }
+}
+
+impl FromStr for SimplificationLevel {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "off" => Ok(SimplificationLevel::Off),
+            "basic" => Ok(SimplificationLevel::Basic),
+            "aggressive" => Ok(SimplificationLevel::Aggressive),
+            _ => Err(format!(
+                "unknown simplification level `{}` (expected off, basic, or aggressive)",
+                s
+            )),
+        }
+    }
+}
+
+/// Simplifies each constraint with `simplify_value`.
+///
+/// The constraints are rewritten one by one, so that the i-th simplified constraint stands for
+/// the i-th original one and the positions recorded for the trace (e.g. its provenance) still
+/// apply.
+pub fn simplify_constraints(
+    constraints: &[SymbolicValueRef],
+    prime: &BigInt,
+    level: SimplificationLevel,
+) -> Vec<SymbolicValueRef> {
+    constraints
+        .iter()
+        .map(|constraint| simplify_value(constraint, prime, level))
+        .collect()
+}
+
+/// Rewrites `value` into an equivalent value modulo `prime`, with the rewrites of `level`.
+///
+/// The left-hand sides of the assignments are kept, and the operands of `AuxBinaryOp` are
+/// simplified without rewriting the operation itself.
+///
+/// The quadratic forms cached by `Assign` describe the numerator and the denominator of its
+/// right-hand side as polynomials of degree at most 2 in each variable, after the executor
+/// substituted the known values. Since every rewrite replaces a value by one equal to it modulo
+/// `prime` for any assignment, and the `/` itself is never rewritten, these polynomials, and thus
+/// the forms, stay valid; only their coefficients are simplified.
+/// Folded constants are reduced modulo `prime`, as the executor does. Only `+`, `-`, and `*` are
+/// rearranged, since the other operators (e.g. `\`, `%`, or the comparisons) depend on the
+/// integer representatives of their operands.
+///
+/// # Examples
+/// ```
+/// use std::rc::Rc;
+/// use num_bigint_dig::BigInt;
+/// use program_structure::ast::ExpressionInfixOpcode;
+/// use zkfuzz::executor::algebraic_simplifier::{simplify_value, SimplificationLevel};
+/// use zkfuzz::executor::debug_ast::DebuggableExpressionInfixOpcode;
+/// use zkfuzz::executor::symbolic_value::SymbolicValue;
+///
+/// let op = |lhs, op, rhs| {
+///     Rc::new(SymbolicValue::BinaryOp(lhs, DebuggableExpressionInfixOpcode(op), rhs))
+/// };
+/// let int = |v: i32| Rc::new(SymbolicValue::ConstantInt(BigInt::from(v)));
+/// let p = BigInt::from(17);
+///
+/// // (2 + 3) * 1
+/// let sum = op(int(2), ExpressionInfixOpcode::Add, int(3));
+/// let value = op(sum, ExpressionInfixOpcode::Mul, int(1));
+/// let simplified = simplify_value(&value, &p, SimplificationLevel::Basic);
+/// assert_eq!(*simplified, SymbolicValue::ConstantInt(BigInt::from(5)));
+/// ```
+pub fn simplify_value(
+    value: &SymbolicValueRef,
+    prime: &BigInt,
+    level: SimplificationLevel,
+) -> SymbolicValueRef {
+    if level == SimplificationLevel::Off {
+        return value.clone();
+    }
+    let simplify = |v: &SymbolicValueRef| simplify_value(v, prime, level);
+    match value.as_ref() {
+        SymbolicValue::NOP
+        | SymbolicValue::ConstantInt(_)
+        | SymbolicValue::ConstantBool(_)
+        | SymbolicValue::Variable(_) => value.clone(),
+        SymbolicValue::Assign(lhs, rhs, is_safe, polys) => {
+            let simplify_polys = |polys: &Vec<QuadraticPoly>| {
+                polys
+                    .iter()
+                    .map(|(name, coefs)| (name.clone(), coefs.clone().map(|c| simplify(&c))))
+                    .collect::<Vec<_>>()
+            };
+            Rc::new(SymbolicValue::Assign(
+                lhs.clone(),
+                simplify(rhs),
+                *is_safe,
+                polys
+                    .as_ref()
+                    .map(|(num, den)| (simplify_polys(num), simplify_polys(den))),
+            ))
+        }
+        SymbolicValue::AssignEq(lhs, rhs) => {
+            Rc::new(SymbolicValue::AssignEq(lhs.clone(), simplify(rhs)))
+        }
+        SymbolicValue::AssignTemplParam(lhs, rhs) => {
+            Rc::new(SymbolicValue::AssignTemplParam(lhs.clone(), simplify(rhs)))
+        }
+        SymbolicValue::AssignCall(lhs, rhs, is_mutable) => Rc::new(SymbolicValue::AssignCall(
+            lhs.clone(),
+            simplify(rhs),
+            *is_mutable,
+        )),
+        SymbolicValue::AuxBinaryOp(lhs, op, rhs) => Rc::new(SymbolicValue::AuxBinaryOp(
+            simplify(lhs),
+            op.clone(),
+            simplify(rhs),
+        )),
+        SymbolicValue::BinaryOp(..) | SymbolicValue::UnaryOp(..)
+            if level == SimplificationLevel::Aggressive && is_field_arithmetic(value) =>
+        {
+            normalize_sum(value, prime, level)
+        }
+        SymbolicValue::BinaryOp(lhs, op, rhs) => {
+            simplify_binary_op(simplify(lhs), &op.0, simplify(rhs), prime)
+        }
+        SymbolicValue::UnaryOp(op, operand) => simplify_unary_op(op, simplify(operand), prime),
+        SymbolicValue::Conditional(cond, then_value, else_value) => {
+            let cond = simplify(cond);
+            match cond.as_ref() {
+                SymbolicValue::ConstantBool(true) => simplify(then_value),
+                SymbolicValue::ConstantBool(false) => simplify(else_value),
+                _ => Rc::new(SymbolicValue::Conditional(
+                    cond.clone(),
+                    simplify(then_value),
+                    simplify(else_value),
+                )),
+            }
+        }
+        SymbolicValue::Array(elements) => Rc::new(SymbolicValue::Array(
+            elements.iter().map(simplify).collect(),
+        )),
+        SymbolicValue::UniformArray(element, size) => Rc::new(SymbolicValue::UniformArray(
+            simplify(element),
+            simplify(size),
+        )),
+        SymbolicValue::Call(id, args) => Rc::new(SymbolicValue::Call(
+            *id,
+            args.iter().map(simplify).collect(),
+        )),
+    }
+}
+
+/// Returns the number of nodes of `value`, i.e. its size.
+pub fn count_nodes(value: &SymbolicValue) -> usize {
+    1 + match value {
+        SymbolicValue::NOP
+        | SymbolicValue::ConstantInt(_)
+        | SymbolicValue::ConstantBool(_)
+        | SymbolicValue::Variable(_) => 0,
+        SymbolicValue::Assign(lhs, rhs, ..)
+        | SymbolicValue::AssignEq(lhs, rhs)
+        | SymbolicValue::AssignTemplParam(lhs, rhs)
+        | SymbolicValue::AssignCall(lhs, rhs, _)
+        | SymbolicValue::BinaryOp(lhs, _, rhs)
+        | SymbolicValue::AuxBinaryOp(lhs, _, rhs)
+        | SymbolicValue::UniformArray(lhs, rhs) => count_nodes(lhs) + count_nodes(rhs),
+        SymbolicValue::Conditional(cond, then_value, else_value) => {
+            count_nodes(cond) + count_nodes(then_value) + count_nodes(else_value)
+        }
+        SymbolicValue::UnaryOp(_, operand) => count_nodes(operand),
+        SymbolicValue::Array(elements) | SymbolicValue::Call(_, elements) => {
+            elements.iter().map(|e| count_nodes(e)).sum()
+        }
+    }
+}
+
+fn binary_op(
+    lhs: SymbolicValueRef,
+    op: ExpressionInfixOpcode,
+    rhs: SymbolicValueRef,
+) -> SymbolicValueRef {
+    Rc::new(SymbolicValue::BinaryOp(
+        lhs,
+        DebuggableExpressionInfixOpcode(op),
+        rhs,
+    ))
+}
+
+fn negate(value: SymbolicValueRef) -> SymbolicValueRef {
+    Rc::new(SymbolicValue::UnaryOp(
+        DebuggableExpressionPrefixOpcode(ExpressionPrefixOpcode::Sub),
+        value,
+    ))
+}
+
+fn is_constant(value: &SymbolicValue, c: &BigInt, prime: &BigInt) -> bool {
+    match value {
+        SymbolicValue::ConstantInt(v) => modreduce(v, prime) == modreduce(c, prime),
+        _ => false,
+    }
+}
+
+fn is_folded(value: &SymbolicValue) -> bool {
+    matches!(
+        value,
+        SymbolicValue::ConstantInt(_) | SymbolicValue::ConstantBool(_)
+    )
+}
+
+fn is_field_arithmetic(value: &SymbolicValue) -> bool {
+    match value {
+        SymbolicValue::BinaryOp(_, op, _) => matches!(
+            op.0,
+            ExpressionInfixOpcode::Add | ExpressionInfixOpcode::Sub | ExpressionInfixOpcode::Mul
+        ),
+        SymbolicValue::UnaryOp(op, _) => op.0 == ExpressionPrefixOpcode::Sub,
+        _ => false,
+    }
+}
+
+/// Applies the basic rewrites to a binary operation whose operands are simplified.
+fn simplify_binary_op(
+    lhs: SymbolicValueRef,
+    op: &ExpressionInfixOpcode,
+    rhs: SymbolicValueRef,
+    prime: &BigInt,
+) -> SymbolicValueRef {
+    if is_folded(&lhs) && is_folded(&rhs) {
+        return Rc::new(evaluate_binary_op(
+            &lhs,
+            &rhs,
+            prime,
+            &DebuggableExpressionInfixOpcode(*op),
+        ));
+    }
+    let zero = BigInt::zero();
+    let one = BigInt::one();
+    match op {
+        ExpressionInfixOpcode::Add if is_constant(&lhs, &zero, prime) => rhs,
+        ExpressionInfixOpcode::Add | ExpressionInfixOpcode::Sub
+            if is_constant(&rhs, &zero, prime) =>
+        {
+            lhs
+        }
+        ExpressionInfixOpcode::Sub if is_constant(&lhs, &zero, prime) => simplify_unary_op(
+            &DebuggableExpressionPrefixOpcode(ExpressionPrefixOpcode::Sub),
+            rhs,
+            prime,
+        ),
+        ExpressionInfixOpcode::Mul if is_constant(&lhs, &one, prime) => rhs,
+        ExpressionInfixOpcode::Mul if is_constant(&rhs, &one, prime) => lhs,
+        _ => binary_op(lhs, *op, rhs),
+    }
+}
+
+/// Applies the basic rewrites to a unary operation whose operand is simplified.
+fn simplify_unary_op(
+    op: &DebuggableExpressionPrefixOpcode,
+    operand: SymbolicValueRef,
+    prime: &BigInt,
+) -> SymbolicValueRef {
+    match (&op.0, operand.as_ref()) {
+        (ExpressionPrefixOpcode::Sub, SymbolicValue::ConstantInt(v)) => {
+            Rc::new(SymbolicValue::ConstantInt(modreduce(&-v, prime)))
+        }
+        (ExpressionPrefixOpcode::Sub, SymbolicValue::UnaryOp(inner, value))
+            if inner.0 == ExpressionPrefixOpcode::Sub =>
+        {
+            value.clone()
+        }
+        _ => Rc::new(SymbolicValue::UnaryOp(op.clone(), operand)),
+    }
+}
+
+/// A product `coefficient * factors[0] * factors[1] * ...` of a sum.
+struct Term {
+    coefficient: BigInt,
+    factors: Vec<SymbolicValueRef>,
+    /// The sorted hashes of the factors, to find the like terms regardless of the order of their
+    /// factors.
+    key: Vec<u64>,
+}
+
+impl Term {
+    fn is_like(&self, other: &Term) -> bool {
+        if self.key != other.key {
+            return false;
+        }
+        // The factors are compared as multisets, since two factors may share their hash
+        let mut unmatched: Vec<&SymbolicValueRef> = other.factors.iter().collect();
+        self.factors.iter().all(|factor| {
+            match unmatched.iter().position(|candidate| *candidate == factor) {
+                Some(i) => {
+                    unmatched.swap_remove(i);
+                    true
+                }
+                None => false,
+            }
+        })
+    }
+}
+
+fn hash_of(value: &SymbolicValue) -> u64 {
+    let mut hasher = FxHasher::default();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Collects the factors of a chain of `*` and unary `-` into `term`, folding its constants into
+/// the coefficient. The other operands are simplified before being collected.
+fn collect_factors(
+    value: &SymbolicValueRef,
+    term: &mut Term,
+    prime: &BigInt,
+    level: SimplificationLevel,
+) {
+    match value.as_ref() {
+        SymbolicValue::ConstantInt(v) => {
+            term.coefficient = modreduce(&(&term.coefficient * v), prime);
+        }
+        SymbolicValue::BinaryOp(lhs, op, rhs) if op.0 == ExpressionInfixOpcode::Mul => {
+            collect_factors(lhs, term, prime, level);
+            collect_factors(rhs, term, prime, level);
+        }
+        SymbolicValue::UnaryOp(op, operand) if op.0 == ExpressionPrefixOpcode::Sub => {
+            term.coefficient = modreduce(&-&term.coefficient, prime);
+            collect_factors(operand, term, prime, level);
+        }
+        SymbolicValue::BinaryOp(_, op, _)
+            if matches!(
+                op.0,
+                ExpressionInfixOpcode::Add | ExpressionInfixOpcode::Sub
+            ) =>
+        {
+            // Products are not distributed over sums, so a sum is normalized on its own
+            push_factor(normalize_sum(value, prime, level), term, prime, level);
+        }
+        _ => push_factor(simplify_value(value, prime, level), term, prime, level),
+    }
+}
+
+fn push_factor(
+    factor: SymbolicValueRef,
+    term: &mut Term,
+    prime: &BigInt,
+    level: SimplificationLevel,
+) {
+    let is_product = match factor.as_ref() {
+        SymbolicValue::ConstantInt(_) => true,
+        SymbolicValue::BinaryOp(_, op, _) => op.0 == ExpressionInfixOpcode::Mul,
+        SymbolicValue::UnaryOp(op, _) => op.0 == ExpressionPrefixOpcode::Sub,
+        _ => false,
+    };
+    if is_product {
+        collect_factors(&factor, term, prime, level);
+    } else {
+        term.key.push(hash_of(&factor));
+        term.factors.push(factor);
+    }
+}
+
+/// Collects the terms of a chain of `+` and `-` into `terms`, adding the constant terms to
+/// `constant` and the coefficients of the like terms together.
+fn collect_terms(
+    value: &SymbolicValueRef,
+    sign: &BigInt,
+    terms: &mut Vec<Term>,
+    constant: &mut BigInt,
+    prime: &BigInt,
+    level: SimplificationLevel,
+) {
+    match value.as_ref() {
+        SymbolicValue::BinaryOp(lhs, op, rhs) if op.0 == ExpressionInfixOpcode::Add => {
+            collect_terms(lhs, sign, terms, constant, prime, level);
+            collect_terms(rhs, sign, terms, constant, prime, level);
+        }
+        SymbolicValue::BinaryOp(lhs, op, rhs) if op.0 == ExpressionInfixOpcode::Sub => {
+            collect_terms(lhs, sign, terms, constant, prime, level);
+            collect_terms(rhs, &-sign, terms, constant, prime, level);
+        }
+        SymbolicValue::UnaryOp(op, operand) if op.0 == ExpressionPrefixOpcode::Sub => {
+            collect_terms(operand, &-sign, terms, constant, prime, level);
+        }
+        _ => {
+            let mut term = Term {
+                coefficient: sign.clone(),
+                factors: Vec::new(),
+                key: Vec::new(),
+            };
+            collect_factors(value, &mut term, prime, level);
+            term.key.sort_unstable();
+            if term.factors.is_empty() {
+                *constant = modreduce(&(&*constant + &term.coefficient), prime);
+            } else if let Some(like) = terms.iter_mut().find(|t| t.is_like(&term)) {
+                like.coefficient = modreduce(&(&like.coefficient + &term.coefficient), prime);
+            } else {
+                term.coefficient = modreduce(&term.coefficient, prime);
+                terms.push(term);
+            }
+        }
+    }
+}
+
+/// Rebuilds a chain of `+`, `-`, and `*` with its constants folded and its like terms combined.
+///
+/// The terms keep the order of their first occurrence, followed by the constant term. A
+/// coefficient above `prime / 2` is written as the subtraction of its negation, e.g. `x - 2 * y`
+/// rather than `x + (p - 2) * y`.
+fn normalize_sum(
+    value: &SymbolicValueRef,
+    prime: &BigInt,
+    level: SimplificationLevel,
+) -> SymbolicValueRef {
+    let mut terms = Vec::new();
+    let mut constant = BigInt::zero();
+    collect_terms(
+        value,
+        &BigInt::one(),
+        &mut terms,
+        &mut constant,
+        prime,
+        level,
+    );
+    terms.retain(|term| !term.coefficient.is_zero());
+    if terms.is_empty() {
+        return Rc::new(SymbolicValue::ConstantInt(constant));
+    }
+
+    let half = prime / BigInt::from(2);
+    // Returns the magnitude of `c` and whether it is negative
+    let signed = |c: &BigInt| {
+        if *c > half {
+            (prime - c, true)
+        } else {
+            (c.clone(), false)
+        }
+    };
+
+    let mut result: Option<SymbolicValueRef> = None;
+    let mut push = |magnitude: SymbolicValueRef, is_negative: bool| {
+        result = Some(match result.take() {
+            None if is_negative => negate(magnitude),
+            None => magnitude,
+            Some(acc) if is_negative => binary_op(acc, ExpressionInfixOpcode::Sub, magnitude),
+            Some(acc) => binary_op(acc, ExpressionInfixOpcode::Add, magnitude),
+        });
+    };
+    for term in terms {
+        let (magnitude, is_negative) = signed(&term.coefficient);
+        let mut factors = term.factors.into_iter();
+        let first = factors.next().unwrap();
+        let monomial = factors.fold(first, |acc, factor| {
+            binary_op(acc, ExpressionInfixOpcode::Mul, factor)
+        });
+        let magnitude = if magnitude.is_one() {
+            monomial
+        } else {
+            binary_op(
+                Rc::new(SymbolicValue::ConstantInt(magnitude)),
+                ExpressionInfixOpcode::Mul,
+                monomial,
+            )
+        };
+        push(magnitude, is_negative);
+    }
+    if !constant.is_zero() {
+        let (magnitude, is_negative) = signed(&constant);
+        push(Rc::new(SymbolicValue::ConstantInt(magnitude)), is_negative);
+    }
+    result.unwrap()
+}
@@ -0,0 +1,115 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use num_bigint_dig::BigInt;
+use rustc_hash::FxHashMap;
+
+use program_structure::ast::ExpressionPrefixOpcode;
+
+use crate::executor::debug_ast::DebuggableExpressionPrefixOpcode;
+use crate::executor::symbolic_value::{SymbolicName, SymbolicValue, SymbolicValueRef};
+
+/// A branch whose symbolic condition was decided by the seed of a concolic execution.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct PathCondition {
+    /// The element ID of the `if` or `while` statement.
+    pub elem_id: usize,
+    /// The simplified condition of the statement.
+    pub condition: SymbolicValueRef,
+    /// Whether the seed satisfies the condition.
+    pub taken: bool,
+}
+
+impl PathCondition {
+    /// Returns the constraint that holds on the path, i.e., `condition` or `!condition`.
+    pub fn to_constraint(&self) -> SymbolicValue {
+        if self.taken {
+            (*self.condition).clone()
+        } else {
+            SymbolicValue::UnaryOp(
+                DebuggableExpressionPrefixOpcode(ExpressionPrefixOpcode::BoolNot),
+                self.condition.clone(),
+            )
+        }
+    }
+
+    /// Returns the same branch with the other outcome.
+    pub fn negate(&self) -> Self {
+        PathCondition {
+            elem_id: self.elem_id,
+            condition: self.condition.clone(),
+            taken: !self.taken,
+        }
+    }
+
+    /// Formats the constraint of the path condition with the names resolved.
+    pub fn lookup_fmt(&self, lookup: &FxHashMap<usize, String>) -> String {
+        self.to_constraint().lookup_fmt(lookup)
+    }
+}
+
+/// Decides the symbolic branches of a symbolic execution with the values that a concrete
+/// execution of a seed input assigned to the signals.
+///
+/// An `if` or `while` whose condition stays symbolic is normally merged or flagged as a symbolic
+/// loop. With a guide in the setting, the executor follows the branch taken by the seed instead,
+/// and records the symbolic condition so that the path can be negated later.
+pub struct ConcolicGuide {
+    assignment: FxHashMap<SymbolicName, BigInt>,
+    path: RefCell<Vec<PathCondition>>,
+}
+
+impl ConcolicGuide {
+    /// Creates a guide from the signals assigned by a concrete execution of the seed.
+    pub fn new(assignment: FxHashMap<SymbolicName, BigInt>) -> Self {
+        ConcolicGuide {
+            assignment: assignment,
+            path: RefCell::new(Vec::new()),
+        }
+    }
+
+    /// Replaces the signals in `value` that are assigned by the seed with their values.
+    pub fn concretize(&self, value: &SymbolicValue) -> SymbolicValue {
+        let rec = |v: &SymbolicValueRef| Rc::new(self.concretize(v));
+        match value {
+            SymbolicValue::Variable(sym_name) => match self.assignment.get(sym_name) {
+                Some(v) => SymbolicValue::ConstantInt(v.clone()),
+                None => value.clone(),
+            },
+            SymbolicValue::BinaryOp(lhs, op, rhs) => {
+                SymbolicValue::BinaryOp(rec(lhs), op.clone(), rec(rhs))
+            }
+            SymbolicValue::AuxBinaryOp(lhs, op, rhs) => {
+                SymbolicValue::AuxBinaryOp(rec(lhs), op.clone(), rec(rhs))
+            }
+            SymbolicValue::UnaryOp(op, expr) => SymbolicValue::UnaryOp(op.clone(), rec(expr)),
+            SymbolicValue::Conditional(cond, if_true, if_false) => {
+                SymbolicValue::Conditional(rec(cond), rec(if_true), rec(if_false))
+            }
+            SymbolicValue::Array(elements) => {
+                SymbolicValue::Array(elements.iter().map(rec).collect())
+            }
+            SymbolicValue::UniformArray(elem, size) => {
+                SymbolicValue::UniformArray(rec(elem), rec(size))
+            }
+            SymbolicValue::Call(id, args) => {
+                SymbolicValue::Call(*id, args.iter().map(rec).collect())
+            }
+            _ => value.clone(),
+        }
+    }
+
+    /// Records that the seed took the branch of `condition` at `elem_id` iff `taken`.
+    pub fn record(&self, elem_id: usize, condition: &SymbolicValue, taken: bool) {
+        self.path.borrow_mut().push(PathCondition {
+            elem_id: elem_id,
+            condition: Rc::new(condition.clone()),
+            taken: taken,
+        });
+    }
+
+    /// Returns the path conditions recorded so far, in the order of execution.
+    pub fn path(&self) -> Vec<PathCondition> {
+        self.path.borrow().clone()
+    }
+}
@@ -0,0 +1,577 @@
+use std::cell::RefCell;
+use std::env;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufReader, BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use num_bigint_dig::BigInt;
+
+use program_structure::ast::{ExpressionInfixOpcode, ExpressionPrefixOpcode};
+
+use crate::executor::debug_ast::{
+    DebuggableExpressionInfixOpcode, DebuggableExpressionPrefixOpcode,
+};
+use crate::executor::symbolic_value::{
+    OwnerName, QuadraticPoly, SymbolicAccess, SymbolicName, SymbolicValue, SymbolicValueRef,
+};
+
+/// A sequence of constraints that can be appended to and iterated over.
+///
+/// Consumers that only need to scan the constraints (e.g., statistics, slicing, the detectors,
+/// and the exports) should go through this trait so that the constraints do not have to be
+/// resident in memory. The searches compile the constraints and evaluate them repeatedly, so they
+/// take the constraints under analysis loaded with `to_vec`.
+pub trait ConstraintStore {
+    /// Returns the number of stored constraints.
+    fn len(&self) -> usize;
+
+    /// Returns `true` if no constraint is stored.
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Appends a constraint to the end of the store.
+    fn push(&mut self, constraint: &SymbolicValueRef);
+
+    /// Returns the constraint at `index`, or `None` if it is out of bounds.
+    fn get(&self, index: usize) -> Option<SymbolicValueRef>;
+
+    /// Returns an iterator over the stored constraints in insertion order.
+    fn iter<'a>(&'a self) -> Box<dyn Iterator<Item = SymbolicValueRef> + 'a>;
+
+    /// Loads all stored constraints into memory.
+    fn to_vec(&self) -> Vec<SymbolicValueRef> {
+        self.iter().collect()
+    }
+}
+
+impl ConstraintStore for Vec<SymbolicValueRef> {
+    fn len(&self) -> usize {
+        Vec::len(self)
+    }
+
+    fn push(&mut self, constraint: &SymbolicValueRef) {
+        Vec::push(self, constraint.clone());
+    }
+
+    fn get(&self, index: usize) -> Option<SymbolicValueRef> {
+        <[SymbolicValueRef]>::get(self, index).cloned()
+    }
+
+    fn iter<'a>(&'a self) -> Box<dyn Iterator<Item = SymbolicValueRef> + 'a> {
+        Box::new(<[SymbolicValueRef]>::iter(self).cloned())
+    }
+
+    fn to_vec(&self) -> Vec<SymbolicValueRef> {
+        self.clone()
+    }
+}
+
+static STORE_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+/// An append-only, disk-backed constraint store.
+///
+/// Each constraint is serialized into a length-prefixed record of a temporary file, and only the
+/// byte offsets of the records are kept in memory. Records are decoded on demand, so the memory
+/// footprint is independent of the size of the constraints. The file is removed when the store
+/// is dropped.
+pub struct DiskConstraintStore {
+    path: PathBuf,
+    writer: RefCell<BufWriter<File>>,
+    reader: RefCell<File>,
+    offsets: Vec<u64>,
+    end: u64,
+}
+
+impl DiskConstraintStore {
+    /// Creates an empty store backed by a fresh file in `dir`.
+    pub fn new(dir: &Path) -> io::Result<Self> {
+        let path = dir.join(format!(
+            "zkfuzz_constraints_{}_{}.bin",
+            std::process::id(),
+            STORE_COUNTER.fetch_add(1, Ordering::SeqCst)
+        ));
+        let writer = OpenOptions::new()
+            .create(true)
+            .write(true)
+            .truncate(true)
+            .open(&path)?;
+        let reader = File::open(&path)?;
+        Ok(DiskConstraintStore {
+            path,
+            writer: RefCell::new(BufWriter::new(writer)),
+            reader: RefCell::new(reader),
+            offsets: Vec::new(),
+            end: 0,
+        })
+    }
+
+    /// Returns the path of the backing file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn flush(&self) {
+        self.writer
+            .borrow_mut()
+            .flush()
+            .expect("Unable to flush the constraint store");
+    }
+}
+
+impl ConstraintStore for DiskConstraintStore {
+    fn len(&self) -> usize {
+        self.offsets.len()
+    }
+
+    fn push(&mut self, constraint: &SymbolicValueRef) {
+        let mut record = Vec::new();
+        encode_symbolic_value(constraint, &mut record);
+        let mut writer = self.writer.borrow_mut();
+        writer
+            .write_all(&(record.len() as u64).to_le_bytes())
+            .and_then(|_| writer.write_all(&record))
+            .expect("Unable to write to the constraint store");
+        self.offsets.push(self.end);
+        self.end += 8 + record.len() as u64;
+    }
+
+    fn get(&self, index: usize) -> Option<SymbolicValueRef> {
+        let offset = *self.offsets.get(index)?;
+        self.flush();
+        let mut reader = self.reader.borrow_mut();
+        reader
+            .seek(SeekFrom::Start(offset))
+            .expect("Unable to read the constraint store");
+        Some(read_record(&mut *reader).expect("The constraint store is corrupted"))
+    }
+
+    fn iter<'a>(&'a self) -> Box<dyn Iterator<Item = SymbolicValueRef> + 'a> {
+        self.flush();
+        let mut reader =
+            BufReader::new(File::open(&self.path).expect("Unable to read the constraint store"));
+        Box::new(
+            (0..self.len())
+                .map(move |_| read_record(&mut reader).expect("The constraint store is corrupted")),
+        )
+    }
+}
+
+impl Drop for DiskConstraintStore {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+/// Creates an empty constraint store of the given kind.
+///
+/// # Parameters
+/// - `kind`: Either `memory` or `disk`. Disk-backed stores are created in the temporary directory.
+///
+/// # Returns
+/// The store, or an error if `kind` is unknown or the backing file cannot be created.
+pub fn new_constraint_store(kind: &str) -> io::Result<Box<dyn ConstraintStore>> {
+    match kind {
+        "memory" => Ok(Box::new(Vec::<SymbolicValueRef>::new())),
+        "disk" => Ok(Box::new(DiskConstraintStore::new(&env::temp_dir())?)),
+        _ => Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!(
+                "unknown constraint store `{}` (expected memory or disk)",
+                kind
+            ),
+        )),
+    }
+}
+
+fn read_record<R: Read>(reader: &mut R) -> Option<SymbolicValueRef> {
+    let mut len = [0_u8; 8];
+    reader.read_exact(&mut len).ok()?;
+    let mut record = vec![0_u8; u64::from_le_bytes(len) as usize];
+    reader.read_exact(&mut record).ok()?;
+    let mut decoder = Decoder {
+        buf: &record,
+        pos: 0,
+    };
+    decoder.read_value().map(Rc::new)
+}
+
+fn infix_opcode_to_u8(op: &ExpressionInfixOpcode) -> u8 {
+    match op {
+        ExpressionInfixOpcode::Mul => 0,
+        ExpressionInfixOpcode::Div => 1,
+        ExpressionInfixOpcode::Add => 2,
+        ExpressionInfixOpcode::Sub => 3,
+        ExpressionInfixOpcode::Pow => 4,
+        ExpressionInfixOpcode::IntDiv => 5,
+        ExpressionInfixOpcode::Mod => 6,
+        ExpressionInfixOpcode::ShiftL => 7,
+        ExpressionInfixOpcode::ShiftR => 8,
+        ExpressionInfixOpcode::LesserEq => 9,
+        ExpressionInfixOpcode::GreaterEq => 10,
+        ExpressionInfixOpcode::Lesser => 11,
+        ExpressionInfixOpcode::Greater => 12,
+        ExpressionInfixOpcode::Eq => 13,
+        ExpressionInfixOpcode::NotEq => 14,
+        ExpressionInfixOpcode::BoolOr => 15,
+        ExpressionInfixOpcode::BoolAnd => 16,
+        ExpressionInfixOpcode::BitOr => 17,
+        ExpressionInfixOpcode::BitAnd => 18,
+        ExpressionInfixOpcode::BitXor => 19,
+    }
+}
+
+fn u8_to_infix_opcode(code: u8) -> Option<ExpressionInfixOpcode> {
+    Some(match code {
+        0 => ExpressionInfixOpcode::Mul,
+        1 => ExpressionInfixOpcode::Div,
+        2 => ExpressionInfixOpcode::Add,
+        3 => ExpressionInfixOpcode::Sub,
+        4 => ExpressionInfixOpcode::Pow,
+        5 => ExpressionInfixOpcode::IntDiv,
+        6 => ExpressionInfixOpcode::Mod,
+        7 => ExpressionInfixOpcode::ShiftL,
+        8 => ExpressionInfixOpcode::ShiftR,
+        9 => ExpressionInfixOpcode::LesserEq,
+        10 => ExpressionInfixOpcode::GreaterEq,
+        11 => ExpressionInfixOpcode::Lesser,
+        12 => ExpressionInfixOpcode::Greater,
+        13 => ExpressionInfixOpcode::Eq,
+        14 => ExpressionInfixOpcode::NotEq,
+        15 => ExpressionInfixOpcode::BoolOr,
+        16 => ExpressionInfixOpcode::BoolAnd,
+        17 => ExpressionInfixOpcode::BitOr,
+        18 => ExpressionInfixOpcode::BitAnd,
+        19 => ExpressionInfixOpcode::BitXor,
+        _ => return None,
+    })
+}
+
+fn prefix_opcode_to_u8(op: &ExpressionPrefixOpcode) -> u8 {
+    match op {
+        ExpressionPrefixOpcode::Sub => 0,
+        ExpressionPrefixOpcode::BoolNot => 1,
+        ExpressionPrefixOpcode::Complement => 2,
+    }
+}
+
+fn u8_to_prefix_opcode(code: u8) -> Option<ExpressionPrefixOpcode> {
+    Some(match code {
+        0 => ExpressionPrefixOpcode::Sub,
+        1 => ExpressionPrefixOpcode::BoolNot,
+        2 => ExpressionPrefixOpcode::Complement,
+        _ => return None,
+    })
+}
+
+fn encode_usize(value: usize, buf: &mut Vec<u8>) {
+    buf.extend_from_slice(&(value as u64).to_le_bytes());
+}
+
+fn encode_bigint(value: &BigInt, buf: &mut Vec<u8>) {
+    let bytes = value.to_signed_bytes_le();
+    encode_usize(bytes.len(), buf);
+    buf.extend_from_slice(&bytes);
+}
+
+fn encode_access(access: &Option<Vec<SymbolicAccess>>, buf: &mut Vec<u8>) {
+    match access {
+        None => buf.push(0),
+        Some(accesses) => {
+            buf.push(1);
+            encode_usize(accesses.len(), buf);
+            for a in accesses {
+                match a {
+                    SymbolicAccess::ComponentAccess(id) => {
+                        buf.push(0);
+                        encode_usize(*id, buf);
+                    }
+                    SymbolicAccess::ArrayAccess(value) => {
+                        buf.push(1);
+                        encode_symbolic_value(value, buf);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Serializes a list of owners, e.g. the owner of a component, into a compact binary
+/// representation.
+pub fn encode_owner_names(owners: &[OwnerName], buf: &mut Vec<u8>) {
+    encode_usize(owners.len(), buf);
+    for owner in owners {
+        encode_usize(owner.id, buf);
+        encode_usize(owner.counter, buf);
+        encode_access(&owner.access, buf);
+    }
+}
+
+/// Serializes a symbolic name into a compact binary representation.
+pub fn encode_symbolic_name(name: &SymbolicName, buf: &mut Vec<u8>) {
+    encode_usize(name.id, buf);
+    encode_owner_names(&name.owner, buf);
+    encode_access(&name.access, buf);
+}
+
+fn encode_quadratic_polys(polys: &[QuadraticPoly], buf: &mut Vec<u8>) {
+    encode_usize(polys.len(), buf);
+    for (name, coefficients) in polys {
+        encode_symbolic_name(name, buf);
+        for c in coefficients.iter() {
+            encode_symbolic_value(c, buf);
+        }
+    }
+}
+
+/// Serializes a symbolic value into a compact binary representation.
+pub fn encode_symbolic_value(value: &SymbolicValue, buf: &mut Vec<u8>) {
+    match value {
+        SymbolicValue::NOP => buf.push(0),
+        SymbolicValue::ConstantInt(v) => {
+            buf.push(1);
+            encode_bigint(v, buf);
+        }
+        SymbolicValue::ConstantBool(b) => {
+            buf.push(2);
+            buf.push(*b as u8);
+        }
+        SymbolicValue::Variable(name) => {
+            buf.push(3);
+            encode_symbolic_name(name, buf);
+        }
+        SymbolicValue::Assign(lhs, rhs, is_safe, zero_div_info) => {
+            buf.push(4);
+            encode_symbolic_value(lhs, buf);
+            encode_symbolic_value(rhs, buf);
+            buf.push(*is_safe as u8);
+            match zero_div_info {
+                None => buf.push(0),
+                Some((numerators, denominators)) => {
+                    buf.push(1);
+                    encode_quadratic_polys(numerators, buf);
+                    encode_quadratic_polys(denominators, buf);
+                }
+            }
+        }
+        SymbolicValue::AssignEq(lhs, rhs) => {
+            buf.push(5);
+            encode_symbolic_value(lhs, buf);
+            encode_symbolic_value(rhs, buf);
+        }
+        SymbolicValue::AssignTemplParam(lhs, rhs) => {
+            buf.push(6);
+            encode_symbolic_value(lhs, buf);
+            encode_symbolic_value(rhs, buf);
+        }
+        SymbolicValue::AssignCall(lhs, rhs, is_mutable) => {
+            buf.push(7);
+            encode_symbolic_value(lhs, buf);
+            encode_symbolic_value(rhs, buf);
+            buf.push(*is_mutable as u8);
+        }
+        SymbolicValue::BinaryOp(lhs, op, rhs) => {
+            buf.push(8);
+            encode_symbolic_value(lhs, buf);
+            buf.push(infix_opcode_to_u8(&op.0));
+            encode_symbolic_value(rhs, buf);
+        }
+        SymbolicValue::AuxBinaryOp(lhs, op, rhs) => {
+            buf.push(9);
+            encode_symbolic_value(lhs, buf);
+            buf.push(infix_opcode_to_u8(&op.0));
+            encode_symbolic_value(rhs, buf);
+        }
+        SymbolicValue::Conditional(cond, if_true, if_false) => {
+            buf.push(10);
+            encode_symbolic_value(cond, buf);
+            encode_symbolic_value(if_true, buf);
+            encode_symbolic_value(if_false, buf);
+        }
+        SymbolicValue::UnaryOp(op, expr) => {
+            buf.push(11);
+            buf.push(prefix_opcode_to_u8(&op.0));
+            encode_symbolic_value(expr, buf);
+        }
+        SymbolicValue::Array(elements) => {
+            buf.push(12);
+            encode_usize(elements.len(), buf);
+            for elem in elements {
+                encode_symbolic_value(elem, buf);
+            }
+        }
+        SymbolicValue::UniformArray(elem, size) => {
+            buf.push(13);
+            encode_symbolic_value(elem, buf);
+            encode_symbolic_value(size, buf);
+        }
+        SymbolicValue::Call(id, args) => {
+            buf.push(14);
+            encode_usize(*id, buf);
+            encode_usize(args.len(), buf);
+            for arg in args {
+                encode_symbolic_value(arg, buf);
+            }
+        }
+    }
+}
+
+/// Deserializes a symbolic value produced by `encode_symbolic_value`.
+///
+/// # Returns
+/// The decoded value, or `None` if `buf` is not a valid encoding.
+pub fn decode_symbolic_value(buf: &[u8]) -> Option<SymbolicValue> {
+    let mut decoder = Decoder { buf, pos: 0 };
+    decoder.read_value()
+}
+
+/// Deserializes a list of owners produced by `encode_owner_names`.
+pub fn decode_owner_names(buf: &[u8]) -> Option<Vec<OwnerName>> {
+    let mut decoder = Decoder { buf, pos: 0 };
+    decoder.read_owner_names()
+}
+
+/// Deserializes a symbolic name produced by `encode_symbolic_name`.
+pub fn decode_symbolic_name(buf: &[u8]) -> Option<SymbolicName> {
+    let mut decoder = Decoder { buf, pos: 0 };
+    decoder.read_symbolic_name()
+}
+
+struct Decoder<'a> {
+    buf: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Decoder<'a> {
+    fn read_u8(&mut self) -> Option<u8> {
+        let b = *self.buf.get(self.pos)?;
+        self.pos += 1;
+        Some(b)
+    }
+
+    fn read_bool(&mut self) -> Option<bool> {
+        self.read_u8().map(|b| b != 0)
+    }
+
+    fn read_bytes(&mut self, len: usize) -> Option<&'a [u8]> {
+        let bytes = self.buf.get(self.pos..self.pos.checked_add(len)?)?;
+        self.pos += len;
+        Some(bytes)
+    }
+
+    fn read_usize(&mut self) -> Option<usize> {
+        let mut bytes = [0_u8; 8];
+        bytes.copy_from_slice(self.read_bytes(8)?);
+        Some(u64::from_le_bytes(bytes) as usize)
+    }
+
+    fn read_bigint(&mut self) -> Option<BigInt> {
+        let len = self.read_usize()?;
+        Some(BigInt::from_signed_bytes_le(self.read_bytes(len)?))
+    }
+
+    fn read_ref(&mut self) -> Option<SymbolicValueRef> {
+        self.read_value().map(Rc::new)
+    }
+
+    fn read_refs(&mut self) -> Option<Vec<SymbolicValueRef>> {
+        let len = self.read_usize()?;
+        (0..len).map(|_| self.read_ref()).collect()
+    }
+
+    fn read_access(&mut self) -> Option<Option<Vec<SymbolicAccess>>> {
+        if !self.read_bool()? {
+            return Some(None);
+        }
+        let len = self.read_usize()?;
+        let mut accesses = Vec::with_capacity(len);
+        for _ in 0..len {
+            accesses.push(match self.read_u8()? {
+                0 => SymbolicAccess::ComponentAccess(self.read_usize()?),
+                1 => SymbolicAccess::ArrayAccess(self.read_value()?),
+                _ => return None,
+            });
+        }
+        Some(Some(accesses))
+    }
+
+    fn read_owner_names(&mut self) -> Option<Vec<OwnerName>> {
+        let num_owners = self.read_usize()?;
+        let mut owner = Vec::with_capacity(num_owners);
+        for _ in 0..num_owners {
+            let owner_id = self.read_usize()?;
+            let counter = self.read_usize()?;
+            let access = self.read_access()?;
+            owner.push(OwnerName {
+                id: owner_id,
+                access,
+                counter,
+            });
+        }
+        Some(owner)
+    }
+
+    fn read_symbolic_name(&mut self) -> Option<SymbolicName> {
+        let id = self.read_usize()?;
+        let owner = self.read_owner_names()?;
+        let access = self.read_access()?;
+        Some(SymbolicName::new(id, Rc::new(owner), access))
+    }
+
+    fn read_quadratic_polys(&mut self) -> Option<Vec<QuadraticPoly>> {
+        let len = self.read_usize()?;
+        let mut polys = Vec::with_capacity(len);
+        for _ in 0..len {
+            let name = self.read_symbolic_name()?;
+            polys.push((name, [self.read_ref()?, self.read_ref()?, self.read_ref()?]));
+        }
+        Some(polys)
+    }
+
+    fn read_value(&mut self) -> Option<SymbolicValue> {
+        Some(match self.read_u8()? {
+            0 => SymbolicValue::NOP,
+            1 => SymbolicValue::ConstantInt(self.read_bigint()?),
+            2 => SymbolicValue::ConstantBool(self.read_bool()?),
+            3 => SymbolicValue::Variable(self.read_symbolic_name()?),
+            4 => {
+                let lhs = self.read_ref()?;
+                let rhs = self.read_ref()?;
+                let is_safe = self.read_bool()?;
+                let zero_div_info = if self.read_bool()? {
+                    Some((self.read_quadratic_polys()?, self.read_quadratic_polys()?))
+                } else {
+                    None
+                };
+                SymbolicValue::Assign(lhs, rhs, is_safe, zero_div_info)
+            }
+            5 => SymbolicValue::AssignEq(self.read_ref()?, self.read_ref()?),
+            6 => SymbolicValue::AssignTemplParam(self.read_ref()?, self.read_ref()?),
+            7 => SymbolicValue::AssignCall(self.read_ref()?, self.read_ref()?, self.read_bool()?),
+            8 => SymbolicValue::BinaryOp(
+                self.read_ref()?,
+                DebuggableExpressionInfixOpcode(u8_to_infix_opcode(self.read_u8()?)?),
+                self.read_ref()?,
+            ),
+            9 => SymbolicValue::AuxBinaryOp(
+                self.read_ref()?,
+                DebuggableExpressionInfixOpcode(u8_to_infix_opcode(self.read_u8()?)?),
+                self.read_ref()?,
+            ),
+            10 => SymbolicValue::Conditional(self.read_ref()?, self.read_ref()?, self.read_ref()?),
+            11 => SymbolicValue::UnaryOp(
+                DebuggableExpressionPrefixOpcode(u8_to_prefix_opcode(self.read_u8()?)?),
+                self.read_ref()?,
+            ),
+            12 => SymbolicValue::Array(self.read_refs()?),
+            13 => SymbolicValue::UniformArray(self.read_ref()?, self.read_ref()?),
+            14 => {
+                let id = self.read_usize()?;
+                SymbolicValue::Call(id, self.read_refs()?)
+            }
+            _ => return None,
+        })
+    }
+}
@@ -1,11 +1,12 @@
 use num_bigint_dig::BigInt;
 use rustc_hash::FxHashMap;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::fmt;
 use std::hash::{Hash, Hasher};
 
 use program_structure::abstract_syntax_tree::ast::{
-    Access, AssignOp, Expression, ExpressionInfixOpcode, ExpressionPrefixOpcode, SignalType,
-    Statement, VariableType,
+    Access, AssignOp, Expression, ExpressionInfixOpcode, ExpressionPrefixOpcode, LogArgument,
+    SignalType, Statement, VariableType,
 };
 use program_structure::ast::Meta;
 
@@ -21,20 +22,103 @@ const RED: &str = "\x1b[31m";
 pub struct DebuggableSignalType(pub SignalType);
 #[derive(Clone)]
 pub struct DebuggableVariableType(pub VariableType);
-#[derive(Clone)]
-pub struct DebuggableAssignOp(pub AssignOp);
-#[derive(Clone, PartialEq)]
-pub struct DebuggableExpressionInfixOpcode(pub ExpressionInfixOpcode);
-#[derive(Clone, PartialEq)]
-pub struct DebuggableExpressionPrefixOpcode(pub ExpressionPrefixOpcode);
+#[derive(Clone, Serialize, Deserialize)]
+pub struct DebuggableAssignOp(#[serde(with = "AssignOpDef")] pub AssignOp);
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+pub struct DebuggableExpressionInfixOpcode(
+    #[serde(with = "ExpressionInfixOpcodeDef")] pub ExpressionInfixOpcode,
+);
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+pub struct DebuggableExpressionPrefixOpcode(
+    #[serde(with = "ExpressionPrefixOpcodeDef")] pub ExpressionPrefixOpcode,
+);
 
-#[derive(Clone)]
+// Mirrors of the AST types of circom, used to (de)serialize the debuggable AST stored in a
+// symbolic library archive (see `symbolic_archive`).
+
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "SignalType")]
+enum SignalTypeDef {
+    Output,
+    Input,
+    Intermediate,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "VariableType")]
+pub(crate) enum VariableTypeDef {
+    Var,
+    Signal(#[serde(with = "SignalTypeDef")] SignalType, Vec<String>),
+    Component,
+    AnonymousComponent,
+    Bus(String, #[serde(with = "SignalTypeDef")] SignalType, Vec<String>),
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "AssignOp")]
+enum AssignOpDef {
+    AssignVar,
+    AssignSignal,
+    AssignConstraintSignal,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "ExpressionInfixOpcode")]
+enum ExpressionInfixOpcodeDef {
+    Mul,
+    Div,
+    Add,
+    Sub,
+    Pow,
+    IntDiv,
+    Mod,
+    ShiftL,
+    ShiftR,
+    LesserEq,
+    GreaterEq,
+    Lesser,
+    Greater,
+    Eq,
+    NotEq,
+    BoolOr,
+    BoolAnd,
+    BitOr,
+    BitAnd,
+    BitXor,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "ExpressionPrefixOpcode")]
+enum ExpressionPrefixOpcodeDef {
+    Sub,
+    BoolNot,
+    Complement,
+}
+
+/// Only the element ID and the span of a `Meta` are archived. The file ID is dropped since it
+/// refers to the file library of the project the archive was compiled from.
+mod meta_serde {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(meta: &Meta, serializer: S) -> Result<S::Ok, S::Error> {
+        (meta.elem_id, meta.start, meta.end).serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Meta, D::Error> {
+        let (elem_id, start, end) = <(usize, usize, usize)>::deserialize(deserializer)?;
+        let mut meta = Meta::new(start, end);
+        meta.elem_id = elem_id;
+        Ok(meta)
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
 pub enum DebugAccess {
     ComponentAccess(usize),
     ArrayAccess(DebuggableExpression),
 }
 
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 pub enum DebuggableExpression {
     InfixOp {
         lhe: Box<DebuggableExpression>,
@@ -71,6 +155,9 @@ pub enum DebuggableExpression {
         is_parallel: bool,
         params: Vec<DebuggableExpression>,
         signals: Vec<DebuggableExpression>,
+        /// The input signals named in `A(n)(in1 <== x, in2 <== y)` with their operators, in the
+        /// order of `signals`. `None` if the inputs are passed by position.
+        names: Option<Vec<(DebuggableAssignOp, usize)>>,
     },
     ArrayInLine {
         values: Vec<DebuggableExpression>,
@@ -84,36 +171,51 @@ pub enum DebuggableExpression {
     },
 }
 
-#[derive(Clone)]
+/// An argument of `log()`, either a string printed as is or an expression printed as its value.
+#[derive(Clone, Serialize, Deserialize)]
+pub enum DebuggableLogArgument {
+    LogStr(String),
+    LogExp(DebuggableExpression),
+}
+
+#[derive(Clone, Serialize, Deserialize)]
 pub enum DebuggableStatement {
     IfThenElse {
+        #[serde(with = "meta_serde")]
         meta: Meta,
         cond: DebuggableExpression,
         if_case: Box<DebuggableStatement>,
         else_case: Option<Box<DebuggableStatement>>,
     },
     While {
+        #[serde(with = "meta_serde")]
         meta: Meta,
         cond: DebuggableExpression,
         stmt: Box<DebuggableStatement>,
     },
     Return {
+        #[serde(with = "meta_serde")]
         meta: Meta,
         value: DebuggableExpression,
     },
     InitializationBlock {
+        #[serde(with = "meta_serde")]
         meta: Meta,
+        #[serde(with = "VariableTypeDef")]
         xtype: VariableType,
         initializations: Vec<DebuggableStatement>,
     },
     Declaration {
+        #[serde(with = "meta_serde")]
         meta: Meta,
+        #[serde(with = "VariableTypeDef")]
         xtype: VariableType,
         id: usize,
         dimensions: Vec<DebuggableExpression>,
         is_constant: bool,
     },
     Substitution {
+        #[serde(with = "meta_serde")]
         meta: Meta,
         var: usize,
         access: Vec<DebugAccess>,
@@ -121,29 +223,36 @@ pub enum DebuggableStatement {
         rhe: DebuggableExpression,
     },
     MultSubstitution {
+        #[serde(with = "meta_serde")]
         meta: Meta,
         lhe: DebuggableExpression,
         op: DebuggableAssignOp,
         rhe: DebuggableExpression,
     },
     UnderscoreSubstitution {
+        #[serde(with = "meta_serde")]
         meta: Meta,
         op: DebuggableAssignOp,
         rhe: DebuggableExpression,
     },
     ConstraintEquality {
+        #[serde(with = "meta_serde")]
         meta: Meta,
         lhe: DebuggableExpression,
         rhe: DebuggableExpression,
     },
     LogCall {
+        #[serde(with = "meta_serde")]
         meta: Meta,
+        args: Vec<DebuggableLogArgument>,
     },
     Block {
+        #[serde(with = "meta_serde")]
         meta: Meta,
         stmts: Vec<DebuggableStatement>,
     },
     Assert {
+        #[serde(with = "meta_serde")]
         meta: Meta,
         arg: DebuggableExpression,
     },
@@ -271,7 +380,7 @@ impl DebuggableExpression {
                 is_parallel,
                 params,
                 signals,
-                names: _,
+                names,
             } => {
                 let i = if let Some(i) = name2id.get(&id) {
                     *i
@@ -280,6 +389,21 @@ impl DebuggableExpression {
                     id2name.insert(name2id[&id], id);
                     name2id.len() - 1
                 };
+                let names = names.map(|names| {
+                    names
+                        .into_iter()
+                        .map(|(op, name)| {
+                            let j = if let Some(j) = name2id.get(&name) {
+                                *j
+                            } else {
+                                name2id.insert(name.clone(), name2id.len());
+                                id2name.insert(name2id[&name], name);
+                                name2id.len() - 1
+                            };
+                            (DebuggableAssignOp(op), j)
+                        })
+                        .collect()
+                });
                 DebuggableExpression::AnonymousComp {
                     id: i,
                     is_parallel,
@@ -291,6 +415,7 @@ impl DebuggableExpression {
                         .into_iter()
                         .map(|s| DebuggableExpression::from(s, name2id, id2name))
                         .collect(),
+                    names: names,
                 }
             }
             Expression::ArrayInLine { meta: _, values } => DebuggableExpression::ArrayInLine {
@@ -430,7 +555,18 @@ impl DebuggableStatement {
                     rhe: DebuggableExpression::from(rhe, name2id, id2name),
                 }
             }
-            Statement::LogCall { meta, args: _ } => DebuggableStatement::LogCall { meta },
+            Statement::LogCall { meta, args } => DebuggableStatement::LogCall {
+                meta,
+                args: args
+                    .into_iter()
+                    .map(|arg| match arg {
+                        LogArgument::LogStr(s) => DebuggableLogArgument::LogStr(s),
+                        LogArgument::LogExp(expr) => DebuggableLogArgument::LogExp(
+                            DebuggableExpression::from(expr, name2id, id2name),
+                        ),
+                    })
+                    .collect(),
+            },
             Statement::Block { meta, stmts } => DebuggableStatement::Block {
                 meta,
                 stmts: stmts
@@ -697,7 +833,7 @@ impl DebuggableExpression {
                 is_parallel,
                 params,
                 signals,
-                ..
+                names,
             } => {
                 s += &format!("{}AnonymousComp\n", indentation);
                 s += &format!("{}  id: {}\n", indentation, id);
@@ -711,6 +847,17 @@ impl DebuggableExpression {
                 for sig in signals {
                     s += &(sig.clone()).lookup_fmt(lookup, indent + 2);
                 }
+                if let Some(names) = names {
+                    s += &format!("{}  names:\n", indentation);
+                    for (op, name) in names {
+                        s += &format!(
+                            "{}    {:?} {}\n",
+                            indentation,
+                            op,
+                            lookup.get(name).cloned().unwrap_or_default()
+                        );
+                    }
+                }
                 s
             }
         }
@@ -757,6 +904,25 @@ impl DebuggableStatement {
         }
     }
 
+    /// Returns the metadata of the statement, or `None` for `Ret`.
+    pub fn get_meta(&self) -> Option<&Meta> {
+        match self {
+            DebuggableStatement::IfThenElse { meta, .. }
+            | DebuggableStatement::While { meta, .. }
+            | DebuggableStatement::Return { meta, .. }
+            | DebuggableStatement::InitializationBlock { meta, .. }
+            | DebuggableStatement::Declaration { meta, .. }
+            | DebuggableStatement::Substitution { meta, .. }
+            | DebuggableStatement::MultSubstitution { meta, .. }
+            | DebuggableStatement::UnderscoreSubstitution { meta, .. }
+            | DebuggableStatement::ConstraintEquality { meta, .. }
+            | DebuggableStatement::LogCall { meta, .. }
+            | DebuggableStatement::Block { meta, .. }
+            | DebuggableStatement::Assert { meta, .. } => Some(meta),
+            DebuggableStatement::Ret => None,
+        }
+    }
+
     pub fn lookup_fmt(&self, lookup: &FxHashMap<usize, String>, indent: usize) -> String {
         let mut s = "".to_string();
         let indentation = "  ".repeat(indent);
@@ -0,0 +1,100 @@
+use rustc_hash::{FxHashMap, FxHashSet};
+
+use crate::executor::symbolic_execution::FunctionAssertion;
+use crate::executor::symbolic_value::{SymbolicValue, SymbolicValueRef};
+
+/// The effects of a call of a function on the executor, reused by the later calls of the
+/// function with the same arguments.
+#[derive(Clone)]
+pub struct FunctionSummary {
+    pub return_value: SymbolicValue,
+    /// The fragment of the symbolic trace produced by the call.
+    pub symbolic_trace: Vec<SymbolicValueRef>,
+    /// The assertions executed by the call, which produce `symbolic_trace` and are attributed
+    /// to the call site of each later call.
+    pub assertions: Vec<FunctionAssertion>,
+    pub mindim: usize,
+    pub divisions: FxHashMap<usize, (usize, SymbolicValue)>,
+    pub branches: FxHashMap<usize, (usize, bool, bool)>,
+    pub called_functions: FxHashSet<usize>,
+}
+
+/// Memoizes the summaries of function calls, keyed by the ID of the function and the simplified
+/// values of its arguments.
+///
+/// Since a circom function cannot read signals or mutate its caller, a call is determined by its
+/// arguments, whether they are concrete or symbolic. Once `capacity` summaries are stored, the
+/// least recently used one is evicted.
+#[derive(Default)]
+pub struct FunctionSummaryCache {
+    summaries: FxHashMap<(usize, Vec<SymbolicValueRef>), (FunctionSummary, u64)>,
+    clock: u64,
+    pub hits: usize,
+    pub misses: usize,
+}
+
+impl FunctionSummaryCache {
+    /// Returns the summary of the call of the function `id` with `args`, if it is memoized.
+    pub fn get(&mut self, id: usize, args: &[SymbolicValueRef]) -> Option<FunctionSummary> {
+        self.clock += 1;
+        match self.summaries.get_mut(&(id, args.to_vec())) {
+            Some((summary, last_used)) => {
+                *last_used = self.clock;
+                self.hits += 1;
+                Some(summary.clone())
+            }
+            None => {
+                self.misses += 1;
+                None
+            }
+        }
+    }
+
+    /// Memoizes the summary of the call of the function `id` with `args`, evicting the least
+    /// recently used summary if `capacity` summaries are already stored. Nothing is stored if
+    /// `capacity` is `0`.
+    pub fn insert(
+        &mut self,
+        id: usize,
+        args: Vec<SymbolicValueRef>,
+        summary: FunctionSummary,
+        capacity: usize,
+    ) {
+        if capacity == 0 {
+            return;
+        }
+        let key = (id, args);
+        if !self.summaries.contains_key(&key) && self.summaries.len() >= capacity {
+            let least_recently_used = self
+                .summaries
+                .iter()
+                .min_by_key(|(_, (_, last_used))| *last_used)
+                .map(|(key, _)| key.clone());
+            if let Some(evicted) = least_recently_used {
+                self.summaries.remove(&evicted);
+            }
+        }
+        self.clock += 1;
+        self.summaries.insert(key, (summary, self.clock));
+    }
+
+    /// Returns the number of memoized summaries.
+    pub fn len(&self) -> usize {
+        self.summaries.len()
+    }
+
+    /// Returns `true` if no summary is memoized.
+    pub fn is_empty(&self) -> bool {
+        self.summaries.is_empty()
+    }
+
+    /// Returns the ratio of the calls served from the cache, or `0` if no call was made.
+    pub fn hit_rate(&self) -> f64 {
+        let calls = self.hits + self.misses;
+        if calls == 0 {
+            0.0
+        } else {
+            self.hits as f64 / calls as f64
+        }
+    }
+}
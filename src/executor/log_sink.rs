@@ -0,0 +1,43 @@
+use std::cell::RefCell;
+
+/// A `log()` call executed by the concrete execution, with its arguments evaluated.
+#[derive(Clone, Debug)]
+pub struct LogRecord {
+    /// The element ID of the `log()` statement.
+    pub elem_id: usize,
+    /// The ID of the template or function owning the statement.
+    pub owner_id: usize,
+    /// The owner-qualified name of the component or function call, e.g. `main.c[1]`.
+    pub owner: String,
+    /// The printed arguments: the strings as is, and the values of the expressions in decimal.
+    pub values: Vec<String>,
+}
+
+impl LogRecord {
+    /// Returns the line printed by the witness generator of circom, whose arguments are
+    /// separated by spaces.
+    pub fn message(&self) -> String {
+        self.values.join(" ")
+    }
+}
+
+/// A sink collecting the `log()` calls executed by the concrete execution, shared by the
+/// executors of the sub-components and functions.
+///
+/// `log()` has no effect on the constraints, so its arguments are only evaluated when a sink is
+/// set, i.e. when a counterexample is replayed with `--replay_logs`.
+#[derive(Default)]
+pub struct LogSink {
+    records: RefCell<Vec<LogRecord>>,
+}
+
+impl LogSink {
+    pub fn record(&self, record: LogRecord) {
+        self.records.borrow_mut().push(record);
+    }
+
+    /// Returns the recorded calls in execution order, leaving the sink empty.
+    pub fn take(&self) -> Vec<LogRecord> {
+        std::mem::take(&mut *self.records.borrow_mut())
+    }
+}
@@ -1,7 +1,18 @@
+pub mod algebraic_simplifier;
+pub mod concolic;
+pub mod constraint_store;
 pub mod coverage;
 pub mod debug_ast;
+pub mod function_summary;
+pub mod log_sink;
+pub mod structural_hash;
+pub mod symbolic_archive;
 pub mod symbolic_execution;
+pub mod symbolic_interner;
 pub mod symbolic_setting;
+pub mod symbolic_snapshot;
 pub mod symbolic_state;
 pub mod symbolic_value;
+pub mod template_semantics;
+pub mod trace_log;
 pub mod utils;
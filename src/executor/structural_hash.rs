@@ -0,0 +1,482 @@
+use std::hash::{Hash, Hasher};
+
+use program_structure::ast::ExpressionInfixOpcode;
+use rustc_hash::FxHashMap;
+
+use crate::executor::symbolic_value::{SymbolicAccess, SymbolicName, SymbolicValue};
+
+/// The 64-bit FNV-1a hash of a byte encoding written by its methods.
+///
+/// Unlike `FxHasher`, its values depend neither on the platform nor on the version of a
+/// dependency, so they can be stored in files and compared across machines.
+#[derive(Clone, Copy, Debug)]
+pub struct StableHasher(u64);
+
+impl Default for StableHasher {
+    fn default() -> Self {
+        StableHasher(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl StableHasher {
+    pub fn write_bytes(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= *byte as u64;
+            self.0 = self.0.wrapping_mul(0x0000_0100_0000_01b3);
+        }
+    }
+
+    /// Writes `v` as 8 little-endian bytes, whatever the width of its type.
+    pub fn write_u64(&mut self, v: u64) {
+        self.write_bytes(&v.to_le_bytes());
+    }
+
+    /// Writes `s` preceded by its length, so that `["ab", "c"]` and `["a", "bc"]` differ.
+    pub fn write_str(&mut self, s: &str) {
+        self.write_u64(s.len() as u64);
+        self.write_bytes(s.as_bytes());
+    }
+
+    pub fn finish(&self) -> u64 {
+        self.0
+    }
+}
+
+/// What a structural comparison of symbolic values abstracts away.
+///
+/// Symbols are always compared by their names rather than by their ids, so that the values of
+/// two circuits, whose libraries number the symbols differently, can be compared. Constants are
+/// compared as they are, without reducing them modulo the prime.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Equivalence {
+    /// Ignores the counters of the owners, which number the calls of a function, so that the
+    /// variables of two calls of the same function compare equal. `lookup_fmt` does not print
+    /// the counters either.
+    pub ignore_owner_counters: bool,
+    /// Compares the operands of `==`, `!=`, and of the associative and commutative operators
+    /// regardless of their order and grouping, so that `b * a + c` and `c + a * b` compare equal.
+    pub ignore_operand_order: bool,
+    /// Compares the symbols by their own names and accesses, without the components and
+    /// function calls owning them, so that `main.a[1].x` and `main.b.x` compare equal.
+    pub ignore_owners: bool,
+    /// Ignores the indices of the array accesses, so that `x[0]` and `x[i]` compare equal.
+    pub ignore_array_indices: bool,
+}
+
+impl Equivalence {
+    /// The equivalence of the findings sharing a root cause, e.g. the same constraint violated
+    /// in two components instantiating the same template or at two indices of an array.
+    pub const FINDINGS: Equivalence = Equivalence {
+        ignore_owner_counters: true,
+        ignore_operand_order: false,
+        ignore_owners: true,
+        ignore_array_indices: true,
+    };
+}
+
+/// Returns `true` if the operands of `op` can be reordered and regrouped.
+pub fn is_associative_commutative(op: &ExpressionInfixOpcode) -> bool {
+    matches!(
+        op,
+        ExpressionInfixOpcode::Add
+            | ExpressionInfixOpcode::Mul
+            | ExpressionInfixOpcode::BoolAnd
+            | ExpressionInfixOpcode::BoolOr
+            | ExpressionInfixOpcode::BitAnd
+            | ExpressionInfixOpcode::BitOr
+            | ExpressionInfixOpcode::BitXor
+    )
+}
+
+/// Collects the operands of a chain of `op`, e.g. `a`, `b`, and `c` of `a + (b + c)`.
+pub(crate) fn flatten_operands<'a>(
+    value: &'a SymbolicValue,
+    op: &ExpressionInfixOpcode,
+    operands: &mut Vec<&'a SymbolicValue>,
+) {
+    match value {
+        SymbolicValue::BinaryOp(lhs, inner_op, rhs)
+        | SymbolicValue::AuxBinaryOp(lhs, inner_op, rhs)
+            if inner_op.0 == *op =>
+        {
+            flatten_operands(lhs, op, operands);
+            flatten_operands(rhs, op, operands);
+        }
+        _ => operands.push(value),
+    }
+}
+
+/// Returns the operands of the binary operation `value`, flattened if `op` is associative and
+/// commutative, or `None` if their order matters under `equivalence`.
+fn unordered_operands<'a>(
+    value: &'a SymbolicValue,
+    lhs: &'a SymbolicValue,
+    op: &ExpressionInfixOpcode,
+    rhs: &'a SymbolicValue,
+    equivalence: Equivalence,
+) -> Option<Vec<&'a SymbolicValue>> {
+    if !equivalence.ignore_operand_order {
+        None
+    } else if is_associative_commutative(op) {
+        let mut operands = Vec::new();
+        flatten_operands(value, op, &mut operands);
+        Some(operands)
+    } else if matches!(op, ExpressionInfixOpcode::Eq | ExpressionInfixOpcode::NotEq) {
+        Some(vec![lhs, rhs])
+    } else {
+        None
+    }
+}
+
+/// Computes a hash of the structure of `value`, consistent with `structurally_equal`.
+///
+/// The hash does not depend on the ids of the symbols, nor on the process, the platform, or the
+/// build (see `StableHasher`), so it can be stored and compared across runs and circuits, e.g. to
+/// align the constraints of two versions of a circuit or to fingerprint findings.
+///
+/// # Parameters
+/// - `value`: The symbolic value to hash.
+/// - `id2name`: A map from ids to the names of the symbols of `value`.
+/// - `equivalence`: What the hash abstracts away.
+pub fn structural_hash(
+    value: &SymbolicValue,
+    id2name: &FxHashMap<usize, String>,
+    equivalence: Equivalence,
+) -> u64 {
+    let mut hasher = StableHasher::default();
+    hash_value(value, id2name, equivalence, &mut hasher);
+    hasher.finish()
+}
+
+/// Returns the number identifying the variant of `value` in the encoding of `hash_value`.
+fn variant_tag(value: &SymbolicValue) -> u8 {
+    match value {
+        SymbolicValue::NOP => 0,
+        SymbolicValue::ConstantInt(..) => 1,
+        SymbolicValue::ConstantBool(..) => 2,
+        SymbolicValue::Variable(..) => 3,
+        SymbolicValue::Assign(..) => 4,
+        SymbolicValue::AssignEq(..) => 5,
+        SymbolicValue::AssignTemplParam(..) => 6,
+        SymbolicValue::AssignCall(..) => 7,
+        SymbolicValue::BinaryOp(..) => 8,
+        SymbolicValue::AuxBinaryOp(..) => 9,
+        SymbolicValue::Conditional(..) => 10,
+        SymbolicValue::UnaryOp(..) => 11,
+        SymbolicValue::Array(..) => 12,
+        SymbolicValue::UniformArray(..) => 13,
+        SymbolicValue::Call(..) => 14,
+    }
+}
+
+fn hash_value(
+    value: &SymbolicValue,
+    id2name: &FxHashMap<usize, String>,
+    equivalence: Equivalence,
+    state: &mut StableHasher,
+) {
+    state.write_bytes(&[variant_tag(value)]);
+    match value {
+        SymbolicValue::NOP => {}
+        SymbolicValue::ConstantInt(v) => state.write_bytes(&v.to_signed_bytes_le()),
+        SymbolicValue::ConstantBool(b) => state.write_bytes(&[*b as u8]),
+        SymbolicValue::Variable(sym_name) => hash_name(sym_name, id2name, equivalence, state),
+        // The quadratic forms cached by `Assign` are derived from its right-hand side
+        SymbolicValue::Assign(lhs, rhs, is_safe, _)
+        | SymbolicValue::AssignCall(lhs, rhs, is_safe) => {
+            hash_value(lhs, id2name, equivalence, state);
+            hash_value(rhs, id2name, equivalence, state);
+            state.write_bytes(&[*is_safe as u8]);
+        }
+        SymbolicValue::AssignEq(lhs, rhs)
+        | SymbolicValue::AssignTemplParam(lhs, rhs)
+        | SymbolicValue::UniformArray(lhs, rhs) => {
+            hash_value(lhs, id2name, equivalence, state);
+            hash_value(rhs, id2name, equivalence, state);
+        }
+        SymbolicValue::BinaryOp(lhs, op, rhs) | SymbolicValue::AuxBinaryOp(lhs, op, rhs) => {
+            state.write_str(&format!("{:?}", op));
+            match unordered_operands(value, lhs, &op.0, rhs, equivalence) {
+                Some(operands) => {
+                    let mut hashes = operands
+                        .into_iter()
+                        .map(|operand| structural_hash(operand, id2name, equivalence))
+                        .collect::<Vec<_>>();
+                    hashes.sort();
+                    state.write_u64(hashes.len() as u64);
+                    for hash in hashes {
+                        state.write_u64(hash);
+                    }
+                }
+                None => {
+                    hash_value(lhs, id2name, equivalence, state);
+                    hash_value(rhs, id2name, equivalence, state);
+                }
+            }
+        }
+        SymbolicValue::Conditional(cond, if_true, if_false) => {
+            hash_value(cond, id2name, equivalence, state);
+            hash_value(if_true, id2name, equivalence, state);
+            hash_value(if_false, id2name, equivalence, state);
+        }
+        SymbolicValue::UnaryOp(op, expr) => {
+            state.write_str(&format!("{:?}", op));
+            hash_value(expr, id2name, equivalence, state);
+        }
+        SymbolicValue::Array(elems) => {
+            state.write_u64(elems.len() as u64);
+            for elem in elems {
+                hash_value(elem, id2name, equivalence, state);
+            }
+        }
+        SymbolicValue::Call(id, args) => {
+            state.write_str(&id2name[id]);
+            state.write_u64(args.len() as u64);
+            for arg in args {
+                hash_value(arg, id2name, equivalence, state);
+            }
+        }
+    }
+}
+
+fn hash_name(
+    sym_name: &SymbolicName,
+    id2name: &FxHashMap<usize, String>,
+    equivalence: Equivalence,
+    state: &mut StableHasher,
+) {
+    state.write_str(&id2name[&sym_name.id]);
+    if !equivalence.ignore_owners {
+        state.write_u64(sym_name.owner.len() as u64);
+        for owner in sym_name.owner.iter() {
+            state.write_str(&id2name[&owner.id]);
+            hash_accesses(&owner.access, id2name, equivalence, state);
+            if !equivalence.ignore_owner_counters {
+                state.write_u64(owner.counter as u64);
+            }
+        }
+    }
+    hash_accesses(&sym_name.access, id2name, equivalence, state);
+}
+
+fn hash_accesses(
+    accesses: &Option<Vec<SymbolicAccess>>,
+    id2name: &FxHashMap<usize, String>,
+    equivalence: Equivalence,
+    state: &mut StableHasher,
+) {
+    match accesses {
+        None => state.write_u64(0),
+        Some(accesses) => {
+            state.write_u64(accesses.len() as u64 + 1);
+            for access in accesses {
+                match access {
+                    SymbolicAccess::ComponentAccess(id) => {
+                        state.write_bytes(&[0]);
+                        state.write_str(&id2name[id]);
+                    }
+                    SymbolicAccess::ArrayAccess(index) => {
+                        state.write_bytes(&[1]);
+                        if !equivalence.ignore_array_indices {
+                            hash_value(index, id2name, equivalence, state);
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Checks whether two symbolic values have the same structure.
+///
+/// With the default `Equivalence`, two values of the same circuit are structurally equal if and
+/// only if they are equal.
+///
+/// # Parameters
+/// - `lhs`: The first symbolic value.
+/// - `lhs_id2name`: A map from ids to the names of the symbols of `lhs`.
+/// - `rhs`: The second symbolic value.
+/// - `rhs_id2name`: A map from ids to the names of the symbols of `rhs`, which may belong to
+///   another circuit.
+/// - `equivalence`: What the comparison abstracts away.
+pub fn structurally_equal(
+    lhs: &SymbolicValue,
+    lhs_id2name: &FxHashMap<usize, String>,
+    rhs: &SymbolicValue,
+    rhs_id2name: &FxHashMap<usize, String>,
+    equivalence: Equivalence,
+) -> bool {
+    StructuralComparison {
+        lhs_id2name,
+        rhs_id2name,
+        equivalence,
+    }
+    .values(lhs, rhs)
+}
+
+struct StructuralComparison<'a> {
+    lhs_id2name: &'a FxHashMap<usize, String>,
+    rhs_id2name: &'a FxHashMap<usize, String>,
+    equivalence: Equivalence,
+}
+
+impl StructuralComparison<'_> {
+    fn values(&self, lhs: &SymbolicValue, rhs: &SymbolicValue) -> bool {
+        match (lhs, rhs) {
+            (SymbolicValue::NOP, SymbolicValue::NOP) => true,
+            (SymbolicValue::ConstantInt(a), SymbolicValue::ConstantInt(b)) => a == b,
+            (SymbolicValue::ConstantBool(a), SymbolicValue::ConstantBool(b)) => a == b,
+            (SymbolicValue::Variable(a), SymbolicValue::Variable(b)) => self.names(a, b),
+            (SymbolicValue::Assign(l1, r1, s1, _), SymbolicValue::Assign(l2, r2, s2, _))
+            | (SymbolicValue::AssignCall(l1, r1, s1), SymbolicValue::AssignCall(l2, r2, s2)) => {
+                s1 == s2 && self.values(l1, l2) && self.values(r1, r2)
+            }
+            (SymbolicValue::AssignEq(l1, r1), SymbolicValue::AssignEq(l2, r2))
+            | (SymbolicValue::AssignTemplParam(l1, r1), SymbolicValue::AssignTemplParam(l2, r2))
+            | (SymbolicValue::UniformArray(l1, r1), SymbolicValue::UniformArray(l2, r2)) => {
+                self.values(l1, l2) && self.values(r1, r2)
+            }
+            (SymbolicValue::BinaryOp(l1, op1, r1), SymbolicValue::BinaryOp(l2, op2, r2))
+            | (SymbolicValue::AuxBinaryOp(l1, op1, r1), SymbolicValue::AuxBinaryOp(l2, op2, r2)) => {
+                if op1 != op2 {
+                    return false;
+                }
+                match (
+                    unordered_operands(lhs, l1, &op1.0, r1, self.equivalence),
+                    unordered_operands(rhs, l2, &op2.0, r2, self.equivalence),
+                ) {
+                    (Some(lhs_operands), Some(rhs_operands)) => {
+                        self.unordered(&lhs_operands, rhs_operands)
+                    }
+                    _ => self.values(l1, l2) && self.values(r1, r2),
+                }
+            }
+            (SymbolicValue::Conditional(c1, t1, f1), SymbolicValue::Conditional(c2, t2, f2)) => {
+                self.values(c1, c2) && self.values(t1, t2) && self.values(f1, f2)
+            }
+            (SymbolicValue::UnaryOp(op1, e1), SymbolicValue::UnaryOp(op2, e2)) => {
+                op1 == op2 && self.values(e1, e2)
+            }
+            (SymbolicValue::Array(elems1), SymbolicValue::Array(elems2)) => {
+                elems1.len() == elems2.len()
+                    && elems1.iter().zip(elems2).all(|(a, b)| self.values(a, b))
+            }
+            (SymbolicValue::Call(id1, args1), SymbolicValue::Call(id2, args2)) => {
+                self.lhs_id2name[id1] == self.rhs_id2name[id2]
+                    && args1.len() == args2.len()
+                    && args1.iter().zip(args2).all(|(a, b)| self.values(a, b))
+            }
+            _ => false,
+        }
+    }
+
+    /// Matches the operands as multisets. Structural equality is an equivalence relation, so
+    /// matching each operand of `lhs` with the first equal operand of `rhs` is enough.
+    fn unordered(&self, lhs: &[&SymbolicValue], mut rhs: Vec<&SymbolicValue>) -> bool {
+        if lhs.len() != rhs.len() {
+            return false;
+        }
+        for a in lhs {
+            match rhs.iter().position(|b| self.values(a, b)) {
+                Some(i) => {
+                    rhs.swap_remove(i);
+                }
+                None => return false,
+            }
+        }
+        true
+    }
+
+    fn names(&self, lhs: &SymbolicName, rhs: &SymbolicName) -> bool {
+        self.lhs_id2name[&lhs.id] == self.rhs_id2name[&rhs.id]
+            && (self.equivalence.ignore_owners
+                || lhs.owner.len() == rhs.owner.len()
+                    && lhs.owner.iter().zip(rhs.owner.iter()).all(|(a, b)| {
+                        self.lhs_id2name[&a.id] == self.rhs_id2name[&b.id]
+                            && (self.equivalence.ignore_owner_counters || a.counter == b.counter)
+                            && self.accesses(&a.access, &b.access)
+                    }))
+            && self.accesses(&lhs.access, &rhs.access)
+    }
+
+    fn accesses(
+        &self,
+        lhs: &Option<Vec<SymbolicAccess>>,
+        rhs: &Option<Vec<SymbolicAccess>>,
+    ) -> bool {
+        match (lhs, rhs) {
+            (None, None) => true,
+            (Some(lhs), Some(rhs)) => {
+                lhs.len() == rhs.len()
+                    && lhs.iter().zip(rhs).all(|(a, b)| match (a, b) {
+                        (
+                            SymbolicAccess::ComponentAccess(id1),
+                            SymbolicAccess::ComponentAccess(id2),
+                        ) => self.lhs_id2name[id1] == self.rhs_id2name[id2],
+                        (SymbolicAccess::ArrayAccess(i1), SymbolicAccess::ArrayAccess(i2)) => {
+                            self.equivalence.ignore_array_indices || self.values(i1, i2)
+                        }
+                        _ => false,
+                    })
+            }
+            _ => false,
+        }
+    }
+}
+
+/// A symbolic value keyed by its structure, to deduplicate or group the values of a circuit in
+/// hash sets and maps.
+///
+/// The keys of a collection are expected to share the same `Equivalence`.
+#[derive(Clone)]
+pub struct StructuralKey<'a> {
+    value: &'a SymbolicValue,
+    id2name: &'a FxHashMap<usize, String>,
+    equivalence: Equivalence,
+    hash: u64,
+}
+
+impl<'a> StructuralKey<'a> {
+    pub fn new(
+        value: &'a SymbolicValue,
+        id2name: &'a FxHashMap<usize, String>,
+        equivalence: Equivalence,
+    ) -> Self {
+        StructuralKey {
+            value,
+            id2name,
+            equivalence,
+            hash: structural_hash(value, id2name, equivalence),
+        }
+    }
+
+    pub fn value(&self) -> &'a SymbolicValue {
+        self.value
+    }
+
+    /// Returns the structural hash of the value (see `structural_hash`).
+    pub fn structural_hash(&self) -> u64 {
+        self.hash
+    }
+}
+
+impl PartialEq for StructuralKey<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.hash == other.hash
+            && structurally_equal(
+                self.value,
+                self.id2name,
+                other.value,
+                other.id2name,
+                self.equivalence,
+            )
+    }
+}
+
+impl Eq for StructuralKey<'_> {}
+
+impl Hash for StructuralKey<'_> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.hash.hash(state);
+    }
+}
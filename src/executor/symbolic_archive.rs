@@ -0,0 +1,215 @@
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter};
+use std::path::{Path, PathBuf};
+
+use rustc_hash::{FxHashMap, FxHashSet};
+use serde::{Deserialize, Serialize};
+
+use program_structure::ast::VariableType;
+
+use crate::executor::debug_ast::{DebuggableExpression, DebuggableStatement, VariableTypeDef};
+use crate::executor::symbolic_value::{SymbolicFunction, SymbolicLibrary, SymbolicTemplate};
+use crate::VERSION;
+
+#[derive(Clone, Serialize, Deserialize)]
+struct ArchivedVariableType(#[serde(with = "VariableTypeDef")] VariableType);
+
+/// The part of a `SymbolicTemplate` that does not depend on the settings of a run.
+///
+/// The semantics plugins and the whitelist flag are attached again when the archive is linked.
+#[derive(Clone, Serialize, Deserialize)]
+struct ArchivedTemplate {
+    template_parameter_names: Vec<usize>,
+    input_ids: FxHashSet<usize>,
+    output_ids: FxHashSet<usize>,
+    input_order: Vec<usize>,
+    output_order: Vec<usize>,
+    id2type: Vec<(usize, ArchivedVariableType)>,
+    id2tags: FxHashMap<usize, Vec<String>>,
+    id2dimension_expressions: FxHashMap<usize, Vec<DebuggableExpression>>,
+    body: Vec<DebuggableStatement>,
+    is_custom_gate: bool,
+}
+
+/// A precompiled set of templates and functions, produced by `zkfuzz compile-lib` and loaded with
+/// `--link` instead of re-parsing the sources of a library such as circomlib.
+#[derive(Serialize, Deserialize)]
+pub struct SymbolicLibraryArchive {
+    /// The version of zkFuzz that compiled the archive.
+    pub version: String,
+    /// The canonical paths of the source files compiled into the archive.
+    pub source_files: Vec<PathBuf>,
+    pub name2id: FxHashMap<String, usize>,
+    pub id2name: FxHashMap<usize, String>,
+    templates: Vec<(usize, ArchivedTemplate)>,
+    functions: Vec<(usize, SymbolicFunction)>,
+}
+
+fn invalid_data<E: ToString>(e: E) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, e.to_string())
+}
+
+impl SymbolicLibraryArchive {
+    /// Archives the templates and functions registered in `library`.
+    ///
+    /// # Arguments
+    ///
+    /// * `library` - The symbolic library the sources of the archive were registered in.
+    /// * `source_files` - The source files compiled into the archive.
+    /// * `excluded_templates` - Names of the templates that are not archived.
+    pub fn from_library(
+        library: &SymbolicLibrary,
+        source_files: Vec<PathBuf>,
+        excluded_templates: &[&str],
+    ) -> Self {
+        let mut templates: Vec<_> = library
+            .template_library
+            .iter()
+            .filter(|(id, _)| !excluded_templates.contains(&library.id2name[id].as_str()))
+            .map(|(id, template)| {
+                let mut id2type: Vec<_> = template
+                    .id2type
+                    .iter()
+                    .map(|(k, v)| (*k, ArchivedVariableType(v.clone())))
+                    .collect();
+                id2type.sort_by_key(|(k, _)| *k);
+                (
+                    *id,
+                    ArchivedTemplate {
+                        template_parameter_names: template.template_parameter_names.clone(),
+                        input_ids: template.input_ids.clone(),
+                        output_ids: template.output_ids.clone(),
+                        input_order: template.input_order.clone(),
+                        output_order: template.output_order.clone(),
+                        id2type: id2type,
+                        id2tags: template.id2tags.clone(),
+                        id2dimension_expressions: template.id2dimension_expressions.clone(),
+                        body: template.body.clone(),
+                        is_custom_gate: template.is_custom_gate,
+                    },
+                )
+            })
+            .collect();
+        templates.sort_by_key(|(id, _)| *id);
+
+        let mut functions: Vec<_> = library
+            .function_library
+            .iter()
+            .map(|(id, function)| (*id, (**function).clone()))
+            .collect();
+        functions.sort_by_key(|(id, _)| *id);
+
+        SymbolicLibraryArchive {
+            version: VERSION.to_string(),
+            source_files: source_files,
+            name2id: library.name2id.clone(),
+            id2name: library.id2name.clone(),
+            templates: templates,
+            functions: functions,
+        }
+    }
+
+    /// Returns the names of the archived templates, sorted alphabetically.
+    pub fn template_names(&self) -> Vec<&String> {
+        let mut names: Vec<_> = self
+            .templates
+            .iter()
+            .map(|(id, _)| &self.id2name[id])
+            .collect();
+        names.sort();
+        names
+    }
+
+    /// Returns the names of the archived functions, sorted alphabetically.
+    pub fn function_names(&self) -> Vec<&String> {
+        let mut names: Vec<_> = self
+            .functions
+            .iter()
+            .map(|(id, _)| &self.id2name[id])
+            .collect();
+        names.sort();
+        names
+    }
+
+    /// Writes the archive to `path` in the bincode format.
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        let writer = BufWriter::new(File::create(path)?);
+        bincode::serialize_into(writer, self).map_err(invalid_data)
+    }
+
+    /// Reads an archive written by `save`.
+    ///
+    /// # Returns
+    ///
+    /// The archive, or an error if the file cannot be read, is not an archive, or was compiled by
+    /// another version of zkFuzz.
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let reader = BufReader::new(File::open(path)?);
+        let archive: SymbolicLibraryArchive = bincode::deserialize_from(reader)
+            .map_err(|e| invalid_data(format!("not a symbolic library archive ({})", e)))?;
+        if archive.version != VERSION {
+            return Err(invalid_data(format!(
+                "the archive was compiled by zkFuzz {} (expected {}); recompile it with `zkfuzz compile-lib`",
+                archive.version, VERSION
+            )));
+        }
+        Ok(archive)
+    }
+
+    /// Registers the archived templates and functions in `library`.
+    ///
+    /// The archive keeps the IDs assigned to names when it was compiled, so it has to be linked
+    /// into an empty library, before any other template is registered. Templates registered
+    /// afterwards reuse the IDs of the archived names and can therefore refer to the archived
+    /// templates and functions.
+    ///
+    /// # Arguments
+    ///
+    /// * `library` - The (empty) library to link the archive into.
+    /// * `whitelist` - Names of the templates whose assignments are considered safe.
+    pub fn link_into(
+        self,
+        library: &mut SymbolicLibrary,
+        whitelist: &FxHashSet<String>,
+    ) -> Result<(), String> {
+        if !library.name2id.is_empty() {
+            return Err("an archive has to be linked into an empty symbolic library".to_string());
+        }
+        library.name2id = self.name2id;
+        library.id2name = self.id2name;
+
+        for (id, archived) in self.templates {
+            let name = library.id2name[&id].clone();
+            let mut template = Box::new(SymbolicTemplate {
+                template_parameter_names: archived.template_parameter_names,
+                input_ids: archived.input_ids,
+                output_ids: archived.output_ids,
+                input_order: archived.input_order,
+                output_order: archived.output_order,
+                id2type: archived
+                    .id2type
+                    .into_iter()
+                    .map(|(k, v)| (k, v.0))
+                    .collect(),
+                id2tags: archived.id2tags,
+                id2dimension_expressions: archived.id2dimension_expressions,
+                body: archived.body,
+                semantics: Vec::new(),
+                is_safe: whitelist.contains(&name),
+                is_custom_gate: archived.is_custom_gate,
+            });
+            template.semantics = library
+                .template_semantics
+                .iter()
+                .filter(|semantics| semantics.is_applicable(&name, &template))
+                .cloned()
+                .collect();
+            library.template_library.insert(id, template);
+        }
+        for (id, function) in self.functions {
+            library.function_library.insert(id, Box::new(function));
+            library.function_counter.insert(id, 0_usize);
+        }
+        Ok(())
+    }
+}
@@ -6,7 +6,7 @@ use colored::Colorize;
 use log::trace;
 use num_bigint_dig::BigInt;
 use num_traits::cast::ToPrimitive;
-use num_traits::FromPrimitive;
+use num_traits::{FromPrimitive, Zero};
 use rustc_hash::{FxHashMap, FxHashSet};
 
 use program_structure::ast::{
@@ -17,22 +17,31 @@ use program_structure::ast::{
 use crate::executor::coverage::CoverageTracker;
 use crate::executor::debug_ast::{
     DebugAccess, DebuggableAssignOp, DebuggableExpression, DebuggableExpressionInfixOpcode,
-    DebuggableStatement, DebuggableVariableType,
+    DebuggableLogArgument, DebuggableStatement, DebuggableVariableType,
 };
+use crate::executor::function_summary::{FunctionSummary, FunctionSummaryCache};
+use crate::executor::log_sink::LogRecord;
+use crate::executor::symbolic_interner::{resolve, SymbolId};
 use crate::executor::symbolic_setting::SymbolicExecutorSetting;
-use crate::executor::symbolic_state::SymbolicState;
+use crate::executor::symbolic_state::{ConstraintProvenance, SymbolBindingMap, SymbolicState};
 use crate::executor::symbolic_value::{
     access_multidimensional_array, decompose_uniform_array, enumerate_array, evaluate_binary_op,
-    extract_variables_from_symbolic_value, generate_lessthan_constraint,
+    extract_variables_from_symbolic_value, find_symbolic_condition,
     get_coefficient_of_polynomials, get_degree_polynomial,
     initialize_symbolic_nested_array_with_value, is_concrete_array, register_array_elements,
-    update_nested_array, OwnerName, SymbolicAccess, SymbolicComponent, SymbolicLibrary,
-    SymbolicName, SymbolicTemplate, SymbolicValue, SymbolicValueRef,
+    split_conditional_constraint, update_nested_array, OwnerName, SymbolicAccess,
+    SymbolicComponent, SymbolicLibrary, SymbolicName, SymbolicTemplate, SymbolicValue,
+    SymbolicValueRef,
+};
+use crate::executor::utils::{
+    complement, generate_cartesian_product_indices, modreduce, strip_ansi,
 };
-use crate::executor::utils::generate_cartesian_product_indices;
 
 use super::symbolic_value::ExecutionResult;
 
+/// The maximum number of times `resolve_size` simplifies the size of an array dimension.
+const MAX_SIZE_SIMPLIFICATIONS: usize = 16;
+
 pub struct SymbolicStore {
     pub components_store: FxHashMap<SymbolicName, SymbolicComponent>,
     pub variable_types: FxHashMap<usize, DebuggableVariableType>,
@@ -46,6 +55,171 @@ impl SymbolicStore {
     }
 }
 
+/// Merges the reachability of branches, e.g. recorded by a sub-executor, into `branches`.
+fn merge_branches<I>(branches: &mut FxHashMap<usize, (usize, bool, bool)>, reached: I)
+where
+    I: IntoIterator<Item = (usize, (usize, bool, bool))>,
+{
+    for (elem_id, (owner_id, then_reached, else_reached)) in reached {
+        let entry = branches.entry(elem_id).or_insert((owner_id, false, false));
+        entry.1 |= then_reached;
+        entry.2 |= else_reached;
+    }
+}
+
+/// Describes the dimensions of a value as in a circom declaration, e.g. `dimensions [2][3]`.
+fn dimensions_fmt(dims: &[usize]) -> String {
+    if dims.is_empty() {
+        "a scalar".to_string()
+    } else {
+        format!(
+            "dimensions {}",
+            dims.iter().map(|d| format!("[{}]", d)).collect::<String>()
+        )
+    }
+}
+
+/// Returns `name` with the array accesses `pos` appended.
+fn append_array_accesses(name: &SymbolicName, pos: &[usize]) -> SymbolicName {
+    if pos.is_empty() {
+        return name.clone();
+    }
+    let mut access = name.access.clone().unwrap_or_default();
+    access.extend(pos.iter().map(|p| {
+        SymbolicAccess::ArrayAccess(SymbolicValue::ConstantInt(BigInt::from_usize(*p).unwrap()))
+    }));
+    SymbolicName::new(name.id, name.owner.clone(), Some(access))
+}
+
+/// Splits `value`, of dimensions `dims`, into its elements with their positions in row-major
+/// order. An array is enumerated, and a variable naming a whole array is expanded into the
+/// variables of its elements.
+fn expand_elements(value: &SymbolicValue, dims: &[usize]) -> Vec<(Vec<usize>, SymbolicValue)> {
+    match value {
+        SymbolicValue::Array(..) => enumerate_array(value)
+            .into_iter()
+            .map(|(pos, elem)| (pos, elem.clone()))
+            .collect(),
+        SymbolicValue::Variable(var_name) if !dims.is_empty() => {
+            generate_cartesian_product_indices(dims)
+                .into_iter()
+                .map(|pos| {
+                    let elem_name = append_array_accesses(var_name, &pos);
+                    (pos, SymbolicValue::Variable(elem_name))
+                })
+                .collect()
+        }
+        _ => vec![(Vec::new(), value.clone())],
+    }
+}
+
+/// Builds the nested array of dimensions `dims` from its elements in row-major order.
+fn nest_elements<I>(dims: &[usize], elements: &mut I) -> SymbolicValue
+where
+    I: Iterator<Item = SymbolicValue>,
+{
+    match dims.split_first() {
+        Some((len, rest)) => SymbolicValue::Array(
+            (0..*len)
+                .map(|_| Rc::new(nest_elements(rest, elements)))
+                .collect(),
+        ),
+        None => elements
+            .next()
+            .unwrap_or(SymbolicValue::ConstantInt(BigInt::zero())),
+    }
+}
+
+/// Why a template instantiation or a function call was not executed.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum RecursionErrorKind {
+    /// The call would nest deeper than `max_call_depth` calls, e.g. a recursion whose
+    /// termination depends on a symbolic argument.
+    DepthExceeded,
+    /// The call repeats a call being executed with the same arguments, and never terminates.
+    Cycle,
+}
+
+/// A template instantiation or a function call stopped by the recursion checks, with the chain
+/// of calls leading to it.
+#[derive(Clone, Debug)]
+pub struct RecursionError {
+    pub kind: RecursionErrorKind,
+    /// The names of the templates and functions being executed, outermost first, followed by
+    /// the stopped call.
+    pub call_chain: Vec<String>,
+}
+
+impl RecursionError {
+    pub fn message(&self) -> String {
+        let chain = self.call_chain.join(" → ");
+        match self.kind {
+            RecursionErrorKind::DepthExceeded => {
+                format!("call depth limit exceeded: {}", chain)
+            }
+            RecursionErrorKind::Cycle => format!("cyclic call: {}", chain),
+        }
+    }
+}
+
+/// An assignment of a signal with `<--` or `<==`, recorded during symbolic execution.
+#[derive(Clone, Debug)]
+pub struct SignalAssignment {
+    /// The assigned signal.
+    pub name: SymbolicName,
+    /// The element ID of the assigning statement.
+    pub elem_id: usize,
+    /// The ID of the template owning the assigning statement.
+    pub owner_id: usize,
+    /// Whether the signal is an input of the template owning the assigning statement.
+    pub is_input: bool,
+}
+
+/// An `assert` executed within a function during symbolic execution.
+///
+/// A function produces no constraint of its own, so the condition of the assertion becomes a path
+/// condition of its caller: it is added to the symbolic trace with the provenance of the call
+/// site in the calling template, which `zkfuzz why` reports.
+#[derive(Clone, Debug)]
+pub struct FunctionAssertion {
+    /// The element ID of the `assert` statement.
+    pub elem_id: usize,
+    /// The ID of the function owning the `assert` statement.
+    pub function_id: usize,
+    /// The statement of the template that (directly or through other functions) called the
+    /// function.
+    pub call_site: ConstraintProvenance,
+    /// The simplified condition of the assertion.
+    pub condition: SymbolicValueRef,
+}
+
+/// A component whose inputs are not all assigned by the end of the execution of its owner, so
+/// that it is never executed and its constraints are missing.
+#[derive(Clone, Debug)]
+pub struct IncompleteComponent {
+    /// The name of the component, e.g. `main.c[1]`.
+    pub name: String,
+    /// The name of the template of the component.
+    pub template_name: String,
+    /// The inputs that are never assigned, e.g. `main.c[1].in[0]`, in lexicographic order.
+    pub missing_inputs: Vec<String>,
+}
+
+impl IncompleteComponent {
+    pub fn message(&self) -> String {
+        format!(
+            "`{}` of template `{}` is never executed: {} not assigned",
+            self.name,
+            self.template_name,
+            self.missing_inputs
+                .iter()
+                .map(|input| format!("`{}`", input))
+                .collect::<Vec<_>>()
+                .join(", ")
+        )
+    }
+}
+
 /// A symbolic execution engine for analyzing and executing statements symbolically.
 ///
 /// The `SymbolicExecutor` maintains multiple execution states, handles branching logic,
@@ -63,9 +237,43 @@ impl SymbolicStore {
 /// * `symbolic_store` – A structured store maintaining components, variable types, and execution states.
 /// * `cur_state` – The current symbolic execution state being processed.
 /// * `violated_condition` – An optional tuple indicating a violated condition (index and symbolic value).
+/// * `violated_assertion` – An optional tuple indicating the first `assert` violated during concrete execution
+///   (its metadata and the violated condition).
+/// * `divisions` – The divisions whose denominator is not a nonzero constant during symbolic execution, keyed by
+///   the element ID of their statement (the ID of the owning template or function and the denominator).
+/// * `symbolic_ternaries` – The `<==` assignments whose right-hand side contains a ternary with a non-constant
+///   condition, keyed by the element ID of their statement (the ID of the owning template and the condition).
+/// * `shape_mismatches` – The multi-substitutions `(a, b) <== A()(x)` whose targets do not match the number or the
+///   dimensions of the assigned values, keyed by the element ID of their statement (the ID of the owning template
+///   and a description of the mismatch).
+/// * `undetermined_dimensions` – The declarations of arrays whose size is not a constant that fits in `usize` once
+///   the arguments are bound, keyed by the element ID of their statement (the ID of the owning template or function
+///   and a description of the size).
+/// * `division_by_zero` – An optional tuple indicating the first division by zero during concrete execution
+///   (the element ID of its statement, the ID of the owning template or function, and the denominator).
+/// * `branches` – The `if` statements executed during symbolic execution, keyed by their element ID (the ID of the
+///   owning template or function, and whether the then and the else branches are reachable).
+/// * `called_functions` – The IDs of the functions called during symbolic execution.
+/// * `function_summaries` – The memoized function calls, shared with the sub-executors.
+/// * `component_templates` – A mapping from the owner names of the executed components to their template IDs.
+/// * `component_args` – A mapping from the owner names of the executed components to the arguments of their templates.
 /// * `id2dimensions` – A mapping from identifiers to their respective dimensional constraints.
 /// * `mindim` – The minimum dimensionality constraint applied during execution.
 /// * `execution_failed` – A flag indicating whether execution has encountered a failure.
+/// * `signal_assignments` – The assignments of signals with `<--` or `<==` during symbolic execution, in the order
+///   they are executed. An assignment made in both branches of a symbolic `if` is recorded once.
+/// * `call_stack` – The templates and functions being executed, outermost first, with their arguments.
+/// * `recursion_error` – An optional `RecursionError` describing the first call stopped for exceeding
+///   `max_call_depth` or repeating a call being executed.
+/// * `incomplete_components` – The components whose inputs are not all assigned by the end of the execution of
+///   their owner (see `record_incomplete_components`).
+/// * `function_assertions` – The `assert` statements executed within functions during symbolic execution, in the
+///   order they are executed.
+/// * `cur_elem_id` – The element ID of the statement being executed, recorded as the provenance of the
+///   constraints it produces.
+/// * `call_site` – The provenance of the outermost function call being executed, to which the assertions within
+///   functions are attributed, or `None` outside of functions.
+/// * `logged_bindings` – The bindings of `cur_state` when they were last recorded in the sink of `--trace_jsonl`.
 /// * `coverage_tracker` – An internal tracker for execution path coverage (enabled when configured).
 /// * `enable_coverage_tracking` – A flag controlling whether execution path coverage is tracked.
 /// * `is_concrete_mode` – A flag indicating whether the engine is running in concrete execution mode.
@@ -75,9 +283,28 @@ pub struct SymbolicExecutor<'a> {
     pub symbolic_store: SymbolicStore,
     pub cur_state: SymbolicState,
     pub violated_condition: Option<(usize, SymbolicValue)>,
+    pub violated_assertion: Option<(Meta, SymbolicValue)>,
+    pub divisions: FxHashMap<usize, (usize, SymbolicValue)>,
+    pub symbolic_ternaries: FxHashMap<usize, (usize, SymbolicValue)>,
+    pub shape_mismatches: FxHashMap<usize, (usize, String)>,
+    pub undetermined_dimensions: FxHashMap<usize, (usize, String)>,
+    pub division_by_zero: Option<(usize, usize, SymbolicValue)>,
+    pub branches: FxHashMap<usize, (usize, bool, bool)>,
+    pub called_functions: FxHashSet<usize>,
+    pub function_summaries: FunctionSummaryCache,
+    pub component_templates: FxHashMap<Vec<OwnerName>, usize>,
+    pub component_args: FxHashMap<Vec<OwnerName>, Vec<SymbolicValueRef>>,
     pub id2dimensions: FxHashMap<usize, Vec<usize>>,
     pub mindim: usize,
     pub execution_failed: bool,
+    pub signal_assignments: Vec<SignalAssignment>,
+    pub call_stack: Vec<(usize, Vec<SymbolicValueRef>)>,
+    pub recursion_error: Option<RecursionError>,
+    pub incomplete_components: Vec<IncompleteComponent>,
+    pub function_assertions: Vec<FunctionAssertion>,
+    cur_elem_id: usize,
+    call_site: Option<ConstraintProvenance>,
+    logged_bindings: SymbolBindingMap,
     coverage_tracker: CoverageTracker,
     enable_coverage_tracking: bool,
     is_concrete_mode: bool,
@@ -109,9 +336,28 @@ impl<'a> SymbolicExecutor<'a> {
             },
             cur_state: SymbolicState::new(),
             violated_condition: None,
+            violated_assertion: None,
+            divisions: FxHashMap::default(),
+            symbolic_ternaries: FxHashMap::default(),
+            shape_mismatches: FxHashMap::default(),
+            undetermined_dimensions: FxHashMap::default(),
+            division_by_zero: None,
+            branches: FxHashMap::default(),
+            called_functions: FxHashSet::default(),
+            function_summaries: FunctionSummaryCache::default(),
+            component_templates: FxHashMap::default(),
+            component_args: FxHashMap::default(),
             id2dimensions: FxHashMap::default(),
             mindim: std::usize::MAX,
             execution_failed: false,
+            signal_assignments: Vec::new(),
+            call_stack: Vec::new(),
+            recursion_error: None,
+            incomplete_components: Vec::new(),
+            function_assertions: Vec::new(),
+            cur_elem_id: usize::MAX,
+            call_site: None,
+            logged_bindings: SymbolBindingMap::default(),
             coverage_tracker: CoverageTracker::new(),
             setting: setting,
             enable_coverage_tracking: false,
@@ -145,6 +391,12 @@ impl<'a> SymbolicExecutor<'a> {
     /// and resets the function counter in the symbolic library.
     pub fn clear(&mut self) {
         self.cur_state = SymbolicState::new();
+        self.violated_assertion = None;
+        self.division_by_zero = None;
+        self.recursion_error = None;
+        self.incomplete_components.clear();
+        self.cur_elem_id = usize::MAX;
+        self.logged_bindings = SymbolBindingMap::default();
         self.symbolic_store.clear();
         self.symbolic_library.clear_function_counter();
         self.coverage_tracker.clear_current_path();
@@ -162,6 +414,8 @@ impl<'a> SymbolicExecutor<'a> {
     pub fn feed_arguments(&mut self, names: &Vec<String>, args: &Vec<Expression>) {
         let mut name2id = self.symbolic_library.name2id.clone();
         let mut id2name = self.symbolic_library.id2name.clone();
+        // The arguments are not produced by a statement of the circuit
+        self.cur_elem_id = usize::MAX;
         for (n, a) in names.iter().zip(args.iter()) {
             let evaled_a = self.evaluate_expression(
                 &DebuggableExpression::from(a.clone(), &mut name2id, &mut id2name),
@@ -177,8 +431,8 @@ impl<'a> SymbolicExecutor<'a> {
             );
             self.cur_state.set_sym_val(sym_name, simplified_a);
             if self.setting.keep_track_constraints {
-                self.cur_state.push_symbolic_trace(&cond);
-                self.cur_state.push_side_constraint(&cond);
+                self.push_symbolic_trace(&cond);
+                self.push_side_constraint(&cond);
             }
         }
     }
@@ -210,6 +464,11 @@ impl<'a> SymbolicExecutor<'a> {
                 }
             }
 
+            self.log_statement(&statements[cur_bid]);
+            if let Some(meta) = statements[cur_bid].get_meta() {
+                self.cur_elem_id = meta.elem_id;
+            }
+
             match &statements[cur_bid] {
                 DebuggableStatement::InitializationBlock { .. } => {
                     self.handle_initialization_block(statements, cur_bid);
@@ -241,18 +500,11 @@ impl<'a> SymbolicExecutor<'a> {
                 DebuggableStatement::Assert { .. } => {
                     self.handle_assert(statements, cur_bid);
                 }
-                DebuggableStatement::UnderscoreSubstitution {
-                    meta,
-                    op: _,
-                    rhe: _,
-                    ..
-                } => {
-                    self.trace_if_enabled(&meta);
-                    self.execute(statements, cur_bid + 1);
+                DebuggableStatement::UnderscoreSubstitution { .. } => {
+                    self.handle_underscore_substitution(statements, cur_bid);
                 }
-                DebuggableStatement::LogCall { meta, .. } => {
-                    self.trace_if_enabled(&meta);
-                    self.execute(statements, cur_bid + 1);
+                DebuggableStatement::LogCall { .. } => {
+                    self.handle_log_call(statements, cur_bid);
                 }
                 DebuggableStatement::Ret => {
                     self.handle_ret();
@@ -337,8 +589,7 @@ impl<'a> SymbolicExecutor<'a> {
     /// it defaults to `0`.
     ///
     /// # Behavior
-    /// - Each dimension expression is evaluated and simplified.
-    /// - If the simplified result is a constant integer, it is converted to `usize`.
+    /// - Each dimension expression is evaluated and resolved with `resolve_size`.
     /// - If the result cannot be determined (e.g., due to unresolved symbolic values), the dimension is set to `0`.
     pub fn evaluate_dimension(
         &mut self,
@@ -348,24 +599,141 @@ impl<'a> SymbolicExecutor<'a> {
         dims.iter()
             .map(|arg0: &DebuggableExpression| {
                 let evaled_arg0 = self.evaluate_expression(arg0, elem_id);
-                let mut memo = FxHashSet::default();
-                let simplified_arg0 =
-                    self.simplify_variables(&evaled_arg0, elem_id, false, false, &mut memo);
-                if let SymbolicValue::ConstantInt(bint) = &simplified_arg0 {
-                    bint.to_usize().unwrap()
-                } else {
-                    /*
-                    panic!(
-                        "Undetermined dimension: {}",
-                        simplified_arg0.lookup_fmt(&self.symbolic_library.id2name)
-                    )
-                    */
-                    0
-                }
+                self.resolve_size(&evaled_arg0, elem_id).unwrap_or(0)
             })
             .collect::<Vec<_>>()
     }
 
+    /// Resolves the size of an array dimension to a `usize`.
+    ///
+    /// The value is simplified until it becomes a constant or stops changing, so that a size that
+    /// flows through intermediate variables, e.g., `var m = n + 1; var arr[m];` in a function, is
+    /// resolved whenever the arguments of the call are concrete.
+    ///
+    /// # Parameters
+    /// - `size`: The evaluated size.
+    /// - `elem_id`: The element ID used for variable evaluation.
+    ///
+    /// # Returns
+    /// The size, or the simplified value if it is not a constant that fits in `usize`, such as a
+    /// symbolic or negative size.
+    fn resolve_size(
+        &mut self,
+        size: &SymbolicValue,
+        elem_id: usize,
+    ) -> Result<usize, SymbolicValue> {
+        let mut value = size.clone();
+        for _ in 0..MAX_SIZE_SIMPLIFICATIONS {
+            let mut memo = FxHashSet::default();
+            let simplified = self.simplify_variables(&value, elem_id, false, false, &mut memo);
+            let is_fixed = simplified == value;
+            value = simplified;
+            if is_fixed || matches!(value, SymbolicValue::ConstantInt(_)) {
+                break;
+            }
+        }
+        if let SymbolicValue::ConstantInt(bint) = &value {
+            if let Some(size) = bint.to_usize() {
+                return Ok(size);
+            }
+        }
+        Err(value)
+    }
+
+    /// Splits an array access whose indices are not constant after simplification into cases.
+    ///
+    /// Each symbolic index is bounded by the declared dimension of the array, and the access is
+    /// rewritten into a chain of conditionals with one case per combination of index values, e.g.,
+    /// `arr[i]` with `var arr[2]` becomes `(i == 0) ? arr[0] : ((i == 1) ? arr[1] : arr[i])`. The
+    /// original access is kept as the last alternative, which is reached only when an index is out
+    /// of range.
+    ///
+    /// # Parameters
+    /// - `id`: The identifier of the accessed array.
+    /// - `dims`: The evaluated array accesses.
+    /// - `sv`: The current value of the array, if it is bound to a concrete array.
+    ///
+    /// # Returns
+    /// The case-split access, or `None` if every index is constant, the dimensions of the array
+    /// are unknown, or the number of cases exceeds `max_index_case_splits`.
+    fn split_symbolic_array_access(
+        &mut self,
+        id: usize,
+        dims: &[SymbolicAccess],
+        sv: Option<&SymbolicValueRef>,
+    ) -> Option<SymbolicValue> {
+        let mut symbolic_indices = Vec::new();
+        for (pos, dim) in dims.iter().enumerate() {
+            match dim {
+                SymbolicAccess::ArrayAccess(SymbolicValue::ConstantInt(_)) => {}
+                SymbolicAccess::ArrayAccess(index) => symbolic_indices.push((pos, index.clone())),
+                SymbolicAccess::ComponentAccess(_) => return None,
+            }
+        }
+        if symbolic_indices.is_empty() || self.setting.max_index_case_splits == 0 {
+            return None;
+        }
+
+        let bounds = self.id2dimensions.get(&id)?;
+        if bounds.len() < dims.len() {
+            return None;
+        }
+        let sizes: Vec<usize> = symbolic_indices.iter().map(|(pos, _)| bounds[*pos]).collect();
+        let num_cases = sizes
+            .iter()
+            .try_fold(1_usize, |acc, size| acc.checked_mul(*size))?;
+        if num_cases == 0 || num_cases > self.setting.max_index_case_splits {
+            return None;
+        }
+
+        let mut result = SymbolicValue::Variable(SymbolicName::new(
+            id,
+            self.cur_state.owner_name.clone(),
+            Some(dims.to_vec()),
+        ));
+        for case in generate_cartesian_product_indices(&sizes).into_iter().rev() {
+            let mut concrete_dims = dims.to_vec();
+            let mut condition = None;
+            for ((pos, index), value) in symbolic_indices.iter().zip(case.into_iter()) {
+                let value = SymbolicValue::ConstantInt(BigInt::from_usize(value).unwrap());
+                concrete_dims[*pos] = SymbolicAccess::ArrayAccess(value.clone());
+                let eq = SymbolicValue::BinaryOp(
+                    Rc::new(index.clone()),
+                    DebuggableExpressionInfixOpcode(ExpressionInfixOpcode::Eq),
+                    Rc::new(value),
+                );
+                condition = Some(match condition {
+                    None => eq,
+                    Some(c) => SymbolicValue::BinaryOp(
+                        Rc::new(c),
+                        DebuggableExpressionInfixOpcode(ExpressionInfixOpcode::BoolAnd),
+                        Rc::new(eq),
+                    ),
+                });
+            }
+
+            let element = match sv.map(|v| &**v) {
+                Some(SymbolicValue::Array(values)) => {
+                    match access_multidimensional_array(values, &concrete_dims) {
+                        ExecutionResult::Success(v) => v,
+                        _ => return None,
+                    }
+                }
+                _ => SymbolicValue::Variable(SymbolicName::new(
+                    id,
+                    self.cur_state.owner_name.clone(),
+                    Some(concrete_dims),
+                )),
+            };
+            result = SymbolicValue::Conditional(
+                Rc::new(condition.unwrap()),
+                Rc::new(element),
+                Rc::new(result),
+            );
+        }
+        Some(result)
+    }
+
     /// Simplifies a given symbolic value (`sym_val`) based on the specified settings for constant
     /// and variable simplifications, returning a potentially simplified version of the input.
     ///
@@ -510,6 +878,12 @@ impl<'a> SymbolicExecutor<'a> {
                     only_variable_simplification,
                     memo,
                 );
+                if let ExpressionInfixOpcode::Div
+                | ExpressionInfixOpcode::IntDiv
+                | ExpressionInfixOpcode::Mod = infix_op.0
+                {
+                    self.record_division(elem_id, rv, &rhs);
+                }
                 evaluate_binary_op(&lhs, &rhs, &self.setting.prime, infix_op)
             }
             SymbolicValue::Conditional(cond, then_val, else_val) => {
@@ -580,7 +954,12 @@ impl<'a> SymbolicExecutor<'a> {
                 );
                 match &simplified_sym_val {
                     SymbolicValue::ConstantInt(rv) => match prefix_op.0 {
-                        ExpressionPrefixOpcode::Sub => SymbolicValue::ConstantInt(-1 * rv),
+                        ExpressionPrefixOpcode::Sub => {
+                            SymbolicValue::ConstantInt(modreduce(&-rv, &self.setting.prime))
+                        }
+                        ExpressionPrefixOpcode::Complement => {
+                            SymbolicValue::ConstantInt(complement(rv, &self.setting.prime))
+                        }
                         _ => SymbolicValue::UnaryOp(prefix_op.clone(), Rc::new(simplified_sym_val)),
                     },
                     SymbolicValue::ConstantBool(rv) => match prefix_op.0 {
@@ -690,7 +1069,7 @@ impl<'a> SymbolicExecutor<'a> {
                     {
                         if let None = template.id2type.get(id) {
                             if sv.is_some() && component_name.is_none() {
-                                match &*sv.unwrap() {
+                                match &**sv.as_ref().unwrap() {
                                     SymbolicValue::Array(values) => {
                                         let ama = access_multidimensional_array(&values, &dims);
                                         if let ExecutionResult::Success(v) = ama {
@@ -706,6 +1085,13 @@ impl<'a> SymbolicExecutor<'a> {
                         }
                     }
 
+                    if component_name.is_none() {
+                        if let Some(v) = self.split_symbolic_array_access(*id, &dims, sv.as_ref())
+                        {
+                            return v;
+                        }
+                    }
+
                     self.construct_symbolic_name(*id, access, elem_id).1
                 };
                 SymbolicValue::Variable(resolved_sym_name)
@@ -761,7 +1147,7 @@ impl<'a> SymbolicExecutor<'a> {
                     .map(|arg| self.evaluate_expression(arg, elem_id))
                     .collect();
                 let mut memo = FxHashSet::default();
-                let simplified_args = evaluated_args
+                let simplified_args: Vec<SymbolicValueRef> = evaluated_args
                     .iter()
                     .map(|arg| {
                         Rc::new(self.simplify_variables(&arg, elem_id, false, false, &mut memo))
@@ -770,10 +1156,29 @@ impl<'a> SymbolicExecutor<'a> {
                 if self.symbolic_library.template_library.contains_key(id) {
                     SymbolicValue::Call(*id, simplified_args)
                 } else if self.symbolic_library.function_library.contains_key(id) {
+                    if self.setting.keep_track_constraints {
+                        self.called_functions.insert(*id);
+                    }
+                    if self.setting.function_summary_capacity > 0 {
+                        if let Some(summary) = self.function_summaries.get(*id, &simplified_args) {
+                            return self.apply_function_summary(summary);
+                        }
+                    }
+                    if !self.check_call(*id, &simplified_args) {
+                        return SymbolicValue::Call(*id, simplified_args);
+                    }
                     let symbolic_library = &mut self.symbolic_library;
                     let mut subse_setting = self.setting.clone();
                     subse_setting.only_initialization_blocks = false;
                     let mut subse = SymbolicExecutor::new(symbolic_library, &subse_setting);
+                    subse.function_summaries = std::mem::take(&mut self.function_summaries);
+                    subse.call_stack = self.call_stack.clone();
+                    subse.call_stack.push((*id, simplified_args.clone()));
+                    subse.call_site = Some(
+                        self.call_site
+                            .clone()
+                            .unwrap_or_else(|| self.cur_provenance()),
+                    );
 
                     let mut updated_owner_list = (*self.cur_state.owner_name).clone();
                     updated_owner_list.push(OwnerName {
@@ -806,17 +1211,65 @@ impl<'a> SymbolicExecutor<'a> {
                     }
 
                     subse.execute(&func.body.clone(), 0);
+                    self.function_summaries = std::mem::take(&mut subse.function_summaries);
+                    // A call that fails or reports a violation is not memoized, so that every
+                    // such call reports it.
+                    let summary = if self.setting.function_summary_capacity > 0
+                        && !subse.execution_failed
+                        && !subse.cur_state.contains_symbolic_loop
+                        && subse.violated_assertion.is_none()
+                        && subse.division_by_zero.is_none()
+                        && subse.recursion_error.is_none()
+                        && subse.undetermined_dimensions.is_empty()
+                    {
+                        Some(FunctionSummary {
+                            return_value: SymbolicValue::Call(*id, Vec::new()),
+                            symbolic_trace: subse.cur_state.symbolic_trace.clone(),
+                            assertions: subse.function_assertions.clone(),
+                            mindim: subse.mindim,
+                            divisions: subse.divisions.clone(),
+                            branches: subse.branches.clone(),
+                            called_functions: subse.called_functions.clone(),
+                        })
+                    } else {
+                        None
+                    };
                     self.mindim = std::cmp::min(subse.mindim, self.mindim);
+                    if self.violated_assertion.is_none() {
+                        self.violated_assertion = subse.violated_assertion.take();
+                    }
+                    // A violated assertion within the function fails the execution of the caller
+                    if subse.cur_state.is_failed && !self.cur_state.is_failed {
+                        self.cur_state.is_failed = true;
+                        self.violated_condition = subse.violated_condition.take();
+                    }
+                    if self.division_by_zero.is_none() {
+                        self.division_by_zero = subse.division_by_zero.take();
+                    }
+                    if self.recursion_error.is_none() {
+                        self.recursion_error = subse.recursion_error.take();
+                    }
+                    self.function_assertions
+                        .extend(std::mem::take(&mut subse.function_assertions));
+                    self.divisions.extend(std::mem::take(&mut subse.divisions));
+                    self.undetermined_dimensions
+                        .extend(std::mem::take(&mut subse.undetermined_dimensions));
+                    merge_branches(&mut self.branches, std::mem::take(&mut subse.branches));
+                    self.called_functions
+                        .extend(std::mem::take(&mut subse.called_functions));
 
                     if !subse.setting.off_trace {
                         trace!("{}", format!("{}", "===========================").cyan());
                     }
 
-                    if !subse.cur_state.contains_symbolic_loop {
+                    let result = if !subse.cur_state.contains_symbolic_loop {
                         // NOTE: a function does not produce any constraint
                         self.cur_state
                             .symbolic_trace
                             .append(&mut subse.cur_state.symbolic_trace);
+                        self.cur_state
+                            .trace_provenance
+                            .append(&mut subse.cur_state.trace_provenance);
                         self.execution_failed = subse.execution_failed;
 
                         let return_sym_name =
@@ -831,19 +1284,37 @@ impl<'a> SymbolicExecutor<'a> {
                                 if is_concrete_array(&return_value) {
                                     return_value
                                 } else {
-                                    SymbolicValue::Call(*id, simplified_args)
+                                    SymbolicValue::Call(*id, simplified_args.clone())
                                 }
                             }
                         }
                     } else {
-                        SymbolicValue::Call(*id, simplified_args)
+                        SymbolicValue::Call(*id, simplified_args.clone())
+                    };
+
+                    if let Some(mut summary) = summary {
+                        summary.return_value = result.clone();
+                        self.function_summaries.insert(
+                            *id,
+                            simplified_args,
+                            summary,
+                            self.setting.function_summary_capacity,
+                        );
                     }
+                    result
                 } else {
                     panic!("Unknown Callee: {}", self.symbolic_library.id2name[id]);
                 }
             }
+            DebuggableExpression::AnonymousComp {
+                id,
+                params,
+                signals,
+                names,
+                ..
+            } => self.instantiate_anonymous_component(*id, params, signals, names, elem_id),
             _ => {
-                // We currently do not support BusCall and AnonymousComp.
+                // We currently do not support BusCall.
                 panic!(
                     "Unhandled expression type: {}",
                     expr.lookup_fmt(&self.symbolic_library.id2name, 0)
@@ -851,6 +1322,121 @@ impl<'a> SymbolicExecutor<'a> {
             }
         }
     }
+
+    /// Adds the constraints that a custom gate is assumed to enforce to the side constraints.
+    ///
+    /// The constraints of a custom template (`template custom`) are defined by the proving backend
+    /// rather than by the circuit, so they are opaque to zkFuzz. Instead, the gate is assumed to
+    /// constrain each of its outputs to the value computed by its `<--` assignments, which turns
+    /// those assignments into side constraints over the inputs and outputs of the gate. Such
+    /// assumptions are not verified and are reported as such.
+    ///
+    /// # Parameters
+    /// - `output_ids`: The IDs of the output signals of the custom template.
+    fn assume_custom_gate_constraints(&mut self, output_ids: &FxHashSet<usize>) {
+        // Each assumption is attributed to the `<--` assignment it is derived from
+        let assumptions: Vec<_> = self
+            .cur_state
+            .symbolic_trace
+            .iter()
+            .zip(self.cur_state.trace_provenance.iter())
+            .filter_map(|(c, provenance)| match &**c {
+                SymbolicValue::Assign(lhs, rhs, ..) => match &**lhs {
+                    SymbolicValue::Variable(name)
+                        if output_ids.contains(&name.id)
+                            && name.owner == self.cur_state.owner_name =>
+                    {
+                        Some((
+                            SymbolicValue::AssignEq(lhs.clone(), rhs.clone()),
+                            provenance.clone(),
+                        ))
+                    }
+                    _ => None,
+                },
+                _ => None,
+            })
+            .collect();
+        for (assumption, provenance) in assumptions {
+            self.log_constraint("side", &assumption);
+            self.cur_state.push_side_constraint(&assumption, provenance);
+        }
+    }
+
+    /// Records a division whose denominator may be zero.
+    ///
+    /// circom evaluates a division by zero to `0` instead of failing, so the witness generator
+    /// silently picks a value that the constraints may not enforce. During symbolic execution,
+    /// every division whose denominator is not a nonzero constant is kept in `divisions`. During
+    /// concrete execution, the first division by zero of a run that satisfies all constraints so
+    /// far is kept in `division_by_zero`.
+    ///
+    /// # Parameters
+    /// - `elem_id`: The element ID of the statement containing the division.
+    /// - `denominator`: The denominator as written in the statement.
+    /// - `simplified_denominator`: The denominator after simplification.
+    fn record_division(
+        &mut self,
+        elem_id: usize,
+        denominator: &SymbolicValue,
+        simplified_denominator: &SymbolicValue,
+    ) {
+        if elem_id == std::usize::MAX {
+            return;
+        }
+        let is_zero = match simplified_denominator {
+            SymbolicValue::ConstantInt(v) => (v % &self.setting.prime).is_zero(),
+            SymbolicValue::ConstantBool(b) => !b,
+            _ => false,
+        };
+        let is_constant = matches!(
+            simplified_denominator,
+            SymbolicValue::ConstantInt(_) | SymbolicValue::ConstantBool(_)
+        );
+        let owner_id = self.cur_state.template_id;
+        if self.setting.keep_track_constraints {
+            if is_zero || !is_constant {
+                self.divisions
+                    .entry(elem_id)
+                    .or_insert_with(|| (owner_id, denominator.clone()));
+            }
+        } else if is_zero && !self.cur_state.is_failed && self.division_by_zero.is_none() {
+            self.division_by_zero = Some((elem_id, owner_id, denominator.clone()));
+        }
+    }
+
+    /// Applies the memoized summary of a function call instead of executing the function.
+    ///
+    /// The trace fragment of the summary keeps the owner names of the call that produced it.
+    /// Since the call is determined by its arguments, the fragment assigns the same values.
+    ///
+    /// # Parameters
+    /// - `summary`: The summary of a previous call with the same arguments.
+    ///
+    /// # Returns
+    /// The return value of the call.
+    fn apply_function_summary(&mut self, summary: FunctionSummary) -> SymbolicValue {
+        self.mindim = std::cmp::min(summary.mindim, self.mindim);
+        self.divisions.extend(summary.divisions);
+        merge_branches(&mut self.branches, summary.branches);
+        self.called_functions.extend(summary.called_functions);
+        // The assertions of the function are attributed to the call site of this call
+        let call_site = self
+            .call_site
+            .clone()
+            .unwrap_or_else(|| self.cur_provenance());
+        self.cur_state
+            .trace_provenance
+            .extend(summary.symbolic_trace.iter().map(|_| call_site.clone()));
+        self.cur_state.symbolic_trace.extend(summary.symbolic_trace);
+        for assertion in summary.assertions {
+            self.function_assertions.push(FunctionAssertion {
+                call_site: call_site.clone(),
+                ..assertion
+            });
+        }
+        self.execution_failed = false;
+        summary.return_value
+    }
 }
 
 impl<'a> SymbolicExecutor<'a> {
@@ -921,8 +1507,14 @@ impl<'a> SymbolicExecutor<'a> {
     /// - Evaluates the condition and simplifies it.
     /// - If the condition resolves to `true`, the if-case is executed.
     /// - If the condition resolves to `false` and an else-case exists, the else-case is executed.
-    /// - If the condition cannot be simplified to a constant boolean, symbolic loops are flagged in the state.
-    /// - Branch coverage is recorded if enabled.
+    /// - If the condition cannot be simplified to a constant boolean:
+    ///   - Within a template body, both branches are executed and their final states are merged
+    ///     under the condition (see `merge_symbolic_branches`).
+    ///   - Within a function body, symbolic loops are flagged in the state.
+    ///   - With a concolic guide in the setting, the branch taken by its seed is executed
+    ///     instead, as long as the seed determines the condition.
+    /// - Branch coverage is recorded if enabled, and the reachability of both branches is
+    ///   recorded in `branches` if constraints are tracked.
     /// - Continues execution with the next statement after the `if-then-else`.
     fn handle_if_then_else(&mut self, statements: &Vec<DebuggableStatement>, cur_bid: usize) {
         if let DebuggableStatement::IfThenElse {
@@ -945,9 +1537,11 @@ impl<'a> SymbolicExecutor<'a> {
                     if self.enable_coverage_tracking {
                         self.coverage_tracker.record_branch(meta.elem_id, true);
                     }
+                    self.record_branch_reachability(meta.elem_id, true, else_case.is_none());
                     self.execute(&vec![*if_case.clone()], 0);
                 }
                 SymbolicValue::ConstantBool(false) => {
+                    self.record_branch_reachability(meta.elem_id, false, true);
                     if let Some(stmt) = else_case {
                         if self.enable_coverage_tracking {
                             self.coverage_tracker.record_branch(meta.elem_id, false);
@@ -956,13 +1550,254 @@ impl<'a> SymbolicExecutor<'a> {
                     }
                 }
                 _ => {
-                    self.cur_state.contains_symbolic_loop = true;
+                    self.record_branch_reachability(meta.elem_id, true, true);
+                    match self.decide_by_concolic_guide(meta.elem_id, &simplified_condition) {
+                        Some(true) => self.execute(&vec![*if_case.clone()], 0),
+                        Some(false) => {
+                            if let Some(stmt) = else_case {
+                                self.execute(&vec![*stmt.clone()], 0);
+                            }
+                        }
+                        None => {
+                            if self
+                                .symbolic_library
+                                .function_library
+                                .contains_key(&self.cur_state.template_id)
+                            {
+                                self.cur_state.contains_symbolic_loop = true;
+                            } else {
+                                self.merge_symbolic_branches(
+                                    &simplified_condition,
+                                    if_case,
+                                    else_case,
+                                );
+                            }
+                        }
+                    }
                 }
             }
             self.execute(statements, cur_bid + 1);
         }
     }
 
+    /// Records which branches of the `if` statement at `elem_id` are reachable.
+    ///
+    /// A statement is executed once per instantiation of its template or call of its function, so
+    /// a branch is reported as unreachable only if none of the executions reaches it.
+    fn record_branch_reachability(
+        &mut self,
+        elem_id: usize,
+        then_reached: bool,
+        else_reached: bool,
+    ) {
+        if self.setting.keep_track_constraints {
+            let owner_id = self.cur_state.template_id;
+            merge_branches(
+                &mut self.branches,
+                std::iter::once((elem_id, (owner_id, then_reached, else_reached))),
+            );
+        }
+    }
+
+    /// Decides a symbolic branch condition with the seed of the concolic guide, if any.
+    ///
+    /// # Returns
+    /// The value of `cond` under the seed, which is recorded as a path condition, or `None` if
+    /// there is no guide or the seed does not determine `cond`.
+    fn decide_by_concolic_guide(&mut self, elem_id: usize, cond: &SymbolicValue) -> Option<bool> {
+        let setting = self.setting;
+        let guide = setting.concolic_guide.as_ref()?;
+        let mut memo = FxHashSet::default();
+        match self.simplify_variables(&guide.concretize(cond), elem_id, true, false, &mut memo) {
+            SymbolicValue::ConstantBool(taken) => {
+                // The initialization blocks are executed again with the body of the template
+                if !setting.only_initialization_blocks {
+                    guide.record(elem_id, cond, taken);
+                }
+                Some(taken)
+            }
+            _ => None,
+        }
+    }
+
+    /// Executes both branches of an `if-then-else` whose condition is symbolic and merges the
+    /// resulting states into the current one.
+    ///
+    /// Each branch is executed on its own copy of the current state. The merged state is guarded
+    /// by `cond` so that no path is dropped:
+    /// - Bindings that differ between the branches become `cond ? then_value : else_value`.
+    /// - `<--` assignments to the same signal in both branches are combined into a single
+    ///   assignment of the conditional value.
+    /// - Any other trace entry or side constraint produced by only one branch is guarded as
+    ///   `cond ? entry : true` (or `cond ? true : entry` for the else-branch).
+    ///
+    /// # Parameters
+    /// - `cond`: The simplified, non-constant condition of the `if-then-else`.
+    /// - `if_case`: The statement executed when `cond` holds.
+    /// - `else_case`: The optional statement executed when `cond` does not hold.
+    fn merge_symbolic_branches(
+        &mut self,
+        cond: &SymbolicValue,
+        if_case: &DebuggableStatement,
+        else_case: &Option<Box<DebuggableStatement>>,
+    ) {
+        let num_trace = self.cur_state.symbolic_trace.len();
+        let num_side_constraints = self.cur_state.side_constraints.len();
+        let num_assignments = self.signal_assignments.len();
+        let fork = self.cur_state.symbol_binding_map.begin_fork();
+        let initial_state = self.cur_state.fork();
+
+        self.execute(&vec![if_case.clone()], 0);
+        let mut then_state = std::mem::replace(&mut self.cur_state, initial_state);
+        // The else-branch continues from the constraints gathered before the fork, and only
+        // those added by the then-branch are kept in `then_state`
+        then_state.hand_over_constraints(num_trace, num_side_constraints, &mut self.cur_state);
+        let num_then_assignments = self.signal_assignments.len();
+        if let Some(stmt) = else_case {
+            self.execute(&vec![*stmt.clone()], 0);
+        }
+
+        // Only one of the branches is taken, so a signal assigned in both is assigned once
+        let then_assigned: FxHashSet<SymbolicName> = self.signal_assignments
+            [num_assignments..num_then_assignments]
+            .iter()
+            .map(|a| a.name.clone())
+            .collect();
+        let else_assignments = self.signal_assignments.split_off(num_then_assignments);
+        self.signal_assignments.extend(
+            else_assignments
+                .into_iter()
+                .filter(|a| !then_assigned.contains(&a.name)),
+        );
+
+        let cond = Rc::new(cond.clone());
+        let always = Rc::new(SymbolicValue::ConstantBool(true));
+
+        // Only the entries written by either branch may differ, so the others are not visited
+        let written: FxHashSet<SymbolId> = then_state
+            .symbol_binding_map
+            .written_since(fork)
+            .chain(self.cur_state.symbol_binding_map.written_since(fork))
+            .collect();
+        for id in written {
+            let merged = match (
+                then_state.symbol_binding_map.get_by_id(id),
+                self.cur_state.symbol_binding_map.get_by_id(id),
+            ) {
+                (Some(then_val), Some(else_val))
+                    if !Rc::ptr_eq(then_val, else_val) && then_val != else_val =>
+                {
+                    Rc::new(SymbolicValue::Conditional(
+                        cond.clone(),
+                        then_val.clone(),
+                        else_val.clone(),
+                    ))
+                }
+                (Some(then_val), None) => then_val.clone(),
+                _ => continue,
+            };
+            self.cur_state.symbol_binding_map.insert_by_id(id, merged);
+        }
+        self.cur_state.symbol_binding_map.end_fork();
+
+        // Each merged entry keeps the provenance of the entry of the then-branch
+        let mut else_trace: Vec<_> = self
+            .cur_state
+            .symbolic_trace
+            .split_off(num_trace)
+            .into_iter()
+            .zip(self.cur_state.trace_provenance.split_off(num_trace))
+            .collect();
+        for (entry, provenance) in then_state
+            .symbolic_trace
+            .iter()
+            .zip(then_state.trace_provenance.iter())
+        {
+            if let SymbolicValue::Assign(lhs, then_rhs, is_safe, _) = &**entry {
+                let pos = else_trace
+                    .iter()
+                    .position(|(e, _)| matches!(&**e, SymbolicValue::Assign(l, ..) if l == lhs));
+                if let Some(pos) = pos {
+                    if let SymbolicValue::Assign(_, else_rhs, ..) = &*else_trace.remove(pos).0 {
+                        self.cur_state
+                            .symbolic_trace
+                            .push(Rc::new(SymbolicValue::Assign(
+                                lhs.clone(),
+                                Rc::new(SymbolicValue::Conditional(
+                                    cond.clone(),
+                                    then_rhs.clone(),
+                                    else_rhs.clone(),
+                                )),
+                                *is_safe,
+                                None,
+                            )));
+                        self.cur_state.trace_provenance.push(provenance.clone());
+                        continue;
+                    }
+                }
+            }
+            self.cur_state
+                .symbolic_trace
+                .push(Rc::new(SymbolicValue::Conditional(
+                    cond.clone(),
+                    entry.clone(),
+                    always.clone(),
+                )));
+            self.cur_state.trace_provenance.push(provenance.clone());
+        }
+        for (entry, provenance) in else_trace {
+            self.cur_state
+                .symbolic_trace
+                .push(Rc::new(SymbolicValue::Conditional(
+                    cond.clone(),
+                    always.clone(),
+                    entry,
+                )));
+            self.cur_state.trace_provenance.push(provenance);
+        }
+
+        let else_side_constraints = self
+            .cur_state
+            .side_constraints
+            .split_off(num_side_constraints);
+        let else_side_constraint_provenance = self
+            .cur_state
+            .side_constraint_provenance
+            .split_off(num_side_constraints);
+        for (entry, provenance) in then_state
+            .side_constraints
+            .iter()
+            .zip(then_state.side_constraint_provenance.iter())
+        {
+            self.cur_state
+                .side_constraints
+                .push(Rc::new(SymbolicValue::Conditional(
+                    cond.clone(),
+                    entry.clone(),
+                    always.clone(),
+                )));
+            self.cur_state
+                .side_constraint_provenance
+                .push(provenance.clone());
+        }
+        for (entry, provenance) in else_side_constraints
+            .into_iter()
+            .zip(else_side_constraint_provenance)
+        {
+            self.cur_state
+                .side_constraints
+                .push(Rc::new(SymbolicValue::Conditional(
+                    cond.clone(),
+                    always.clone(),
+                    entry,
+                )));
+            self.cur_state.side_constraint_provenance.push(provenance);
+        }
+
+        self.cur_state.contains_symbolic_loop |= then_state.contains_symbolic_loop;
+        self.cur_state.is_failed |= then_state.is_failed;
+    }
+
     /// Handles the substitution of a value to a variable or data structure within a set of statements.
     ///
     /// This function processes a `Substitution` statement, performing symbolic evaluation and updates
@@ -1054,6 +1889,15 @@ impl<'a> SymbolicExecutor<'a> {
                 }
             }
 
+            if is_bulk_assignment {
+                for lvn in left_var_names.iter() {
+                    self.record_signal_assignment(op, lvn, meta.elem_id);
+                }
+            } else {
+                self.record_signal_assignment(op, &left_var_name, meta.elem_id);
+            }
+            self.record_symbolic_ternary(op, &simplified_rhe, meta.elem_id);
+
             if let SymbolicValue::Call(callee_name, args) = &simplified_rhe {
                 self.handle_call_substitution(
                     op,
@@ -1106,6 +1950,12 @@ impl<'a> SymbolicExecutor<'a> {
         {
             self.trace_if_enabled(&meta);
 
+            if let DebuggableExpression::Tuple { values } = lhe {
+                self.handle_tuple_destructuring(values, op, rhe, meta.elem_id);
+                self.execute(statements, cur_bid + 1);
+                return;
+            }
+
             let lhe_val = self.evaluate_expression(lhe, meta.elem_id);
             let rhe_val = self.evaluate_expression(rhe, meta.elem_id);
             let mut memo_left = FxHashSet::default();
@@ -1118,12 +1968,15 @@ impl<'a> SymbolicExecutor<'a> {
             if self.setting.keep_track_constraints {
                 match op {
                     DebuggableAssignOp(AssignOp::AssignConstraintSignal) => {
-                        let cont = SymbolicValue::AssignEq(
-                            Rc::new(simplified_lhe_val),
-                            Rc::new(simplified_rhe_val),
-                        );
-                        self.cur_state.push_symbolic_trace(&cont);
-                        self.cur_state.push_side_constraint(&cont);
+                        let lhs = Rc::new(simplified_lhe_val);
+                        let constraints =
+                            split_conditional_constraint(&Rc::new(simplified_rhe_val), &|rhs| {
+                                SymbolicValue::AssignEq(lhs.clone(), rhs)
+                            });
+                        for cont in constraints {
+                            self.push_symbolic_trace(&cont);
+                            self.push_side_constraint(&cont);
+                        }
                     }
                     DebuggableAssignOp(AssignOp::AssignSignal) => {
                         let cont = SymbolicValue::Assign(
@@ -1133,7 +1986,7 @@ impl<'a> SymbolicExecutor<'a> {
                                 .is_safe,
                             None,
                         );
-                        self.cur_state.push_symbolic_trace(&cont);
+                        self.push_symbolic_trace(&cont);
                     }
                     _ => {}
                 }
@@ -1160,6 +2013,336 @@ impl<'a> SymbolicExecutor<'a> {
     ///   - `false`: Skips the loop body and proceeds to the next statement.
     /// - If the condition cannot be fully resolved (symbolic loop), marks the current state as containing
     ///   a symbolic loop and skips the loop execution.
+    /// Evaluates the right-hand side of `_ <== rhe` (or `_ <-- rhe`, `_ = rhe`) and discards its
+    /// value, as circom does. The right-hand side is still executed, so the assertions and
+    /// divisions of the functions it calls are checked, and a template it calls is instantiated
+    /// as a component under a fresh name (see `anonymous_component_name`) whose constraints are
+    /// collected. An anonymous component on the right-hand side is instantiated while the
+    /// right-hand side is evaluated (see `instantiate_anonymous_component`).
+    fn handle_underscore_substitution(
+        &mut self,
+        statements: &Vec<DebuggableStatement>,
+        cur_bid: usize,
+    ) {
+        if let DebuggableStatement::UnderscoreSubstitution { meta, op, rhe } = &statements[cur_bid]
+        {
+            self.trace_if_enabled(meta);
+
+            let evaled_rhe = self.evaluate_expression(rhe, meta.elem_id);
+            let mut memo = FxHashSet::default();
+            let simplified_rhe =
+                self.simplify_variables(&evaled_rhe, meta.elem_id, true, false, &mut memo);
+            if let SymbolicValue::Call(callee_id, args) = &simplified_rhe {
+                if self
+                    .symbolic_library
+                    .template_library
+                    .contains_key(callee_id)
+                {
+                    let component_name = self.anonymous_component_name(*callee_id);
+                    self.handle_call_substitution(
+                        op,
+                        callee_id,
+                        args,
+                        &component_name,
+                        &simplified_rhe,
+                    );
+                }
+            }
+            self.execute(statements, cur_bid + 1);
+        }
+    }
+
+    /// Assigns the elements of the tuple `rhe` to the signals of the tuple `targets`, as in
+    /// `(a, _, c) <== A()(x)`, skipping the targets written `_`. A value that is not a tuple is
+    /// assigned as a tuple of one value.
+    ///
+    /// A target that is an array, as `arr` in `(arr, b) <== A()(x)`, is assigned element by
+    /// element. If the number of targets differs from the number of values, or the dimensions of
+    /// a target differ from those of its value, nothing is assigned and the statement is recorded
+    /// in `shape_mismatches`, as circom rejects it.
+    fn handle_tuple_destructuring(
+        &mut self,
+        targets: &Vec<DebuggableExpression>,
+        op: &DebuggableAssignOp,
+        rhe: &DebuggableExpression,
+        elem_id: usize,
+    ) {
+        let evaled_rhe = self.evaluate_expression(rhe, elem_id);
+        let mut memo = FxHashSet::default();
+        let simplified_rhe = self.simplify_variables(&evaled_rhe, elem_id, true, false, &mut memo);
+        let mut memo = FxHashSet::default();
+        let semi_simplified_rhe =
+            self.simplify_variables(&evaled_rhe, elem_id, true, true, &mut memo);
+        let (values, semi_simplified_values) = match (simplified_rhe, semi_simplified_rhe) {
+            (SymbolicValue::Array(values), SymbolicValue::Array(semi_simplified_values))
+                if values.len() == semi_simplified_values.len() =>
+            {
+                (values, semi_simplified_values)
+            }
+            (SymbolicValue::Array(values), _) => (values.clone(), values),
+            (value, semi_simplified_value) => {
+                (vec![Rc::new(value)], vec![Rc::new(semi_simplified_value)])
+            }
+        };
+
+        if targets.len() != values.len() {
+            self.record_shape_mismatch(
+                elem_id,
+                format!(
+                    "a tuple of {} targets is assigned {} values",
+                    targets.len(),
+                    values.len()
+                ),
+            );
+            return;
+        }
+
+        let mut assignments = Vec::new();
+        for (target, (value, semi_simplified_value)) in targets
+            .iter()
+            .zip(values.iter().zip(semi_simplified_values.iter()))
+        {
+            if let DebuggableExpression::Variable { id, access } = target {
+                if self
+                    .symbolic_library
+                    .id2name
+                    .get(id)
+                    .map_or(false, |n| n == "_")
+                {
+                    continue;
+                }
+                let (base_name, var_name) = self.construct_symbolic_name(*id, access, elem_id);
+                let target_dims = self.remaining_dimensions(&var_name);
+                if let (Some(target_dims), Some(value_dims)) =
+                    (&target_dims, self.dimensions_of_value(value))
+                {
+                    if *target_dims != value_dims {
+                        let message = format!(
+                            "`{}` of {} is assigned a value of {}",
+                            strip_ansi(&var_name.lookup_fmt(&self.symbolic_library.id2name)),
+                            dimensions_fmt(target_dims),
+                            dimensions_fmt(&value_dims)
+                        );
+                        self.record_shape_mismatch(elem_id, message);
+                        return;
+                    }
+                }
+                assignments.push((
+                    *id,
+                    access,
+                    base_name,
+                    var_name,
+                    target_dims.unwrap_or_default(),
+                    value,
+                    semi_simplified_value,
+                ));
+            }
+        }
+
+        for (id, access, base_name, var_name, dims, value, semi_simplified_value) in assignments {
+            let elements = expand_elements(value, &dims);
+            let semi_simplified_elements = expand_elements(semi_simplified_value, &dims);
+            let semi_simplified_elements = if semi_simplified_elements.len() == elements.len() {
+                semi_simplified_elements
+            } else {
+                elements.clone()
+            };
+            for ((pos, elem), (_, semi_simplified_elem)) in
+                elements.iter().zip(semi_simplified_elements.iter())
+            {
+                let elem_name = append_array_accesses(&var_name, pos);
+                self.cur_state.set_sym_val(elem_name.clone(), elem.clone());
+                self.record_signal_assignment(op, &elem_name, elem_id);
+                self.handle_non_call_substitution(op, &elem_name, semi_simplified_elem);
+            }
+            if !access.is_empty() {
+                let value = if dims.is_empty() {
+                    (**value).clone()
+                } else {
+                    nest_elements(&dims, &mut elements.into_iter().map(|(_, elem)| elem))
+                };
+                self.handle_component_access(id, access, &base_name, &value, elem_id);
+            }
+        }
+    }
+
+    /// Instantiates the anonymous component `A(params)(signals)` under a fresh name (see
+    /// `anonymous_component_name`), feeds `signals` to its inputs, and returns its output, or
+    /// the tuple of its outputs in the order of their declarations if it has several.
+    ///
+    /// With named arguments, as in `A(n)(in2 <== y, in1 <== x)`, each signal is fed to the input
+    /// it names with the operator it is written with. Otherwise, the signals are fed to the inputs
+    /// in the order of their declarations with `<==`. The component is executed once all its
+    /// inputs are fed.
+    fn instantiate_anonymous_component(
+        &mut self,
+        template_id: usize,
+        params: &Vec<DebuggableExpression>,
+        signals: &Vec<DebuggableExpression>,
+        names: &Option<Vec<(DebuggableAssignOp, usize)>>,
+        elem_id: usize,
+    ) -> SymbolicValue {
+        let args = params
+            .iter()
+            .map(|param| {
+                let evaled_param = self.evaluate_expression(param, elem_id);
+                let mut memo = FxHashSet::default();
+                Rc::new(self.simplify_variables(&evaled_param, elem_id, true, false, &mut memo))
+            })
+            .collect::<Vec<_>>();
+        let component_name = self.anonymous_component_name(template_id);
+        self.initialize_template_component(&template_id, &args, &component_name);
+
+        let template = &self.symbolic_library.template_library[&template_id];
+        let inputs = match names {
+            Some(names) => names.clone(),
+            None => template
+                .input_order
+                .iter()
+                .map(|id| (DebuggableAssignOp(AssignOp::AssignConstraintSignal), *id))
+                .collect(),
+        };
+        let output_order = template.output_order.clone();
+
+        let mut owner_name = (*self.cur_state.owner_name).clone();
+        owner_name.push(OwnerName {
+            id: component_name.id,
+            counter: 0,
+            access: component_name.access.clone(),
+        });
+        let owner_name = Rc::new(owner_name);
+
+        for ((op, input_id), signal) in inputs.iter().zip(signals.iter()) {
+            let evaled_signal = self.evaluate_expression(signal, elem_id);
+            let mut memo = FxHashSet::default();
+            let value = self.simplify_variables(&evaled_signal, elem_id, true, false, &mut memo);
+            self.feed_anonymous_component_input(
+                &component_name,
+                &owner_name,
+                op,
+                *input_id,
+                &value,
+                elem_id,
+            );
+        }
+
+        if self.is_ready(&component_name) {
+            let pre_dims = component_name.access.clone().unwrap_or_default();
+            self.execute_ready_component(component_name.id, &component_name, &pre_dims);
+        }
+
+        let mut outputs = output_order
+            .into_iter()
+            .map(|id| SymbolicValue::Variable(SymbolicName::new(id, owner_name.clone(), None)))
+            .collect::<Vec<_>>();
+        if outputs.len() == 1 {
+            outputs.pop().unwrap()
+        } else {
+            SymbolicValue::Array(outputs.into_iter().map(Rc::new).collect())
+        }
+    }
+
+    /// Assigns `value` to the input `input_id` of the anonymous component `component_name`, whose
+    /// signals are owned by `owner_name`, element by element if the input is an array, and binds
+    /// it to the input of the component.
+    fn feed_anonymous_component_input(
+        &mut self,
+        component_name: &SymbolicName,
+        owner_name: &Rc<Vec<OwnerName>>,
+        op: &DebuggableAssignOp,
+        input_id: usize,
+        value: &SymbolicValue,
+        elem_id: usize,
+    ) {
+        let dims = self.symbolic_store.components_store[component_name]
+            .id2dimensions
+            .get(&input_id)
+            .cloned()
+            .unwrap_or_default();
+        let elements: Vec<(Vec<usize>, SymbolicValue)> = match value {
+            SymbolicValue::Array(..) => enumerate_array(value)
+                .into_iter()
+                .map(|(pos, elem)| (pos, elem.clone()))
+                .collect(),
+            SymbolicValue::Variable(var_name) if !dims.is_empty() => {
+                generate_cartesian_product_indices(&dims)
+                    .into_iter()
+                    .map(|pos| {
+                        let mut elem_name = var_name.clone();
+                        let mut access = elem_name.access.unwrap_or_default();
+                        access.extend(pos.iter().map(|p| {
+                            SymbolicAccess::ArrayAccess(SymbolicValue::ConstantInt(
+                                BigInt::from_usize(*p).unwrap(),
+                            ))
+                        }));
+                        elem_name.access = Some(access);
+                        elem_name.update_hash();
+                        (pos, SymbolicValue::Variable(elem_name))
+                    })
+                    .collect()
+            }
+            _ => vec![(Vec::new(), value.clone())],
+        };
+
+        for (pos, elem) in elements {
+            let access = if pos.is_empty() {
+                None
+            } else {
+                Some(
+                    pos.iter()
+                        .map(|p| {
+                            SymbolicAccess::ArrayAccess(SymbolicValue::ConstantInt(
+                                BigInt::from_usize(*p).unwrap(),
+                            ))
+                        })
+                        .collect::<Vec<_>>(),
+                )
+            };
+            let signal_name = SymbolicName::new(input_id, owner_name.clone(), access.clone());
+            self.cur_state
+                .set_sym_val(signal_name.clone(), elem.clone());
+            self.record_signal_assignment(op, &signal_name, elem_id);
+            self.handle_non_call_substitution(op, &signal_name, &elem);
+            if let Some(component) = self.symbolic_store.components_store.get_mut(component_name) {
+                component.inputs_binding_map.insert(
+                    SymbolicName::new(input_id, Rc::new(Vec::new()), access),
+                    Some(elem),
+                );
+            }
+        }
+    }
+
+    /// Returns a fresh name for a component of template `template_id` that is instantiated without
+    /// a name, by an underscore substitution or as an anonymous component, e.g. `_Square[1]` for
+    /// the second such instance of `Square` in the current component, so that the signals of the
+    /// instances are distinct.
+    fn anonymous_component_name(&mut self, template_id: usize) -> SymbolicName {
+        let name = format!("_{}", self.symbolic_library.id2name[&template_id]);
+        let id = match self.symbolic_library.name2id.get(&name) {
+            Some(id) => *id,
+            None => {
+                let id = self.symbolic_library.name2id.len();
+                self.symbolic_library.name2id.insert(name.clone(), id);
+                self.symbolic_library.id2name.insert(id, name);
+                id
+            }
+        };
+        let owner = self.cur_state.owner_name.clone();
+        let index = self
+            .symbolic_store
+            .components_store
+            .keys()
+            .filter(|n| n.id == id && n.owner == owner)
+            .count();
+        SymbolicName::new(
+            id,
+            owner,
+            Some(vec![SymbolicAccess::ArrayAccess(
+                SymbolicValue::ConstantInt(BigInt::from(index)),
+            )]),
+        )
+    }
+
     fn handle_while(&mut self, statements: &Vec<DebuggableStatement>, cur_bid: usize) {
         if let DebuggableStatement::While {
             meta, cond, stmt, ..
@@ -1172,7 +2355,11 @@ impl<'a> SymbolicExecutor<'a> {
             let evaled_condition =
                 self.simplify_variables(&tmp_cond, meta.elem_id, true, false, &mut memo);
 
-            if let SymbolicValue::ConstantBool(flag) = evaled_condition {
+            let flag = match evaled_condition {
+                SymbolicValue::ConstantBool(flag) => Some(flag),
+                _ => self.decide_by_concolic_guide(meta.elem_id, &evaled_condition),
+            };
+            if let Some(flag) = flag {
                 if flag {
                     self.execute(&vec![*stmt.clone()], 0);
                     self.execute(statements, cur_bid);
@@ -1232,6 +2419,8 @@ impl<'a> SymbolicExecutor<'a> {
     ///   - Assigns an initial symbolic value to the variable.
     /// - Evaluates the variable's dimensions using the current template or function library context
     ///   and stores them in the `id2dimensions` map.
+    /// - A dimension whose size cannot be resolved is set to `0` and recorded in
+    ///   `undetermined_dimensions`.
     /// - Proceeds to execute the next statement in the program.
     ///
     /// # Panics
@@ -1254,19 +2443,19 @@ impl<'a> SymbolicExecutor<'a> {
                 self.cur_state.set_sym_val(var_name, value);
             }
 
-            let dims = if let Some(templ) = self
+            let dim_expr = if let Some(templ) = self
                 .symbolic_library
                 .template_library
                 .get(&self.cur_state.template_id)
             {
-                self.evaluate_dimension(&templ.id2dimension_expressions[id].clone(), elem_id)
+                Some(templ.id2dimension_expressions[id].clone())
             } else if let Some(func) = self
                 .symbolic_library
                 .function_library
                 .get(&self.cur_state.template_id)
             {
-                if let Some(dim_expr) = func.id2dimension_expressions.get(id) {
-                    self.evaluate_dimension(&dim_expr.clone(), elem_id)
+                if let Some(dim_expr) = func.id2dimension_expressions.get(id).cloned() {
+                    Some(dim_expr)
                 } else {
                     panic!(
                         "Dim-expression of {} within {} cannt be found.",
@@ -1275,13 +2464,24 @@ impl<'a> SymbolicExecutor<'a> {
                     );
                 }
             } else {
-                vec![]
+                None
                 /*
                 panic!(
                     "{} does not exist in the library",
                     self.symbolic_library.id2name[&self.cur_state.template_id]
                 );*/
             };
+            let mut dims = Vec::new();
+            for dim in dim_expr.iter().flatten() {
+                let size = self.evaluate_expression(dim, elem_id);
+                match self.resolve_size(&size, elem_id) {
+                    Ok(size) => dims.push(size),
+                    Err(size) => {
+                        self.record_undetermined_dimension(elem_id, *id, &size);
+                        dims.push(0);
+                    }
+                }
+            }
             if let Some(md) = dims.iter().min() {
                 self.mindim = std::cmp::min(self.mindim, *md);
             }
@@ -1347,9 +2547,9 @@ impl<'a> SymbolicExecutor<'a> {
 
             if self.setting.keep_track_constraints {
                 if !self.setting.constraint_assert_dissabled {
-                    self.cur_state.push_symbolic_trace(&cond);
+                    self.push_symbolic_trace(&cond);
                 }
-                self.cur_state.push_side_constraint(&cond);
+                self.push_side_constraint(&cond);
             } else {
                 if !self.cur_state.is_failed {
                     let mut memo = FxHashSet::default();
@@ -1377,13 +2577,96 @@ impl<'a> SymbolicExecutor<'a> {
             let expr = self.evaluate_expression(&arg, meta.elem_id);
             let mut memo = FxHashSet::default();
             let condition = self.simplify_variables(&expr, meta.elem_id, true, true, &mut memo);
-            if self.setting.keep_track_constraints {
-                self.cur_state.push_symbolic_trace(&condition);
+            let is_within_function = self
+                .symbolic_library
+                .function_library
+                .contains_key(&self.cur_state.template_id);
+            if self.setting.keep_track_constraints && is_within_function {
+                // The assertion is a path condition of the template calling the function
+                let call_site = self
+                    .call_site
+                    .clone()
+                    .unwrap_or_else(|| self.cur_provenance());
+                self.function_assertions.push(FunctionAssertion {
+                    elem_id: meta.elem_id,
+                    function_id: self.cur_state.template_id,
+                    call_site: call_site.clone(),
+                    condition: Rc::new(condition.clone()),
+                });
+                self.log_constraint("trace", &condition);
+                self.cur_state.push_symbolic_trace(&condition, call_site);
+            } else if self.setting.keep_track_constraints {
+                self.push_symbolic_trace(&condition);
+            } else if !self.cur_state.is_failed {
+                let mut memo = FxHashSet::default();
+                let simplified_condition =
+                    self.simplify_variables(&condition, meta.elem_id, false, false, &mut memo);
+                if let SymbolicValue::ConstantBool(false) = simplified_condition {
+                    // Record the first assertion violated by a run that satisfies all constraints
+                    // so far
+                    if self.violated_assertion.is_none() {
+                        self.violated_assertion = Some((meta.clone(), expr));
+                    }
+                    // The witness generator aborts on a violated assertion within a function, so
+                    // that the candidate assignment fails like on a violated `===`
+                    if is_within_function {
+                        self.execution_failed = true;
+                        self.cur_state.is_failed = true;
+                        self.violated_condition = Some((meta.elem_id, condition.clone()));
+                    }
+                }
+            }
+            self.execute(statements, cur_bid + 1);
+        }
+    }
+
+    /// Evaluates the arguments of `log()` and records them in the sink of `--replay_logs`, if
+    /// any. The expressions whose value is not a constant are recorded in their symbolic form.
+    fn handle_log_call(&mut self, statements: &Vec<DebuggableStatement>, cur_bid: usize) {
+        if let DebuggableStatement::LogCall { meta, args } = &statements[cur_bid] {
+            self.trace_if_enabled(&meta);
+            if let Some(log_sink) = self.setting.log_sink.clone() {
+                let mut values = Vec::new();
+                for arg in args {
+                    match arg {
+                        DebuggableLogArgument::LogStr(s) => values.push(s.clone()),
+                        DebuggableLogArgument::LogExp(expr) => {
+                            let value = self.evaluate_expression(&expr, meta.elem_id);
+                            let mut memo = FxHashSet::default();
+                            let value = self.simplify_variables(
+                                &value,
+                                meta.elem_id,
+                                false,
+                                false,
+                                &mut memo,
+                            );
+                            values.push(self.log_value_fmt(&value));
+                        }
+                    }
+                }
+                let id2name = &self.symbolic_library.id2name;
+                log_sink.record(LogRecord {
+                    elem_id: meta.elem_id,
+                    owner_id: self.cur_state.template_id,
+                    owner: self.cur_state.get_owner(id2name),
+                    values,
+                });
             }
             self.execute(statements, cur_bid + 1);
         }
     }
 
+    /// Formats a value printed by `log()` as the witness generator of circom does, in decimal
+    /// within `[0, p)`.
+    fn log_value_fmt(&self, value: &SymbolicValue) -> String {
+        let prime = &self.setting.prime;
+        match value {
+            SymbolicValue::ConstantInt(v) => modreduce(v, prime).to_string(),
+            SymbolicValue::ConstantBool(b) => if *b { "1" } else { "0" }.to_string(),
+            _ => strip_ansi(&value.lookup_fmt(&self.symbolic_library.id2name)),
+        }
+    }
+
     fn handle_ret(&mut self) {
         if !self.setting.off_trace {
             trace!(
@@ -1406,10 +2689,8 @@ impl<'a> SymbolicExecutor<'a> {
         let mut concrete_counts = Vec::new();
         let mut is_success = true;
         for c in counts.iter() {
-            let mut memo = FxHashSet::default();
-            let s = self.simplify_variables(&c, elem_id, false, false, &mut memo);
-            if let SymbolicValue::ConstantInt(v) = s {
-                concrete_counts.push(v.to_usize().unwrap())
+            if let Ok(count) = self.resolve_size(c, elem_id) {
+                concrete_counts.push(count)
             } else {
                 is_success = false;
                 break;
@@ -1595,7 +2876,7 @@ impl<'a> SymbolicExecutor<'a> {
                 Rc::new(right_call.clone()),
                 is_mutable,
             );
-            self.cur_state.push_symbolic_trace(&cont);
+            self.push_symbolic_trace(&cont);
         }
     }
 
@@ -1639,6 +2920,9 @@ impl<'a> SymbolicExecutor<'a> {
         let mut subse_setting = self.setting.clone();
         subse_setting.only_initialization_blocks = true;
         subse_setting.off_trace = true;
+        // The initialization blocks run again when the component is executed, so their `log()`
+        // calls are recorded then
+        subse_setting.log_sink = None;
         let mut se_for_initialization =
             SymbolicExecutor::new(&mut self.symbolic_library, &subse_setting);
         se_for_initialization.cur_state.owner_name = self.cur_state.owner_name.clone();
@@ -1669,7 +2953,9 @@ impl<'a> SymbolicExecutor<'a> {
                 .set_rc_sym_val(tp_name, args[i].clone());
         }
 
+        se_for_initialization.function_summaries = std::mem::take(&mut self.function_summaries);
         se_for_initialization.execute(&template.body, 0);
+        self.function_summaries = std::mem::take(&mut se_for_initialization.function_summaries);
 
         let mut inputs_binding_map = FxHashMap::default();
         let mut id2dimensions = FxHashMap::default();
@@ -1857,7 +3143,8 @@ impl<'a> SymbolicExecutor<'a> {
     /// # Behavior
     /// - If `keep_track_constraints` is enabled in the settings:
     ///   - For `AssignConstraintSignal` operations:
-    ///     - Creates an equality constraint (`AssignEq`) between the variable and the value.
+    ///     - Creates an equality constraint (`AssignEq`) between the variable and the value. A
+    ///       ternary value with a symbolic condition yields one guarded constraint per branch.
     ///     - Adds the constraint to the symbolic trace and side constraints.
     ///   - For `AssignSignal` operations:
     ///     - Creates a direct assignment (`Assign`) between the variable and the value.
@@ -1872,12 +3159,15 @@ impl<'a> SymbolicExecutor<'a> {
         if self.setting.keep_track_constraints {
             match op {
                 DebuggableAssignOp(AssignOp::AssignConstraintSignal) => {
-                    let cont = SymbolicValue::AssignEq(
-                        Rc::new(SymbolicValue::Variable(var_name.clone())),
-                        Rc::new(value.clone()),
-                    );
-                    self.cur_state.push_symbolic_trace(&cont);
-                    self.cur_state.push_side_constraint(&cont);
+                    let lhs = Rc::new(SymbolicValue::Variable(var_name.clone()));
+                    let constraints =
+                        split_conditional_constraint(&Rc::new(value.clone()), &|rhs| {
+                            SymbolicValue::AssignEq(lhs.clone(), rhs)
+                        });
+                    for cont in constraints {
+                        self.push_symbolic_trace(&cont);
+                        self.push_side_constraint(&cont);
+                    }
                 }
                 DebuggableAssignOp(AssignOp::AssignSignal) => {
                     // handling zero-division pattern
@@ -1944,7 +3234,7 @@ impl<'a> SymbolicExecutor<'a> {
                         self.symbolic_library.template_library[&self.cur_state.template_id].is_safe,
                         zero_div_info,
                     );
-                    self.cur_state.push_symbolic_trace(&cont);
+                    self.push_symbolic_trace(&cont);
                 }
                 _ => {}
             }
@@ -2058,20 +3348,55 @@ impl<'a> SymbolicExecutor<'a> {
     ///
     /// # Arguments
     ///
-    /// * `name` - Name of the component to check readiness for.
+    /// * `name` - Name of the component to check readiness for, including the indices of its
+    ///   instance within a component array (e.g. `c[1][0]`).
     ///
     /// # Returns
     ///
-    /// A boolean indicating readiness status.
+    /// A boolean indicating readiness status. A component that has already been executed is not
+    /// ready.
     fn is_ready(&self, name: &SymbolicName) -> bool {
-        self.symbolic_store.components_store.contains_key(name)
-            && (self.symbolic_store.components_store[name]
-                .inputs_binding_map
-                .iter()
-                .all(|(_, v)| v.is_some())
-                || self.symbolic_store.components_store[name]
-                    .inputs_binding_map
-                    .is_empty())
+        self.symbolic_store
+            .components_store
+            .get(name)
+            .map_or(false, |component| component.is_ready())
+    }
+
+    /// Records the components of the current template that are instantiated but never executed
+    /// because some of their inputs are not assigned, in `incomplete_components`.
+    ///
+    /// It is called once the body of the template has been executed, as the inputs of an
+    /// instance of a component array may be assigned across several loops.
+    pub fn record_incomplete_components(&mut self) {
+        if !self.setting.keep_track_constraints || self.is_concrete_mode {
+            return;
+        }
+        let id2name = &self.symbolic_library.id2name;
+        let mut incomplete_components: Vec<IncompleteComponent> = self
+            .symbolic_store
+            .components_store
+            .iter()
+            .filter(|(_, component)| !component.is_done)
+            .filter_map(|(name, component)| {
+                let component_name = name.lookup_fmt(id2name);
+                let mut missing_inputs: Vec<String> = component
+                    .missing_inputs()
+                    .into_iter()
+                    .map(|input| format!("{}{}", component_name, input.lookup_fmt(id2name)))
+                    .collect();
+                if missing_inputs.is_empty() {
+                    return None;
+                }
+                missing_inputs.sort();
+                Some(IncompleteComponent {
+                    name: component_name,
+                    template_name: id2name[&component.template_id].clone(),
+                    missing_inputs: missing_inputs,
+                })
+            })
+            .collect();
+        incomplete_components.sort_by(|a, b| a.name.cmp(&b.name));
+        self.incomplete_components.extend(incomplete_components);
     }
 
     /// Executes a ready-to-run component in the symbolic execution context.
@@ -2092,7 +3417,7 @@ impl<'a> SymbolicExecutor<'a> {
     /// - If `propagate_assignments` is enabled in the settings:
     ///   - Merges the symbol binding map of the component back into the parent executor.
     /// - Propagates symbolic traces and side constraints generated during the component's execution.
-    /// - If the component's template specifies `is_lessthan`, generates and appends a "less-than" constraint.
+    /// - Appends the auxiliary constraints emitted by the semantics plugins attached to the component's template.
     /// - Optionally logs detailed execution traces if tracing is enabled in the settings.
     ///
     /// # Notes
@@ -2106,7 +3431,22 @@ impl<'a> SymbolicExecutor<'a> {
         pre_dims: &Vec<SymbolicAccess>,
     ) {
         if !self.symbolic_store.components_store[component_name].is_done {
+            // The component is marked as executed first, so that binding its inputs again does
+            // not execute it twice
+            if let Some(component) = self.symbolic_store.components_store.get_mut(component_name) {
+                component.is_done = true;
+            }
+            let template_id = self.symbolic_store.components_store[component_name].template_id;
+            let args = self.symbolic_store.components_store[component_name]
+                .args
+                .clone();
+            if !self.check_call(template_id, &args) {
+                return;
+            }
             let mut subse = SymbolicExecutor::new(&mut self.symbolic_library, self.setting);
+            subse.function_summaries = std::mem::take(&mut self.function_summaries);
+            subse.call_stack = self.call_stack.clone();
+            subse.call_stack.push((template_id, args));
             let mut updated_owner_list = (*self.cur_state.owner_name).clone();
             updated_owner_list.push(OwnerName {
                 id: component_id,
@@ -2157,8 +3497,36 @@ impl<'a> SymbolicExecutor<'a> {
                 );
             }
 
-            let is_lessthan = templ.is_lessthan;
+            let semantics = templ.semantics.clone();
+            let custom_gate_output_ids = if templ.is_custom_gate {
+                Some(templ.output_ids.clone())
+            } else {
+                None
+            };
             subse.execute(&templ.body.clone(), 0);
+            subse.log_bindings();
+            subse.record_incomplete_components();
+            self.incomplete_components
+                .extend(std::mem::take(&mut subse.incomplete_components));
+            self.function_summaries = std::mem::take(&mut subse.function_summaries);
+
+            if let Some(output_ids) = custom_gate_output_ids {
+                if self.setting.keep_track_constraints {
+                    subse.assume_custom_gate_constraints(&output_ids);
+                }
+            }
+
+            // The constraints of the plugins follow the constraints of the component, and are
+            // not produced by any statement
+            subse.cur_elem_id = usize::MAX;
+            for plugin in semantics.iter() {
+                for cond in plugin.emit_constraints(
+                    &subse.symbolic_library.name2id,
+                    subse.cur_state.owner_name.clone(),
+                ) {
+                    subse.push_symbolic_trace(&cond);
+                }
+            }
 
             self.cur_state
                 .symbolic_trace
@@ -2166,19 +3534,56 @@ impl<'a> SymbolicExecutor<'a> {
             self.cur_state
                 .side_constraints
                 .append(&mut subse.cur_state.side_constraints);
+            self.cur_state
+                .trace_provenance
+                .append(&mut subse.cur_state.trace_provenance);
+            self.cur_state
+                .side_constraint_provenance
+                .append(&mut subse.cur_state.side_constraint_provenance);
             self.execution_failed = subse.execution_failed;
-            if self.setting.propagate_assignments {
-                for (k, v) in subse.cur_state.symbol_binding_map.iter() {
-                    self.cur_state.set_rc_sym_val(k.clone(), v.clone());
-                }
+            if self.violated_assertion.is_none() {
+                self.violated_assertion = subse.violated_assertion.take();
             }
-
-            if is_lessthan {
-                let cond = generate_lessthan_constraint(
-                    &subse.symbolic_library.name2id,
-                    subse.cur_state.owner_name,
+            self.function_assertions
+                .extend(std::mem::take(&mut subse.function_assertions));
+            if self.division_by_zero.is_none() {
+                self.division_by_zero = subse.division_by_zero.take();
+            }
+            if self.recursion_error.is_none() {
+                self.recursion_error = subse.recursion_error.take();
+            }
+            self.signal_assignments
+                .extend(std::mem::take(&mut subse.signal_assignments));
+            self.divisions.extend(std::mem::take(&mut subse.divisions));
+            self.symbolic_ternaries
+                .extend(std::mem::take(&mut subse.symbolic_ternaries));
+            self.shape_mismatches
+                .extend(std::mem::take(&mut subse.shape_mismatches));
+            self.undetermined_dimensions
+                .extend(std::mem::take(&mut subse.undetermined_dimensions));
+            merge_branches(&mut self.branches, std::mem::take(&mut subse.branches));
+            self.called_functions
+                .extend(std::mem::take(&mut subse.called_functions));
+            if self.setting.keep_track_constraints {
+                self.component_templates.insert(
+                    (*subse.cur_state.owner_name).clone(),
+                    self.symbolic_store.components_store[component_name].template_id,
                 );
-                self.cur_state.push_symbolic_trace(&cond);
+                self.component_templates
+                    .extend(std::mem::take(&mut subse.component_templates));
+                self.component_args.insert(
+                    (*subse.cur_state.owner_name).clone(),
+                    self.symbolic_store.components_store[component_name]
+                        .args
+                        .clone(),
+                );
+                self.component_args
+                    .extend(std::mem::take(&mut subse.component_args));
+            }
+            if self.setting.propagate_assignments {
+                for (id, v) in subse.cur_state.symbol_binding_map.iter_ids() {
+                    self.cur_state.symbol_binding_map.insert_by_id(id, v.clone());
+                }
             }
 
             if !self.setting.off_trace {
@@ -2190,6 +3595,170 @@ impl<'a> SymbolicExecutor<'a> {
 
 // Other utility methods
 impl<'a> SymbolicExecutor<'a> {
+    /// Records the assignment of the signal `var_name` by the statement at `elem_id` if `op` is
+    /// `<--` or `<==`.
+    fn record_signal_assignment(
+        &mut self,
+        op: &DebuggableAssignOp,
+        var_name: &SymbolicName,
+        elem_id: usize,
+    ) {
+        if !self.setting.keep_track_constraints || self.is_concrete_mode {
+            return;
+        }
+        if let DebuggableAssignOp(AssignOp::AssignSignal | AssignOp::AssignConstraintSignal) = op {
+            let is_input = var_name.owner == self.cur_state.owner_name
+                && self
+                    .symbolic_library
+                    .template_library
+                    .get(&self.cur_state.template_id)
+                    .map_or(false, |template| template.input_ids.contains(&var_name.id));
+            self.signal_assignments.push(SignalAssignment {
+                name: var_name.clone(),
+                elem_id: elem_id,
+                owner_id: self.cur_state.template_id,
+                is_input: is_input,
+            });
+        }
+    }
+
+    /// Records the `<==` assignment at `elem_id` if its right-hand side `value` contains a ternary
+    /// whose condition is not a constant, which circom cannot turn into a quadratic constraint.
+    fn record_symbolic_ternary(
+        &mut self,
+        op: &DebuggableAssignOp,
+        value: &SymbolicValue,
+        elem_id: usize,
+    ) {
+        if !self.setting.keep_track_constraints || self.is_concrete_mode {
+            return;
+        }
+        if let DebuggableAssignOp(AssignOp::AssignConstraintSignal) = op {
+            if let Some(condition) = find_symbolic_condition(value) {
+                let owner_id = self.cur_state.template_id;
+                self.symbolic_ternaries
+                    .entry(elem_id)
+                    .or_insert_with(|| (owner_id, (*condition).clone()));
+            }
+        }
+    }
+
+    /// Records that the multi-substitution at `elem_id` assigns values whose shape does not match
+    /// its targets, as described by `message`.
+    fn record_shape_mismatch(&mut self, elem_id: usize, message: String) {
+        if !self.setting.keep_track_constraints || self.is_concrete_mode {
+            return;
+        }
+        let owner_id = self.cur_state.template_id;
+        self.shape_mismatches
+            .entry(elem_id)
+            .or_insert((owner_id, message));
+    }
+
+    /// Records that the array `id` declared at `elem_id` has the dimension `size`, which is not a
+    /// constant that fits in `usize`.
+    fn record_undetermined_dimension(&mut self, elem_id: usize, id: usize, size: &SymbolicValue) {
+        if !self.setting.keep_track_constraints || self.is_concrete_mode {
+            return;
+        }
+        let reason = if let SymbolicValue::ConstantInt(_) = size {
+            "which is not a valid array size"
+        } else {
+            "which is not a constant"
+        };
+        let message = format!(
+            "`{}` is declared with the size `{}`, {}",
+            self.symbolic_library.id2name[&id],
+            size.lookup_fmt(&self.symbolic_library.id2name),
+            reason
+        );
+        let owner_id = self.cur_state.template_id;
+        self.undetermined_dimensions
+            .entry(elem_id)
+            .or_insert((owner_id, message));
+    }
+
+    /// Returns the dimensions of the signal or variable `name` left once its array accesses are
+    /// applied, or `None` if the dimensions of its declaration are not registered.
+    fn remaining_dimensions(&self, name: &SymbolicName) -> Option<Vec<usize>> {
+        let dims = if name.owner == self.cur_state.owner_name {
+            self.id2dimensions.get(&name.id)?
+        } else {
+            let component = name.owner.last()?;
+            let component_name = SymbolicName::new(
+                component.id,
+                Rc::new(name.owner[..name.owner.len() - 1].to_vec()),
+                component.access.clone(),
+            );
+            self.symbolic_store
+                .components_store
+                .get(&component_name)?
+                .id2dimensions
+                .get(&name.id)?
+        };
+        let num_accesses = name.get_dim();
+        if num_accesses <= dims.len() {
+            Some(dims[num_accesses..].to_vec())
+        } else {
+            None
+        }
+    }
+
+    /// Returns the dimensions of `value`, or `None` if they cannot be determined.
+    fn dimensions_of_value(&self, value: &SymbolicValue) -> Option<Vec<usize>> {
+        match value {
+            SymbolicValue::Array(elems) => {
+                let mut dims = vec![elems.len()];
+                if let Some(first) = elems.first() {
+                    dims.extend(self.dimensions_of_value(first)?);
+                }
+                Some(dims)
+            }
+            SymbolicValue::UniformArray(..) => None,
+            SymbolicValue::Variable(name) => self.remaining_dimensions(name),
+            _ => Some(Vec::new()),
+        }
+    }
+
+    /// Checks whether a call of the template or function `id` with `args` may be executed.
+    ///
+    /// A call that would nest deeper than `max_call_depth` calls, or that repeats a call being
+    /// executed with the same arguments, is not executed: the first such call is recorded in
+    /// `recursion_error` with the chain of calls leading to it, and the execution is marked as
+    /// failed, instead of recursing until the stack overflows.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the call may be executed.
+    fn check_call(&mut self, id: usize, args: &[SymbolicValueRef]) -> bool {
+        let kind = if self
+            .call_stack
+            .iter()
+            .any(|(callee, callee_args)| *callee == id && callee_args.as_slice() == args)
+        {
+            RecursionErrorKind::Cycle
+        } else if self.call_stack.len() >= self.setting.max_call_depth {
+            RecursionErrorKind::DepthExceeded
+        } else {
+            return true;
+        };
+        if self.recursion_error.is_none() {
+            let id2name = &self.symbolic_library.id2name;
+            let mut call_chain: Vec<String> = self
+                .call_stack
+                .iter()
+                .map(|(callee, _)| id2name[callee].clone())
+                .collect();
+            call_chain.push(id2name[&id].clone());
+            self.recursion_error = Some(RecursionError {
+                kind: kind,
+                call_chain: call_chain,
+            });
+        }
+        self.execution_failed = true;
+        false
+    }
+
     /// Traces the current state if tracing is enabled.
     ///
     /// This method logs the current state information if tracing is not disabled.
@@ -2207,6 +3776,83 @@ impl<'a> SymbolicExecutor<'a> {
         }
     }
 
+    /// Records the execution of `statement` in the sink of `--trace_jsonl`, if any.
+    fn log_statement(&mut self, statement: &DebuggableStatement) {
+        self.log_bindings();
+        if let Some(trace_log) = &self.setting.trace_log {
+            if !self.setting.off_trace {
+                let id2name = &self.symbolic_library.id2name;
+                trace_log.log_statement(
+                    statement,
+                    &self.cur_state.get_owner_stack(id2name),
+                    id2name
+                        .get(&self.cur_state.template_id)
+                        .map_or("", |name| name.as_str()),
+                    self.cur_state.get_depth(),
+                );
+            }
+        }
+    }
+
+    /// Records the variables and signals bound to a new value since the last call in the sink of
+    /// `--trace_jsonl`, if any.
+    pub fn log_bindings(&mut self) {
+        if let Some(trace_log) = &self.setting.trace_log {
+            if !self.setting.off_trace {
+                let id2name = &self.symbolic_library.id2name;
+                let mut changed: Vec<_> = self
+                    .cur_state
+                    .symbol_binding_map
+                    .changed_since(&self.logged_bindings)
+                    .map(|(id, value)| (resolve(id), value))
+                    .collect();
+                changed.sort_by(|a, b| a.0.cmp(&b.0));
+                for (name, value) in changed {
+                    trace_log.log_binding(&name, value, id2name);
+                }
+                self.logged_bindings = self.cur_state.symbol_binding_map.clone();
+            }
+        }
+    }
+
+    /// Adds a constraint to the symbolic trace and records it in the sink of `--trace_jsonl`.
+    fn push_symbolic_trace(&mut self, constraint: &SymbolicValue) {
+        self.log_constraint("trace", constraint);
+        let provenance = self.cur_provenance();
+        self.cur_state.push_symbolic_trace(constraint, provenance);
+    }
+
+    /// Adds a side constraint and records it in the sink of `--trace_jsonl`.
+    fn push_side_constraint(&mut self, constraint: &SymbolicValue) {
+        self.log_constraint("side", constraint);
+        let provenance = self.cur_provenance();
+        self.cur_state.push_side_constraint(constraint, provenance);
+    }
+
+    /// Returns the provenance of the constraints produced by the statement being executed.
+    fn cur_provenance(&self) -> ConstraintProvenance {
+        ConstraintProvenance {
+            elem_id: self.cur_elem_id,
+            owner_id: self.cur_state.template_id,
+            owner_name: self.cur_state.owner_name.clone(),
+        }
+    }
+
+    fn log_constraint(&mut self, store: &str, constraint: &SymbolicValue) {
+        self.log_bindings();
+        if let Some(trace_log) = &self.setting.trace_log {
+            if !self.setting.off_trace {
+                let id2name = &self.symbolic_library.id2name;
+                trace_log.log_constraint(
+                    store,
+                    constraint,
+                    &self.cur_state.get_owner(id2name),
+                    id2name,
+                );
+            }
+        }
+    }
+
     /// Constructs symbolic names for a given base ID and access pattern.
     ///
     /// This function parses a sequence of accesses to create symbolic names
@@ -2406,15 +4052,11 @@ impl<'a> SymbolicExecutor<'a> {
         let (_, dims) = decompose_uniform_array(Rc::new(uarray.clone()));
         let mut concrete_dims = Vec::new();
         for c in dims.iter() {
-            let mut memo = FxHashSet::default();
-            let s = self.simplify_variables(&c, elem_id, false, false, &mut memo);
-            if let SymbolicValue::ConstantInt(v) = s {
-                concrete_dims.push(v.to_usize().unwrap())
+            if let Ok(dim) = self.resolve_size(c, elem_id) {
+                concrete_dims.push(dim)
             } else {
-                panic!(
-                    "Cannot determine the dimensions of {}",
-                    uarray.lookup_fmt(&self.symbolic_library.id2name)
-                );
+                // The size is reported at the declaration of the array, if it is in a function
+                return uarray.clone();
             }
         }
 
@@ -0,0 +1,85 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use rustc_hash::FxHashMap;
+
+use crate::executor::symbolic_value::SymbolicName;
+
+/// The interned id of a `SymbolicName`.
+pub type SymbolId = u64;
+
+/// Maps each distinct `SymbolicName` to a dense id, and each id back to the name.
+///
+/// A name is hashed and compared structurally only once, when it is interned. Afterwards, the
+/// maps of the executor key their entries by the id, which avoids storing a copy of the owners
+/// and accesses of the name in every state and re-hashing them on each lookup.
+#[derive(Default)]
+struct SymbolicNameInterner {
+    ids: FxHashMap<Rc<SymbolicName>, SymbolId>,
+    names: Vec<Rc<SymbolicName>>,
+    /// The number of times the interner has been reset (see `reset_interner`).
+    generation: u64,
+}
+
+thread_local! {
+    static INTERNER: RefCell<SymbolicNameInterner> = RefCell::new(SymbolicNameInterner::default());
+}
+
+/// Returns the id of `name`, interning it if it has not been seen yet.
+///
+/// The ids are shared by all executors of the current thread, so that the states of an executor
+/// and of its sub-executors agree on them.
+pub fn intern(name: &SymbolicName) -> SymbolId {
+    INTERNER.with(|interner| {
+        let mut interner = interner.borrow_mut();
+        if let Some(id) = interner.ids.get(name) {
+            return *id;
+        }
+        let id = interner.names.len() as SymbolId;
+        let name = Rc::new(name.clone());
+        interner.ids.insert(name.clone(), id);
+        interner.names.push(name);
+        id
+    })
+}
+
+/// Resolves `id` back to the structured name it was interned from.
+///
+/// The name is shared with the interner rather than copied, so resolving is as cheap as cloning
+/// an `Rc`.
+///
+/// # Panics
+///
+/// Panics if `id` was not returned by `intern` on the current thread since the last
+/// `reset_interner`.
+pub fn resolve(id: SymbolId) -> Rc<SymbolicName> {
+    INTERNER.with(|interner| interner.borrow().names[id as usize].clone())
+}
+
+/// Returns the number of distinct names interned on the current thread.
+pub fn interned_count() -> usize {
+    INTERNER.with(|interner| interner.borrow().names.len())
+}
+
+/// Returns the number of times the interner of the current thread has been reset, which tells
+/// the ids cached by a name apart from the ids of the current interner.
+pub fn interner_generation() -> u64 {
+    INTERNER.with(|interner| interner.borrow().generation)
+}
+
+/// Forgets every name interned on the current thread, releasing the names of the analyses that
+/// are finished.
+///
+/// The interner otherwise only grows, which matters when several circuits are analysed by the
+/// same process (e.g. the entries of `--entry`, the buffers of `analyze_source`, or the oracles
+/// created by the Python bindings).
+/// It must not be called while an executor is alive, since the ids of its states would no longer
+/// resolve. The names kept by a library re-intern themselves on their next `symbol_id`.
+pub fn reset_interner() {
+    INTERNER.with(|interner| {
+        let mut interner = interner.borrow_mut();
+        let generation = interner.generation + 1;
+        *interner = SymbolicNameInterner::default();
+        interner.generation = generation;
+    })
+}
@@ -1,5 +1,20 @@
+use std::rc::Rc;
+
 use num_bigint_dig::BigInt;
 
+use crate::executor::concolic::ConcolicGuide;
+use crate::executor::log_sink::LogSink;
+use crate::executor::trace_log::TraceLog;
+
+/// The default maximum number of cases an array access with symbolic indices is split into.
+pub const DEFAULT_MAX_INDEX_CASE_SPLITS: usize = 32;
+
+/// The default maximum number of function calls whose summaries are memoized.
+pub const DEFAULT_FUNCTION_SUMMARY_CAPACITY: usize = 1024;
+
+/// The default maximum number of nested template instantiations and function calls.
+pub const DEFAULT_MAX_CALL_DEPTH: usize = 64;
+
 #[derive(Clone)]
 pub struct SymbolicExecutorSetting {
     pub prime: BigInt,
@@ -10,6 +25,17 @@ pub struct SymbolicExecutorSetting {
     pub substitute_output: bool,
     pub propagate_assignments: bool,
     pub constraint_assert_dissabled: bool,
+    pub max_index_case_splits: usize,
+    /// The maximum number of memoized function calls (`0` disables the memoization).
+    pub function_summary_capacity: usize,
+    /// The maximum number of nested template instantiations and function calls.
+    pub max_call_depth: usize,
+    /// The sink of `--trace_jsonl`, shared by the executors of the sub-components and functions.
+    pub trace_log: Option<Rc<TraceLog>>,
+    /// The seed of `--search_mode concolic`, which decides the branches with symbolic conditions.
+    pub concolic_guide: Option<Rc<ConcolicGuide>>,
+    /// The sink of `--replay_logs`, which collects the `log()` calls of the concrete execution.
+    pub log_sink: Option<Rc<LogSink>>,
 }
 
 pub fn get_default_setting_for_symbolic_execution(
@@ -25,6 +51,12 @@ pub fn get_default_setting_for_symbolic_execution(
         substitute_output: false,
         propagate_assignments: false,
         constraint_assert_dissabled: constraint_assert_dissabled,
+        max_index_case_splits: DEFAULT_MAX_INDEX_CASE_SPLITS,
+        function_summary_capacity: DEFAULT_FUNCTION_SUMMARY_CAPACITY,
+        max_call_depth: DEFAULT_MAX_CALL_DEPTH,
+        trace_log: None,
+        concolic_guide: None,
+        log_sink: None,
     }
 }
 
@@ -41,5 +73,11 @@ pub fn get_default_setting_for_concrete_execution(
         substitute_output: true,
         propagate_assignments: true,
         constraint_assert_dissabled: constraint_assert_dissabled,
+        max_index_case_splits: DEFAULT_MAX_INDEX_CASE_SPLITS,
+        function_summary_capacity: DEFAULT_FUNCTION_SUMMARY_CAPACITY,
+        max_call_depth: DEFAULT_MAX_CALL_DEPTH,
+        trace_log: None,
+        concolic_guide: None,
+        log_sink: None,
     }
 }
@@ -0,0 +1,401 @@
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter};
+use std::path::Path;
+use std::rc::Rc;
+
+use rustc_hash::{FxHashMap, FxHashSet};
+use serde::{Deserialize, Serialize};
+
+use crate::executor::constraint_store::{
+    decode_owner_names, decode_symbolic_name, decode_symbolic_value, encode_owner_names,
+    encode_symbolic_name, encode_symbolic_value,
+};
+use crate::executor::symbolic_archive::SymbolicLibraryArchive;
+use crate::executor::symbolic_execution::{SignalAssignment, SymbolicExecutor};
+use crate::executor::symbolic_state::{ConstraintProvenance, SymbolBindingMap, SymbolicState};
+use crate::executor::symbolic_value::{
+    OwnerName, SymbolicLibrary, SymbolicValue, SymbolicValueRef,
+};
+use crate::VERSION;
+
+/// A `SymbolicState` whose names and values are encoded with the binary format of the constraint
+/// stores.
+#[derive(Serialize, Deserialize)]
+struct ArchivedState {
+    owner_name: Vec<u8>,
+    template_id: usize,
+    is_within_initialization_block: bool,
+    contains_symbolic_loop: bool,
+    depth: usize,
+    symbol_binding_map: Vec<(Vec<u8>, Vec<u8>)>,
+    symbolic_trace: Vec<Vec<u8>>,
+    side_constraints: Vec<Vec<u8>>,
+    trace_provenance: Vec<(usize, usize, Vec<u8>)>,
+    side_constraint_provenance: Vec<(usize, usize, Vec<u8>)>,
+    is_failed: bool,
+}
+
+fn encode_value(value: &SymbolicValue) -> Vec<u8> {
+    let mut buf = Vec::new();
+    encode_symbolic_value(value, &mut buf);
+    buf
+}
+
+fn encode_owners(owners: &[OwnerName]) -> Vec<u8> {
+    let mut buf = Vec::new();
+    encode_owner_names(owners, &mut buf);
+    buf
+}
+
+fn encode_provenance(provenance: &[ConstraintProvenance]) -> Vec<(usize, usize, Vec<u8>)> {
+    provenance
+        .iter()
+        .map(|p| (p.elem_id, p.owner_id, encode_owners(&p.owner_name)))
+        .collect()
+}
+
+fn decode_value(buf: &[u8]) -> Result<SymbolicValue, String> {
+    decode_symbolic_value(buf).ok_or_else(|| "corrupted symbolic value".to_string())
+}
+
+fn decode_owners(buf: &[u8]) -> Result<Vec<OwnerName>, String> {
+    decode_owner_names(buf).ok_or_else(|| "corrupted owner name".to_string())
+}
+
+fn decode_provenance(
+    provenance: &[(usize, usize, Vec<u8>)],
+) -> Result<Vec<ConstraintProvenance>, String> {
+    provenance
+        .iter()
+        .map(|(elem_id, owner_id, owner_name)| {
+            decode_owners(owner_name).map(|owner_name| ConstraintProvenance {
+                elem_id: *elem_id,
+                owner_id: *owner_id,
+                owner_name: Rc::new(owner_name),
+            })
+        })
+        .collect()
+}
+
+fn decode_values(bufs: &[Vec<u8>]) -> Result<Vec<SymbolicValueRef>, String> {
+    bufs.iter().map(|b| decode_value(b).map(Rc::new)).collect()
+}
+
+fn invalid_data<E: ToString>(e: E) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, e.to_string())
+}
+
+/// The result of the symbolic execution of a main template, written by `--save_state` and loaded
+/// with `--load_state` so that several search modes can be run on a circuit without executing it
+/// again.
+#[derive(Serialize, Deserialize)]
+pub struct SymbolicSnapshot {
+    /// The version of zkFuzz that took the snapshot.
+    pub version: String,
+    /// The name of the main template that was executed.
+    pub main_template_name: String,
+    library: SymbolicLibraryArchive,
+    state: ArchivedState,
+    id2dimensions: Vec<(usize, Vec<usize>)>,
+    component_templates: Vec<(Vec<u8>, usize)>,
+    component_args: Vec<(Vec<u8>, Vec<Vec<u8>>)>,
+    divisions: Vec<(usize, usize, Vec<u8>)>,
+    symbolic_ternaries: Vec<(usize, usize, Vec<u8>)>,
+    shape_mismatches: Vec<(usize, usize, String)>,
+    undetermined_dimensions: Vec<(usize, usize, String)>,
+    branches: Vec<(usize, usize, bool, bool)>,
+    called_functions: Vec<usize>,
+    signal_assignments: Vec<(Vec<u8>, usize, usize, bool)>,
+}
+
+/// The part of a `SymbolicExecutor` restored from a `SymbolicSnapshot`.
+pub struct RestoredExecution {
+    pub cur_state: SymbolicState,
+    pub id2dimensions: FxHashMap<usize, Vec<usize>>,
+    pub component_templates: FxHashMap<Vec<OwnerName>, usize>,
+    pub component_args: FxHashMap<Vec<OwnerName>, Vec<SymbolicValueRef>>,
+    pub divisions: FxHashMap<usize, (usize, SymbolicValue)>,
+    pub symbolic_ternaries: FxHashMap<usize, (usize, SymbolicValue)>,
+    pub shape_mismatches: FxHashMap<usize, (usize, String)>,
+    pub undetermined_dimensions: FxHashMap<usize, (usize, String)>,
+    pub branches: FxHashMap<usize, (usize, bool, bool)>,
+    pub called_functions: FxHashSet<usize>,
+    pub signal_assignments: Vec<SignalAssignment>,
+}
+
+impl RestoredExecution {
+    /// Moves the restored state into `sexe`, as if `sexe` had executed the main template.
+    pub fn apply_to(self, sexe: &mut SymbolicExecutor) {
+        sexe.cur_state = self.cur_state;
+        sexe.id2dimensions = self.id2dimensions;
+        sexe.component_templates = self.component_templates;
+        sexe.component_args = self.component_args;
+        sexe.divisions = self.divisions;
+        sexe.symbolic_ternaries = self.symbolic_ternaries;
+        sexe.shape_mismatches = self.shape_mismatches;
+        sexe.undetermined_dimensions = self.undetermined_dimensions;
+        sexe.branches = self.branches;
+        sexe.called_functions = self.called_functions;
+        sexe.signal_assignments = self.signal_assignments;
+    }
+}
+
+impl SymbolicSnapshot {
+    /// Takes a snapshot of `sexe` after the execution of the main template.
+    ///
+    /// # Arguments
+    ///
+    /// * `sexe` - The symbolic executor that executed the main template.
+    /// * `main_template_name` - The name of the main template.
+    pub fn capture(sexe: &SymbolicExecutor, main_template_name: &str) -> Self {
+        let state = &sexe.cur_state;
+        let mut symbol_binding_map: Vec<_> = state
+            .symbol_binding_map
+            .iter()
+            .map(|(name, value)| {
+                let mut buf = Vec::new();
+                encode_symbolic_name(&name, &mut buf);
+                (buf, encode_value(value))
+            })
+            .collect();
+        symbol_binding_map.sort();
+
+        let mut id2dimensions: Vec<_> = sexe
+            .id2dimensions
+            .iter()
+            .map(|(id, dims)| (*id, dims.clone()))
+            .collect();
+        id2dimensions.sort();
+        let mut component_templates: Vec<_> = sexe
+            .component_templates
+            .iter()
+            .map(|(owner, template_id)| (encode_owners(owner), *template_id))
+            .collect();
+        component_templates.sort();
+        let mut component_args: Vec<_> = sexe
+            .component_args
+            .iter()
+            .map(|(owner, args)| {
+                (
+                    encode_owners(owner),
+                    args.iter().map(|arg| encode_value(arg)).collect(),
+                )
+            })
+            .collect();
+        component_args.sort();
+        let mut divisions: Vec<_> = sexe
+            .divisions
+            .iter()
+            .map(|(elem_id, (owner_id, denominator))| {
+                (*elem_id, *owner_id, encode_value(denominator))
+            })
+            .collect();
+        divisions.sort();
+        let mut symbolic_ternaries: Vec<_> = sexe
+            .symbolic_ternaries
+            .iter()
+            .map(|(elem_id, (owner_id, condition))| (*elem_id, *owner_id, encode_value(condition)))
+            .collect();
+        symbolic_ternaries.sort();
+        let mut shape_mismatches: Vec<_> = sexe
+            .shape_mismatches
+            .iter()
+            .map(|(elem_id, (owner_id, message))| (*elem_id, *owner_id, message.clone()))
+            .collect();
+        shape_mismatches.sort();
+        let mut undetermined_dimensions: Vec<_> = sexe
+            .undetermined_dimensions
+            .iter()
+            .map(|(elem_id, (owner_id, message))| (*elem_id, *owner_id, message.clone()))
+            .collect();
+        undetermined_dimensions.sort();
+        let mut branches: Vec<_> = sexe
+            .branches
+            .iter()
+            .map(|(elem_id, (owner_id, then_reached, else_reached))| {
+                (*elem_id, *owner_id, *then_reached, *else_reached)
+            })
+            .collect();
+        branches.sort();
+        let mut called_functions: Vec<_> = sexe.called_functions.iter().cloned().collect();
+        called_functions.sort();
+        let signal_assignments: Vec<_> = sexe
+            .signal_assignments
+            .iter()
+            .map(|a| {
+                let mut buf = Vec::new();
+                encode_symbolic_name(&a.name, &mut buf);
+                (buf, a.elem_id, a.owner_id, a.is_input)
+            })
+            .collect();
+
+        SymbolicSnapshot {
+            version: VERSION.to_string(),
+            main_template_name: main_template_name.to_string(),
+            library: SymbolicLibraryArchive::from_library(sexe.symbolic_library, Vec::new(), &[]),
+            state: ArchivedState {
+                owner_name: encode_owners(&state.owner_name),
+                template_id: state.template_id,
+                is_within_initialization_block: state.is_within_initialization_block,
+                contains_symbolic_loop: state.contains_symbolic_loop,
+                depth: state.depth,
+                symbol_binding_map: symbol_binding_map,
+                symbolic_trace: state
+                    .symbolic_trace
+                    .iter()
+                    .map(|c| encode_value(c))
+                    .collect(),
+                side_constraints: state
+                    .side_constraints
+                    .iter()
+                    .map(|c| encode_value(c))
+                    .collect(),
+                trace_provenance: encode_provenance(&state.trace_provenance),
+                side_constraint_provenance: encode_provenance(&state.side_constraint_provenance),
+                is_failed: state.is_failed,
+            },
+            id2dimensions: id2dimensions,
+            component_templates: component_templates,
+            component_args: component_args,
+            divisions: divisions,
+            symbolic_ternaries: symbolic_ternaries,
+            shape_mismatches: shape_mismatches,
+            undetermined_dimensions: undetermined_dimensions,
+            branches: branches,
+            called_functions: called_functions,
+            signal_assignments: signal_assignments,
+        }
+    }
+
+    /// Returns the number of constraints in the symbolic trace and in the side constraints.
+    pub fn num_constraints(&self) -> (usize, usize) {
+        (
+            self.state.symbolic_trace.len(),
+            self.state.side_constraints.len(),
+        )
+    }
+
+    /// Writes the snapshot to `path` in the bincode format.
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        let writer = BufWriter::new(File::create(path)?);
+        bincode::serialize_into(writer, self).map_err(invalid_data)
+    }
+
+    /// Reads a snapshot written by `save`.
+    ///
+    /// # Returns
+    ///
+    /// The snapshot, or an error if the file cannot be read, is not a snapshot, or was taken by
+    /// another version of zkFuzz.
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let reader = BufReader::new(File::open(path)?);
+        let snapshot: SymbolicSnapshot = bincode::deserialize_from(reader)
+            .map_err(|e| invalid_data(format!("not a symbolic execution snapshot ({})", e)))?;
+        if snapshot.version != VERSION {
+            return Err(invalid_data(format!(
+                "the snapshot was taken by zkFuzz {} (expected {}); execute the circuit again with `--save_state`",
+                snapshot.version, VERSION
+            )));
+        }
+        Ok(snapshot)
+    }
+
+    /// Registers the templates and functions of the snapshot in `library` and decodes the state
+    /// of the executor.
+    ///
+    /// Like `SymbolicLibraryArchive::link_into`, the snapshot keeps the IDs assigned to names
+    /// during the execution, so it has to be restored into an empty library.
+    ///
+    /// # Arguments
+    ///
+    /// * `library` - The (empty) library to restore the templates and functions into.
+    /// * `whitelist` - Names of the templates whose assignments are considered safe.
+    ///
+    /// # Returns
+    ///
+    /// The state to apply to a `SymbolicExecutor` built on `library`, or an error if the library
+    /// is not empty or the snapshot is corrupted.
+    pub fn restore(
+        self,
+        library: &mut SymbolicLibrary,
+        whitelist: &FxHashSet<String>,
+    ) -> Result<RestoredExecution, String> {
+        self.library.link_into(library, whitelist)?;
+
+        let state = self.state;
+        let mut symbol_binding_map = SymbolBindingMap::default();
+        for (name, value) in &state.symbol_binding_map {
+            let name =
+                decode_symbolic_name(name).ok_or_else(|| "corrupted symbolic name".to_string())?;
+            symbol_binding_map.insert(&name, Rc::new(decode_value(value)?));
+        }
+        let cur_state = SymbolicState {
+            owner_name: Rc::new(decode_owners(&state.owner_name)?),
+            template_id: state.template_id,
+            is_within_initialization_block: state.is_within_initialization_block,
+            contains_symbolic_loop: state.contains_symbolic_loop,
+            depth: state.depth,
+            symbol_binding_map: symbol_binding_map,
+            symbolic_trace: decode_values(&state.symbolic_trace)?,
+            side_constraints: decode_values(&state.side_constraints)?,
+            trace_provenance: decode_provenance(&state.trace_provenance)?,
+            side_constraint_provenance: decode_provenance(&state.side_constraint_provenance)?,
+            is_failed: state.is_failed,
+        };
+
+        let mut component_templates = FxHashMap::default();
+        for (owner, template_id) in &self.component_templates {
+            component_templates.insert(decode_owners(owner)?, *template_id);
+        }
+        let mut component_args = FxHashMap::default();
+        for (owner, args) in &self.component_args {
+            component_args.insert(decode_owners(owner)?, decode_values(args)?);
+        }
+        let mut divisions = FxHashMap::default();
+        for (elem_id, owner_id, denominator) in &self.divisions {
+            divisions.insert(*elem_id, (*owner_id, decode_value(denominator)?));
+        }
+        let mut symbolic_ternaries = FxHashMap::default();
+        for (elem_id, owner_id, condition) in &self.symbolic_ternaries {
+            symbolic_ternaries.insert(*elem_id, (*owner_id, decode_value(condition)?));
+        }
+        let mut signal_assignments = Vec::new();
+        for (name, elem_id, owner_id, is_input) in &self.signal_assignments {
+            signal_assignments.push(SignalAssignment {
+                name: decode_symbolic_name(name)
+                    .ok_or_else(|| "corrupted symbolic name".to_string())?,
+                elem_id: *elem_id,
+                owner_id: *owner_id,
+                is_input: *is_input,
+            });
+        }
+
+        Ok(RestoredExecution {
+            cur_state: cur_state,
+            id2dimensions: self.id2dimensions.into_iter().collect(),
+            component_templates: component_templates,
+            component_args: component_args,
+            divisions: divisions,
+            symbolic_ternaries: symbolic_ternaries,
+            shape_mismatches: self
+                .shape_mismatches
+                .into_iter()
+                .map(|(elem_id, owner_id, message)| (elem_id, (owner_id, message)))
+                .collect(),
+            undetermined_dimensions: self
+                .undetermined_dimensions
+                .into_iter()
+                .map(|(elem_id, owner_id, message)| (elem_id, (owner_id, message)))
+                .collect(),
+            branches: self
+                .branches
+                .into_iter()
+                .map(|(elem_id, owner_id, then_reached, else_reached)| {
+                    (elem_id, (owner_id, then_reached, else_reached))
+                })
+                .collect(),
+            called_functions: self.called_functions.into_iter().collect(),
+            signal_assignments: signal_assignments,
+        })
+    }
+}
@@ -1,17 +1,181 @@
+use std::ops::Index;
 use std::rc::Rc;
 
 use colored::Colorize;
-use rustc_hash::FxHashMap;
+use im_rc::HashMap as PersistentHashMap;
+use im_rc::Vector as PersistentVector;
+use rustc_hash::{FxBuildHasher, FxHashMap};
 
+use crate::executor::symbolic_interner::{resolve, SymbolId};
 use crate::executor::symbolic_value::{
     OwnerName, SymbolicAccess, SymbolicName, SymbolicValue, SymbolicValueRef,
 };
 use crate::executor::utils::italic;
 
-pub type SymbolBindingMap = FxHashMap<SymbolicName, SymbolicValueRef>;
 pub type SymbolicTrace = Vec<SymbolicValueRef>;
 pub type SymbolicConstraints = Vec<SymbolicValueRef>;
 
+/// The statement that produced a constraint of the symbolic trace or of the side constraints.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ConstraintProvenance {
+    /// The element ID of the statement, or `usize::MAX` for the constraints that no statement
+    /// produced, e.g. the template arguments of the main component.
+    pub elem_id: usize,
+    /// The ID of the template or function containing the statement.
+    pub owner_id: usize,
+    /// The components and function calls within which the statement was executed, outermost
+    /// first.
+    pub owner_name: Rc<Vec<OwnerName>>,
+}
+
+impl ConstraintProvenance {
+    /// Retrieves the owner names of the statement, outermost first, e.g. `["main", "c[0]"]`.
+    pub fn get_owner_stack(&self, id2name: &FxHashMap<usize, String>) -> Vec<String> {
+        format_owner_stack(&self.owner_name, id2name)
+    }
+}
+
+/// Formats each owner name with its accesses, e.g. `c[0]`.
+fn format_owner_stack(owner_name: &[OwnerName], id2name: &FxHashMap<usize, String>) -> Vec<String> {
+    owner_name
+        .iter()
+        .map(|e: &OwnerName| {
+            let access_str: String = if let Some(accesses) = &e.access {
+                accesses
+                    .iter()
+                    .map(|s: &SymbolicAccess| s.lookup_fmt(id2name))
+                    .collect::<Vec<_>>()
+                    .join("")
+            } else {
+                "".to_string()
+            };
+            id2name[&e.id].clone() + &access_str
+        })
+        .collect()
+}
+
+/// Maps the variables and signals of a state to their symbolic values.
+///
+/// The entries are keyed by the interned ids of the names (see `SymbolicName::symbol_id`), and
+/// the structured names are resolved from the interner only when the map is iterated.
+///
+/// The map is persistent: cloning it, as done when the execution forks at a branch, is O(1),
+/// and the branches share the entries they do not overwrite instead of copying the whole map.
+/// While a fork is open (see `begin_fork`), the ids of the written entries are logged so that
+/// the branches can be merged by visiting only those entries.
+#[derive(Clone, Debug, Default)]
+pub struct SymbolBindingMap {
+    values: PersistentHashMap<SymbolId, SymbolicValueRef, FxBuildHasher>,
+    writes: PersistentVector<SymbolId>,
+    open_forks: usize,
+}
+
+impl PartialEq for SymbolBindingMap {
+    fn eq(&self, other: &Self) -> bool {
+        self.values == other.values
+    }
+}
+
+impl SymbolBindingMap {
+    pub fn get(&self, sym_name: &SymbolicName) -> Option<&SymbolicValueRef> {
+        self.values.get(&sym_name.symbol_id())
+    }
+
+    /// Returns the value bound to the name interned as `id`.
+    pub fn get_by_id(&self, id: SymbolId) -> Option<&SymbolicValueRef> {
+        self.values.get(&id)
+    }
+
+    pub fn contains_key(&self, sym_name: &SymbolicName) -> bool {
+        self.values.contains_key(&sym_name.symbol_id())
+    }
+
+    pub fn insert(
+        &mut self,
+        sym_name: &SymbolicName,
+        sym_val: SymbolicValueRef,
+    ) -> Option<SymbolicValueRef> {
+        self.insert_by_id(sym_name.symbol_id(), sym_val)
+    }
+
+    /// Binds the name interned as `id` to `sym_val`.
+    pub fn insert_by_id(
+        &mut self,
+        id: SymbolId,
+        sym_val: SymbolicValueRef,
+    ) -> Option<SymbolicValueRef> {
+        if self.open_forks > 0 {
+            self.writes.push_back(id);
+        }
+        self.values.insert(id, sym_val)
+    }
+
+    /// Starts logging the written entries for a fork of the execution, and returns the position
+    /// to pass to `written_since` once the branches have been executed.
+    ///
+    /// The map must be cloned for the branches after this call, and each call must be matched
+    /// by a call to `end_fork` on the merged map.
+    pub fn begin_fork(&mut self) -> usize {
+        self.open_forks += 1;
+        self.writes.len()
+    }
+
+    /// Closes the innermost fork, and drops the log once no fork is open.
+    pub fn end_fork(&mut self) {
+        self.open_forks -= 1;
+        if self.open_forks == 0 {
+            self.writes.clear();
+        }
+    }
+
+    /// Iterates over the ids of the entries written since `begin_fork` returned `pos`, possibly
+    /// more than once.
+    pub fn written_since(&self, pos: usize) -> impl Iterator<Item = SymbolId> {
+        self.writes.skip(pos).into_iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Iterates over the entries, with each name resolved back from the interner.
+    pub fn iter(&self) -> impl Iterator<Item = (Rc<SymbolicName>, &SymbolicValueRef)> + '_ {
+        self.values.iter().map(|(id, value)| (resolve(*id), value))
+    }
+
+    /// Iterates over the entries keyed by the interned ids of their names, without resolving the
+    /// names.
+    pub fn iter_ids(&self) -> impl Iterator<Item = (SymbolId, &SymbolicValueRef)> + '_ {
+        self.values.iter().map(|(id, value)| (*id, value))
+    }
+
+    /// Iterates over the ids of the entries that are missing from `other` or bound to another
+    /// value there.
+    pub fn changed_since<'b>(
+        &'b self,
+        other: &'b SymbolBindingMap,
+    ) -> impl Iterator<Item = (SymbolId, &'b SymbolicValueRef)> + 'b {
+        self.iter_ids().filter(move |(id, value)| {
+            other
+                .values
+                .get(id)
+                .map_or(true, |v| !Rc::ptr_eq(v, value) && v != *value)
+        })
+    }
+}
+
+impl Index<&SymbolicName> for SymbolBindingMap {
+    type Output = SymbolicValueRef;
+
+    fn index(&self, sym_name: &SymbolicName) -> &SymbolicValueRef {
+        &self.values[&sym_name.symbol_id()]
+    }
+}
+
 /// Represents the state of symbolic execution, holding symbolic values,
 /// trace constraints, side constraints, and depth information.
 #[derive(Clone)]
@@ -24,6 +188,10 @@ pub struct SymbolicState {
     pub symbol_binding_map: SymbolBindingMap,
     pub symbolic_trace: SymbolicTrace,
     pub side_constraints: SymbolicConstraints,
+    /// The provenance of each constraint of `symbolic_trace`, at the same index.
+    pub trace_provenance: Vec<ConstraintProvenance>,
+    /// The provenance of each constraint of `side_constraints`, at the same index.
+    pub side_constraint_provenance: Vec<ConstraintProvenance>,
     pub is_failed: bool,
 }
 
@@ -43,10 +211,55 @@ impl SymbolicState {
             symbol_binding_map: SymbolBindingMap::default(),
             symbolic_trace: SymbolicTrace::new(),
             side_constraints: SymbolicConstraints::new(),
+            trace_provenance: Vec::new(),
+            side_constraint_provenance: Vec::new(),
             is_failed: false,
         }
     }
 
+    /// Creates the state in which a branch is executed when the execution forks.
+    ///
+    /// The constraints gathered before the fork are shared by both branches, so they are not
+    /// copied: the returned state has no constraint, and the constraints of `self` are handed
+    /// over to it with `hand_over_constraints` once the branch executed on `self` is done.
+    pub fn fork(&self) -> Self {
+        SymbolicState {
+            owner_name: self.owner_name.clone(),
+            template_id: self.template_id,
+            is_within_initialization_block: self.is_within_initialization_block,
+            contains_symbolic_loop: self.contains_symbolic_loop,
+            depth: self.depth,
+            symbol_binding_map: self.symbol_binding_map.clone(),
+            symbolic_trace: SymbolicTrace::new(),
+            side_constraints: SymbolicConstraints::new(),
+            trace_provenance: Vec::new(),
+            side_constraint_provenance: Vec::new(),
+            is_failed: self.is_failed,
+        }
+    }
+
+    /// Moves the first `num_trace` trace constraints and `num_side_constraints` side
+    /// constraints, i.e., those gathered before the fork, to the state `other` created by
+    /// `fork`. Only the constraints added by the branch are kept in `self`.
+    pub fn hand_over_constraints(
+        &mut self,
+        num_trace: usize,
+        num_side_constraints: usize,
+        other: &mut SymbolicState,
+    ) {
+        let trace = self.symbolic_trace.split_off(num_trace);
+        other.symbolic_trace = std::mem::replace(&mut self.symbolic_trace, trace);
+        let provenance = self.trace_provenance.split_off(num_trace);
+        other.trace_provenance = std::mem::replace(&mut self.trace_provenance, provenance);
+        let side_constraints = self.side_constraints.split_off(num_side_constraints);
+        other.side_constraints = std::mem::replace(&mut self.side_constraints, side_constraints);
+        let provenance = self
+            .side_constraint_provenance
+            .split_off(num_side_constraints);
+        other.side_constraint_provenance =
+            std::mem::replace(&mut self.side_constraint_provenance, provenance);
+    }
+
     /// Adds an owner to the current symbolic state.
     ///
     /// This method appends a new owner name to the existing list of owners.
@@ -71,22 +284,16 @@ impl SymbolicState {
     ///
     /// A string representing the full owner name.
     pub fn get_owner(&self, id2name: &FxHashMap<usize, String>) -> String {
-        self.owner_name
-            .iter()
-            .map(|e: &OwnerName| {
-                let access_str: String = if let Some(accesses) = &e.access {
-                    accesses
-                        .iter()
-                        .map(|s: &SymbolicAccess| s.lookup_fmt(id2name))
-                        .collect::<Vec<_>>()
-                        .join("")
-                } else {
-                    "".to_string()
-                };
-                id2name[&e.id].clone() + &access_str
-            })
-            .collect::<Vec<_>>()
-            .join(".")
+        self.get_owner_stack(id2name).join(".")
+    }
+
+    /// Retrieves the owner names of the current state, outermost first, e.g. `["main", "c[0]"]`.
+    ///
+    /// # Arguments
+    ///
+    /// * `id2name` - A hash map containing mappings from usize to String for name lookups.
+    pub fn get_owner_stack(&self, id2name: &FxHashMap<usize, String>) -> Vec<String> {
+        format_owner_stack(&self.owner_name, id2name)
     }
 
     /// Sets the template ID for the current symbolic state.
@@ -114,7 +321,7 @@ impl SymbolicState {
     /// * `sym_name` - The name of the variable.
     /// * `sym_val` - The symbolic value to associate with the variable.
     pub fn set_sym_val(&mut self, sym_name: SymbolicName, sym_val: SymbolicValue) {
-        self.symbol_binding_map.insert(sym_name, Rc::new(sym_val));
+        self.symbol_binding_map.insert(&sym_name, Rc::new(sym_val));
     }
 
     /// Sets a reference-counted symbolic value for a given variable name in the state.
@@ -124,7 +331,7 @@ impl SymbolicState {
     /// * `sym_name` - The name of the variable.
     /// * `sym_val` - The reference-counted symbolic value to associate with the variable.
     pub fn set_rc_sym_val(&mut self, sym_name: SymbolicName, sym_val: SymbolicValueRef) {
-        self.symbol_binding_map.insert(sym_name, sym_val);
+        self.symbol_binding_map.insert(&sym_name, sym_val);
     }
 
     /// Retrieves a symbolic value associated with a given variable name.
@@ -153,8 +360,14 @@ impl SymbolicState {
     /// # Arguments
     ///
     /// * `constraint` - The symbolic value representing the constraint.
-    pub fn push_symbolic_trace(&mut self, constraint: &SymbolicValue) {
+    /// * `provenance` - The statement that produced the constraint.
+    pub fn push_symbolic_trace(
+        &mut self,
+        constraint: &SymbolicValue,
+        provenance: ConstraintProvenance,
+    ) {
         self.symbolic_trace.push(Rc::new(constraint.clone()));
+        self.trace_provenance.push(provenance);
     }
 
     /// Adds a side constraint to the current state.
@@ -162,8 +375,14 @@ impl SymbolicState {
     /// # Arguments
     ///
     /// * `constraint` - The symbolic value representing the constraint.
-    pub fn push_side_constraint(&mut self, constraint: &SymbolicValue) {
+    /// * `provenance` - The statement that produced the constraint.
+    pub fn push_side_constraint(
+        &mut self,
+        constraint: &SymbolicValue,
+        provenance: ConstraintProvenance,
+    ) {
         self.side_constraints.push(Rc::new(constraint.clone()));
+        self.side_constraint_provenance.push(provenance);
     }
 
     /// Formats the symbolic state for lookup and display.
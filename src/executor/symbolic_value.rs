@@ -1,4 +1,4 @@
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
 use std::cmp::Ordering;
 use std::collections::VecDeque;
 use std::hash::{Hash, Hasher};
@@ -10,6 +10,7 @@ use num_traits::FromPrimitive;
 use num_traits::ToPrimitive;
 use num_traits::{One, Signed, Zero};
 use rustc_hash::{FxHashMap, FxHashSet, FxHasher};
+use serde::{Deserialize, Serialize};
 
 use program_structure::ast::{ExpressionInfixOpcode, SignalType, Statement, VariableType};
 
@@ -17,7 +18,11 @@ use crate::executor::debug_ast::{
     DebuggableExpression, DebuggableExpressionInfixOpcode, DebuggableExpressionPrefixOpcode,
     DebuggableStatement,
 };
-use crate::executor::utils::{extended_euclidean, generate_cartesian_product_indices, modpow};
+use crate::executor::symbolic_interner::{intern, interner_generation, SymbolId};
+use crate::executor::template_semantics::TemplateSemantics;
+use crate::executor::utils::{
+    bitwise, generate_cartesian_product_indices, moddiv, modpow, modreduce, shift_left, shift_right,
+};
 
 /// Represents the access type within a symbolic expression, such as component or array access.
 #[derive(Clone, PartialEq, Eq, Hash, Debug)]
@@ -75,6 +80,8 @@ pub struct SymbolicName {
     pub owner: Rc<Vec<OwnerName>>,
     pub access: Option<Vec<SymbolicAccess>>,
     precomputed_hash: RefCell<Option<u64>>,
+    /// The interned id of the name and the generation of the interner it was interned in.
+    interned_id: Cell<Option<(u64, SymbolId)>>,
 }
 
 impl SymbolicName {
@@ -84,9 +91,41 @@ impl SymbolicName {
             owner,
             access,
             precomputed_hash: RefCell::new(None),
+            interned_id: Cell::new(None),
+        }
+    }
+
+    /// Returns the interned id of this name, which is cached until the name is modified or the
+    /// interner is reset.
+    pub fn symbol_id(&self) -> SymbolId {
+        let generation = interner_generation();
+        match self.interned_id.get() {
+            Some((cached_generation, id)) if cached_generation == generation => id,
+            _ => {
+                let id = intern(self);
+                self.interned_id.set(Some((generation, id)));
+                id
+            }
         }
     }
 
+    /// Returns the tags attached to the declaration of this signal in `template`.
+    ///
+    /// # Arguments
+    ///
+    /// * `template` - The template in which the signal is declared.
+    ///
+    /// # Returns
+    ///
+    /// A slice of tag names (e.g. `["binary"]`). The slice is empty when the signal has no tags.
+    pub fn get_tags<'a>(&self, template: &'a SymbolicTemplate) -> &'a [String] {
+        template
+            .id2tags
+            .get(&self.id)
+            .map(|tags| tags.as_slice())
+            .unwrap_or(&[])
+    }
+
     pub fn get_dim(&self) -> usize {
         if let Some(ref local_access) = self.access {
             local_access.len()
@@ -137,6 +176,7 @@ impl SymbolicName {
 
     pub fn update_hash(&self) {
         *self.precomputed_hash.borrow_mut() = Some(self.compute_hash());
+        self.interned_id.set(None);
     }
 
     fn get_or_update_hash(&self) -> u64 {
@@ -385,15 +425,23 @@ pub struct SymbolicTemplate {
     pub template_parameter_names: Vec<usize>,
     pub input_ids: FxHashSet<usize>,
     pub output_ids: FxHashSet<usize>,
+    /// The input signals in the order of their declarations, which is the order in which an
+    /// anonymous component `A()(x, y)` takes its inputs.
+    pub input_order: Vec<usize>,
+    /// The output signals in the order of their declarations, which is the order in which
+    /// `(a, b) <== A()(x)` assigns them.
+    pub output_order: Vec<usize>,
     pub id2type: FxHashMap<usize, VariableType>,
+    pub id2tags: FxHashMap<usize, Vec<String>>,
     pub id2dimension_expressions: FxHashMap<usize, Vec<DebuggableExpression>>,
     pub body: Vec<DebuggableStatement>,
-    pub is_lessthan: bool,
+    pub semantics: Vec<Rc<dyn TemplateSemantics>>,
     pub is_safe: bool,
+    pub is_custom_gate: bool,
 }
 
 /// Represents a symbolic function used in the symbolic execution process.
-#[derive(Default, Clone)]
+#[derive(Default, Clone, Serialize, Deserialize)]
 pub struct SymbolicFunction {
     pub function_argument_names: Vec<usize>,
     pub id2dimension_expressions: FxHashMap<usize, Vec<DebuggableExpression>>,
@@ -401,6 +449,10 @@ pub struct SymbolicFunction {
 }
 
 /// Represents a symbolic component used in the symbolic execution process.
+///
+/// Each instance of a component array (e.g. `c[1][0]` of `component c[2][3]`) is a component of
+/// its own, keyed by its indexed name, whose inputs are bound element by element as they are
+/// assigned. It is executed once all of them are bound, after which `is_done` is set.
 #[derive(Default, Clone)]
 pub struct SymbolicComponent {
     pub template_id: usize,
@@ -410,6 +462,22 @@ pub struct SymbolicComponent {
     pub is_done: bool,
 }
 
+impl SymbolicComponent {
+    /// Returns `true` if every input of the component is bound and it has not been executed yet.
+    pub fn is_ready(&self) -> bool {
+        !self.is_done && self.inputs_binding_map.values().all(|v| v.is_some())
+    }
+
+    /// Returns the inputs of the component that are not bound yet, e.g. `in[1]`.
+    pub fn missing_inputs(&self) -> Vec<&SymbolicName> {
+        self.inputs_binding_map
+            .iter()
+            .filter(|(_, v)| v.is_none())
+            .map(|(k, _)| k)
+            .collect()
+    }
+}
+
 /// Manages symbolic libraries, templates, and functions for symbolic execution.
 #[derive(Default, Clone)]
 pub struct SymbolicLibrary {
@@ -418,16 +486,20 @@ pub struct SymbolicLibrary {
     pub name2id: FxHashMap<String, usize>,
     pub id2name: FxHashMap<usize, String>,
     pub function_counter: FxHashMap<usize, usize>,
+    pub template_semantics: Vec<Rc<dyn TemplateSemantics>>,
 }
 
 fn gather_variables_for_template(
     dbody: &DebuggableStatement,
     input_ids: &mut FxHashSet<usize>,
     output_ids: &mut FxHashSet<usize>,
+    declared_signals: &mut Vec<(usize, usize)>,
     id2type: &mut FxHashMap<usize, VariableType>,
+    id2tags: &mut FxHashMap<usize, Vec<String>>,
     id2dimensions: &mut FxHashMap<usize, Vec<DebuggableExpression>>,
 ) {
     if let DebuggableStatement::Declaration {
+        meta,
         id,
         xtype,
         dimensions,
@@ -436,13 +508,18 @@ fn gather_variables_for_template(
     {
         id2type.insert(*id, xtype.clone());
         id2dimensions.insert(*id, dimensions.clone());
-        if let VariableType::Signal(typ, _taglist) = &xtype {
+        if let VariableType::Signal(typ, taglist) = &xtype {
+            if !taglist.is_empty() {
+                id2tags.insert(*id, taglist.clone());
+            }
             match typ {
                 SignalType::Input => {
                     input_ids.insert(*id);
+                    declared_signals.push((meta.start, *id));
                 }
                 SignalType::Output => {
                     output_ids.insert(*id);
+                    declared_signals.push((meta.start, *id));
                 }
                 SignalType::Intermediate => {}
             }
@@ -467,6 +544,22 @@ impl SymbolicLibrary {
         }
     }
 
+    /// Registers semantics plugin and attaches it to every already registered template it applies to.
+    ///
+    /// Templates registered afterwards are matched against the plugin in `register_template`.
+    ///
+    /// # Arguments
+    ///
+    /// * `semantics` - The plugin emitting auxiliary constraints for the templates it applies to.
+    pub fn register_template_semantics(&mut self, semantics: Rc<dyn TemplateSemantics>) {
+        for (id, template) in self.template_library.iter_mut() {
+            if semantics.is_applicable(&self.id2name[id], template) {
+                template.semantics.push(semantics.clone());
+            }
+        }
+        self.template_semantics.push(semantics);
+    }
+
     /// Registers a library template by extracting input signals from the provided block statement body.
     ///
     /// # Arguments
@@ -474,22 +567,23 @@ impl SymbolicLibrary {
     /// * `name` - Name under which the template will be registered within the library.
     /// * `body` - Block statement serving as the main logic body defining the behavior captured by the template.
     /// * `template_parameter_names` - List of names identifying parameters used within the template logic.
-    /// * `whitelist` -
-    /// * `is_lessthan_dissabled` -
+    /// * `whitelist` - Names of the templates whose assignments are considered safe.
+    /// * `is_custom_gate` - Whether the template is declared with `template custom`.
     pub fn register_template(
         &mut self,
         name: String,
         body: &Statement,
         template_parameter_names: &Vec<String>,
         whitelist: &FxHashSet<String>,
-        is_lessthan_dissabled: bool,
+        is_custom_gate: bool,
     ) {
         let mut input_ids = FxHashSet::default();
         let mut output_ids = FxHashSet::default();
+        let mut declared_signals = Vec::new();
         let mut id2type = FxHashMap::default();
+        let mut id2tags = FxHashMap::default();
         let mut id2dimension_expressions = FxHashMap::default();
 
-        let is_lessthan = !is_lessthan_dissabled && &name == "LessThan";
         let is_safe = whitelist.contains(&name);
 
         let i = if let Some(i) = self.name2id.get(&name) {
@@ -507,35 +601,60 @@ impl SymbolicLibrary {
                 stmt,
                 &mut input_ids,
                 &mut output_ids,
+                &mut declared_signals,
                 &mut id2type,
+                &mut id2tags,
                 &mut id2dimension_expressions,
             );
         });
 
-        self.template_library.insert(
-            i,
-            Box::new(SymbolicTemplate {
-                template_parameter_names: template_parameter_names
-                    .iter()
-                    .map(|p: &String| {
-                        if let Some(i) = self.name2id.get(p) {
-                            *i
-                        } else {
-                            self.name2id.insert(p.clone(), self.name2id.len());
-                            self.id2name.insert(self.name2id[p], name.clone());
-                            self.name2id.len() - 1
-                        }
-                    })
-                    .collect::<Vec<_>>(),
-                input_ids: input_ids,
-                output_ids: output_ids,
-                id2type: id2type,
-                id2dimension_expressions: id2dimension_expressions,
-                body: vec![dbody.clone(), DebuggableStatement::Ret],
-                is_lessthan: is_lessthan,
-                is_safe: is_safe,
-            }),
-        );
+        // The statements are not visited in the order of the source
+        declared_signals.sort();
+        let mut seen = FxHashSet::default();
+        declared_signals.retain(|(_, id)| seen.insert(*id));
+        let input_order = declared_signals
+            .iter()
+            .map(|(_, id)| *id)
+            .filter(|id| input_ids.contains(id))
+            .collect();
+        let output_order = declared_signals
+            .iter()
+            .map(|(_, id)| *id)
+            .filter(|id| output_ids.contains(id))
+            .collect();
+
+        let mut template = Box::new(SymbolicTemplate {
+            template_parameter_names: template_parameter_names
+                .iter()
+                .map(|p: &String| {
+                    if let Some(i) = self.name2id.get(p) {
+                        *i
+                    } else {
+                        self.name2id.insert(p.clone(), self.name2id.len());
+                        self.id2name.insert(self.name2id[p], name.clone());
+                        self.name2id.len() - 1
+                    }
+                })
+                .collect::<Vec<_>>(),
+            input_ids: input_ids,
+            output_ids: output_ids,
+            input_order: input_order,
+            output_order: output_order,
+            id2type: id2type,
+            id2tags: id2tags,
+            id2dimension_expressions: id2dimension_expressions,
+            body: vec![dbody.clone(), DebuggableStatement::Ret],
+            semantics: Vec::new(),
+            is_safe: is_safe,
+            is_custom_gate: is_custom_gate,
+        });
+        template.semantics = self
+            .template_semantics
+            .iter()
+            .filter(|semantics| semantics.is_applicable(&name, &template))
+            .cloned()
+            .collect();
+        self.template_library.insert(i, template);
     }
 
     /// Registers a function in the symbolic library.
@@ -764,7 +883,8 @@ pub fn val_for_relational_operators(z: &BigInt, p: &BigInt) -> BigInt {
 /// # Special Cases
 /// - Modular arithmetic operations respect the `prime` modulus.
 /// - Division and modulo operations handle zero as a special case, returning zero when the divisor is zero.
-/// - Negative values are normalized using the modulus where applicable.
+/// - Integer operands and results are canonicalized to `[0, prime)` with `modreduce`, so that a
+///   negative constant such as `-1` evaluates to `prime - 1`.
 ///
 /// # Panics
 /// - The function may panic if an unsupported operation is encountered in a match statement with `todo!`.
@@ -822,38 +942,11 @@ pub fn evaluate_binary_op(
 
     match (&normalized_lhs, &normalized_rhs) {
         (SymbolicValue::ConstantInt(lv), SymbolicValue::ConstantInt(rv)) => match &op.0 {
-            ExpressionInfixOpcode::Add => SymbolicValue::ConstantInt((lv + rv) % prime),
-            ExpressionInfixOpcode::Sub => {
-                let mut tmp = (lv - rv) % prime;
-                if tmp.is_negative() {
-                    tmp += prime;
-                }
-                SymbolicValue::ConstantInt(tmp)
-            }
-            ExpressionInfixOpcode::Mul => SymbolicValue::ConstantInt((lv * rv) % prime),
+            ExpressionInfixOpcode::Add => SymbolicValue::ConstantInt(modreduce(&(lv + rv), prime)),
+            ExpressionInfixOpcode::Sub => SymbolicValue::ConstantInt(modreduce(&(lv - rv), prime)),
+            ExpressionInfixOpcode::Mul => SymbolicValue::ConstantInt(modreduce(&(lv * rv), prime)),
             ExpressionInfixOpcode::Pow => SymbolicValue::ConstantInt(modpow(lv, rv, prime)),
-            ExpressionInfixOpcode::Div => {
-                if lv.is_zero() || rv.is_zero() {
-                    SymbolicValue::ConstantInt(BigInt::zero())
-                } else {
-                    let mut r = prime.clone();
-                    let mut new_r = rv.clone();
-                    if r.is_negative() {
-                        r += prime;
-                    }
-                    if new_r.is_negative() {
-                        new_r += prime;
-                    }
-
-                    let (_, _, mut rv_inv) = extended_euclidean(r, new_r);
-                    rv_inv %= prime;
-                    if rv_inv.is_negative() {
-                        rv_inv += prime;
-                    }
-
-                    SymbolicValue::ConstantInt((lv * rv_inv) % prime)
-                }
-            }
+            ExpressionInfixOpcode::Div => SymbolicValue::ConstantInt(moddiv(lv, rv, prime)),
             ExpressionInfixOpcode::IntDiv => {
                 SymbolicValue::ConstantInt(if lv.is_zero() || rv.is_zero() {
                     BigInt::zero()
@@ -868,15 +961,17 @@ pub fn evaluate_binary_op(
                     lv % rv
                 })
             }
-            ExpressionInfixOpcode::BitOr => SymbolicValue::ConstantInt(lv | rv),
-            ExpressionInfixOpcode::BitAnd => SymbolicValue::ConstantInt(lv & rv),
-            ExpressionInfixOpcode::BitXor => SymbolicValue::ConstantInt(lv ^ rv),
-            ExpressionInfixOpcode::ShiftL => {
-                SymbolicValue::ConstantInt(lv << rv.to_usize().unwrap())
+            ExpressionInfixOpcode::BitOr => {
+                SymbolicValue::ConstantInt(bitwise(lv, rv, prime, |a, b| a | b))
             }
-            ExpressionInfixOpcode::ShiftR => {
-                SymbolicValue::ConstantInt(lv >> rv.to_usize().unwrap())
+            ExpressionInfixOpcode::BitAnd => {
+                SymbolicValue::ConstantInt(bitwise(lv, rv, prime, |a, b| a & b))
             }
+            ExpressionInfixOpcode::BitXor => {
+                SymbolicValue::ConstantInt(bitwise(lv, rv, prime, |a, b| a ^ b))
+            }
+            ExpressionInfixOpcode::ShiftL => SymbolicValue::ConstantInt(shift_left(lv, rv, prime)),
+            ExpressionInfixOpcode::ShiftR => SymbolicValue::ConstantInt(shift_right(lv, rv, prime)),
             ExpressionInfixOpcode::Lesser => SymbolicValue::ConstantBool(
                 val_for_relational_operators(&(lv % prime), prime)
                     < val_for_relational_operators(&(rv % prime), prime),
@@ -942,38 +1037,11 @@ pub fn evaluate_binary_op_integer_mode(
 
     match (&normalized_lhs, &normalized_rhs) {
         (SymbolicValue::ConstantInt(lv), SymbolicValue::ConstantInt(rv)) => match &op.0 {
-            ExpressionInfixOpcode::Add => SymbolicValue::ConstantInt((lv + rv) % prime),
-            ExpressionInfixOpcode::Sub => {
-                let mut tmp = (lv - rv) % prime;
-                if tmp.is_negative() {
-                    tmp += prime;
-                }
-                SymbolicValue::ConstantInt(tmp)
-            }
-            ExpressionInfixOpcode::Mul => SymbolicValue::ConstantInt((lv * rv) % prime),
+            ExpressionInfixOpcode::Add => SymbolicValue::ConstantInt(modreduce(&(lv + rv), prime)),
+            ExpressionInfixOpcode::Sub => SymbolicValue::ConstantInt(modreduce(&(lv - rv), prime)),
+            ExpressionInfixOpcode::Mul => SymbolicValue::ConstantInt(modreduce(&(lv * rv), prime)),
             ExpressionInfixOpcode::Pow => SymbolicValue::ConstantInt(modpow(lv, rv, prime)),
-            ExpressionInfixOpcode::Div => {
-                if lv.is_zero() || rv.is_zero() {
-                    SymbolicValue::ConstantInt(BigInt::zero())
-                } else {
-                    let mut r = prime.clone();
-                    let mut new_r = rv.clone();
-                    if r.is_negative() {
-                        r += prime;
-                    }
-                    if new_r.is_negative() {
-                        new_r += prime;
-                    }
-
-                    let (_, _, mut rv_inv) = extended_euclidean(r, new_r);
-                    rv_inv %= prime;
-                    if rv_inv.is_negative() {
-                        rv_inv += prime;
-                    }
-
-                    SymbolicValue::ConstantInt((lv * rv_inv) % prime)
-                }
-            }
+            ExpressionInfixOpcode::Div => SymbolicValue::ConstantInt(moddiv(lv, rv, prime)),
             ExpressionInfixOpcode::IntDiv => {
                 SymbolicValue::ConstantInt(if lv.is_zero() || rv.is_zero() {
                     BigInt::zero()
@@ -988,15 +1056,17 @@ pub fn evaluate_binary_op_integer_mode(
                     lv % rv
                 })
             }
-            ExpressionInfixOpcode::BitOr => SymbolicValue::ConstantInt(lv | rv),
-            ExpressionInfixOpcode::BitAnd => SymbolicValue::ConstantInt(lv & rv),
-            ExpressionInfixOpcode::BitXor => SymbolicValue::ConstantInt(lv ^ rv),
-            ExpressionInfixOpcode::ShiftL => {
-                SymbolicValue::ConstantInt(lv << rv.to_usize().unwrap())
+            ExpressionInfixOpcode::BitOr => {
+                SymbolicValue::ConstantInt(bitwise(lv, rv, prime, |a, b| a | b))
+            }
+            ExpressionInfixOpcode::BitAnd => {
+                SymbolicValue::ConstantInt(bitwise(lv, rv, prime, |a, b| a & b))
             }
-            ExpressionInfixOpcode::ShiftR => {
-                SymbolicValue::ConstantInt(lv >> rv.to_usize().unwrap())
+            ExpressionInfixOpcode::BitXor => {
+                SymbolicValue::ConstantInt(bitwise(lv, rv, prime, |a, b| a ^ b))
             }
+            ExpressionInfixOpcode::ShiftL => SymbolicValue::ConstantInt(shift_left(lv, rv, prime)),
+            ExpressionInfixOpcode::ShiftR => SymbolicValue::ConstantInt(shift_right(lv, rv, prime)),
             ExpressionInfixOpcode::Lesser => SymbolicValue::ConstantBool(lv % prime < rv % prime),
             ExpressionInfixOpcode::Greater => SymbolicValue::ConstantBool(lv % prime > rv % prime),
             ExpressionInfixOpcode::LesserEq => {
@@ -1018,14 +1088,15 @@ pub fn evaluate_binary_op_integer_mode(
     }
 }
 
+/// Converts a constant to an integer in `[0, prime)`, mapping booleans to `0` and `1`.
 pub fn normalize_to_int(val: &SymbolicValue, prime: &BigInt) -> SymbolicValue {
     match val {
         SymbolicValue::ConstantBool(b) => {
             SymbolicValue::ConstantInt(if *b { BigInt::one() } else { BigInt::zero() })
         }
         SymbolicValue::ConstantInt(num) => {
-            if num.is_negative() {
-                SymbolicValue::ConstantInt(num + prime)
+            if num.is_negative() || num >= prime {
+                SymbolicValue::ConstantInt(modreduce(num, prime))
             } else {
                 val.clone()
             }
@@ -1041,62 +1112,6 @@ pub fn normalize_to_bool(val: &SymbolicValue, prime: &BigInt) -> SymbolicValue {
     }
 }
 
-pub fn generate_lessthan_constraint(
-    name2id: &FxHashMap<String, usize>,
-    owner_name: Rc<Vec<OwnerName>>,
-) -> SymbolicValue {
-    let in_0 = Rc::new(SymbolicValue::Variable(SymbolicName::new(
-        name2id["in"],
-        owner_name.clone(),
-        Some(vec![SymbolicAccess::ArrayAccess(
-            SymbolicValue::ConstantInt(BigInt::zero()),
-        )]),
-    )));
-    let in_1 = Rc::new(SymbolicValue::Variable(SymbolicName::new(
-        name2id["in"],
-        owner_name.clone(),
-        Some(vec![SymbolicAccess::ArrayAccess(
-            SymbolicValue::ConstantInt(BigInt::one()),
-        )]),
-    )));
-    let lessthan_out = Rc::new(SymbolicValue::Variable(SymbolicName::new(
-        name2id["out"],
-        owner_name,
-        None,
-    )));
-    let cond_1 = SymbolicValue::BinaryOp(
-        Rc::new(SymbolicValue::BinaryOp(
-            Rc::new(SymbolicValue::ConstantInt(BigInt::one())),
-            DebuggableExpressionInfixOpcode(ExpressionInfixOpcode::Eq),
-            lessthan_out.clone(),
-        )),
-        DebuggableExpressionInfixOpcode(ExpressionInfixOpcode::BoolAnd),
-        Rc::new(SymbolicValue::AuxBinaryOp(
-            in_0.clone(),
-            DebuggableExpressionInfixOpcode(ExpressionInfixOpcode::Lesser),
-            in_1.clone(),
-        )),
-    );
-    let cond_0 = SymbolicValue::BinaryOp(
-        Rc::new(SymbolicValue::BinaryOp(
-            Rc::new(SymbolicValue::ConstantInt(BigInt::zero())),
-            DebuggableExpressionInfixOpcode(ExpressionInfixOpcode::Eq),
-            lessthan_out.clone(),
-        )),
-        DebuggableExpressionInfixOpcode(ExpressionInfixOpcode::BoolAnd),
-        Rc::new(SymbolicValue::AuxBinaryOp(
-            in_0,
-            DebuggableExpressionInfixOpcode(ExpressionInfixOpcode::GreaterEq),
-            in_1,
-        )),
-    );
-    SymbolicValue::BinaryOp(
-        Rc::new(cond_1),
-        DebuggableExpressionInfixOpcode(ExpressionInfixOpcode::BoolOr),
-        Rc::new(cond_0),
-    )
-}
-
 fn check_array_concrete(array: &Vec<SymbolicValueRef>) -> bool {
     for value_ref in array {
         match &**value_ref {
@@ -1115,6 +1130,37 @@ fn check_array_concrete(array: &Vec<SymbolicValueRef>) -> bool {
     true
 }
 
+/// Splits a constraint over a ternary value into constraints guarded by its condition.
+///
+/// `make(v)` builds the constraint for a value `v`. For `v = Conditional(c, t, f)` whose condition is
+/// symbolic, the constraints of `t` are guarded as `Conditional(c, _, true)` and those of `f` as
+/// `Conditional(c, true, _)`, which is how the merged branches of a symbolic `if` are recorded, so
+/// that `x <== c ? a : b` and the equivalent `if` statement produce the same constraints. Other
+/// values yield the single constraint `make(v)`.
+pub fn split_conditional_constraint<F>(value: &SymbolicValueRef, make: &F) -> Vec<SymbolicValue>
+where
+    F: Fn(SymbolicValueRef) -> SymbolicValue,
+{
+    match &**value {
+        SymbolicValue::Conditional(cond, if_true, if_false)
+            if !matches!(&**cond, SymbolicValue::ConstantBool(_)) =>
+        {
+            let always = Rc::new(SymbolicValue::ConstantBool(true));
+            let mut constraints: Vec<_> = split_conditional_constraint(if_true, make)
+                .into_iter()
+                .map(|c| SymbolicValue::Conditional(cond.clone(), Rc::new(c), always.clone()))
+                .collect();
+            constraints.extend(
+                split_conditional_constraint(if_false, make)
+                    .into_iter()
+                    .map(|c| SymbolicValue::Conditional(cond.clone(), always.clone(), Rc::new(c))),
+            );
+            constraints
+        }
+        _ => vec![make(value.clone())],
+    }
+}
+
 pub fn is_concrete_array(value: &SymbolicValue) -> bool {
     match value {
         SymbolicValue::Array(array) => check_array_concrete(array),
@@ -1207,7 +1253,7 @@ pub fn extract_variables_from_symbolic_value(
             extract_variables_from_symbolic_value(&lhs, variables);
             extract_variables_from_symbolic_value(&rhs, variables);
         }
-        SymbolicValue::BinaryOp(lhs, _, rhs) => {
+        SymbolicValue::BinaryOp(lhs, _, rhs) | SymbolicValue::AuxBinaryOp(lhs, _, rhs) => {
             extract_variables_from_symbolic_value(&lhs, variables);
             extract_variables_from_symbolic_value(&rhs, variables);
         }
@@ -1235,6 +1281,32 @@ pub fn extract_variables_from_symbolic_value(
     }
 }
 
+/// Returns the condition of the first ternary (`c ? a : b`) in `value` whose condition is not a
+/// constant, if any.
+///
+/// circom generates a constraint for such a ternary only if the whole expression is quadratic in
+/// the signals, which a selection by a signal-dependent condition is not, so an assignment
+/// `out <== c ? a : b` is rejected or compiled without constraining `out` as intended.
+pub fn find_symbolic_condition(value: &SymbolicValue) -> Option<SymbolicValueRef> {
+    match value {
+        SymbolicValue::Conditional(cond, then_val, else_val) => match &**cond {
+            SymbolicValue::ConstantInt(_) | SymbolicValue::ConstantBool(_) => {
+                find_symbolic_condition(then_val).or_else(|| find_symbolic_condition(else_val))
+            }
+            _ => Some(cond.clone()),
+        },
+        SymbolicValue::BinaryOp(lhs, _, rhs) | SymbolicValue::AuxBinaryOp(lhs, _, rhs) => {
+            find_symbolic_condition(lhs).or_else(|| find_symbolic_condition(rhs))
+        }
+        SymbolicValue::UnaryOp(_, expr) => find_symbolic_condition(expr),
+        SymbolicValue::Array(elements) | SymbolicValue::Call(_, elements) => {
+            elements.iter().find_map(|elem| find_symbolic_condition(elem))
+        }
+        SymbolicValue::UniformArray(value, _) => find_symbolic_condition(value),
+        _ => None,
+    }
+}
+
 pub fn get_coefficient_of_polynomials(
     expr: &SymbolicValue,
     target_name: &SymbolicName,
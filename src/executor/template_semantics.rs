@@ -0,0 +1,118 @@
+use std::rc::Rc;
+
+use num_bigint_dig::BigInt;
+use num_traits::{One, Zero};
+use rustc_hash::FxHashMap;
+
+use program_structure::ast::ExpressionInfixOpcode;
+
+use crate::executor::debug_ast::DebuggableExpressionInfixOpcode;
+use crate::executor::symbolic_value::{
+    OwnerName, SymbolicAccess, SymbolicName, SymbolicTemplate, SymbolicValue,
+};
+
+/// Semantics of a template that are not visible from its constraints alone.
+///
+/// A plugin decides which templates it applies to when they are registered in the
+/// `SymbolicLibrary`, and emits auxiliary constraints into the symbolic trace each time a
+/// component of such a template has been executed. This allows well-known gadgets (e.g.,
+/// comparators, range checks, or hash functions) to be summarized without touching the
+/// executor itself.
+pub trait TemplateSemantics {
+    /// Returns `true` if the plugin applies to the given template.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Name under which the template is registered.
+    /// * `template` - The registered template.
+    fn is_applicable(&self, name: &str, template: &SymbolicTemplate) -> bool;
+
+    /// Emits the auxiliary constraints of an executed component.
+    ///
+    /// # Arguments
+    ///
+    /// * `name2id` - A hash map associating names with their IDs.
+    /// * `owner_name` - The owner of the component's signals.
+    ///
+    /// # Returns
+    ///
+    /// The constraints appended to the symbolic trace of the parent.
+    fn emit_constraints(
+        &self,
+        name2id: &FxHashMap<String, usize>,
+        owner_name: Rc<Vec<OwnerName>>,
+    ) -> Vec<SymbolicValue>;
+}
+
+/// Semantics of circomlib's `LessThan`: `out` is `1` iff `in[0] < in[1]`, and `0` otherwise.
+pub struct LessThanSemantics;
+
+impl TemplateSemantics for LessThanSemantics {
+    fn is_applicable(&self, name: &str, _template: &SymbolicTemplate) -> bool {
+        name == "LessThan"
+    }
+
+    fn emit_constraints(
+        &self,
+        name2id: &FxHashMap<String, usize>,
+        owner_name: Rc<Vec<OwnerName>>,
+    ) -> Vec<SymbolicValue> {
+        vec![generate_lessthan_constraint(name2id, owner_name)]
+    }
+}
+
+fn generate_lessthan_constraint(
+    name2id: &FxHashMap<String, usize>,
+    owner_name: Rc<Vec<OwnerName>>,
+) -> SymbolicValue {
+    let in_0 = Rc::new(SymbolicValue::Variable(SymbolicName::new(
+        name2id["in"],
+        owner_name.clone(),
+        Some(vec![SymbolicAccess::ArrayAccess(
+            SymbolicValue::ConstantInt(BigInt::zero()),
+        )]),
+    )));
+    let in_1 = Rc::new(SymbolicValue::Variable(SymbolicName::new(
+        name2id["in"],
+        owner_name.clone(),
+        Some(vec![SymbolicAccess::ArrayAccess(
+            SymbolicValue::ConstantInt(BigInt::one()),
+        )]),
+    )));
+    let lessthan_out = Rc::new(SymbolicValue::Variable(SymbolicName::new(
+        name2id["out"],
+        owner_name,
+        None,
+    )));
+    let cond_1 = SymbolicValue::BinaryOp(
+        Rc::new(SymbolicValue::BinaryOp(
+            Rc::new(SymbolicValue::ConstantInt(BigInt::one())),
+            DebuggableExpressionInfixOpcode(ExpressionInfixOpcode::Eq),
+            lessthan_out.clone(),
+        )),
+        DebuggableExpressionInfixOpcode(ExpressionInfixOpcode::BoolAnd),
+        Rc::new(SymbolicValue::AuxBinaryOp(
+            in_0.clone(),
+            DebuggableExpressionInfixOpcode(ExpressionInfixOpcode::Lesser),
+            in_1.clone(),
+        )),
+    );
+    let cond_0 = SymbolicValue::BinaryOp(
+        Rc::new(SymbolicValue::BinaryOp(
+            Rc::new(SymbolicValue::ConstantInt(BigInt::zero())),
+            DebuggableExpressionInfixOpcode(ExpressionInfixOpcode::Eq),
+            lessthan_out.clone(),
+        )),
+        DebuggableExpressionInfixOpcode(ExpressionInfixOpcode::BoolAnd),
+        Rc::new(SymbolicValue::AuxBinaryOp(
+            in_0,
+            DebuggableExpressionInfixOpcode(ExpressionInfixOpcode::GreaterEq),
+            in_1,
+        )),
+    );
+    SymbolicValue::BinaryOp(
+        Rc::new(cond_1),
+        DebuggableExpressionInfixOpcode(ExpressionInfixOpcode::BoolOr),
+        Rc::new(cond_0),
+    )
+}
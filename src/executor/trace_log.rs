@@ -0,0 +1,207 @@
+use std::cell::{Cell, RefCell};
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+use log::warn;
+use rustc_hash::FxHashMap;
+use serde_json::{json, Value};
+
+use crate::executor::debug_ast::DebuggableStatement;
+use crate::executor::symbolic_value::{SymbolicName, SymbolicValue};
+use crate::executor::utils::strip_ansi;
+
+/// Returns the name of the kind of `statement`, e.g. `Substitution`.
+pub fn statement_kind(statement: &DebuggableStatement) -> &'static str {
+    match statement {
+        DebuggableStatement::IfThenElse { .. } => "IfThenElse",
+        DebuggableStatement::While { .. } => "While",
+        DebuggableStatement::Return { .. } => "Return",
+        DebuggableStatement::InitializationBlock { .. } => "InitializationBlock",
+        DebuggableStatement::Declaration { .. } => "Declaration",
+        DebuggableStatement::Substitution { .. } => "Substitution",
+        DebuggableStatement::MultSubstitution { .. } => "MultSubstitution",
+        DebuggableStatement::UnderscoreSubstitution { .. } => "UnderscoreSubstitution",
+        DebuggableStatement::ConstraintEquality { .. } => "ConstraintEquality",
+        DebuggableStatement::LogCall { .. } => "LogCall",
+        DebuggableStatement::Block { .. } => "Block",
+        DebuggableStatement::Assert { .. } => "Assert",
+        DebuggableStatement::Ret => "Ret",
+    }
+}
+
+/// A sink streaming the execution path of the symbolic execution as JSON lines, written by
+/// `--trace_jsonl`.
+///
+/// Every executed statement produces a `statement` event, every constraint added to the symbolic
+/// trace or the side constraints produces a `constraint` event right after the event of the
+/// statement that added it, and every variable or signal bound to a new value produces a
+/// `binding` event before the next `statement` or `constraint` event. The events are numbered by
+/// a `step` increasing across the whole execution, including the sub-components and functions:
+///
+/// ```json
+/// {"event":"statement","step":7,"kind":"Substitution","elem_id":12,"owner":"main.c","owner_stack":["main","c"],"template":"IsZero","depth":1}
+/// {"event":"binding","step":8,"name":"main.c.inv","value":"(Div 1 main.c.in)"}
+/// {"event":"constraint","step":9,"store":"side","owner":"main.c","constraint":"(AssignEq main.c.out ...)"}
+/// ```
+///
+/// `reconstruct_state` replays these deltas to recover the state at any step. Only the symbolic
+/// execution of the circuit is logged; the concrete executions of the search run with tracing
+/// turned off.
+pub struct TraceLog {
+    writer: RefCell<BufWriter<File>>,
+    step: Cell<usize>,
+}
+
+impl TraceLog {
+    /// Creates the file at `path`, truncating it if it exists.
+    pub fn create(path: &Path) -> io::Result<Self> {
+        Ok(TraceLog {
+            writer: RefCell::new(BufWriter::new(File::create(path)?)),
+            step: Cell::new(0),
+        })
+    }
+
+    fn write_event(&self, mut event: Value) {
+        event["step"] = json!(self.step.get());
+        self.step.set(self.step.get() + 1);
+        if let Err(e) = writeln!(self.writer.borrow_mut(), "{}", event) {
+            warn!("Unable to write the execution trace: {}", e);
+        }
+    }
+
+    /// Records the execution of `statement` by the component or function whose owner names,
+    /// outermost first, are `owner_stack`.
+    pub fn log_statement(
+        &self,
+        statement: &DebuggableStatement,
+        owner_stack: &[String],
+        template_name: &str,
+        depth: usize,
+    ) {
+        self.write_event(json!({
+            "event": "statement",
+            "kind": statement_kind(statement),
+            "elem_id": statement.get_meta().map(|meta| meta.elem_id),
+            "owner": owner_stack.join("."),
+            "owner_stack": owner_stack,
+            "template": template_name,
+            "depth": depth,
+        }));
+    }
+
+    /// Records a constraint added to `store`, either `trace` or `side`.
+    pub fn log_constraint(
+        &self,
+        store: &str,
+        constraint: &SymbolicValue,
+        owner: &str,
+        id2name: &FxHashMap<usize, String>,
+    ) {
+        self.write_event(json!({
+            "event": "constraint",
+            "store": store,
+            "owner": owner,
+            "constraint": strip_ansi(&constraint.lookup_fmt(id2name)),
+        }));
+    }
+
+    /// Records that the variable or signal `name` is bound to `value`.
+    pub fn log_binding(
+        &self,
+        name: &SymbolicName,
+        value: &SymbolicValue,
+        id2name: &FxHashMap<usize, String>,
+    ) {
+        self.write_event(json!({
+            "event": "binding",
+            "name": strip_ansi(&name.lookup_fmt(id2name)),
+            "value": strip_ansi(&value.lookup_fmt(id2name)),
+        }));
+    }
+
+    /// Writes the buffered events to the file.
+    pub fn flush(&self) -> io::Result<()> {
+        self.writer.borrow_mut().flush()
+    }
+}
+
+/// The state of the symbolic execution at a step of a trace written by `--trace_jsonl`.
+#[derive(Default, Debug)]
+pub struct TraceState {
+    pub step: usize,
+    /// The last `statement` event at or before the step.
+    pub statement: Option<Value>,
+    /// The owner names of the statement, outermost first.
+    pub owner_stack: Vec<String>,
+    /// The value of every variable and signal bound at or before the step, keyed by its
+    /// owner-qualified name.
+    pub bindings: BTreeMap<String, String>,
+    pub trace_constraints: Vec<String>,
+    pub side_constraints: Vec<String>,
+}
+
+impl TraceState {
+    pub fn to_json(&self) -> Value {
+        json!({
+            "step": self.step,
+            "statement": self.statement,
+            "owner_stack": self.owner_stack,
+            "bindings": self.bindings,
+            "trace_constraints": self.trace_constraints,
+            "side_constraints": self.side_constraints,
+        })
+    }
+}
+
+/// Reads the events of a trace written by `--trace_jsonl`.
+pub fn read_trace_log(path: &Path) -> Result<Vec<Value>, String> {
+    fs::read_to_string(path)
+        .map_err(|e| e.to_string())?
+        .lines()
+        .enumerate()
+        .map(|(i, line)| serde_json::from_str(line).map_err(|e| format!("line {}: {}", i + 1, e)))
+        .collect()
+}
+
+/// Reconstructs the state of the symbolic execution at `step` by replaying the events of a trace
+/// up to it. A step past the end of the trace yields the final state.
+pub fn reconstruct_state(events: &[Value], step: usize) -> TraceState {
+    let mut state = TraceState {
+        step: step,
+        ..TraceState::default()
+    };
+    for event in events {
+        if event["step"].as_u64().map_or(true, |s| s as usize > step) {
+            break;
+        }
+        let text = |key: &str| event[key].as_str().unwrap_or_default().to_string();
+        match event["event"].as_str() {
+            Some("statement") => {
+                state.owner_stack = event["owner_stack"]
+                    .as_array()
+                    .map(|stack| {
+                        stack
+                            .iter()
+                            .filter_map(|owner| owner.as_str().map(|s| s.to_string()))
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                state.statement = Some(event.clone());
+            }
+            Some("binding") => {
+                state.bindings.insert(text("name"), text("value"));
+            }
+            Some("constraint") => {
+                if event["store"] == "side" {
+                    state.side_constraints.push(text("constraint"));
+                } else {
+                    state.trace_constraints.push(text("constraint"));
+                }
+            }
+            _ => {}
+        }
+    }
+    state
+}
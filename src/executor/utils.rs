@@ -1,5 +1,5 @@
 use num_bigint_dig::BigInt;
-use num_traits::{One, Signed, Zero};
+use num_traits::{One, Signed, ToPrimitive, Zero};
 use std::ops::{Div, Rem, Sub};
 
 pub fn extended_euclidean<F>(a: F, b: F) -> (F, F, F)
@@ -29,9 +29,36 @@ where
     (r0, s0, t0)
 }
 
+/// Returns the canonical representative of `v` modulo `modulus`, i.e., the value in
+/// `[0, modulus)` congruent to `v`.
+///
+/// `%` on `BigInt` keeps the sign of the dividend, so that a negative constant such as `-3`
+/// stays negative after `-3 % p`. Every field element produced by the evaluator goes through
+/// this function, so that the results of arithmetic never depend on the sign of the operands.
+///
+/// # Examples
+/// ```
+/// use num_bigint_dig::BigInt;
+/// use zkfuzz::executor::utils::modreduce;
+///
+/// let p = BigInt::from(17);
+/// assert_eq!(BigInt::from(-3) % &p, BigInt::from(-3));
+/// assert_eq!(modreduce(&BigInt::from(-3), &p), BigInt::from(14));
+/// assert_eq!(modreduce(&BigInt::from(-37), &p), BigInt::from(14));
+/// assert_eq!(modreduce(&BigInt::from(20), &p), BigInt::from(3));
+/// ```
+pub fn modreduce(v: &BigInt, modulus: &BigInt) -> BigInt {
+    let r = v % modulus;
+    if r.is_negative() {
+        r + modulus
+    } else {
+        r
+    }
+}
+
 pub fn modpow(base: &BigInt, exp: &BigInt, modulus: &BigInt) -> BigInt {
     let mut result = BigInt::from(1);
-    let mut base = base % modulus; // Reduce base mod modulus initially
+    let mut base = modreduce(base, modulus); // Reduce base mod modulus initially
     let mut exp = exp.clone();
 
     while exp > BigInt::from(0) {
@@ -51,26 +78,109 @@ pub fn moddiv(lv: &BigInt, rv: &BigInt, modulus: &BigInt) -> BigInt {
         return BigInt::zero();
     }
 
-    let mut r = modulus.clone();
-    let mut new_r = rv.clone();
-    if r.is_negative() {
-        r += modulus;
-    }
-    if new_r.is_negative() {
-        new_r += modulus;
+    let (_, _, rv_inv) = extended_euclidean(modulus.clone(), modreduce(rv, modulus));
+    modreduce(&(lv * modreduce(&rv_inv, modulus)), modulus)
+}
+
+/// Returns the mask of the bits kept by the bitwise operators of circom, `2^b - 1` where `b` is
+/// the bit length of `prime` (254 for bn128).
+pub fn field_mask(prime: &BigInt) -> BigInt {
+    (BigInt::one() << prime.bits()) - BigInt::one()
+}
+
+/// Shifts `v` in `[0, prime)` by `k <= prime / 2` bits, truncating a left shift to the bits of
+/// `prime` as the witness generator of circom does.
+fn shift_by(v: &BigInt, k: &BigInt, prime: &BigInt, is_left: bool) -> BigInt {
+    match k.to_usize() {
+        Some(k) if k < prime.bits() => {
+            if is_left {
+                modreduce(&((v << k) & field_mask(prime)), prime)
+            } else {
+                v >> k
+            }
+        }
+        _ => BigInt::zero(),
     }
+}
 
-    let (_, _, mut rv_inv) = extended_euclidean(r, new_r);
-    rv_inv %= modulus;
-    if rv_inv.is_negative() {
-        rv_inv += modulus;
+/// Evaluates `v << k` with the semantics of circom.
+///
+/// The operands are reduced to `[0, prime)` first. An amount `k > prime / 2` stands for the
+/// negative amount `k - prime`, so that `v << k` is `v >> (prime - k)`. The shifted value is
+/// truncated to the bits of `prime` before being reduced, and a shift by at least the bit length
+/// of `prime` gives `0`.
+///
+/// # Examples
+/// ```
+/// use num_bigint_dig::BigInt;
+/// use zkfuzz::executor::utils::shift_left;
+///
+/// let p = BigInt::from(17);
+/// assert_eq!(shift_left(&BigInt::from(3), &BigInt::from(2), &p), BigInt::from(12));
+/// // `(3 << 3) & 0b11111 = 24`, reduced to `7`
+/// assert_eq!(shift_left(&BigInt::from(3), &BigInt::from(3), &p), BigInt::from(7));
+/// // `-1 = 16` stands for a right shift by one
+/// assert_eq!(shift_left(&BigInt::from(6), &BigInt::from(-1), &p), BigInt::from(3));
+/// assert_eq!(shift_left(&BigInt::from(6), &BigInt::from(5), &p), BigInt::from(0));
+/// ```
+pub fn shift_left(v: &BigInt, k: &BigInt, prime: &BigInt) -> BigInt {
+    let (v, k) = (modreduce(v, prime), modreduce(k, prime));
+    if k > prime / BigInt::from(2) {
+        shift_by(&v, &(prime - k), prime, false)
+    } else {
+        shift_by(&v, &k, prime, true)
     }
+}
 
-    let mut result = (lv * rv_inv) % modulus;
-    if result.is_negative() {
-        result += modulus;
+/// Evaluates `v >> k` with the semantics of circom, i.e. `v / 2^k` for `k <= prime / 2`, and
+/// `v << (prime - k)` for the larger amounts, which stand for negative ones (see `shift_left`).
+pub fn shift_right(v: &BigInt, k: &BigInt, prime: &BigInt) -> BigInt {
+    let (v, k) = (modreduce(v, prime), modreduce(k, prime));
+    if k > prime / BigInt::from(2) {
+        shift_by(&v, &(prime - k), prime, true)
+    } else {
+        shift_by(&v, &k, prime, false)
     }
-    result
+}
+
+/// Evaluates a bitwise `&`, `|`, or `^` with the semantics of circom: the operator is applied to
+/// the representatives in `[0, prime)` of the operands, and the result, truncated to the bits of
+/// `prime`, is reduced modulo `prime`.
+///
+/// # Examples
+/// ```
+/// use num_bigint_dig::BigInt;
+/// use zkfuzz::executor::utils::bitwise;
+///
+/// let p = BigInt::from(17);
+/// // `-1 = 16 = 0b10000`
+/// assert_eq!(bitwise(&BigInt::from(-1), &BigInt::from(3), &p, |a, b| a & b), BigInt::from(0));
+/// // `0b10000 | 0b00011 = 19`, reduced to `2`
+/// assert_eq!(bitwise(&BigInt::from(-1), &BigInt::from(3), &p, |a, b| a | b), BigInt::from(2));
+/// ```
+pub fn bitwise<F>(lv: &BigInt, rv: &BigInt, prime: &BigInt, op: F) -> BigInt
+where
+    F: Fn(&BigInt, &BigInt) -> BigInt,
+{
+    let result = op(&modreduce(lv, prime), &modreduce(rv, prime)) & field_mask(prime);
+    modreduce(&result, prime)
+}
+
+/// Evaluates `~v` with the semantics of circom: the bits of the representative of `v` in
+/// `[0, prime)` are flipped up to the bit length of `prime`, and the result is reduced modulo
+/// `prime`.
+///
+/// # Examples
+/// ```
+/// use num_bigint_dig::BigInt;
+/// use zkfuzz::executor::utils::complement;
+///
+/// let p = BigInt::from(17);
+/// // `~0b00011 = 0b11100 = 28`, reduced to `11`
+/// assert_eq!(complement(&BigInt::from(3), &p), BigInt::from(11));
+/// ```
+pub fn complement(v: &BigInt, prime: &BigInt) -> BigInt {
+    modreduce(&(field_mask(prime) - modreduce(v, prime)), prime)
 }
 
 /// Returns Some(x) such that x² ≡ n (mod p), or None if no solution exists.
@@ -251,3 +361,22 @@ pub fn generate_cartesian_product_indices(dims: &[usize]) -> Vec<Vec<usize>> {
 pub fn italic(text: &str) -> String {
     format!("\x1b[3m{}\x1b[0m", text)
 }
+
+/// Removes the ANSI escape sequences with which `lookup_fmt` colors the terminal output.
+pub fn strip_ansi(s: &str) -> String {
+    let mut stripped = String::with_capacity(s.len());
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            // Skip `ESC [ ... <letter>`
+            for c in chars.by_ref() {
+                if c.is_ascii_alphabetic() {
+                    break;
+                }
+            }
+        } else {
+            stripped.push(c);
+        }
+    }
+    stripped
+}
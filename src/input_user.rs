@@ -1,6 +1,12 @@
 use std::path::PathBuf;
 
+use clap::ArgMatches;
+
+use crate::manifest_user::ProjectManifest;
+
+#[derive(Clone)]
 pub struct Input {
+    pub subcommand: String,
     pub input_program: PathBuf,
     /*
     pub out_r1cs: PathBuf,
@@ -40,14 +46,94 @@ pub struct Input {
     pub flag_printout_stats_csv: bool,
     pub flag_symbolic_template_params: bool,
     pub flag_save_output: bool,
+    pub flag_check_output_tags: bool,
+    pub flag_check_division_by_zero: bool,
+    pub flag_overflow_audit: bool,
+    pub flag_free_witnesses: bool,
+    pub flag_suggest_repair: bool,
+    pub flag_replay_logs: bool,
+    pub flag_list_detectors: bool,
     pub show_stats_of_ast: bool,
+    pub stats_sort_by: String,
+    pub component_stats_csv: String,
+    pub plonk_gate_widths: String,
+    pub report_html: String,
+    pub findings_stream: String,
+    pub out_dir: String,
+    pub export_constraints: String,
+    pub seed: String,
+    pub link: String,
+    pub load_state: String,
+    pub save_state: String,
+    pub library_output: String,
     pub lessthan_dissabled_flag: bool,
     pub prime: String,
     pub debug_prime: String,
     pub heuristics_range: String,
+    pub heuristics_stages: String,
     pub search_mode: String,
     pub path_to_mutation_setting: String,
     pub path_to_whitelist: String,
+    pub input_spec: String,
+    pub corpus: String,
+    pub input_dir: String,
+    pub output_csv: String,
+    pub timeout_per_file: String,
+    pub output_clusters: String,
+    pub entries: Vec<PathBuf>,
+    pub output_entries: String,
+    pub path_to_summary: String,
+    pub path_to_constraints: String,
+    pub new_input: String,
+    pub host: String,
+    pub port: String,
+    pub target_signal: String,
+    pub constraint_store: String,
+    pub assert_search_iterations: String,
+    pub concolic_seed: String,
+    pub concolic_max_paths: String,
+    pub path_to_wasm: String,
+    pub differential_iterations: String,
+    pub density_samples: String,
+    pub density_sampling: String,
+    pub simplify: String,
+    pub validate_with_circom: String,
+    pub inspect_with_circom: String,
+    pub groth16_zkey: String,
+    pub snarkjs: String,
+    pub max_counterexamples: String,
+    pub auto_time_budget: String,
+    pub max_index_case_splits: String,
+    pub function_summary_capacity: String,
+    pub max_call_depth: String,
+    pub main_params: String,
+    pub detectors: String,
+    pub log_level: String,
+    pub trace_jsonl: String,
+    pub at_step: String,
+    pub ce_format: String,
+    pub harness_lang: String,
+    pub num_cases: String,
+    pub harness_output: String,
+    pub template_timeouts: String,
+    pub compare_prime: String,
+    pub export_cfg: String,
+    pub flag_no_cache: bool,
+    pub cache_dir: String,
+    pub prime_samples: String,
+    pub constraint_format: String,
+    pub line_width: String,
+    pub min_severity: String,
+    pub fail_on: String,
+    pub baseline: String,
+    pub eval_backend: String,
+    pub flag_stdin: bool,
+    pub constraint_index: String,
+    pub flag_side: bool,
+    pub signal: String,
+    pub regression_snapshot: String,
+    pub flag_update_regression_snapshot: bool,
+    pub flag_plain: bool,
 }
 
 /*
@@ -65,8 +151,52 @@ impl Input {
     pub fn new() -> Result<Input, ()> {
         use ansi_term::Colour;
         //use input_processing::SimplificationStyle;
-        let matches = input_processing::view();
-        let input = input_processing::get_input(&matches)?;
+        let app_matches = input_processing::view();
+        let (subcommand, matches) = match app_matches.subcommand() {
+            // `baseline update` is named after both of its subcommands
+            ("baseline", Some(sub_matches)) => match sub_matches.subcommand() {
+                (name, Some(action_matches)) => {
+                    (format!("baseline {}", name), action_matches.clone())
+                }
+                _ => {
+                    return Result::Err(eprintln!(
+                        "{}",
+                        Colour::Red.paint("Missing action of `zkfuzz baseline` (update)")
+                    ))
+                }
+            },
+            (name, Some(sub_matches)) => (name.to_string(), sub_matches.clone()),
+            _ => ("fuzz".to_string(), app_matches.clone()),
+        };
+        let is_search_skipped = matches!(
+            subcommand.as_str(),
+            "exec" | "stats" | "gen-tests" | "show-constraints" | "why" | "explain"
+        );
+        let input_dir = input_processing::get_input_dir(&matches)?;
+        let entries = input_processing::get_entries(&matches)?;
+        let manifest = if subcommand == "run" {
+            Some(input_processing::get_manifest(&matches)?)
+        } else {
+            None
+        };
+        let input = if subcommand == "compile-lib" || subcommand == "regression" {
+            input_processing::get_library_dir(&matches)?
+        } else if input_processing::get_stdin(&matches) {
+            // The circuit is read from stdin, and its results are named after this path
+            PathBuf::from("./stdin.circom")
+        } else if manifest.is_some() && matches.occurrences_of("input") == 0 {
+            input_processing::get_manifest_entry(&manifest.as_ref().unwrap().entry)?
+        } else if subcommand == "serve" {
+            // The circuits are submitted to the server
+            PathBuf::from("./circuit.circom")
+        } else if !entries.is_empty() {
+            // The entries are analysed together, and their results are named after the first one
+            entries[0].clone()
+        } else if input_dir == "none" && !input_processing::get_list_detectors(&matches) {
+            input_processing::get_input(&matches)?
+        } else {
+            PathBuf::from(matches.value_of("input").unwrap())
+        };
         let file_name = input.file_stem().unwrap().to_str().unwrap().to_string();
         //let output_path = input_processing::get_output_path(&matches)?;
 
@@ -80,8 +210,10 @@ impl Input {
         //let output_js_path = Input::build_folder(&output_path, &file_name, JS);
         //let o_style = input_processing::get_simplification_style(&matches)?;
         let link_libraries = input_processing::get_link_libraries(&matches);
-        Result::Ok(Input {
+        let out_dir = input_processing::get_out_dir(&matches)?;
+        let mut input = Input {
             //field: P_BN128,
+            subcommand: subcommand.clone(),
             input_program: input,
             /*
             out_r1cs: Input::build_output(&output_path, &file_name, R1CS),
@@ -123,20 +255,138 @@ impl Input {
             */
             constraint_assert_dissabled_flag: input_processing::get_constraint_assert_dissabled(&matches),
             flag_printout_ast: input_processing::get_ast(&matches),
-            flag_printout_stats: input_processing::get_stats(&matches),
+            flag_printout_stats: input_processing::get_stats(&matches)
+                || (subcommand == "stats"
+                    && !input_processing::get_stats_csv(&matches)
+                    && !input_processing::get_show_stats_of_ast(&matches)),
             flag_printout_stats_csv: input_processing::get_stats_csv(&matches),
             flag_symbolic_template_params: input_processing::get_symbolic_template_params(&matches),
-            flag_save_output: input_processing::get_save_output(&matches),
+            flag_save_output: subcommand == "export" || out_dir != "none" || input_processing::get_save_output(&matches),
+            flag_check_output_tags: input_processing::get_check_output_tags(&matches),
+            flag_check_division_by_zero: input_processing::get_check_division_by_zero(&matches),
+            flag_overflow_audit: input_processing::get_overflow_audit(&matches),
+            flag_free_witnesses: input_processing::get_free_witnesses(&matches),
+            flag_suggest_repair: input_processing::get_suggest_repair(&matches),
+            flag_replay_logs: input_processing::get_replay_logs(&matches),
+            flag_list_detectors: input_processing::get_list_detectors(&matches),
             show_stats_of_ast: input_processing::get_show_stats_of_ast(&matches),
+            stats_sort_by: input_processing::get_stats_sort_by(&matches)?,
+            component_stats_csv: input_processing::get_component_stats_csv(&matches)?,
+            plonk_gate_widths: input_processing::get_plonk_gate_widths(&matches)?,
+            report_html: input_processing::get_report_html(&matches)?,
+            findings_stream: input_processing::get_findings_stream(&matches)?,
+            out_dir: out_dir,
+            export_constraints: input_processing::get_export_constraints(&matches)?,
+            seed: input_processing::get_seed(&matches)?,
+            link: input_processing::get_link(&matches)?,
+            load_state: input_processing::get_load_state(&matches)?,
+            save_state: input_processing::get_save_state(&matches)?,
+            library_output: input_processing::get_library_output(&matches)?,
             lessthan_dissabled_flag: input_processing::get_lessthan_dissabled_flag(&matches),
             prime: input_processing::get_prime(&matches)?,
             debug_prime: input_processing::get_debug_prime(&matches)?,
             heuristics_range: input_processing::get_heuristics_range(&matches)?,
-            search_mode: input_processing::get_search_mode(&matches)?,
+            heuristics_stages: input_processing::get_heuristics_stages(&matches)?,
+            search_mode: if is_search_skipped { String::from("off") } else { input_processing::get_search_mode(&matches)? },
             path_to_mutation_setting: input_processing::get_path_to_mutation_setting(&matches)?,
             path_to_whitelist: input_processing::get_path_to_whitelist(&matches)?,
+            input_spec: input_processing::get_input_spec(&matches)?,
+            corpus: input_processing::get_corpus(&matches)?,
+            input_dir: input_dir,
+            output_csv: input_processing::get_output_csv(&matches)?,
+            timeout_per_file: input_processing::get_timeout_per_file(&matches)?,
+            output_clusters: input_processing::get_output_clusters(&matches)?,
+            entries: entries,
+            output_entries: input_processing::get_output_entries(&matches)?,
+            path_to_summary: input_processing::get_path_to_summary(&matches)?,
+            path_to_constraints: input_processing::get_path_to_constraints(&matches)?,
+            new_input: input_processing::get_new_input(&matches)?,
+            host: input_processing::get_host(&matches)?,
+            port: input_processing::get_port(&matches)?,
+            target_signal: input_processing::get_target_signal(&matches)?,
+            constraint_store: input_processing::get_constraint_store(&matches)?,
+            assert_search_iterations: input_processing::get_assert_search_iterations(&matches)?,
+            concolic_seed: input_processing::get_concolic_seed(&matches)?,
+            concolic_max_paths: input_processing::get_concolic_max_paths(&matches)?,
+            path_to_wasm: input_processing::get_path_to_wasm(&matches)?,
+            differential_iterations: input_processing::get_differential_iterations(&matches)?,
+            density_samples: input_processing::get_density_samples(&matches)?,
+            density_sampling: input_processing::get_density_sampling(&matches)?,
+            simplify: input_processing::get_simplify(&matches)?,
+            validate_with_circom: input_processing::get_validate_with_circom(&matches)?,
+            inspect_with_circom: input_processing::get_inspect_with_circom(&matches)?,
+            groth16_zkey: input_processing::get_groth16_zkey(&matches)?,
+            snarkjs: input_processing::get_snarkjs(&matches)?,
+            max_counterexamples: input_processing::get_max_counterexamples(&matches)?,
+            auto_time_budget: input_processing::get_auto_time_budget(&matches)?,
+            max_index_case_splits: input_processing::get_max_index_case_splits(&matches)?,
+            function_summary_capacity: input_processing::get_function_summary_capacity(&matches)?,
+            max_call_depth: input_processing::get_max_call_depth(&matches)?,
+            main_params: input_processing::get_main_params(&matches)?,
+            detectors: input_processing::get_detectors(&matches)?,
+            log_level: input_processing::get_log_level(&matches)?,
+            trace_jsonl: input_processing::get_trace_jsonl(&matches)?,
+            at_step: input_processing::get_at_step(&matches)?,
+            ce_format: input_processing::get_ce_format(&matches)?,
+            harness_lang: input_processing::get_harness_lang(&matches)?,
+            num_cases: input_processing::get_num_cases(&matches)?,
+            harness_output: input_processing::get_harness_output(&matches)?,
+            template_timeouts: input_processing::get_template_timeouts(&matches)?,
+            compare_prime: input_processing::get_compare_prime(&matches)?,
+            export_cfg: input_processing::get_export_cfg(&matches)?,
+            flag_no_cache: input_processing::get_no_cache(&matches),
+            cache_dir: input_processing::get_cache_dir(&matches)?,
+            prime_samples: input_processing::get_prime_samples(&matches)?,
+            constraint_format: input_processing::get_constraint_format(&matches)?,
+            line_width: input_processing::get_line_width(&matches)?,
+            min_severity: input_processing::get_min_severity(&matches)?,
+            fail_on: input_processing::get_fail_on(&matches)?,
+            baseline: input_processing::get_baseline(&matches)?,
+            eval_backend: input_processing::get_eval_backend(&matches)?,
+            flag_stdin: input_processing::get_stdin(&matches),
+            constraint_index: input_processing::get_constraint_index(&matches)?,
+            flag_side: input_processing::get_side(&matches),
+            signal: input_processing::get_signal(&matches)?,
+            regression_snapshot: input_processing::get_regression_snapshot(&matches)?,
+            flag_update_regression_snapshot: input_processing::get_update_regression_snapshot(&matches),
+            flag_plain: input_processing::get_plain(&matches),
             link_libraries
-        })
+        };
+        if let Some(manifest) = &manifest {
+            input.apply_manifest(manifest, &matches)?;
+        }
+        Result::Ok(input)
+    }
+
+    /// Fills in the options of `zkfuzz run` that are not given on the command line with the
+    /// settings of the project manifest.
+    fn apply_manifest(
+        &mut self,
+        manifest: &ProjectManifest,
+        matches: &ArgMatches,
+    ) -> Result<(), ()> {
+        if matches.occurrences_of("link_libraries") == 0 {
+            self.link_libraries = manifest.include.clone();
+        }
+        if matches.occurrences_of("prime") == 0 {
+            if let Some(prime) = &manifest.prime {
+                self.prime = input_processing::check_prime(prime)?;
+            }
+        }
+        if matches.occurrences_of("search_mode") == 0 {
+            if let Some(search_mode) = &manifest.search_mode {
+                self.search_mode = search_mode.clone();
+            }
+        }
+        if matches.occurrences_of("detectors") == 0 {
+            if let Some(detectors) = &manifest.detectors {
+                self.detectors = detectors.join(",");
+            }
+        }
+        if matches.occurrences_of("template_timeouts") == 0 && !manifest.timeouts.is_empty() {
+            self.template_timeouts = manifest.template_timeouts();
+        }
+        Ok(())
     }
 
     /*
@@ -153,6 +403,13 @@ impl Input {
         file
     }*/
 
+    /// Returns the subcommand zkFuzz was invoked with: `exec`, `fuzz`, `run`, `stats`, `export`,
+    /// `diff`, `trace`, `gen-tests`, `show-constraints`, `why`, `explain`, `baseline update`,
+    /// `serve`, `compile-lib`, or `regression`. Invocations without a subcommand are treated as `fuzz`.
+    pub fn subcommand(&self) -> &str {
+        &self.subcommand
+    }
+
     pub fn get_link_libraries(&self) -> &Vec<PathBuf> {
         &self.link_libraries
     }
@@ -259,6 +516,9 @@ impl Input {
     pub fn heuristics_range(&self) -> String{
         self.heuristics_range.clone()
     }
+    pub fn heuristics_stages(&self) -> String{
+        self.heuristics_stages.clone()
+    }
     pub fn search_mode(&self) -> String{
         self.search_mode.clone()
     }
@@ -268,11 +528,224 @@ impl Input {
     pub fn path_to_whitelist(&self) -> String{
         self.path_to_whitelist.clone()
     }
+    pub fn input_spec(&self) -> String{
+        self.input_spec.clone()
+    }
+    pub fn corpus(&self) -> String{
+        self.corpus.clone()
+    }
+    pub fn input_dir(&self) -> String{
+        self.input_dir.clone()
+    }
+    pub fn output_csv(&self) -> String{
+        self.output_csv.clone()
+    }
+    pub fn timeout_per_file(&self) -> String{
+        self.timeout_per_file.clone()
+    }
+    pub fn output_clusters(&self) -> String{
+        self.output_clusters.clone()
+    }
+    pub fn get_entries(&self) -> &Vec<PathBuf> {
+        &self.entries
+    }
+    pub fn output_entries(&self) -> String{
+        self.output_entries.clone()
+    }
+    pub fn path_to_summary(&self) -> String{
+        self.path_to_summary.clone()
+    }
+    pub fn path_to_constraints(&self) -> String{
+        self.path_to_constraints.clone()
+    }
+    pub fn new_input(&self) -> String{
+        self.new_input.clone()
+    }
+    pub fn host(&self) -> String{
+        self.host.clone()
+    }
+    pub fn port(&self) -> String{
+        self.port.clone()
+    }
+    pub fn target_signal(&self) -> String{
+        self.target_signal.clone()
+    }
+    pub fn constraint_store(&self) -> String{
+        self.constraint_store.clone()
+    }
+    pub fn assert_search_iterations(&self) -> String{
+        self.assert_search_iterations.clone()
+    }
+    pub fn concolic_seed(&self) -> String{
+        self.concolic_seed.clone()
+    }
+    pub fn concolic_max_paths(&self) -> String{
+        self.concolic_max_paths.clone()
+    }
+    pub fn path_to_wasm(&self) -> String{
+        self.path_to_wasm.clone()
+    }
+    pub fn differential_iterations(&self) -> String{
+        self.differential_iterations.clone()
+    }
+    pub fn density_samples(&self) -> String{
+        self.density_samples.clone()
+    }
+    pub fn density_sampling(&self) -> String{
+        self.density_sampling.clone()
+    }
+    pub fn simplify(&self) -> String{
+        self.simplify.clone()
+    }
+    pub fn validate_with_circom(&self) -> String{
+        self.validate_with_circom.clone()
+    }
+    pub fn inspect_with_circom(&self) -> String{
+        self.inspect_with_circom.clone()
+    }
+    pub fn groth16_zkey(&self) -> String{
+        self.groth16_zkey.clone()
+    }
+    pub fn snarkjs(&self) -> String{
+        self.snarkjs.clone()
+    }
+    pub fn max_counterexamples(&self) -> String{
+        self.max_counterexamples.clone()
+    }
+    pub fn auto_time_budget(&self) -> String{
+        self.auto_time_budget.clone()
+    }
+    pub fn max_index_case_splits(&self) -> String{
+        self.max_index_case_splits.clone()
+    }
+    pub fn function_summary_capacity(&self) -> String{
+        self.function_summary_capacity.clone()
+    }
+    pub fn max_call_depth(&self) -> String{
+        self.max_call_depth.clone()
+    }
+    pub fn main_params(&self) -> String{
+        self.main_params.clone()
+    }
+    pub fn ce_format(&self) -> String{
+        self.ce_format.clone()
+    }
+    pub fn stats_sort_by(&self) -> String{
+        self.stats_sort_by.clone()
+    }
+    pub fn component_stats_csv(&self) -> String{
+        self.component_stats_csv.clone()
+    }
+    pub fn plonk_gate_widths(&self) -> Vec<usize> {
+        self.plonk_gate_widths.split(',').map(|w| w.trim().parse().unwrap()).collect()
+    }
+    pub fn report_html(&self) -> String{
+        self.report_html.clone()
+    }
+    pub fn findings_stream(&self) -> String{
+        self.findings_stream.clone()
+    }
+    pub fn out_dir(&self) -> String{
+        self.out_dir.clone()
+    }
+    pub fn export_constraints(&self) -> String{
+        self.export_constraints.clone()
+    }
+    pub fn detectors(&self) -> String{
+        self.detectors.clone()
+    }
+    pub fn log_level(&self) -> String{
+        self.log_level.clone()
+    }
+    pub fn trace_jsonl(&self) -> String{
+        self.trace_jsonl.clone()
+    }
+    pub fn at_step(&self) -> String{
+        self.at_step.clone()
+    }
+    pub fn seed(&self) -> String{
+        self.seed.clone()
+    }
+    pub fn link(&self) -> String{
+        self.link.clone()
+    }
+    pub fn load_state(&self) -> String{
+        self.load_state.clone()
+    }
+    pub fn save_state(&self) -> String{
+        self.save_state.clone()
+    }
+    pub fn library_output(&self) -> String{
+        self.library_output.clone()
+    }
+    pub fn harness_lang(&self) -> String{
+        self.harness_lang.clone()
+    }
+    pub fn num_cases(&self) -> String{
+        self.num_cases.clone()
+    }
+    pub fn harness_output(&self) -> String{
+        self.harness_output.clone()
+    }
+    pub fn template_timeouts(&self) -> String{
+        self.template_timeouts.clone()
+    }
+    pub fn compare_prime(&self) -> String{
+        self.compare_prime.clone()
+    }
+    pub fn prime_samples(&self) -> String{
+        self.prime_samples.clone()
+    }
+    pub fn export_cfg(&self) -> String{
+        self.export_cfg.clone()
+    }
+    pub fn cache_dir(&self) -> String{
+        self.cache_dir.clone()
+    }
+    pub fn constraint_format(&self) -> String{
+        self.constraint_format.clone()
+    }
+    pub fn line_width(&self) -> String{
+        self.line_width.clone()
+    }
+    pub fn min_severity(&self) -> String{
+        self.min_severity.clone()
+    }
+    pub fn fail_on(&self) -> String{
+        self.fail_on.clone()
+    }
+    pub fn baseline(&self) -> String{
+        self.baseline.clone()
+    }
+    pub fn eval_backend(&self) -> String{
+        self.eval_backend.clone()
+    }
+    pub fn constraint_index(&self) -> String{
+        self.constraint_index.clone()
+    }
+    pub fn signal(&self) -> String{
+        self.signal.clone()
+    }
+    pub fn regression_snapshot(&self) -> String{
+        self.regression_snapshot.clone()
+    }
 }
 mod input_processing {
     use ansi_term::Colour;
-    use clap::{App, Arg, ArgMatches};
+    use clap::{App, Arg, ArgMatches, SubCommand};
     use std::path::{Path, PathBuf};
+    use std::str::FromStr;
+    use crate::manifest_user::{
+        load_manifest, parse_template_timeouts, ProjectManifest, MANIFEST_FILE_NAME,
+    };
+    use crate::cache_user::DEFAULT_CACHE_DIR;
+    use crate::mutator::batch_evaluation::EvaluationBackend;
+    use crate::mutator::detector::DEFAULT_DETECTORS;
+    use crate::mutator::prime_consistency::prime_modulus;
+    use crate::mutator::severity::Severity;
+    use crate::mutator::staged_search::{parse_stages, DEFAULT_HEURISTICS_STAGES};
+    use crate::mutator::test_harness::HarnessLanguage;
+    use crate::stats::pretty_print::PrettyFormat;
     use crate::VERSION;
 
     pub fn get_input(matches: &ArgMatches) -> Result<PathBuf, ()> {
@@ -369,6 +842,34 @@ mod input_processing {
         matches.is_present("save_output")
     }
 
+    pub fn get_check_output_tags(matches: &ArgMatches) -> bool {
+        matches.is_present("check_output_tags")
+    }
+
+    pub fn get_check_division_by_zero(matches: &ArgMatches) -> bool {
+        matches.is_present("check_division_by_zero")
+    }
+
+    pub fn get_overflow_audit(matches: &ArgMatches) -> bool {
+        matches.is_present("overflow_audit")
+    }
+
+    pub fn get_free_witnesses(matches: &ArgMatches) -> bool {
+        matches.is_present("free_witnesses")
+    }
+
+    pub fn get_suggest_repair(matches: &ArgMatches) -> bool {
+        matches.is_present("suggest_repair")
+    }
+
+    pub fn get_replay_logs(matches: &ArgMatches) -> bool {
+        matches.is_present("replay_logs")
+    }
+
+    pub fn get_list_detectors(matches: &ArgMatches) -> bool {
+        matches.is_present("list_detectors")
+    }
+
     pub fn get_show_stats_of_ast(matches: &ArgMatches) -> bool {
         matches.is_present("show_stats_of_ast")
     }
@@ -408,28 +909,27 @@ mod input_processing {
     pub fn get_prime(matches: &ArgMatches) -> Result<String, ()> {
         
         match matches.is_present("prime"){
-            true => 
-               {
-                   let prime_value = matches.value_of("prime").unwrap();
-                   if prime_value == "bn128"
-                      || prime_value == "bls12381"
-                      || prime_value == "goldilocks"
-                      || prime_value == "grumpkin"
-                      || prime_value == "pallas"
-                      || prime_value == "vesta"
-                      || prime_value == "secq256r1"
-                      {
-                        Ok(String::from(matches.value_of("prime").unwrap()))
-                    }
-                    else{
-                        Result::Err(eprintln!("{}", Colour::Red.paint("invalid prime number")))
-                    }
-               }
-               
+            true => check_prime(matches.value_of("prime").unwrap()),
             false => Ok(String::from("bn128")),
         }
     }
 
+    pub fn check_prime(prime_value: &str) -> Result<String, ()> {
+        if prime_value == "bn128"
+           || prime_value == "bls12381"
+           || prime_value == "goldilocks"
+           || prime_value == "grumpkin"
+           || prime_value == "pallas"
+           || prime_value == "vesta"
+           || prime_value == "secq256r1"
+           {
+             Ok(String::from(prime_value))
+         }
+         else{
+             Result::Err(eprintln!("{}", Colour::Red.paint("invalid prime number")))
+         }
+    }
+
     pub fn get_debug_prime(matches: &ArgMatches) -> Result<String, ()> {
         match matches.is_present("debug_prime") {
             true => Ok(String::from(matches.value_of("debug_prime").unwrap())),
@@ -444,6 +944,16 @@ mod input_processing {
         }
     }
 
+    pub fn get_heuristics_stages(matches: &ArgMatches) -> Result<String, ()> {
+        match matches.value_of("heuristics_stages") {
+            Some(stages) => match parse_stages(stages) {
+                Ok(_) => Ok(String::from(stages)),
+                Err(e) => Result::Err(eprintln!("{}", Colour::Red.paint(format!("invalid heuristics stages: {}", e)))),
+            },
+            None => Ok(String::from(DEFAULT_HEURISTICS_STAGES))
+        }
+    }
+
     pub fn get_search_mode(matches: &ArgMatches) -> Result<String, ()> {
         match matches.is_present("search_mode") {
             true => Ok(String::from(matches.value_of("search_mode").unwrap())),
@@ -465,270 +975,1605 @@ mod input_processing {
         }
     }
 
-    pub fn view() -> ArgMatches<'static> {
-        App::new("ZK Circuit Fuzzer")
-            .version(VERSION)
-            .author("Anonymous Authors")
-            .about("Fuzzer designed to help you identify vulnerabilities in zero-knowledge proof circuits")
-            .arg(
-                Arg::with_name("input")
-                    .multiple(false)
-                    .default_value("./circuit.circom")
-                    .help("Path to a circuit with a main component"),
-            )
-            /*
-            .arg(
-                Arg::with_name("no_simplification")
-                    .long("O0")
-                    .hidden(false)
-                    .takes_value(false)
-                    .help("No simplification is applied")
-                    .display_order(420)
-            )
-            .arg(
-                Arg::with_name("reduced_simplification")
-                    .long("O1")
-                    .hidden(false)
-                    .takes_value(false)
-                    .help("Only applies signal to signal and signal to constant simplification. This is the default option")
-                    .display_order(460)
-            )
-            .arg(
-                Arg::with_name("full_simplification")
-                    .long("O2")
-                    .takes_value(false)
-                    .hidden(false)
-                    .help("Full constraint simplification")
-                    .display_order(480)
-            )
-            .arg(
-                Arg::with_name("simplification_rounds")
-                    .long("O2round")
-                    .takes_value(true)
-                    .hidden(false)
-                    .help("Maximum number of rounds of the simplification process")
-                    .display_order(500)
-            )
-            .arg(
-                Arg::with_name("output")
-                    .short("o")
-                    .long("output")
-                    .takes_value(true)
-                    .default_value(".")
-                    .display_order(1)
-                    .help("Path to the directory where the output will be written"),
-            )
-            .arg(
-                Arg::with_name("print_json_c")
-                    .long("json")
-                    .takes_value(false)
-                    .display_order(120)
-                    .help("Outputs the constraints in json format"),
-            )
-            .arg(
-                Arg::with_name("print_ir")
-                    .long("irout")
-                    .takes_value(false)
-                    .hidden(true)
-                    .display_order(360)
-                    .help("Outputs the low-level IR of the given circom program"),
-            )
-            .arg(
-                Arg::with_name("inspect_constraints")
-                    .long("inspect")
-                    .takes_value(false)
-                    .display_order(801)
-                    .help("Does an additional check over the constraints produced"),
-            )
-            .arg(
-                Arg::with_name("print_json_sub")
-                    .long("simplification_substitution")
-                    .takes_value(false)
-                    .display_order(980)
-                    .help("Outputs the substitution applied in the simplification phase in json format"),
-            )
-            .arg(
-                Arg::with_name("print_sym")
-                    .long("sym")
-                    .takes_value(false)
-                    .display_order(60)
-                    .help("Outputs witness in sym format"),
-            )
-            .arg(
-                Arg::with_name("print_r1cs")
-                    .long("r1cs")
-                    .takes_value(false)
-                    .display_order(30)
-                    .help("Outputs the constraints in r1cs format"),
-            )
-            .arg(
-                Arg::with_name("print_wasm")
-                    .long("wasm")
-                    .takes_value(false)
-                    .display_order(90)
-                    .help("Compiles the circuit to wasm"),
-            )
-            .arg(
-                Arg::with_name("print_wat")
-                    .long("wat")
-                    .takes_value(false)
-                    .display_order(120)
-                    .help("Compiles the circuit to wat"),
-            )*/
-            .arg(
-                Arg::with_name("link_libraries")
-                .short("l")
-                .takes_value(true)
-                .multiple(true)
-                .number_of_values(1)   
-                .display_order(100) 
-                .help("Adds directory to library search path"),
-            )
-            .arg (
-                Arg::with_name("prime")
-                    .short("prime")
-                    .long("prime")
-                    .takes_value(true)
-                    .default_value("bn128")
-                    .display_order(300)
-                    .help("To choose the prime number to use to generate the circuit. Receives the name of the curve (bn128, bls12381, goldilocks, grumpkin, pallas, vesta, secq256r1)"),
-            )
-            .arg(
-                Arg::with_name("constraint_assert_dissabled")
-                    .long("constraint_assert_dissabled")
-                    .takes_value(false)
-                    .hidden(false)
-                    .display_order(810)
-                    .help("Does not add asserts in the generated code for === constraint equalities"),
-            )
-            .arg (
-                Arg::with_name("debug_prime")
-                    .long("debug_prime")
-                    .takes_value(true)
-                    .default_value("21888242871839275222246405745257275088548364400416034343698204186575808495617")
-                    .display_order(310)
-                    .help("(zkFuzz) Prime number for zkFuzz"),
-            )
-            .arg (
-                Arg::with_name("search_mode")
-                    .long("search_mode")
-                    .takes_value(true)
-                    .default_value("ga")
-                    .display_order(320)
-                    .help("(zkFuzz) Search mode to find the counter example that shows the given circuit is not well-constrained"),
-            )
-            .arg (
-                Arg::with_name("heuristics_range")
-                    .long("heuristics_range")
-                    .takes_value(true)
-                    .default_value("100")
-                    .display_order(330)
-                    .help("(zkFuzz) Heuristics range for zkFuzz"),
-            )
-            .arg (
-                Arg::with_name("path_to_mutation_setting")
-                    .long("path_to_mutation_setting")
-                    .takes_value(true)
-                    .default_value("none")
-                    .display_order(340)
-                    .help("(zkFuzz) Path to the setting file for Mutation Testing"),
-            )
-            .arg (
-                Arg::with_name("path_to_whitelist")
-                    .long("path_to_whitelist")
-                    .takes_value(true)
-                    .default_value("none")
-                    .display_order(350)
-                    .help("(zkFuzz) Path to the white-lists file"),
-            )
-            .arg(
-                Arg::with_name("lessthan_dissabled")
-                    .long("lessthan_dissabled")
-                    .takes_value(false)
-                    .display_order(820)
-                    .help("(zkFuzz) Does not detect overflow erros due to LessThan template"),
-            )
-            /*
-            .arg(
-                Arg::with_name("print_c")
-                    .long("c")
-                    .short("c")
-                    .takes_value(false)
-                    .display_order(150)
-                    .help("Compiles the circuit to c"),
-            )
-            .arg(
-                Arg::with_name("parallel_simplification")
-                    .long("parallel")
-                    .takes_value(false)
-                    .hidden(true)
-                    .display_order(180)
-                    .help("Runs non-linear simplification in parallel"),
-            )
-            .arg(
-                Arg::with_name("main_inputs_log")
-                    .long("inputs")
-                    .takes_value(false)
-                    .hidden(true)
-                    .display_order(210)
-                    .help("Produces a log_inputs.txt file"),
-            )
-            .arg(
-                Arg::with_name("flag_verbose")
-                    .long("verbose")
-                    .takes_value(false)
-                    .display_order(800)
-                    .help("Shows logs during compilation"),
-            )
-            .arg(
-                Arg::with_name("flag_old_heuristics")
-                    .long("use_old_simplification_heuristics")
-                    .takes_value(false)
-                    .display_order(980)
-                    .help("Applies the old version of the heuristics when performing linear simplification"),
-            )
-            */
-            .arg(
-                Arg::with_name("print_ast")
-                    .long("print_ast")
-                    .takes_value(false)
-                    .display_order(830)
-                    .help("(zkFuzz) Prints AST"),
-            )
-            .arg(
-                Arg::with_name("show_stats_of_ast")
-                    .long("show_stats_of_ast")
-                    .takes_value(false)
-                    .display_order(840)
-                    .help("(zkFuzz) Prints the basic stats of AST"),
-            )
-            .arg(
-                Arg::with_name("print_stats")
-                    .long("print_stats")
-                    .takes_value(false)
-                    .display_order(850)
-                    .help("(zkFuzz) Prints the stats of constraints"),
-            )
-            .arg(
-                Arg::with_name("print_stats_csv")
-                    .long("print_stats_csv")
-                    .takes_value(false)
-                    .display_order(860)
-                    .help("(zkFuzz) Prints the stats of constraints in CSV format"),
-            )
-            .arg(
-                Arg::with_name("symbolic_template_params")
-                    .long("symbolic_template_params")
-                    .takes_value(false)
-                    .display_order(870)
-                    .help("(zkFuzz) Treats the template parameters of the main template as symbolic values"),
-            )
-            .arg(
-                Arg::with_name("save_output")
-                    .long("save_output")
-                    .takes_value(false)
-                    .display_order(880)
-                    .help("(zkFuzz) Save the output when the counterexample is found"),
+    pub fn get_input_spec(matches: &ArgMatches) -> Result<String, ()> {
+        match matches.is_present("input_spec") {
+            true => {
+                let route = matches.value_of("input_spec").unwrap();
+                if route == "none" || Path::new(route).is_file() {
+                    Ok(String::from(route))
+                } else {
+                    Result::Err(eprintln!("{}", Colour::Red.paint("Input specification does not exist: ".to_owned() + route)))
+                }
+            }
+            false => Ok(String::from("none"))
+        }
+    }
+
+    pub fn get_corpus(matches: &ArgMatches) -> Result<String, ()> {
+        match matches.is_present("corpus") {
+            true => {
+                let route = matches.value_of("corpus").unwrap();
+                if route == "none" || !Path::new(route).is_file() {
+                    Ok(String::from(route))
+                } else {
+                    Result::Err(eprintln!("{}", Colour::Red.paint("Corpus is not a directory: ".to_owned() + route)))
+                }
+            }
+            false => Ok(String::from("none"))
+        }
+    }
+
+    pub fn get_input_dir(matches: &ArgMatches) -> Result<String, ()> {
+        match matches.is_present("input_dir") {
+            true => {
+                let route = Path::new(matches.value_of("input_dir").unwrap());
+                if route.is_dir() {
+                    Ok(String::from(matches.value_of("input_dir").unwrap()))
+                } else {
+                    Result::Err(eprintln!("{}", Colour::Red.paint("Input directory does not exist: ".to_owned() + matches.value_of("input_dir").unwrap())))
+                }
+            }
+            false => Ok(String::from("none"))
+        }
+    }
+
+    pub fn get_output_csv(matches: &ArgMatches) -> Result<String, ()> {
+        match matches.is_present("output_csv") {
+            true => Ok(String::from(matches.value_of("output_csv").unwrap())),
+            false => Ok(String::from("results.csv"))
+        }
+    }
+
+    pub fn get_timeout_per_file(matches: &ArgMatches) -> Result<String, ()> {
+        match matches.is_present("timeout_per_file") {
+            true => {
+                let timeout = matches.value_of("timeout_per_file").unwrap();
+                if timeout.parse::<u64>().is_ok() {
+                    Ok(String::from(timeout))
+                } else {
+                    Result::Err(eprintln!("{}", Colour::Red.paint("invalid timeout (expected seconds)")))
+                }
+            }
+            false => Ok(String::from("3600"))
+        }
+    }
+
+    pub fn get_output_clusters(matches: &ArgMatches) -> Result<String, ()> {
+        match matches.is_present("output_clusters") {
+            true => Ok(String::from(matches.value_of("output_clusters").unwrap())),
+            false => Ok(String::from("none"))
+        }
+    }
+
+    pub fn get_entries(matches: &ArgMatches) -> Result<Vec<PathBuf>, ()> {
+        let mut entries = Vec::new();
+        if let Some(paths) = matches.value_of("entry") {
+            for path in paths.split(',').map(str::trim).filter(|path| !path.is_empty()) {
+                let route = Path::new(path);
+                if route.is_file() {
+                    entries.push(route.to_path_buf());
+                } else {
+                    return Result::Err(eprintln!("{}", Colour::Red.paint("Entry does not exist: ".to_owned() + path)));
+                }
+            }
+        }
+        Ok(entries)
+    }
+
+    pub fn get_output_entries(matches: &ArgMatches) -> Result<String, ()> {
+        match matches.is_present("output_entries") {
+            true => Ok(String::from(matches.value_of("output_entries").unwrap())),
+            false => Ok(String::from("entries.json"))
+        }
+    }
+
+    pub fn get_path_to_summary(matches: &ArgMatches) -> Result<String, ()> {
+        match matches.is_present("path_to_summary") {
+            true => Ok(String::from(matches.value_of("path_to_summary").unwrap())),
+            false => Ok(String::from("none"))
+        }
+    }
+
+    pub fn get_path_to_constraints(matches: &ArgMatches) -> Result<String, ()> {
+        match matches.is_present("path_to_constraints") {
+            true => Ok(String::from(matches.value_of("path_to_constraints").unwrap())),
+            false => Ok(String::from("none"))
+        }
+    }
+
+    pub fn get_new_input(matches: &ArgMatches) -> Result<String, ()> {
+        match matches.is_present("new_input") {
+            true => {
+                let route = matches.value_of("new_input").unwrap();
+                if Path::new(route).is_file() {
+                    Ok(String::from(route))
+                } else {
+                    Result::Err(eprintln!("{}", Colour::Red.paint("Input file does not exist: ".to_owned() + route)))
+                }
+            }
+            false => Ok(String::from("none"))
+        }
+    }
+
+    pub fn get_host(matches: &ArgMatches) -> Result<String, ()> {
+        match matches.is_present("host") {
+            true => Ok(String::from(matches.value_of("host").unwrap())),
+            false => Ok(String::from("127.0.0.1"))
+        }
+    }
+
+    pub fn get_port(matches: &ArgMatches) -> Result<String, ()> {
+        match matches.is_present("port") {
+            true => {
+                let port = matches.value_of("port").unwrap();
+                if port.parse::<u16>().is_ok() {
+                    Ok(String::from(port))
+                } else {
+                    Result::Err(eprintln!("{}", Colour::Red.paint("invalid port")))
+                }
+            }
+            false => Ok(String::from("4242"))
+        }
+    }
+
+    pub fn get_target_signal(matches: &ArgMatches) -> Result<String, ()> {
+        match matches.is_present("target_signal") {
+            true => Ok(String::from(matches.value_of("target_signal").unwrap())),
+            false => Ok(String::from("none"))
+        }
+    }
+
+    pub fn get_constraint_store(matches: &ArgMatches) -> Result<String, ()> {
+        match matches.value_of("constraint_store") {
+            Some(store) if store == "memory" || store == "disk" => Ok(String::from(store)),
+            Some(_) => Result::Err(eprintln!("{}", Colour::Red.paint("invalid constraint store (expected memory or disk)"))),
+            None => Ok(String::from("memory"))
+        }
+    }
+
+    pub fn get_detectors(matches: &ArgMatches) -> Result<String, ()> {
+        match matches.value_of("detectors") {
+            Some(detectors) => Ok(String::from(detectors)),
+            None => Ok(String::from(DEFAULT_DETECTORS))
+        }
+    }
+
+    pub fn get_stats_sort_by(matches: &ArgMatches) -> Result<String, ()> {
+        match matches.value_of("stats_sort_by") {
+            Some(key) if ["constraints", "nonlinear", "depth", "component"].contains(&key) => Ok(String::from(key)),
+            Some(_) => Result::Err(eprintln!("{}", Colour::Red.paint("invalid sort key (expected constraints, nonlinear, depth, or component)"))),
+            None => Ok(String::from("constraints"))
+        }
+    }
+
+    pub fn get_plonk_gate_widths(matches: &ArgMatches) -> Result<String, ()> {
+        match matches.value_of("plonk_gate_widths") {
+            Some(widths) => {
+                // A gate has to add at least two wires
+                if widths.split(',').all(|w| w.trim().parse::<usize>().map_or(false, |w| w >= 2)) {
+                    Ok(String::from(widths))
+                } else {
+                    Result::Err(eprintln!("{}", Colour::Red.paint("invalid Plonk gate widths (expected comma-separated integers of at least 2)")))
+                }
+            }
+            None => Ok(String::from("3"))
+        }
+    }
+
+    pub fn get_library_dir(matches: &ArgMatches) -> Result<PathBuf, ()> {
+        let route = Path::new(matches.value_of("input").unwrap()).to_path_buf();
+        if route.is_dir() {
+            Result::Ok(route)
+        } else {
+            Result::Err(eprintln!("{}", Colour::Red.paint("Library directory does not exist: ".to_owned() + &route.to_string_lossy())))
+        }
+    }
+
+    pub fn get_link(matches: &ArgMatches) -> Result<String, ()> {
+        match matches.is_present("link") {
+            true => {
+                let route = matches.value_of("link").unwrap();
+                if route == "none" || Path::new(route).is_file() {
+                    Ok(String::from(route))
+                } else {
+                    Result::Err(eprintln!("{}", Colour::Red.paint("Library archive does not exist: ".to_owned() + route)))
+                }
+            }
+            false => Ok(String::from("none"))
+        }
+    }
+
+    pub fn get_load_state(matches: &ArgMatches) -> Result<String, ()> {
+        match matches.is_present("load_state") {
+            true => {
+                let route = matches.value_of("load_state").unwrap();
+                if route == "none" || Path::new(route).is_file() {
+                    Ok(String::from(route))
+                } else {
+                    Result::Err(eprintln!("{}", Colour::Red.paint("Snapshot of the symbolic execution does not exist: ".to_owned() + route)))
+                }
+            }
+            false => Ok(String::from("none"))
+        }
+    }
+
+    pub fn get_save_state(matches: &ArgMatches) -> Result<String, ()> {
+        match matches.is_present("save_state") {
+            true => Ok(String::from(matches.value_of("save_state").unwrap())),
+            false => Ok(String::from("none"))
+        }
+    }
+
+    pub fn get_library_output(matches: &ArgMatches) -> Result<String, ()> {
+        match matches.is_present("library_output") {
+            true => Ok(String::from(matches.value_of("library_output").unwrap())),
+            false => Ok(String::from("library.zklib"))
+        }
+    }
+
+    pub fn get_seed(matches: &ArgMatches) -> Result<String, ()> {
+        match matches.value_of("seed") {
+            Some(seed) if seed.parse::<u64>().is_ok() => Ok(String::from(seed)),
+            Some(_) => Result::Err(eprintln!("{}", Colour::Red.paint("invalid seed (expected a non-negative integer)"))),
+            None => Ok(String::from("0"))
+        }
+    }
+
+    pub fn get_component_stats_csv(matches: &ArgMatches) -> Result<String, ()> {
+        match matches.is_present("component_stats_csv") {
+            true => Ok(String::from(matches.value_of("component_stats_csv").unwrap())),
+            false => Ok(String::from("none"))
+        }
+    }
+
+    pub fn get_report_html(matches: &ArgMatches) -> Result<String, ()> {
+        match matches.is_present("report_html") {
+            true => Ok(String::from(matches.value_of("report_html").unwrap())),
+            false => Ok(String::from("none"))
+        }
+    }
+
+    pub fn get_findings_stream(matches: &ArgMatches) -> Result<String, ()> {
+        match matches.is_present("findings_stream") {
+            true => Ok(String::from(matches.value_of("findings_stream").unwrap())),
+            false => Ok(String::from("none"))
+        }
+    }
+
+    pub fn get_out_dir(matches: &ArgMatches) -> Result<String, ()> {
+        match matches.is_present("out_dir") {
+            true => Ok(String::from(matches.value_of("out_dir").unwrap())),
+            false => Ok(String::from("none"))
+        }
+    }
+
+    pub fn get_export_constraints(matches: &ArgMatches) -> Result<String, ()> {
+        match matches.is_present("export_constraints") {
+            true => Ok(String::from(matches.value_of("export_constraints").unwrap())),
+            false => Ok(String::from("none"))
+        }
+    }
+
+    pub fn get_assert_search_iterations(matches: &ArgMatches) -> Result<String, ()> {
+        match matches.is_present("assert_search_iterations") {
+            true => {
+                let iterations = matches.value_of("assert_search_iterations").unwrap();
+                if iterations.parse::<usize>().is_ok() {
+                    Ok(String::from(iterations))
+                } else {
+                    Result::Err(eprintln!("{}", Colour::Red.paint("invalid number of iterations for the assertion search")))
+                }
+            }
+            false => Ok(String::from("100000"))
+        }
+    }
+
+    pub fn get_concolic_seed(matches: &ArgMatches) -> Result<String, ()> {
+        match matches.is_present("concolic_seed") {
+            true => {
+                let route = matches.value_of("concolic_seed").unwrap();
+                if route == "none" || Path::new(route).is_file() {
+                    Ok(String::from(route))
+                } else {
+                    Result::Err(eprintln!("{}", Colour::Red.paint("Seed input of the concolic search does not exist: ".to_owned() + route)))
+                }
+            }
+            false => Ok(String::from("none"))
+        }
+    }
+
+    pub fn get_concolic_max_paths(matches: &ArgMatches) -> Result<String, ()> {
+        match matches.is_present("concolic_max_paths") {
+            true => {
+                let max_paths = matches.value_of("concolic_max_paths").unwrap();
+                match max_paths.parse::<usize>() {
+                    Ok(n) if n > 0 => Ok(String::from(max_paths)),
+                    _ => Result::Err(eprintln!("{}", Colour::Red.paint("invalid maximum number of paths for the concolic search"))),
+                }
+            }
+            false => Ok(String::from("16"))
+        }
+    }
+
+    pub fn get_path_to_wasm(matches: &ArgMatches) -> Result<String, ()> {
+        match matches.is_present("path_to_wasm") {
+            true => Ok(String::from(matches.value_of("path_to_wasm").unwrap())),
+            false => Ok(String::from("none"))
+        }
+    }
+
+    pub fn get_differential_iterations(matches: &ArgMatches) -> Result<String, ()> {
+        match matches.is_present("differential_iterations") {
+            true => {
+                let iterations = matches.value_of("differential_iterations").unwrap();
+                if iterations.parse::<usize>().is_ok() {
+                    Ok(String::from(iterations))
+                } else {
+                    Result::Err(eprintln!("{}", Colour::Red.paint("invalid number of iterations for the differential testing")))
+                }
+            }
+            false => Ok(String::from("100"))
+        }
+    }
+
+    pub fn get_density_samples(matches: &ArgMatches) -> Result<String, ()> {
+        match matches.is_present("density_samples") {
+            true => {
+                let samples = matches.value_of("density_samples").unwrap();
+                if samples.parse::<usize>().is_ok() {
+                    Ok(String::from(samples))
+                } else {
+                    Result::Err(eprintln!("{}", Colour::Red.paint("invalid number of samples for the violation density")))
+                }
+            }
+            false => Ok(String::from("0"))
+        }
+    }
+
+    pub fn get_density_sampling(matches: &ArgMatches) -> Result<String, ()> {
+        match matches.is_present("density_sampling") {
+            true => {
+                let sampling = matches.value_of("density_sampling").unwrap();
+                match sampling {
+                    "uniform" | "ranges" => Ok(String::from(sampling)),
+                    _ => Result::Err(eprintln!("{}", Colour::Red.paint("invalid density sampling (must be one of uniform, ranges)"))),
+                }
+            }
+            false => Ok(String::from("ranges"))
+        }
+    }
+
+    pub fn get_simplify(matches: &ArgMatches) -> Result<String, ()> {
+        match matches.is_present("simplify") {
+            true => {
+                let level = matches.value_of("simplify").unwrap();
+                match level {
+                    "off" | "basic" | "aggressive" => Ok(String::from(level)),
+                    _ => Result::Err(eprintln!("{}", Colour::Red.paint("invalid simplification level (must be one of off, basic, aggressive)"))),
+                }
+            }
+            false => Ok(String::from("off"))
+        }
+    }
+
+    pub fn get_validate_with_circom(matches: &ArgMatches) -> Result<String, ()> {
+        match matches.is_present("validate_with_circom") {
+            true => Ok(String::from(matches.value_of("validate_with_circom").unwrap())),
+            false => Ok(String::from("none"))
+        }
+    }
+
+    pub fn get_inspect_with_circom(matches: &ArgMatches) -> Result<String, ()> {
+        match matches.is_present("inspect_with_circom") {
+            true => Ok(String::from(matches.value_of("inspect_with_circom").unwrap())),
+            false => Ok(String::from("none"))
+        }
+    }
+
+    pub fn get_groth16_zkey(matches: &ArgMatches) -> Result<String, ()> {
+        match matches.is_present("groth16_zkey") {
+            true => {
+                let route = matches.value_of("groth16_zkey").unwrap();
+                if route == "none" || Path::new(route).is_file() {
+                    Ok(String::from(route))
+                } else {
+                    Result::Err(eprintln!("{}", Colour::Red.paint("Proving key does not exist: ".to_owned() + route)))
+                }
+            }
+            false => Ok(String::from("none"))
+        }
+    }
+
+    pub fn get_snarkjs(matches: &ArgMatches) -> Result<String, ()> {
+        match matches.is_present("snarkjs") {
+            true => Ok(String::from(matches.value_of("snarkjs").unwrap())),
+            false => Ok(String::from("snarkjs"))
+        }
+    }
+
+    pub fn get_max_counterexamples(matches: &ArgMatches) -> Result<String, ()> {
+        match matches.is_present("max_counterexamples") {
+            true => {
+                let max = matches.value_of("max_counterexamples").unwrap();
+                if max.parse::<usize>().map_or(false, |n| n > 0) {
+                    Ok(String::from(max))
+                } else {
+                    Result::Err(eprintln!("{}", Colour::Red.paint("invalid maximum number of counterexamples (must be a positive integer)")))
+                }
+            }
+            false => Ok(String::from("1"))
+        }
+    }
+
+    pub fn get_auto_time_budget(matches: &ArgMatches) -> Result<String, ()> {
+        match matches.is_present("auto_time_budget") {
+            true => {
+                let budget = matches.value_of("auto_time_budget").unwrap();
+                if budget.parse::<u64>().map_or(false, |n| n > 0) {
+                    Ok(String::from(budget))
+                } else {
+                    Result::Err(eprintln!("{}", Colour::Red.paint("invalid time budget (expected a positive number of seconds)")))
+                }
+            }
+            false => Ok(String::from("300"))
+        }
+    }
+
+    pub fn get_ce_format(matches: &ArgMatches) -> Result<String, ()> {
+        match matches.is_present("ce_format") {
+            true => {
+                let format = matches.value_of("ce_format").unwrap();
+                match format {
+                    "decimal" | "hex" | "bits" | "bytes_le" | "bytes_be" => Ok(String::from(format)),
+                    _ => Result::Err(eprintln!("{}", Colour::Red.paint("invalid counterexample format (must be one of decimal, hex, bits, bytes_le, bytes_be)"))),
+                }
+            }
+            false => Ok(String::from("decimal"))
+        }
+    }
+
+    pub fn get_max_index_case_splits(matches: &ArgMatches) -> Result<String, ()> {
+        match matches.is_present("max_index_case_splits") {
+            true => {
+                let max = matches.value_of("max_index_case_splits").unwrap();
+                if max.parse::<usize>().is_ok() {
+                    Ok(String::from(max))
+                } else {
+                    Result::Err(eprintln!("{}", Colour::Red.paint("invalid maximum number of cases for symbolic array indices")))
+                }
+            }
+            false => Ok(String::from("32"))
+        }
+    }
+
+    pub fn get_function_summary_capacity(matches: &ArgMatches) -> Result<String, ()> {
+        match matches.is_present("function_summary_capacity") {
+            true => {
+                let capacity = matches.value_of("function_summary_capacity").unwrap();
+                if capacity.parse::<usize>().is_ok() {
+                    Ok(String::from(capacity))
+                } else {
+                    Result::Err(eprintln!("{}", Colour::Red.paint("invalid capacity of the function summaries")))
+                }
+            }
+            false => Ok(String::from("1024"))
+        }
+    }
+
+    pub fn get_max_call_depth(matches: &ArgMatches) -> Result<String, ()> {
+        match matches.is_present("max_call_depth") {
+            true => {
+                let depth = matches.value_of("max_call_depth").unwrap();
+                if depth.parse::<usize>().is_ok() {
+                    Ok(String::from(depth))
+                } else {
+                    Result::Err(eprintln!("{}", Colour::Red.paint("invalid maximum depth of nested calls")))
+                }
+            }
+            false => Ok(String::from("64"))
+        }
+    }
+
+    pub fn get_main_params(matches: &ArgMatches) -> Result<String, ()> {
+        match matches.is_present("main_params") {
+            true => Ok(String::from(matches.value_of("main_params").unwrap())),
+            false => Ok(String::from("none"))
+        }
+    }
+
+    pub fn get_log_level(matches: &ArgMatches) -> Result<String, ()> {
+        match matches.is_present("log_level") {
+            true => {
+                let spec = matches.value_of("log_level").unwrap();
+                // Each directive is either a level or `<module>=<level>`
+                let is_valid = spec == "none"
+                    || spec.split(',').map(str::trim).all(|directive| {
+                        let level = directive.rsplit('=').next().unwrap();
+                        log::LevelFilter::from_str(level).is_ok()
+                    });
+                if is_valid {
+                    Ok(String::from(spec))
+                } else {
+                    Result::Err(eprintln!("{}", Colour::Red.paint("invalid log level (expected e.g. `info` or `warn,executor=trace`)")))
+                }
+            }
+            false => Ok(String::from("none"))
+        }
+    }
+
+    pub fn get_trace_jsonl(matches: &ArgMatches) -> Result<String, ()> {
+        match matches.is_present("trace_jsonl") {
+            true => Ok(String::from(matches.value_of("trace_jsonl").unwrap())),
+            false => Ok(String::from("none"))
+        }
+    }
+
+    pub fn get_at_step(matches: &ArgMatches) -> Result<String, ()> {
+        match matches.is_present("at_step") {
+            true => {
+                let step = matches.value_of("at_step").unwrap();
+                if step == "none" || step.parse::<usize>().is_ok() {
+                    Ok(String::from(step))
+                } else {
+                    Result::Err(eprintln!("{}", Colour::Red.paint("invalid step of the execution trace")))
+                }
+            }
+            false => Ok(String::from("none"))
+        }
+    }
+
+    pub fn get_harness_lang(matches: &ArgMatches) -> Result<String, ()> {
+        match matches.is_present("harness_lang") {
+            true => {
+                let lang = matches.value_of("harness_lang").unwrap();
+                match HarnessLanguage::from_str(lang) {
+                    Ok(_) => Ok(String::from(lang)),
+                    Err(e) => Result::Err(eprintln!("{}", Colour::Red.paint(e))),
+                }
+            }
+            false => Ok(String::from("js"))
+        }
+    }
+
+    pub fn get_num_cases(matches: &ArgMatches) -> Result<String, ()> {
+        match matches.is_present("num_cases") {
+            true => {
+                let num_cases = matches.value_of("num_cases").unwrap();
+                match num_cases.parse::<usize>() {
+                    Ok(n) if n > 0 => Ok(String::from(num_cases)),
+                    _ => Result::Err(eprintln!("{}", Colour::Red.paint("invalid number of test cases (expected a positive integer)"))),
+                }
+            }
+            false => Ok(String::from("10"))
+        }
+    }
+
+    pub fn get_template_timeouts(matches: &ArgMatches) -> Result<String, ()> {
+        match matches.is_present("template_timeouts") {
+            true => {
+                let timeouts = matches.value_of("template_timeouts").unwrap();
+                match parse_template_timeouts(timeouts) {
+                    Ok(_) => Ok(String::from(timeouts)),
+                    Err(e) => Result::Err(eprintln!("{}", Colour::Red.paint(format!("invalid template timeouts: {}", e)))),
+                }
+            }
+            false => Ok(String::from("none"))
+        }
+    }
+
+    pub fn get_compare_prime(matches: &ArgMatches) -> Result<String, ()> {
+        match matches.is_present("compare_prime") {
+            true => {
+                let prime = matches.value_of("compare_prime").unwrap();
+                match prime == "none" || prime_modulus(prime).is_some() {
+                    true => Ok(String::from(prime)),
+                    false => Result::Err(eprintln!("{}", Colour::Red.paint(format!("invalid prime to compare against: {} (expected a curve accepted by --prime or a decimal prime)", prime)))),
+                }
+            }
+            false => Ok(String::from("none"))
+        }
+    }
+
+    pub fn get_prime_samples(matches: &ArgMatches) -> Result<String, ()> {
+        match matches.is_present("prime_samples") {
+            true => {
+                let samples = matches.value_of("prime_samples").unwrap();
+                match samples.parse::<usize>() {
+                    Ok(n) if n > 0 => Ok(String::from(samples)),
+                    _ => Result::Err(eprintln!("{}", Colour::Red.paint("invalid number of samples (expected a positive integer)"))),
+                }
+            }
+            false => Ok(String::from("1000"))
+        }
+    }
+
+    pub fn get_export_cfg(matches: &ArgMatches) -> Result<String, ()> {
+        match matches.is_present("export_cfg") {
+            true => Ok(String::from(matches.value_of("export_cfg").unwrap())),
+            false => Ok(String::from("none"))
+        }
+    }
+
+    pub fn get_no_cache(matches: &ArgMatches) -> bool {
+        matches.is_present("no_cache")
+    }
+
+    pub fn get_cache_dir(matches: &ArgMatches) -> Result<String, ()> {
+        match matches.is_present("cache_dir") {
+            true => Ok(String::from(matches.value_of("cache_dir").unwrap())),
+            false => Ok(String::from(DEFAULT_CACHE_DIR))
+        }
+    }
+
+    pub fn get_manifest(matches: &ArgMatches) -> Result<ProjectManifest, ()> {
+        let route = matches.value_of("manifest").unwrap_or(MANIFEST_FILE_NAME);
+        load_manifest(Path::new(route)).map_err(|e| {
+            eprintln!("{}", Colour::Red.paint(format!("Unable to load the project manifest {}: {}", route, e)))
+        })
+    }
+
+    pub fn get_manifest_entry(entry: &Path) -> Result<PathBuf, ()> {
+        if entry.is_file() {
+            Result::Ok(entry.to_path_buf())
+        } else {
+            Result::Err(eprintln!("{}", Colour::Red.paint("Entry of the project manifest does not exist: ".to_owned() + &entry.to_string_lossy())))
+        }
+    }
+
+    pub fn get_constraint_format(matches: &ArgMatches) -> Result<String, ()> {
+        match matches.is_present("constraint_format") {
+            true => {
+                let format = matches.value_of("constraint_format").unwrap();
+                match PrettyFormat::from_str(format) {
+                    Ok(_) => Ok(String::from(format)),
+                    Err(e) => Result::Err(eprintln!("{}", Colour::Red.paint(e))),
+                }
+            }
+            false => Ok(String::from("text"))
+        }
+    }
+
+    pub fn get_line_width(matches: &ArgMatches) -> Result<String, ()> {
+        match matches.is_present("line_width") {
+            true => {
+                let line_width = matches.value_of("line_width").unwrap();
+                match line_width.parse::<usize>() {
+                    Ok(_) => Ok(String::from(line_width)),
+                    _ => Result::Err(eprintln!("{}", Colour::Red.paint("invalid line width (expected a non-negative integer)"))),
+                }
+            }
+            false => Ok(String::from("100"))
+        }
+    }
+
+    pub fn get_min_severity(matches: &ArgMatches) -> Result<String, ()> {
+        match matches.is_present("min_severity") {
+            true => {
+                let severity = matches.value_of("min_severity").unwrap();
+                match Severity::from_str(severity) {
+                    Ok(severity) => Ok(severity.to_string()),
+                    Err(e) => Result::Err(eprintln!("{}", Colour::Red.paint(e))),
+                }
+            }
+            false => Ok(String::from("info"))
+        }
+    }
+
+    pub fn get_stdin(matches: &ArgMatches) -> bool {
+        matches.is_present("stdin")
+    }
+
+    pub fn get_constraint_index(matches: &ArgMatches) -> Result<String, ()> {
+        match matches.is_present("constraint_index") {
+            true => {
+                let index = matches.value_of("constraint_index").unwrap();
+                match index.parse::<usize>() {
+                    Ok(_) => Ok(String::from(index)),
+                    _ => Result::Err(eprintln!("{}", Colour::Red.paint("invalid constraint index (expected a non-negative integer)"))),
+                }
+            }
+            false => Ok(String::from("none"))
+        }
+    }
+
+    pub fn get_side(matches: &ArgMatches) -> bool {
+        matches.is_present("side")
+    }
+
+    pub fn get_signal(matches: &ArgMatches) -> Result<String, ()> {
+        match matches.is_present("signal") {
+            true => Ok(String::from(matches.value_of("signal").unwrap())),
+            false => Ok(String::from("none"))
+        }
+    }
+
+    pub fn get_regression_snapshot(matches: &ArgMatches) -> Result<String, ()> {
+        match matches.is_present("regression_snapshot") {
+            true => Ok(String::from(matches.value_of("regression_snapshot").unwrap())),
+            false => Ok(String::from("regression_snapshot.json"))
+        }
+    }
+
+    pub fn get_update_regression_snapshot(matches: &ArgMatches) -> bool {
+        matches.is_present("update_regression_snapshot")
+    }
+
+    pub fn get_plain(matches: &ArgMatches) -> bool {
+        matches.is_present("plain")
+    }
+
+    pub fn get_fail_on(matches: &ArgMatches) -> Result<String, ()> {
+        match matches.is_present("fail_on") {
+            true => {
+                let severity = matches.value_of("fail_on").unwrap();
+                if severity == "none" {
+                    return Ok(String::from("none"));
+                }
+                match Severity::from_str(severity) {
+                    Ok(severity) => Ok(severity.to_string()),
+                    Err(e) => Result::Err(eprintln!("{}", Colour::Red.paint(e))),
+                }
+            }
+            false => Ok(String::from("none"))
+        }
+    }
+
+    pub fn get_baseline(matches: &ArgMatches) -> Result<String, ()> {
+        match matches.is_present("baseline") {
+            true => Ok(String::from(matches.value_of("baseline").unwrap())),
+            false => Ok(String::from("none"))
+        }
+    }
+
+    pub fn get_eval_backend(matches: &ArgMatches) -> Result<String, ()> {
+        match matches.is_present("eval_backend") {
+            true => {
+                let backend = matches.value_of("eval_backend").unwrap();
+                match EvaluationBackend::from_str(backend) {
+                    Ok(backend) if backend.is_available() => Ok(backend.to_string()),
+                    Ok(backend) => Result::Err(eprintln!("{}", Colour::Red.paint(format!("the {} evaluation backend is unavailable (build zkFuzz with --features {})", backend, backend)))),
+                    Err(e) => Result::Err(eprintln!("{}", Colour::Red.paint(e))),
+                }
+            }
+            false => Ok(String::from("cpu"))
+        }
+    }
+
+    pub fn get_harness_output(matches: &ArgMatches) -> Result<String, ()> {
+        match matches.is_present("harness_output") {
+            true => Ok(String::from(matches.value_of("harness_output").unwrap())),
+            false => Ok(String::from("none"))
+        }
+    }
+
+    /// Arguments selecting the circuit to analyse.
+    fn input_args() -> Vec<Arg<'static, 'static>> {
+        vec![
+            Arg::with_name("input")
+                .multiple(false)
+                .default_value("./circuit.circom")
+                .help("Path to a circuit with a main component"),
+            Arg::with_name("link_libraries")
+                .short("l")
+                .takes_value(true)
+                .multiple(true)
+                .number_of_values(1)
+                .display_order(100)
+                .help("Adds directory to library search path"),
+            Arg::with_name("stdin")
+                .long("stdin")
+                .takes_value(false)
+                .display_order(101)
+                .help("(zkFuzz) Reads the circuit from stdin instead of the input file, resolving its includes against the current directory and -l"),
+            Arg::with_name("link")
+                .long("link")
+                .takes_value(true)
+                .default_value("none")
+                .display_order(110)
+                .help("(zkFuzz) Path to a library archive built by `zkfuzz compile-lib`, whose templates and functions are loaded instead of parsing the files it was compiled from"),
+            Arg::with_name("load_state")
+                .long("load_state")
+                .takes_value(true)
+                .default_value("none")
+                .display_order(120)
+                .help("(zkFuzz) Path to a snapshot written by --save_state, whose trace/side constraints are loaded instead of executing the circuit again"),
+            Arg::with_name("save_state")
+                .long("save_state")
+                .takes_value(true)
+                .default_value("none")
+                .display_order(121)
+                .help("(zkFuzz) Path to save a snapshot of the symbolic execution, which can be loaded by later runs with --load_state"),
+        ]
+    }
+
+    /// Arguments of `compile-lib`.
+    fn library_args() -> Vec<Arg<'static, 'static>> {
+        vec![
+            Arg::with_name("input")
+                .multiple(false)
+                .required(true)
+                .help("Path to the directory of the library (e.g. node_modules/circomlib/circuits)"),
+            Arg::with_name("link_libraries")
+                .short("l")
+                .takes_value(true)
+                .multiple(true)
+                .number_of_values(1)
+                .display_order(100)
+                .help("Adds directory to library search path"),
+            Arg::with_name("library_output")
+                .short("o")
+                .long("output")
+                .takes_value(true)
+                .default_value("library.zklib")
+                .display_order(110)
+                .help("(zkFuzz) Path to the library archive to write"),
+        ]
+    }
+
+    /// Arguments of `regression`.
+    fn regression_args() -> Vec<Arg<'static, 'static>> {
+        vec![
+            Arg::with_name("input")
+                .multiple(false)
+                .required(true)
+                .help("Path to the directory of the corpus (e.g. a checkout of circomlib/circuits)"),
+            Arg::with_name("link_libraries")
+                .short("l")
+                .takes_value(true)
+                .multiple(true)
+                .number_of_values(1)
+                .display_order(100)
+                .help("Adds directory to library search path"),
+            Arg::with_name("prime")
+                .short("prime")
+                .long("prime")
+                .takes_value(true)
+                .default_value("bn128")
+                .display_order(300)
+                .help("To choose the prime number to use to execute the templates. Receives the name of the curve (bn128, bls12381, goldilocks, grumpkin, pallas, vesta, secq256r1)"),
+            Arg::with_name("regression_snapshot")
+                .long("regression_snapshot")
+                .takes_value(true)
+                .default_value("regression_snapshot.json")
+                .display_order(110)
+                .help("(zkFuzz) Path to the snapshot of the expected outcome and numbers of constraints of every template of the corpus"),
+            Arg::with_name("update_regression_snapshot")
+                .long("update_regression_snapshot")
+                .takes_value(false)
+                .display_order(111)
+                .help("(zkFuzz) Records the outcomes of this run in --regression_snapshot instead of comparing them with it"),
+        ]
+    }
+
+    /// Arguments controlling the parsing and the symbolic execution of the circuit.
+    fn execution_args() -> Vec<Arg<'static, 'static>> {
+        vec![
+            Arg::with_name("prime")
+                .short("prime")
+                .long("prime")
+                .takes_value(true)
+                .default_value("bn128")
+                .display_order(300)
+                .help("To choose the prime number to use to generate the circuit. Receives the name of the curve (bn128, bls12381, goldilocks, grumpkin, pallas, vesta, secq256r1)"),
+            Arg::with_name("debug_prime")
+                .long("debug_prime")
+                .takes_value(true)
+                .default_value("21888242871839275222246405745257275088548364400416034343698204186575808495617")
+                .display_order(310)
+                .help("(zkFuzz) Prime number for zkFuzz"),
+            Arg::with_name("constraint_assert_dissabled")
+                .long("constraint_assert_dissabled")
+                .takes_value(false)
+                .hidden(false)
+                .display_order(810)
+                .help("Does not add asserts in the generated code for === constraint equalities"),
+            Arg::with_name("lessthan_dissabled")
+                .long("lessthan_dissabled")
+                .takes_value(false)
+                .display_order(820)
+                .help("(zkFuzz) Does not detect overflow erros due to LessThan template"),
+            Arg::with_name("symbolic_template_params")
+                .long("symbolic_template_params")
+                .takes_value(false)
+                .display_order(870)
+                .help("(zkFuzz) Treats the template parameters of the main template as symbolic values"),
+            Arg::with_name("function_summary_capacity")
+                .long("function_summary_capacity")
+                .takes_value(true)
+                .default_value("1024")
+                .display_order(871)
+                .help("(zkFuzz) Maximum number of function calls whose results are memoized (0 disables the memoization)"),
+            Arg::with_name("main_params")
+                .long("main_params")
+                .takes_value(true)
+                .default_value("none")
+                .display_order(872)
+                .help("(zkFuzz) Path to a JSON object overriding the arguments of the main component by parameter name, e.g. {\"n\": 8, \"coeffs\": [1, 2]} (-: read from stdin)"),
+            Arg::with_name("max_call_depth")
+                .long("max_call_depth")
+                .takes_value(true)
+                .default_value("64")
+                .display_order(873)
+                .help("(zkFuzz) Maximum number of nested template instantiations and function calls; deeper or cyclic calls are reported instead of executed"),
+            Arg::with_name("max_index_case_splits")
+                .long("max_index_case_splits")
+                .takes_value(true)
+                .default_value("32")
+                .display_order(356)
+                .help("(zkFuzz) Maximum number of cases an array access with symbolic indices is split into (0 disables the splitting)"),
+            Arg::with_name("constraint_store")
+                .long("constraint_store")
+                .takes_value(true)
+                .default_value("memory")
+                .display_order(357)
+                .help("(zkFuzz) Where to keep the trace/side constraints: memory or disk"),
+            Arg::with_name("simplify")
+                .long("simplify")
+                .takes_value(true)
+                .default_value("off")
+                .display_order(357)
+                .help("(zkFuzz) How much the constraints are simplified algebraically before the search and the export: off, basic (constant folding, x * 1, x + 0, and double negations), or aggressive (also flattens sums and products and combines like terms modulo p)"),
+            Arg::with_name("print_ast")
+                .long("print_ast")
+                .takes_value(false)
+                .display_order(830)
+                .help("(zkFuzz) Prints AST"),
+            Arg::with_name("log_level")
+                .long("log_level")
+                .takes_value(true)
+                .default_value("none")
+                .display_order(895)
+                .help("(zkFuzz) Log levels per module, e.g. `warn,executor=trace,mutator=info` (none: use RUST_LOG)"),
+            Arg::with_name("trace_jsonl")
+                .long("trace_jsonl")
+                .takes_value(true)
+                .default_value("none")
+                .display_order(896)
+                .help("(zkFuzz) Path to stream the executed statements and the constraints they add as JSON lines"),
+        ]
+    }
+
+    /// Arguments controlling the search for counterexamples.
+    fn search_args() -> Vec<Arg<'static, 'static>> {
+        vec![
+            Arg::with_name("search_mode")
+                .long("search_mode")
+                .takes_value(true)
+                .default_value("ga")
+                .display_order(320)
+                .help("(zkFuzz) Search mode to find the counter example that shows the given circuit is not well-constrained (quick, full, heuristics, assert, assign, ga, concolic, or auto)"),
+            Arg::with_name("heuristics_range")
+                .long("heuristics_range")
+                .takes_value(true)
+                .default_value("100")
+                .display_order(330)
+                .help("(zkFuzz) Largest magnitude searched by --search_mode heuristics for the signals whose range is not inferred from the constraints"),
+            Arg::with_name("heuristics_stages")
+                .long("heuristics_stages")
+                .takes_value(true)
+                .default_value(DEFAULT_HEURISTICS_STAGES)
+                .display_order(335)
+                .help("(zkFuzz) Stages of --search_mode heuristics, run in order, each optionally limited to a number of seconds (e.g. propagate:10,targeted:60,full)"),
+            Arg::with_name("path_to_mutation_setting")
+                .long("path_to_mutation_setting")
+                .visible_alias("mutation_setting")
+                .takes_value(true)
+                .default_value("none")
+                .display_order(340)
+                .help("(zkFuzz) Path to the setting file for Mutation Testing"),
+            Arg::with_name("path_to_whitelist")
+                .long("path_to_whitelist")
+                .visible_alias("whitelist")
+                .takes_value(true)
+                .default_value("none")
+                .display_order(350)
+                .help("(zkFuzz) Path to the white-lists file"),
+            Arg::with_name("input_spec")
+                .long("input_spec")
+                .takes_value(true)
+                .default_value("none")
+                .display_order(351)
+                .help("(zkFuzz) Path to a TOML file declaring the domain of each input (ranges, values, fixed values, or \"boolean\") that the search respects"),
+            Arg::with_name("corpus")
+                .long("corpus")
+                .takes_value(true)
+                .default_value("none")
+                .display_order(352)
+                .help("(zkFuzz) Directory of the inputs kept across runs (counterexamples, near-violations, and coverage-increasing inputs), which seed the genetic search of later runs"),
+            Arg::with_name("target_signal")
+                .long("target_signal")
+                .takes_value(true)
+                .default_value("none")
+                .display_order(355)
+                .help("(zkFuzz) Restricts the search to the constraints relevant to the given signal (e.g. main.out[3])"),
+            Arg::with_name("assert_search_iterations")
+                .long("assert_search_iterations")
+                .takes_value(true)
+                .default_value("100000")
+                .display_order(358)
+                .help("(zkFuzz) Maximum number of inputs executed by --search_mode assert, --search_mode assign, --search_mode concolic, and --check_division_by_zero"),
+            Arg::with_name("concolic_seed")
+                .long("concolic_seed")
+                .takes_value(true)
+                .default_value("none")
+                .display_order(358)
+                .help("(zkFuzz) Path to the input.json that --search_mode concolic starts from (all inputs are 0 if none)"),
+            Arg::with_name("concolic_max_paths")
+                .long("concolic_max_paths")
+                .takes_value(true)
+                .default_value("16")
+                .display_order(358)
+                .help("(zkFuzz) Maximum number of paths explored by --search_mode concolic"),
+            Arg::with_name("path_to_wasm")
+                .long("path_to_wasm")
+                .takes_value(true)
+                .default_value("none")
+                .display_order(359)
+                .help("(zkFuzz) Path to the WASM witness calculator of the circuit (circom --wasm) to differentially test zkFuzz's executor against"),
+            Arg::with_name("differential_iterations")
+                .long("differential_iterations")
+                .takes_value(true)
+                .default_value("100")
+                .display_order(359)
+                .help("(zkFuzz) Number of random inputs used with --path_to_wasm"),
+            Arg::with_name("density_samples")
+                .long("density_samples")
+                .takes_value(true)
+                .default_value("0")
+                .display_order(359)
+                .help("(zkFuzz) Number of random assignments sampled to estimate the fraction accepted by the side constraints but diverging from the trace, with a 95% confidence interval (0 disables the estimation)"),
+            Arg::with_name("density_sampling")
+                .long("density_sampling")
+                .takes_value(true)
+                .default_value("ranges")
+                .display_order(359)
+                .help("(zkFuzz) How the assignments of --density_samples are drawn: uniform (whole field) or ranges (within the widths inferred from the constraints)"),
+            Arg::with_name("validate_with_circom")
+                .long("validate_with_circom")
+                .takes_value(true)
+                .default_value("none")
+                .display_order(359)
+                .help("(zkFuzz) Path to the circom binary used to compile the circuit and label the counterexample CONFIRMED or UNCONFIRMED"),
+            Arg::with_name("inspect_with_circom")
+                .long("inspect_with_circom")
+                .takes_value(true)
+                .default_value("none")
+                .display_order(359)
+                .help("(zkFuzz) Path to the circom binary whose --inspect warnings are cross-referenced with the findings of zkFuzz"),
+            Arg::with_name("groth16_zkey")
+                .long("groth16_zkey")
+                .takes_value(true)
+                .default_value("none")
+                .display_order(359)
+                .help("(zkFuzz) Path to the Groth16 proving key of the circuit compiled with `circom --O0`, used to prove and verify the witness of each counterexample CONFIRMED by --validate_with_circom with snarkjs"),
+            Arg::with_name("snarkjs")
+                .long("snarkjs")
+                .takes_value(true)
+                .default_value("snarkjs")
+                .display_order(359)
+                .help("(zkFuzz) Path to the snarkjs binary used with --groth16_zkey"),
+            Arg::with_name("max_counterexamples")
+                .long("max_counterexamples")
+                .takes_value(true)
+                .default_value("1")
+                .display_order(359)
+                .help("(zkFuzz) Keep searching until this many distinct counterexamples are found (--search_mode ga)"),
+            Arg::with_name("auto_time_budget")
+                .long("auto_time_budget")
+                .takes_value(true)
+                .default_value("300")
+                .display_order(359)
+                .help("(zkFuzz) Time budget in seconds shared by the strategies of --search_mode auto"),
+            Arg::with_name("template_timeouts")
+                .long("template_timeouts")
+                .takes_value(true)
+                .default_value("none")
+                .display_order(359)
+                .help("(zkFuzz) Time limits in seconds of the search (--search_mode quick, full, ga, concolic, or auto) keyed by main template, e.g. Main:600,MerkleProof:120"),
+            Arg::with_name("seed")
+                .long("seed")
+                .takes_value(true)
+                .default_value("0")
+                .display_order(359)
+                .help("(zkFuzz) Seed of every random choice of the search, reported in the output to replay the run (0 draws a seed at random)"),
+            Arg::with_name("save_output")
+                .long("save_output")
+                .takes_value(false)
+                .display_order(880)
+                .help("(zkFuzz) Save the output when the counterexample is found"),
+            Arg::with_name("ce_format")
+                .long("ce_format")
+                .takes_value(true)
+                .default_value("decimal")
+                .display_order(880)
+                .help("(zkFuzz) Format of the values in the grouped assignment of the saved counterexample: decimal, hex, bits, bytes_le, or bytes_be"),
+            Arg::with_name("out_dir")
+                .long("out_dir")
+                .takes_value(true)
+                .default_value("none")
+                .display_order(880)
+                .help("(zkFuzz) Directory to save the counterexamples to (implies --save_output), named <circuit>_<detector or search mode>_<index>, along with <circuit>_manifest.json listing the files written by the run. The counterexamples are saved next to the input if none"),
+            Arg::with_name("report_html")
+                .long("report_html")
+                .takes_value(true)
+                .default_value("none")
+                .display_order(881)
+                .help("(zkFuzz) Path to save a self-contained HTML report of the statistics, findings, counterexamples, and symbolic trace"),
+            Arg::with_name("findings_stream")
+                .long("findings_stream")
+                .takes_value(true)
+                .default_value("none")
+                .display_order(881)
+                .help("(zkFuzz) Path to stream the findings and counterexamples as JSON lines as soon as they are found, flushed after each line"),
+            Arg::with_name("export_constraints")
+                .long("export_constraints")
+                .takes_value(true)
+                .default_value("none")
+                .display_order(882)
+                .help("(zkFuzz) Path to export the side constraints over the prime field, as an SMT-LIB 2 under-constraint query for Picus/QED2-style checking (.smt2) or as JSON (otherwise)"),
+            Arg::with_name("check_output_tags")
+                .long("check_output_tags")
+                .takes_value(false)
+                .display_order(890)
+                .help("(zkFuzz) Reports tagged outputs (e.g. {binary}) that do not satisfy their tags"),
+            Arg::with_name("check_division_by_zero")
+                .long("check_division_by_zero")
+                .takes_value(false)
+                .display_order(891)
+                .help("(zkFuzz) Searches for inputs whose witness generation divides by zero and reports them as counterexamples"),
+            Arg::with_name("overflow_audit")
+                .long("overflow_audit")
+                .takes_value(false)
+                .display_order(892)
+                .help("(zkFuzz) Reports integer operations whose operands may exceed the prime before reduction"),
+            Arg::with_name("free_witnesses")
+                .long("free_witnesses")
+                .takes_value(false)
+                .display_order(892)
+                .help("(zkFuzz) Lets the genetic search choose the signals assigned with <-- in every component, as a malicious prover controlling the whole witness"),
+            Arg::with_name("compare_prime")
+                .long("compare_prime")
+                .takes_value(true)
+                .default_value("none")
+                .display_order(898)
+                .help("(zkFuzz) Curve (e.g. goldilocks) or decimal prime under which the constraints are re-executed to report the constraints whose truth value on small witnesses depends on the prime"),
+            Arg::with_name("prime_samples")
+                .long("prime_samples")
+                .takes_value(true)
+                .default_value("1000")
+                .display_order(899)
+                .help("(zkFuzz) Number of small inputs drawn by --compare_prime"),
+            Arg::with_name("no_cache")
+                .long("no-cache")
+                .takes_value(false)
+                .display_order(900)
+                .help("(zkFuzz) Analyses the circuit even if the result of the same analysis is cached"),
+            Arg::with_name("cache_dir")
+                .long("cache_dir")
+                .takes_value(true)
+                .default_value(DEFAULT_CACHE_DIR)
+                .display_order(901)
+                .help("(zkFuzz) Directory of the results cached by the preprocessed circuit and the options of the analysis"),
+            Arg::with_name("min_severity")
+                .long("min_severity")
+                .takes_value(true)
+                .default_value("info")
+                .display_order(902)
+                .help("(zkFuzz) Minimum severity (info, low, medium, high, or critical) of the findings of the detectors reported in the output, the summary, and the HTML report"),
+            Arg::with_name("fail_on")
+                .long("fail_on")
+                .takes_value(true)
+                .default_value("none")
+                .display_order(903)
+                .help("(zkFuzz) Minimum severity of the findings that make the run exit with the code of the most severe finding (2 for info to 6 for critical), or none"),
+            Arg::with_name("baseline")
+                .long("baseline")
+                .takes_value(true)
+                .default_value("none")
+                .display_order(903)
+                .help("(zkFuzz) Path to the baseline of accepted findings written by `zkfuzz baseline update`; only the other findings are reported and decide the exit code"),
+            Arg::with_name("eval_backend")
+                .long("eval_backend")
+                .takes_value(true)
+                .default_value("cpu")
+                .display_order(904)
+                .help("(zkFuzz) Device evaluating the candidate assignments of the quick and full search modes (cpu or gpu); gpu evaluates them in batches and requires the gpu feature"),
+            Arg::with_name("suggest_repair")
+                .long("suggest_repair")
+                .takes_value(false)
+                .display_order(897)
+                .help("(zkFuzz) Suggests side constraints that eliminate a non-deterministic counterexample"),
+            Arg::with_name("replay_logs")
+                .long("replay_logs")
+                .takes_value(false)
+                .display_order(898)
+                .help("(zkFuzz) Replays the counterexample and prints the output of its log() calls with their source locations"),
+            Arg::with_name("detectors")
+                .long("detectors")
+                .takes_value(true)
+                .default_value(DEFAULT_DETECTORS)
+                .display_order(893)
+                .help("(zkFuzz) Comma-separated detectors run before the search (e.g. unused_outputs,component_boundaries,custom:<name>), or all/none"),
+            Arg::with_name("list_detectors")
+                .long("list_detectors")
+                .takes_value(false)
+                .display_order(894)
+                .help("(zkFuzz) Lists the available detectors and exits"),
+            Arg::with_name("path_to_summary")
+                .long("path_to_summary")
+                .takes_value(true)
+                .hidden(true)
+                .help("(zkFuzz) Path where the summary of the run is written as JSON (used internally by --input_dir)"),
+            Arg::with_name("path_to_constraints")
+                .long("path_to_constraints")
+                .takes_value(true)
+                .hidden(true)
+                .help("(zkFuzz) Path where the trace/side constraints are written as JSON (used internally by serve)"),
+        ]
+    }
+
+    /// Arguments of the batch mode.
+    fn batch_args() -> Vec<Arg<'static, 'static>> {
+        vec![
+            Arg::with_name("input_dir")
+                .long("input_dir")
+                .takes_value(true)
+                .display_order(360)
+                .help("(zkFuzz) Path to a directory of circuits. Every .circom file found under it is analysed in turn"),
+            Arg::with_name("output_csv")
+                .long("output_csv")
+                .takes_value(true)
+                .default_value("results.csv")
+                .display_order(370)
+                .help("(zkFuzz) Path to the CSV file where the results of --input_dir are written"),
+            Arg::with_name("timeout_per_file")
+                .long("timeout_per_file")
+                .takes_value(true)
+                .default_value("3600")
+                .display_order(380)
+                .help("(zkFuzz) Timeout in seconds for each circuit analysed with --input_dir (0 means no timeout)"),
+            Arg::with_name("output_clusters")
+                .long("output_clusters")
+                .takes_value(true)
+                .default_value("none")
+                .display_order(385)
+                .help("(zkFuzz) Path to the JSON file where the counterexamples of --input_dir are grouped by root cause"),
+            Arg::with_name("entry")
+                .long("entry")
+                .takes_value(true)
+                .display_order(386)
+                .help("(zkFuzz) Comma-separated circuits with their own main component (e.g. main_a.circom,main_b.circom). They are parsed together and analysed in turn"),
+            Arg::with_name("output_entries")
+                .long("output_entries")
+                .takes_value(true)
+                .default_value("entries.json")
+                .display_order(387)
+                .help("(zkFuzz) Path to the JSON file where the results of --entry are written"),
+        ]
+    }
+
+    /// Arguments of `diff`.
+    fn diff_args() -> Vec<Arg<'static, 'static>> {
+        vec![
+            Arg::with_name("input")
+                .multiple(false)
+                .required(true)
+                .index(1)
+                .help("Path to the old version of the circuit"),
+            Arg::with_name("new_input")
+                .multiple(false)
+                .required(true)
+                .index(2)
+                .help("Path to the new version of the circuit"),
+            Arg::with_name("link_libraries")
+                .short("l")
+                .takes_value(true)
+                .multiple(true)
+                .number_of_values(1)
+                .display_order(100)
+                .help("Adds directory to library search path"),
+        ]
+    }
+
+    /// Arguments of `trace`.
+    fn trace_args() -> Vec<Arg<'static, 'static>> {
+        vec![
+            Arg::with_name("input")
+                .multiple(false)
+                .required(true)
+                .index(1)
+                .help("Path to the execution trace written by --trace_jsonl"),
+            Arg::with_name("at_step")
+                .long("at-step")
+                .takes_value(true)
+                .default_value("none")
+                .display_order(100)
+                .help("(zkFuzz) Step of the execution trace to reconstruct the symbolic state at (the final state if none)"),
+        ]
+    }
+
+    /// Arguments of `run`.
+    fn run_args() -> Vec<Arg<'static, 'static>> {
+        vec![
+            Arg::with_name("manifest")
+                .long("manifest")
+                .takes_value(true)
+                .default_value(MANIFEST_FILE_NAME)
+                .display_order(395)
+                .help("(zkFuzz) Path to the project manifest whose settings are the defaults of the other options"),
+        ]
+    }
+
+    /// Arguments of `gen-tests`.
+    fn gen_tests_args() -> Vec<Arg<'static, 'static>> {
+        vec![
+            Arg::with_name("harness_lang")
+                .long("lang")
+                .takes_value(true)
+                .default_value("js")
+                .display_order(400)
+                .help("(zkFuzz) Language of the test harness: js (mocha and circom_tester) or rust (ark-circom)"),
+            Arg::with_name("num_cases")
+                .long("num_cases")
+                .takes_value(true)
+                .default_value("10")
+                .display_order(410)
+                .help("(zkFuzz) Number of test cases to generate"),
+            Arg::with_name("harness_output")
+                .short("o")
+                .long("output")
+                .takes_value(true)
+                .default_value("none")
+                .display_order(420)
+                .help("(zkFuzz) Path of the generated test harness (<circuit>_test.js or <circuit>_test.rs next to the circuit if none)"),
+            Arg::with_name("seed")
+                .long("seed")
+                .takes_value(true)
+                .default_value("0")
+                .display_order(430)
+                .help("(zkFuzz) Seed of the random inputs of the test cases (0: draw the seed at random)"),
+        ]
+    }
+
+    /// Arguments of the `show-constraints` subcommand.
+    fn show_constraints_args() -> Vec<Arg<'static, 'static>> {
+        vec![
+            Arg::with_name("constraint_format")
+                .long("format")
+                .takes_value(true)
+                .default_value("text")
+                .display_order(400)
+                .help("(zkFuzz) Format of the constraints: text (infix notation of circom) or latex"),
+            Arg::with_name("line_width")
+                .long("width")
+                .takes_value(true)
+                .default_value("100")
+                .display_order(410)
+                .help("(zkFuzz) Maximum number of characters per line, at which the constraints are wrapped (0 disables the wrapping)"),
+        ]
+    }
+
+    /// Arguments of the `why` subcommand.
+    fn why_args() -> Vec<Arg<'static, 'static>> {
+        vec![
+            Arg::with_name("constraint_index")
+                .long("constraint-index")
+                .takes_value(true)
+                .required(true)
+                .display_order(400)
+                .help("(zkFuzz) Index of the constraint to explain, starting at 0, in the order printed by show-constraints"),
+            Arg::with_name("side")
+                .long("side")
+                .takes_value(false)
+                .display_order(410)
+                .help("(zkFuzz) Explains a side constraint instead of a trace constraint"),
+        ]
+    }
+
+    /// Arguments of the `explain` subcommand.
+    fn explain_args() -> Vec<Arg<'static, 'static>> {
+        vec![
+            Arg::with_name("signal")
+                .long("signal")
+                .takes_value(true)
+                .required(true)
+                .display_order(400)
+                .help("(zkFuzz) Name of the signal to explain, e.g. main.out, which selects all its elements if it is an array"),
+        ]
+    }
+
+    /// Arguments of the server mode.
+    fn serve_args() -> Vec<Arg<'static, 'static>> {
+        vec![
+            Arg::with_name("host")
+                .long("host")
+                .takes_value(true)
+                .default_value("127.0.0.1")
+                .display_order(100)
+                .help("(zkFuzz) Address the JSON-RPC server listens on"),
+            Arg::with_name("port")
+                .long("port")
+                .takes_value(true)
+                .default_value("4242")
+                .display_order(110)
+                .help("(zkFuzz) Port the JSON-RPC server listens on"),
+        ]
+    }
+
+    /// Arguments printing statistics of the circuit.
+    fn stats_args() -> Vec<Arg<'static, 'static>> {
+        vec![
+            Arg::with_name("show_stats_of_ast")
+                .long("show_stats_of_ast")
+                .takes_value(false)
+                .display_order(840)
+                .help("(zkFuzz) Prints the basic stats of AST"),
+            Arg::with_name("export_cfg")
+                .long("export_cfg")
+                .takes_value(true)
+                .default_value("none")
+                .display_order(845)
+                .help("(zkFuzz) Directory where the control-flow graph of each template is saved in the DOT format"),
+            Arg::with_name("print_stats")
+                .long("print_stats")
+                .takes_value(false)
+                .display_order(850)
+                .help("(zkFuzz) Prints the stats of constraints"),
+            Arg::with_name("print_stats_csv")
+                .long("print_stats_csv")
+                .takes_value(false)
+                .display_order(860)
+                .help("(zkFuzz) Prints the stats of constraints in CSV format"),
+            Arg::with_name("stats_sort_by")
+                .long("stats_sort_by")
+                .takes_value(true)
+                .default_value("constraints")
+                .display_order(865)
+                .help("(zkFuzz) Sort key of the per-component stats: constraints, nonlinear, depth, or component"),
+            Arg::with_name("component_stats_csv")
+                .long("component_stats_csv")
+                .takes_value(true)
+                .default_value("none")
+                .display_order(866)
+                .help("(zkFuzz) Path to save the per-component stats of constraints in CSV format"),
+            Arg::with_name("plonk_gate_widths")
+                .long("plonk_gate_widths")
+                .takes_value(true)
+                .default_value("3")
+                .display_order(867)
+                .help("(zkFuzz) Comma-separated widths of the Plonk gates the proving cost is estimated for"),
+        ]
+    }
+
+    pub fn view() -> ArgMatches<'static> {
+        App::new("ZK Circuit Fuzzer")
+            .version(VERSION)
+            .author("Anonymous Authors")
+            .about("Fuzzer designed to help you identify vulnerabilities in zero-knowledge proof circuits")
+            .after_help("Running zkFuzz without a subcommand is equivalent to `zkfuzz fuzz`, and accepts the options of every subcommand.")
+            .args(&input_args())
+            .args(&execution_args())
+            .args(&search_args())
+            .args(&batch_args())
+            .args(&stats_args())
+            .arg(
+                Arg::with_name("plain")
+                    .long("plain")
+                    .takes_value(false)
+                    .global(true)
+                    .display_order(901)
+                    .help("(zkFuzz) Prints the reports without colors and emoji, as when the NO_COLOR environment variable is set"),
+            )
+            .subcommand(
+                SubCommand::with_name("exec")
+                    .about("Executes the circuit symbolically and reports its trace/side constraints without searching for counterexamples")
+                    .args(&input_args())
+                    .args(&execution_args()),
+            )
+            .subcommand(
+                SubCommand::with_name("fuzz")
+                    .about("Searches for counterexamples showing that the circuit is under- or over-constrained")
+                    .args(&input_args())
+                    .args(&execution_args())
+                    .args(&search_args())
+                    .args(&batch_args()),
+            )
+            .subcommand(
+                SubCommand::with_name("run")
+                    .about("Runs `fuzz` with the settings of the project manifest (zkfuzz.toml) as defaults of its options")
+                    .args(&input_args())
+                    .args(&execution_args())
+                    .args(&search_args())
+                    .args(&batch_args())
+                    .args(&run_args()),
+            )
+            .subcommand(
+                SubCommand::with_name("stats")
+                    .about("Prints the statistics of the AST and of the trace/side constraints of the circuit")
+                    .args(&input_args())
+                    .args(&execution_args())
+                    .args(&stats_args()),
+            )
+            .subcommand(
+                SubCommand::with_name("export")
+                    .about("Searches for a counterexample and exports it as JSON, together with the input.json and the script replaying it with the witness generator of circom")
+                    .args(&input_args())
+                    .args(&execution_args())
+                    .args(&search_args()),
+            )
+            .subcommand(
+                SubCommand::with_name("diff")
+                    .about("Executes two versions of a circuit symbolically and reports the trace/side constraints added, removed, or changed in each template")
+                    .args(&diff_args())
+                    .args(&execution_args()),
+            )
+            .subcommand(
+                SubCommand::with_name("trace")
+                    .about("Reconstructs the symbolic state at a step of an execution trace written by --trace_jsonl")
+                    .args(&trace_args()),
+            )
+            .subcommand(
+                SubCommand::with_name("gen-tests")
+                    .about("Generates a property-based test harness checking that circom computes the outputs zkFuzz expects from random inputs, and that its constraints accept the witness")
+                    .args(&input_args())
+                    .args(&execution_args())
+                    .args(&gen_tests_args()),
+            )
+            .subcommand(
+                SubCommand::with_name("show-constraints")
+                    .about("Prints the trace/side constraints of the circuit in infix notation, as text or LaTeX")
+                    .args(&input_args())
+                    .args(&execution_args())
+                    .args(&show_constraints_args()),
+            )
+            .subcommand(
+                SubCommand::with_name("why")
+                    .about("Prints the statement that produced a trace/side constraint, with the components and function calls it was executed within")
+                    .args(&input_args())
+                    .args(&execution_args())
+                    .args(&why_args()),
+            )
+            .subcommand(
+                SubCommand::with_name("explain")
+                    .about("Prints how the witness generator computes a signal next to the side constraints checking it, and the sub-expressions found on one side only")
+                    .args(&input_args())
+                    .args(&execution_args())
+                    .args(&explain_args()),
+            )
+            .subcommand(
+                SubCommand::with_name("baseline")
+                    .about("Manages the baseline of accepted findings given to --baseline")
+                    .subcommand(
+                        SubCommand::with_name("update")
+                            .about("Runs `fuzz` and records its findings in the baseline of --baseline, keeping the names and reasons of the entries found again")
+                            .args(&input_args())
+                            .args(&execution_args())
+                            .args(&search_args()),
+                    ),
+            )
+            .subcommand(
+                SubCommand::with_name("serve")
+                    .about("Runs a JSON-RPC server to submit circuits, query the progress of their analyses, fetch their constraints and findings, and cancel them")
+                    .args(&serve_args()),
+            )
+            .subcommand(
+                SubCommand::with_name("compile-lib")
+                    .about("Compiles the templates and functions of a library into an archive that can be loaded with --link")
+                    .args(&library_args())
+                    .arg(
+                        Arg::with_name("prime")
+                            .short("prime")
+                            .long("prime")
+                            .takes_value(true)
+                            .default_value("bn128")
+                            .display_order(300)
+                            .help("To choose the prime number to use to parse the library. Receives the name of the curve (bn128, bls12381, goldilocks, grumpkin, pallas, vesta, secq256r1)"),
+                    ),
+            )
+            .subcommand(
+                SubCommand::with_name("regression")
+                    .about("Executes every template of a corpus such as circomlib with small parameters, and compares the numbers of constraints with a snapshot")
+                    .args(&regression_args()),
             )
             .get_matches()
     }
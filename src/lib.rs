@@ -1,8 +1,18 @@
+#[macro_use]
+pub mod output_user;
+
 pub mod executor;
 pub mod mutator;
 
-pub mod input_user;
+pub mod analysis_user;
+pub mod artifact_user;
+pub mod cache_user;
+pub mod manifest_user;
 pub mod parser_user;
+#[cfg(feature = "python")]
+pub mod python;
+pub mod regression_user;
+pub mod stats;
 pub mod type_analysis_user;
 
 const VERSION: &'static str = env!("CARGO_PKG_VERSION");
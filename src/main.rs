@@ -1,66 +1,80 @@
+#[macro_use]
+mod output_user;
+
 mod executor;
 mod mutator;
 mod stats;
 
+mod artifact_user;
+mod batch_user;
+mod cache_user;
 mod input_user;
+mod manifest_user;
 mod parser_user;
+mod regression_user;
+mod report_user;
+mod search_user;
+mod server_user;
 mod type_analysis_user;
 
 use std::env;
 use std::fs::File;
-use std::io::{self, BufRead, Write};
+use std::io::{self, BufRead, Read, Write};
 use std::path::Path;
+use std::rc::Rc;
 use std::str::FromStr;
 use std::time;
 
+use artifact_user::ArtifactWriter;
+use batch_user::EntryResult;
+use cache_user::{CachedResult, ResultCache};
 use colored::Colorize;
 use env_logger;
 use input_user::Input;
-use log::{debug, info, warn};
+use output_user::{init_output, is_plain, to_plain};
+use log::{debug, warn};
 use num_bigint_dig::BigInt;
-use rand::distributions::Alphanumeric;
 use rand::{thread_rng, Rng};
+use regression_user::{RegressionSnapshot, RegressionStatus};
+use report_user::{report_circuit, report_integer_overflows, CircuitAnalysis, StaticFindings};
 use rustc_hash::{FxHashMap, FxHashSet};
-use serde_json::json;
+use search_user::{examine_counter_examples, search_counter_examples, ExaminedCounterExamples};
+use serde_json::{json, Value};
 
 use program_structure::ast::Expression;
 use program_structure::program_archive::ProgramArchive;
 
+use executor::algebraic_simplifier::{count_nodes, simplify_constraints, SimplificationLevel};
+use executor::constraint_store::{new_constraint_store, ConstraintStore};
+use executor::symbolic_archive::SymbolicLibraryArchive;
 use executor::symbolic_execution::SymbolicExecutor;
+use executor::symbolic_interner::reset_interner;
 use executor::symbolic_setting::{
     get_default_setting_for_concrete_execution, get_default_setting_for_symbolic_execution,
+    SymbolicExecutorSetting,
 };
-use executor::symbolic_value::{OwnerName, SymbolicLibrary};
-
-use mutator::mutation_config::load_config_from_json;
-use mutator::mutation_test_crossover_fn::random_crossover;
-use mutator::mutation_test_evolution_fn::simple_evolution;
-use mutator::mutation_test_trace_fitness_fn::evaluate_trace_fitness_by_error;
-use mutator::mutation_test_trace_initialization_fn::{
-    initialize_population_with_constant_replacement,
-    initialize_population_with_operator_or_const_replacement,
-    initialize_population_with_operator_or_const_replacement_or_addition,
-    initialize_population_with_operator_or_const_replacement_or_deletion,
-};
-use mutator::mutation_test_trace_mutation_fn::{
-    mutate_trace_with_constant_replacement, mutate_trace_with_operator_or_const_replacement,
-    mutate_trace_with_operator_or_const_replacement_or_addition,
-    mutate_trace_with_operator_or_const_replacement_or_deletion,
-};
-use mutator::mutation_test_trace_selection_fn::roulette_selection;
-use mutator::mutation_test_update_input_fn::{
-    update_input_population_with_coverage_maximization, update_input_population_with_fitness_score,
-    update_input_population_with_random_sampling,
-};
+use executor::symbolic_snapshot::SymbolicSnapshot;
+use executor::symbolic_value::{OwnerName, SymbolicLibrary, SymbolicValueRef};
+use executor::template_semantics::LessThanSemantics;
+use executor::trace_log::{read_trace_log, reconstruct_state, TraceLog};
+
 use mutator::{
-    brute_force::brute_force_search, mutation_test::mutation_test_search,
-    unused_outputs::check_unused_outputs, utils::BaseVerificationConfig,
+    detector::{Detector, DetectorRegistry},
+    finding_stream::FindingStream,
+    input_spec::{load_input_spec, InputSpec},
+    provenance::explain_constraint,
+    severity::Severity,
+    signal_explanation::explain_signal,
+    test_harness::{generate_test_cases, render_harness, HarnessLanguage},
 };
 
-use stats::ast_stats::ASTStats;
+use stats::ast_stats::{ASTStats, ControlFlowGraph};
+use stats::constraint_diff::diff_constraints;
+use stats::cost_estimate::estimate_cost;
+use stats::pretty_print::{PrettyFormat, PrettyPrinter};
 use stats::symbolic_stats::{
-    print_constraint_summary_statistics_csv, print_constraint_summary_statistics_pretty,
-    ConstraintStatistics,
+    find_compression_anomalies, ConstraintStatistics, COMPRESSION_ANOMALY_THRESHOLD,
+    COMPRESSION_ANOMALY_TOP_K,
 };
 
 const VERSION: &'static str = env!("CARGO_PKG_VERSION");
@@ -73,9 +87,9 @@ fn display_zkfuzz_header() {
       ZK Circuit Fuzzer
     "#;
 
-    eprintln!("{}", logo.bright_cyan().bold());
-    eprintln!("{}", "Welcome to the zkFuzz".green().bold());
-    eprintln!("{}", "══════════════════════════════════".green());
+    eoutln!("{}", logo.bright_cyan().bold());
+    eoutln!("{}", "Welcome to the zkFuzz".green().bold());
+    eoutln!("{}", "══════════════════════════════════".green());
 }
 
 fn read_file_to_lines(file_path: &str) -> io::Result<Vec<String>> {
@@ -88,63 +102,91 @@ fn read_file_to_lines(file_path: &str) -> io::Result<Vec<String>> {
     Ok(lines)
 }
 
+/// Initializes the logger with `RUST_LOG` and the directives of `--log_level`, whose module names
+/// are relative to zkFuzz (e.g. `executor=trace` for `zkfuzz::executor`). In plain mode, the
+/// records are written without colors and emoji.
+fn init_logger(log_level: &str) {
+    let mut builder = env_logger::Builder::from_default_env();
+    if is_plain() {
+        builder.write_style(env_logger::WriteStyle::Never);
+        builder.format(|buf, record| {
+            writeln!(
+                buf,
+                "[{} {}] {}",
+                record.level(),
+                record.target(),
+                to_plain(&record.args().to_string())
+            )
+        });
+    }
+    if log_level != "none" {
+        let filters = log_level
+            .split(',')
+            .map(str::trim)
+            .map(|directive| match directive.split_once('=') {
+                Some((module, level)) if !module.starts_with("zkfuzz") => {
+                    format!("zkfuzz::{}={}", module, level)
+                }
+                _ => directive.to_string(),
+            })
+            .collect::<Vec<_>>()
+            .join(",");
+        builder.parse_filters(&filters);
+    }
+    builder.init();
+}
+
 fn main() {
+    // The header is printed before the arguments are parsed
+    init_output(env::args().any(|arg| arg == "--plain"));
     display_zkfuzz_header();
 
     let result = start();
     if result.is_err() {
-        eprintln!("{}", "previous errors were found".red());
+        eoutln!("{}", "previous errors were found".red());
         std::process::exit(1);
     } else {
-        eprintln!("{}", "Everything went okay".green());
+        eoutln!("{}", "Everything went okay".green());
         //std::process::exit(0);
     }
 }
 
 fn show_stats(program_archive: &ProgramArchive) {
-    println!("template_name,num_statements,num_variables,num_if_then_else,num_while,num_constraint_equality,num_assign_var,num_assign_constraint_signal,num_assign_signal,avg_loc_constraint_equality,avg_loc_assign_constraint_signal,avg_loc_assign_signal");
+    outln!("template_name,num_statements,num_variables,num_if_then_else,num_while,num_constraint_equality,num_assign_var,num_assign_constraint_signal,num_assign_signal,avg_loc_constraint_equality,avg_loc_assign_constraint_signal,avg_loc_assign_signal,cyclomatic_complexity,max_loop_depth,num_component_instantiations");
     for (k, v) in program_archive.templates.clone().into_iter() {
         let mut ass = ASTStats::default();
         ass.collect_stats(v.get_body());
-        println!("{},{}", k, ass.get_csv());
+        let cfg = ControlFlowGraph::build(v.get_body());
+        outln!("{},{},{}", k, ass.get_csv(), cfg.get_csv());
     }
 }
 
-fn start() -> Result<(), ()> {
-    let start_time = time::Instant::now();
-    //use compilation_user::CompilerConfig;
-
-    let user_input = Input::new()?;
-    let mut program_archive = parser_user::parse_project(&user_input)?;
-    type_analysis_user::analyse_project(&mut program_archive)?;
-
-    if user_input.show_stats_of_ast {
-        show_stats(&program_archive);
-        return Result::Ok(());
+/// Saves the control-flow graph of each template as `<dir>/<template>.dot`.
+fn export_cfgs(program_archive: &ProgramArchive, dir: &Path) -> Result<(), ()> {
+    std::fs::create_dir_all(dir)
+        .map_err(|e| eoutln!("{} {}", "Unable to create the CFG directory:".red(), e))?;
+    for (name, template) in program_archive.templates.iter() {
+        let cfg = ControlFlowGraph::build(template.get_body());
+        std::fs::write(dir.join(format!("{}.dot", name)), cfg.to_dot(name))
+            .map_err(|e| eoutln!("{} {}", "Unable to write the CFG:".red(), e))?;
     }
+    eoutln!(
+        "{} {} ({} templates)",
+        "💾 Saving the control-flow graphs to:",
+        dir.display().to_string().cyan(),
+        program_archive.templates.len()
+    );
+    Ok(())
+}
 
-    env_logger::init();
-
-    eprintln!("{}", "🧾 Loading Whitelists...".green());
-    let whitelist = if user_input.path_to_whitelist() == "none" {
-        FxHashSet::from_iter(["IsZero".to_string(), "Num2Bits".to_string()])
-    } else {
-        FxHashSet::from_iter(
-            read_file_to_lines(&&&user_input.path_to_whitelist())
-                .unwrap()
-                .into_iter(),
-        )
-    };
-
-    let mut symbolic_library = SymbolicLibrary {
-        template_library: FxHashMap::default(),
-        name2id: FxHashMap::default(),
-        id2name: FxHashMap::default(),
-        function_library: FxHashMap::default(),
-        function_counter: FxHashMap::default(),
-    };
-
-    eprintln!("{}", "🧩 Parsing Templates...".green());
+/// Registers the templates and functions of `program_archive` in `symbolic_library`.
+fn register_program(
+    symbolic_library: &mut SymbolicLibrary,
+    program_archive: &ProgramArchive,
+    whitelist: &FxHashSet<String>,
+    print_ast: bool,
+) {
+    eoutln!("{}", "🧩 Parsing Templates...".green());
     let mut templates_names = program_archive
         .templates
         .keys()
@@ -158,16 +200,19 @@ fn start() -> Result<(), ()> {
             k.clone(),
             &body.clone(),
             v.get_name_of_params(),
-            &whitelist,
-            user_input.lessthan_dissabled_flag,
+            whitelist,
+            v.is_custom_gate(),
         );
 
-        if user_input.flag_printout_ast {
-            eprintln!(
+        if print_ast {
+            eoutln!(
                 "{}{} {}{}",
-                BACK_GRAY_SCRIPT_BLACK, "🌳 AST Tree for", k, RESET
+                BACK_GRAY_SCRIPT_BLACK,
+                "🌳 AST Tree for",
+                k,
+                RESET
             );
-            eprintln!(
+            eoutln!(
                 "{}",
                 symbolic_library.template_library[&symbolic_library.name2id[&k]]
                     .body
@@ -179,7 +224,7 @@ fn start() -> Result<(), ()> {
         }
     }
 
-    eprintln!("{}", "⚙️ Parsing Function...".green());
+    eoutln!("{}", "⚙️ Parsing Function...".green());
     let mut function_names = program_archive
         .functions
         .keys()
@@ -191,12 +236,15 @@ fn start() -> Result<(), ()> {
         let body = v.get_body().clone();
         symbolic_library.register_function(k.clone(), body.clone(), v.get_name_of_params());
 
-        if user_input.flag_printout_ast {
-            eprintln!(
+        if print_ast {
+            eoutln!(
                 "{}{} {}{}",
-                BACK_GRAY_SCRIPT_BLACK, "🌴 AST Tree for", k, RESET
+                BACK_GRAY_SCRIPT_BLACK,
+                "🌴 AST Tree for",
+                k,
+                RESET
             );
-            eprintln!(
+            eoutln!(
                 "{}",
                 symbolic_library.function_library[&symbolic_library.name2id[&k]]
                     .body
@@ -207,19 +255,626 @@ fn start() -> Result<(), ()> {
             );
         }
     }
+}
+
+/// Returns the parameter names of the main template, which is either parsed or linked from a
+/// library archive.
+fn get_main_template_param_names(
+    program_archive: &ProgramArchive,
+    symbolic_library: &SymbolicLibrary,
+    name: &str,
+) -> Vec<String> {
+    match program_archive.templates.get(name) {
+        Some(template) => template.get_name_of_params().clone(),
+        None => symbolic_library.template_library[&symbolic_library.name2id[name]]
+            .template_parameter_names
+            .iter()
+            .map(|p| symbolic_library.id2name[p].clone())
+            .collect(),
+    }
+}
+
+/// Reads the JSON object given to --main_params, from stdin if the path is `-`.
+fn load_main_params(user_input: &Input) -> Result<Option<Value>, ()> {
+    if user_input.main_params() == "none" {
+        return Ok(None);
+    }
+    let mut source = String::new();
+    let read = if user_input.main_params() == "-" {
+        io::stdin().read_to_string(&mut source)
+    } else {
+        File::open(user_input.main_params()).and_then(|mut f| f.read_to_string(&mut source))
+    };
+    read.map_err(|e| eoutln!("{} {}", "Unable to read the main parameters:".red(), e))?;
+    serde_json::from_str(&source)
+        .map(Some)
+        .map_err(|e| eoutln!("{} {}", "Invalid JSON of the main parameters:".red(), e))
+}
+
+/// Overrides the arguments of the main component with the parameters given by --main_params.
+fn apply_main_params(
+    program_archive: &mut ProgramArchive,
+    symbolic_library: &SymbolicLibrary,
+    params: &Value,
+) -> Result<(), ()> {
+    let param_names = match &program_archive.initial_template_call {
+        Expression::Call { id, .. } => {
+            get_main_template_param_names(program_archive, symbolic_library, id)
+        }
+        _ => return Result::Err(eoutln!("{}", "Cannot Find Main Call".red())),
+    };
+    parser_user::override_main_params(program_archive, &param_names, params)
+        .map_err(|e| eoutln!("{} {}", "Invalid main parameters:".red(), e))?;
+    eoutln!(
+        "{} {}",
+        "🎛️ Overriding the Main Parameters:".green(),
+        params.to_string().cyan()
+    );
+    Ok(())
+}
+
+/// Compiles the library given to `zkfuzz compile-lib` into a symbolic library archive.
+fn compile_library(user_input: &Input) -> Result<(), ()> {
+    eoutln!("{}", "📚 Parsing Library...".green());
+    let (mut program_archive, source_files) = parser_user::parse_library(
+        &user_input.input_program,
+        &user_input.prime(),
+        user_input.get_link_libraries(),
+    )?;
+    type_analysis_user::analyse_project(&mut program_archive)?;
+
+    let mut symbolic_library = SymbolicLibrary::default();
+    register_program(
+        &mut symbolic_library,
+        &program_archive,
+        &FxHashSet::default(),
+        false,
+    );
+    let archive = SymbolicLibraryArchive::from_library(
+        &symbolic_library,
+        source_files,
+        &[parser_user::LIBRARY_ROOT_TEMPLATE],
+    );
+    archive
+        .save(Path::new(&user_input.library_output()))
+        .map_err(|e| eoutln!("{} {}", "Unable to write the library archive:".red(), e))?;
+
+    eoutln!(
+        "{} {} ({} files, {} templates, {} functions)",
+        "💾 Saving the library archive to:",
+        user_input.library_output().cyan(),
+        archive.source_files.len(),
+        archive.template_names().len(),
+        archive.function_names().len()
+    );
+    Result::Ok(())
+}
+
+/// Executes every template of the corpus given to `zkfuzz regression` and compares the outcomes
+/// with `--regression_snapshot`, or records them there with `--update_regression_snapshot`.
+fn run_regression(user_input: &Input) -> Result<(), ()> {
+    let snapshot_path = user_input.regression_snapshot();
+    let snapshot =
+        if user_input.flag_update_regression_snapshot && !Path::new(&snapshot_path).exists() {
+            None
+        } else {
+            Some(
+                RegressionSnapshot::load(Path::new(&snapshot_path)).map_err(|e| {
+                    eoutln!("{} {}", "Unable to read the regression snapshot:".red(), e)
+                })?,
+            )
+        };
+
+    eoutln!("{}", "🧪 Executing the Templates of the Corpus...".green());
+    let entries = regression_user::run_regression_corpus(
+        &user_input.input_program,
+        &user_input.prime(),
+        user_input.get_link_libraries(),
+        snapshot.as_ref(),
+    );
+    let num_executed = entries
+        .iter()
+        .filter(|entry| entry.status == RegressionStatus::Executed)
+        .count();
+    eoutln!(
+        "{} {} templates ({} executed, {} failed)",
+        "📋 Corpus:".green(),
+        entries.len(),
+        num_executed,
+        entries.len() - num_executed
+    );
+    for entry in entries
+        .iter()
+        .filter(|entry| entry.status == RegressionStatus::Panic)
+    {
+        eoutln!(
+            "  ├─ {}:{} panicked: {}",
+            entry.file,
+            entry.template,
+            entry.panic_message.as_deref().unwrap_or("")
+        );
+    }
+
+    if user_input.flag_update_regression_snapshot {
+        RegressionSnapshot::new(&user_input.prime(), entries)
+            .save(Path::new(&snapshot_path))
+            .map_err(|e| eoutln!("{} {}", "Unable to write the regression snapshot:".red(), e))?;
+        eoutln!(
+            "{} {}",
+            "💾 Saving the regression snapshot to:",
+            snapshot_path.cyan()
+        );
+        return Result::Ok(());
+    }
+
+    let snapshot = snapshot.unwrap();
+    if snapshot.prime != user_input.prime() {
+        eoutln!(
+            "{} the snapshot was recorded with --prime {}",
+            "⚠️ Warning:".yellow(),
+            snapshot.prime
+        );
+    }
+    let comparison = snapshot.compare(&entries);
+    for (expected, actual) in &comparison.changed {
+        eoutln!(
+            "  ├─ {} {}:{}: {} -> {}",
+            "changed".red(),
+            actual.file,
+            actual.template,
+            expected.outcome(),
+            actual.outcome()
+        );
+    }
+    for entry in &comparison.removed {
+        eoutln!("  ├─ {} {}:{}", "missing".red(), entry.file, entry.template);
+    }
+    for entry in &comparison.added {
+        eoutln!(
+            "  ├─ {} {}:{}: {}",
+            "new".yellow(),
+            entry.file,
+            entry.template,
+            entry.outcome()
+        );
+    }
+    if comparison.is_regression() {
+        eoutln!(
+            "{} {} changed, {} missing",
+            "❌ Regression:".red(),
+            comparison.changed.len(),
+            comparison.removed.len()
+        );
+        return Result::Err(());
+    }
+    eoutln!(
+        "{} every template of the snapshot has the expected outcome",
+        "✅ No Regression:".green()
+    );
+    Result::Ok(())
+}
+
+/// Prints the symbolic state at `--at-step` of the execution trace given to `zkfuzz trace`.
+fn replay_trace(user_input: &Input) -> Result<(), ()> {
+    let events = read_trace_log(&user_input.input_program)
+        .map_err(|e| eoutln!("{} {}", "Unable to read the execution trace:".red(), e))?;
+    let num_steps = events.len();
+    let step = match user_input.at_step().as_str() {
+        "none" => num_steps.saturating_sub(1),
+        step => step.parse().unwrap(),
+    };
+    let state = reconstruct_state(&events, step);
+
+    eoutln!(
+        "{} {}/{}",
+        "🕰️ Symbolic State at Step:".green(),
+        step.to_string().bright_yellow(),
+        num_steps.saturating_sub(1)
+    );
+    if let Some(statement) = &state.statement {
+        eoutln!(
+            "  ├─ Statement         : {} (elem_id={}, step {}) in {}",
+            statement["kind"].as_str().unwrap_or_default(),
+            statement["elem_id"],
+            statement["step"],
+            statement["template"].as_str().unwrap_or_default()
+        );
+    }
+    eoutln!("  ├─ Owner Stack       : {}", state.owner_stack.join(" → "));
+    eoutln!("  ├─ Bindings          : {}", state.bindings.len());
+    for (name, value) in &state.bindings {
+        eoutln!("  │   • {} = {}", name, value);
+    }
+    eoutln!("  ├─ Trace Constraints : {}", state.trace_constraints.len());
+    for constraint in &state.trace_constraints {
+        eoutln!("  │   • {}", constraint);
+    }
+    eoutln!("  └─ Side Constraints  : {}", state.side_constraints.len());
+    for constraint in &state.side_constraints {
+        eoutln!("      • {}", constraint);
+    }
+    Result::Ok(())
+}
+
+/// Returns `path` relative to the directory `base`, with `/` as separator.
+fn relative_path(base: &Path, path: &Path) -> String {
+    let base = base.canonicalize().unwrap_or_else(|_| base.to_path_buf());
+    let path = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+    let base_components: Vec<_> = base.components().collect();
+    let path_components: Vec<_> = path.components().collect();
+    let common = base_components
+        .iter()
+        .zip(&path_components)
+        .take_while(|(a, b)| a == b)
+        .count();
+    let mut parts = vec!["..".to_string(); base_components.len() - common];
+    parts.extend(
+        path_components[common..]
+            .iter()
+            .map(|c| c.as_os_str().to_string_lossy().to_string()),
+    );
+    parts.join("/")
+}
+
+/// Rewrites the trace and side constraints with the algebraic simplifier at the level of
+/// `--simplify`, and reports the number of nodes they have before and after.
+fn simplify_gathered_constraints(user_input: &Input, sym_executor: &mut SymbolicExecutor) {
+    let level = SimplificationLevel::from_str(&user_input.simplify()).unwrap();
+    if level == SimplificationLevel::Off {
+        return;
+    }
+    let prime = sym_executor.setting.prime.clone();
+    let size = |constraints: &[SymbolicValueRef]| -> usize {
+        constraints.iter().map(|c| count_nodes(c)).sum()
+    };
+    let state = &mut sym_executor.cur_state;
+    let before = size(&state.symbolic_trace) + size(&state.side_constraints);
+    state.symbolic_trace = simplify_constraints(&state.symbolic_trace, &prime, level);
+    state.side_constraints = simplify_constraints(&state.side_constraints, &prime, level);
+    let after = size(&state.symbolic_trace) + size(&state.side_constraints);
+    eoutln!(
+        "{} {} ({} → {} nodes)",
+        "🧮 Simplifying the Constraints:".green(),
+        level.as_str().bright_yellow(),
+        before,
+        after
+    );
+}
+
+/// Prints the trace and side constraints of `zkfuzz show-constraints` in infix notation, as text
+/// or as LaTeX display math, wrapped at `--width` characters.
+fn show_constraints(user_input: &Input, sym_executor: &SymbolicExecutor) {
+    let format = PrettyFormat::from_str(&user_input.constraint_format()).unwrap();
+    let width: usize = user_input.line_width().parse().unwrap();
+    let prime = BigInt::from_str(&user_input.debug_prime()).unwrap();
+    let printer = PrettyPrinter::new(format, &sym_executor.symbolic_library.id2name, &prime);
+    for (title, constraints) in [
+        ("Trace Constraints", &sym_executor.cur_state.symbolic_trace),
+        ("Side Constraints", &sym_executor.cur_state.side_constraints),
+    ] {
+        match format {
+            PrettyFormat::Text => outln!("{} ({}):", title, constraints.len()),
+            PrettyFormat::Latex => outln!("% {} ({})", title, constraints.len()),
+        }
+        for constraint in constraints {
+            let lines = printer.render(constraint, width);
+            match format {
+                PrettyFormat::Text => {
+                    for line in lines {
+                        outln!("  {}", line);
+                    }
+                }
+                PrettyFormat::Latex => outln!("\\[\n{}\n\\]", lines.join("\n")),
+            }
+        }
+    }
+}
+
+/// Prints the statement that produced the constraint selected by `zkfuzz why`, together with the
+/// components and function calls it was executed within.
+fn show_constraint_origin(
+    user_input: &Input,
+    sym_executor: &SymbolicExecutor,
+    program_archive: &ProgramArchive,
+) -> Result<(), ()> {
+    let index: usize = user_input.constraint_index().parse().unwrap();
+    let (kind, constraints) = if user_input.flag_side {
+        ("Side Constraint", &sym_executor.cur_state.side_constraints)
+    } else {
+        ("Trace Constraint", &sym_executor.cur_state.symbolic_trace)
+    };
+    let origin = explain_constraint(
+        sym_executor,
+        &program_archive.file_library,
+        index,
+        user_input.flag_side,
+    )
+    .ok_or_else(|| {
+        eoutln!(
+            "{} {} (the circuit has {} {}s)",
+            "Constraint index out of range:".red(),
+            index,
+            constraints.len(),
+            kind.to_lowercase()
+        )
+    })?;
+
+    let prime = BigInt::from_str(&user_input.debug_prime()).unwrap();
+    let printer = PrettyPrinter::new(
+        PrettyFormat::Text,
+        &sym_executor.symbolic_library.id2name,
+        &prime,
+    );
+    outln!("🔎 {} #{}:", kind, index);
+    for line in printer.render(&origin.constraint, user_input.line_width().parse().unwrap()) {
+        outln!("  {}", line);
+    }
+    outln!("👤 Owner: {}", origin.owner);
+    match origin.location {
+        Some(location) => outln!("📍 Location: {}", location),
+        None => outln!(
+            "📍 Location: not produced by a statement (template argument or template semantics)"
+        ),
+    }
+    if let Some(assertion) = origin.assertion {
+        outln!(
+            "🧷 Asserted in: {} (the location above is its call site)",
+            assertion
+        );
+    }
+    if let Some(snippet) = origin.snippet {
+        outln!("📜 Source:");
+        for line in snippet.lines() {
+            outln!("  {}", line);
+        }
+    }
+    Ok(())
+}
+
+/// Prints how the witness generator computes the signal of `zkfuzz explain --signal`, i.e. the
+/// trace constraints assigning it, next to the side constraints checking it, followed by the
+/// sub-expressions found on one side only.
+fn show_signal_explanation(user_input: &Input, sym_executor: &SymbolicExecutor) -> Result<(), ()> {
+    let signal = user_input.signal();
+    let explanation = explain_signal(
+        &sym_executor.cur_state.symbolic_trace,
+        &sym_executor.cur_state.side_constraints,
+        &sym_executor.symbolic_library.id2name,
+        &signal,
+    );
+    if explanation.is_empty() {
+        eoutln!(
+            "{} {} (no trace or side constraint mentions it)",
+            "Unknown signal:".red(),
+            signal
+        );
+        return Err(());
+    }
+
+    let prime = BigInt::from_str(&user_input.debug_prime()).unwrap();
+    let printer = PrettyPrinter::new(
+        PrettyFormat::Text,
+        &sym_executor.symbolic_library.id2name,
+        &prime,
+    );
+    let width: usize = user_input.line_width().parse().unwrap();
+    outln!("🔎 Signal: {}", signal);
+    for (title, constraints) in [
+        (
+            "🧮 Trace Constraints (computed by the witness generator)",
+            &explanation.definitions,
+        ),
+        (
+            "🔒 Side Constraints (checked by the verifier)",
+            &explanation.side_constraints,
+        ),
+    ] {
+        outln!("{}: {}", title, constraints.len());
+        for (index, constraint) in constraints {
+            for (i, line) in printer.render(constraint, width).iter().enumerate() {
+                if i == 0 {
+                    outln!("  #{:<4} {}", index, line);
+                } else {
+                    outln!("        {}", line);
+                }
+            }
+        }
+    }
+    if explanation.definitions.is_empty() {
+        outln!(
+            "  {}",
+            "The signal is not assigned, e.g. it is an input of the main component".yellow()
+        );
+    }
+    for (title, subterms) in [
+        ("➕ Only in the Trace", &explanation.trace_only),
+        ("➖ Only in the Side Constraints", &explanation.side_only),
+    ] {
+        if subterms.is_empty() {
+            continue;
+        }
+        outln!("{}: {}", title, subterms.len());
+        for subterm in subterms {
+            outln!("  {}", printer.fmt(subterm).yellow());
+        }
+    }
+    Ok(())
+}
 
-    let base_config = get_default_setting_for_symbolic_execution(
+/// Writes the test harness of `zkfuzz gen-tests` for the main template, whose inputs are drawn
+/// at random and whose outputs are computed with the trace semantics.
+fn generate_test_harness(
+    user_input: &Input,
+    sym_executor: &mut SymbolicExecutor,
+    main_template_name: &str,
+    template_param_names: Vec<String>,
+    template_param_values: Vec<Expression>,
+    input_spec: &InputSpec,
+) -> Result<(), ()> {
+    let language = HarnessLanguage::from_str(&user_input.harness_lang()).unwrap();
+    let num_cases: usize = user_input.num_cases().parse().unwrap();
+    let verification_base_config = BaseVerificationConfig {
+        target_template_name: main_template_name.to_string(),
+        prime: BigInt::from_str(&user_input.debug_prime()).unwrap(),
+        range: BigInt::from_str(&user_input.heuristics_range()).unwrap(),
+        quick_mode: false,
+        heuristics_mode: false,
+        progress_interval: 10000,
+        template_param_names: template_param_names,
+        template_param_values: template_param_values,
+        check_output_tags: false,
+        max_counterexamples: 1,
+        input_shapes: type_analysis_user::gather_input_shapes(
+            sym_executor.symbolic_library,
+            main_template_name,
+            &sym_executor.id2dimensions,
+        ),
+        input_spec: input_spec.clone(),
+    };
+    let seed = match user_input.seed().parse::<u64>().unwrap() {
+        0 => thread_rng().gen_range(1, u64::MAX),
+        seed => seed,
+    };
+    eoutln!(
+        "{} {}",
+        "🎲 Random Seed:".green(),
+        seed.to_string().bright_yellow()
+    );
+
+    let symbolic_trace = sym_executor.cur_state.symbolic_trace.clone();
+    let side_constraints = sym_executor.cur_state.side_constraints.clone();
+    let id2dimensions = sym_executor.id2dimensions.clone();
+    let subse_base_config = get_default_setting_for_concrete_execution(
         BigInt::from_str(&user_input.debug_prime()).unwrap(),
         user_input.constraint_assert_dissabled_flag(),
     );
-    let mut sym_executor = SymbolicExecutor::new(&mut symbolic_library, &base_config);
+    let mut conc_executor =
+        SymbolicExecutor::new(&mut sym_executor.symbolic_library, &subse_base_config);
+    let cases = generate_test_cases(
+        &mut conc_executor,
+        &symbolic_trace,
+        &side_constraints,
+        &verification_base_config,
+        &id2dimensions,
+        num_cases,
+        seed,
+    );
 
-    match &program_archive.initial_template_call {
-        Expression::Call { id, args, .. } => {
-            let template = program_archive.templates[id].clone();
+    let circuit = user_input.input_program.as_path();
+    let output = if user_input.harness_output() == "none" {
+        let stem = circuit.file_stem().unwrap_or_default().to_string_lossy();
+        circuit.with_file_name(format!("{}_test.{}", stem, language.extension()))
+    } else {
+        Path::new(&user_input.harness_output()).to_path_buf()
+    };
+    let output_dir = match output.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir.to_path_buf(),
+        _ => Path::new(".").to_path_buf(),
+    };
+    let harness = render_harness(
+        language,
+        main_template_name,
+        &relative_path(&output_dir, circuit),
+        &cases,
+    );
+    std::fs::write(&output, harness)
+        .map_err(|e| eoutln!("{} {}", "Unable to write the test harness:".red(), e))?;
+
+    if cases.len() < num_cases {
+        eoutln!(
+            "{} only {} of {} requested cases were generated, as the circuit rejected the other drawn inputs",
+            "⚠️ Test Cases:".yellow(),
+            cases.len(),
+            num_cases
+        );
+    }
+    eoutln!(
+        "{} {} ({} cases)",
+        "💾 Saving the test harness to:",
+        output.display().to_string().cyan(),
+        cases.len()
+    );
+    Result::Ok(())
+}
+
+fn load_whitelist(user_input: &Input) -> FxHashSet<String> {
+    eoutln!("{}", "🧾 Loading Whitelists...".green());
+    if user_input.path_to_whitelist() == "none" {
+        FxHashSet::from_iter(["IsZero".to_string(), "Num2Bits".to_string()])
+    } else {
+        FxHashSet::from_iter(
+            read_file_to_lines(&user_input.path_to_whitelist())
+                .unwrap()
+                .into_iter(),
+        )
+    }
+}
+
+/// Parses the circuit given to --stdin. Its includes are resolved against the current directory
+/// and then the link libraries.
+fn parse_stdin(user_input: &Input) -> Result<ProgramArchive, ()> {
+    let mut source = String::new();
+    io::stdin()
+        .read_to_string(&mut source)
+        .map_err(|e| eoutln!("{} {}", "Unable to read the circuit from stdin:".red(), e))?;
+    let mut link_libraries = vec![env::current_dir()
+        .map_err(|e| eoutln!("{} {}", "Cannot locate the current directory:".red(), e))?];
+    link_libraries.extend(user_input.get_link_libraries().iter().cloned());
+    parser_user::parse_source(&source, |_| None, &user_input.prime(), &link_libraries)
+}
 
-            eprintln!("{}", "🛒 Gathering Trace/Side Constraints...".green());
+/// Parses a circuit given to `zkfuzz diff` and registers its templates and functions.
+fn load_program(
+    input_file: &Path,
+    user_input: &Input,
+    whitelist: &FxHashSet<String>,
+    main_params: Option<&Value>,
+) -> Result<(ProgramArchive, SymbolicLibrary), ()> {
+    let mut program_archive = parser_user::parse_project(
+        input_file,
+        &user_input.prime(),
+        user_input.get_link_libraries(),
+    )?;
+    type_analysis_user::analyse_project(&mut program_archive)?;
+
+    let mut symbolic_library = SymbolicLibrary::default();
+    if !user_input.lessthan_dissabled_flag {
+        symbolic_library.register_template_semantics(Rc::new(LessThanSemantics));
+    }
+    register_program(&mut symbolic_library, &program_archive, whitelist, false);
+    if let Some(params) = main_params {
+        apply_main_params(&mut program_archive, &symbolic_library, params)?;
+    }
+    Ok((program_archive, symbolic_library))
+}
+
+/// Fails if some components are never executed because their inputs are not all assigned, as
+/// their constraints would be missing from the analysis.
+fn check_incomplete_components(sym_executor: &SymbolicExecutor) -> Result<(), ()> {
+    if sym_executor.incomplete_components.is_empty() {
+        return Ok(());
+    }
+    eoutln!(
+        "{} {}",
+        "🧩 Incomplete Components:".red(),
+        sym_executor.incomplete_components.len()
+    );
+    for component in &sym_executor.incomplete_components {
+        eoutln!("  ├─ {}", component.message());
+    }
+    Result::Err(())
+}
 
+/// Executes the main template of a circuit given to `zkfuzz diff` and returns its name.
+fn execute_main_template(
+    sym_executor: &mut SymbolicExecutor,
+    program_archive: &ProgramArchive,
+    user_input: &Input,
+) -> Result<String, ()> {
+    match &program_archive.initial_template_call {
+        Expression::Call { id, args, .. } => {
+            let main_template_param_names =
+                get_main_template_param_names(program_archive, sym_executor.symbolic_library, id);
             sym_executor.symbolic_library.name2id.insert(
                 "main".to_string(),
                 sym_executor.symbolic_library.name2id.len(),
@@ -228,7 +883,6 @@ fn start() -> Result<(), ()> {
                 sym_executor.symbolic_library.name2id["main"],
                 "main".to_string(),
             );
-
             sym_executor.cur_state.add_owner(&OwnerName {
                 id: sym_executor.symbolic_library.name2id["main"],
                 counter: 0,
@@ -237,9 +891,8 @@ fn start() -> Result<(), ()> {
             sym_executor
                 .cur_state
                 .set_template_id(sym_executor.symbolic_library.name2id[id]);
-
             if !user_input.flag_symbolic_template_params {
-                sym_executor.feed_arguments(template.get_name_of_params(), args);
+                sym_executor.feed_arguments(&main_template_param_names, args);
             }
 
             let body = sym_executor.symbolic_library.template_library
@@ -247,287 +900,953 @@ fn start() -> Result<(), ()> {
                 .body
                 .clone();
             sym_executor.execute(&body, 0);
+            sym_executor.record_incomplete_components();
+            check_incomplete_components(sym_executor)?;
+            Ok(id.clone())
+        }
+        _ => Result::Err(eoutln!("{}", "Cannot Find Main Call".red())),
+    }
+}
 
-            eprintln!("{}", "══════════════════════════════════".green());
-            let mut ts = ConstraintStatistics::new();
-            let mut ss = ConstraintStatistics::new();
-            for c in &sym_executor.cur_state.symbolic_trace {
-                ts.update(c);
-            }
-            for c in &sym_executor.cur_state.side_constraints {
-                ss.update(c);
+/// Compares the trace/side constraints of the two circuits given to `zkfuzz diff`.
+fn diff_circuits(user_input: &Input) -> Result<(), ()> {
+    init_logger(&user_input.log_level());
+    let whitelist = load_whitelist(user_input);
+    let mut setting = get_default_setting_for_symbolic_execution(
+        BigInt::from_str(&user_input.debug_prime()).unwrap(),
+        user_input.constraint_assert_dissabled_flag(),
+    );
+    setting.max_index_case_splits = user_input.max_index_case_splits().parse().unwrap();
+    setting.function_summary_capacity = user_input.function_summary_capacity().parse().unwrap();
+    setting.max_call_depth = user_input.max_call_depth().parse().unwrap();
+
+    // Both versions are analysed with the same parameters
+    let main_params = load_main_params(user_input)?;
+
+    let (old_archive, mut old_library) = load_program(
+        &user_input.input_program,
+        user_input,
+        &whitelist,
+        main_params.as_ref(),
+    )?;
+    let new_input_program = Path::new(&user_input.new_input()).to_path_buf();
+    let (new_archive, mut new_library) = load_program(
+        &new_input_program,
+        user_input,
+        &whitelist,
+        main_params.as_ref(),
+    )?;
+
+    eoutln!("{}", "🛒 Gathering Trace/Side Constraints...".green());
+    let mut old_executor = SymbolicExecutor::new(&mut old_library, &setting);
+    let old_main_template_name =
+        execute_main_template(&mut old_executor, &old_archive, user_input)?;
+    let mut new_executor = SymbolicExecutor::new(&mut new_library, &setting);
+    let new_main_template_name =
+        execute_main_template(&mut new_executor, &new_archive, user_input)?;
+
+    let diffs = diff_constraints(
+        &old_executor,
+        &old_main_template_name,
+        &new_executor,
+        &new_main_template_name,
+    );
+    eoutln!("{}", "══════════════════════════════════".green());
+    if diffs.is_empty() {
+        eoutln!(
+            "{}",
+            "🔀 The two versions have the same trace/side constraints".green()
+        );
+        return Result::Ok(());
+    }
+    eoutln!(
+        "{} {} template(s) with different constraints",
+        "🔀 Constraint Diff:".yellow(),
+        diffs.len()
+    );
+    for diff in &diffs {
+        eoutln!("📄 {}", diff.template_name.cyan());
+        for (kind, constraints) in [("Trace", &diff.trace), ("Side", &diff.side)] {
+            if constraints.is_empty() {
+                continue;
             }
-            debug!(
-                "Final State: {}",
-                sym_executor
-                    .cur_state
-                    .lookup_fmt(&sym_executor.symbolic_library.id2name)
+            eoutln!(
+                "  ├─ {} Constraints: {} added, {} removed, {} changed",
+                kind,
+                constraints.added.len(),
+                constraints.removed.len(),
+                constraints.changed.len()
             );
+            for constraint in &constraints.removed {
+                eoutln!("  │   {} {}", "-".red(), constraint.red());
+            }
+            for constraint in &constraints.added {
+                eoutln!("  │   {} {}", "+".green(), constraint.green());
+            }
+            for (old_constraint, new_constraint) in &constraints.changed {
+                eoutln!("  │   {} {}", "~".yellow(), old_constraint);
+                eoutln!("  │     {} {}", "→".yellow(), new_constraint);
+            }
+        }
+    }
+    Result::Ok(())
+}
 
-            let mut is_safe = true;
-            if user_input.search_mode != "off" {
-                eprintln!("{}", "══════════════════════════════════".green());
-                eprintln!("{}", "🩺 Scanning TCCT Instances...".green());
-
-                let (main_template_name, template_param_names, template_param_values) =
-                    match &program_archive.initial_template_call {
-                        Expression::Call { id, args, .. } => {
-                            let template = &program_archive.templates[id];
-                            (id, template.get_name_of_params().clone(), args.clone())
-                        }
-                        _ => unimplemented!(),
-                    };
-
-                let verification_base_config = BaseVerificationConfig {
-                    target_template_name: main_template_name.to_string(),
-                    prime: BigInt::from_str(&user_input.debug_prime()).unwrap(),
-                    range: BigInt::from_str(&user_input.heuristics_range()).unwrap(),
-                    quick_mode: &*user_input.search_mode == "quick",
-                    heuristics_mode: &*user_input.search_mode == "heuristics",
-                    progress_interval: 10000,
-                    template_param_names: template_param_names,
-                    template_param_values: template_param_values,
-                };
-
-                let mut new_base_config = base_config.clone();
-                new_base_config.off_trace = true;
-                sym_executor.setting = &new_base_config;
-
-                let mut counter_example =
-                    check_unused_outputs(&mut sym_executor, &verification_base_config);
-                let mut auxiliary_result = json!({});
-                if let Some(_) = &counter_example {
-                    is_safe = false;
-                } else {
-                    let subse_base_config = get_default_setting_for_concrete_execution(
-                        BigInt::from_str(&user_input.debug_prime()).unwrap(),
-                        user_input.constraint_assert_dissabled_flag(),
-                    );
-                    let mut conc_executor = SymbolicExecutor::new(
-                        &mut sym_executor.symbolic_library,
-                        &subse_base_config,
-                    );
-                    conc_executor.feed_arguments(
-                        &verification_base_config.template_param_names,
-                        &verification_base_config.template_param_values,
-                    );
-
-                    counter_example = match &*user_input.search_mode() {
-                        "quick" => brute_force_search(
-                            &mut conc_executor,
-                            &sym_executor.cur_state.symbolic_trace.clone(),
-                            &sym_executor.cur_state.side_constraints.clone(),
-                            &verification_base_config,
-                        ),
-                        "full" => brute_force_search(
-                            &mut conc_executor,
-                            &sym_executor.cur_state.symbolic_trace.clone(),
-                            &sym_executor.cur_state.side_constraints.clone(),
-                            &verification_base_config,
-                        ),
-                        "heuristics" => brute_force_search(
-                            &mut conc_executor,
-                            &sym_executor.cur_state.symbolic_trace.clone(),
-                            &sym_executor.cur_state.side_constraints.clone(),
-                            &verification_base_config,
-                        ),
-                        "ga" => {
-                            let mutation_config =
-                                load_config_from_json(&&user_input.path_to_mutation_setting())
-                                    .unwrap();
-                            info!("\n{}", mutation_config);
-
-                            let trace_initialization_fn = match mutation_config.trace_mutation_method.as_str() {
-                                "naive" => initialize_population_with_constant_replacement,
-                                "constant" => initialize_population_with_constant_replacement,
-                                "constant_operator" => initialize_population_with_operator_or_const_replacement,
-                                "constant_operator_add" => initialize_population_with_operator_or_const_replacement_or_addition,
-                                "constant_operator_delete" => initialize_population_with_operator_or_const_replacement_or_deletion,
-                                _ => panic!("`trace_mutation_method` should be one of [`constant`, `constant_operator`, `constant_operator_add`, `constant_operator_delete`]")
-                            };
-
-                            let trace_mutation_fn = match mutation_config.trace_mutation_method.as_str() {
-                                "naive" => mutate_trace_with_constant_replacement,
-                                "constant" => mutate_trace_with_constant_replacement,
-                                "constant_operator" => mutate_trace_with_operator_or_const_replacement,
-                                "constant_operator_add" => mutate_trace_with_operator_or_const_replacement_or_addition,
-                                "constant_operator_delete" => mutate_trace_with_operator_or_const_replacement_or_deletion,
-                                _ => panic!("`trace_mutation_method` should be one of [`constant`, `constant_operator`, `constant_operator_add`, `constant_operator_delete`]")
-                            };
-
-                            let update_input_fn = match mutation_config
-                                .input_initialization_method
-                                .as_str()
-                            {
-                                "random" => update_input_population_with_random_sampling,
-                                "fitness" => update_input_population_with_fitness_score,
-                                "coverage" => update_input_population_with_coverage_maximization,
-                                _ => panic!("`input_initialization_method` should be one of [`random`, `fitness`, `coverage`]")
-                            };
-
-                            let result = mutation_test_search(
-                                &mut conc_executor,
-                                &sym_executor.cur_state.symbolic_trace.clone(),
-                                &sym_executor.cur_state.side_constraints.clone(),
-                                &verification_base_config,
-                                &mutation_config,
-                                trace_initialization_fn,
-                                update_input_fn,
-                                evaluate_trace_fitness_by_error,
-                                simple_evolution,
-                                trace_mutation_fn,
-                                random_crossover,
-                                roulette_selection,
-                            );
-                            auxiliary_result["mutation_test_config"] =
-                                serde_json::to_value(result.mutation_config)
-                                    .expect("Failed to serialize to JSON");
-                            auxiliary_result["mutation_test_log"] = json!({"random_seed":result.random_seed,"generation":result.generation, "fitness_score_log":result.fitness_score_log});
-                            result.counter_example
-                        }
-                        _ => panic!(
-                            "search_mode={} is not supported",
-                            user_input.search_mode.to_string()
-                        ),
-                    };
-                }
-                if let Some(ce) = &counter_example {
-                    is_safe = false;
-                    if user_input.flag_save_output {
-                        // Save the output as JSON
-                        let ce_meta = FxHashMap::from_iter([
-                            (
-                                "0_target_path".to_string(),
-                                user_input.input_file().to_string(),
-                            ),
-                            ("1_main_template".to_string(), id.to_string()),
-                            ("2_search_mode".to_string(), user_input.search_mode()),
-                            (
-                                "3_execution_time".to_string(),
-                                format!("{:?}", start_time.elapsed()),
-                            ),
-                            (
-                                "4_git_hash_of_zkfuzz".to_string(),
-                                format!("{}", option_env!("GIT_HASH").unwrap_or("unknown")),
-                            ),
-                        ]);
-
-                        let mut json_output =
-                            ce.to_json_with_meta(&sym_executor.symbolic_library.id2name, &ce_meta);
-                        json_output["8_auxiliary_result"] = auxiliary_result;
-
-                        let mut file_path = user_input.input_file().to_string();
-                        file_path.push('_');
-                        let random_string: String = thread_rng()
-                            .sample_iter(&Alphanumeric)
-                            .take(10)
-                            .map(char::from)
-                            .collect();
-                        file_path.push_str(&random_string);
-                        file_path.push_str("_counterexample.json");
-                        eprintln!("{} {}", "💾 Saving the output to:", file_path.cyan(),);
-
-                        let mut file = File::create(file_path).expect("Unable to create file");
-                        let json_string = serde_json::to_string_pretty(&json_output).unwrap();
-                        file.write_all(json_string.as_bytes())
-                            .expect("Unable to write data");
-                    } else {
-                        eprintln!("{}", ce.lookup_fmt(&sym_executor.symbolic_library.id2name));
-                    }
-                }
+/// Moves the constraints gathered by `sym_executor` into stores of the kind given to
+/// `--constraint_store`, so that they can be spilled to disk.
+fn move_constraints_to_stores(
+    user_input: &Input,
+    sym_executor: &mut SymbolicExecutor,
+) -> Result<(Box<dyn ConstraintStore>, Box<dyn ConstraintStore>), ()> {
+    let (mut trace_store, mut side_store) = match (
+        new_constraint_store(&user_input.constraint_store()),
+        new_constraint_store(&user_input.constraint_store()),
+    ) {
+        (Ok(trace_store), Ok(side_store)) => (trace_store, side_store),
+        (Err(e), _) | (_, Err(e)) => {
+            return Result::Err(eoutln!(
+                "{} {}",
+                "Unable to create the constraint store:".red(),
+                e
+            ))
+        }
+    };
+    for c in std::mem::take(&mut sym_executor.cur_state.symbolic_trace) {
+        trace_store.push(&c);
+    }
+    for c in std::mem::take(&mut sym_executor.cur_state.side_constraints) {
+        side_store.push(&c);
+    }
+    Result::Ok((trace_store, side_store))
+}
+
+/// A circuit whose main component `analyse_circuit` analyses.
+struct AnalysedCircuit<'a> {
+    program_archive: &'a ProgramArchive,
+    /// The main template and the arguments of the main component.
+    template_name: &'a String,
+    args: &'a Vec<Expression>,
+    param_names: Vec<String>,
+    /// The public inputs of the main component.
+    public_inputs: &'a [String],
+    /// The id of the file declaring the main component in the file library of the program.
+    file_id: usize,
+    start_time: time::Instant,
+}
+
+/// Analyses the main component of `circuit` once `sym_executor` has gathered its constraints:
+/// runs the detectors and the search selected by `user_input`, reports the findings, and writes
+/// the files of the run (the counterexamples, the summary, the HTML report, etc.).
+///
+/// The search is run by `search_user`, the report by `report_user`, and the files are written
+/// by the `ArtifactWriter`.
+///
+/// `start` analyses the input file with it, and `analyse_entries` each entry given to `--entry`.
+///
+/// # Parameters
+/// - `search_setting`: The setting of `sym_executor` while the constraints are searched, which
+///   does not record the trace again.
+/// - `result_cache`: The cache where the result is stored, if the run is cacheable.
+///
+/// # Returns
+/// The result of the circuit, whose most severe finding decides the exit code.
+fn analyse_circuit<'a>(
+    user_input: &Input,
+    sym_executor: &mut SymbolicExecutor<'a>,
+    search_setting: &'a SymbolicExecutorSetting,
+    circuit: &AnalysedCircuit,
+    detectors: &[Rc<dyn Detector>],
+    input_spec: &InputSpec,
+    artifacts: &mut ArtifactWriter,
+    result_cache: Option<&ResultCache>,
+) -> Result<EntryResult, ()> {
+    let id = circuit.template_name;
+
+    eoutln!("{}", "══════════════════════════════════".green());
+    debug!(
+        "Final State: {}",
+        sym_executor
+            .cur_state
+            .lookup_fmt(&sym_executor.symbolic_library.id2name)
+    );
+
+    let function_summaries = &sym_executor.function_summaries;
+    let function_calls = function_summaries.hits + function_summaries.misses;
+    if function_calls > 0 {
+        eoutln!(
+            "{} {}/{} calls served from the cache ({:.1}% hit rate)",
+            "📚 Function Summaries:".green(),
+            function_summaries.hits,
+            function_calls,
+            function_summaries.hit_rate() * 100.0
+        );
+    }
+
+    let recursion_error = sym_executor
+        .recursion_error
+        .as_ref()
+        .map(|error| error.message());
+    if let Some(message) = &recursion_error {
+        eoutln!("{} {}", "🔁 Unbounded Recursion:".yellow(), message);
+    }
+
+    let static_findings = StaticFindings::gather(sym_executor, circuit.program_archive, id);
+
+    // Findings of the detectors other than the search, streamed by `--findings_stream`
+    // as they are recorded and collected for the summary and the HTML report
+    let findings_stream = user_input.findings_stream();
+    let mut findings = FindingStream::create(match findings_stream.as_str() {
+        "none" => None,
+        path => Some(Path::new(path)),
+    })
+    .map_err(|e| eoutln!("{} {}", "Unable to create the findings stream:".red(), e))?;
+    if findings_stream != "none" {
+        artifacts.record("findings_stream", &findings_stream);
+    }
+    static_findings.stream(&mut findings, recursion_error.clone());
+
+    if user_input.flag_overflow_audit {
+        report_integer_overflows(user_input, sym_executor, &mut findings);
+    }
+
+    simplify_gathered_constraints(user_input, sym_executor);
+
+    let (trace_store, side_store) = move_constraints_to_stores(user_input, sym_executor)?;
+    if user_input.path_to_constraints() != "none" {
+        artifacts.save_constraints(
+            &user_input.path_to_constraints(),
+            trace_store.as_ref(),
+            side_store.as_ref(),
+            &sym_executor.symbolic_library.id2name,
+        );
+    }
+    if user_input.export_constraints() != "none" {
+        artifacts.export_constraints(
+            &user_input.export_constraints(),
+            side_store.as_ref(),
+            sym_executor.symbolic_library,
+            id,
+            &BigInt::from_str(&user_input.debug_prime()).unwrap(),
+        );
+    }
+
+    let mut trace_stats = ConstraintStatistics::new();
+    let mut side_stats = ConstraintStatistics::new();
+    trace_stats.update_from_store(trace_store.as_ref());
+    side_stats.update_from_store(side_store.as_ref());
+    let cost_estimate = estimate_cost(side_store.as_ref(), &user_input.plonk_gate_widths());
+    let compression_anomalies = find_compression_anomalies(
+        &trace_stats,
+        &side_stats,
+        &sym_executor.symbolic_library.id2name,
+        COMPRESSION_ANOMALY_THRESHOLD,
+        COMPRESSION_ANOMALY_TOP_K,
+    );
+
+    let mut verdict = "Skipped".to_string();
+    let mut search = None;
+    let mut counter_examples = ExaminedCounterExamples::default();
+    if user_input.search_mode != "off" {
+        let mut outcome = search_counter_examples(
+            user_input,
+            sym_executor,
+            search_setting,
+            circuit,
+            detectors,
+            input_spec,
+            trace_store.as_ref(),
+            side_store.as_ref(),
+            &compression_anomalies,
+            static_findings.auxiliary_result(recursion_error),
+            &mut findings,
+        )?;
+        counter_examples = examine_counter_examples(
+            user_input,
+            sym_executor,
+            circuit,
+            trace_store.as_ref(),
+            &mut outcome,
+            artifacts,
+            &mut findings,
+        );
+        verdict = match &outcome.counter_example {
+            Some(ce) => ce.flag.to_json()["1_type"]
+                .as_str()
+                .unwrap_or("Unknown")
+                .to_string(),
+            None => "NoCounterExample".to_string(),
+        };
+        search = Some(outcome);
+    }
+
+    let analysis = CircuitAnalysis {
+        static_findings,
+        trace_stats,
+        side_stats,
+        cost_estimate,
+        compression_anomalies,
+        verdict,
+        search,
+        counter_examples,
+    };
+    report_circuit(
+        user_input,
+        sym_executor,
+        circuit,
+        trace_store.as_ref(),
+        analysis,
+        findings,
+        artifacts,
+        result_cache,
+    )
+}
+
+/// Returns the path of a file written for a single circuit, such as `--path_to_summary`, for the
+/// entry at `entry`: the file stem of the entry is appended to that of `path` (e.g.
+/// `summary_main_a.json`), so that the entries do not overwrite each other's files.
+fn entry_output_path(path: &str, entry: &Path) -> String {
+    if path == "none" {
+        return path.to_string();
+    }
+    let path = Path::new(path);
+    let entry_stem = entry
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let stem = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let file_name = match path.extension() {
+        Some(extension) => format!("{}_{}.{}", stem, entry_stem, extension.to_string_lossy()),
+        None => format!("{}_{}", stem, entry_stem),
+    };
+    path.with_file_name(file_name).to_string_lossy().to_string()
+}
+
+/// Returns the options of the analysis of the entry at `entry`: those of `user_input`, with the
+/// entry as the input file and the files written for a single circuit named after the entry (see
+/// `entry_output_path`).
+fn input_for_entry(user_input: &Input, entry: &Path) -> Input {
+    let mut entry_input = user_input.clone();
+    entry_input.input_program = entry.to_path_buf();
+    for path in [
+        &mut entry_input.path_to_summary,
+        &mut entry_input.path_to_constraints,
+        &mut entry_input.export_constraints,
+        &mut entry_input.report_html,
+        &mut entry_input.component_stats_csv,
+        &mut entry_input.findings_stream,
+    ] {
+        *path = entry_output_path(path, entry);
+    }
+    entry_input
+}
+
+/// Analyses an entry given to `--entry` with the symbolic library shared by all the entries.
+///
+/// The main component of the entry is executed with its own executor and then analysed as a
+/// single circuit (see `analyse_circuit`), with the options of `input_for_entry`.
+fn analyse_entry(
+    user_input: &Input,
+    symbolic_library: &mut SymbolicLibrary,
+    program_archive: &ProgramArchive,
+    setting: &SymbolicExecutorSetting,
+    search_setting: &SymbolicExecutorSetting,
+    detectors: &[Rc<dyn Detector>],
+    input_spec: &InputSpec,
+    entry: &parser_user::EntryPoint,
+) -> Result<EntryResult, ()> {
+    let start_time = time::Instant::now();
+    let (id, args) = match &entry.main_call {
+        Expression::Call { id, args, .. } => (id, args),
+        _ => return Result::Err(eoutln!("{}", "Cannot Find Main Call".red())),
+    };
+    let main_template_param_names =
+        get_main_template_param_names(program_archive, symbolic_library, id);
+
+    // The executors of the previous entries are dropped, so the names they interned can go
+    reset_interner();
+    symbolic_library.clear_function_counter();
+    let mut sym_executor = SymbolicExecutor::new(symbolic_library, setting);
+    sym_executor.cur_state.add_owner(&OwnerName {
+        id: sym_executor.symbolic_library.name2id["main"],
+        counter: 0,
+        access: None,
+    });
+    sym_executor
+        .cur_state
+        .set_template_id(sym_executor.symbolic_library.name2id[id]);
+    if !user_input.flag_symbolic_template_params {
+        sym_executor.feed_arguments(&main_template_param_names, args);
+    }
+    let body = sym_executor.symbolic_library.template_library
+        [&sym_executor.symbolic_library.name2id[id]]
+        .body
+        .clone();
+    sym_executor.execute(&body, 0);
+    sym_executor.record_incomplete_components();
+    check_incomplete_components(&sym_executor)?;
+
+    let mut artifacts = ArtifactWriter::new(&user_input.out_dir(), &entry.path)
+        .map_err(|e| eoutln!("{} {}", "Unable to create the output directory:".red(), e))?;
+    // The main template of an entry is usually declared by the entry itself, whose copy is the
+    // file of the template in the program parsed with the other entries
+    let circuit = AnalysedCircuit {
+        program_archive: program_archive,
+        template_name: id,
+        args: args,
+        param_names: main_template_param_names,
+        public_inputs: &entry.public_inputs,
+        file_id: program_archive.templates[id].get_file_id(),
+        start_time: start_time,
+    };
+    analyse_circuit(
+        user_input,
+        &mut sym_executor,
+        search_setting,
+        &circuit,
+        detectors,
+        input_spec,
+        &mut artifacts,
+        None,
+    )
+}
+
+/// Analyses the circuits given to `--entry` and returns their most severe finding.
+///
+/// The entries are parsed together (see `parser_user::parse_entries`), so that the templates and
+/// functions of the files they share are parsed, type-checked and registered once in a single
+/// symbolic library. Each entry is then executed with its own executor and analysed in turn as a
+/// single circuit (see `analyse_entry`). The entries are not analysed in parallel, since the
+/// symbolic values of the library are reference-counted and cannot be shared across threads;
+/// `--input_dir` runs independent circuits in separate processes instead.
+fn analyse_entries(
+    user_input: &Input,
+    detectors: &[Rc<dyn Detector>],
+    min_severity: Severity,
+) -> Result<Option<Severity>, ()> {
+    init_logger(&user_input.log_level());
+
+    let (mut program_archive, entries) = parser_user::parse_entries(
+        user_input.get_entries(),
+        &user_input.prime(),
+        user_input.get_link_libraries(),
+    )?;
+    type_analysis_user::analyse_project(&mut program_archive)?;
+
+    let whitelist = load_whitelist(user_input);
+    let mut symbolic_library = SymbolicLibrary::default();
+    if !user_input.lessthan_dissabled_flag {
+        symbolic_library.register_template_semantics(Rc::new(LessThanSemantics));
+    }
+    register_program(
+        &mut symbolic_library,
+        &program_archive,
+        &whitelist,
+        user_input.flag_printout_ast,
+    );
+    // Every entry is executed as the main component
+    symbolic_library
+        .name2id
+        .insert("main".to_string(), symbolic_library.name2id.len());
+    symbolic_library
+        .id2name
+        .insert(symbolic_library.name2id["main"], "main".to_string());
+
+    let input_spec = if user_input.input_spec() == "none" {
+        InputSpec::default()
+    } else {
+        load_input_spec(&user_input.input_spec())
+            .map_err(|e| eoutln!("{} {}", "Invalid input specification:".red(), e))?
+    };
+    let mut setting = get_default_setting_for_symbolic_execution(
+        BigInt::from_str(&user_input.debug_prime()).unwrap(),
+        user_input.constraint_assert_dissabled_flag(),
+    );
+    setting.max_index_case_splits = user_input.max_index_case_splits().parse().unwrap();
+    setting.function_summary_capacity = user_input.function_summary_capacity().parse().unwrap();
+    setting.max_call_depth = user_input.max_call_depth().parse().unwrap();
+    let mut search_setting = setting.clone();
+    search_setting.off_trace = true;
+
+    let mut results = Vec::new();
+    for (i, entry) in entries.iter().enumerate() {
+        eoutln!("{}", "══════════════════════════════════".green());
+        eoutln!(
+            "{} [{}/{}] {}",
+            "🚪 Entry:".green(),
+            i + 1,
+            entries.len(),
+            entry.path.display()
+        );
+        let start_time = time::Instant::now();
+        let mut result = analyse_entry(
+            &input_for_entry(user_input, &entry.path),
+            &mut symbolic_library,
+            &program_archive,
+            &setting,
+            &search_setting,
+            detectors,
+            &input_spec,
+            entry,
+        )
+        .unwrap_or_else(|_| {
+            let name = match &entry.main_call {
+                Expression::Call { id, .. } => id.as_str(),
+                _ => "",
+            };
+            EntryResult::failed(&entry.path, name)
+        });
+        result.execution_time_ms = start_time.elapsed().as_millis();
+        results.push(result);
+    }
+
+    batch_user::report_entries(user_input, &results, min_severity)?;
+    let max_severity = results.iter().filter_map(|r| r.max_severity).max();
+    Ok(max_severity)
+}
+
+/// Returns whether the result of the run can be served from the cache, i.e., whether it is a
+/// search of a single circuit whose only outputs are the report, the summary and the HTML report.
+/// With `--out_dir`, every file of the run is listed in the manifest, so the run is not cached.
+fn is_cacheable(user_input: &Input) -> bool {
+    !user_input.flag_no_cache
+        && !user_input.flag_stdin
+        && matches!(user_input.subcommand(), "fuzz" | "run")
+        && user_input.input_dir() == "none"
+        && user_input.load_state() == "none"
+        && user_input.save_state() == "none"
+        && user_input.trace_jsonl() == "none"
+        && user_input.findings_stream() == "none"
+        && user_input.baseline() == "none"
+        && user_input.path_to_constraints() == "none"
+        && user_input.export_constraints() == "none"
+        && user_input.component_stats_csv() == "none"
+        && user_input.export_cfg() == "none"
+        && user_input.out_dir() == "none"
+        && !user_input.show_stats_of_ast
+        && !user_input.flag_printout_ast
+        && !user_input.flag_printout_stats
+        && !user_input.flag_printout_stats_csv
+}
+
+fn report_cached_result(
+    user_input: &Input,
+    cache: &ResultCache,
+    cached: &CachedResult,
+) -> Result<(), ()> {
+    eoutln!(
+        "{} {} ({} trace constraints, verdict: {})",
+        "📦 Cached Result:".green(),
+        cache.path().display().to_string().cyan(),
+        cached.symbolic_trace.len(),
+        cached.verdict
+    );
+    for line in &cached.report {
+        eoutln!("{}", line);
+    }
+    eoutln!(
+        " └─ Execution Time    : {:?} {}",
+        time::Duration::from_millis(cached.execution_time_ms),
+        "(cached, use --no-cache to run the analysis again)".yellow()
+    );
+
+    if user_input.path_to_summary() != "none" {
+        let mut summary = cached.summary.clone();
+        summary["cached"] = json!(true);
+        let mut file = File::create(user_input.path_to_summary()).expect("Unable to create file");
+        file.write_all(summary.to_string().as_bytes())
+            .expect("Unable to write data");
+    }
+    if user_input.report_html() != "none" {
+        // A cached result is only served for `--report_html` if it includes the report.
+        let report_html = cached.report_html.as_ref().unwrap();
+        eoutln!(
+            "{} {}",
+            "💾 Saving the HTML report to:",
+            user_input.report_html().cyan()
+        );
+        let mut file = File::create(user_input.report_html()).expect("Unable to create file");
+        file.write_all(report_html.as_bytes())
+            .expect("Unable to write data");
+    }
+    eoutln!(
+        "{}",
+        "════════════════════════════════════════════════════════════════".green()
+    );
+    exit_on_findings(
+        user_input,
+        cached.summary["max_severity"]
+            .as_str()
+            .and_then(|severity| Severity::from_str(severity).ok()),
+    );
+    Result::Ok(())
+}
+
+/// Exits with the code of `max_severity`, the most severe finding of the run, if it is at least
+/// the severity of `--fail_on`.
+fn exit_on_findings(user_input: &Input, max_severity: Option<Severity>) {
+    let fail_on = match Severity::from_str(&user_input.fail_on()) {
+        Ok(fail_on) => fail_on,
+        Err(_) => return,
+    };
+    if let Some(severity) = max_severity.filter(|severity| *severity >= fail_on) {
+        eoutln!(
+            "{} {} (--fail_on {})",
+            "🚦 Failing on a finding of severity".red(),
+            severity.to_string().red().bold(),
+            fail_on
+        );
+        std::process::exit(severity.exit_code());
+    }
+}
+
+fn start() -> Result<(), ()> {
+    let start_time = time::Instant::now();
+    //use compilation_user::CompilerConfig;
+
+    let user_input = Input::new()?;
+
+    let detector_registry = DetectorRegistry::default();
+    if user_input.flag_list_detectors {
+        eoutln!("{}", "🔎 Available Detectors:".green());
+        for (name, description) in detector_registry.list() {
+            eoutln!("  ├─ {}: {}", name.cyan(), description);
+        }
+        return Result::Ok(());
+    }
+    let detectors = detector_registry
+        .select(&user_input.detectors())
+        .map_err(|name| eoutln!("{} {}", "Unknown detector:".red(), name))?;
+    let min_severity = Severity::from_str(&user_input.min_severity()).unwrap();
+
+    if user_input.input_dir() != "none" {
+        return batch_user::run_batch(&user_input);
+    }
+
+    if !user_input.get_entries().is_empty() {
+        let max_severity = analyse_entries(&user_input, &detectors, min_severity)?;
+        exit_on_findings(&user_input, max_severity);
+        return Result::Ok(());
+    }
+
+    if user_input.subcommand() == "compile-lib" {
+        return compile_library(&user_input);
+    }
+
+    if user_input.subcommand() == "regression" {
+        return run_regression(&user_input);
+    }
+
+    if user_input.subcommand() == "diff" {
+        return diff_circuits(&user_input);
+    }
+
+    if user_input.subcommand() == "trace" {
+        return replay_trace(&user_input);
+    }
+
+    if user_input.subcommand() == "serve" {
+        return server_user::run_server(&user_input);
+    }
+
+    let mut artifacts = ArtifactWriter::new(&user_input.out_dir(), &user_input.input_program)
+        .map_err(|e| eoutln!("{} {}", "Unable to create the output directory:".red(), e))?;
+
+    let result_cache = if is_cacheable(&user_input) {
+        // The settings of the project manifest are not part of the arguments.
+        let mut args: Vec<String> = env::args().skip(1).collect();
+        args.extend([
+            format!("--prime={}", user_input.prime()),
+            format!("--search_mode={}", user_input.search_mode()),
+            format!("--detectors={}", user_input.detectors()),
+            format!("--template_timeouts={}", user_input.template_timeouts()),
+        ]);
+        ResultCache::new(
+            Path::new(&user_input.cache_dir()),
+            &user_input.input_program,
+            user_input.get_link_libraries(),
+            &args,
+        )
+        .ok()
+    } else {
+        None
+    };
+    if let Some(cache) = &result_cache {
+        if let Some(cached) = cache.load() {
+            if user_input.report_html() == "none" || cached.report_html.is_some() {
+                return report_cached_result(&user_input, cache, &cached);
             }
+        }
+    }
 
-            eprintln!(
-                "{}",
-                "╔═══════════════════════════════════════════════════════════════╗".green()
-            );
-            eprintln!(
-                "{}",
-                "║                        zkFuzz Report                        ║".green()
-            );
-            eprintln!(
+    let linked_archive = if user_input.link() == "none" {
+        None
+    } else {
+        let archive = SymbolicLibraryArchive::load(Path::new(&user_input.link()))
+            .map_err(|e| eoutln!("{} {}", "Unable to load the library archive:".red(), e))?;
+        eoutln!(
+            "{} {} ({} templates, {} functions)",
+            "🔗 Linking Library Archive:".green(),
+            user_input.link().cyan(),
+            archive.template_names().len(),
+            archive.function_names().len()
+        );
+        Some(archive)
+    };
+
+    let mut program_archive = match &linked_archive {
+        None if user_input.flag_stdin => parse_stdin(&user_input)?,
+        Some(_) if user_input.flag_stdin => {
+            return Result::Err(eoutln!(
                 "{}",
-                "╚═══════════════════════════════════════════════════════════════╝".green()
+                "--stdin cannot be combined with --link".red()
+            ))
+        }
+        None => parser_user::parse_project(
+            &user_input.input_program,
+            &user_input.prime(),
+            user_input.get_link_libraries(),
+        )?,
+        Some(archive) => {
+            let stripped_file = parser_user::strip_linked_includes(
+                &user_input.input_program,
+                &archive.source_files,
+                user_input.get_link_libraries(),
+            )
+            .map_err(|e| eoutln!("{} {}", "Unable to read the input file:".red(), e))?;
+            let program_archive = parser_user::parse_project(
+                &stripped_file,
+                &user_input.prime(),
+                user_input.get_link_libraries(),
             );
-            eprintln!("{}", "📊 Execution Summary:".cyan().bold());
-            eprintln!(" ├─ Prime Number      : {}", user_input.debug_prime());
-            eprintln!(
-                " ├─ Compression Rate  : {:.2}% ({}/{})",
-                (ss.total_constraints as f64 / ts.total_constraints as f64) * 100 as f64,
-                ss.total_constraints,
-                ts.total_constraints
+            let _ = std::fs::remove_file(&stripped_file);
+            program_archive?
+        }
+    };
+    if linked_archive.is_none() {
+        type_analysis_user::analyse_project(&mut program_archive)?;
+    } else {
+        // The type checker of circom cannot see the templates and functions of the archive,
+        // which were type-checked when the archive was compiled.
+        eoutln!(
+            "{}",
+            "⚠️ Skipping the type analysis of circom for a circuit linked against a library archive"
+                .yellow()
+        );
+    }
+
+    if user_input.export_cfg() != "none" {
+        export_cfgs(&program_archive, Path::new(&user_input.export_cfg()))?;
+        artifacts.record("control_flow_graphs", &user_input.export_cfg());
+    }
+    if user_input.show_stats_of_ast {
+        show_stats(&program_archive);
+        return Result::Ok(());
+    }
+
+    init_logger(&user_input.log_level());
+
+    let whitelist = load_whitelist(&user_input);
+
+    let mut symbolic_library = SymbolicLibrary {
+        template_library: FxHashMap::default(),
+        name2id: FxHashMap::default(),
+        id2name: FxHashMap::default(),
+        function_library: FxHashMap::default(),
+        function_counter: FxHashMap::default(),
+        template_semantics: Vec::new(),
+    };
+    // A snapshot contains the templates and functions of the linked archive and of the program,
+    // with the IDs assigned to their names during the execution.
+    let restored_execution = if user_input.load_state() == "none" {
+        if let Some(archive) = linked_archive {
+            archive
+                .link_into(&mut symbolic_library, &whitelist)
+                .map_err(|e| eoutln!("{} {}", "Unable to link the library archive:".red(), e))?;
+        }
+        None
+    } else {
+        let snapshot = SymbolicSnapshot::load(Path::new(&user_input.load_state()))
+            .map_err(|e| eoutln!("{} {}", "Unable to load the snapshot:".red(), e))?;
+        let (num_trace_constraints, num_side_constraints) = snapshot.num_constraints();
+        eoutln!(
+            "{} {} (Trace: {}, Side: {})",
+            "📂 Loading Snapshot:".green(),
+            user_input.load_state().cyan(),
+            num_trace_constraints,
+            num_side_constraints
+        );
+        let main_template_name = snapshot.main_template_name.clone();
+        let restored = snapshot
+            .restore(&mut symbolic_library, &whitelist)
+            .map_err(|e| eoutln!("{} {}", "Unable to load the snapshot:".red(), e))?;
+        Some((main_template_name, restored))
+    };
+    if !user_input.lessthan_dissabled_flag {
+        symbolic_library.register_template_semantics(Rc::new(LessThanSemantics));
+    }
+
+    if restored_execution.is_none() {
+        register_program(
+            &mut symbolic_library,
+            &program_archive,
+            &whitelist,
+            user_input.flag_printout_ast,
+        );
+    }
+
+    if let Some(params) = load_main_params(&user_input)? {
+        apply_main_params(&mut program_archive, &symbolic_library, &params)?;
+    }
+
+    let input_spec = if user_input.input_spec() == "none" {
+        InputSpec::default()
+    } else {
+        load_input_spec(&user_input.input_spec())
+            .map_err(|e| eoutln!("{} {}", "Invalid input specification:".red(), e))?
+    };
+
+    let mut base_config = get_default_setting_for_symbolic_execution(
+        BigInt::from_str(&user_input.debug_prime()).unwrap(),
+        user_input.constraint_assert_dissabled_flag(),
+    );
+    base_config.max_index_case_splits = user_input.max_index_case_splits().parse().unwrap();
+    base_config.function_summary_capacity = user_input.function_summary_capacity().parse().unwrap();
+    base_config.max_call_depth = user_input.max_call_depth().parse().unwrap();
+    if user_input.trace_jsonl() != "none" {
+        let trace_log = TraceLog::create(Path::new(&user_input.trace_jsonl()))
+            .map_err(|e| eoutln!("{} {}", "Unable to create the execution trace:".red(), e))?;
+        base_config.trace_log = Some(Rc::new(trace_log));
+    }
+    // The search evaluates the constraints without recording them again
+    let mut search_config = base_config.clone();
+    search_config.off_trace = true;
+    let mut sym_executor = SymbolicExecutor::new(&mut symbolic_library, &base_config);
+
+    match &program_archive.initial_template_call {
+        Expression::Call { id, args, .. } => {
+            let main_template_param_names = get_main_template_param_names(
+                &program_archive,
+                sym_executor.symbolic_library,
+                id,
             );
-            eprintln!(
-                " ├─ Verification      : {}",
-                if is_safe {
-                    "🆗 No Counter Example Found".green().bold()
-                } else {
-                    "💥 NOT SAFE 💥".red().bold()
+
+            if let Some((main_template_name, restored)) = restored_execution {
+                if main_template_name != *id {
+                    return Result::Err(eoutln!(
+                        "{} {} (expected {})",
+                        "The snapshot was taken from another main template:".red(),
+                        main_template_name,
+                        id
+                    ));
                 }
-            );
-            eprintln!(" └─ Execution Time    : {:?}", start_time.elapsed());
-
-            if user_input.flag_printout_stats {
-                println!(
-                    "\n{}",
-                    "🪶 Stats of Symbolic Trace  ══════════════════════"
-                        .yellow()
-                        .bold()
+                restored.apply_to(&mut sym_executor);
+            } else {
+                eoutln!("{}", "🛒 Gathering Trace/Side Constraints...".green());
+
+                sym_executor.symbolic_library.name2id.insert(
+                    "main".to_string(),
+                    sym_executor.symbolic_library.name2id.len(),
                 );
-                print_constraint_summary_statistics_pretty(&ts);
-                println!(
-                    "\n{}",
-                    "⛓️ Stats of Side Constraint ══════════════════════"
-                        .yellow()
-                        .bold()
+                sym_executor.symbolic_library.id2name.insert(
+                    sym_executor.symbolic_library.name2id["main"],
+                    "main".to_string(),
                 );
-                print_constraint_summary_statistics_pretty(&ss);
-            } else if user_input.flag_printout_stats_csv {
-                let headers = vec![
-                    "Total_Constraints",
-                    "Constant_Counts",
-                    "Conditional_Counts",
-                    "Array_Counts",
-                    "Avg_Depth",
-                    "Max_Depth",
-                    "Count_Assign",
-                    "Count_AssignEq",
-                    "Count_AssignCall",
-                    "Count_QuadZeroDiv",
-                    "Count_Mul",
-                    "Count_Div",
-                    "Count_Add",
-                    "Count_Sub",
-                    "Count_Pow",
-                    "Count_IntDiv",
-                    "Count_Mod",
-                    "Count_ShiftL",
-                    "Count_ShiftR",
-                    "Count_LesserEq",
-                    "Count_GreaterEq",
-                    "Count_Lesser",
-                    "Count_Greater",
-                    "Count_Eq",
-                    "Count_NotEq",
-                    "Count_BoolOr",
-                    "Count_BoolAnd",
-                    "Count_BitOr",
-                    "Count_BitAnd",
-                    "Count_BitXor",
-                    "Number_of_Variable",
-                    "Variable_Avg_Count",
-                    "Variable_Max_Count",
-                    "Function_Avg_Count",
-                    "Function_Max_Count",
-                ];
-                println!("{}", headers.join(","));
-                print_constraint_summary_statistics_csv(&ts);
-                print_constraint_summary_statistics_csv(&ss);
+
+                sym_executor.cur_state.add_owner(&OwnerName {
+                    id: sym_executor.symbolic_library.name2id["main"],
+                    counter: 0,
+                    access: None,
+                });
+                sym_executor
+                    .cur_state
+                    .set_template_id(sym_executor.symbolic_library.name2id[id]);
+
+                if !user_input.flag_symbolic_template_params {
+                    sym_executor.feed_arguments(&main_template_param_names, args);
+                }
+
+                let body = sym_executor.symbolic_library.template_library
+                    [&sym_executor.symbolic_library.name2id[id]]
+                    .body
+                    .clone();
+                sym_executor.execute(&body, 0);
+                sym_executor.log_bindings();
+                sym_executor.record_incomplete_components();
+                check_incomplete_components(&sym_executor)?;
             }
-            eprintln!(
-                "{}",
-                "════════════════════════════════════════════════════════════════".green()
-            );
+
+            if let Some(trace_log) = &base_config.trace_log {
+                trace_log.flush().map_err(|e| {
+                    eoutln!("{} {}", "Unable to write the execution trace:".red(), e)
+                })?;
+                eoutln!(
+                    "{} {}",
+                    "💾 Saving the execution trace to:",
+                    user_input.trace_jsonl().cyan()
+                );
+                artifacts.record("execution_trace", &user_input.trace_jsonl());
+            }
+
+            if user_input.save_state() != "none" {
+                SymbolicSnapshot::capture(&sym_executor, id)
+                    .save(Path::new(&user_input.save_state()))
+                    .map_err(|e| eoutln!("{} {}", "Unable to write the snapshot:".red(), e))?;
+                eoutln!(
+                    "{} {}",
+                    "💾 Saving the snapshot to:",
+                    user_input.save_state().cyan()
+                );
+                artifacts.record("snapshot", &user_input.save_state());
+            }
+
+            if user_input.subcommand() == "show-constraints" {
+                simplify_gathered_constraints(&user_input, &mut sym_executor);
+                show_constraints(&user_input, &sym_executor);
+                return Result::Ok(());
+            }
+
+            if user_input.subcommand() == "why" {
+                return show_constraint_origin(&user_input, &sym_executor, &program_archive);
+            }
+
+            if user_input.subcommand() == "explain" {
+                return show_signal_explanation(&user_input, &sym_executor);
+            }
+
+            if user_input.subcommand() == "gen-tests" {
+                return generate_test_harness(
+                    &user_input,
+                    &mut sym_executor,
+                    id,
+                    main_template_param_names,
+                    args.clone(),
+                    &input_spec,
+                );
+            }
+
+            let circuit = AnalysedCircuit {
+                program_archive: &program_archive,
+                template_name: id,
+                args: args,
+                param_names: main_template_param_names,
+                public_inputs: program_archive.get_public_inputs_main_component(),
+                file_id: program_archive.file_id_main,
+                start_time: start_time,
+            };
+            let result = analyse_circuit(
+                &user_input,
+                &mut sym_executor,
+                &search_config,
+                &circuit,
+                &detectors,
+                &input_spec,
+                &mut artifacts,
+                result_cache.as_ref(),
+            )?;
+            exit_on_findings(&user_input, result.max_severity);
         }
         _ => {
             warn!("Cannot Find Main Call");
@@ -0,0 +1,99 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use rustc_hash::FxHashMap;
+use serde::Deserialize;
+
+/// The name of the project manifest read by `zkfuzz run` from the current directory.
+pub const MANIFEST_FILE_NAME: &str = "zkfuzz.toml";
+
+/// The settings of a project declared in its manifest, e.g.
+///
+/// ```toml
+/// entry = "circuits/main.circom"
+/// include = ["node_modules/circomlib/circuits", "circuits/lib"]
+/// prime = "bn128"
+/// search_mode = "ga"
+/// detectors = ["unused_outputs", "component_boundaries"]
+///
+/// [timeouts]
+/// Main = 600
+/// MerkleProof = 120
+/// ```
+///
+/// Each setting is the default of the corresponding option of `zkfuzz run`, and the options
+/// given on the command line take precedence over it.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ProjectManifest {
+    /// The circuit with the main component (the positional input).
+    pub entry: PathBuf,
+    /// The directories searched for the included files (`-l`).
+    #[serde(default)]
+    pub include: Vec<PathBuf>,
+    /// The name of the curve whose prime is used to parse the circuit (`--prime`).
+    pub prime: Option<String>,
+    /// The search mode (`--search_mode`).
+    pub search_mode: Option<String>,
+    /// The detectors to run (`--detectors`).
+    pub detectors: Option<Vec<String>>,
+    /// The time limit of the search in seconds, keyed by the name of the main template
+    /// (`--template_timeouts`).
+    #[serde(default)]
+    pub timeouts: BTreeMap<String, u64>,
+}
+
+impl ProjectManifest {
+    /// The timeouts in the syntax of `--template_timeouts`, or `none` if there are none.
+    pub fn template_timeouts(&self) -> String {
+        if self.timeouts.is_empty() {
+            return "none".to_string();
+        }
+        self.timeouts
+            .iter()
+            .map(|(name, secs)| format!("{}:{}", name, secs))
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+}
+
+/// Parses a project manifest whose paths are relative to the directory `root`.
+pub fn parse_manifest(content: &str, root: &Path) -> Result<ProjectManifest, String> {
+    let mut manifest: ProjectManifest = toml::from_str(content).map_err(|e| e.to_string())?;
+    if manifest.entry.as_os_str().is_empty() {
+        return Err("`entry` is empty".to_string());
+    }
+    manifest.entry = root.join(&manifest.entry);
+    manifest.include = manifest.include.iter().map(|dir| root.join(dir)).collect();
+    Ok(manifest)
+}
+
+/// Loads the project manifest at `file_path`. Its paths are resolved against the directory of
+/// the manifest, so that a project can be analysed from any directory.
+pub fn load_manifest(file_path: &Path) -> Result<ProjectManifest, String> {
+    let content = fs::read_to_string(file_path).map_err(|e| e.to_string())?;
+    let root = file_path.parent().unwrap_or_else(|| Path::new(""));
+    parse_manifest(&content, root)
+}
+
+/// Parses the time limits of `--template_timeouts`, a comma-separated list of
+/// `<template>:<seconds>` (e.g. `Main:600,MerkleProof:120`), or `none`.
+pub fn parse_template_timeouts(timeouts: &str) -> Result<FxHashMap<String, u64>, String> {
+    let mut parsed = FxHashMap::default();
+    if timeouts == "none" {
+        return Ok(parsed);
+    }
+    for entry in timeouts.split(',') {
+        let (name, secs) = entry
+            .trim()
+            .split_once(':')
+            .ok_or_else(|| format!("`{}` is not of the form <template>:<seconds>", entry))?;
+        let secs = match secs.parse::<u64>() {
+            Ok(secs) if secs > 0 => secs,
+            _ => return Err(format!("invalid timeout of `{}`: `{}`", name, secs)),
+        };
+        parsed.insert(name.to_string(), secs);
+    }
+    Ok(parsed)
+}
@@ -0,0 +1,362 @@
+use std::io;
+use std::io::Write;
+
+use colored::Colorize;
+use num_bigint_dig::BigInt;
+use num_bigint_dig::RandBigInt;
+use num_traits::{One, Zero};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use rustc_hash::FxHashMap;
+
+use program_structure::ast::Meta;
+use program_structure::file_definition::FileLibrary;
+
+use crate::executor::debug_ast::DebuggableStatement;
+use crate::executor::symbolic_execution::SymbolicExecutor;
+use crate::executor::symbolic_value::{
+    extract_variables, OwnerName, SymbolicLibrary, SymbolicName, SymbolicValueRef,
+};
+use crate::mutator::input_spec::InputDomain;
+use crate::mutator::utils::{
+    gather_input_domains, gather_input_variables, BaseVerificationConfig, CounterExample,
+    VerificationResult, ViolatedConstraint,
+};
+
+/// An `assert` statement of the circuit, treated as a target of the reachability analysis.
+pub struct AssertionTarget {
+    pub owner_name: String,
+    pub meta: Meta,
+}
+
+impl AssertionTarget {
+    /// Returns a human-readable source location of the assertion, e.g. `IsZero (line 12)`.
+    pub fn location(&self, file_library: &FileLibrary) -> String {
+        source_location(&self.owner_name, &self.meta, file_library)
+    }
+}
+
+/// Returns a human-readable source location of a statement, e.g. `IsZero (line 12)`.
+///
+/// Falls back to the element ID of the statement when its file is unknown, e.g. for the
+/// templates linked from a library archive.
+pub fn source_location(owner_name: &str, meta: &Meta, file_library: &FileLibrary) -> String {
+    match meta
+        .file_id
+        .and_then(|file_id| file_library.get_line(meta.start, file_id))
+    {
+        Some(line) => format!("{} (line {})", owner_name, line),
+        None => format!("{} (elem_id={})", owner_name, meta.elem_id),
+    }
+}
+
+fn gather_assertions_in_statements(
+    owner_name: &str,
+    statements: &[DebuggableStatement],
+    targets: &mut Vec<AssertionTarget>,
+) {
+    for stmt in statements {
+        match stmt {
+            DebuggableStatement::Assert { meta, .. } => targets.push(AssertionTarget {
+                owner_name: owner_name.to_string(),
+                meta: meta.clone(),
+            }),
+            DebuggableStatement::IfThenElse {
+                if_case, else_case, ..
+            } => {
+                gather_assertions_in_statements(
+                    owner_name,
+                    std::slice::from_ref(if_case.as_ref()),
+                    targets,
+                );
+                if let Some(else_case) = else_case {
+                    gather_assertions_in_statements(
+                        owner_name,
+                        std::slice::from_ref(else_case.as_ref()),
+                        targets,
+                    );
+                }
+            }
+            DebuggableStatement::While { stmt, .. } => {
+                gather_assertions_in_statements(
+                    owner_name,
+                    std::slice::from_ref(stmt.as_ref()),
+                    targets,
+                );
+            }
+            DebuggableStatement::Block { stmts, .. } => {
+                gather_assertions_in_statements(owner_name, stmts, targets);
+            }
+            DebuggableStatement::InitializationBlock {
+                initializations, ..
+            } => {
+                gather_assertions_in_statements(owner_name, initializations, targets);
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Collects every `assert` statement in the templates and functions of the symbolic library.
+///
+/// # Parameters
+/// - `symbolic_library`: The symbolic library containing the template and function definitions.
+///
+/// # Returns
+/// A vector of `AssertionTarget`s sorted by their owner and element ID.
+pub fn gather_assertions(symbolic_library: &SymbolicLibrary) -> Vec<AssertionTarget> {
+    let mut targets = Vec::new();
+    for (id, template) in symbolic_library.template_library.iter() {
+        let name = &symbolic_library.id2name[id];
+        gather_assertions_in_statements(name, &template.body, &mut targets);
+    }
+    for (id, function) in symbolic_library.function_library.iter() {
+        let name = &symbolic_library.id2name[id];
+        gather_assertions_in_statements(name, &function.body, &mut targets);
+    }
+    targets.sort_by(|a, b| (&a.owner_name, a.meta.elem_id).cmp(&(&b.owner_name, b.meta.elem_id)));
+    targets
+}
+
+/// Draws a candidate value for an input signal without a finite domain.
+///
+/// The first `num_corner_iterations` iterations enumerate the combinations of the corner cases
+/// `{0, 1, p - 1}` across the inputs, after which values are drawn uniformly from the field.
+fn draw_input_value(
+    iteration: usize,
+    index: usize,
+    num_corner_iterations: usize,
+    base_config: &BaseVerificationConfig,
+    rng: &mut StdRng,
+) -> BigInt {
+    let corner_cases = [
+        BigInt::zero(),
+        BigInt::one(),
+        &base_config.prime - BigInt::one(),
+    ];
+    if iteration < num_corner_iterations {
+        let digit = match corner_cases.len().checked_pow(index as u32) {
+            Some(base) => (iteration / base) % corner_cases.len(),
+            None => 0,
+        };
+        corner_cases[digit].clone()
+    } else {
+        rng.gen_bigint_range(&BigInt::zero(), &base_config.prime)
+    }
+}
+
+/// Samples assignments of the inputs of the main template.
+///
+/// Inputs are drawn from the domains implied by their tags (the input preconditions) or from the
+/// field, starting with the combinations of the corner cases `{0, 1, p - 1}`.
+pub struct InputSampler {
+    pub input_variables: Vec<SymbolicName>,
+    domains: FxHashMap<SymbolicName, InputDomain>,
+    num_corner_iterations: usize,
+    rng: StdRng,
+}
+
+impl InputSampler {
+    /// Creates a sampler for the inputs of the main template appearing in the constraints.
+    ///
+    /// # Parameters
+    /// - `symbolic_library`: The symbolic library containing the template definitions.
+    /// - `symbolic_trace`: A vector of constraints representing the program trace.
+    /// - `side_constraints`: A vector of additional constraints for validation.
+    /// - `base_config`: The verification base_configs.
+    /// - `max_iterations`: The number of assignments that will be sampled.
+    /// - `seed`: The seed of the random number generator.
+    pub fn new(
+        symbolic_library: &SymbolicLibrary,
+        symbolic_trace: &Vec<SymbolicValueRef>,
+        side_constraints: &Vec<SymbolicValueRef>,
+        base_config: &BaseVerificationConfig,
+        max_iterations: usize,
+        seed: u64,
+    ) -> Self {
+        let mut variables = extract_variables(symbolic_trace);
+        variables.append(&mut extract_variables(side_constraints));
+        let input_variables = gather_input_variables(symbolic_library, base_config, &variables);
+        let domains = gather_input_domains(symbolic_library, base_config, &input_variables);
+
+        let num_corner_iterations = 3_usize
+            .checked_pow(input_variables.len() as u32)
+            .map_or(max_iterations / 2, |n| n.min(max_iterations / 2));
+        InputSampler {
+            input_variables: input_variables,
+            domains: domains,
+            num_corner_iterations: num_corner_iterations,
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+
+    /// Returns the assignment of the inputs for the `iteration`-th candidate.
+    pub fn sample(
+        &mut self,
+        iteration: usize,
+        base_config: &BaseVerificationConfig,
+    ) -> FxHashMap<SymbolicName, BigInt> {
+        let mut assignment = FxHashMap::default();
+        for (index, var) in self.input_variables.iter().enumerate() {
+            let value = match self.domains.get(var) {
+                Some(domain) => domain.sample(&mut self.rng),
+                None => draw_input_value(
+                    iteration,
+                    index,
+                    self.num_corner_iterations,
+                    base_config,
+                    &mut self.rng,
+                ),
+            };
+            assignment.insert(var.clone(), value);
+        }
+        assignment
+    }
+}
+
+/// Executes the circuit concretely on randomly sampled inputs until `check` reports a finding.
+///
+/// Candidate inputs are sampled from the domains implied by the input tags (the input
+/// preconditions) or from the field, starting with the combinations of the corner cases
+/// `{0, 1, p - 1}`. After each execution, `check` inspects the executor and returns the
+/// verification result of the candidate, if any.
+///
+/// # Parameters
+/// - `sexe`: A mutable reference to the concrete executor.
+/// - `symbolic_trace`: A vector of constraints representing the program trace.
+/// - `side_constraints`: A vector of additional constraints for validation.
+/// - `base_config`: The verification base_configs.
+/// - `max_iterations`: The maximum number of candidate inputs to execute.
+/// - `seed`: The seed of the random number generator.
+/// - `check`: Returns the finding of the last execution, if any.
+///
+/// # Returns
+/// An `Option<CounterExample>` containing the first candidate reported by `check`, or `None`.
+pub fn random_concrete_search<F>(
+    sexe: &mut SymbolicExecutor,
+    symbolic_trace: &Vec<SymbolicValueRef>,
+    side_constraints: &Vec<SymbolicValueRef>,
+    base_config: &BaseVerificationConfig,
+    max_iterations: usize,
+    seed: u64,
+    mut check: F,
+) -> Option<CounterExample>
+where
+    F: FnMut(&SymbolicExecutor) -> Option<VerificationResult>,
+{
+    let mut sampler = InputSampler::new(
+        sexe.symbolic_library,
+        symbolic_trace,
+        side_constraints,
+        base_config,
+        max_iterations,
+        seed,
+    );
+    let mut counter_example = None;
+    let mut num_iterations = 0;
+    while num_iterations < max_iterations {
+        if num_iterations % base_config.progress_interval == 0 {
+            out!("\rProgress: {} / {}", num_iterations, max_iterations);
+            io::stdout().flush().unwrap();
+        }
+
+        let assignment = sampler.sample(num_iterations, base_config);
+        num_iterations += 1;
+
+        sexe.clear();
+        sexe.cur_state.add_owner(&OwnerName {
+            id: sexe.symbolic_library.name2id["main"],
+            counter: 0,
+            access: None,
+        });
+        sexe.feed_arguments(
+            &base_config.template_param_names,
+            &base_config.template_param_values,
+        );
+        sexe.concrete_execute(&base_config.target_template_name, &assignment);
+
+        if let Some(flag) = check(sexe) {
+            counter_example = Some(CounterExample {
+                flag: flag,
+                target_output: None,
+                assignment: assignment,
+            });
+            break;
+        }
+    }
+
+    out!("\rProgress: {} / {}", num_iterations, max_iterations);
+    io::stdout().flush().unwrap();
+
+    outln!("\n • Search completed");
+    outln!("     ├─ Total iterations: {}", num_iterations);
+    match &counter_example {
+        Some(ce) => outln!("     └─ Verification result: {}", ce.flag),
+        None => outln!(
+            "     └─ Verification result: {}",
+            VerificationResult::WellConstrained
+        ),
+    }
+
+    counter_example
+}
+
+/// Searches for input assignments that reach and violate an `assert` statement of the circuit.
+///
+/// Every `assert` in the circuit is treated as a target. Candidate inputs are sampled from the
+/// domains implied by the input tags (the input preconditions) or from the field, and the circuit
+/// is executed concretely on each candidate. A candidate is reported as soon as it satisfies all
+/// constraints encountered before an `assert` whose condition evaluates to `false`.
+///
+/// # Parameters
+/// - `sexe`: A mutable reference to the concrete executor.
+/// - `symbolic_trace`: A vector of constraints representing the program trace.
+/// - `side_constraints`: A vector of additional constraints for validation.
+/// - `base_config`: The verification base_configs.
+/// - `file_library`: The file library used to resolve the source locations of the assertions.
+/// - `max_iterations`: The maximum number of candidate inputs to execute.
+/// - `seed`: The seed of the random number generator.
+///
+/// # Returns
+/// An `Option<CounterExample>` containing the triggering assignment if an assertion can be violated,
+/// or `None` otherwise.
+pub fn assertion_reachability_search(
+    sexe: &mut SymbolicExecutor,
+    symbolic_trace: &Vec<SymbolicValueRef>,
+    side_constraints: &Vec<SymbolicValueRef>,
+    base_config: &BaseVerificationConfig,
+    file_library: &FileLibrary,
+    max_iterations: usize,
+    seed: u64,
+) -> Option<CounterExample> {
+    let targets = gather_assertions(sexe.symbolic_library);
+    outln!(
+        "{} {}",
+        "🎯 #Assertions:".green(),
+        targets.len().to_string().bright_yellow()
+    );
+    if targets.is_empty() {
+        return None;
+    }
+
+    random_concrete_search(
+        sexe,
+        symbolic_trace,
+        side_constraints,
+        base_config,
+        max_iterations,
+        seed,
+        |sexe| {
+            sexe.violated_assertion.as_ref().map(|(meta, condition)| {
+                let location = match targets.iter().find(|t| t.meta.elem_id == meta.elem_id) {
+                    Some(target) => target.location(file_library),
+                    None => format!("elem_id={}", meta.elem_id),
+                };
+                VerificationResult::AssertionFailure(
+                    location,
+                    ViolatedConstraint::new(condition, &sexe.symbolic_library.id2name),
+                )
+            })
+        },
+    )
+}
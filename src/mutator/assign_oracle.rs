@@ -0,0 +1,348 @@
+use std::io;
+use std::io::Write;
+use std::time::Instant;
+
+use colored::Colorize;
+use num_bigint_dig::BigInt;
+use num_bigint_dig::RandBigInt;
+use num_traits::{One, Zero};
+use program_structure::ast::ExpressionInfixOpcode;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use rustc_hash::{FxHashMap, FxHashSet};
+
+use crate::executor::symbolic_execution::SymbolicExecutor;
+use crate::executor::symbolic_value::{
+    extract_variables_from_symbolic_value, SymbolicLibrary, SymbolicName, SymbolicValue,
+    SymbolicValueRef,
+};
+use crate::executor::utils::{moddiv, modreduce};
+use crate::mutator::assertion::InputSampler;
+use crate::mutator::utils::{
+    emulate_symbolic_trace, evaluate_symbolic_value, BaseVerificationConfig, CounterExample,
+    UnderConstrainedType, VerificationResult,
+};
+
+/// A signal computed with `<--`, paired with the side constraints that mention it.
+pub struct AssignmentPair {
+    pub signal: SymbolicName,
+    pub constraints: Vec<SymbolicValueRef>,
+}
+
+/// Pairs each signal assigned with `<--` with the side constraints mentioning it.
+///
+/// # Parameters
+/// - `symbolic_trace`: A vector of constraints representing the program trace.
+/// - `side_constraints`: A vector of additional constraints for validation.
+///
+/// # Returns
+/// The pairs, in the order in which the signals are assigned in the trace.
+pub fn pair_assignments_with_constraints(
+    symbolic_trace: &[SymbolicValueRef],
+    side_constraints: &[SymbolicValueRef],
+) -> Vec<AssignmentPair> {
+    let variables_of_constraints: Vec<FxHashSet<SymbolicName>> = side_constraints
+        .iter()
+        .map(|c| {
+            let mut variables = FxHashSet::default();
+            extract_variables_from_symbolic_value(c, &mut variables);
+            variables
+        })
+        .collect();
+
+    let mut seen = FxHashSet::default();
+    let mut pairs = Vec::new();
+    for inst in symbolic_trace {
+        if let SymbolicValue::Assign(lhs, ..) = inst.as_ref() {
+            if let SymbolicValue::Variable(signal) = lhs.as_ref() {
+                if !seen.insert(signal.clone()) {
+                    continue;
+                }
+                pairs.push(AssignmentPair {
+                    signal: signal.clone(),
+                    constraints: side_constraints
+                        .iter()
+                        .zip(variables_of_constraints.iter())
+                        .filter(|(_, variables)| variables.contains(signal))
+                        .map(|(c, _)| c.clone())
+                        .collect(),
+                });
+            }
+        }
+    }
+    pairs
+}
+
+fn to_field_element(value: &SymbolicValue, prime: &BigInt) -> Option<BigInt> {
+    match value {
+        SymbolicValue::ConstantInt(v) => Some(modreduce(v, prime)),
+        SymbolicValue::ConstantBool(b) => Some(if *b { BigInt::one() } else { BigInt::zero() }),
+        _ => None,
+    }
+}
+
+/// Returns `lhs - rhs` of an equality constraint under `assignment`, or `None` if the constraint
+/// is not an equality or cannot be evaluated.
+fn residual_of_equality(
+    prime: &BigInt,
+    constraint: &SymbolicValue,
+    assignment: &FxHashMap<SymbolicName, BigInt>,
+    symbolic_library: &mut SymbolicLibrary,
+) -> Option<BigInt> {
+    let (lhs, rhs) = match constraint {
+        SymbolicValue::AssignEq(lhs, rhs) => (lhs, rhs),
+        SymbolicValue::BinaryOp(lhs, op, rhs) | SymbolicValue::AuxBinaryOp(lhs, op, rhs)
+            if matches!(op.0, ExpressionInfixOpcode::Eq) =>
+        {
+            (lhs, rhs)
+        }
+        _ => return None,
+    };
+    let lv = to_field_element(
+        &evaluate_symbolic_value(prime, lhs, assignment, symbolic_library)?,
+        prime,
+    )?;
+    let rv = to_field_element(
+        &evaluate_symbolic_value(prime, rhs, assignment, symbolic_library)?,
+        prime,
+    )?;
+    Some(modreduce(&(lv - rv), prime))
+}
+
+/// Returns `Some(true)` if all `constraints` hold under `assignment`, or `None` if one of them
+/// cannot be evaluated.
+pub(crate) fn are_satisfied(
+    prime: &BigInt,
+    constraints: &[SymbolicValueRef],
+    assignment: &FxHashMap<SymbolicName, BigInt>,
+    symbolic_library: &mut SymbolicLibrary,
+) -> Option<bool> {
+    for c in constraints {
+        match evaluate_symbolic_value(prime, c, assignment, symbolic_library)? {
+            SymbolicValue::ConstantBool(true) => {}
+            SymbolicValue::ConstantBool(false) => return Some(false),
+            _ => return None,
+        }
+    }
+    Some(true)
+}
+
+/// Proposes values of `signal`, other than `assigned_value`, that may satisfy `constraints` while
+/// every other signal keeps its value in `assignment`.
+///
+/// Each equality constraint is interpolated as a quadratic polynomial in `signal` from its values
+/// at `0`, `1`, and `2`. Since `assigned_value` is a root, the other root is proposed, and any
+/// value is proposed if the constraint does not depend on `signal`. These are complemented with
+/// the neighbours of `assigned_value`, the corner cases `{0, 1, p - 1}`, and a random value.
+pub(crate) fn propose_alternative_values(
+    prime: &BigInt,
+    signal: &SymbolicName,
+    assigned_value: &BigInt,
+    constraints: &[SymbolicValueRef],
+    assignment: &FxHashMap<SymbolicName, BigInt>,
+    symbolic_library: &mut SymbolicLibrary,
+    rng: &mut StdRng,
+) -> Vec<BigInt> {
+    let two = BigInt::from(2);
+    let mut candidates = Vec::new();
+    let mut probe = assignment.clone();
+    for c in constraints {
+        let mut values = Vec::new();
+        for x in [BigInt::zero(), BigInt::one(), two.clone()] {
+            probe.insert(signal.clone(), x);
+            match residual_of_equality(prime, c, &probe, symbolic_library) {
+                Some(v) => values.push(v),
+                None => break,
+            }
+        }
+        if values.len() != 3 {
+            continue;
+        }
+        // f(x) = a * x^2 + b * x + c through (0, f0), (1, f1), (2, f2)
+        let a = moddiv(&(&values[2] - &two * &values[1] + &values[0]), &two, prime);
+        let b = modreduce(&(&values[1] - &values[0] - &a), prime);
+        if !a.is_zero() {
+            // The roots of a * x^2 + b * x + c sum up to -b / a
+            candidates.push(moddiv(&(prime - &b), &a, prime) - assigned_value);
+        } else if b.is_zero() {
+            candidates.push(assigned_value + BigInt::one());
+        }
+    }
+    candidates.extend([
+        assigned_value + BigInt::one(),
+        assigned_value - BigInt::one(),
+        BigInt::zero(),
+        BigInt::one(),
+        prime - BigInt::one(),
+        rng.gen_bigint_range(&BigInt::zero(), prime),
+    ]);
+
+    let assigned_value = modreduce(assigned_value, prime);
+    let mut seen = FxHashSet::default();
+    candidates
+        .into_iter()
+        .map(|v| modreduce(&v, prime))
+        .filter(|v| *v != assigned_value && seen.insert(v.clone()))
+        .collect()
+}
+
+/// Searches for signals computed with `<--` whose `===` checks admit another value.
+///
+/// A frequent bug computes a value with `<--` and then checks it with a `===` constraint that is
+/// too weak. For each signal assigned with `<--`, this detector takes the side constraints
+/// mentioning it and, on witnesses computed honestly from sampled inputs, tries to replace the
+/// assigned value with another one while keeping every other signal fixed. A replacement that
+/// still satisfies all side constraints yields a second valid witness for the same inputs.
+///
+/// # Parameters
+/// - `sexe`: A mutable reference to the concrete executor.
+/// - `symbolic_trace`: A vector of constraints representing the program trace.
+/// - `side_constraints`: A vector of additional constraints for validation.
+/// - `base_config`: The verification base_configs.
+/// - `max_iterations`: The maximum number of candidate inputs to try.
+/// - `seed`: The seed of the random number generator.
+///
+/// # Returns
+/// An `Option<CounterExample>` containing the divergent witness, in which the signal takes a
+/// value other than the one computed by its `<--` assignment, or `None` otherwise.
+pub fn assignment_oracle_search(
+    sexe: &mut SymbolicExecutor,
+    symbolic_trace: &Vec<SymbolicValueRef>,
+    side_constraints: &Vec<SymbolicValueRef>,
+    base_config: &BaseVerificationConfig,
+    max_iterations: usize,
+    seed: u64,
+) -> Option<CounterExample> {
+    assignment_oracle_search_until(
+        sexe,
+        symbolic_trace,
+        side_constraints,
+        base_config,
+        max_iterations,
+        seed,
+        None,
+        &mut Vec::new(),
+    )
+}
+
+/// Maximum number of inputs collected by `assignment_oracle_search_until`.
+const MAX_HONEST_INPUTS: usize = 64;
+
+/// Runs `assignment_oracle_search` until `deadline`, if any, and collects up to
+/// `MAX_HONEST_INPUTS` sampled inputs whose honest witness satisfies every constraint into
+/// `honest_inputs`.
+pub fn assignment_oracle_search_until(
+    sexe: &mut SymbolicExecutor,
+    symbolic_trace: &Vec<SymbolicValueRef>,
+    side_constraints: &Vec<SymbolicValueRef>,
+    base_config: &BaseVerificationConfig,
+    max_iterations: usize,
+    seed: u64,
+    deadline: Option<Instant>,
+    honest_inputs: &mut Vec<FxHashMap<SymbolicName, BigInt>>,
+) -> Option<CounterExample> {
+    let pairs = pair_assignments_with_constraints(symbolic_trace, side_constraints);
+    outln!(
+        "{} {}",
+        "🔗 #Assignments (<--):".green(),
+        pairs.len().to_string().bright_yellow()
+    );
+    if pairs.is_empty() {
+        return None;
+    }
+
+    let prime = &base_config.prime;
+    let mut sampler = InputSampler::new(
+        sexe.symbolic_library,
+        symbolic_trace,
+        side_constraints,
+        base_config,
+        max_iterations,
+        seed,
+    );
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut counter_example = None;
+    let mut num_iterations = 0;
+    'search: while num_iterations < max_iterations {
+        if num_iterations % base_config.progress_interval == 0 {
+            out!("\rProgress: {} / {}", num_iterations, max_iterations);
+            io::stdout().flush().unwrap();
+        }
+        if deadline.map_or(false, |d| Instant::now() >= d) {
+            break;
+        }
+
+        let mut assignment = sampler.sample(num_iterations, base_config);
+        let inputs = assignment.clone();
+        num_iterations += 1;
+
+        // Compute the honest witness of the sampled inputs
+        match emulate_symbolic_trace(
+            prime,
+            symbolic_trace,
+            &FxHashMap::default(),
+            &mut assignment,
+            sexe.symbolic_library,
+        ) {
+            Some((true, _)) => {}
+            _ => continue,
+        }
+        if are_satisfied(prime, side_constraints, &assignment, sexe.symbolic_library) != Some(true)
+        {
+            continue;
+        }
+        if honest_inputs.len() < MAX_HONEST_INPUTS {
+            honest_inputs.push(inputs);
+        }
+
+        for pair in &pairs {
+            let assigned_value = match assignment.get(&pair.signal) {
+                Some(v) => v.clone(),
+                None => continue,
+            };
+            for alternative_value in propose_alternative_values(
+                prime,
+                &pair.signal,
+                &assigned_value,
+                &pair.constraints,
+                &assignment,
+                sexe.symbolic_library,
+                &mut rng,
+            ) {
+                let mut witness = assignment.clone();
+                witness.insert(pair.signal.clone(), alternative_value);
+                if are_satisfied(prime, &pair.constraints, &witness, sexe.symbolic_library)
+                    == Some(true)
+                {
+                    counter_example = Some(CounterExample {
+                        flag: VerificationResult::UnderConstrained(
+                            UnderConstrainedType::NonDeterministic(
+                                pair.signal.clone(),
+                                pair.signal.lookup_fmt(&sexe.symbolic_library.id2name),
+                                assigned_value,
+                            ),
+                        ),
+                        target_output: Some(pair.signal.clone()),
+                        assignment: witness,
+                    });
+                    break 'search;
+                }
+            }
+        }
+    }
+
+    out!("\rProgress: {} / {}", num_iterations, max_iterations);
+    io::stdout().flush().unwrap();
+
+    outln!("\n • Search completed");
+    outln!("     ├─ Total iterations: {}", num_iterations);
+    match &counter_example {
+        Some(ce) => outln!("     └─ Verification result: {}", ce.flag),
+        None => outln!(
+            "     └─ Verification result: {}",
+            VerificationResult::WellConstrained
+        ),
+    }
+
+    counter_example
+}
@@ -0,0 +1,238 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use rustc_hash::{FxHashMap, FxHashSet};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::executor::structural_hash::StableHasher;
+use crate::executor::utils::strip_ansi;
+use crate::mutator::clustering::FindingSignature;
+
+/// The version of the format of the baseline files, bumped whenever the fingerprints change.
+const BASELINE_VERSION: usize = 1;
+
+/// A finding recorded in a baseline, identified by a fingerprint that does not depend on the
+/// values of the counterexample or on the lines of the circuit.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct BaselineEntry {
+    pub fingerprint: String,
+    /// The main template of the circuit the finding was reported on.
+    pub template: String,
+    /// The kind of counterexample (e.g. `UnderConstrained-NonDeterministic`) or the category of
+    /// the finding (e.g. `Potential Division-by-Zero`).
+    pub kind: String,
+    pub description: String,
+    /// A name given to the accepted finding, printed when it is suppressed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// Why the finding is accepted.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+impl BaselineEntry {
+    /// Creates the entry of a counterexample from its signature, which abstracts the concrete
+    /// signal paths and values away. The violated constraint is identified by its structural
+    /// hash, while the description shows it as formatted in the counterexample.
+    pub fn of_counter_example(template: &str, signature: &FindingSignature) -> Self {
+        let description = if signature.constraint.is_empty() {
+            signature.signal.clone()
+        } else {
+            format!("{} {}", signature.signal, signature.constraint)
+        };
+        BaselineEntry {
+            fingerprint: fingerprint(&[
+                template,
+                &signature.kind,
+                &format!("{:016x}", signature.structure),
+                &signature.signal,
+            ]),
+            template: template.to_string(),
+            kind: signature.kind.clone(),
+            description: description.trim().to_string(),
+            name: None,
+            reason: None,
+        }
+    }
+
+    /// Creates the entry of a finding of the detectors other than the search.
+    pub fn of_finding(template: &str, category: &str, message: &str) -> Self {
+        let description = without_line_numbers(&strip_ansi(message));
+        BaselineEntry {
+            fingerprint: fingerprint(&[template, category, &description]),
+            template: template.to_string(),
+            kind: category.to_string(),
+            description: description,
+            name: None,
+            reason: None,
+        }
+    }
+
+    /// Returns the name of the entry, or its fingerprint if it has none.
+    pub fn label(&self) -> &str {
+        self.name.as_deref().unwrap_or(&self.fingerprint)
+    }
+}
+
+/// Returns a hexadecimal hash of `parts`, which is the same across runs, platforms, and builds,
+/// so that the baselines can be checked in.
+fn fingerprint(parts: &[&str]) -> String {
+    let mut hasher = StableHasher::default();
+    for part in parts {
+        hasher.write_str(part);
+    }
+    format!("{:016x}", hasher.finish())
+}
+
+/// Replaces the number following each `line ` in `message` by `?`, so that a finding keeps its
+/// fingerprint when unrelated lines are added above it, e.g. `Div (line 7)` becomes
+/// `Div (line ?)`.
+fn without_line_numbers(message: &str) -> String {
+    let mut result = String::with_capacity(message.len());
+    let mut rest = message;
+    while let Some(pos) = rest.find("line ") {
+        let (before, after) = rest.split_at(pos + "line ".len());
+        result.push_str(before);
+        let digits = after.len() - after.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+        if digits > 0 {
+            result.push('?');
+        }
+        rest = &after[digits..];
+    }
+    result.push_str(rest);
+    result
+}
+
+/// The findings accepted for a codebase, written by `zkfuzz baseline update` and given to
+/// `--baseline` so that a run only reports the findings that are not in it.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Baseline {
+    pub version: usize,
+    pub findings: Vec<BaselineEntry>,
+}
+
+/// The findings of a run split by a baseline.
+pub struct BaselineComparison {
+    /// Whether each finding of the run, in order, is in the baseline.
+    pub is_suppressed: Vec<bool>,
+    /// The entries of the baseline matching a finding of the run.
+    pub suppressed: Vec<BaselineEntry>,
+    /// The entries of the baseline of the main template that the run did not find, e.g. fixed
+    /// bugs.
+    pub unmatched: Vec<BaselineEntry>,
+}
+
+impl BaselineComparison {
+    pub fn num_new(&self) -> usize {
+        self.is_suppressed.iter().filter(|s| !**s).count()
+    }
+
+    pub fn to_json(&self) -> Value {
+        json!({
+            "num_new": self.num_new(),
+            "suppressed": self.suppressed,
+            "unmatched": self.unmatched,
+        })
+    }
+}
+
+impl Baseline {
+    /// Reads a baseline written by `save`.
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let baseline: Baseline = serde_json::from_str(&fs::read_to_string(path)?)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        if baseline.version != BASELINE_VERSION {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "unsupported baseline version {} (expected {})",
+                    baseline.version, BASELINE_VERSION
+                ),
+            ));
+        }
+        Ok(baseline)
+    }
+
+    /// Writes the baseline as indented JSON, so that its changes can be reviewed.
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        fs::write(path, json + "\n")
+    }
+
+    /// Splits the findings of a run on the main template `template` into the ones in the
+    /// baseline and the new ones.
+    pub fn compare(&self, template: &str, findings: &[BaselineEntry]) -> BaselineComparison {
+        let known: FxHashMap<&str, &BaselineEntry> = self
+            .findings
+            .iter()
+            .map(|entry| (entry.fingerprint.as_str(), entry))
+            .collect();
+        let found: FxHashSet<&str> = findings.iter().map(|f| f.fingerprint.as_str()).collect();
+
+        let mut suppressed: Vec<BaselineEntry> = Vec::new();
+        let is_suppressed = findings
+            .iter()
+            .map(|finding| match known.get(finding.fingerprint.as_str()) {
+                Some(entry) => {
+                    if !suppressed.contains(*entry) {
+                        suppressed.push((*entry).clone());
+                    }
+                    true
+                }
+                None => false,
+            })
+            .collect();
+        let unmatched = self
+            .findings
+            .iter()
+            .filter(|entry| {
+                entry.template == template && !found.contains(entry.fingerprint.as_str())
+            })
+            .cloned()
+            .collect();
+        BaselineComparison {
+            is_suppressed,
+            suppressed,
+            unmatched,
+        }
+    }
+
+    /// Returns the baseline recording the findings of a run on the main template `template` in
+    /// place of its previous findings, keeping the names and reasons of the entries that are
+    /// found again and the entries of the other templates.
+    pub fn update(&self, template: &str, findings: &[BaselineEntry]) -> Baseline {
+        let previous: FxHashMap<&str, &BaselineEntry> = self
+            .findings
+            .iter()
+            .map(|entry| (entry.fingerprint.as_str(), entry))
+            .collect();
+
+        let mut entries: Vec<BaselineEntry> = self
+            .findings
+            .iter()
+            .filter(|entry| entry.template != template)
+            .cloned()
+            .collect();
+        for finding in findings {
+            if entries.iter().any(|e| e.fingerprint == finding.fingerprint) {
+                continue;
+            }
+            let mut entry = finding.clone();
+            if let Some(previous) = previous.get(finding.fingerprint.as_str()) {
+                entry.name = previous.name.clone();
+                entry.reason = previous.reason.clone();
+            }
+            entries.push(entry);
+        }
+        entries.sort_by(|a, b| {
+            (&a.template, &a.kind, &a.description).cmp(&(&b.template, &b.kind, &b.description))
+        });
+        Baseline {
+            version: BASELINE_VERSION,
+            findings: entries,
+        }
+    }
+}
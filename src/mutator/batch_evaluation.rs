@@ -0,0 +1,658 @@
+use std::fmt;
+use std::io;
+use std::io::Write;
+use std::str::FromStr;
+use std::time::Instant;
+
+use num_bigint_dig::BigInt;
+use num_traits::{One, Signed, ToPrimitive, Zero};
+use program_structure::ast::{ExpressionInfixOpcode, ExpressionPrefixOpcode};
+use rustc_hash::FxHashMap;
+
+use crate::executor::symbolic_execution::SymbolicExecutor;
+use crate::executor::symbolic_value::{
+    extract_variables, SymbolicName, SymbolicValue, SymbolicValueRef,
+};
+use crate::executor::utils::modreduce;
+use crate::mutator::brute_force::brute_force_search_until;
+use crate::mutator::compiled_constraints::CompiledConstraints;
+#[cfg(feature = "gpu")]
+use crate::mutator::gpu_evaluation::GpuBatchEvaluator;
+use crate::mutator::input_spec::InputDomain;
+use crate::mutator::search_ranges::SearchRanges;
+use crate::mutator::structural_seeds::StructuralSeeds;
+use crate::mutator::utils::{
+    gather_input_domains, is_vulnerable, verify_assignment_with_compiled_constraints,
+    BaseVerificationConfig, CounterExample, VerificationResult,
+};
+
+/// The number of assignments evaluated per batch on the CPU.
+const CPU_BATCH_SIZE: usize = 1 << 12;
+
+/// An instruction of a `FieldProgram`, operating on a stack of field elements in `[0, p)` where
+/// `false` and `true` are `0` and `1`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum FieldInstruction {
+    /// Pushes the constant at the given index of `FieldProgram::constants`.
+    Constant(usize),
+    /// Pushes the value of the variable at the given slot.
+    Load(usize),
+    /// Pushes `1`.
+    One,
+    Add,
+    Sub,
+    Mul,
+    /// Pops `x` and pushes `-x`.
+    Neg,
+    /// Pops `rhs` and `lhs`, and pushes whether they are equal.
+    Eq,
+    /// Pops `rhs` and `lhs`, and pushes whether they differ.
+    NotEq,
+    /// Pops `rhs` and `lhs`, and pushes whether both are non-zero.
+    And,
+    /// Pops `rhs` and `lhs`, and pushes whether either is non-zero.
+    Or,
+    /// Pops `x` and pushes whether it is zero.
+    Not,
+    /// Pops the else-branch, the then-branch, and the condition, and pushes the then-branch if
+    /// the condition is non-zero.
+    Select,
+}
+
+/// The type of a value on the stack of a tape being lowered, which decides whether the
+/// instructions reproduce the evaluation of `CompiledConstraints`.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    /// An integer in `[0, p)`.
+    Int,
+    /// The negation of an integer in `[0, p)`, which `CompiledConstraints` keeps in `(-p, 0]`.
+    Negated,
+    Bool,
+}
+
+/// The trace and side constraints lowered to tapes of field arithmetic over the variables of a
+/// search, which a `BatchEvaluator` runs on many assignments at once.
+///
+/// Only the constraints that `CompiledConstraints` evaluates to the same result as the tapes are
+/// lowered: those built from additions, multiplications, negations, (in)equalities, boolean
+/// operators, and conditionals, over constants and variables assigned values in `[0, p)`. The
+/// others, e.g. with divisions, relational operators, or function calls, are counted, and the
+/// assignments whose verdict depends on them are confirmed on the CPU.
+#[derive(Clone)]
+pub struct FieldProgram {
+    pub prime: BigInt,
+    /// The number of variables, whose values are loaded from the slots of an assignment.
+    pub num_slots: usize,
+    pub constants: Vec<BigInt>,
+    pub trace: Vec<Vec<FieldInstruction>>,
+    pub side_constraints: Vec<Vec<FieldInstruction>>,
+    pub num_unlowered_trace: usize,
+    pub num_unlowered_side_constraints: usize,
+}
+
+impl FieldProgram {
+    /// Lowers the constraints over `variables`, whose positions are their slots.
+    pub fn lower(
+        symbolic_trace: &[SymbolicValueRef],
+        side_constraints: &[SymbolicValueRef],
+        variables: &[SymbolicName],
+        prime: &BigInt,
+    ) -> Self {
+        let slots: FxHashMap<&SymbolicName, usize> = variables
+            .iter()
+            .enumerate()
+            .map(|(slot, var)| (var, slot))
+            .collect();
+        let mut program = FieldProgram {
+            prime: prime.clone(),
+            num_slots: variables.len(),
+            constants: Vec::new(),
+            trace: Vec::new(),
+            side_constraints: Vec::new(),
+            num_unlowered_trace: 0,
+            num_unlowered_side_constraints: 0,
+        };
+        for constraint in symbolic_trace {
+            match program.lower_constraint(constraint, &slots) {
+                Some(tape) => program.trace.push(tape),
+                None => program.num_unlowered_trace += 1,
+            }
+        }
+        for constraint in side_constraints {
+            match program.lower_constraint(constraint, &slots) {
+                Some(tape) => program.side_constraints.push(tape),
+                None => program.num_unlowered_side_constraints += 1,
+            }
+        }
+        program
+    }
+
+    fn lower_constraint(
+        &mut self,
+        constraint: &SymbolicValue,
+        slots: &FxHashMap<&SymbolicName, usize>,
+    ) -> Option<Vec<FieldInstruction>> {
+        let num_constants = self.constants.len();
+        let mut tape = Vec::new();
+        match self.lower_value(constraint, slots, &mut tape) {
+            Some(Kind::Bool) => Some(tape),
+            _ => {
+                self.constants.truncate(num_constants);
+                None
+            }
+        }
+    }
+
+    fn lower_value(
+        &mut self,
+        value: &SymbolicValue,
+        slots: &FxHashMap<&SymbolicName, usize>,
+        tape: &mut Vec<FieldInstruction>,
+    ) -> Option<Kind> {
+        let kind = match value {
+            SymbolicValue::ConstantInt(v) => {
+                if v.is_negative() || *v >= self.prime {
+                    return None;
+                }
+                self.constants.push(v.clone());
+                tape.push(FieldInstruction::Constant(self.constants.len() - 1));
+                Kind::Int
+            }
+            SymbolicValue::ConstantBool(b) => {
+                tape.push(FieldInstruction::One);
+                if !b {
+                    tape.push(FieldInstruction::Not);
+                }
+                Kind::Bool
+            }
+            SymbolicValue::Variable(sym_name) => {
+                tape.push(FieldInstruction::Load(*slots.get(sym_name)?));
+                Kind::Int
+            }
+            SymbolicValue::Assign(lhs, rhs, ..)
+            | SymbolicValue::AssignEq(lhs, rhs)
+            | SymbolicValue::AssignCall(lhs, rhs, _) => {
+                // The sides of an assignment are compared without normalizing negated integers
+                if self.lower_value(lhs, slots, tape)? != Kind::Int
+                    || self.lower_value(rhs, slots, tape)? == Kind::Negated
+                {
+                    return None;
+                }
+                tape.push(FieldInstruction::Eq);
+                Kind::Bool
+            }
+            SymbolicValue::AssignTemplParam(..) => {
+                tape.push(FieldInstruction::One);
+                Kind::Bool
+            }
+            SymbolicValue::BinaryOp(lhs, op, rhs) | SymbolicValue::AuxBinaryOp(lhs, op, rhs) => {
+                let (instruction, kind) = match op.0 {
+                    ExpressionInfixOpcode::Add => (FieldInstruction::Add, Kind::Int),
+                    ExpressionInfixOpcode::Sub => (FieldInstruction::Sub, Kind::Int),
+                    ExpressionInfixOpcode::Mul => (FieldInstruction::Mul, Kind::Int),
+                    ExpressionInfixOpcode::Eq => (FieldInstruction::Eq, Kind::Bool),
+                    ExpressionInfixOpcode::NotEq => (FieldInstruction::NotEq, Kind::Bool),
+                    ExpressionInfixOpcode::BoolAnd => (FieldInstruction::And, Kind::Bool),
+                    ExpressionInfixOpcode::BoolOr => (FieldInstruction::Or, Kind::Bool),
+                    _ => return None,
+                };
+                self.lower_value(lhs, slots, tape)?;
+                self.lower_value(rhs, slots, tape)?;
+                tape.push(instruction);
+                kind
+            }
+            SymbolicValue::UnaryOp(op, expr) => match op.0 {
+                ExpressionPrefixOpcode::Sub => {
+                    let kind = match self.lower_value(expr, slots, tape)? {
+                        Kind::Int => Kind::Negated,
+                        Kind::Negated => Kind::Int,
+                        Kind::Bool => return None,
+                    };
+                    tape.push(FieldInstruction::Neg);
+                    kind
+                }
+                ExpressionPrefixOpcode::BoolNot => {
+                    if self.lower_value(expr, slots, tape)? != Kind::Bool {
+                        return None;
+                    }
+                    tape.push(FieldInstruction::Not);
+                    Kind::Bool
+                }
+                _ => return None,
+            },
+            SymbolicValue::Conditional(cond, then_branch, else_branch) => {
+                // A negated condition is never positive, whereas its field element is non-zero
+                if self.lower_value(cond, slots, tape)? == Kind::Negated {
+                    return None;
+                }
+                let kind = self.lower_value(then_branch, slots, tape)?;
+                if self.lower_value(else_branch, slots, tape)? != kind {
+                    return None;
+                }
+                tape.push(FieldInstruction::Select);
+                kind
+            }
+            SymbolicValue::NOP
+            | SymbolicValue::Array(..)
+            | SymbolicValue::UniformArray(..)
+            | SymbolicValue::Call(..) => return None,
+        };
+        Some(kind)
+    }
+
+    /// Returns the number of lowered constraints.
+    pub fn num_lowered(&self) -> usize {
+        self.trace.len() + self.side_constraints.len()
+    }
+}
+
+/// Evaluates the tapes of a `FieldProgram` on batches of assignments.
+pub trait BatchEvaluator {
+    /// Returns the number of assignments evaluated per batch.
+    fn batch_size(&self) -> usize;
+
+    /// Evaluates the lowered constraints on `assignments`, which holds the values of the slots of
+    /// each assignment in a row, in `[0, p)`.
+    ///
+    /// # Returns
+    /// For each assignment, whether it satisfies the lowered trace constraints and whether it
+    /// satisfies the lowered side constraints.
+    fn evaluate_batch(&mut self, assignments: &[BigInt]) -> Vec<(bool, bool)>;
+}
+
+/// Evaluates the tapes with `BigInt` arithmetic on the host. It is the reference that the kernel
+/// of the GPU backend reproduces.
+pub struct CpuBatchEvaluator {
+    program: FieldProgram,
+}
+
+impl CpuBatchEvaluator {
+    pub fn new(program: FieldProgram) -> Self {
+        CpuBatchEvaluator { program: program }
+    }
+
+    fn run_tape(
+        &self,
+        tape: &[FieldInstruction],
+        slots: &[BigInt],
+        stack: &mut Vec<BigInt>,
+    ) -> bool {
+        let prime = &self.program.prime;
+        let from_bool = |b: bool| if b { BigInt::one() } else { BigInt::zero() };
+        stack.clear();
+        for instruction in tape {
+            let value = match instruction {
+                FieldInstruction::Constant(i) => self.program.constants[*i].clone(),
+                FieldInstruction::Load(slot) => slots[*slot].clone(),
+                FieldInstruction::One => BigInt::one(),
+                FieldInstruction::Neg => {
+                    let x = stack.pop().unwrap();
+                    if x.is_zero() {
+                        x
+                    } else {
+                        prime - x
+                    }
+                }
+                FieldInstruction::Not => from_bool(stack.pop().unwrap().is_zero()),
+                FieldInstruction::Select => {
+                    let else_value = stack.pop().unwrap();
+                    let then_value = stack.pop().unwrap();
+                    if stack.pop().unwrap().is_zero() {
+                        else_value
+                    } else {
+                        then_value
+                    }
+                }
+                _ => {
+                    let rhs = stack.pop().unwrap();
+                    let lhs = stack.pop().unwrap();
+                    match instruction {
+                        FieldInstruction::Add => (lhs + rhs) % prime,
+                        FieldInstruction::Sub => (lhs + prime - rhs) % prime,
+                        FieldInstruction::Mul => (lhs * rhs) % prime,
+                        FieldInstruction::Eq => from_bool(lhs == rhs),
+                        FieldInstruction::NotEq => from_bool(lhs != rhs),
+                        FieldInstruction::And => from_bool(!lhs.is_zero() && !rhs.is_zero()),
+                        FieldInstruction::Or => from_bool(!lhs.is_zero() || !rhs.is_zero()),
+                        _ => unreachable!(),
+                    }
+                }
+            };
+            stack.push(value);
+        }
+        !stack.pop().unwrap().is_zero()
+    }
+}
+
+impl BatchEvaluator for CpuBatchEvaluator {
+    fn batch_size(&self) -> usize {
+        CPU_BATCH_SIZE
+    }
+
+    fn evaluate_batch(&mut self, assignments: &[BigInt]) -> Vec<(bool, bool)> {
+        let mut stack = Vec::new();
+        assignments
+            .chunks(self.program.num_slots.max(1))
+            .map(|slots| {
+                (
+                    self.program
+                        .trace
+                        .iter()
+                        .all(|tape| self.run_tape(tape, slots, &mut stack)),
+                    self.program
+                        .side_constraints
+                        .iter()
+                        .all(|tape| self.run_tape(tape, slots, &mut stack)),
+                )
+            })
+            .collect()
+    }
+}
+
+/// The device on which the batched search evaluates the candidate assignments.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EvaluationBackend {
+    Cpu,
+    Gpu,
+}
+
+impl EvaluationBackend {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EvaluationBackend::Cpu => "cpu",
+            EvaluationBackend::Gpu => "gpu",
+        }
+    }
+
+    /// Returns whether zkFuzz was built with the backend: the GPU backend requires the `gpu`
+    /// feature.
+    pub fn is_available(&self) -> bool {
+        match self {
+            EvaluationBackend::Cpu => true,
+            EvaluationBackend::Gpu => cfg!(feature = "gpu"),
+        }
+    }
+}
+
+impl fmt::Display for EvaluationBackend {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl FromStr for EvaluationBackend {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "cpu" => Ok(EvaluationBackend::Cpu),
+            "gpu" => Ok(EvaluationBackend::Gpu),
+            _ => Err(format!(
+                "unknown evaluation backend: {} (expected cpu or gpu)",
+                s
+            )),
+        }
+    }
+}
+
+/// Creates the evaluator of `program` on `backend`.
+///
+/// # Returns
+/// The evaluator, or the reason why the backend is unavailable, e.g. no GPU adapter.
+pub fn create_evaluator(
+    backend: EvaluationBackend,
+    program: &FieldProgram,
+) -> Result<Box<dyn BatchEvaluator>, String> {
+    match backend {
+        EvaluationBackend::Cpu => Ok(Box::new(CpuBatchEvaluator::new(program.clone()))),
+        #[cfg(feature = "gpu")]
+        EvaluationBackend::Gpu => Ok(Box::new(GpuBatchEvaluator::new(program)?)),
+        #[cfg(not(feature = "gpu"))]
+        EvaluationBackend::Gpu => Err("zkFuzz was built without the `gpu` feature".to_string()),
+    }
+}
+
+/// The values a variable takes in the batched search.
+enum CandidateSet {
+    Values(Vec<BigInt>),
+    /// Every value between the two bounds, inclusive.
+    Range(BigInt, BigInt),
+}
+
+impl CandidateSet {
+    fn size(&self) -> BigInt {
+        match self {
+            CandidateSet::Values(values) => BigInt::from(values.len()),
+            CandidateSet::Range(min, max) => max - min + BigInt::one(),
+        }
+    }
+
+    fn value_at(&self, position: &BigInt) -> BigInt {
+        match self {
+            CandidateSet::Values(values) => values[position.to_usize().unwrap()].clone(),
+            CandidateSet::Range(min, _) => min + position,
+        }
+    }
+}
+
+/// Reduces `values` to `[0, p)`, removing the duplicates and keeping the first occurrences.
+fn reduce_values<'a, I>(values: I, prime: &BigInt) -> CandidateSet
+where
+    I: IntoIterator<Item = &'a BigInt>,
+{
+    let mut reduced: Vec<BigInt> = Vec::new();
+    for value in values {
+        let value = modreduce(value, prime);
+        if !reduced.contains(&value) {
+            reduced.push(value);
+        }
+    }
+    CandidateSet::Values(reduced)
+}
+
+/// Moves `positions` to the next assignment, the last variable varying the fastest.
+///
+/// # Returns
+/// `false` once every assignment has been enumerated.
+fn advance(positions: &mut [BigInt], sizes: &[BigInt]) -> bool {
+    for (position, size) in positions.iter_mut().zip(sizes.iter()).rev() {
+        *position += BigInt::one();
+        if *position < *size {
+            return true;
+        }
+        *position = BigInt::zero();
+    }
+    false
+}
+
+/// Runs the search of `brute_force_search_until` in the quick or full mode, evaluating the
+/// candidate assignments in batches on `backend`.
+///
+/// Each variable takes the values of its domain, where ranges are enumerated entirely instead of
+/// their bounds, then the values scheduled by `SearchRanges` in the quick mode, or every value of
+/// `[0, p)` in the full mode. The values are reduced to `[0, p)`, so that `-1` is tried as
+/// `p-1`. The assignments whose verdict on the lowered constraints (see `FieldProgram`) may be a
+/// counterexample are confirmed on the CPU with `BigInt` arithmetic in the order they are
+/// enumerated, so the first confirmed one is reported. Unlike the sequential search, the
+/// variables in infeasible branches are not pruned.
+///
+/// The sequential search is run instead in the heuristics mode, without variables, or if the
+/// backend is unavailable.
+pub fn batch_brute_force_search(
+    sexe: &mut SymbolicExecutor,
+    symbolic_trace: &Vec<SymbolicValueRef>,
+    side_constraints: &Vec<SymbolicValueRef>,
+    base_config: &BaseVerificationConfig,
+    domains: &FxHashMap<SymbolicName, InputDomain>,
+    deadline: Option<Instant>,
+    backend: EvaluationBackend,
+) -> Option<CounterExample> {
+    let mut variables: Vec<SymbolicName> = Vec::new();
+    for var in extract_variables(symbolic_trace)
+        .into_iter()
+        .chain(extract_variables(side_constraints))
+    {
+        if !variables.contains(&var) {
+            variables.push(var);
+        }
+    }
+
+    let program = FieldProgram::lower(
+        symbolic_trace,
+        side_constraints,
+        &variables,
+        &base_config.prime,
+    );
+    let evaluator = if base_config.heuristics_mode || variables.is_empty() {
+        Err("only the quick and full modes are batched".to_string())
+    } else {
+        create_evaluator(backend, &program)
+    };
+    let mut evaluator = match evaluator {
+        Ok(evaluator) => evaluator,
+        Err(reason) => {
+            outln!(" • Running the sequential search: {}", reason);
+            return brute_force_search_until(
+                sexe,
+                symbolic_trace,
+                side_constraints,
+                base_config,
+                domains,
+                deadline,
+            );
+        }
+    };
+    outln!(
+        " • Evaluating on the {} backend: {} / {} constraints lowered",
+        backend,
+        program.num_lowered(),
+        symbolic_trace.len() + side_constraints.len()
+    );
+
+    let prime = &base_config.prime;
+    let mut restricted_domains =
+        gather_input_domains(sexe.symbolic_library, base_config, &variables);
+    restricted_domains.extend(
+        domains
+            .iter()
+            .map(|(var, domain)| (var.clone(), domain.clone())),
+    );
+    let seeds = StructuralSeeds::gather(
+        symbolic_trace,
+        side_constraints,
+        sexe.symbolic_library,
+        base_config,
+        &variables,
+    );
+    let ranges = SearchRanges::infer(sexe, symbolic_trace, side_constraints, base_config);
+    let candidate_sets: Vec<CandidateSet> = variables
+        .iter()
+        .map(|var| match restricted_domains.get(var) {
+            Some(InputDomain::Range(min, max)) if !min.is_negative() && max < prime => {
+                CandidateSet::Range(min.clone(), max.clone())
+            }
+            Some(domain) => reduce_values(domain.candidates().iter(), prime),
+            None if base_config.quick_mode => reduce_values(
+                ranges.schedule(var, seeds.candidates_of(var), true).iter(),
+                prime,
+            ),
+            None => CandidateSet::Range(BigInt::zero(), prime - BigInt::one()),
+        })
+        .collect();
+    let sizes: Vec<BigInt> = candidate_sets.iter().map(|set| set.size()).collect();
+
+    // The verdict of an assignment is only known if it does not depend on the constraints that
+    // are not lowered.
+    let verdict = |is_satisfied: bool, num_unlowered: usize| {
+        if !is_satisfied {
+            Some(false)
+        } else if num_unlowered == 0 {
+            Some(true)
+        } else {
+            None
+        }
+    };
+    let compiled_trace = CompiledConstraints::compile(symbolic_trace);
+    let compiled_side_constraints = CompiledConstraints::compile(side_constraints);
+    let mut positions = vec![BigInt::zero(); variables.len()];
+    let mut is_exhausted = sizes.iter().any(|size| size.is_zero());
+    let mut num_evaluated = 0;
+    let mut num_confirmed = 0;
+    let mut timed_out = false;
+    let mut counter_example = None;
+    while !is_exhausted && counter_example.is_none() {
+        if deadline.map_or(false, |d| Instant::now() >= d) {
+            timed_out = true;
+            break;
+        }
+        let mut batch: Vec<BigInt> = Vec::new();
+        while batch.len() < evaluator.batch_size() * variables.len() && !is_exhausted {
+            batch.extend(
+                candidate_sets
+                    .iter()
+                    .zip(positions.iter())
+                    .map(|(set, position)| set.value_at(position)),
+            );
+            is_exhausted = !advance(&mut positions, &sizes);
+        }
+
+        for (values, (is_satisfy_st, is_satisfy_sc)) in batch
+            .chunks(variables.len())
+            .zip(evaluator.evaluate_batch(&batch))
+        {
+            num_evaluated += 1;
+            let st = verdict(is_satisfy_st, program.num_unlowered_trace);
+            let sc = verdict(is_satisfy_sc, program.num_unlowered_side_constraints);
+            let is_suspicious = st.is_none()
+                || sc.is_none()
+                || st != sc
+                || (base_config.check_output_tags && sc == Some(true));
+            if !is_suspicious {
+                continue;
+            }
+            num_confirmed += 1;
+            let assignment: FxHashMap<SymbolicName, BigInt> = variables
+                .iter()
+                .cloned()
+                .zip(values.iter().cloned())
+                .collect();
+            let result = verify_assignment_with_compiled_constraints(
+                sexe,
+                &compiled_trace,
+                &compiled_side_constraints,
+                &assignment,
+                base_config,
+            );
+            if is_vulnerable(&result) {
+                counter_example = Some(CounterExample {
+                    flag: result,
+                    target_output: None,
+                    assignment: assignment,
+                });
+                break;
+            }
+        }
+        out!(
+            "\rProgress: {} / {}^{}",
+            num_evaluated,
+            prime,
+            variables.len()
+        );
+        io::stdout().flush().unwrap();
+    }
+
+    if timed_out {
+        outln!("\n • Search stopped at the deadline");
+    } else {
+        outln!("\n • Search completed");
+    }
+    outln!("     ├─ Total iterations: {}", num_evaluated);
+    outln!("     ├─ Confirmed on the CPU: {}", num_confirmed);
+    outln!(
+        "     └─ Verification result: {}",
+        counter_example
+            .as_ref()
+            .map_or(VerificationResult::WellConstrained, |ce| ce.flag.clone())
+    );
+    counter_example
+}
@@ -1,8 +1,9 @@
 use std::collections::HashSet;
 use std::io;
 use std::io::Write;
-use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::sync::Arc;
+use std::time::Instant;
 
 use num_bigint_dig::BigInt;
 use num_traits::{One, Zero};
@@ -10,8 +11,14 @@ use rustc_hash::FxHashMap;
 
 use crate::executor::symbolic_execution::SymbolicExecutor;
 use crate::executor::symbolic_value::{extract_variables, SymbolicName, SymbolicValueRef};
+use crate::mutator::constraint_scheduler::ConstraintScheduler;
+use crate::mutator::input_spec::InputDomain;
+use crate::mutator::path_conditions::PathConditions;
+use crate::mutator::search_ranges::SearchRanges;
+use crate::mutator::structural_seeds::StructuralSeeds;
 use crate::mutator::utils::{
-    is_vulnerable, verify_assignment, BaseVerificationConfig, CounterExample, VerificationResult,
+    gather_input_domains, is_vulnerable, verify_assignment_with_scheduler, BaseVerificationConfig,
+    CounterExample, VerificationResult,
 };
 
 /// Performs a brute-force search over variable assignments to evaluate constraints.
@@ -29,6 +36,29 @@ pub fn brute_force_search(
     symbolic_trace: &Vec<SymbolicValueRef>,
     side_constraints: &Vec<SymbolicValueRef>,
     base_config: &BaseVerificationConfig,
+) -> Option<CounterExample> {
+    brute_force_search_until(
+        sexe,
+        symbolic_trace,
+        side_constraints,
+        base_config,
+        &FxHashMap::default(),
+        None,
+    )
+}
+
+/// Runs `brute_force_search` until `deadline`, if any, enumerating the values of `domains` for
+/// the variables it restricts instead of the candidates of the search mode.
+///
+/// The domains take precedence over those declared with `--input_spec` or induced by tags, and
+/// may restrict any variable of the constraints, not only the inputs of the main template.
+pub fn brute_force_search_until(
+    sexe: &mut SymbolicExecutor,
+    symbolic_trace: &Vec<SymbolicValueRef>,
+    side_constraints: &Vec<SymbolicValueRef>,
+    base_config: &BaseVerificationConfig,
+    domains: &FxHashMap<SymbolicName, InputDomain>,
+    deadline: Option<Instant>,
 ) -> Option<CounterExample> {
     let mut trace_variables = extract_variables(symbolic_trace);
     let mut side_variables = extract_variables(side_constraints);
@@ -38,24 +68,71 @@ pub fn brute_force_search(
     variables.append(&mut side_variables);
     let variables_set: HashSet<SymbolicName> = variables.iter().cloned().collect();
     variables = variables_set.into_iter().collect();
+    let path_conditions = PathConditions::gather(
+        symbolic_trace,
+        side_constraints,
+        sexe.symbolic_library,
+        base_config,
+    );
+    path_conditions.order_variables(&mut variables);
+    let mut restricted_domains =
+        gather_input_domains(sexe.symbolic_library, base_config, &variables);
+    restricted_domains.extend(
+        domains
+            .iter()
+            .map(|(var, domain)| (var.clone(), domain.clone())),
+    );
+    // The quick and heuristics modes try the values of the range inferred for each variable,
+    // special values and those derived from its constraints (e.g. the constants it is compared
+    // against) first.
+    let seeds = StructuralSeeds::gather(
+        symbolic_trace,
+        side_constraints,
+        sexe.symbolic_library,
+        base_config,
+        &variables,
+    );
+    let ranges = SearchRanges::infer(sexe, symbolic_trace, side_constraints, base_config);
+    let mut schedules = FxHashMap::default();
+    if base_config.quick_mode || base_config.heuristics_mode {
+        for var in &variables {
+            if !restricted_domains.contains_key(var) {
+                schedules.insert(
+                    var.clone(),
+                    ranges.schedule(var, seeds.candidates_of(var), base_config.quick_mode),
+                );
+            }
+        }
+    }
 
     let mut assignment = FxHashMap::default();
     let current_iteration = Arc::new(AtomicUsize::new(0));
+    let num_pruned = AtomicUsize::new(0);
+    let timed_out = AtomicBool::new(false);
 
     fn search(
         sexe: &mut SymbolicExecutor,
-        symbolic_trace: &[SymbolicValueRef],
-        side_constraints: &[SymbolicValueRef],
+        scheduler: &mut ConstraintScheduler,
         base_config: &BaseVerificationConfig,
         index: usize,
         variables: &[SymbolicName],
+        domains: &FxHashMap<SymbolicName, InputDomain>,
+        schedules: &FxHashMap<SymbolicName, Vec<BigInt>>,
         assignment: &mut FxHashMap<SymbolicName, BigInt>,
+        path_conditions: &PathConditions,
         current_iteration: &Arc<AtomicUsize>,
+        num_pruned: &AtomicUsize,
+        deadline: Option<Instant>,
+        timed_out: &AtomicBool,
     ) -> VerificationResult {
         if index == variables.len() {
             let iter = current_iteration.fetch_add(1, Ordering::SeqCst);
+            if deadline.map_or(false, |d| Instant::now() >= d) {
+                timed_out.store(true, Ordering::SeqCst);
+                return VerificationResult::WellConstrained;
+            }
             if iter % base_config.progress_interval == 0 {
-                print!(
+                out!(
                     "\rProgress: {} / {}^{}",
                     iter,
                     &base_config.prime,
@@ -64,78 +141,84 @@ pub fn brute_force_search(
                 io::stdout().flush().unwrap();
             }
 
-            return verify_assignment(
-                sexe,
-                symbolic_trace,
-                side_constraints,
-                assignment,
-                base_config,
-            );
+            return verify_assignment_with_scheduler(sexe, scheduler, assignment, base_config);
         }
 
         let var = &variables[index];
-        if base_config.quick_mode {
-            let candidates = vec![BigInt::zero(), BigInt::one(), -1 * BigInt::one()];
-            for c in candidates.into_iter() {
+        // A variable that only occurs in branches falsified by the variables assigned so far
+        // does not affect the verdict, so a single value of it is tried.
+        if path_conditions.is_infeasible(
+            var,
+            &base_config.prime,
+            assignment,
+            &mut sexe.symbolic_library,
+        ) {
+            num_pruned.fetch_add(1, Ordering::SeqCst);
+            assignment.insert(var.clone(), BigInt::zero());
+            let result = search(
+                sexe,
+                scheduler,
+                base_config,
+                index + 1,
+                variables,
+                domains,
+                schedules,
+                assignment,
+                path_conditions,
+                current_iteration,
+                num_pruned,
+                deadline,
+                timed_out,
+            );
+            if is_vulnerable(&result) || timed_out.load(Ordering::SeqCst) {
+                return result;
+            }
+            assignment.remove(var);
+        } else if let Some(domain) = domains.get(var) {
+            for c in domain.candidates().iter() {
                 assignment.insert(var.clone(), c.clone());
                 let result = search(
                     sexe,
-                    symbolic_trace,
-                    side_constraints,
+                    scheduler,
                     base_config,
                     index + 1,
                     variables,
+                    domains,
+                    schedules,
                     assignment,
+                    path_conditions,
                     current_iteration,
+                    num_pruned,
+                    deadline,
+                    timed_out,
                 );
-                if is_vulnerable(&result) {
+                if is_vulnerable(&result) || timed_out.load(Ordering::SeqCst) {
                     return result;
                 }
                 assignment.remove(var);
             }
-        } else if base_config.heuristics_mode {
-            let mut value = -&base_config.range;
-            while value <= base_config.range {
-                assignment.insert(var.clone(), value.clone());
-
-                let result = search(
-                    sexe,
-                    symbolic_trace,
-                    side_constraints,
-                    base_config,
-                    index + 1,
-                    variables,
-                    assignment,
-                    current_iteration,
-                );
-
-                if is_vulnerable(&result) {
-                    return result;
-                }
-                assignment.remove(&var);
-                value += BigInt::one();
-            }
-            let mut value = &base_config.prime - &base_config.range;
-
-            while value < base_config.prime {
-                assignment.insert(var.clone(), value.clone());
-
+        } else if let Some(candidates) = schedules.get(var) {
+            for c in candidates.iter() {
+                assignment.insert(var.clone(), c.clone());
                 let result = search(
                     sexe,
-                    symbolic_trace,
-                    side_constraints,
+                    scheduler,
                     base_config,
                     index + 1,
                     variables,
+                    domains,
+                    schedules,
                     assignment,
+                    path_conditions,
                     current_iteration,
+                    num_pruned,
+                    deadline,
+                    timed_out,
                 );
-
-                if is_vulnerable(&result) {
+                if is_vulnerable(&result) || timed_out.load(Ordering::SeqCst) {
                     return result;
                 }
-                assignment.remove(&var);
-                value += BigInt::one();
+                assignment.remove(var);
             }
         } else {
             let mut value = BigInt::zero();
@@ -143,15 +226,20 @@ pub fn brute_force_search(
                 assignment.insert(var.clone(), value.clone());
                 let result = search(
                     sexe,
-                    symbolic_trace,
-                    side_constraints,
+                    scheduler,
                     base_config,
                     index + 1,
                     variables,
+                    domains,
+                    schedules,
                     assignment,
+                    path_conditions,
                     current_iteration,
+                    num_pruned,
+                    deadline,
+                    timed_out,
                 );
-                if is_vulnerable(&result) {
+                if is_vulnerable(&result) || timed_out.load(Ordering::SeqCst) {
                     return result;
                 }
                 assignment.remove(var);
@@ -161,18 +249,28 @@ pub fn brute_force_search(
         VerificationResult::WellConstrained
     }
 
+    // The constraints are compiled once so that each candidate assignment is evaluated without
+    // walking the symbolic values, and in the order learned by the scheduler across the
+    // candidates of every search mode.
+    let mut scheduler = ConstraintScheduler::new(symbolic_trace, side_constraints);
+
     let flag = search(
         sexe,
-        &symbolic_trace,
-        &side_constraints,
+        &mut scheduler,
         base_config,
         0,
         &variables,
+        &restricted_domains,
+        &schedules,
         &mut assignment,
+        &path_conditions,
         &current_iteration,
+        &num_pruned,
+        deadline,
+        &timed_out,
     );
 
-    print!(
+    out!(
         "\rProgress: {} / {}^{}",
         current_iteration.load(Ordering::SeqCst),
         base_config.prime,
@@ -180,12 +278,33 @@ pub fn brute_force_search(
     );
     io::stdout().flush().unwrap();
 
-    println!("\n • Search completed");
-    println!(
+    if timed_out.load(Ordering::SeqCst) {
+        outln!("\n • Search stopped at the deadline");
+    } else {
+        outln!("\n • Search completed");
+    }
+    outln!(
         "     ├─ Total iterations: {}",
         current_iteration.load(Ordering::SeqCst)
     );
-    println!("     └─ Verification result: {}", flag);
+    if scheduler.num_candidates() > 0 {
+        outln!(
+            "     ├─ Average constraints evaluated per candidate: {:.2} / {}",
+            scheduler.average_evaluated(),
+            scheduler.num_constraints()
+        );
+    }
+    let num_bounded = ranges.num_bounded(&variables);
+    if !schedules.is_empty() && num_bounded > 0 {
+        outln!("     ├─ Variables with inferred ranges: {}", num_bounded);
+    }
+    if !path_conditions.is_empty() {
+        outln!(
+            "     ├─ Variables fixed in infeasible branches: {}",
+            num_pruned.load(Ordering::SeqCst)
+        );
+    }
+    outln!("     └─ Verification result: {}", flag);
 
     if is_vulnerable(&flag) {
         Some(CounterExample {
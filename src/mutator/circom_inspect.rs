@@ -0,0 +1,209 @@
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+
+use serde_json::{json, Value};
+
+use crate::executor::utils::strip_ansi;
+
+/// A warning printed by `circom --inspect`, e.g.
+/// `warning[CA01]: In template "Main()": Local signal out does not appear in any constraint`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct InspectWarning {
+    /// The code of the warning, e.g. `CA01`.
+    pub code: String,
+    /// The template the warning is about, without its arguments.
+    pub template: Option<String>,
+    /// The signal the warning is about, as written by circom (e.g. `out` or `n2b.in`).
+    pub signal: Option<String>,
+    /// The line of the circuit the warning points to.
+    pub line: Option<usize>,
+    pub message: String,
+}
+
+impl InspectWarning {
+    /// Returns `true` if `text` mentions the signal of the warning, either by its name or by a
+    /// path ending with it (e.g. `main.out` or `main.out[2]` for `out`).
+    pub fn is_mentioned_in(&self, text: &str) -> bool {
+        let signal = match &self.signal {
+            Some(signal) => signal,
+            None => return false,
+        };
+        let is_identifier = |c: char| c.is_alphanumeric() || c == '_' || c == '.';
+        strip_ansi(text)
+            .split(|c: char| !(is_identifier(c) || c == '[' || c == ']'))
+            .map(|token| token.split('[').next().unwrap_or(""))
+            .any(|token| token == signal || token.ends_with(&format!(".{}", signal)))
+    }
+
+    pub fn to_json(&self) -> Value {
+        json!({
+            "code": self.code,
+            "template": self.template,
+            "signal": self.signal,
+            "line": self.line,
+            "message": self.message,
+        })
+    }
+}
+
+/// The result of cross-referencing the warnings of `circom --inspect` with the findings of
+/// zkFuzz.
+pub struct InspectCrossCheck {
+    /// The warnings about a signal that a finding of zkFuzz mentions, with the finding.
+    pub agreements: Vec<(InspectWarning, String)>,
+    /// The warnings that no finding of zkFuzz mentions.
+    pub circom_only: Vec<InspectWarning>,
+    /// The findings of zkFuzz that mention none of the signals of the warnings.
+    pub zkfuzz_only: Vec<String>,
+}
+
+impl InspectCrossCheck {
+    /// Cross-references the warnings with the findings, each described by a line of text that
+    /// names the signals involved (e.g. `UnderConstrained-NonDeterministic: main.out`).
+    pub fn new(warnings: Vec<InspectWarning>, findings: &[String]) -> Self {
+        let mut agreements = Vec::new();
+        let mut circom_only = Vec::new();
+        let mut matched = vec![false; findings.len()];
+        for warning in warnings {
+            let mentions: Vec<usize> = (0..findings.len())
+                .filter(|i| warning.is_mentioned_in(&findings[*i]))
+                .collect();
+            match mentions.first() {
+                Some(i) => {
+                    let finding = findings[*i].clone();
+                    for j in mentions {
+                        matched[j] = true;
+                    }
+                    agreements.push((warning, finding));
+                }
+                None => circom_only.push(warning),
+            }
+        }
+        let zkfuzz_only = findings
+            .iter()
+            .zip(matched)
+            .filter(|(_, matched)| !matched)
+            .map(|(finding, _)| finding.clone())
+            .collect();
+        InspectCrossCheck {
+            agreements,
+            circom_only,
+            zkfuzz_only,
+        }
+    }
+
+    pub fn to_json(&self) -> Value {
+        json!({
+            "agreements": self
+                .agreements
+                .iter()
+                .map(|(warning, finding)| json!({"warning": warning.to_json(), "finding": finding}))
+                .collect::<Vec<_>>(),
+            "circom_only": self
+                .circom_only
+                .iter()
+                .map(|warning| warning.to_json())
+                .collect::<Vec<_>>(),
+            "zkfuzz_only": self.zkfuzz_only,
+        })
+    }
+}
+
+/// Parses the warnings that circom prints to stderr, each a header line
+/// `warning[<code>]: <message>` followed by the location `┌─ "<file>":<line>:<column>`.
+pub fn parse_inspect_warnings(output: &str) -> Vec<InspectWarning> {
+    let mut warnings: Vec<InspectWarning> = Vec::new();
+    for line in strip_ansi(output).lines() {
+        let line = line.trim();
+        if let Some(rest) = line.strip_prefix("warning[") {
+            let (code, message) = match rest.split_once("]:") {
+                Some((code, message)) => (code.to_string(), message.trim().to_string()),
+                None => continue,
+            };
+            let template = message
+                .split_once("template \"")
+                .and_then(|(_, rest)| rest.split(|c| c == '"' || c == '(').next())
+                .map(|name| name.to_string());
+            warnings.push(InspectWarning {
+                code: code,
+                template: template,
+                signal: signal_of_message(&message),
+                line: None,
+                message: message,
+            });
+        } else if let Some(location) = line.strip_prefix("┌─") {
+            if let Some(warning) = warnings.last_mut() {
+                if warning.line.is_none() {
+                    warning.line = location
+                        .rsplitn(3, ':')
+                        .nth(1)
+                        .and_then(|line| line.trim().parse().ok());
+                }
+            }
+        }
+    }
+    warnings
+}
+
+/// Extracts the signal of a warning from the word following `signal` or `signals` (skipping
+/// `array`), e.g. `out` in `Local signal out does not appear in any constraint`.
+fn signal_of_message(message: &str) -> Option<String> {
+    let mut words = message.split_whitespace();
+    while let Some(word) = words.next() {
+        if word == "signal" || word == "signals" {
+            let mut name = words.next()?;
+            if name == "array" {
+                name = words.next()?;
+            }
+            let name = name.trim_matches(|c: char| !(c.is_alphanumeric() || c == '_'));
+            if !name.is_empty() {
+                return Some(name.to_string());
+            }
+        }
+    }
+    None
+}
+
+/// Runs `circom --inspect` on the circuit and returns its warnings.
+///
+/// # Parameters
+/// - `path_to_circom`: The circom binary.
+/// - `input_file`: The path to the circuit.
+/// - `link_libraries`: The directories passed to circom with `-l`.
+/// - `prime_name`: The name of the prime passed to circom with `-p`.
+///
+/// # Returns
+/// The warnings, or an error if circom could not be run or failed to compile the circuit.
+pub fn run_circom_inspect(
+    path_to_circom: &str,
+    input_file: &str,
+    link_libraries: &[PathBuf],
+    prime_name: &str,
+) -> Result<Vec<InspectWarning>, String> {
+    let out_dir = env::temp_dir().join(format!("zkfuzz_inspect_{}", std::process::id()));
+    fs::create_dir_all(&out_dir).map_err(|e| e.to_string())?;
+    let mut command = Command::new(path_to_circom);
+    command
+        .arg(input_file)
+        .args(["--inspect", "-p", prime_name, "-o"])
+        .arg(&out_dir);
+    for lib in link_libraries {
+        command.arg("-l").arg(lib);
+    }
+    let output = command.output();
+    let _ = fs::remove_dir_all(&out_dir);
+    let output = output.map_err(|e| format!("unable to run `{}`: {}", path_to_circom, e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "circom failed to compile the circuit: {}",
+            strip_ansi(&String::from_utf8_lossy(&output.stderr)).trim()
+        ));
+    }
+    Ok(parse_inspect_warnings(&format!(
+        "{}\n{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    )))
+}
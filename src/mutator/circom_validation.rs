@@ -0,0 +1,505 @@
+use std::env;
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use num_bigint_dig::{BigInt, Sign};
+use num_traits::{One, Zero};
+use rustc_hash::FxHashMap;
+use serde_json::{json, Value};
+
+use crate::executor::symbolic_value::SymbolicLibrary;
+use crate::executor::utils::{modreduce, strip_ansi};
+use crate::mutator::utils::{
+    BaseVerificationConfig, CounterExample, UnderConstrainedType, VerificationResult,
+};
+use crate::mutator::witness_export::build_input_json;
+
+/// Node.js driver that runs one input through the witness calculator of `circom --wasm` and
+/// prints whether it was accepted and the whole witness.
+const WITNESS_DRIVER_SCRIPT: &str = r#"const fs = require("fs");
+const path = require("path");
+
+const wasmPath = path.resolve(process.argv[2]);
+const builder = require(path.join(path.dirname(wasmPath), "witness_calculator.js"));
+const input = JSON.parse(fs.readFileSync(process.argv[3], "utf8"));
+
+builder(fs.readFileSync(wasmPath)).then(async (witnessCalculator) => {
+    try {
+        const witness = await witnessCalculator.calculateWitness(input, 0);
+        process.stdout.write(JSON.stringify({
+            accepted: true,
+            witness: witness.map((w) => w.toString()),
+        }));
+    } catch (e) {
+        process.stdout.write(JSON.stringify({
+            accepted: false,
+            error: String((e && e.message) || e),
+        }));
+    }
+});
+"#;
+
+/// A linear combination of the wires of an R1CS, as pairs of a wire and its coefficient.
+pub type LinearCombination = Vec<(usize, BigInt)>;
+
+/// The constraint system of a `.r1cs` file: every constraint is `A * B = C`.
+pub struct R1cs {
+    pub prime: BigInt,
+    pub num_wires: usize,
+    pub num_outputs: usize,
+    pub constraints: Vec<(LinearCombination, LinearCombination, LinearCombination)>,
+}
+
+impl R1cs {
+    /// Returns the index of the first constraint that `witness` violates, if any.
+    pub fn first_violated(&self, witness: &[BigInt]) -> Option<usize> {
+        let eval = |lc: &LinearCombination| {
+            lc.iter().fold(BigInt::zero(), |acc, (wire, coeff)| {
+                (acc + coeff * witness.get(*wire).cloned().unwrap_or_else(BigInt::zero))
+                    % &self.prime
+            })
+        };
+        self.constraints
+            .iter()
+            .position(|(a, b, c)| (eval(a) * eval(b) - eval(c)) % &self.prime != BigInt::zero())
+    }
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, n: usize) -> Result<&'a [u8], String> {
+        if self.pos + n > self.bytes.len() {
+            return Err("unexpected end of the R1CS file".to_string());
+        }
+        let slice = &self.bytes[self.pos..self.pos + n];
+        self.pos += n;
+        Ok(slice)
+    }
+
+    fn u32(&mut self) -> Result<u32, String> {
+        let mut buf = [0u8; 4];
+        buf.copy_from_slice(self.take(4)?);
+        Ok(u32::from_le_bytes(buf))
+    }
+
+    fn u64(&mut self) -> Result<u64, String> {
+        let mut buf = [0u8; 8];
+        buf.copy_from_slice(self.take(8)?);
+        Ok(u64::from_le_bytes(buf))
+    }
+
+    fn field(&mut self, field_size: usize) -> Result<BigInt, String> {
+        Ok(BigInt::from_bytes_le(Sign::Plus, self.take(field_size)?))
+    }
+
+    fn linear_combination(&mut self, field_size: usize) -> Result<LinearCombination, String> {
+        let num_terms = self.u32()?;
+        let mut lc = Vec::with_capacity(num_terms as usize);
+        for _ in 0..num_terms {
+            let wire = self.u32()? as usize;
+            lc.push((wire, self.field(field_size)?));
+        }
+        Ok(lc)
+    }
+}
+
+/// Parses the binary `.r1cs` format written by `circom --r1cs`.
+pub fn parse_r1cs(bytes: &[u8]) -> Result<R1cs, String> {
+    let mut reader = Reader {
+        bytes: bytes,
+        pos: 0,
+    };
+    if reader.take(4)? != b"r1cs" {
+        return Err("not an R1CS file".to_string());
+    }
+    let _version = reader.u32()?;
+    let num_sections = reader.u32()?;
+    let mut sections = FxHashMap::default();
+    for _ in 0..num_sections {
+        let section_type = reader.u32()?;
+        let size = reader.u64()? as usize;
+        sections.insert(section_type, reader.take(size)?);
+    }
+
+    let header = sections
+        .get(&1)
+        .ok_or_else(|| "the R1CS file has no header".to_string())?;
+    let mut reader = Reader {
+        bytes: header,
+        pos: 0,
+    };
+    let field_size = reader.u32()? as usize;
+    let prime = reader.field(field_size)?;
+    let num_wires = reader.u32()? as usize;
+    let num_outputs = reader.u32()? as usize;
+    let _num_public_inputs = reader.u32()?;
+    let _num_private_inputs = reader.u32()?;
+    let _num_labels = reader.u64()?;
+    let num_constraints = reader.u32()?;
+
+    let mut constraints = Vec::with_capacity(num_constraints as usize);
+    if let Some(section) = sections.get(&2) {
+        let mut reader = Reader {
+            bytes: section,
+            pos: 0,
+        };
+        for _ in 0..num_constraints {
+            let a = reader.linear_combination(field_size)?;
+            let b = reader.linear_combination(field_size)?;
+            let c = reader.linear_combination(field_size)?;
+            constraints.push((a, b, c));
+        }
+    }
+
+    Ok(R1cs {
+        prime: prime,
+        num_wires: num_wires,
+        num_outputs: num_outputs,
+        constraints: constraints,
+    })
+}
+
+/// Parses the `.sym` file written by `circom --sym` into a map from the name of each signal,
+/// e.g. `main.c.in[0]`, to its wire. Signals removed by the optimizer have no wire.
+pub fn parse_sym(content: &str) -> FxHashMap<String, usize> {
+    content
+        .lines()
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.splitn(4, ',').collect();
+            if fields.len() != 4 {
+                return None;
+            }
+            let wire = fields[1].trim().parse::<i64>().ok()?;
+            if wire < 0 {
+                return None;
+            }
+            Some((fields[3].trim().to_string(), wire as usize))
+        })
+        .collect()
+}
+
+/// Whether the real circom pipeline agrees with a counterexample.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum ValidationStatus {
+    Confirmed,
+    Unconfirmed,
+}
+
+pub struct CircomValidation {
+    pub status: ValidationStatus,
+    pub reason: String,
+}
+
+impl CircomValidation {
+    fn confirmed(reason: String) -> Self {
+        CircomValidation {
+            status: ValidationStatus::Confirmed,
+            reason: reason,
+        }
+    }
+
+    fn unconfirmed(reason: String) -> Self {
+        CircomValidation {
+            status: ValidationStatus::Unconfirmed,
+            reason: reason,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self.status {
+            ValidationStatus::Confirmed => "CONFIRMED",
+            ValidationStatus::Unconfirmed => "UNCONFIRMED",
+        }
+    }
+
+    pub fn to_json(&self) -> Value {
+        json!({
+            "status": self.label(),
+            "reason": self.reason,
+        })
+    }
+}
+
+/// The witness computed by the witness calculator of circom, or `None` if it rejected the input.
+pub fn run_witness_calculator(
+    path_to_wasm: &Path,
+    input: &Value,
+) -> io::Result<Option<Vec<BigInt>>> {
+    let tmp_dir = env::temp_dir();
+    let script_path = tmp_dir.join(format!("zkfuzz_validation_{}.js", std::process::id()));
+    let input_path = tmp_dir.join(format!("zkfuzz_validation_{}.json", std::process::id()));
+    File::create(&script_path)?.write_all(WITNESS_DRIVER_SCRIPT.as_bytes())?;
+    File::create(&input_path)?.write_all(serde_json::to_string(input)?.as_bytes())?;
+
+    let output = Command::new("node")
+        .arg(&script_path)
+        .arg(path_to_wasm)
+        .arg(&input_path)
+        .output();
+    let _ = fs::remove_file(&script_path);
+    let _ = fs::remove_file(&input_path);
+    let output = output?;
+    if !output.status.success() {
+        return Err(io::Error::new(
+            io::ErrorKind::Other,
+            String::from_utf8_lossy(&output.stderr).to_string(),
+        ));
+    }
+
+    let result: Value = serde_json::from_slice(&output.stdout)?;
+    if !result["accepted"].as_bool().unwrap_or(false) {
+        return Ok(None);
+    }
+    Ok(Some(
+        result["witness"]
+            .as_array()
+            .map(|witness| {
+                witness
+                    .iter()
+                    .map(|w| {
+                        w.as_str()
+                            .and_then(|s| s.parse::<BigInt>().ok())
+                            .unwrap_or_else(BigInt::zero)
+                    })
+                    .collect()
+            })
+            .unwrap_or_default(),
+    ))
+}
+
+/// Compiles the circuit with circom into `out_dir` and returns the paths to the `.r1cs`, `.sym`,
+/// and `.wasm` files.
+pub fn compile_with_circom(
+    path_to_circom: &str,
+    input_file: &str,
+    link_libraries: &[PathBuf],
+    prime_name: &str,
+    out_dir: &Path,
+) -> Result<(PathBuf, PathBuf, PathBuf), String> {
+    fs::create_dir_all(out_dir).map_err(|e| e.to_string())?;
+    let mut command = Command::new(path_to_circom);
+    command
+        .arg(input_file)
+        .args(["--r1cs", "--wasm", "--sym", "--O0", "-p", prime_name, "-o"])
+        .arg(out_dir);
+    for lib in link_libraries {
+        command.arg("-l").arg(lib);
+    }
+    let output = command
+        .output()
+        .map_err(|e| format!("unable to run `{}`: {}", path_to_circom, e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "circom failed to compile the circuit: {}",
+            strip_ansi(&String::from_utf8_lossy(&output.stderr)).trim()
+        ));
+    }
+
+    let stem = Path::new(input_file)
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+    Ok((
+        out_dir.join(format!("{}.r1cs", stem)),
+        out_dir.join(format!("{}.sym", stem)),
+        out_dir
+            .join(format!("{}_js", stem))
+            .join(format!("{}.wasm", stem)),
+    ))
+}
+
+/// Builds the witness of an under-constrained counterexample: the witness computed by circom
+/// (or zeros if it rejected the inputs) with the wires of the signals of the counterexample
+/// overwritten by their values, and the constant wire set to one.
+pub(crate) fn forge_witness(
+    r1cs: &R1cs,
+    wires: &FxHashMap<String, usize>,
+    honest_witness: Option<&Vec<BigInt>>,
+    ce_values: &FxHashMap<String, BigInt>,
+) -> Vec<BigInt> {
+    let mut witness = honest_witness
+        .cloned()
+        .unwrap_or_else(|| vec![BigInt::zero(); r1cs.num_wires]);
+    if let Some(w) = witness.get_mut(0) {
+        *w = BigInt::one();
+    }
+    for (name, wire) in wires {
+        if let (Some(value), Some(w)) = (ce_values.get(name), witness.get_mut(*wire)) {
+            *w = modreduce(value, &r1cs.prime);
+        }
+    }
+    witness
+}
+
+/// Returns the values of the signals of a counterexample, keyed by their names as in the `.sym`
+/// file.
+pub(crate) fn counterexample_values(
+    ce: &CounterExample,
+    symbolic_library: &SymbolicLibrary,
+) -> FxHashMap<String, BigInt> {
+    ce.assignment
+        .iter()
+        .map(|(name, value)| {
+            (
+                strip_ansi(&name.lookup_fmt(&symbolic_library.id2name)),
+                value.clone(),
+            )
+        })
+        .collect()
+}
+
+/// Decides whether the R1CS and the witness calculator of circom exhibit the counterexample.
+///
+/// # Parameters
+/// - `ce`: The counterexample found by zkFuzz.
+/// - `r1cs`: The constraint system compiled by circom.
+/// - `wires`: The wire of each signal, from the `.sym` file.
+/// - `honest_witness`: The witness computed by circom's witness calculator for the inputs of the
+///   counterexample, or `None` if it rejected them.
+/// - `ce_values`: The values of the signals of the counterexample, keyed by their names.
+pub fn judge_counterexample(
+    ce: &CounterExample,
+    r1cs: &R1cs,
+    wires: &FxHashMap<String, usize>,
+    honest_witness: Option<&Vec<BigInt>>,
+    ce_values: &FxHashMap<String, BigInt>,
+) -> CircomValidation {
+    match &ce.flag {
+        VerificationResult::UnderConstrained(kind) => {
+            let witness = forge_witness(r1cs, wires, honest_witness, ce_values);
+            if let Some(i) = r1cs.first_violated(&witness) {
+                return CircomValidation::unconfirmed(format!(
+                    "constraint #{} of the R1CS rejects the witness of the counterexample",
+                    i
+                ));
+            }
+            let honest_witness = match honest_witness {
+                Some(honest_witness) => honest_witness,
+                None => {
+                    return CircomValidation::confirmed(
+                        "the R1CS accepts a witness of inputs rejected by circom".to_string(),
+                    );
+                }
+            };
+            let mut differing: Vec<(&String, usize)> = wires
+                .iter()
+                .filter(|(_, wire)| witness.get(**wire) != honest_witness.get(**wire))
+                .map(|(name, wire)| (name, *wire))
+                .collect();
+            differing.sort_by_key(|(_, wire)| *wire);
+            let is_output = |wire: usize| 1 <= wire && wire <= r1cs.num_outputs;
+            match differing.iter().find(|(_, wire)| is_output(*wire)).or(
+                if matches!(kind, UnderConstrainedType::NonDeterministic(..)) {
+                    None
+                } else {
+                    differing.first()
+                },
+            ) {
+                Some((name, _)) => CircomValidation::confirmed(format!(
+                    "the R1CS accepts a witness where `{}` differs from circom's witness \
+                     calculator",
+                    name
+                )),
+                None => CircomValidation::unconfirmed(
+                    "the witness of the counterexample matches circom's witness calculator"
+                        .to_string(),
+                ),
+            }
+        }
+        VerificationResult::OverConstrained => match honest_witness {
+            Some(honest_witness) => match r1cs.first_violated(honest_witness) {
+                Some(i) => CircomValidation::confirmed(format!(
+                    "constraint #{} of the R1CS rejects the witness computed by circom",
+                    i
+                )),
+                None => CircomValidation::unconfirmed(
+                    "the R1CS accepts the witness computed by circom".to_string(),
+                ),
+            },
+            None => CircomValidation::unconfirmed(
+                "circom's witness calculator rejects the inputs".to_string(),
+            ),
+        },
+        VerificationResult::AssertionFailure(..) | VerificationResult::DivisionByZero(..) => {
+            match honest_witness {
+                Some(_) => CircomValidation::unconfirmed(
+                    "circom's witness calculator accepts the inputs".to_string(),
+                ),
+                None => CircomValidation::confirmed(
+                    "circom's witness calculator rejects the inputs".to_string(),
+                ),
+            }
+        }
+        VerificationResult::WellConstrained => {
+            CircomValidation::unconfirmed("nothing to validate".to_string())
+        }
+    }
+}
+
+/// Replays a counterexample through the real circom pipeline.
+///
+/// The circuit is compiled with `circom --r1cs --wasm --sym --O0`, so that every signal keeps its
+/// wire, and the inputs of the counterexample are run through the generated witness calculator
+/// with `node`. An under-constrained counterexample is confirmed when the R1CS accepts its witness
+/// while the witness calculator computes different outputs (or, for the other kinds of
+/// under-constrained circuits, any different signal) or rejects its inputs. An over-constrained
+/// one is confirmed when the R1CS rejects the witness computed by circom, and a failing assertion
+/// or division by zero when the witness calculator rejects the inputs.
+///
+/// # Parameters
+/// - `path_to_circom`: The circom binary.
+/// - `input_file`: The circuit.
+/// - `link_libraries`: The directories passed to circom with `-l`.
+/// - `prime_name`: The curve passed to circom with `-p`.
+/// - `ce`: The counterexample found by zkFuzz.
+/// - `symbolic_library`: The symbolic library containing the template definitions.
+/// - `base_config`: The verification base_configs.
+/// - `id2dimensions`: The dimensions of the signals declared in the main template.
+///
+/// # Returns
+/// A `CircomValidation` labeling the counterexample CONFIRMED or UNCONFIRMED, the latter also
+/// when the circuit could not be compiled or its witness calculator could not be run.
+pub fn validate_with_circom(
+    path_to_circom: &str,
+    input_file: &str,
+    link_libraries: &[PathBuf],
+    prime_name: &str,
+    ce: &CounterExample,
+    symbolic_library: &SymbolicLibrary,
+    base_config: &BaseVerificationConfig,
+    id2dimensions: &FxHashMap<usize, Vec<usize>>,
+) -> CircomValidation {
+    let out_dir = env::temp_dir().join(format!("zkfuzz_validation_{}", std::process::id()));
+    let result = (|| {
+        let (r1cs_path, sym_path, wasm_path) = compile_with_circom(
+            path_to_circom,
+            input_file,
+            link_libraries,
+            prime_name,
+            &out_dir,
+        )?;
+        let r1cs = parse_r1cs(&fs::read(&r1cs_path).map_err(|e| e.to_string())?)?;
+        let wires = parse_sym(&fs::read_to_string(&sym_path).map_err(|e| e.to_string())?);
+
+        let input = build_input_json(&ce.assignment, symbolic_library, base_config, id2dimensions);
+        let honest_witness = run_witness_calculator(&wasm_path, &input)
+            .map_err(|e| format!("unable to run the witness calculator: {}", e))?;
+
+        let ce_values = counterexample_values(ce, symbolic_library);
+        Ok::<_, String>(judge_counterexample(
+            ce,
+            &r1cs,
+            &wires,
+            honest_witness.as_ref(),
+            &ce_values,
+        ))
+    })();
+    let _ = fs::remove_dir_all(&out_dir);
+    result.unwrap_or_else(CircomValidation::unconfirmed)
+}
@@ -0,0 +1,189 @@
+use std::hash::{Hash, Hasher};
+
+use rustc_hash::FxHashMap;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::executor::structural_hash::StableHasher;
+use crate::executor::utils::strip_ansi;
+use crate::mutator::utils::{CounterExample, UnderConstrainedType, VerificationResult};
+
+/// What a counterexample violates, with the concrete signal paths abstracted away so that
+/// findings of the same root cause compare equal across components and circuits.
+///
+/// Two signatures are equal if they have the same kind, structure, and signal; `constraint` is
+/// only reported.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct FindingSignature {
+    /// The kind of the finding, e.g. `UnderConstrained-NonDeterministic`.
+    pub kind: String,
+    /// The structural hash of the violated constraint, assertion, or denominator under
+    /// `Equivalence::FINDINGS` (see `structural_hash`), of the violated tag, or `0`.
+    #[serde(default)]
+    pub structure: u64,
+    /// The violated constraint, assertion, denominator, or tag, as formatted in the finding.
+    pub constraint: String,
+    /// The divergent signal, relative to the main component.
+    pub signal: String,
+}
+
+impl PartialEq for FindingSignature {
+    fn eq(&self, other: &Self) -> bool {
+        self.kind == other.kind && self.structure == other.structure && self.signal == other.signal
+    }
+}
+
+impl Eq for FindingSignature {}
+
+impl Hash for FindingSignature {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.kind.hash(state);
+        self.structure.hash(state);
+        self.signal.hash(state);
+    }
+}
+
+impl FindingSignature {
+    /// Computes the signature of a counterexample.
+    ///
+    /// # Parameters
+    /// - `ce`: The counterexample.
+    /// - `lookup`: A map from ids to the names of the symbols.
+    pub fn of(ce: &CounterExample, lookup: &FxHashMap<usize, String>) -> Self {
+        let target = ce
+            .target_output
+            .as_ref()
+            .map_or("".to_string(), |name| name.lookup_fmt(lookup));
+        let (kind, violated, signal) = match &ce.flag {
+            VerificationResult::UnderConstrained(typ) => match typ {
+                UnderConstrainedType::UnusedOutput => {
+                    ("UnderConstrained-UnusedOutput", None, target)
+                }
+                UnderConstrainedType::UnexpectedInput(_pos, violated_condition) => (
+                    "UnderConstrained-UnexpectedInput",
+                    Some((
+                        violated_condition.structure,
+                        violated_condition.formatted.clone(),
+                    )),
+                    target,
+                ),
+                UnderConstrainedType::NonDeterministic(_sym_name, name, _value) => {
+                    ("UnderConstrained-NonDeterministic", None, name.clone())
+                }
+                UnderConstrainedType::TagViolation(_sym_name, name, tag) => {
+                    let mut hasher = StableHasher::default();
+                    hasher.write_str(tag);
+                    (
+                        "UnderConstrained-TagViolation",
+                        Some((hasher.finish(), tag.clone())),
+                        name.clone(),
+                    )
+                }
+            },
+            VerificationResult::OverConstrained => ("OverConstrained", None, target),
+            VerificationResult::AssertionFailure(_location, condition) => (
+                "AssertionFailure",
+                Some((condition.structure, condition.formatted.clone())),
+                target,
+            ),
+            VerificationResult::DivisionByZero(_location, denominator) => (
+                "DivisionByZero",
+                Some((denominator.structure, denominator.formatted.clone())),
+                target,
+            ),
+            VerificationResult::WellConstrained => ("WellConstrained", None, target),
+        };
+        let (structure, constraint) = violated.unwrap_or((0, "".to_string()));
+        FindingSignature {
+            kind: kind.to_string(),
+            structure: structure,
+            constraint: strip_ansi(&constraint),
+            signal: relative_signal_path(&signal),
+        }
+    }
+
+    /// Returns a stable hexadecimal hash of the signature, used as the id of its cluster.
+    pub fn structural_hash(&self) -> String {
+        let mut hasher = StableHasher::default();
+        hasher.write_str(&self.kind);
+        hasher.write_u64(self.structure);
+        hasher.write_str(&self.signal);
+        format!("{:016x}", hasher.finish())
+    }
+}
+
+/// Abstracts the path of a signal, e.g. `main.n2b[3].out[0]`, into `n2b[].out[]`.
+fn relative_signal_path(path: &str) -> String {
+    let mut relative = String::new();
+    let mut depth = 0;
+    for c in strip_ansi(path).chars() {
+        match c {
+            '[' => {
+                if depth == 0 {
+                    relative.push_str("[]");
+                }
+                depth += 1;
+            }
+            ']' => depth -= 1,
+            _ if depth > 0 => {}
+            c => relative.push(c),
+        }
+    }
+    match relative.strip_prefix("main.") {
+        Some(rest) => rest.to_string(),
+        None => relative,
+    }
+}
+
+/// A group of findings sharing the same signature.
+pub struct FindingCluster<T> {
+    pub signature: FindingSignature,
+    /// The first finding of the cluster, reported on behalf of the others.
+    pub representative: T,
+    pub count: usize,
+}
+
+impl<T: Serialize> FindingCluster<T> {
+    pub fn to_json(&self) -> Value {
+        json!({
+            "id": self.signature.structural_hash(),
+            "kind": self.signature.kind,
+            "constraint": self.signature.constraint,
+            "signal": self.signature.signal,
+            "count": self.count,
+            "representative": self.representative,
+        })
+    }
+}
+
+/// Groups findings by their signature, so that each root cause is triaged once.
+///
+/// # Parameters
+/// - `findings`: The signature of each finding together with what identifies it (e.g. the path of
+///   its counterexample).
+///
+/// # Returns
+/// One cluster per distinct signature, the largest first. Clusters of the same size keep the order
+/// in which their first finding appears.
+pub fn cluster_findings<T, I>(findings: I) -> Vec<FindingCluster<T>>
+where
+    I: IntoIterator<Item = (FindingSignature, T)>,
+{
+    let mut clusters: Vec<FindingCluster<T>> = Vec::new();
+    let mut index: FxHashMap<FindingSignature, usize> = FxHashMap::default();
+    for (signature, finding) in findings {
+        match index.get(&signature) {
+            Some(&i) => clusters[i].count += 1,
+            None => {
+                index.insert(signature.clone(), clusters.len());
+                clusters.push(FindingCluster {
+                    signature: signature,
+                    representative: finding,
+                    count: 1,
+                });
+            }
+        }
+    }
+    clusters.sort_by(|a, b| b.count.cmp(&a.count));
+    clusters
+}
@@ -0,0 +1,407 @@
+use std::borrow::Cow;
+
+use num_bigint_dig::BigInt;
+use num_traits::{One, Signed, Zero};
+use program_structure::ast::{ExpressionInfixOpcode, ExpressionPrefixOpcode};
+use rustc_hash::FxHashMap;
+
+use crate::executor::debug_ast::DebuggableExpressionInfixOpcode;
+use crate::executor::symbolic_value::{
+    val_for_relational_operators, SymbolicLibrary, SymbolicName, SymbolicValue, SymbolicValueRef,
+};
+use crate::executor::utils::{
+    bitwise, complement, moddiv, modpow, modreduce, shift_left, shift_right,
+};
+use crate::mutator::utils::evaluate_constraints;
+
+/// The cost, in tape instructions, charged for a constraint evaluated with
+/// `evaluate_constraints`, which allocates a `SymbolicValue` per node.
+const UNCOMPILED_COST: usize = 256;
+
+/// An instruction of the postfix tape of a compiled constraint.
+#[derive(Clone, Debug)]
+enum Instruction {
+    /// Pushes the constant at the given index of `CompiledConstraints::constants`.
+    Constant(usize),
+    /// Pushes the value of the variable at the given slot.
+    Load(usize),
+    /// Pushes `true`, the value of `AssignTemplParam`.
+    True,
+    /// Pops `rhs` and `lhs`, and pushes `lhs op rhs`. The flag selects the integer mode of
+    /// `AuxBinaryOp` for the relational operators.
+    Binary(DebuggableExpressionInfixOpcode, bool),
+    /// Pops an integer and pushes its negation.
+    Negate,
+    /// Pops an integer and pushes its bitwise complement.
+    Complement,
+    /// Pops a boolean and pushes its negation.
+    Not,
+    /// Pops the else-branch, the then-branch, and the condition, and pushes the selected branch.
+    Select,
+    /// Pops `rhs` and `lhs` of an assignment and pushes whether they are equal.
+    AssignEqual,
+}
+
+/// A value on the stack of the tape.
+#[derive(Clone, Debug)]
+enum Value<'a> {
+    Int(Cow<'a, BigInt>),
+    Bool(bool),
+}
+
+impl<'a> Value<'a> {
+    fn to_int(self, prime: &BigInt) -> Cow<'a, BigInt> {
+        match self {
+            Value::Int(v) if v.is_negative() || &*v >= prime => Cow::Owned(modreduce(&v, prime)),
+            Value::Int(v) => v,
+            Value::Bool(b) => Cow::Owned(if b { BigInt::one() } else { BigInt::zero() }),
+        }
+    }
+
+    fn to_bool(&self, prime: &BigInt) -> bool {
+        match self {
+            Value::Int(v) => !(&**v % prime).is_zero(),
+            Value::Bool(b) => *b,
+        }
+    }
+}
+
+/// Constraints compiled once into flat postfix tapes over variable slots.
+///
+/// `evaluate_constraints` walks the `SymbolicValue` trees and allocates a new `SymbolicValue`
+/// for every node on every evaluation, which dominates the cost of the brute-force search. The
+/// tapes are evaluated on a stack of plain integers and booleans instead, with the values of the
+/// variables borrowed from the assignment. Constraints that cannot be compiled (e.g., function
+/// calls and arrays), or whose evaluation hits a case the tape does not handle, are evaluated
+/// with `evaluate_constraints`, so that both produce the same results.
+pub struct CompiledConstraints {
+    constraints: Vec<SymbolicValueRef>,
+    tapes: Vec<Option<Vec<Instruction>>>,
+    constants: Vec<BigInt>,
+    variables: Vec<SymbolicName>,
+}
+
+impl CompiledConstraints {
+    /// Compiles `constraints` into tapes.
+    pub fn compile(constraints: &[SymbolicValueRef]) -> Self {
+        let mut compiled = CompiledConstraints {
+            constraints: constraints.to_vec(),
+            tapes: Vec::with_capacity(constraints.len()),
+            constants: Vec::new(),
+            variables: Vec::new(),
+        };
+        let mut slots = FxHashMap::default();
+        for constraint in constraints {
+            let mut tape = Vec::new();
+            let tape = if compiled.compile_value(constraint, &mut slots, &mut tape) {
+                Some(tape)
+            } else {
+                None
+            };
+            compiled.tapes.push(tape);
+        }
+        compiled
+    }
+
+    /// Returns the number of constraints evaluated on their tapes.
+    pub fn num_compiled(&self) -> usize {
+        self.tapes.iter().filter(|tape| tape.is_some()).count()
+    }
+
+    /// Returns the number of constraints.
+    pub fn len(&self) -> usize {
+        self.constraints.len()
+    }
+
+    /// Returns `true` if there is no constraint.
+    pub fn is_empty(&self) -> bool {
+        self.constraints.is_empty()
+    }
+
+    fn compile_value(
+        &mut self,
+        value: &SymbolicValue,
+        slots: &mut FxHashMap<SymbolicName, usize>,
+        tape: &mut Vec<Instruction>,
+    ) -> bool {
+        match value {
+            SymbolicValue::ConstantInt(v) => {
+                self.constants.push(v.clone());
+                tape.push(Instruction::Constant(self.constants.len() - 1));
+            }
+            SymbolicValue::ConstantBool(true) => tape.push(Instruction::True),
+            SymbolicValue::ConstantBool(false) => {
+                tape.push(Instruction::True);
+                tape.push(Instruction::Not);
+            }
+            SymbolicValue::Variable(sym_name) => {
+                let slot = *slots.entry(sym_name.clone()).or_insert_with(|| {
+                    self.variables.push(sym_name.clone());
+                    self.variables.len() - 1
+                });
+                tape.push(Instruction::Load(slot));
+            }
+            SymbolicValue::Assign(lhs, rhs, ..)
+            | SymbolicValue::AssignEq(lhs, rhs)
+            | SymbolicValue::AssignCall(lhs, rhs, _) => {
+                if !self.compile_value(lhs, slots, tape) || !self.compile_value(rhs, slots, tape) {
+                    return false;
+                }
+                tape.push(Instruction::AssignEqual);
+            }
+            SymbolicValue::AssignTemplParam(..) => tape.push(Instruction::True),
+            SymbolicValue::BinaryOp(lhs, op, rhs) | SymbolicValue::AuxBinaryOp(lhs, op, rhs) => {
+                if !self.compile_value(lhs, slots, tape) || !self.compile_value(rhs, slots, tape) {
+                    return false;
+                }
+                tape.push(Instruction::Binary(
+                    op.clone(),
+                    matches!(value, SymbolicValue::AuxBinaryOp(..)),
+                ));
+            }
+            SymbolicValue::UnaryOp(op, expr) => {
+                let instruction = match op.0 {
+                    ExpressionPrefixOpcode::Sub => Instruction::Negate,
+                    ExpressionPrefixOpcode::BoolNot => Instruction::Not,
+                    ExpressionPrefixOpcode::Complement => Instruction::Complement,
+                };
+                if !self.compile_value(expr, slots, tape) {
+                    return false;
+                }
+                tape.push(instruction);
+            }
+            SymbolicValue::Conditional(cond, then_branch, else_branch) => {
+                if !self.compile_value(cond, slots, tape)
+                    || !self.compile_value(then_branch, slots, tape)
+                    || !self.compile_value(else_branch, slots, tape)
+                {
+                    return false;
+                }
+                tape.push(Instruction::Select);
+            }
+            SymbolicValue::NOP
+            | SymbolicValue::Array(..)
+            | SymbolicValue::UniformArray(..)
+            | SymbolicValue::Call(..) => return false,
+        }
+        true
+    }
+
+    /// Evaluates a tape, returning `None` if it hits a case that is left to
+    /// `evaluate_constraints`, such as an unassigned variable or mismatched operand types.
+    fn run_tape<'a>(
+        &'a self,
+        prime: &BigInt,
+        tape: &[Instruction],
+        slots: &[Option<&'a BigInt>],
+        stack: &mut Vec<Value<'a>>,
+    ) -> Option<bool> {
+        stack.clear();
+        for instruction in tape {
+            let value = match instruction {
+                Instruction::Constant(i) => Value::Int(Cow::Borrowed(&self.constants[*i])),
+                Instruction::Load(slot) => Value::Int(Cow::Borrowed(slots[*slot]?)),
+                Instruction::True => Value::Bool(true),
+                Instruction::Binary(op, is_integer_mode) => {
+                    let rhs = stack.pop()?;
+                    let lhs = stack.pop()?;
+                    evaluate_binary_op_on_values(lhs, rhs, prime, op, *is_integer_mode)
+                }
+                Instruction::Negate => match stack.pop()? {
+                    Value::Int(v) => Value::Int(Cow::Owned(modreduce(&-v.into_owned(), prime))),
+                    Value::Bool(_) => return None,
+                },
+                Instruction::Complement => {
+                    Value::Int(Cow::Owned(complement(&stack.pop()?.to_int(prime), prime)))
+                }
+                Instruction::Not => match stack.pop()? {
+                    Value::Bool(b) => Value::Bool(!b),
+                    Value::Int(_) => return None,
+                },
+                Instruction::Select => {
+                    let else_value = stack.pop()?;
+                    let then_value = stack.pop()?;
+                    let is_then = match stack.pop()? {
+                        Value::Bool(b) => b,
+                        Value::Int(v) => v.is_positive(),
+                    };
+                    if is_then {
+                        then_value
+                    } else {
+                        else_value
+                    }
+                }
+                Instruction::AssignEqual => {
+                    let rhs = stack.pop()?;
+                    let lhs = match stack.pop()? {
+                        Value::Int(v) => v,
+                        Value::Bool(_) => return None,
+                    };
+                    let rhs = match rhs {
+                        Value::Int(v) => modreduce(&v, prime),
+                        Value::Bool(b) => {
+                            if b {
+                                BigInt::one()
+                            } else {
+                                BigInt::zero()
+                            }
+                        }
+                    };
+                    Value::Bool(modreduce(&lhs, prime) == rhs)
+                }
+            };
+            stack.push(value);
+        }
+        match stack.pop()? {
+            Value::Bool(b) if stack.is_empty() => Some(b),
+            _ => None,
+        }
+    }
+
+    /// Returns the cost of evaluating the constraint at `index`, measured in tape instructions.
+    /// Constraints without a tape are evaluated by `evaluate_constraints`, which is charged
+    /// `UNCOMPILED_COST` instructions.
+    pub fn cost(&self, index: usize) -> usize {
+        self.tapes[index]
+            .as_ref()
+            .map_or(UNCOMPILED_COST, |tape| tape.len())
+    }
+
+    fn evaluate_at<'a>(
+        &'a self,
+        index: usize,
+        prime: &BigInt,
+        slots: &[Option<&'a BigInt>],
+        stack: &mut Vec<Value<'a>>,
+        assignment: &FxHashMap<SymbolicName, BigInt>,
+        symbolic_library: &mut SymbolicLibrary,
+    ) -> bool {
+        match self.tapes[index]
+            .as_ref()
+            .and_then(|tape| self.run_tape(prime, tape, slots, stack))
+        {
+            Some(b) => b,
+            None => evaluate_constraints(
+                prime,
+                std::slice::from_ref(&self.constraints[index]),
+                assignment,
+                symbolic_library,
+            ),
+        }
+    }
+
+    /// Evaluates the constraints under `assignment`.
+    ///
+    /// # Returns
+    /// `true` if all constraints are satisfied, `false` otherwise, exactly as
+    /// `evaluate_constraints` on the original constraints.
+    pub fn evaluate(
+        &self,
+        prime: &BigInt,
+        assignment: &FxHashMap<SymbolicName, BigInt>,
+        symbolic_library: &mut SymbolicLibrary,
+    ) -> bool {
+        let slots: Vec<Option<&BigInt>> =
+            self.variables.iter().map(|v| assignment.get(v)).collect();
+        let mut stack = Vec::new();
+        (0..self.constraints.len()).all(|index| {
+            self.evaluate_at(
+                index,
+                prime,
+                &slots,
+                &mut stack,
+                assignment,
+                symbolic_library,
+            )
+        })
+    }
+
+    /// Evaluates the constraints in the order given by `order`, a permutation of their indices,
+    /// and stops at the first violated one.
+    ///
+    /// # Returns
+    /// The position in `order` of the first violated constraint, or `None` if all constraints
+    /// are satisfied.
+    pub fn find_violation(
+        &self,
+        order: &[usize],
+        prime: &BigInt,
+        assignment: &FxHashMap<SymbolicName, BigInt>,
+        symbolic_library: &mut SymbolicLibrary,
+    ) -> Option<usize> {
+        let slots: Vec<Option<&BigInt>> =
+            self.variables.iter().map(|v| assignment.get(v)).collect();
+        let mut stack = Vec::new();
+        order.iter().position(|&index| {
+            !self.evaluate_at(
+                index,
+                prime,
+                &slots,
+                &mut stack,
+                assignment,
+                symbolic_library,
+            )
+        })
+    }
+}
+
+/// Applies `op` to two concrete values with the semantics of `evaluate_binary_op` (or of
+/// `evaluate_binary_op_integer_mode` if `is_integer_mode` is set).
+fn evaluate_binary_op_on_values<'a>(
+    lhs: Value<'a>,
+    rhs: Value<'a>,
+    prime: &BigInt,
+    op: &DebuggableExpressionInfixOpcode,
+    is_integer_mode: bool,
+) -> Value<'a> {
+    if let ExpressionInfixOpcode::BoolAnd | ExpressionInfixOpcode::BoolOr = op.0 {
+        let (lv, rv) = (lhs.to_bool(prime), rhs.to_bool(prime));
+        return Value::Bool(match op.0 {
+            ExpressionInfixOpcode::BoolAnd => lv && rv,
+            _ => lv || rv,
+        });
+    }
+
+    let (lv, rv) = (lhs.to_int(prime), rhs.to_int(prime));
+    let (lv, rv) = (&*lv, &*rv);
+    let relational = |ordering: fn(&BigInt, &BigInt) -> bool| {
+        if is_integer_mode {
+            Value::Bool(ordering(&(lv % prime), &(rv % prime)))
+        } else {
+            Value::Bool(ordering(
+                &val_for_relational_operators(&(lv % prime), prime),
+                &val_for_relational_operators(&(rv % prime), prime),
+            ))
+        }
+    };
+    let int = |v: BigInt| Value::Int(Cow::Owned(v));
+    match op.0 {
+        ExpressionInfixOpcode::Add => int(modreduce(&(lv + rv), prime)),
+        ExpressionInfixOpcode::Sub => int(modreduce(&(lv - rv), prime)),
+        ExpressionInfixOpcode::Mul => int(modreduce(&(lv * rv), prime)),
+        ExpressionInfixOpcode::Pow => int(modpow(lv, rv, prime)),
+        ExpressionInfixOpcode::Div => int(moddiv(lv, rv, prime)),
+        ExpressionInfixOpcode::IntDiv => int(if lv.is_zero() || rv.is_zero() {
+            BigInt::zero()
+        } else {
+            lv / rv
+        }),
+        ExpressionInfixOpcode::Mod => int(if lv.is_zero() || rv.is_zero() {
+            BigInt::zero()
+        } else {
+            lv % rv
+        }),
+        ExpressionInfixOpcode::BitOr => int(bitwise(lv, rv, prime, |a, b| a | b)),
+        ExpressionInfixOpcode::BitAnd => int(bitwise(lv, rv, prime, |a, b| a & b)),
+        ExpressionInfixOpcode::BitXor => int(bitwise(lv, rv, prime, |a, b| a ^ b)),
+        ExpressionInfixOpcode::ShiftL => int(shift_left(lv, rv, prime)),
+        ExpressionInfixOpcode::ShiftR => int(shift_right(lv, rv, prime)),
+        ExpressionInfixOpcode::Lesser => relational(|a, b| a < b),
+        ExpressionInfixOpcode::Greater => relational(|a, b| a > b),
+        ExpressionInfixOpcode::LesserEq => relational(|a, b| a <= b),
+        ExpressionInfixOpcode::GreaterEq => relational(|a, b| a >= b),
+        ExpressionInfixOpcode::Eq => Value::Bool(lv % prime == rv % prime),
+        ExpressionInfixOpcode::NotEq => Value::Bool(lv % prime != rv % prime),
+        ExpressionInfixOpcode::BoolAnd | ExpressionInfixOpcode::BoolOr => unreachable!(),
+    }
+}
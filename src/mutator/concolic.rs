@@ -0,0 +1,302 @@
+use std::collections::VecDeque;
+use std::io;
+use std::io::Write;
+use std::rc::Rc;
+
+use colored::Colorize;
+use num_bigint_dig::BigInt;
+use num_bigint_dig::RandBigInt;
+use num_traits::{One, Zero};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use rustc_hash::{FxHashMap, FxHashSet};
+
+use crate::executor::concolic::{ConcolicGuide, PathCondition};
+use crate::executor::symbolic_execution::SymbolicExecutor;
+use crate::executor::symbolic_setting::SymbolicExecutorSetting;
+use crate::executor::symbolic_value::{OwnerName, SymbolicName, SymbolicValue, SymbolicValueRef};
+use crate::mutator::input_spec::InputDomain;
+use crate::mutator::utils::{
+    evaluate_symbolic_value, gather_input_domains, BaseVerificationConfig, CounterExample,
+    VerificationResult,
+};
+
+/// A path of the circuit explored by the concolic search.
+pub struct ConcolicPath {
+    /// The input that drives the execution down the path.
+    pub input: FxHashMap<SymbolicName, BigInt>,
+    /// The branches with symbolic conditions on the path, in the order of execution.
+    pub conditions: Vec<PathCondition>,
+    pub symbolic_trace: Vec<SymbolicValueRef>,
+    pub side_constraints: Vec<SymbolicValueRef>,
+}
+
+/// Executes the main template concretely on `input`.
+///
+/// # Returns
+/// The values of all signals and variables assigned by the execution.
+fn execute_concretely(
+    sexe: &mut SymbolicExecutor,
+    base_config: &BaseVerificationConfig,
+    input: &FxHashMap<SymbolicName, BigInt>,
+) -> FxHashMap<SymbolicName, BigInt> {
+    sexe.clear();
+    sexe.cur_state.add_owner(&OwnerName {
+        id: sexe.symbolic_library.name2id["main"],
+        counter: 0,
+        access: None,
+    });
+    sexe.feed_arguments(
+        &base_config.template_param_names,
+        &base_config.template_param_values,
+    );
+    sexe.concrete_execute(&base_config.target_template_name, input);
+
+    sexe.cur_state
+        .symbol_binding_map
+        .iter()
+        .filter_map(|(name, value)| match &**value {
+            SymbolicValue::ConstantInt(v) => Some((name, v.clone())),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Executes the main template symbolically along the path that `input` takes.
+///
+/// # Parameters
+/// - `sexe`: The concrete executor, which also provides the symbolic library.
+/// - `symbolic_setting`: The setting of the symbolic execution of the circuit.
+/// - `base_config`: The verification base_configs.
+/// - `input`: The assignment of the inputs of the main template.
+///
+/// # Returns
+/// The path, whose constraints only contain the branches taken by `input` wherever the condition
+/// of a branch is symbolic.
+pub fn execute_along_path(
+    sexe: &mut SymbolicExecutor,
+    symbolic_setting: &SymbolicExecutorSetting,
+    base_config: &BaseVerificationConfig,
+    input: FxHashMap<SymbolicName, BigInt>,
+) -> ConcolicPath {
+    let signals = execute_concretely(sexe, base_config, &input);
+    let guide = Rc::new(ConcolicGuide::new(signals));
+
+    let mut path_setting = symbolic_setting.clone();
+    path_setting.off_trace = true;
+    path_setting.trace_log = None;
+    path_setting.concolic_guide = Some(guide.clone());
+
+    let mut path_executor = SymbolicExecutor::new(&mut sexe.symbolic_library, &path_setting);
+    path_executor.cur_state.add_owner(&OwnerName {
+        id: path_executor.symbolic_library.name2id["main"],
+        counter: 0,
+        access: None,
+    });
+    let template_id = path_executor.symbolic_library.name2id[&base_config.target_template_name];
+    path_executor.cur_state.set_template_id(template_id);
+    path_executor.feed_arguments(
+        &base_config.template_param_names,
+        &base_config.template_param_values,
+    );
+    let body = path_executor.symbolic_library.template_library[&template_id]
+        .body
+        .clone();
+    path_executor.execute(&body, 0);
+
+    ConcolicPath {
+        input: input,
+        conditions: guide.path(),
+        symbolic_trace: std::mem::take(&mut path_executor.cur_state.symbolic_trace),
+        side_constraints: std::mem::take(&mut path_executor.cur_state.side_constraints),
+    }
+}
+
+/// Checks whether the execution of the circuit on `input` follows `conditions`.
+fn follows_path(
+    sexe: &mut SymbolicExecutor,
+    base_config: &BaseVerificationConfig,
+    input: &FxHashMap<SymbolicName, BigInt>,
+    conditions: &[PathCondition],
+) -> bool {
+    let signals = execute_concretely(sexe, base_config, input);
+    conditions.iter().all(|c| {
+        matches!(
+            evaluate_symbolic_value(
+                &base_config.prime,
+                &c.to_constraint(),
+                &signals,
+                &mut sexe.symbolic_library
+            ),
+            Some(SymbolicValue::ConstantBool(true))
+        )
+    })
+}
+
+/// Draws a candidate input by changing some elements of `input`.
+///
+/// Each changed element takes a value of its tag domain, if any, or one of the corner cases
+/// `{0, 1, p - 1}`, a neighbour of its current value, or a uniformly random value.
+fn mutate_input(
+    input: &FxHashMap<SymbolicName, BigInt>,
+    variables: &[SymbolicName],
+    domains: &FxHashMap<SymbolicName, InputDomain>,
+    base_config: &BaseVerificationConfig,
+    rng: &mut StdRng,
+) -> FxHashMap<SymbolicName, BigInt> {
+    let prime = &base_config.prime;
+    let mut candidate = input.clone();
+    let num_mutations = rng.gen_range(1, variables.len() + 1);
+    for _ in 0..num_mutations {
+        let var = &variables[rng.gen_range(0, variables.len())];
+        let current = candidate.get(var).cloned().unwrap_or_else(BigInt::zero);
+        let value = match domains.get(var) {
+            Some(domain) => domain.sample(rng),
+            None => match rng.gen_range(0, 4) {
+                0 => [BigInt::zero(), BigInt::one(), prime - BigInt::one()][rng.gen_range(0, 3)]
+                    .clone(),
+                1 => (current + BigInt::one()) % prime,
+                2 => (current + prime - BigInt::one()) % prime,
+                _ => rng.gen_bigint_range(&BigInt::zero(), prime),
+            },
+        };
+        candidate.insert(var.clone(), value);
+    }
+    candidate
+}
+
+/// Searches for an input whose execution follows `conditions`, starting from `input`.
+fn solve_path(
+    sexe: &mut SymbolicExecutor,
+    base_config: &BaseVerificationConfig,
+    input: &FxHashMap<SymbolicName, BigInt>,
+    conditions: &[PathCondition],
+    max_iterations: usize,
+    rng: &mut StdRng,
+) -> (Option<FxHashMap<SymbolicName, BigInt>>, usize) {
+    let mut variables: Vec<_> = input.keys().cloned().collect();
+    variables.sort();
+    if variables.is_empty() {
+        return (None, 0);
+    }
+    let domains = gather_input_domains(sexe.symbolic_library, base_config, &variables);
+    for i in 0..max_iterations {
+        let candidate = mutate_input(input, &variables, &domains, base_config, rng);
+        if follows_path(sexe, base_config, &candidate, conditions) {
+            return (Some(candidate), i + 1);
+        }
+    }
+    (None, max_iterations)
+}
+
+/// Explores the paths of the circuit concolically, starting from a seed input, and searches each
+/// path for a counterexample.
+///
+/// The circuit is executed concretely on the seed, and then symbolically with the branches whose
+/// conditions are symbolic decided by the concrete values, which yields the constraints of the
+/// path together with its path conditions. Unlike the symbolic execution of the whole circuit,
+/// this also unrolls loops whose bounds depend on signals. Each new path is searched with
+/// `search`, with its path conditions appended to the symbolic trace. Then, for each branch on
+/// the path, the prefix of the path up to the branch is kept and the branch is negated, and
+/// inputs following the negated path are drawn by mutating the input of the path. The inputs
+/// found this way are explored in turn, until `max_paths` paths are explored or
+/// `max_iterations` inputs are executed.
+///
+/// # Parameters
+/// - `sexe`: A mutable reference to the concrete executor.
+/// - `symbolic_setting`: The setting of the symbolic execution of the circuit.
+/// - `base_config`: The verification base_configs.
+/// - `seed_input`: The assignment of the inputs of the main template to start from.
+/// - `max_paths`: The maximum number of paths to explore.
+/// - `max_iterations`: The maximum number of candidate inputs to execute.
+/// - `seed`: The seed of the random number generator.
+/// - `search`: The search backend run on the symbolic trace and side constraints of each path.
+///
+/// # Returns
+/// An `Option<CounterExample>` containing the first counterexample found on a path, or `None`.
+pub fn concolic_search<F>(
+    sexe: &mut SymbolicExecutor,
+    symbolic_setting: &SymbolicExecutorSetting,
+    base_config: &BaseVerificationConfig,
+    seed_input: FxHashMap<SymbolicName, BigInt>,
+    max_paths: usize,
+    max_iterations: usize,
+    seed: u64,
+    mut search: F,
+) -> Option<CounterExample>
+where
+    F: FnMut(
+        &mut SymbolicExecutor,
+        &Vec<SymbolicValueRef>,
+        &Vec<SymbolicValueRef>,
+    ) -> Option<CounterExample>,
+{
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut worklist = VecDeque::from(vec![seed_input]);
+    let mut explored = FxHashSet::default();
+    let mut negated = FxHashSet::default();
+    let mut num_iterations = 0;
+    let mut counter_example = None;
+
+    while let Some(input) = worklist.pop_front() {
+        if explored.len() >= max_paths {
+            break;
+        }
+        let path = execute_along_path(sexe, symbolic_setting, base_config, input);
+        num_iterations += 1;
+        if !explored.insert(path.conditions.clone()) {
+            continue;
+        }
+
+        outln!(
+            "{} #{} ({} branch(es), Trace: {}, Side: {})",
+            "🛤️ Path".green(),
+            explored.len(),
+            path.conditions.len(),
+            path.symbolic_trace.len(),
+            path.side_constraints.len()
+        );
+        for condition in &path.conditions {
+            outln!(
+                "     ├─ {}",
+                condition.lookup_fmt(&sexe.symbolic_library.id2name)
+            );
+        }
+        io::stdout().flush().unwrap();
+
+        let mut symbolic_trace = path.symbolic_trace.clone();
+        symbolic_trace.extend(path.conditions.iter().map(|c| Rc::new(c.to_constraint())));
+        counter_example = search(sexe, &symbolic_trace, &path.side_constraints);
+        if counter_example.is_some() {
+            break;
+        }
+
+        for i in 0..path.conditions.len() {
+            let mut target = path.conditions[..i].to_vec();
+            target.push(path.conditions[i].negate());
+            if explored.contains(&target) || !negated.insert(target.clone()) {
+                continue;
+            }
+            let budget = (max_iterations.saturating_sub(num_iterations)) / max_paths.max(1);
+            let (found, used) =
+                solve_path(sexe, base_config, &path.input, &target, budget, &mut rng);
+            num_iterations += used;
+            if let Some(input) = found {
+                worklist.push_back(input);
+            }
+        }
+    }
+
+    outln!(" • Concolic search completed");
+    outln!("     ├─ Explored paths: {}", explored.len());
+    outln!("     ├─ Total executions: {}", num_iterations);
+    match &counter_example {
+        Some(ce) => outln!("     └─ Verification result: {}", ce.flag),
+        None => outln!(
+            "     └─ Verification result: {}",
+            VerificationResult::WellConstrained
+        ),
+    }
+
+    counter_example
+}
@@ -0,0 +1,300 @@
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+use num_bigint_dig::BigInt;
+use rustc_hash::{FxHashMap, FxHashSet};
+use serde_json::{json, Value};
+
+use program_structure::ast::Expression;
+use program_structure::constants::UsefulConstants;
+use program_structure::program_archive::ProgramArchive;
+
+use crate::executor::symbolic_execution::SymbolicExecutor;
+use crate::executor::symbolic_interner::reset_interner;
+use crate::executor::symbolic_setting::{
+    get_default_setting_for_concrete_execution, get_default_setting_for_symbolic_execution,
+    SymbolicExecutorSetting,
+};
+use crate::executor::symbolic_value::{
+    extract_variables, OwnerName, SymbolicLibrary, SymbolicName, SymbolicValueRef,
+};
+use crate::executor::template_semantics::LessThanSemantics;
+use crate::executor::utils::modreduce;
+use crate::mutator::constraint_scheduler::ConstraintScheduler;
+use crate::mutator::input_spec::InputSpec;
+use crate::mutator::utils::{
+    classify_evaluated_assignment, is_vulnerable, BaseVerificationConfig, VerificationResult,
+};
+use crate::parser_user::parse_project;
+use crate::type_analysis_user::{analyse_project, gather_input_shapes};
+
+/// An assignment of the variables of the trace and side constraints.
+pub type Assignment = FxHashMap<SymbolicName, BigInt>;
+
+/// The verdict of `ConstraintOracle` on an assignment.
+#[derive(Clone)]
+pub struct Verdict {
+    pub satisfies_trace: bool,
+    pub satisfies_side_constraints: bool,
+    /// The result of `verify_assignment` on the assignment, which re-executes the circuit
+    /// concretely when only the side constraints are satisfied.
+    pub result: VerificationResult,
+}
+
+impl Verdict {
+    /// Returns `true` if the assignment is a counterexample.
+    pub fn is_vulnerable(&self) -> bool {
+        is_vulnerable(&self.result)
+    }
+
+    pub fn to_json(&self) -> Value {
+        json!({
+            "satisfies_trace": self.satisfies_trace,
+            "satisfies_side_constraints": self.satisfies_side_constraints,
+            "result": self.result.to_json(),
+        })
+    }
+}
+
+/// Checks assignments against the trace and side constraints of a circuit executed once, so
+/// that an external fuzzer can generate the inputs while zkFuzz decides which are
+/// counterexamples.
+///
+/// The oracle owns the symbolic library of the circuit and creates the concrete executor of
+/// each batch, so it has no lifetime and can be kept across calls, e.g. by the Python bindings.
+/// The constraints are evaluated by a `ConstraintScheduler`, whose order is learned over all the
+/// batches, and the verdicts are the same as `verify_assignment` on each assignment.
+pub struct ConstraintOracle {
+    symbolic_library: SymbolicLibrary,
+    setting: SymbolicExecutorSetting,
+    base_config: BaseVerificationConfig,
+    scheduler: ConstraintScheduler,
+    variables: Vec<SymbolicName>,
+}
+
+impl ConstraintOracle {
+    /// Creates the oracle of the constraints produced by the symbolic execution of the main
+    /// template of `base_config`.
+    ///
+    /// # Parameters
+    /// - `symbolic_library`: The library the main template was executed with.
+    /// - `symbolic_trace`: The trace constraints.
+    /// - `side_constraints`: The side constraints.
+    /// - `base_config`: The main template, its arguments, and the prime.
+    pub fn new(
+        symbolic_library: SymbolicLibrary,
+        symbolic_trace: &[SymbolicValueRef],
+        side_constraints: &[SymbolicValueRef],
+        base_config: BaseVerificationConfig,
+    ) -> Self {
+        let mut variables = extract_variables(symbolic_trace);
+        for var in extract_variables(side_constraints) {
+            if !variables.contains(&var) {
+                variables.push(var);
+            }
+        }
+        variables.sort();
+        ConstraintOracle {
+            symbolic_library: symbolic_library,
+            setting: get_default_setting_for_concrete_execution(base_config.prime.clone(), false),
+            base_config: base_config,
+            scheduler: ConstraintScheduler::new(symbolic_trace, side_constraints),
+            variables: variables,
+        }
+    }
+
+    /// Registers the templates and functions of a parsed and analysed program and executes its
+    /// main component symbolically.
+    ///
+    /// # Returns
+    /// The oracle of the constraints of the main component, or an error if the program has no
+    /// main component or a component whose inputs are not all assigned.
+    pub fn from_program(program_archive: &ProgramArchive, prime: &BigInt) -> Result<Self, String> {
+        let (id, args) = match &program_archive.initial_template_call {
+            Expression::Call { id, args, .. } => (id, args),
+            _ => return Err("cannot find the main component".to_string()),
+        };
+
+        // The names of the circuits executed before are no longer needed, since the executor of
+        // each batch interns the names of its circuit again
+        reset_interner();
+        let mut symbolic_library = SymbolicLibrary::default();
+        symbolic_library.register_template_semantics(Rc::new(LessThanSemantics));
+        let whitelist = FxHashSet::default();
+        let mut template_names: Vec<&String> = program_archive.templates.keys().collect();
+        template_names.sort();
+        for name in template_names {
+            let template = &program_archive.templates[name];
+            symbolic_library.register_template(
+                name.clone(),
+                template.get_body(),
+                template.get_name_of_params(),
+                &whitelist,
+                template.is_custom_gate(),
+            );
+        }
+        let mut function_names: Vec<&String> = program_archive.functions.keys().collect();
+        function_names.sort();
+        for name in function_names {
+            let function = &program_archive.functions[name];
+            symbolic_library.register_function(
+                name.clone(),
+                function.get_body().clone(),
+                function.get_name_of_params(),
+            );
+        }
+        let main_id = symbolic_library.name2id.len();
+        symbolic_library.name2id.insert("main".to_string(), main_id);
+        symbolic_library.id2name.insert(main_id, "main".to_string());
+
+        let template_param_names = program_archive.templates[id].get_name_of_params().clone();
+        let setting = get_default_setting_for_symbolic_execution(prime.clone(), false);
+        let mut sexe = SymbolicExecutor::new(&mut symbolic_library, &setting);
+        sexe.cur_state.add_owner(&OwnerName {
+            id: main_id,
+            counter: 0,
+            access: None,
+        });
+        sexe.cur_state
+            .set_template_id(sexe.symbolic_library.name2id[id]);
+        sexe.feed_arguments(&template_param_names, args);
+        let body = sexe.symbolic_library.template_library[&sexe.symbolic_library.name2id[id]]
+            .body
+            .clone();
+        sexe.execute(&body, 0);
+        sexe.record_incomplete_components();
+        if !sexe.incomplete_components.is_empty() {
+            return Err(sexe
+                .incomplete_components
+                .iter()
+                .map(|component| component.message())
+                .collect::<Vec<_>>()
+                .join("\n"));
+        }
+
+        let symbolic_trace = sexe.cur_state.symbolic_trace.clone();
+        let side_constraints = sexe.cur_state.side_constraints.clone();
+        let input_shapes = gather_input_shapes(sexe.symbolic_library, id, &sexe.id2dimensions);
+        drop(sexe);
+
+        let base_config = BaseVerificationConfig {
+            target_template_name: id.to_string(),
+            prime: prime.clone(),
+            range: BigInt::from(100),
+            quick_mode: false,
+            heuristics_mode: false,
+            progress_interval: 10000,
+            template_param_names: template_param_names,
+            template_param_values: args.clone(),
+            check_output_tags: false,
+            max_counterexamples: 1,
+            input_shapes: input_shapes,
+            input_spec: InputSpec::default(),
+        };
+        Ok(ConstraintOracle::new(
+            symbolic_library,
+            &symbolic_trace,
+            &side_constraints,
+            base_config,
+        ))
+    }
+
+    /// Parses the circuit at `input_file` and creates the oracle of its main component.
+    ///
+    /// # Parameters
+    /// - `input_file`: The path to the circuit.
+    /// - `prime_name`: The name of the curve whose prime is used (e.g. `bn128`).
+    /// - `link_libraries`: Directories added to the library search path.
+    pub fn load(
+        input_file: &Path,
+        prime_name: &str,
+        link_libraries: &[PathBuf],
+    ) -> Result<Self, String> {
+        let mut program_archive = parse_project(input_file, prime_name, link_libraries)
+            .map_err(|_| format!("unable to parse `{}`", input_file.display()))?;
+        analyse_project(&mut program_archive)
+            .map_err(|_| format!("unable to analyse `{}`", input_file.display()))?;
+        let prime = UsefulConstants::new(&prime_name.to_string())
+            .get_p()
+            .clone();
+        ConstraintOracle::from_program(&program_archive, &prime)
+    }
+
+    /// Returns the variables of the constraints, which every assignment has to assign.
+    pub fn variables(&self) -> &[SymbolicName] {
+        &self.variables
+    }
+
+    /// Returns the names of the variables, e.g. `main.in[0]`.
+    pub fn variable_names(&self) -> Vec<String> {
+        self.variables
+            .iter()
+            .map(|var| var.lookup_fmt(&self.symbolic_library.id2name))
+            .collect()
+    }
+
+    pub fn prime(&self) -> &BigInt {
+        &self.base_config.prime
+    }
+
+    /// Builds an assignment from the values of the variables given by name.
+    ///
+    /// # Returns
+    /// The assignment with the values reduced to `[0, p)`, or an error listing the names that
+    /// are not variables of the constraints and the variables that are not assigned.
+    pub fn assignment_from_names(
+        &self,
+        values: &FxHashMap<String, BigInt>,
+    ) -> Result<Assignment, String> {
+        let prime = &self.base_config.prime;
+        let names = self.variable_names();
+        let mut problems: Vec<String> = values
+            .keys()
+            .filter(|name| !names.contains(name))
+            .map(|name| format!("`{}` is not a variable of the constraints", name))
+            .collect();
+        problems.sort();
+        let mut assignment = Assignment::default();
+        for (var, name) in self.variables.iter().zip(names.iter()) {
+            match values.get(name) {
+                Some(value) => {
+                    assignment.insert(var.clone(), modreduce(value, prime));
+                }
+                None => problems.push(format!("`{}` is not assigned", name)),
+            }
+        }
+        if !problems.is_empty() {
+            return Err(problems.join("\n"));
+        }
+        Ok(assignment)
+    }
+
+    /// Evaluates a batch of assignments, each of which assigns every variable of `variables`.
+    ///
+    /// # Returns
+    /// The verdict of each assignment, in order.
+    pub fn evaluate_batch(&mut self, assignments: &[Assignment]) -> Vec<Verdict> {
+        let mut sexe = SymbolicExecutor::new(&mut self.symbolic_library, &self.setting);
+        assignments
+            .iter()
+            .map(|assignment| {
+                let (is_satisfy_st, is_satisfy_sc) = self.scheduler.evaluate(
+                    &self.base_config.prime,
+                    assignment,
+                    &mut sexe.symbolic_library,
+                );
+                Verdict {
+                    satisfies_trace: is_satisfy_st,
+                    satisfies_side_constraints: is_satisfy_sc,
+                    result: classify_evaluated_assignment(
+                        &mut sexe,
+                        is_satisfy_st,
+                        is_satisfy_sc,
+                        assignment,
+                        &self.base_config,
+                    ),
+                }
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,146 @@
+use num_bigint_dig::BigInt;
+use rustc_hash::FxHashMap;
+
+use crate::executor::symbolic_value::{SymbolicLibrary, SymbolicName, SymbolicValueRef};
+use crate::mutator::compiled_constraints::CompiledConstraints;
+
+/// The number of candidates between two reorderings of the constraints.
+const REORDER_INTERVAL: usize = 256;
+
+/// A set of compiled constraints, evaluated in the order learned by `ConstraintScheduler`.
+struct ScheduledConstraints {
+    compiled: CompiledConstraints,
+    order: Vec<usize>,
+    num_evaluations: Vec<usize>,
+    num_violations: Vec<usize>,
+    num_evaluated: usize,
+}
+
+impl ScheduledConstraints {
+    fn new(constraints: &[SymbolicValueRef]) -> Self {
+        let compiled = CompiledConstraints::compile(constraints);
+        let mut scheduled = ScheduledConstraints {
+            order: (0..compiled.len()).collect(),
+            num_evaluations: vec![0; compiled.len()],
+            num_violations: vec![0; compiled.len()],
+            num_evaluated: 0,
+            compiled: compiled,
+        };
+        scheduled.reorder();
+        scheduled
+    }
+
+    /// Evaluates the constraints up to the first violated one, and records which were evaluated.
+    fn evaluate(
+        &mut self,
+        prime: &BigInt,
+        assignment: &FxHashMap<SymbolicName, BigInt>,
+        symbolic_library: &mut SymbolicLibrary,
+    ) -> bool {
+        let violation =
+            self.compiled
+                .find_violation(&self.order, prime, assignment, symbolic_library);
+        let num_evaluated = violation.map_or(self.order.len(), |position| position + 1);
+        for &index in &self.order[..num_evaluated] {
+            self.num_evaluations[index] += 1;
+        }
+        if let Some(position) = violation {
+            self.num_violations[self.order[position]] += 1;
+        }
+        self.num_evaluated += num_evaluated;
+        violation.is_none()
+    }
+
+    /// Sorts the constraints by decreasing estimated violation rate per unit of cost, so that
+    /// the cheap constraints are tried first until the violations tell them apart.
+    fn reorder(&mut self) {
+        let priority = |index: usize| {
+            // Laplace estimate of the probability that the constraint is the one violated
+            let rate =
+                (self.num_violations[index] + 1) as f64 / (self.num_evaluations[index] + 2) as f64;
+            rate / self.compiled.cost(index).max(1) as f64
+        };
+        let priorities: Vec<f64> = (0..self.order.len()).map(priority).collect();
+        self.order
+            .sort_by(|a, b| priorities[*b].total_cmp(&priorities[*a]).then(a.cmp(b)));
+    }
+}
+
+/// Schedules the evaluation of the symbolic trace and the side constraints for each candidate
+/// assignment of the brute-force search.
+///
+/// Each set of constraints is only evaluated up to its first violated constraint, since the
+/// verdict only depends on whether the whole set is satisfied. The constraints are ordered so
+/// that the cheapest and most frequently violated ones are evaluated first, from the violations
+/// counted over the candidates evaluated so far, which decides most candidates after a few
+/// constraints. The order does not change the verdict, only the number of evaluated constraints.
+pub struct ConstraintScheduler {
+    trace: ScheduledConstraints,
+    side_constraints: ScheduledConstraints,
+    num_candidates: usize,
+}
+
+impl ConstraintScheduler {
+    /// Compiles the constraints, which are initially ordered by increasing cost.
+    pub fn new(symbolic_trace: &[SymbolicValueRef], side_constraints: &[SymbolicValueRef]) -> Self {
+        ConstraintScheduler {
+            trace: ScheduledConstraints::new(symbolic_trace),
+            side_constraints: ScheduledConstraints::new(side_constraints),
+            num_candidates: 0,
+        }
+    }
+
+    /// Evaluates a candidate assignment.
+    ///
+    /// # Returns
+    /// A tuple of whether the assignment satisfies the symbolic trace and whether it satisfies
+    /// the side constraints, exactly as `CompiledConstraints::evaluate` on each set.
+    pub fn evaluate(
+        &mut self,
+        prime: &BigInt,
+        assignment: &FxHashMap<SymbolicName, BigInt>,
+        symbolic_library: &mut SymbolicLibrary,
+    ) -> (bool, bool) {
+        let is_satisfy_st = self.trace.evaluate(prime, assignment, symbolic_library);
+        let is_satisfy_sc = self
+            .side_constraints
+            .evaluate(prime, assignment, symbolic_library);
+        self.num_candidates += 1;
+        if self.num_candidates % REORDER_INTERVAL == 0 {
+            self.trace.reorder();
+            self.side_constraints.reorder();
+        }
+        (is_satisfy_st, is_satisfy_sc)
+    }
+
+    /// Returns the number of evaluated candidates.
+    pub fn num_candidates(&self) -> usize {
+        self.num_candidates
+    }
+
+    /// Returns the total number of trace and side constraints.
+    pub fn num_constraints(&self) -> usize {
+        self.trace.compiled.len() + self.side_constraints.compiled.len()
+    }
+
+    /// Returns the average number of constraints evaluated per candidate.
+    pub fn average_evaluated(&self) -> f64 {
+        if self.num_candidates == 0 {
+            0.0
+        } else {
+            (self.trace.num_evaluated + self.side_constraints.num_evaluated) as f64
+                / self.num_candidates as f64
+        }
+    }
+
+    /// Returns the current evaluation order of the trace constraints, as indices into the
+    /// symbolic trace.
+    pub fn trace_order(&self) -> &[usize] {
+        &self.trace.order
+    }
+
+    /// Returns the current evaluation order of the side constraints.
+    pub fn side_constraint_order(&self) -> &[usize] {
+        &self.side_constraints.order
+    }
+}
@@ -0,0 +1,221 @@
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use num_bigint_dig::BigInt;
+use rustc_hash::{FxHashMap, FxHasher};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::executor::symbolic_value::{SymbolicLibrary, SymbolicName};
+use crate::mutator::utils::BaseVerificationConfig;
+use crate::mutator::witness_export::{build_input_json, parse_input_json};
+
+/// Why an input is kept in the corpus.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CorpusEntryKind {
+    /// The input of a counterexample.
+    Counterexample,
+    /// An input that improved the best fitness score of the genetic search, i.e., that came
+    /// closer to a violation than every input before it.
+    NearViolation,
+    /// An input that increased the coverage of the circuit, kept by
+    /// `input_initialization_method = "coverage"`.
+    Coverage,
+}
+
+impl CorpusEntryKind {
+    /// The entries of a lower rank are loaded first and survive the minimization.
+    fn rank(&self) -> usize {
+        match self {
+            CorpusEntryKind::Counterexample => 0,
+            CorpusEntryKind::NearViolation => 1,
+            CorpusEntryKind::Coverage => 2,
+        }
+    }
+}
+
+/// An input found by the search that is worth keeping across runs.
+#[derive(Clone)]
+pub struct CorpusCandidate {
+    pub kind: CorpusEntryKind,
+    pub input: FxHashMap<SymbolicName, BigInt>,
+    /// The fitness score of the input, zero for a counterexample.
+    pub fitness: BigInt,
+}
+
+/// An input stored in the corpus, as the `input.json` of the main component, so that it remains
+/// usable after the circuit is edited.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CorpusEntry {
+    pub kind: CorpusEntryKind,
+    /// The fitness score of the input as a decimal string.
+    pub fitness: String,
+    pub input: Value,
+}
+
+impl CorpusEntry {
+    /// Converts a candidate of the search into an entry of the corpus.
+    ///
+    /// # Parameters
+    /// - `candidate`: The input found by the search.
+    /// - `symbolic_library`: The symbolic library containing the template definitions.
+    /// - `base_config`: The verification configuration specifying the target template and prime.
+    /// - `id2dimensions`: The dimensions of the signals declared in the main template.
+    pub fn from_candidate(
+        candidate: &CorpusCandidate,
+        symbolic_library: &SymbolicLibrary,
+        base_config: &BaseVerificationConfig,
+        id2dimensions: &FxHashMap<usize, Vec<usize>>,
+    ) -> Self {
+        CorpusEntry {
+            kind: candidate.kind,
+            fitness: candidate.fitness.to_string(),
+            input: build_input_json(
+                &candidate.input,
+                symbolic_library,
+                base_config,
+                id2dimensions,
+            ),
+        }
+    }
+
+    fn fitness_score(&self) -> Option<BigInt> {
+        BigInt::from_str(&self.fitness).ok()
+    }
+
+    /// Returns `true` if the entry should be kept rather than `other`, an entry of the same input.
+    fn is_better_than(&self, other: &CorpusEntry) -> bool {
+        (other.kind.rank(), self.fitness_score()) > (self.kind.rank(), other.fitness_score())
+    }
+
+    /// The name of the file storing the entry, derived from its input so that an input is stored
+    /// at most once.
+    fn file_name(&self) -> String {
+        let mut hasher = FxHasher::default();
+        self.input.to_string().hash(&mut hasher);
+        format!("{:016x}.json", hasher.finish())
+    }
+}
+
+/// Returns the directory of the corpus of the given main template under `--corpus`.
+pub fn corpus_dir(root: &str, target_template_name: &str) -> PathBuf {
+    Path::new(root).join(target_template_name)
+}
+
+/// Reads every entry of a corpus, the counterexamples first and then the entries with the best
+/// fitness scores. Files that are not entries are ignored.
+fn read_corpus(dir: &Path) -> Vec<(PathBuf, CorpusEntry)> {
+    let mut entries: Vec<(PathBuf, CorpusEntry)> = match fs::read_dir(dir) {
+        Ok(read_dir) => read_dir
+            .filter_map(|e| e.ok().map(|e| e.path()))
+            .filter(|path| path.extension().map_or(false, |ext| ext == "json"))
+            .filter_map(|path| {
+                let entry = fs::read_to_string(&path)
+                    .ok()
+                    .and_then(|content| serde_json::from_str(&content).ok())?;
+                Some((path, entry))
+            })
+            .collect(),
+        Err(_) => Vec::new(),
+    };
+    entries.sort_by(|(path_a, a), (path_b, b)| {
+        a.kind
+            .rank()
+            .cmp(&b.kind.rank())
+            .then_with(|| b.fitness_score().cmp(&a.fitness_score()))
+            .then_with(|| path_a.cmp(path_b))
+    });
+    entries
+}
+
+/// Loads the inputs of a corpus to seed the genetic search.
+///
+/// # Parameters
+/// - `dir`: The directory of the corpus.
+/// - `symbolic_library`: The symbolic library containing the template definitions.
+/// - `base_config`: The verification configuration specifying the target template and prime.
+/// - `limit`: The maximum number of inputs loaded.
+///
+/// # Returns
+/// The inputs, the counterexamples first and then the inputs with the best fitness scores, and
+/// the number of entries skipped because they do not match the inputs of the main template,
+/// e.g. after an input was renamed.
+pub fn load_corpus(
+    dir: &Path,
+    symbolic_library: &SymbolicLibrary,
+    base_config: &BaseVerificationConfig,
+    limit: usize,
+) -> (Vec<FxHashMap<SymbolicName, BigInt>>, usize) {
+    let mut inputs = Vec::new();
+    let mut num_skipped = 0;
+    for (_, entry) in read_corpus(dir) {
+        if inputs.len() >= limit {
+            break;
+        }
+        match parse_input_json(&entry.input, symbolic_library, base_config) {
+            Ok(input) => inputs.push(input),
+            Err(_) => num_skipped += 1,
+        }
+    }
+    (inputs, num_skipped)
+}
+
+/// The changes made to a corpus by `update_corpus`.
+#[derive(Default, Debug, PartialEq)]
+pub struct CorpusUpdate {
+    /// The entries of inputs that were not in the corpus yet.
+    pub num_added: usize,
+    /// The entries removed by the minimization.
+    pub num_removed: usize,
+    /// The entries left in the corpus.
+    pub num_entries: usize,
+}
+
+/// Adds the given entries to a corpus and minimizes it.
+///
+/// An input already in the corpus is stored once, with the best of its kinds and fitness scores.
+/// The minimization keeps every counterexample and the `max_entries` other entries with the
+/// best fitness scores, near-violations before coverage entries.
+///
+/// # Parameters
+/// - `dir`: The directory of the corpus, created if it does not exist.
+/// - `entries`: The entries found by the last run.
+/// - `max_entries`: The maximum number of entries other than counterexamples.
+pub fn update_corpus(
+    dir: &Path,
+    entries: &[CorpusEntry],
+    max_entries: usize,
+) -> io::Result<CorpusUpdate> {
+    fs::create_dir_all(dir)?;
+    let mut update = CorpusUpdate::default();
+    for entry in entries {
+        let path = dir.join(entry.file_name());
+        let existing: Option<CorpusEntry> = fs::read_to_string(&path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok());
+        if existing.as_ref().map_or(true, |e| entry.is_better_than(e)) {
+            if existing.is_none() {
+                update.num_added += 1;
+            }
+            fs::write(&path, serde_json::to_string_pretty(entry)?)?;
+        }
+    }
+
+    let mut num_kept = 0;
+    for (path, entry) in read_corpus(dir) {
+        if entry.kind == CorpusEntryKind::Counterexample || num_kept < max_entries {
+            if entry.kind != CorpusEntryKind::Counterexample {
+                num_kept += 1;
+            }
+            update.num_entries += 1;
+        } else {
+            fs::remove_file(&path)?;
+            update.num_removed += 1;
+        }
+    }
+    Ok(update)
+}
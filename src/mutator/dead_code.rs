@@ -0,0 +1,92 @@
+use program_structure::file_definition::FileLibrary;
+use rustc_hash::FxHashSet;
+
+use crate::executor::symbolic_execution::SymbolicExecutor;
+use crate::mutator::division::{gather_statement_metas, statement_location};
+
+/// A branch of an `if` statement whose condition is constant in every execution of the statement.
+pub struct UnreachableBranch {
+    pub owner_name: String,
+    pub location: String,
+    /// `"then"` or `"else"`.
+    pub branch: &'static str,
+}
+
+/// Lists the branches of the `if` statements that no execution of the main template reaches.
+///
+/// A branch is unreachable if the condition of its statement evaluated to the same constant in
+/// every instantiation of the owning template and every call of the owning function. Branches of
+/// templates and functions that are never executed are not listed (see `gather_unused_definitions`).
+///
+/// # Parameters
+/// - `sexe`: The symbolic executor after the execution of the main template.
+/// - `file_library`: The file library used to resolve the source locations of the statements.
+///
+/// # Returns
+/// A vector of `UnreachableBranch`es sorted by their owner and location.
+pub fn gather_unreachable_branches(
+    sexe: &SymbolicExecutor,
+    file_library: &FileLibrary,
+) -> Vec<UnreachableBranch> {
+    let metas = gather_statement_metas(sexe.symbolic_library);
+    let mut branches = Vec::new();
+    for (elem_id, (owner_id, then_reached, else_reached)) in &sexe.branches {
+        for (reached, branch) in [(*then_reached, "then"), (*else_reached, "else")] {
+            if !reached {
+                branches.push(UnreachableBranch {
+                    owner_name: sexe
+                        .symbolic_library
+                        .id2name
+                        .get(owner_id)
+                        .cloned()
+                        .unwrap_or_default(),
+                    location: statement_location(
+                        sexe.symbolic_library,
+                        &metas,
+                        file_library,
+                        *elem_id,
+                        *owner_id,
+                    ),
+                    branch: branch,
+                });
+            }
+        }
+    }
+    branches.sort_by(|a, b| {
+        (&a.owner_name, &a.location, a.branch).cmp(&(&b.owner_name, &b.location, b.branch))
+    });
+    branches
+}
+
+/// Lists the templates that are never instantiated from the main template and the functions that
+/// are never called, including those defined in included files.
+///
+/// # Parameters
+/// - `sexe`: The symbolic executor after the execution of the main template.
+/// - `main_template_name`: The name of the main template.
+///
+/// # Returns
+/// A sorted vector of definitions formatted as `template <name>` or `function <name>`.
+pub fn gather_unused_definitions(sexe: &SymbolicExecutor, main_template_name: &str) -> Vec<String> {
+    let library = &sexe.symbolic_library;
+    let mut used_templates: FxHashSet<usize> = sexe.component_templates.values().cloned().collect();
+    if let Some(id) = library.name2id.get(main_template_name) {
+        used_templates.insert(*id);
+    }
+
+    let mut unused: Vec<_> = library
+        .template_library
+        .keys()
+        .filter(|id| !used_templates.contains(*id))
+        .map(|id| format!("template {}", library.id2name[id]))
+        .chain(
+            library
+                .function_library
+                .keys()
+                .filter(|id| !sexe.called_functions.contains(*id))
+                .map(|id| format!("function {}", library.id2name[id])),
+        )
+        .collect();
+    unused.sort();
+    unused
+}
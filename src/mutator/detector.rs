@@ -0,0 +1,296 @@
+use std::rc::Rc;
+
+use crate::executor::constraint_store::ConstraintStore;
+use crate::executor::symbolic_execution::SymbolicExecutor;
+use crate::mutator::range_checks::gather_unchecked_comparison_inputs;
+use crate::mutator::severity::Severity;
+use crate::mutator::unused_outputs::{
+    check_component_boundaries, check_unconstrained_outputs, check_unused_outputs_in_store,
+};
+use crate::mutator::utils::{BaseVerificationConfig, CounterExample};
+
+/// The detectors run when `--detectors` is not given.
+pub const DEFAULT_DETECTORS: &str = "unused_outputs,component_boundaries";
+
+/// The prefix of the detectors registered outside of zkFuzz, e.g. `custom:my_invariant`.
+pub const CUSTOM_DETECTOR_PREFIX: &str = "custom:";
+
+/// An issue reported by a `Detector`.
+pub struct Finding {
+    /// The kind of the issue, e.g. `Component Boundary Issue`.
+    pub category: &'static str,
+    pub message: String,
+    /// How severe the issue is, which decides whether it is reported under `--min_severity` and
+    /// whether it fails the run under `--fail_on`.
+    pub severity: Severity,
+    /// A counterexample demonstrating the issue, if the detector can construct one. The first
+    /// counterexample found by the detectors is reported as the verdict of the run, and the search
+    /// of `--search_mode` is skipped.
+    pub counter_example: Option<CounterExample>,
+}
+
+/// A check over the constraints gathered by the symbolic execution of the main template.
+///
+/// Detectors run after the symbolic execution and before the search, and report their findings
+/// next to the counterexamples. Downstream crates can implement this trait for project-specific
+/// invariants and add them to a `DetectorRegistry` without modifying zkFuzz.
+pub trait Detector {
+    /// Returns the name that selects the detector in `--detectors`.
+    fn name(&self) -> &'static str;
+
+    /// Returns a one-line description of the detector shown by `--list_detectors`.
+    fn description(&self) -> &'static str;
+
+    /// Returns the heading under which the findings are printed.
+    fn heading(&self) -> &'static str;
+
+    /// Runs the detector.
+    ///
+    /// # Arguments
+    ///
+    /// * `sexe` - The symbolic executor after the execution of the main template.
+    /// * `symbolic_trace` - The constraint store containing the symbolic trace.
+    /// * `side_constraints` - The constraint store containing the side constraints.
+    /// * `base_config` - The verification configuration specifying the target template.
+    ///
+    /// # Returns
+    ///
+    /// The findings of the detector, in the order they are reported.
+    fn run(
+        &self,
+        sexe: &mut SymbolicExecutor,
+        symbolic_trace: &dyn ConstraintStore,
+        side_constraints: &dyn ConstraintStore,
+        base_config: &BaseVerificationConfig,
+    ) -> Vec<Finding>;
+}
+
+/// Reports the outputs of the main template that do not appear in any constraint.
+pub struct UnusedOutputsDetector;
+
+impl Detector for UnusedOutputsDetector {
+    fn name(&self) -> &'static str {
+        "unused_outputs"
+    }
+
+    fn description(&self) -> &'static str {
+        "Outputs of the main template that do not appear in any constraint"
+    }
+
+    fn heading(&self) -> &'static str {
+        "🕳️ Unused Outputs"
+    }
+
+    fn run(
+        &self,
+        sexe: &mut SymbolicExecutor,
+        symbolic_trace: &dyn ConstraintStore,
+        _side_constraints: &dyn ConstraintStore,
+        base_config: &BaseVerificationConfig,
+    ) -> Vec<Finding> {
+        match check_unused_outputs_in_store(sexe, symbolic_trace, base_config) {
+            Some(counter_example) => {
+                let mut outputs: Vec<_> = counter_example.assignment.keys().collect();
+                outputs.sort();
+                let message = outputs
+                    .iter()
+                    .map(|o| o.lookup_fmt(&sexe.symbolic_library.id2name))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                vec![Finding {
+                    category: "Unused Output",
+                    message: message,
+                    severity: Severity::Critical,
+                    counter_example: Some(counter_example),
+                }]
+            }
+            None => Vec::new(),
+        }
+    }
+}
+
+/// Reports the outputs of the main template assigned with `<--` that appear in no side
+/// constraint, found by `check_unconstrained_outputs`.
+pub struct UnconstrainedOutputsDetector;
+
+impl Detector for UnconstrainedOutputsDetector {
+    fn name(&self) -> &'static str {
+        "unconstrained_outputs"
+    }
+
+    fn description(&self) -> &'static str {
+        "Outputs of the main template assigned with `<--` that appear in no constraint"
+    }
+
+    fn heading(&self) -> &'static str {
+        "🎈 Unconstrained Outputs"
+    }
+
+    fn run(
+        &self,
+        sexe: &mut SymbolicExecutor,
+        symbolic_trace: &dyn ConstraintStore,
+        side_constraints: &dyn ConstraintStore,
+        base_config: &BaseVerificationConfig,
+    ) -> Vec<Finding> {
+        check_unconstrained_outputs(sexe, symbolic_trace, side_constraints, base_config)
+            .into_iter()
+            .map(|output| Finding {
+                category: "Unconstrained Output",
+                message: output.lookup_fmt(&sexe.symbolic_library.id2name),
+                severity: Severity::Critical,
+                counter_example: output.counter_example,
+            })
+            .collect()
+    }
+}
+
+/// Reports the under-constraints across component boundaries found by
+/// `check_component_boundaries`.
+pub struct ComponentBoundariesDetector;
+
+impl Detector for ComponentBoundariesDetector {
+    fn name(&self) -> &'static str {
+        "component_boundaries"
+    }
+
+    fn description(&self) -> &'static str {
+        "Dead outputs of sub-components and `<--` assignments from them that are never constrained"
+    }
+
+    fn heading(&self) -> &'static str {
+        "🧱 Component Boundary Issues"
+    }
+
+    fn run(
+        &self,
+        sexe: &mut SymbolicExecutor,
+        symbolic_trace: &dyn ConstraintStore,
+        side_constraints: &dyn ConstraintStore,
+        _base_config: &BaseVerificationConfig,
+    ) -> Vec<Finding> {
+        check_component_boundaries(sexe, symbolic_trace, side_constraints)
+            .iter()
+            .map(|issue| Finding {
+                category: "Component Boundary Issue",
+                message: issue.lookup_fmt(&sexe.symbolic_library.id2name),
+                severity: Severity::Medium,
+                counter_example: None,
+            })
+            .collect()
+    }
+}
+
+/// Reports the inputs of comparison and bits templates that are not range-checked, found by
+/// `gather_unchecked_comparison_inputs`.
+pub struct ComparisonRangeChecksDetector;
+
+impl Detector for ComparisonRangeChecksDetector {
+    fn name(&self) -> &'static str {
+        "comparison_range_checks"
+    }
+
+    fn description(&self) -> &'static str {
+        "Inputs of LessThan-like comparisons and Bits2Num that are not range-checked to their width"
+    }
+
+    fn heading(&self) -> &'static str {
+        "📏 Unchecked Comparison Inputs"
+    }
+
+    fn run(
+        &self,
+        sexe: &mut SymbolicExecutor,
+        symbolic_trace: &dyn ConstraintStore,
+        side_constraints: &dyn ConstraintStore,
+        base_config: &BaseVerificationConfig,
+    ) -> Vec<Finding> {
+        gather_unchecked_comparison_inputs(
+            sexe,
+            &symbolic_trace.to_vec(),
+            &side_constraints.to_vec(),
+            base_config,
+        )
+        .iter()
+        .map(|issue| Finding {
+            category: "Unchecked Comparison Input",
+            message: issue.message(),
+            severity: Severity::Medium,
+            counter_example: None,
+        })
+        .collect()
+    }
+}
+
+/// The detectors available to a run, keyed by their names.
+pub struct DetectorRegistry {
+    builtin: Vec<Rc<dyn Detector>>,
+    custom: Vec<Rc<dyn Detector>>,
+}
+
+impl Default for DetectorRegistry {
+    /// Creates a registry containing the built-in detectors of zkFuzz.
+    fn default() -> Self {
+        DetectorRegistry {
+            builtin: vec![
+                Rc::new(UnusedOutputsDetector),
+                Rc::new(ComponentBoundariesDetector),
+                Rc::new(ComparisonRangeChecksDetector),
+                Rc::new(UnconstrainedOutputsDetector),
+            ],
+            custom: Vec::new(),
+        }
+    }
+}
+
+impl DetectorRegistry {
+    /// Registers a detector defined outside of zkFuzz, which is selected as `custom:<name>`.
+    pub fn register(&mut self, detector: Rc<dyn Detector>) {
+        self.custom.push(detector);
+    }
+
+    /// Returns the names of the available detectors and their descriptions, in the format
+    /// accepted by `select`.
+    pub fn list(&self) -> Vec<(String, &'static str)> {
+        self.builtin
+            .iter()
+            .map(|d| (d.name().to_string(), d.description()))
+            .chain(self.custom.iter().map(|d| {
+                (
+                    format!("{}{}", CUSTOM_DETECTOR_PREFIX, d.name()),
+                    d.description(),
+                )
+            }))
+            .collect()
+    }
+
+    /// Selects detectors from a comma-separated list of names, e.g.
+    /// `unused_outputs,custom:my_invariant`. `all` selects every available detector, and `none`
+    /// selects no detector.
+    ///
+    /// # Returns
+    ///
+    /// The selected detectors in the given order, or the name that matches no detector.
+    pub fn select(&self, names: &str) -> Result<Vec<Rc<dyn Detector>>, String> {
+        let mut selected: Vec<Rc<dyn Detector>> = Vec::new();
+        for name in names.split(',').map(str::trim).filter(|n| !n.is_empty()) {
+            let detectors: Vec<&Rc<dyn Detector>> = match name {
+                "none" => Vec::new(),
+                "all" => self.builtin.iter().chain(self.custom.iter()).collect(),
+                _ => {
+                    let detector = match name.strip_prefix(CUSTOM_DETECTOR_PREFIX) {
+                        Some(custom_name) => self.custom.iter().find(|d| d.name() == custom_name),
+                        None => self.builtin.iter().find(|d| d.name() == name),
+                    };
+                    vec![detector.ok_or_else(|| name.to_string())?]
+                }
+            };
+            for d in detectors {
+                if !selected.iter().any(|s| Rc::ptr_eq(s, d)) {
+                    selected.push(d.clone());
+                }
+            }
+        }
+        Ok(selected)
+    }
+}
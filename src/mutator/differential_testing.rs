@@ -0,0 +1,394 @@
+use std::env;
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::process::Command;
+use std::rc::Rc;
+
+use colored::Colorize;
+use num_bigint_dig::BigInt;
+use num_bigint_dig::RandBigInt;
+use num_traits::{One, Zero};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use rustc_hash::{FxHashMap, FxHashSet};
+use serde_json::Value;
+
+use crate::executor::debug_ast::DebuggableStatement;
+use crate::executor::symbolic_execution::SymbolicExecutor;
+use crate::executor::symbolic_value::{OwnerName, SymbolicAccess, SymbolicName, SymbolicValue};
+use crate::executor::utils::{generate_cartesian_product_indices, modreduce};
+use crate::mutator::utils::{get_domain_of_tags, BaseVerificationConfig};
+use crate::mutator::witness_export::build_input_json;
+
+/// Node.js driver that evaluates every input of a JSON array with the witness calculator of
+/// `circom --wasm` and prints, for each input, whether it was accepted and the main outputs.
+const WASM_DRIVER_SCRIPT: &str = r#"const fs = require("fs");
+const path = require("path");
+
+const wasmPath = path.resolve(process.argv[2]);
+const builder = require(path.join(path.dirname(wasmPath), "witness_calculator.js"));
+const inputs = JSON.parse(fs.readFileSync(process.argv[3], "utf8"));
+const numOutputs = parseInt(process.argv[4]);
+
+builder(fs.readFileSync(wasmPath)).then(async (witnessCalculator) => {
+    const results = [];
+    for (const input of inputs) {
+        try {
+            const witness = await witnessCalculator.calculateWitness(input, 0);
+            const outputs = [];
+            for (let i = 1; i <= numOutputs; i++) {
+                outputs.push(witness[i].toString());
+            }
+            results.push({ accepted: true, outputs: outputs });
+        } catch (e) {
+            results.push({ accepted: false, error: String((e && e.message) || e) });
+        }
+    }
+    process.stdout.write(JSON.stringify(results));
+});
+"#;
+
+/// The outcome of running one input through a witness calculator.
+#[derive(Clone, PartialEq)]
+pub struct WitnessOutcome {
+    pub accepted: bool,
+    pub outputs: Vec<Option<BigInt>>,
+}
+
+impl WitnessOutcome {
+    fn fmt_outputs(&self) -> String {
+        if !self.accepted {
+            return "rejected".to_string();
+        }
+        let outputs = self
+            .outputs
+            .iter()
+            .map(|o| match o {
+                Some(v) => v.to_string(),
+                None => "?".to_string(),
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+        format!("accepted [{}]", outputs)
+    }
+
+    /// Whether two outcomes of the same input disagree: one side accepts the input and the other
+    /// rejects it, or both accept it but compute different outputs.
+    pub fn diverges_from(&self, other: &WitnessOutcome) -> bool {
+        self.accepted != other.accepted || (self.accepted && self.outputs != other.outputs)
+    }
+}
+
+/// An input on which zkFuzz's concrete executor and the WASM witness calculator disagree.
+pub struct Divergence {
+    pub input: Value,
+    pub zkfuzz: WitnessOutcome,
+    pub wasm: WitnessOutcome,
+}
+
+fn gather_declarations_in_order(
+    statements: &[DebuggableStatement],
+    targets: &FxHashSet<usize>,
+    ids: &mut Vec<usize>,
+) {
+    for stmt in statements {
+        match stmt {
+            DebuggableStatement::Declaration { id, .. } => {
+                if targets.contains(id) && !ids.contains(id) {
+                    ids.push(*id);
+                }
+            }
+            DebuggableStatement::IfThenElse {
+                if_case, else_case, ..
+            } => {
+                gather_declarations_in_order(std::slice::from_ref(if_case.as_ref()), targets, ids);
+                if let Some(else_case) = else_case {
+                    gather_declarations_in_order(
+                        std::slice::from_ref(else_case.as_ref()),
+                        targets,
+                        ids,
+                    );
+                }
+            }
+            DebuggableStatement::Block { stmts, .. } => {
+                gather_declarations_in_order(stmts, targets, ids);
+            }
+            DebuggableStatement::InitializationBlock {
+                initializations, ..
+            } => {
+                gather_declarations_in_order(initializations, targets, ids);
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Lists the inputs (or outputs) of the main template, flattened in declaration and row-major
+/// order, which is the order used by circom's witness.
+pub fn flatten_main_signals(
+    sexe: &SymbolicExecutor,
+    base_config: &BaseVerificationConfig,
+    id2dimensions: &FxHashMap<usize, Vec<usize>>,
+    is_input: bool,
+) -> Vec<SymbolicName> {
+    let template = &sexe.symbolic_library.template_library
+        [&sexe.symbolic_library.name2id[&base_config.target_template_name]];
+    let targets = if is_input {
+        &template.input_ids
+    } else {
+        &template.output_ids
+    };
+    let mut ids = Vec::new();
+    gather_declarations_in_order(&template.body, targets, &mut ids);
+
+    let owner = Rc::new(vec![OwnerName {
+        id: sexe.symbolic_library.name2id["main"],
+        counter: 0,
+        access: None,
+    }]);
+    let mut names = Vec::new();
+    for id in ids {
+        let dims = id2dimensions.get(&id).cloned().unwrap_or_default();
+        if dims.is_empty() {
+            names.push(SymbolicName::new(id, owner.clone(), None));
+            continue;
+        }
+        for indices in generate_cartesian_product_indices(&dims) {
+            let access = indices
+                .into_iter()
+                .map(|i| {
+                    SymbolicAccess::ArrayAccess(SymbolicValue::ConstantInt(BigInt::from(i)))
+                })
+                .collect();
+            names.push(SymbolicName::new(id, owner.clone(), Some(access)));
+        }
+    }
+    names
+}
+
+fn draw_differential_value(prime: &BigInt, rng: &mut StdRng) -> BigInt {
+    match rng.gen_range(0, 4) {
+        0 | 1 => BigInt::from(rng.gen_range(0, 16)),
+        2 => prime - BigInt::from(rng.gen_range(1, 16)),
+        _ => rng.gen_bigint_range(&BigInt::zero(), prime),
+    }
+}
+
+/// Returns the domains of the inputs of the main template restricted by their tags (e.g.
+/// `{binary}`), keyed by their IDs.
+pub(crate) fn gather_tagged_input_domains(
+    sexe: &SymbolicExecutor,
+    base_config: &BaseVerificationConfig,
+) -> FxHashMap<usize, Vec<BigInt>> {
+    let template = &sexe.symbolic_library.template_library
+        [&sexe.symbolic_library.name2id[&base_config.target_template_name]];
+    template
+        .input_ids
+        .iter()
+        .filter_map(|id| {
+            template
+                .id2tags
+                .get(id)
+                .and_then(|tags| get_domain_of_tags(tags))
+                .map(|domain| (*id, domain))
+        })
+        .collect()
+}
+
+/// Draws a random value for each of the flattened `inputs`, from the domain of its tags if it
+/// has one, and otherwise favouring small values and values close to the prime.
+pub(crate) fn draw_input_assignment(
+    inputs: &[SymbolicName],
+    domains: &FxHashMap<usize, Vec<BigInt>>,
+    prime: &BigInt,
+    rng: &mut StdRng,
+) -> FxHashMap<SymbolicName, BigInt> {
+    let mut assignment = FxHashMap::default();
+    for name in inputs {
+        let value = match domains.get(&name.id) {
+            Some(domain) => domain[rng.gen_range(0, domain.len())].clone(),
+            None => draw_differential_value(prime, rng),
+        };
+        assignment.insert(name.clone(), value);
+    }
+    assignment
+}
+
+/// Runs `assignment` through the concrete executor, and returns whether it is accepted and the
+/// values of the flattened `outputs`.
+pub(crate) fn run_zkfuzz(
+    sexe: &mut SymbolicExecutor,
+    base_config: &BaseVerificationConfig,
+    assignment: &FxHashMap<SymbolicName, BigInt>,
+    outputs: &[SymbolicName],
+) -> WitnessOutcome {
+    sexe.clear();
+    sexe.cur_state.add_owner(&OwnerName {
+        id: sexe.symbolic_library.name2id["main"],
+        counter: 0,
+        access: None,
+    });
+    sexe.feed_arguments(
+        &base_config.template_param_names,
+        &base_config.template_param_values,
+    );
+    sexe.concrete_execute(&base_config.target_template_name, assignment);
+
+    if sexe.cur_state.is_failed || sexe.violated_assertion.is_some() {
+        return WitnessOutcome {
+            accepted: false,
+            outputs: Vec::new(),
+        };
+    }
+
+    let mut values = Vec::new();
+    for name in outputs {
+        let value = match sexe.cur_state.symbol_binding_map.get(name).cloned() {
+            Some(value) => {
+                let mut memo = FxHashSet::default();
+                match sexe.simplify_variables(&value, std::usize::MAX, false, false, &mut memo) {
+                    SymbolicValue::ConstantInt(v) => Some(modreduce(&v, &base_config.prime)),
+                    SymbolicValue::ConstantBool(b) => {
+                        Some(if b { BigInt::one() } else { BigInt::zero() })
+                    }
+                    _ => None,
+                }
+            }
+            None => None,
+        };
+        values.push(value);
+    }
+    WitnessOutcome {
+        accepted: true,
+        outputs: values,
+    }
+}
+
+fn run_wasm(
+    path_to_wasm: &str,
+    inputs: &[Value],
+    num_outputs: usize,
+) -> io::Result<Vec<WitnessOutcome>> {
+    let tmp_dir = env::temp_dir();
+    let script_path = tmp_dir.join(format!("zkfuzz_differential_{}.js", std::process::id()));
+    let inputs_path = tmp_dir.join(format!("zkfuzz_differential_{}.json", std::process::id()));
+    File::create(&script_path)?.write_all(WASM_DRIVER_SCRIPT.as_bytes())?;
+    File::create(&inputs_path)?.write_all(serde_json::to_string(inputs)?.as_bytes())?;
+
+    let output = Command::new("node")
+        .arg(&script_path)
+        .arg(path_to_wasm)
+        .arg(&inputs_path)
+        .arg(num_outputs.to_string())
+        .output();
+    let _ = fs::remove_file(&script_path);
+    let _ = fs::remove_file(&inputs_path);
+    let output = output?;
+    if !output.status.success() {
+        return Err(io::Error::new(
+            io::ErrorKind::Other,
+            String::from_utf8_lossy(&output.stderr).to_string(),
+        ));
+    }
+
+    Ok(parse_wasm_outcomes(&output.stdout)?)
+}
+
+/// Parses the outcomes printed by `WASM_DRIVER_SCRIPT`, one per input. An output that is not a
+/// decimal number is unknown.
+pub fn parse_wasm_outcomes(stdout: &[u8]) -> serde_json::Result<Vec<WitnessOutcome>> {
+    let results: Vec<Value> = serde_json::from_slice(stdout)?;
+    Ok(results
+        .into_iter()
+        .map(|r| WitnessOutcome {
+            accepted: r["accepted"].as_bool().unwrap_or(false),
+            outputs: r["outputs"]
+                .as_array()
+                .map(|outputs| {
+                    outputs
+                        .iter()
+                        .map(|o| o.as_str().and_then(|s| s.parse::<BigInt>().ok()))
+                        .collect()
+                })
+                .unwrap_or_default(),
+        })
+        .collect())
+}
+
+/// Runs random inputs through zkFuzz's concrete executor and the witness calculator generated by
+/// `circom --wasm` for the same circuit, and reports the inputs on which they disagree.
+///
+/// An input diverges when one side accepts it and the other rejects it (a failing `assert` or
+/// `===`), or when both accept it but compute different main outputs. The WASM witness calculator
+/// is driven by `node`, which must be available in `PATH`.
+///
+/// # Parameters
+/// - `sexe`: A mutable reference to the concrete executor.
+/// - `base_config`: The verification base_configs.
+/// - `id2dimensions`: The dimensions of the signals declared in the main template.
+/// - `path_to_wasm`: Path to the `.wasm` file, next to which `witness_calculator.js` is expected.
+/// - `num_iterations`: The number of random inputs.
+/// - `seed`: The seed of the random number generator.
+///
+/// # Returns
+/// The divergences found, or an error if the WASM witness calculator could not be run.
+pub fn differential_test_against_wasm(
+    sexe: &mut SymbolicExecutor,
+    base_config: &BaseVerificationConfig,
+    id2dimensions: &FxHashMap<usize, Vec<usize>>,
+    path_to_wasm: &str,
+    num_iterations: usize,
+    seed: u64,
+) -> io::Result<Vec<Divergence>> {
+    let inputs = flatten_main_signals(sexe, base_config, id2dimensions, true);
+    let outputs = flatten_main_signals(sexe, base_config, id2dimensions, false);
+    let domains = gather_tagged_input_domains(sexe, base_config);
+
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut assignments = Vec::with_capacity(num_iterations);
+    let mut input_jsons = Vec::with_capacity(num_iterations);
+    for _ in 0..num_iterations {
+        let assignment = draw_input_assignment(&inputs, &domains, &base_config.prime, &mut rng);
+        input_jsons.push(build_input_json(
+            &assignment,
+            sexe.symbolic_library,
+            base_config,
+            id2dimensions,
+        ));
+        assignments.push(assignment);
+    }
+
+    let wasm_outcomes = run_wasm(path_to_wasm, &input_jsons, outputs.len())?;
+
+    let mut divergences = Vec::new();
+    for ((assignment, input), wasm) in assignments
+        .iter()
+        .zip(input_jsons.into_iter())
+        .zip(wasm_outcomes.into_iter())
+    {
+        let zkfuzz = run_zkfuzz(sexe, base_config, assignment, &outputs);
+        if zkfuzz.diverges_from(&wasm) {
+            divergences.push(Divergence {
+                input: input,
+                zkfuzz: zkfuzz,
+                wasm: wasm,
+            });
+        }
+    }
+    Ok(divergences)
+}
+
+/// Prints a summary of the divergences found by `differential_test_against_wasm`.
+pub fn print_divergences(divergences: &[Divergence], num_iterations: usize) {
+    eoutln!(
+        "{} {}/{} inputs diverge",
+        "🔀 Differential Testing:".green(),
+        divergences.len().to_string().bright_yellow(),
+        num_iterations
+    );
+    for d in divergences {
+        eoutln!("  ├─ input : {}", d.input);
+        eoutln!("  │  ├─ zkFuzz : {}", d.zkfuzz.fmt_outputs());
+        eoutln!("  │  └─ wasm   : {}", d.wasm.fmt_outputs());
+    }
+}
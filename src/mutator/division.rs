@@ -0,0 +1,184 @@
+use program_structure::ast::Meta;
+use program_structure::file_definition::FileLibrary;
+use rustc_hash::FxHashMap;
+
+use crate::executor::debug_ast::DebuggableStatement;
+use crate::executor::symbolic_execution::SymbolicExecutor;
+use crate::executor::symbolic_value::{SymbolicLibrary, SymbolicValueRef};
+use crate::mutator::assertion::{random_concrete_search, source_location};
+use crate::mutator::utils::{
+    BaseVerificationConfig, CounterExample, VerificationResult, ViolatedConstraint,
+};
+
+/// A division whose denominator is not provably nonzero.
+///
+/// circom evaluates `x / 0` to `0` during witness generation, so a prover can reach such a
+/// division with a zero denominator and obtain a value that the constraints may not enforce.
+pub struct DivisionSite {
+    pub owner_name: String,
+    pub location: String,
+    pub denominator: String,
+}
+
+fn gather_statement_metas_in_statements(
+    statements: &[DebuggableStatement],
+    metas: &mut FxHashMap<usize, Meta>,
+) {
+    for stmt in statements {
+        if let Some(meta) = stmt.get_meta() {
+            metas.insert(meta.elem_id, meta.clone());
+        }
+        match stmt {
+            DebuggableStatement::IfThenElse {
+                if_case, else_case, ..
+            } => {
+                gather_statement_metas_in_statements(std::slice::from_ref(if_case.as_ref()), metas);
+                if let Some(else_case) = else_case {
+                    gather_statement_metas_in_statements(
+                        std::slice::from_ref(else_case.as_ref()),
+                        metas,
+                    );
+                }
+            }
+            DebuggableStatement::While { stmt, .. } => {
+                gather_statement_metas_in_statements(std::slice::from_ref(stmt.as_ref()), metas);
+            }
+            DebuggableStatement::Block { stmts, .. } => {
+                gather_statement_metas_in_statements(stmts, metas);
+            }
+            DebuggableStatement::InitializationBlock {
+                initializations, ..
+            } => {
+                gather_statement_metas_in_statements(initializations, metas);
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Collects the metadata of every statement in the templates and functions of the library,
+/// keyed by element ID.
+pub(crate) fn gather_statement_metas(symbolic_library: &SymbolicLibrary) -> FxHashMap<usize, Meta> {
+    let mut metas = FxHashMap::default();
+    for template in symbolic_library.template_library.values() {
+        gather_statement_metas_in_statements(&template.body, &mut metas);
+    }
+    for function in symbolic_library.function_library.values() {
+        gather_statement_metas_in_statements(&function.body, &mut metas);
+    }
+    metas
+}
+
+/// Formats the source location of the statement at `elem_id` owned by the template or function
+/// `owner_id`.
+pub(crate) fn statement_location(
+    symbolic_library: &SymbolicLibrary,
+    metas: &FxHashMap<usize, Meta>,
+    file_library: &FileLibrary,
+    elem_id: usize,
+    owner_id: usize,
+) -> String {
+    let owner_name = symbolic_library
+        .id2name
+        .get(&owner_id)
+        .map_or("unknown", |name| name.as_str());
+    match metas.get(&elem_id) {
+        Some(meta) => source_location(owner_name, meta, file_library),
+        None => format!("{} (elem_id={})", owner_name, elem_id),
+    }
+}
+
+/// Lists the divisions recorded during symbolic execution whose denominator is not provably
+/// nonzero.
+///
+/// # Parameters
+/// - `sexe`: The symbolic executor after the execution of the main template.
+/// - `file_library`: The file library used to resolve the source locations of the divisions.
+///
+/// # Returns
+/// A vector of `DivisionSite`s sorted by their owner and location.
+pub fn gather_division_sites(
+    sexe: &SymbolicExecutor,
+    file_library: &FileLibrary,
+) -> Vec<DivisionSite> {
+    let metas = gather_statement_metas(sexe.symbolic_library);
+    let mut sites: Vec<_> = sexe
+        .divisions
+        .iter()
+        .map(|(elem_id, (owner_id, denominator))| DivisionSite {
+            owner_name: sexe
+                .symbolic_library
+                .id2name
+                .get(owner_id)
+                .cloned()
+                .unwrap_or_default(),
+            location: statement_location(
+                sexe.symbolic_library,
+                &metas,
+                file_library,
+                *elem_id,
+                *owner_id,
+            ),
+            denominator: denominator.lookup_fmt(&sexe.symbolic_library.id2name),
+        })
+        .collect();
+    sites.sort_by(|a, b| (&a.owner_name, &a.location).cmp(&(&b.owner_name, &b.location)));
+    sites
+}
+
+/// Searches for input assignments whose witness generation divides by zero.
+///
+/// Candidate inputs are sampled as in `assertion_reachability_search`, and a candidate is reported
+/// as soon as its concrete execution satisfies all constraints and evaluates a division whose
+/// denominator is zero.
+///
+/// # Parameters
+/// - `sexe`: A mutable reference to the concrete executor.
+/// - `symbolic_trace`: A vector of constraints representing the program trace.
+/// - `side_constraints`: A vector of additional constraints for validation.
+/// - `base_config`: The verification base_configs.
+/// - `file_library`: The file library used to resolve the source locations of the divisions.
+/// - `max_iterations`: The maximum number of candidate inputs to execute.
+/// - `seed`: The seed of the random number generator.
+///
+/// # Returns
+/// An `Option<CounterExample>` containing the triggering assignment if a division by zero is
+/// reachable, or `None` otherwise.
+pub fn division_by_zero_search(
+    sexe: &mut SymbolicExecutor,
+    symbolic_trace: &Vec<SymbolicValueRef>,
+    side_constraints: &Vec<SymbolicValueRef>,
+    base_config: &BaseVerificationConfig,
+    file_library: &FileLibrary,
+    max_iterations: usize,
+    seed: u64,
+) -> Option<CounterExample> {
+    let metas = gather_statement_metas(sexe.symbolic_library);
+    random_concrete_search(
+        sexe,
+        symbolic_trace,
+        side_constraints,
+        base_config,
+        max_iterations,
+        seed,
+        |sexe| {
+            if sexe.cur_state.is_failed {
+                return None;
+            }
+            sexe.division_by_zero
+                .as_ref()
+                .map(|(elem_id, owner_id, denominator)| {
+                    VerificationResult::DivisionByZero(
+                        statement_location(
+                            sexe.symbolic_library,
+                            &metas,
+                            file_library,
+                            *elem_id,
+                            *owner_id,
+                        ),
+                        ViolatedConstraint::new(denominator, &sexe.symbolic_library.id2name),
+                    )
+                })
+        },
+    )
+}
@@ -0,0 +1,217 @@
+use std::cmp;
+
+use rustc_hash::{FxHashMap, FxHashSet};
+use serde_json::{json, Value};
+
+use crate::executor::constraint_store::ConstraintStore;
+use crate::executor::symbolic_value::{
+    extract_variables_from_symbolic_value, SymbolicLibrary, SymbolicName, SymbolicValue,
+};
+use crate::mutator::severity::Severity;
+use crate::mutator::utils::{
+    BaseVerificationConfig, CounterExample, UnderConstrainedType, VerificationResult,
+};
+
+/// Who chooses the inputs of the main template from which the divergent values of a
+/// counterexample are computed.
+///
+/// The prover chooses the private inputs, while the verifier fixes the public ones as part of the
+/// statement being proven. A divergence computed from public inputs is only reachable for the
+/// statements whose public inputs take the values of the counterexample.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Controllability {
+    /// Only private inputs, or no input at all: the prover alone can reach the divergence.
+    PrivateOnly,
+    /// Both public and private inputs.
+    PublicAndPrivate,
+    /// Only public inputs.
+    PublicOnly,
+}
+
+impl Controllability {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Controllability::PrivateOnly => "private_only",
+            Controllability::PublicAndPrivate => "public_and_private",
+            Controllability::PublicOnly => "public_only",
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            Controllability::PrivateOnly => "reachable from private inputs only",
+            Controllability::PublicAndPrivate => "requires both public and private inputs",
+            Controllability::PublicOnly => "reachable from public inputs only",
+        }
+    }
+
+    /// Returns the highest severity of an under-constraint whose divergence has this
+    /// controllability.
+    pub fn max_severity(&self) -> Severity {
+        match self {
+            Controllability::PrivateOnly => Severity::Critical,
+            Controllability::PublicAndPrivate => Severity::High,
+            Controllability::PublicOnly => Severity::Medium,
+        }
+    }
+}
+
+/// The inputs of the main template that the divergent signal of a counterexample is computed
+/// from, and who chooses them.
+pub struct Exploitability {
+    /// The signal whose values differ between the witnesses of the counterexample.
+    pub divergent_signal: SymbolicName,
+    pub controllability: Controllability,
+    pub public_inputs: Vec<SymbolicName>,
+    pub private_inputs: Vec<SymbolicName>,
+}
+
+impl Exploitability {
+    pub fn lookup_fmt(&self, lookup: &FxHashMap<usize, String>) -> String {
+        let inputs: Vec<_> = self
+            .public_inputs
+            .iter()
+            .map(|name| format!("{} (public)", name.lookup_fmt(lookup)))
+            .chain(
+                self.private_inputs
+                    .iter()
+                    .map(|name| format!("{} (private)", name.lookup_fmt(lookup))),
+            )
+            .collect();
+        format!(
+            "{}: `{}` is computed from {}",
+            self.controllability.description(),
+            self.divergent_signal.lookup_fmt(lookup),
+            if inputs.is_empty() {
+                "no input".to_string()
+            } else {
+                inputs.join(", ")
+            }
+        )
+    }
+
+    pub fn to_json(&self, lookup: &FxHashMap<usize, String>) -> Value {
+        json!({
+            "divergent_signal": self.divergent_signal.lookup_fmt(lookup),
+            "controllability": self.controllability.as_str(),
+            "public_inputs": self
+                .public_inputs
+                .iter()
+                .map(|name| name.lookup_fmt(lookup))
+                .collect::<Vec<_>>(),
+            "private_inputs": self
+                .private_inputs
+                .iter()
+                .map(|name| name.lookup_fmt(lookup))
+                .collect::<Vec<_>>(),
+        })
+    }
+}
+
+/// Returns the severity of `ce`, capped by the controllability of its divergence if it was
+/// assessed.
+pub fn assessed_severity(ce: &CounterExample, exploitability: Option<&Exploitability>) -> Severity {
+    let severity = Severity::of_counter_example(ce);
+    match exploitability {
+        Some(exploitability) => cmp::min(severity, exploitability.controllability.max_severity()),
+        None => severity,
+    }
+}
+
+/// Returns the signal whose values differ between the witnesses of an under-constraint.
+fn divergent_signal(ce: &CounterExample) -> Option<SymbolicName> {
+    match &ce.flag {
+        VerificationResult::UnderConstrained(UnderConstrainedType::NonDeterministic(name, ..))
+        | VerificationResult::UnderConstrained(UnderConstrainedType::TagViolation(name, ..)) => {
+            Some(name.clone())
+        }
+        VerificationResult::UnderConstrained(_) => ce.target_output.clone(),
+        _ => None,
+    }
+}
+
+/// Classifies an under-constraint by the inputs of the main template its divergence is computed
+/// from.
+///
+/// The dependencies of the divergent signal are followed backward through the assignments of
+/// the symbolic trace (`<--`, `<==`, and the assignments of variables), so that an input counts
+/// only if the divergent value is computed from it, rather than merely appearing in a constraint
+/// with it.
+///
+/// # Parameters
+/// - `ce`: The counterexample to classify.
+/// - `symbolic_trace`: The symbolic trace of the whole circuit.
+/// - `symbolic_library`: The symbolic library containing the template definitions.
+/// - `base_config`: The verification configuration specifying the target template.
+/// - `public_inputs`: The names of the public inputs of the main component, e.g. `a` for
+///   `component main {public [a]} = T();`.
+///
+/// # Returns
+/// The exploitability of the counterexample, or `None` if it is not an under-constraint or its
+/// divergent signal is not known.
+pub fn assess_exploitability(
+    ce: &CounterExample,
+    symbolic_trace: &dyn ConstraintStore,
+    symbolic_library: &SymbolicLibrary,
+    base_config: &BaseVerificationConfig,
+    public_inputs: &[String],
+) -> Option<Exploitability> {
+    let divergent_signal = divergent_signal(ce)?;
+
+    let mut dependencies: FxHashMap<SymbolicName, FxHashSet<SymbolicName>> = FxHashMap::default();
+    for c in symbolic_trace.iter() {
+        match &*c {
+            SymbolicValue::Assign(lhs, rhs, ..)
+            | SymbolicValue::AssignEq(lhs, rhs)
+            | SymbolicValue::AssignCall(lhs, rhs, _) => {
+                if let SymbolicValue::Variable(name) = &**lhs {
+                    extract_variables_from_symbolic_value(
+                        rhs,
+                        dependencies.entry(name.clone()).or_default(),
+                    );
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let template = &symbolic_library.template_library
+        [&symbolic_library.name2id[&base_config.target_template_name]];
+    let mut public = Vec::new();
+    let mut private = Vec::new();
+    let mut visited: FxHashSet<SymbolicName> = FxHashSet::default();
+    visited.insert(divergent_signal.clone());
+    let mut worklist = vec![divergent_signal.clone()];
+    while let Some(name) = worklist.pop() {
+        if name.owner.len() == 1 && template.input_ids.contains(&name.id) {
+            if public_inputs.contains(&symbolic_library.id2name[&name.id]) {
+                public.push(name);
+            } else {
+                private.push(name);
+            }
+            continue;
+        }
+        if let Some(sources) = dependencies.get(&name) {
+            for source in sources {
+                if visited.insert(source.clone()) {
+                    worklist.push(source.clone());
+                }
+            }
+        }
+    }
+
+    let lookup = &symbolic_library.id2name;
+    public.sort_by_cached_key(|name| name.lookup_fmt(lookup));
+    private.sort_by_cached_key(|name| name.lookup_fmt(lookup));
+    let controllability = match (public.is_empty(), private.is_empty()) {
+        (true, _) => Controllability::PrivateOnly,
+        (false, true) => Controllability::PublicOnly,
+        (false, false) => Controllability::PublicAndPrivate,
+    };
+    Some(Exploitability {
+        divergent_signal,
+        controllability,
+        public_inputs: public,
+        private_inputs: private,
+    })
+}
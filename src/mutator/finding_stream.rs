@@ -0,0 +1,123 @@
+use std::fs::File;
+use std::io::{self, LineWriter, Write};
+use std::path::Path;
+use std::time;
+
+use log::warn;
+use serde_json::{json, Value};
+
+use crate::executor::utils::strip_ansi;
+use crate::mutator::severity::Severity;
+
+/// Collects the findings of a run and streams each of them as a JSON line as soon as it is
+/// recorded, written by `--findings_stream`.
+///
+/// Every finding produces a `finding` event, every counterexample a `counterexample` event, and
+/// the end of the run an `end` event. The events are numbered by a `seq` and stamped with the
+/// milliseconds elapsed since the stream was opened:
+///
+/// ```json
+/// {"event":"finding","seq":0,"elapsed_ms":12,"severity":"medium","category":"Potential Division-by-Zero","message":"`main.b` at Div (line 7)"}
+/// {"event":"counterexample","seq":1,"elapsed_ms":840,"severity":"critical","verdict":"UnderConstrained-NonDeterministic: main.out","counterexample":{...}}
+/// {"event":"end","seq":2,"elapsed_ms":841,"num_findings":1,"num_counterexamples":1,"max_severity":"critical"}
+/// ```
+///
+/// Each line is flushed once written, so that the findings of a run that is interrupted or
+/// crashes are kept up to its last event, and a stream without an `end` event is known to be
+/// partial. The summary and the HTML report of the run are built from the findings collected here.
+pub struct FindingStream {
+    writer: Option<LineWriter<File>>,
+    findings: Vec<(Severity, &'static str, String)>,
+    max_severity: Option<Severity>,
+    num_counterexamples: usize,
+    seq: usize,
+    start_time: time::Instant,
+}
+
+impl FindingStream {
+    /// Creates a stream writing to the file at `path`, truncating it if it exists, or a stream
+    /// only collecting the findings if `path` is `None`.
+    pub fn create(path: Option<&Path>) -> io::Result<Self> {
+        let writer = match path {
+            Some(path) => Some(LineWriter::new(File::create(path)?)),
+            None => None,
+        };
+        Ok(FindingStream {
+            writer,
+            findings: Vec::new(),
+            max_severity: None,
+            num_counterexamples: 0,
+            seq: 0,
+            start_time: time::Instant::now(),
+        })
+    }
+
+    fn write_event(&mut self, mut event: Value) {
+        event["seq"] = json!(self.seq);
+        event["elapsed_ms"] = json!(self.start_time.elapsed().as_millis() as u64);
+        self.seq += 1;
+        if let Some(writer) = self.writer.as_mut() {
+            if let Err(e) = writeln!(writer, "{}", event).and_then(|_| writer.flush()) {
+                warn!("Unable to write the findings stream: {}", e);
+            }
+        }
+    }
+
+    /// Records a finding of the detectors other than the search.
+    pub fn push(&mut self, finding: (Severity, &'static str, String)) {
+        let (severity, category, message) = &finding;
+        self.write_event(json!({
+            "event": "finding",
+            "severity": severity.to_string(),
+            "category": category,
+            "message": strip_ansi(message),
+        }));
+        self.max_severity = self.max_severity.max(Some(*severity));
+        self.findings.push(finding);
+    }
+
+    /// Records the findings in order.
+    pub fn extend<I: IntoIterator<Item = (Severity, &'static str, String)>>(
+        &mut self,
+        findings: I,
+    ) {
+        for finding in findings {
+            self.push(finding);
+        }
+    }
+
+    /// Records a counterexample found by the search or a detector.
+    ///
+    /// # Parameters
+    /// - `severity`: The severity of the counterexample.
+    /// - `verdict`: The classification of the counterexample, e.g.
+    ///   `UnderConstrained-NonDeterministic: main.out`.
+    /// - `counter_example`: The counterexample serialized as in the saved output.
+    pub fn push_counter_example(
+        &mut self,
+        severity: Severity,
+        verdict: &str,
+        counter_example: Value,
+    ) {
+        self.write_event(json!({
+            "event": "counterexample",
+            "severity": severity.to_string(),
+            "verdict": verdict,
+            "counterexample": counter_example,
+        }));
+        self.max_severity = self.max_severity.max(Some(severity));
+        self.num_counterexamples += 1;
+    }
+
+    /// Ends the stream with an `end` event and returns the recorded findings.
+    pub fn finish(mut self) -> Vec<(Severity, &'static str, String)> {
+        let max_severity = self.max_severity.map(|severity| severity.to_string());
+        self.write_event(json!({
+            "event": "end",
+            "num_findings": self.findings.len(),
+            "num_counterexamples": self.num_counterexamples,
+            "max_severity": max_severity,
+        }));
+        self.findings
+    }
+}
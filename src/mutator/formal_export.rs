@@ -0,0 +1,357 @@
+use std::collections::BTreeSet;
+
+use num_bigint_dig::BigInt;
+use num_traits::{One, Signed, ToPrimitive, Zero};
+use program_structure::ast::{ExpressionInfixOpcode, ExpressionPrefixOpcode};
+use rustc_hash::{FxHashMap, FxHashSet};
+use serde_json::{json, Value};
+
+use crate::executor::constraint_store::ConstraintStore;
+use crate::executor::debug_ast::{
+    DebuggableExpressionInfixOpcode, DebuggableExpressionPrefixOpcode,
+};
+use crate::executor::symbolic_value::{
+    extract_variables_from_symbolic_value, SymbolicLibrary, SymbolicValue,
+};
+use crate::executor::utils::{moddiv, modreduce, strip_ansi};
+
+/// Largest constant exponent expanded into a product by the export.
+const MAX_EXPANDED_EXPONENT: usize = 16;
+
+/// The formats of `--export_constraints`.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum ExportFormat {
+    /// The documented intermediate JSON of `ConstraintSystem::to_json`.
+    Json,
+    /// The query of `ConstraintSystem::to_smt2`.
+    Smt2,
+}
+
+impl ExportFormat {
+    /// Chooses the format from the extension of the exported file, `.smt2` or JSON otherwise.
+    pub fn from_path(path: &str) -> Self {
+        if path.ends_with(".smt2") {
+            ExportFormat::Smt2
+        } else {
+            ExportFormat::Json
+        }
+    }
+}
+
+/// A polynomial expression over the prime field.
+#[derive(Clone, Debug, PartialEq)]
+pub enum FieldTerm {
+    /// A constant in `[0, p)`.
+    Constant(BigInt),
+    /// A signal, by its owner-qualified name (e.g. `main.n2b.out[0]`).
+    Signal(String),
+    Add(Box<FieldTerm>, Box<FieldTerm>),
+    Mul(Box<FieldTerm>, Box<FieldTerm>),
+    Neg(Box<FieldTerm>),
+}
+
+impl FieldTerm {
+    /// Converts the term into the JSON of the intermediate format, one of `{"const": "5"}`,
+    /// `{"signal": "main.a"}`, `{"op": "add" | "mul", "args": [lhs, rhs]}`, and
+    /// `{"op": "neg", "args": [arg]}`.
+    pub fn to_json(&self) -> Value {
+        match self {
+            FieldTerm::Constant(c) => json!({"const": c.to_string()}),
+            FieldTerm::Signal(name) => json!({"signal": name}),
+            FieldTerm::Add(lhs, rhs) => {
+                json!({"op": "add", "args": [lhs.to_json(), rhs.to_json()]})
+            }
+            FieldTerm::Mul(lhs, rhs) => {
+                json!({"op": "mul", "args": [lhs.to_json(), rhs.to_json()]})
+            }
+            FieldTerm::Neg(arg) => json!({"op": "neg", "args": [arg.to_json()]}),
+        }
+    }
+
+    /// Converts the term into SMT-LIB over the sort `F` of the field, naming each signal with
+    /// `symbol`.
+    pub fn to_smt2(&self, symbol: &dyn Fn(&str) -> String) -> String {
+        match self {
+            FieldTerm::Constant(c) => format!("(as ff{} F)", c),
+            FieldTerm::Signal(name) => symbol(name),
+            FieldTerm::Add(lhs, rhs) => {
+                format!("(ff.add {} {})", lhs.to_smt2(symbol), rhs.to_smt2(symbol))
+            }
+            FieldTerm::Mul(lhs, rhs) => {
+                format!("(ff.mul {} {})", lhs.to_smt2(symbol), rhs.to_smt2(symbol))
+            }
+            FieldTerm::Neg(arg) => format!("(ff.neg {})", arg.to_smt2(symbol)),
+        }
+    }
+
+    fn collect_signals(&self, signals: &mut BTreeSet<String>) {
+        match self {
+            FieldTerm::Constant(_) => {}
+            FieldTerm::Signal(name) => {
+                signals.insert(name.clone());
+            }
+            FieldTerm::Add(lhs, rhs) | FieldTerm::Mul(lhs, rhs) => {
+                lhs.collect_signals(signals);
+                rhs.collect_signals(signals);
+            }
+            FieldTerm::Neg(arg) => arg.collect_signals(signals),
+        }
+    }
+}
+
+/// Converts a symbolic value into a term of the field, or returns `None` if it is not a
+/// polynomial, e.g. a comparison or a division by a signal.
+fn to_field_term(
+    value: &SymbolicValue,
+    prime: &BigInt,
+    lookup: &FxHashMap<usize, String>,
+) -> Option<FieldTerm> {
+    match value {
+        SymbolicValue::ConstantInt(c) => Some(FieldTerm::Constant(modreduce(c, prime))),
+        SymbolicValue::Variable(name) => {
+            Some(FieldTerm::Signal(strip_ansi(&name.lookup_fmt(lookup))))
+        }
+        SymbolicValue::UnaryOp(
+            DebuggableExpressionPrefixOpcode(ExpressionPrefixOpcode::Sub),
+            arg,
+        ) => Some(FieldTerm::Neg(Box::new(to_field_term(arg, prime, lookup)?))),
+        SymbolicValue::BinaryOp(lhs, DebuggableExpressionInfixOpcode(op), rhs)
+        | SymbolicValue::AuxBinaryOp(lhs, DebuggableExpressionInfixOpcode(op), rhs) => {
+            let l = to_field_term(lhs, prime, lookup)?;
+            match op {
+                ExpressionInfixOpcode::Add => Some(FieldTerm::Add(
+                    Box::new(l),
+                    Box::new(to_field_term(rhs, prime, lookup)?),
+                )),
+                ExpressionInfixOpcode::Sub => Some(FieldTerm::Add(
+                    Box::new(l),
+                    Box::new(FieldTerm::Neg(Box::new(to_field_term(rhs, prime, lookup)?))),
+                )),
+                ExpressionInfixOpcode::Mul => Some(FieldTerm::Mul(
+                    Box::new(l),
+                    Box::new(to_field_term(rhs, prime, lookup)?),
+                )),
+                // A division by a non-zero constant is a multiplication by its inverse
+                ExpressionInfixOpcode::Div => match &**rhs {
+                    SymbolicValue::ConstantInt(c) if !(c % prime).is_zero() => {
+                        Some(FieldTerm::Mul(
+                            Box::new(l),
+                            Box::new(FieldTerm::Constant(moddiv(&BigInt::one(), c, prime))),
+                        ))
+                    }
+                    _ => None,
+                },
+                ExpressionInfixOpcode::Pow => match &**rhs {
+                    SymbolicValue::ConstantInt(e) if !e.is_negative() => {
+                        let e = e.to_usize().filter(|e| *e <= MAX_EXPANDED_EXPONENT)?;
+                        let mut term = FieldTerm::Constant(BigInt::one());
+                        for _ in 0..e {
+                            term = FieldTerm::Mul(Box::new(term), Box::new(l.clone()));
+                        }
+                        Some(term)
+                    }
+                    _ => None,
+                },
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+/// An equality between two terms of the field.
+pub struct FieldConstraint {
+    pub lhs: FieldTerm,
+    pub rhs: FieldTerm,
+    /// The side constraint the equality was converted from, as printed by zkFuzz.
+    pub source: String,
+}
+
+/// The side constraints of a circuit as equalities over the prime field, ready to be checked by
+/// other tools.
+pub struct ConstraintSystem {
+    pub prime: BigInt,
+    /// The inputs of the main component appearing in the constraints.
+    pub inputs: Vec<String>,
+    /// The outputs of the main component appearing in the constraints.
+    pub outputs: Vec<String>,
+    /// Every signal appearing in the constraints, sorted.
+    pub signals: Vec<String>,
+    pub constraints: Vec<FieldConstraint>,
+    /// The side constraints that are not polynomial equalities (e.g. the range checks assumed
+    /// from tags), which are left out of the export.
+    pub unsupported: Vec<String>,
+}
+
+/// Converts the side constraints of a circuit into a system of equalities over the prime field.
+///
+/// # Parameters
+/// - `side_constraints`: The side constraints collected by the symbolic execution, which are
+///   scanned once.
+/// - `symbolic_library`: The symbolic library containing the template definitions.
+/// - `main_template_name`: The name of the main template.
+/// - `prime`: The order of the field.
+pub fn collect_constraint_system(
+    side_constraints: &dyn ConstraintStore,
+    symbolic_library: &SymbolicLibrary,
+    main_template_name: &str,
+    prime: &BigInt,
+) -> ConstraintSystem {
+    let lookup = &symbolic_library.id2name;
+    let template =
+        &symbolic_library.template_library[&symbolic_library.name2id[main_template_name]];
+
+    let mut constraints = Vec::new();
+    let mut unsupported = Vec::new();
+    let mut inputs = BTreeSet::new();
+    let mut outputs = BTreeSet::new();
+    for c in side_constraints.iter() {
+        let equality = match &*c {
+            SymbolicValue::AssignEq(lhs, rhs)
+            | SymbolicValue::AssignTemplParam(lhs, rhs)
+            | SymbolicValue::BinaryOp(
+                lhs,
+                DebuggableExpressionInfixOpcode(ExpressionInfixOpcode::Eq),
+                rhs,
+            ) => to_field_term(lhs, prime, lookup).zip(to_field_term(rhs, prime, lookup)),
+            _ => None,
+        };
+        let source = strip_ansi(&c.lookup_fmt(lookup));
+        match equality {
+            Some((lhs, rhs)) => constraints.push(FieldConstraint {
+                lhs: lhs,
+                rhs: rhs,
+                source: source,
+            }),
+            None => unsupported.push(source),
+        }
+
+        let mut variables = FxHashSet::default();
+        extract_variables_from_symbolic_value(&c, &mut variables);
+        for v in variables.iter().filter(|v| v.owner.len() == 1) {
+            let name = strip_ansi(&v.lookup_fmt(lookup));
+            if template.input_ids.contains(&v.id) {
+                inputs.insert(name);
+            } else if template.output_ids.contains(&v.id) {
+                outputs.insert(name);
+            }
+        }
+    }
+
+    let mut signals = BTreeSet::new();
+    for c in &constraints {
+        c.lhs.collect_signals(&mut signals);
+        c.rhs.collect_signals(&mut signals);
+    }
+
+    ConstraintSystem {
+        prime: prime.clone(),
+        inputs: inputs.into_iter().collect(),
+        outputs: outputs.into_iter().collect(),
+        signals: signals.into_iter().collect(),
+        constraints: constraints,
+        unsupported: unsupported,
+    }
+}
+
+/// Quotes a signal name as an SMT-LIB symbol.
+fn smt2_symbol(name: &str) -> String {
+    format!("|{}|", name.replace('|', "_").replace('\\', "_"))
+}
+
+impl ConstraintSystem {
+    /// Converts the system into the intermediate JSON format:
+    ///
+    /// ```json
+    /// {
+    ///   "format": "zkfuzz-constraints",
+    ///   "version": 1,
+    ///   "prime": "21888...",
+    ///   "inputs": ["main.a"],
+    ///   "outputs": ["main.out"],
+    ///   "signals": ["main.a", "main.out"],
+    ///   "constraints": [
+    ///     {"lhs": {"signal": "main.out"},
+    ///      "rhs": {"op": "mul", "args": [{"signal": "main.a"}, {"const": "2"}]},
+    ///      "source": "(AssignEq main.out (Mul main.a 2))"}
+    ///   ],
+    ///   "unsupported": []
+    /// }
+    /// ```
+    pub fn to_json(&self) -> Value {
+        json!({
+            "format": "zkfuzz-constraints",
+            "version": 1,
+            "prime": self.prime.to_string(),
+            "inputs": self.inputs,
+            "outputs": self.outputs,
+            "signals": self.signals,
+            "constraints": self.constraints.iter().map(|c| json!({
+                "lhs": c.lhs.to_json(),
+                "rhs": c.rhs.to_json(),
+                "source": c.source,
+            })).collect::<Vec<_>>(),
+            "unsupported": self.unsupported,
+        })
+    }
+
+    /// Converts the system into the uniqueness query of Picus and QED2, in SMT-LIB 2 over the
+    /// finite field theory of cvc5 (`QF_FF`).
+    ///
+    /// Every signal other than the inputs is declared twice, as `|s|` and `|s|'`, and both copies
+    /// are constrained by the system. The query asserts that some output differs between the two
+    /// copies, so `sat` means that the outputs are not uniquely determined by the inputs, i.e.,
+    /// the circuit is under-constrained, and `unsat` that they are.
+    pub fn to_smt2(&self) -> String {
+        let inputs: BTreeSet<&String> = self.inputs.iter().collect();
+        let first = |name: &str| smt2_symbol(name);
+        let second = |name: &str| {
+            if inputs.contains(&name.to_string()) {
+                smt2_symbol(name)
+            } else {
+                smt2_symbol(&format!("{}'", name))
+            }
+        };
+
+        let mut smt2 = String::new();
+        smt2 += "; Generated by zkFuzz: sat means that the circuit is under-constrained\n";
+        smt2 += "(set-logic QF_FF)\n";
+        smt2 += &format!("(define-sort F () (_ FiniteField {}))\n", self.prime);
+        let mut signals: BTreeSet<&String> = self.signals.iter().collect();
+        signals.extend(self.inputs.iter().chain(self.outputs.iter()));
+        for s in &signals {
+            smt2 += &format!("(declare-const {} F)\n", first(s));
+            if !inputs.contains(s) {
+                smt2 += &format!("(declare-const {} F)\n", second(s));
+            }
+        }
+        for c in &self.constraints {
+            smt2 += &format!("; {}\n", c.source);
+            smt2 += &format!(
+                "(assert (= {} {}))\n",
+                c.lhs.to_smt2(&first),
+                c.rhs.to_smt2(&first)
+            );
+            smt2 += &format!(
+                "(assert (= {} {}))\n",
+                c.lhs.to_smt2(&second),
+                c.rhs.to_smt2(&second)
+            );
+        }
+        for c in &self.unsupported {
+            smt2 += &format!("; unsupported: {}\n", c);
+        }
+        let differences: Vec<String> = self
+            .outputs
+            .iter()
+            .map(|o| format!("(not (= {} {}))", first(o), second(o)))
+            .collect();
+        smt2 += &match differences.len() {
+            0 => "(assert false)\n".to_string(),
+            1 => format!("(assert {})\n", differences[0]),
+            _ => format!("(assert (or {}))\n", differences.join(" ")),
+        };
+        smt2 += "(check-sat)\n";
+        smt2
+    }
+}
@@ -0,0 +1,543 @@
+use std::borrow::Cow;
+
+use num_bigint_dig::BigInt;
+use num_traits::Zero;
+use wgpu::util::DeviceExt;
+
+use crate::mutator::batch_evaluation::{BatchEvaluator, FieldInstruction, FieldProgram};
+
+/// The number of 32-bit limbs of a field element, which bounds the prime by `2^256`.
+const NUM_LIMBS: usize = 8;
+const WORKGROUP_SIZE: usize = 64;
+/// The maximum number of workgroups of a dispatch along one dimension.
+const MAX_WORKGROUPS: usize = 65535;
+const MAX_BATCH_SIZE: usize = 1 << 20;
+
+/// The helpers of the kernel on field elements in Montgomery form, as little-endian limbs. The
+/// generated part defines `NUM_SLOTS`, `INV`, `modulus()`, and `one()`.
+const KERNEL_PRELUDE: &str = r#"
+@group(0) @binding(0) var<storage, read> assignments: array<u32>;
+@group(0) @binding(1) var<storage, read> constants: array<u32>;
+@group(0) @binding(2) var<storage, read_write> results: array<u32>;
+
+struct Wide {
+    value: array<u32, 8>,
+    carry: u32,
+}
+
+fn load_slot(base: u32, slot: u32) -> array<u32, 8> {
+    var r: array<u32, 8>;
+    for (var i = 0u; i < 8u; i++) {
+        r[i] = assignments[base + slot * 8u + i];
+    }
+    return r;
+}
+
+fn load_constant(index: u32) -> array<u32, 8> {
+    var r: array<u32, 8>;
+    for (var i = 0u; i < 8u; i++) {
+        r[i] = constants[index * 8u + i];
+    }
+    return r;
+}
+
+fn is_zero(a: array<u32, 8>) -> bool {
+    var x = a;
+    var acc = 0u;
+    for (var i = 0u; i < 8u; i++) {
+        acc |= x[i];
+    }
+    return acc == 0u;
+}
+
+fn from_bool(b: bool) -> array<u32, 8> {
+    if (b) {
+        return one();
+    }
+    return array<u32, 8>();
+}
+
+fn less_than(a: array<u32, 8>, b: array<u32, 8>) -> bool {
+    var x = a;
+    var y = b;
+    for (var i = 7i; i >= 0i; i--) {
+        if (x[i] != y[i]) {
+            return x[i] < y[i];
+        }
+    }
+    return false;
+}
+
+fn add_raw(a: array<u32, 8>, b: array<u32, 8>) -> Wide {
+    var x = a;
+    var y = b;
+    var r: array<u32, 8>;
+    var carry = 0u;
+    for (var i = 0u; i < 8u; i++) {
+        let s = x[i] + y[i];
+        let t = s + carry;
+        r[i] = t;
+        carry = select(0u, 1u, s < x[i]) + select(0u, 1u, t < s);
+    }
+    return Wide(r, carry);
+}
+
+fn sub_raw(a: array<u32, 8>, b: array<u32, 8>) -> Wide {
+    var x = a;
+    var y = b;
+    var r: array<u32, 8>;
+    var borrow = 0u;
+    for (var i = 0u; i < 8u; i++) {
+        let d = x[i] - y[i];
+        let t = d - borrow;
+        r[i] = t;
+        borrow = select(0u, 1u, x[i] < y[i]) + select(0u, 1u, d < borrow);
+    }
+    return Wide(r, borrow);
+}
+
+fn f_add(a: array<u32, 8>, b: array<u32, 8>) -> array<u32, 8> {
+    let s = add_raw(a, b);
+    if (s.carry != 0u || !less_than(s.value, modulus())) {
+        return sub_raw(s.value, modulus()).value;
+    }
+    return s.value;
+}
+
+fn f_sub(a: array<u32, 8>, b: array<u32, 8>) -> array<u32, 8> {
+    let d = sub_raw(a, b);
+    if (d.carry != 0u) {
+        return add_raw(d.value, modulus()).value;
+    }
+    return d.value;
+}
+
+fn f_neg(a: array<u32, 8>) -> array<u32, 8> {
+    if (is_zero(a)) {
+        return a;
+    }
+    return sub_raw(modulus(), a).value;
+}
+
+// Returns the low and high words of `a * b`.
+fn mul_wide(a: u32, b: u32) -> vec2<u32> {
+    let a0 = a & 0xffffu;
+    let a1 = a >> 16u;
+    let b0 = b & 0xffffu;
+    let b1 = b >> 16u;
+    let p00 = a0 * b0;
+    let p01 = a0 * b1;
+    let p10 = a1 * b0;
+    let p11 = a1 * b1;
+    let mid = p01 + (p00 >> 16u) + (p10 & 0xffffu);
+    return vec2<u32>((mid << 16u) | (p00 & 0xffffu), p11 + (mid >> 16u) + (p10 >> 16u));
+}
+
+// Returns the low and high words of `a * b + c + d`.
+fn mad(a: u32, b: u32, c: u32, d: u32) -> vec2<u32> {
+    let m = mul_wide(a, b);
+    let lo = m.x + c;
+    let r = lo + d;
+    return vec2<u32>(r, m.y + select(0u, 1u, lo < c) + select(0u, 1u, r < d));
+}
+
+// Montgomery multiplication with coarsely integrated operand scanning.
+fn f_mul(a: array<u32, 8>, b: array<u32, 8>) -> array<u32, 8> {
+    var x = a;
+    var y = b;
+    var m = modulus();
+    var t: array<u32, 10>;
+    for (var i = 0u; i < 8u; i++) {
+        var carry = 0u;
+        for (var j = 0u; j < 8u; j++) {
+            let r = mad(x[j], y[i], t[j], carry);
+            t[j] = r.x;
+            carry = r.y;
+        }
+        let s = t[8] + carry;
+        t[9] = select(0u, 1u, s < carry);
+        t[8] = s;
+
+        let q = t[0] * INV;
+        carry = mad(q, m[0], t[0], 0u).y;
+        for (var j = 1u; j < 8u; j++) {
+            let r = mad(q, m[j], t[j], carry);
+            t[j - 1u] = r.x;
+            carry = r.y;
+        }
+        let u = t[8] + carry;
+        t[7] = u;
+        t[8] = t[9] + select(0u, 1u, u < carry);
+    }
+    var r: array<u32, 8>;
+    for (var i = 0u; i < 8u; i++) {
+        r[i] = t[i];
+    }
+    if (t[8] != 0u || !less_than(r, m)) {
+        return sub_raw(r, m).value;
+    }
+    return r;
+}
+
+fn f_eq(a: array<u32, 8>, b: array<u32, 8>) -> array<u32, 8> {
+    var x = a;
+    var y = b;
+    var diff = 0u;
+    for (var i = 0u; i < 8u; i++) {
+        diff |= x[i] ^ y[i];
+    }
+    return from_bool(diff == 0u);
+}
+
+fn f_ne(a: array<u32, 8>, b: array<u32, 8>) -> array<u32, 8> {
+    return from_bool(is_zero(f_eq(a, b)));
+}
+
+fn f_and(a: array<u32, 8>, b: array<u32, 8>) -> array<u32, 8> {
+    return from_bool(!is_zero(a) && !is_zero(b));
+}
+
+fn f_or(a: array<u32, 8>, b: array<u32, 8>) -> array<u32, 8> {
+    return from_bool(!is_zero(a) || !is_zero(b));
+}
+
+fn f_not(a: array<u32, 8>) -> array<u32, 8> {
+    return from_bool(is_zero(a));
+}
+
+fn f_select(c: array<u32, 8>, a: array<u32, 8>, b: array<u32, 8>) -> array<u32, 8> {
+    if (is_zero(c)) {
+        return b;
+    }
+    return a;
+}
+"#;
+
+/// Returns the little-endian limbs of `value`, which is in `[0, 2^256)`.
+fn to_limbs(value: &BigInt) -> [u32; NUM_LIMBS] {
+    let (_, bytes) = value.to_bytes_le();
+    let mut limbs = [0u32; NUM_LIMBS];
+    for (i, byte) in bytes.iter().enumerate().take(NUM_LIMBS * 4) {
+        limbs[i / 4] |= (*byte as u32) << (8 * (i % 4));
+    }
+    limbs
+}
+
+/// Returns the limbs of `value` in Montgomery form, i.e. `value * 2^256 mod p`.
+fn to_montgomery_limbs(value: &BigInt, prime: &BigInt) -> [u32; NUM_LIMBS] {
+    to_limbs(&((value << (32 * NUM_LIMBS)) % prime))
+}
+
+/// Returns `-p^-1 mod 2^32` for the lowest limb `p0` of an odd prime.
+fn montgomery_factor(p0: u32) -> u32 {
+    // Each Newton iteration doubles the number of correct low bits of the inverse
+    let mut inv = 1u32;
+    for _ in 0..5 {
+        inv = inv.wrapping_mul(2u32.wrapping_sub(p0.wrapping_mul(inv)));
+    }
+    inv.wrapping_neg()
+}
+
+fn format_limbs(limbs: &[u32; NUM_LIMBS]) -> String {
+    let words = limbs
+        .iter()
+        .map(|limb| format!("0x{:08x}u", limb))
+        .collect::<Vec<_>>()
+        .join(", ");
+    format!("array<u32, 8>({})", words)
+}
+
+/// Generates the WGSL compute kernel that evaluates the tapes of `program` on one assignment per
+/// invocation, with each tape unrolled into straight-line code. Bit 0 of the result of an
+/// assignment is whether it satisfies the trace constraints, and bit 1 whether it satisfies the
+/// side constraints.
+pub fn generate_kernel(program: &FieldProgram) -> String {
+    let prime_limbs = to_limbs(&program.prime);
+    let mut kernel = String::new();
+    kernel.push_str(&format!(
+        "const NUM_SLOTS: u32 = {}u;\nconst INV: u32 = 0x{:08x}u;\n\n",
+        program.num_slots,
+        montgomery_factor(prime_limbs[0])
+    ));
+    kernel.push_str(&format!(
+        "fn modulus() -> array<u32, 8> {{\n    return {};\n}}\n\n",
+        format_limbs(&prime_limbs)
+    ));
+    kernel.push_str(&format!(
+        "fn one() -> array<u32, 8> {{\n    return {};\n}}\n",
+        format_limbs(&to_montgomery_limbs(&BigInt::from(1), &program.prime))
+    ));
+    kernel.push_str(KERNEL_PRELUDE);
+
+    kernel.push_str(&format!("\n@compute @workgroup_size({})\n", WORKGROUP_SIZE));
+    kernel.push_str("fn evaluate(@builtin(global_invocation_id) id: vec3<u32>) {\n");
+    kernel.push_str("    let index = id.x;\n");
+    kernel.push_str("    if (index >= arrayLength(&results)) {\n        return;\n    }\n");
+    kernel.push_str("    let base = index * NUM_SLOTS * 8u;\n");
+    for (flag, tapes) in [
+        ("trace_ok", &program.trace),
+        ("side_ok", &program.side_constraints),
+    ] {
+        kernel.push_str(&format!("    var {} = true;\n", flag));
+        for tape in tapes.iter() {
+            kernel.push_str(&format!("    if ({}) {{\n", flag));
+            let result = generate_tape(tape, &mut kernel);
+            kernel.push_str(&format!(
+                "        {} = !is_zero({});\n    }}\n",
+                flag, result
+            ));
+        }
+    }
+    kernel
+        .push_str("    results[index] = select(0u, 1u, trace_ok) | select(0u, 2u, side_ok);\n}\n");
+    kernel
+}
+
+/// Appends the statements evaluating `tape` to `kernel`, one `let` per instruction.
+///
+/// # Returns
+/// The name of the value of the tape.
+fn generate_tape(tape: &[FieldInstruction], kernel: &mut String) -> String {
+    let mut stack: Vec<String> = Vec::new();
+    for (i, instruction) in tape.iter().enumerate() {
+        let expr = match instruction {
+            FieldInstruction::Constant(index) => format!("load_constant({}u)", index),
+            FieldInstruction::Load(slot) => format!("load_slot(base, {}u)", slot),
+            FieldInstruction::One => "one()".to_string(),
+            FieldInstruction::Neg => format!("f_neg({})", stack.pop().unwrap()),
+            FieldInstruction::Not => format!("f_not({})", stack.pop().unwrap()),
+            FieldInstruction::Select => {
+                let else_value = stack.pop().unwrap();
+                let then_value = stack.pop().unwrap();
+                let cond = stack.pop().unwrap();
+                format!("f_select({}, {}, {})", cond, then_value, else_value)
+            }
+            _ => {
+                let rhs = stack.pop().unwrap();
+                let lhs = stack.pop().unwrap();
+                let function = match instruction {
+                    FieldInstruction::Add => "f_add",
+                    FieldInstruction::Sub => "f_sub",
+                    FieldInstruction::Mul => "f_mul",
+                    FieldInstruction::Eq => "f_eq",
+                    FieldInstruction::NotEq => "f_ne",
+                    FieldInstruction::And => "f_and",
+                    FieldInstruction::Or => "f_or",
+                    _ => unreachable!(),
+                };
+                format!("{}({}, {})", function, lhs, rhs)
+            }
+        };
+        kernel.push_str(&format!("        let v{} = {};\n", i, expr));
+        stack.push(format!("v{}", i));
+    }
+    stack.pop().unwrap()
+}
+
+/// Evaluates the tapes of a `FieldProgram` with a compute kernel on the GPU, with the field
+/// elements in Montgomery form over 32-bit limbs.
+pub struct GpuBatchEvaluator {
+    prime: BigInt,
+    num_slots: usize,
+    batch_size: usize,
+    device: wgpu::Device,
+    queue: wgpu::Queue,
+    pipeline: wgpu::ComputePipeline,
+    bind_group_layout: wgpu::BindGroupLayout,
+    constants: wgpu::Buffer,
+}
+
+impl GpuBatchEvaluator {
+    /// Compiles the kernel of `program` on the first high-performance adapter.
+    ///
+    /// # Returns
+    /// The evaluator, or the reason why it cannot run, e.g. no adapter or a prime wider than
+    /// 256 bits.
+    pub fn new(program: &FieldProgram) -> Result<Self, String> {
+        if program.num_slots == 0 {
+            return Err("there is no variable to evaluate".to_string());
+        }
+        if program.prime.bits() > 32 * NUM_LIMBS || (&program.prime % BigInt::from(2)).is_zero() {
+            return Err("the GPU backend supports odd primes below 2^256".to_string());
+        }
+
+        let instance = wgpu::Instance::default();
+        let adapter = pollster::block_on(instance.request_adapter(&wgpu::RequestAdapterOptions {
+            power_preference: wgpu::PowerPreference::HighPerformance,
+            force_fallback_adapter: false,
+            compatible_surface: None,
+        }))
+        .ok_or_else(|| "no GPU adapter is available".to_string())?;
+        let (device, queue) = pollster::block_on(adapter.request_device(
+            &wgpu::DeviceDescriptor {
+                label: Some("zkfuzz"),
+                required_features: wgpu::Features::empty(),
+                required_limits: adapter.limits(),
+            },
+            None,
+        ))
+        .map_err(|e| format!("cannot open the GPU device: {}", e))?;
+
+        device.push_error_scope(wgpu::ErrorFilter::Validation);
+        let module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("zkfuzz-constraints"),
+            source: wgpu::ShaderSource::Wgsl(Cow::Owned(generate_kernel(program))),
+        });
+        if let Some(e) = pollster::block_on(device.pop_error_scope()) {
+            return Err(format!("cannot compile the kernel: {}", e));
+        }
+
+        let storage_entry = |binding: u32, read_only: bool| wgpu::BindGroupLayoutEntry {
+            binding: binding,
+            visibility: wgpu::ShaderStages::COMPUTE,
+            ty: wgpu::BindingType::Buffer {
+                ty: wgpu::BufferBindingType::Storage {
+                    read_only: read_only,
+                },
+                has_dynamic_offset: false,
+                min_binding_size: None,
+            },
+            count: None,
+        };
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("zkfuzz-bindings"),
+            entries: &[
+                storage_entry(0, true),
+                storage_entry(1, true),
+                storage_entry(2, false),
+            ],
+        });
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("zkfuzz-pipeline-layout"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+        let pipeline = device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+            label: Some("zkfuzz-pipeline"),
+            layout: Some(&pipeline_layout),
+            module: &module,
+            entry_point: "evaluate",
+        });
+
+        // A binding cannot be empty, so the constants are padded to at least one element
+        let mut constant_words: Vec<u32> = program
+            .constants
+            .iter()
+            .flat_map(|c| to_montgomery_limbs(c, &program.prime))
+            .collect();
+        constant_words.resize(constant_words.len().max(NUM_LIMBS), 0);
+        let constants = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("zkfuzz-constants"),
+            contents: &words_to_bytes(&constant_words),
+            usage: wgpu::BufferUsages::STORAGE,
+        });
+
+        let bytes_per_assignment = program.num_slots * NUM_LIMBS * 4;
+        let batch_size = MAX_BATCH_SIZE
+            .min(device.limits().max_storage_buffer_binding_size as usize / bytes_per_assignment)
+            .min(MAX_WORKGROUPS * WORKGROUP_SIZE)
+            .max(1);
+
+        Ok(GpuBatchEvaluator {
+            prime: program.prime.clone(),
+            num_slots: program.num_slots,
+            batch_size: batch_size,
+            device: device,
+            queue: queue,
+            pipeline: pipeline,
+            bind_group_layout: bind_group_layout,
+            constants: constants,
+        })
+    }
+}
+
+fn words_to_bytes(words: &[u32]) -> Vec<u8> {
+    words.iter().flat_map(|word| word.to_le_bytes()).collect()
+}
+
+impl BatchEvaluator for GpuBatchEvaluator {
+    fn batch_size(&self) -> usize {
+        self.batch_size
+    }
+
+    fn evaluate_batch(&mut self, assignments: &[BigInt]) -> Vec<(bool, bool)> {
+        let num_assignments = assignments.len() / self.num_slots;
+        if num_assignments == 0 {
+            return Vec::new();
+        }
+        let words: Vec<u32> = assignments
+            .iter()
+            .flat_map(|value| to_montgomery_limbs(value, &self.prime))
+            .collect();
+        let input = self
+            .device
+            .create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some("zkfuzz-assignments"),
+                contents: &words_to_bytes(&words),
+                usage: wgpu::BufferUsages::STORAGE,
+            });
+        let results_size = (num_assignments * 4) as u64;
+        let results = self.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("zkfuzz-results"),
+            size: results_size,
+            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_SRC,
+            mapped_at_creation: false,
+        });
+        let readback = self.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("zkfuzz-readback"),
+            size: results_size,
+            usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let bind_group = self.device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("zkfuzz-bind-group"),
+            layout: &self.bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: input.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: self.constants.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: results.as_entire_binding(),
+                },
+            ],
+        });
+
+        let mut encoder = self
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                label: Some("zkfuzz-encoder"),
+            });
+        {
+            let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+                label: Some("zkfuzz-pass"),
+                timestamp_writes: None,
+            });
+            pass.set_pipeline(&self.pipeline);
+            pass.set_bind_group(0, &bind_group, &[]);
+            let num_workgroups = (num_assignments + WORKGROUP_SIZE - 1) / WORKGROUP_SIZE;
+            pass.dispatch_workgroups(num_workgroups as u32, 1, 1);
+        }
+        encoder.copy_buffer_to_buffer(&results, 0, &readback, 0, results_size);
+        self.queue.submit(Some(encoder.finish()));
+
+        let slice = readback.slice(..);
+        slice.map_async(wgpu::MapMode::Read, |_| {});
+        let _ = self.device.poll(wgpu::Maintain::Wait);
+        let flags = slice
+            .get_mapped_range()
+            .chunks_exact(4)
+            .map(|bytes| {
+                let flag = u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+                (flag & 1 != 0, flag & 2 != 0)
+            })
+            .collect();
+        readback.unmap();
+        flags
+    }
+}
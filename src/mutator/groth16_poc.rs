@@ -0,0 +1,265 @@
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+
+use num_bigint_dig::BigInt;
+use rustc_hash::FxHashMap;
+use serde_json::{json, Value};
+
+use crate::executor::symbolic_value::SymbolicLibrary;
+use crate::executor::utils::{modreduce, strip_ansi};
+use crate::mutator::circom_validation::{
+    compile_with_circom, counterexample_values, forge_witness, parse_r1cs, parse_sym,
+    run_witness_calculator,
+};
+use crate::mutator::utils::{BaseVerificationConfig, CounterExample, VerificationResult};
+use crate::mutator::witness_export::build_input_json;
+
+/// Returns the number of bytes of an element of the field of `prime` in the `.wtns` format,
+/// a multiple of 8 as in circom.
+fn field_size(prime: &BigInt) -> usize {
+    ((prime.bits() - 1) / 64 + 1) * 8
+}
+
+fn push_field(bytes: &mut Vec<u8>, value: &BigInt, n8: usize) {
+    let (_, mut le) = value.to_bytes_le();
+    le.resize(n8, 0);
+    bytes.extend_from_slice(&le);
+}
+
+/// Serializes a witness in the binary `.wtns` format (version 2) read by snarkjs.
+///
+/// # Parameters
+/// - `witness`: The value of every wire, the constant wire first.
+/// - `prime`: The prime of the field, whose values are reduced to `[0, p)` beforehand.
+pub fn write_wtns(witness: &[BigInt], prime: &BigInt) -> Vec<u8> {
+    let n8 = field_size(prime);
+    let mut bytes = Vec::new();
+    bytes.extend_from_slice(b"wtns");
+    bytes.extend_from_slice(&2u32.to_le_bytes());
+    bytes.extend_from_slice(&2u32.to_le_bytes());
+
+    // The header: the field size, the prime, and the number of wires
+    bytes.extend_from_slice(&1u32.to_le_bytes());
+    bytes.extend_from_slice(&((4 + n8 + 4) as u64).to_le_bytes());
+    bytes.extend_from_slice(&(n8 as u32).to_le_bytes());
+    push_field(&mut bytes, prime, n8);
+    bytes.extend_from_slice(&(witness.len() as u32).to_le_bytes());
+
+    bytes.extend_from_slice(&2u32.to_le_bytes());
+    bytes.extend_from_slice(&((witness.len() * n8) as u64).to_le_bytes());
+    for value in witness {
+        push_field(&mut bytes, &modreduce(value, prime), n8);
+    }
+    bytes
+}
+
+/// Whether snarkjs accepted a proof of the witness of a counterexample.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum PocStatus {
+    /// The proof of the forged witness verifies against the verification key.
+    Verified,
+    /// The proof was generated but does not verify.
+    Rejected,
+    /// No proof was generated, e.g. because snarkjs could not be run.
+    Failed,
+}
+
+/// A Groth16 proof generated from the witness of a counterexample, and the files needed to
+/// check it independently.
+pub struct Groth16Poc {
+    pub status: PocStatus,
+    pub reason: String,
+    /// The files written, with their kinds (e.g. `poc_proof`).
+    pub artifacts: Vec<(&'static str, String)>,
+}
+
+impl Groth16Poc {
+    fn failed(reason: String, artifacts: Vec<(&'static str, String)>) -> Self {
+        Groth16Poc {
+            status: PocStatus::Failed,
+            reason: reason,
+            artifacts: artifacts,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self.status {
+            PocStatus::Verified => "VERIFIED",
+            PocStatus::Rejected => "REJECTED",
+            PocStatus::Failed => "FAILED",
+        }
+    }
+
+    pub fn to_json(&self) -> Value {
+        json!({
+            "status": self.label(),
+            "reason": self.reason,
+            "artifacts": self
+                .artifacts
+                .iter()
+                .map(|(kind, path)| json!({"kind": kind, "path": path}))
+                .collect::<Vec<_>>(),
+        })
+    }
+}
+
+/// The tools and the circuit used to generate a Groth16 proof of concept.
+pub struct Groth16PocConfig<'a> {
+    /// The circom binary, which compiles the circuit with `--O0` as `--validate_with_circom`.
+    pub path_to_circom: &'a str,
+    pub path_to_snarkjs: &'a str,
+    /// The proving key, set up for the R1CS compiled with `circom --O0`.
+    pub path_to_zkey: &'a str,
+    pub input_file: &'a str,
+    pub link_libraries: &'a [PathBuf],
+    /// The curve passed to circom with `-p`.
+    pub prime_name: &'a str,
+}
+
+/// Runs `snarkjs` with `args`, and returns its standard output or an error with its standard
+/// error.
+fn run_snarkjs(path_to_snarkjs: &str, args: &[&str]) -> Result<String, String> {
+    let output = Command::new(path_to_snarkjs)
+        .args(args)
+        .output()
+        .map_err(|e| format!("unable to run `{}`: {}", path_to_snarkjs, e))?;
+    let stdout = strip_ansi(&String::from_utf8_lossy(&output.stdout));
+    if !output.status.success() {
+        let stderr = strip_ansi(&String::from_utf8_lossy(&output.stderr));
+        return Err(format!(
+            "`snarkjs {}` failed: {}",
+            args[..2].join(" "),
+            if stderr.trim().is_empty() {
+                stdout.trim().to_string()
+            } else {
+                stderr.trim().to_string()
+            }
+        ));
+    }
+    Ok(stdout)
+}
+
+/// Generates a Groth16 proof from the witness of an under-constrained counterexample and
+/// verifies it with snarkjs, which shows that a verifier accepts the forged witness.
+///
+/// The circuit is compiled with `circom --r1cs --wasm --sym --O0` and the witness is built as
+/// for `--validate_with_circom`: the witness computed by circom for the inputs of the
+/// counterexample, with the signals of the counterexample overwritten. It is written to
+/// `<prefix>_poc.wtns`, proven with `snarkjs groth16 prove` into `<prefix>_poc_proof.json` and
+/// `<prefix>_poc_public.json`, and verified with `snarkjs groth16 verify` against the
+/// verification key exported from the proving key to `<prefix>_poc_verification_key.json`.
+///
+/// # Parameters
+/// - `config`: The tools, the proving key, and the circuit.
+/// - `ce`: The counterexample, which should be confirmed by `validate_with_circom`.
+/// - `symbolic_library`: The symbolic library containing the template definitions.
+/// - `base_config`: The verification configuration specifying the target template and prime.
+/// - `id2dimensions`: The dimensions of the signals declared in the main template.
+/// - `prefix`: The common prefix of the files of the counterexample.
+///
+/// # Returns
+/// The proof of concept, `FAILED` if the counterexample is not under-constrained, if the R1CS
+/// rejects its witness, or if circom or snarkjs cannot be run.
+pub fn generate_groth16_poc(
+    config: &Groth16PocConfig,
+    ce: &CounterExample,
+    symbolic_library: &SymbolicLibrary,
+    base_config: &BaseVerificationConfig,
+    id2dimensions: &FxHashMap<usize, Vec<usize>>,
+    prefix: &str,
+) -> Groth16Poc {
+    if !matches!(ce.flag, VerificationResult::UnderConstrained(_)) {
+        return Groth16Poc::failed(
+            "only the witnesses of under-constrained counterexamples can be proven".to_string(),
+            Vec::new(),
+        );
+    }
+
+    let out_dir = env::temp_dir().join(format!("zkfuzz_poc_{}", std::process::id()));
+    let mut artifacts = Vec::new();
+    let result = (|| {
+        let (r1cs_path, sym_path, wasm_path) = compile_with_circom(
+            config.path_to_circom,
+            config.input_file,
+            config.link_libraries,
+            config.prime_name,
+            &out_dir,
+        )?;
+        let r1cs = parse_r1cs(&fs::read(&r1cs_path).map_err(|e| e.to_string())?)?;
+        let wires = parse_sym(&fs::read_to_string(&sym_path).map_err(|e| e.to_string())?);
+        let input = build_input_json(&ce.assignment, symbolic_library, base_config, id2dimensions);
+        let honest_witness = run_witness_calculator(&wasm_path, &input)
+            .map_err(|e| format!("unable to run the witness calculator: {}", e))?;
+        let witness = forge_witness(
+            &r1cs,
+            &wires,
+            honest_witness.as_ref(),
+            &counterexample_values(ce, symbolic_library),
+        );
+        if let Some(i) = r1cs.first_violated(&witness) {
+            return Err(format!(
+                "constraint #{} of the R1CS rejects the witness of the counterexample",
+                i
+            ));
+        }
+
+        let wtns_path = format!("{}_poc.wtns", prefix);
+        fs::write(&wtns_path, write_wtns(&witness, &r1cs.prime)).map_err(|e| e.to_string())?;
+        artifacts.push(("poc_witness", wtns_path.clone()));
+
+        let proof_path = format!("{}_poc_proof.json", prefix);
+        let public_path = format!("{}_poc_public.json", prefix);
+        run_snarkjs(
+            config.path_to_snarkjs,
+            &[
+                "groth16",
+                "prove",
+                config.path_to_zkey,
+                &wtns_path,
+                &proof_path,
+                &public_path,
+            ],
+        )?;
+        artifacts.push(("poc_proof", proof_path.clone()));
+        artifacts.push(("poc_public_signals", public_path.clone()));
+
+        let vkey_path = format!("{}_poc_verification_key.json", prefix);
+        run_snarkjs(
+            config.path_to_snarkjs,
+            &[
+                "zkey",
+                "export",
+                "verificationkey",
+                config.path_to_zkey,
+                &vkey_path,
+            ],
+        )?;
+        artifacts.push(("poc_verification_key", vkey_path.clone()));
+
+        // `groth16 verify` exits with an error on an invalid proof
+        Ok(
+            match run_snarkjs(
+                config.path_to_snarkjs,
+                &["groth16", "verify", &vkey_path, &public_path, &proof_path],
+            ) {
+                Ok(stdout) if stdout.contains("OK") => (
+                    PocStatus::Verified,
+                    "snarkjs verifies the proof of the witness of the counterexample".to_string(),
+                ),
+                Ok(stdout) => (PocStatus::Rejected, stdout.trim().to_string()),
+                Err(e) => (PocStatus::Rejected, e),
+            },
+        )
+    })();
+    let _ = fs::remove_dir_all(&out_dir);
+    match result {
+        Ok((status, reason)) => Groth16Poc {
+            status: status,
+            reason: reason,
+            artifacts: artifacts,
+        },
+        Err(reason) => Groth16Poc::failed(reason, artifacts),
+    }
+}
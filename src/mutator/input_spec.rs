@@ -0,0 +1,203 @@
+use std::fs;
+use std::str::FromStr;
+
+use num_bigint_dig::{BigInt, RandBigInt};
+use num_traits::{One, ToPrimitive, Zero};
+use rand::Rng;
+use rustc_hash::FxHashMap;
+
+use crate::executor::symbolic_value::{
+    SymbolicAccess, SymbolicName, SymbolicTemplate, SymbolicValue,
+};
+
+/// Maximum number of values of a range enumerated by the brute-force search. Larger ranges are
+/// only tried at their bounds.
+pub const MAX_ENUMERATED_RANGE: usize = 1 << 10;
+
+/// The values an input of the main template may take during the search.
+#[derive(Clone, Debug, PartialEq)]
+pub enum InputDomain {
+    /// Exactly these values.
+    Values(Vec<BigInt>),
+    /// Every value between the two bounds, inclusive.
+    Range(BigInt, BigInt),
+}
+
+impl InputDomain {
+    pub fn contains(&self, value: &BigInt) -> bool {
+        match self {
+            InputDomain::Values(values) => values.contains(value),
+            InputDomain::Range(min, max) => min <= value && value <= max,
+        }
+    }
+
+    /// Draws a value of the domain uniformly at random.
+    pub fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> BigInt {
+        match self {
+            InputDomain::Values(values) => values[rng.gen_range(0, values.len())].clone(),
+            InputDomain::Range(min, max) => rng.gen_bigint_range(min, &(max + BigInt::one())),
+        }
+    }
+
+    /// Returns the values tried by the brute-force search: every value of the domain, or only the
+    /// bounds of a range with more than `MAX_ENUMERATED_RANGE` values.
+    pub fn candidates(&self) -> Vec<BigInt> {
+        match self {
+            InputDomain::Values(values) => values.clone(),
+            InputDomain::Range(min, max) => {
+                let size = max - min + BigInt::one();
+                if size.to_usize().map_or(false, |n| n <= MAX_ENUMERATED_RANGE) {
+                    let mut values = Vec::new();
+                    let mut value = min.clone();
+                    while &value <= max {
+                        values.push(value.clone());
+                        value += 1;
+                    }
+                    values
+                } else {
+                    vec![
+                        min.clone(),
+                        min + BigInt::one(),
+                        max - BigInt::one(),
+                        max.clone(),
+                    ]
+                }
+            }
+        }
+    }
+}
+
+/// The domains declared with `--input_spec` for the inputs of the main template.
+///
+/// A domain is keyed by the name of an input, e.g. `in`, which applies to all its elements, or
+/// by one of its elements, e.g. `in[0]`, which takes precedence.
+#[derive(Clone, Default, Debug)]
+pub struct InputSpec {
+    pub domains: FxHashMap<String, InputDomain>,
+}
+
+impl InputSpec {
+    /// Returns the domain declared for `var`, an input of the main template.
+    ///
+    /// # Parameters
+    /// - `var`: The input variable.
+    /// - `lookup`: A map from ids to the names of the symbols.
+    pub fn domain_of(
+        &self,
+        var: &SymbolicName,
+        lookup: &FxHashMap<usize, String>,
+    ) -> Option<&InputDomain> {
+        if self.domains.is_empty() {
+            return None;
+        }
+        let name = &lookup[&var.id];
+        let mut element = name.clone();
+        for access in var.access.iter().flatten() {
+            match access {
+                SymbolicAccess::ArrayAccess(SymbolicValue::ConstantInt(index)) => {
+                    element += &format!("[{}]", index);
+                }
+                _ => return self.domains.get(name),
+            }
+        }
+        self.domains
+            .get(&element)
+            .or_else(|| self.domains.get(name))
+    }
+
+    /// Returns the keys of the specification that do not name an input of `template`, sorted.
+    pub fn unknown_inputs(
+        &self,
+        template: &SymbolicTemplate,
+        name2id: &FxHashMap<String, usize>,
+    ) -> Vec<String> {
+        let mut unknown: Vec<String> = self
+            .domains
+            .keys()
+            .filter(|key| {
+                let name = key.split('[').next().unwrap();
+                name2id
+                    .get(name)
+                    .map_or(true, |id| !template.input_ids.contains(id))
+            })
+            .cloned()
+            .collect();
+        unknown.sort();
+        unknown
+    }
+}
+
+/// Parses a bound or a value of a domain, written either as an integer or as a decimal string
+/// for values that do not fit in 64 bits.
+fn parse_value(value: &toml::Value) -> Result<BigInt, String> {
+    match value {
+        toml::Value::Integer(n) => Ok(BigInt::from(*n)),
+        toml::Value::String(s) => BigInt::from_str(s).map_err(|_| format!("invalid value `{}`", s)),
+        v => Err(format!("invalid value `{}`", v)),
+    }
+}
+
+fn parse_domain(name: &str, value: &toml::Value) -> Result<InputDomain, String> {
+    let invalid = |reason: String| format!("invalid domain of `{}`: {}", name, reason);
+    match value {
+        toml::Value::String(s) if s == "boolean" => {
+            Ok(InputDomain::Values(vec![BigInt::zero(), BigInt::one()]))
+        }
+        toml::Value::Table(table) => {
+            if let Some(fixed) = table.get("fixed") {
+                Ok(InputDomain::Values(vec![
+                    parse_value(fixed).map_err(invalid)?
+                ]))
+            } else if let Some(values) = table.get("values") {
+                let values = values
+                    .as_array()
+                    .ok_or_else(|| invalid("`values` should be an array".to_string()))?
+                    .iter()
+                    .map(parse_value)
+                    .collect::<Result<Vec<_>, _>>()
+                    .map_err(invalid)?;
+                if values.is_empty() {
+                    return Err(invalid("`values` is empty".to_string()));
+                }
+                Ok(InputDomain::Values(values))
+            } else if let (Some(min), Some(max)) = (table.get("min"), table.get("max")) {
+                let min = parse_value(min).map_err(invalid)?;
+                let max = parse_value(max).map_err(invalid)?;
+                if min > max {
+                    return Err(invalid("`min` exceeds `max`".to_string()));
+                }
+                Ok(InputDomain::Range(min, max))
+            } else {
+                Err(invalid(
+                    "expected `fixed`, `values`, or `min` and `max`".to_string(),
+                ))
+            }
+        }
+        v => Err(invalid(format!("unexpected `{}`", v))),
+    }
+}
+
+/// Parses an input specification, e.g.
+///
+/// ```toml
+/// a = { min = 0, max = 255 }
+/// op = { values = [0, 1, 2] }
+/// nonce = { fixed = "18446744073709551616" }
+/// flag = "boolean"
+/// "in[0]" = { min = 0, max = 15 }
+/// ```
+pub fn parse_input_spec(content: &str) -> Result<InputSpec, String> {
+    let table: toml::Table = toml::from_str(content).map_err(|e| e.to_string())?;
+    let mut spec = InputSpec::default();
+    for (name, value) in &table {
+        spec.domains
+            .insert(name.clone(), parse_domain(name, value)?);
+    }
+    Ok(spec)
+}
+
+/// Loads the input specification of `--input_spec`.
+pub fn load_input_spec(file_path: &str) -> Result<InputSpec, String> {
+    let content = fs::read_to_string(file_path).map_err(|e| e.to_string())?;
+    parse_input_spec(&content)
+}
@@ -0,0 +1,99 @@
+use std::rc::Rc;
+
+use program_structure::file_definition::FileLibrary;
+use serde_json::{json, Value};
+
+use crate::executor::log_sink::LogSink;
+use crate::executor::symbolic_execution::SymbolicExecutor;
+use crate::executor::symbolic_setting::SymbolicExecutorSetting;
+use crate::executor::symbolic_value::{OwnerName, SymbolicLibrary};
+use crate::mutator::division::{gather_statement_metas, statement_location};
+use crate::mutator::utils::{BaseVerificationConfig, CounterExample};
+
+/// A line printed by a `log()` call while replaying a counterexample.
+pub struct LoggedLine {
+    /// The source location of the `log()` statement, e.g. `IsZero (line 12)`.
+    pub location: String,
+    /// The owner-qualified name of the component or function call, e.g. `main.c`.
+    pub owner: String,
+    /// The arguments separated by spaces, as printed by the witness generator of circom.
+    pub message: String,
+}
+
+impl LoggedLine {
+    pub fn lookup_fmt(&self) -> String {
+        format!("{} `{}`: {}", self.location, self.owner, self.message)
+    }
+
+    pub fn to_json(&self) -> Value {
+        json!({
+            "location": self.location,
+            "owner": self.owner,
+            "message": self.message,
+        })
+    }
+}
+
+/// Replays a counterexample with the concrete executor and collects the output of its `log()`
+/// calls.
+///
+/// The inputs of the counterexample are fed to the main template, whose signals are computed
+/// as the witness generator would, so the lines are those that circom prints when generating
+/// the witness of the counterexample. Function calls are not memoized during the replay, so that
+/// every call prints its lines.
+///
+/// # Parameters
+/// - `symbolic_library`: The symbolic library containing the template definitions.
+/// - `setting`: The setting of the concrete execution.
+/// - `base_config`: The verification configuration specifying the target template and its
+///   parameters.
+/// - `counter_example`: The counterexample to replay.
+/// - `file_library`: The file library used to resolve the source locations of the calls.
+///
+/// # Returns
+/// The printed lines, in execution order.
+pub fn replay_logs(
+    symbolic_library: &mut SymbolicLibrary,
+    setting: &SymbolicExecutorSetting,
+    base_config: &BaseVerificationConfig,
+    counter_example: &CounterExample,
+    file_library: &FileLibrary,
+) -> Vec<LoggedLine> {
+    let log_sink = Rc::new(LogSink::default());
+    let mut replay_setting = setting.clone();
+    replay_setting.function_summary_capacity = 0;
+    replay_setting.log_sink = Some(log_sink.clone());
+
+    let mut sexe = SymbolicExecutor::new(symbolic_library, &replay_setting);
+    sexe.cur_state.add_owner(&OwnerName {
+        id: sexe.symbolic_library.name2id["main"],
+        counter: 0,
+        access: None,
+    });
+    sexe.feed_arguments(
+        &base_config.template_param_names,
+        &base_config.template_param_values,
+    );
+    sexe.concrete_execute(
+        &base_config.target_template_name,
+        &counter_example.assignment,
+    );
+
+    let library = &*sexe.symbolic_library;
+    let metas = gather_statement_metas(library);
+    log_sink
+        .take()
+        .into_iter()
+        .map(|record| LoggedLine {
+            location: statement_location(
+                library,
+                &metas,
+                file_library,
+                record.elem_id,
+                record.owner_id,
+            ),
+            owner: record.owner.clone(),
+            message: record.message(),
+        })
+        .collect()
+}
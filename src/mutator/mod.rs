@@ -1,4 +1,28 @@
+pub mod assertion;
+pub mod assign_oracle;
+pub mod baseline;
+pub mod batch_evaluation;
 pub mod brute_force;
+pub mod circom_inspect;
+pub mod circom_validation;
+pub mod clustering;
+pub mod compiled_constraints;
+pub mod concolic;
+pub mod constraint_oracle;
+pub mod constraint_scheduler;
+pub mod corpus;
+pub mod dead_code;
+pub mod detector;
+pub mod differential_testing;
+pub mod division;
+pub mod exploitability;
+pub mod finding_stream;
+pub mod formal_export;
+#[cfg(feature = "gpu")]
+pub mod gpu_evaluation;
+pub mod groth16_poc;
+pub mod input_spec;
+pub mod log_replay;
 pub mod mutation_config;
 pub mod mutation_test;
 pub mod mutation_test_crossover_fn;
@@ -9,5 +33,26 @@ pub mod mutation_test_trace_mutation_fn;
 pub mod mutation_test_trace_selection_fn;
 pub mod mutation_test_update_input_fn;
 pub mod mutation_utils;
+pub mod overflow_audit;
+pub mod path_conditions;
+pub mod portfolio;
+pub mod prime_consistency;
+pub mod provenance;
+pub mod range_checks;
+pub mod repair;
+pub mod search_ranges;
+pub mod severity;
+pub mod shape_mismatch;
+pub mod signal_assignments;
+pub mod signal_explanation;
+pub mod slicing;
+pub mod staged_search;
+pub mod structural_seeds;
+pub mod ternary;
+pub mod test_harness;
+pub mod undetermined_dimension;
 pub mod unused_outputs;
 pub mod utils;
+pub mod violation_density;
+pub mod witness_export;
+pub mod witness_order;
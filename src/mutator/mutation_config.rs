@@ -1,13 +1,51 @@
 use std::fmt;
-use std::fs::File;
+use std::fs::{self, File};
+use std::path::Path;
 use std::str::FromStr;
+use std::time::Instant;
 
 use colored::Colorize;
 use log::info;
 use num_bigint_dig::BigInt;
+use rustc_hash::FxHashMap;
 use serde::{Deserialize, Serialize};
 use serde_with::{serde_as, DisplayFromStr};
 
+use crate::executor::symbolic_value::SymbolicName;
+
+/// Relative weights of the operators used to mutate the values of input signals.
+///
+/// An operator is drawn with probability proportional to its weight, and a weight of zero
+/// disables the operator.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ValueMutationOperatorConfig {
+    /// Draws a fresh value from `random_value_ranges`.
+    pub random: f64,
+    /// Replaces the value with one of `0`, `1`, `p-1`, and `p/2`.
+    pub boundary: f64,
+    /// Flips one bit of the binary representation of the value.
+    pub bit_flip: f64,
+    /// Copies the value of another input signal.
+    pub copy_signal: f64,
+    /// Adds a small non-zero delta in `[-max_small_delta, max_small_delta]`.
+    pub small_delta: f64,
+    pub max_small_delta: u64,
+}
+
+impl Default for ValueMutationOperatorConfig {
+    fn default() -> Self {
+        ValueMutationOperatorConfig {
+            random: 1.0,
+            boundary: 0.0,
+            bit_flip: 0.0,
+            copy_signal: 0.0,
+            small_delta: 0.0,
+            max_small_delta: 8,
+        }
+    }
+}
+
 #[serde_as]
 #[derive(Clone, Serialize, Deserialize)]
 #[serde(default)]
@@ -42,6 +80,27 @@ pub struct MutationConfig {
     pub dissable_runtime_mutation_for_hash_check: bool,
     pub dissable_heuristic_for_invalid_array_subscript: bool,
     pub save_fitness_scores: bool,
+    pub value_mutation_operators: ValueMutationOperatorConfig,
+    /// The number of populations evolved in turn, on the calling thread, by the island model. A
+    /// single island runs the plain genetic search.
+    pub num_islands: usize,
+    /// The number of generations between two migrations.
+    pub migration_interval: usize,
+    /// The number of the fittest traces of an island copied to the next island at a migration.
+    pub num_migrants: usize,
+    /// Seeds the first input population with values derived from the structure of the circuit
+    /// (see `StructuralSeeds`).
+    pub structural_seeding: bool,
+    /// Lets the search choose the values of the signals assigned with `<--` in any component, not
+    /// only those of the inputs of the main template (see `gather_free_witnesses`).
+    pub free_witnesses: bool,
+    /// Inputs found by another search whose honest witness satisfies every constraint. They
+    /// replace the first inputs of the population each time it is updated.
+    #[serde(skip)]
+    pub seed_inputs: Vec<FxHashMap<SymbolicName, BigInt>>,
+    /// The search stops at the first generation starting after this instant.
+    #[serde(skip)]
+    pub deadline: Option<Instant>,
 }
 
 impl Default for MutationConfig {
@@ -83,6 +142,14 @@ impl Default for MutationConfig {
             dissable_runtime_mutation_for_hash_check:false,
             dissable_heuristic_for_invalid_array_subscript:false,
             save_fitness_scores: false,
+            value_mutation_operators: ValueMutationOperatorConfig::default(),
+            num_islands: 1,
+            migration_interval: 10,
+            num_migrants: 2,
+            structural_seeding: true,
+            free_witnesses: false,
+            seed_inputs: Vec::new(),
+            deadline: None,
         }
     }
 }
@@ -106,7 +173,9 @@ impl fmt::Display for MutationConfig {
     ├─ Input Generation Maximum Iteration         : {} 
     ├─ Input Generation Crossover Rate            : {}
     ├─ Input Generation Mutation Rate             : {}
-    └─ Input Generation Singlepoint Mutation Rate : {}",
+    ├─ Input Generation Singlepoint Mutation Rate : {}
+    ├─ Islands (Migration Interval, Migrants)     : {} ({}, {})
+    └─ Value Mutation Operator Weights            : random={}, boundary={}, bit_flip={}, copy_signal={}, small_delta={}",
             self.program_population_size.to_string().bright_yellow(),
             self.input_population_size.to_string().bright_yellow(),
             self.max_generations.to_string().bright_yellow(),
@@ -129,7 +198,15 @@ impl fmt::Display for MutationConfig {
                 .bright_yellow(),
             self.input_generation_singlepoint_mutation_rate
                 .to_string()
-                .bright_yellow()
+                .bright_yellow(),
+            self.num_islands.to_string().bright_yellow(),
+            self.migration_interval.to_string().bright_yellow(),
+            self.num_migrants.to_string().bright_yellow(),
+            self.value_mutation_operators.random.to_string().bright_yellow(),
+            self.value_mutation_operators.boundary.to_string().bright_yellow(),
+            self.value_mutation_operators.bit_flip.to_string().bright_yellow(),
+            self.value_mutation_operators.copy_signal.to_string().bright_yellow(),
+            self.value_mutation_operators.small_delta.to_string().bright_yellow()
         )
     }
 }
@@ -144,3 +221,26 @@ pub fn load_config_from_json(file_path: &str) -> Result<MutationConfig, serde_js
         Ok(MutationConfig::default())
     }
 }
+
+pub fn load_config_from_toml(file_path: &str) -> Result<MutationConfig, toml::de::Error> {
+    match fs::read_to_string(file_path) {
+        Ok(content) => toml::from_str(&content),
+        Err(_) => {
+            info!("Use the default setting for mutation testing");
+            Ok(MutationConfig::default())
+        }
+    }
+}
+
+/// Loads the mutation settings from a TOML file if `file_path` ends with `.toml`, and from a JSON
+/// file otherwise.
+pub fn load_mutation_config(file_path: &str) -> Result<MutationConfig, String> {
+    if Path::new(file_path)
+        .extension()
+        .map_or(false, |ext| ext == "toml")
+    {
+        load_config_from_toml(file_path).map_err(|e| e.to_string())
+    } else {
+        load_config_from_json(file_path).map_err(|e| e.to_string())
+    }
+}
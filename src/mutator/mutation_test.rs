@@ -1,6 +1,6 @@
-use std::collections::HashSet;
 use std::io;
 use std::io::Write;
+use std::time::Instant;
 
 use colored::Colorize;
 use log::info;
@@ -14,22 +14,30 @@ use rustc_hash::{FxHashMap, FxHashSet};
 use crate::executor::symbolic_execution::SymbolicExecutor;
 use crate::executor::symbolic_state::{SymbolicConstraints, SymbolicTrace};
 use crate::executor::symbolic_value::{
-    extract_variables, QuadraticPoly, SymbolicName, SymbolicValue,
+    extract_variables, QuadraticPoly, SymbolicLibrary, SymbolicName, SymbolicValue,
 };
 
 use crate::executor::utils::solve_quadratic_modulus_equation;
+use crate::mutator::corpus::{CorpusCandidate, CorpusEntryKind};
 use crate::mutator::mutation_config::MutationConfig;
+use crate::mutator::mutation_utils::MutationOperatorStats;
+use crate::mutator::structural_seeds::StructuralSeeds;
 use crate::mutator::utils::{
-    evaluate_symbolic_value, gather_potential_zero_division, gather_runtime_mutable_inputs,
-    is_containing_binary_check, BaseVerificationConfig, CounterExample, Direction,
+    evaluate_symbolic_value, gather_free_witnesses, gather_input_domains, gather_input_variables,
+    gather_potential_zero_division, gather_runtime_mutable_inputs, is_containing_binary_check,
+    BaseVerificationConfig, CounterExample, Direction,
 };
 
 pub struct MutationTestResult {
     pub random_seed: u64,
     pub mutation_config: MutationConfig,
     pub counter_example: Option<CounterExample>,
+    pub counter_examples: Vec<CounterExample>,
     pub generation: usize,
     pub fitness_score_log: Vec<BigInt>,
+    pub operator_stats: MutationOperatorStats,
+    /// The inputs worth keeping in the corpus of `--corpus`.
+    pub corpus_candidates: Vec<CorpusCandidate>,
 }
 
 pub type Gene = FxHashMap<usize, SymbolicValue>;
@@ -56,14 +64,23 @@ pub type Gene = FxHashMap<usize, SymbolicValue>;
 /// - `trace_mutation_fn`: A function that applies mutation to a trace.
 /// - `trace_crossover_fn`: A function that combines two parent traces to produce an offspring trace.
 /// - `trace_selection_fn`: A function that selects traces from the population based on their fitness scores.
+/// - `on_counter_example`: Called with each distinct counterexample as soon as it is found, e.g.
+///   to stream it while the search goes on.
 ///
 /// # Returns
 /// A `MutationTestResult` containing:
 /// - `random_seed`: The seed used for the random number generator.
 /// - `mutation_config`: A copy of the mutation configuration.
 /// - `counter_example`: An optional counterexample found during the search.
+/// - `counter_examples`: All distinct counterexamples found during the search, in the order in
+///   which they were found. The search continues until `base_config.max_counterexamples` of them
+///   are found, and `counter_example` is the first one.
 /// - `generation`: The generation in which the counterexample was found, or the maximum number of generations if no solution was found.
 /// - `fitness_score_log`: A log of the best fitness scores across generations.
+/// - `operator_stats`: How often each value mutation operator was applied to the inputs, and how
+///   often an input it produced improved the best fitness score or yielded a counterexample.
+/// - `corpus_candidates`: The inputs of the counterexamples, the inputs that improved the best
+///   fitness score, and, with the coverage-guided input generation, the last input populations.
 ///
 /// # Type Parameters
 /// - `TraceInitializationFn`: A closure or function that initializes the population of traces.
@@ -73,6 +90,7 @@ pub type Gene = FxHashMap<usize, SymbolicValue>;
 /// - `TraceMutationFn`: A closure or function that mutates a trace.
 /// - `TraceCrossoverFn`: A closure or function that performs crossover between two traces.
 /// - `TraceSelectionFn`: A closure or function that selects traces from the population.
+/// - `CounterExampleFn`: A closure that receives each counterexample found.
 ///
 /// # Algorithm
 /// 1. **Initialization**:
@@ -85,9 +103,19 @@ pub type Gene = FxHashMap<usize, SymbolicValue>;
 ///    - Update the input population at regular intervals.
 ///    - Evolve the trace population using mutation, crossover, and selection.
 ///    - Evaluate the fitness of the population.
-///    - If a counterexample is found, return it immediately.
+///    - If a counterexample is found, return it immediately, unless more counterexamples are
+///      requested. In that case, a counterexample whose witness was already found is discarded,
+///      and the trace and input that produced it are penalized so that the search moves on.
+///
+///    - With `mutation_config.num_islands` greater than one, the steps above are applied to each
+///      island, a population of traces and inputs with its own random number generator. Every
+///      `mutation_config.migration_interval` generations, the `mutation_config.num_migrants`
+///      fittest traces of each island replace the least fit traces of the next island of a ring.
+///      The islands are evolved in turn on the calling thread, since the symbolic values and the
+///      executor they are evaluated with cannot be shared across threads.
 ///
 /// 3. **Termination**:
+///    - Stop as soon as the islands together found the requested number of counterexamples.
 ///    - Stop after reaching the maximum number of generations.
 ///    - If no solution is found, return a result indicating failure.
 ///
@@ -102,6 +130,7 @@ pub fn mutation_test_search<
     TraceMutationFn,
     TraceCrossoverFn,
     TraceSelectionFn,
+    CounterExampleFn,
 >(
     sexe: &mut SymbolicExecutor,
     symbolic_trace: &SymbolicTrace,
@@ -115,6 +144,7 @@ pub fn mutation_test_search<
     trace_mutation_fn: TraceMutationFn,
     trace_crossover_fn: TraceCrossoverFn,
     trace_selection_fn: TraceSelectionFn,
+    mut on_counter_example: CounterExampleFn,
 ) -> MutationTestResult
 where
     TraceInitializationFn: Fn(
@@ -132,6 +162,7 @@ where
         &Vec<BigInt>,
         &BaseVerificationConfig,
         &MutationConfig,
+        &mut MutationOperatorStats,
         &mut StdRng,
     ),
     TraceFitnessFn: Fn(
@@ -167,6 +198,7 @@ where
     ),
     TraceCrossoverFn: Fn(&Gene, &Gene, &mut StdRng) -> Gene,
     TraceSelectionFn: for<'a> Fn(&'a [Gene], &[BigInt], &mut StdRng) -> &'a Gene,
+    CounterExampleFn: FnMut(&SymbolicLibrary, &CounterExample),
 {
     let mut mutation_config = base_mutation_config.clone();
 
@@ -177,7 +209,6 @@ where
     } else {
         mutation_config.seed
     };
-    let mut rng = StdRng::seed_from_u64(seed);
 
     // Gather mutable locations
     let mut assign_pos = Vec::new();
@@ -198,19 +229,26 @@ where
     // Gather input variables
     let mut variables = extract_variables(symbolic_trace);
     variables.append(&mut extract_variables(side_constraints));
-    let variables_set: HashSet<SymbolicName> = variables.iter().cloned().collect();
-    let mut unique_variables: Vec<SymbolicName> = variables_set.iter().cloned().collect();
-    unique_variables.sort();
-    let mut input_variables = Vec::new();
-    for v in unique_variables.iter() {
-        if v.owner.len() == 1
-            && sexe.symbolic_library.template_library
-                [&sexe.symbolic_library.name2id[&base_config.target_template_name]]
-                .input_ids
-                .contains(&v.id)
-        {
-            input_variables.push(v.clone());
-        }
+    let mut input_variables =
+        gather_input_variables(sexe.symbolic_library, base_config, &variables);
+
+    let input_domains = gather_input_domains(sexe.symbolic_library, base_config, &input_variables);
+
+    // Values derived from the constraints, assigned to the first inputs of the first population
+    let structural_seeds = if mutation_config.structural_seeding {
+        let seeds = StructuralSeeds::gather(
+            symbolic_trace,
+            side_constraints,
+            sexe.symbolic_library,
+            base_config,
+            &input_variables,
+        );
+        seeds.assignments(&input_variables, mutation_config.input_population_size)
+    } else {
+        Vec::new()
+    };
+    if !structural_seeds.is_empty() {
+        info!("🌱 Structural Seeds: {}", structural_seeds.len());
     }
 
     let dummy_runtime_mutable_positions = FxHashMap::default();
@@ -224,6 +262,23 @@ where
         )
     };
 
+    // A malicious prover controls the whole witness, not only the inputs of the main template.
+    // The signals assigned with `<--` then take values drawn along with the inputs, which the
+    // mutated traces read back instead of computing them.
+    let free_witness_mutations: Gene = if mutation_config.free_witnesses {
+        let free_witnesses = gather_free_witnesses(symbolic_trace);
+        info!("🕵️ Free Witnesses: {}", free_witnesses.len());
+        free_witnesses
+            .into_iter()
+            .map(|(i, name)| {
+                input_variables.push(name.clone());
+                (i, SymbolicValue::Variable(name))
+            })
+            .collect()
+    } else {
+        FxHashMap::default()
+    };
+
     info!(
         "\n⚖️ Constraints Summary:
     ├─ #Trace Constraints : {}
@@ -237,30 +292,56 @@ where
     );
 
     // Initial Pupulation of Mutated Inputs
-    let mut trace_population = trace_initialization_fn(
-        &assign_pos,
-        mutation_config.program_population_size,
-        &symbolic_trace,
-        base_config,
-        &mutation_config,
-        &mut rng,
-    );
-    let mut fitness_scores =
-        vec![-base_config.prime.clone(); mutation_config.program_population_size + 1];
-    let mut fitness_scores_inputs =
-        vec![-base_config.prime.clone(); mutation_config.input_population_size];
-    let mut input_population = Vec::new();
+    let num_islands = mutation_config.num_islands.max(1);
+    let mut islands: Vec<Island> = (0..num_islands)
+        .map(|i| {
+            // Each island draws from its own generator, the first one seeded with `seed`
+            let mut island_rng = StdRng::seed_from_u64(seed.wrapping_add(i as u64));
+            let trace_population = trace_initialization_fn(
+                &assign_pos,
+                mutation_config.program_population_size,
+                &symbolic_trace,
+                base_config,
+                &mutation_config,
+                &mut island_rng,
+            );
+            Island {
+                trace_population: trace_population,
+                fitness_scores: vec![
+                    -base_config.prime.clone();
+                    mutation_config.program_population_size + 1
+                ],
+                fitness_scores_inputs: vec![
+                    -base_config.prime.clone();
+                    mutation_config.input_population_size
+                ],
+                input_population: Vec::new(),
+                rng: island_rng,
+            }
+        })
+        .collect();
     let mut fitness_score_log = if mutation_config.save_fitness_scores {
         Vec::with_capacity(mutation_config.max_generations)
     } else {
         Vec::new()
     };
+    let mut operator_stats = MutationOperatorStats::default();
+    let mut best_score_so_far = -base_config.prime.clone();
+    let mut counter_examples: Vec<CounterExample> = Vec::new();
+    let mut found_witnesses = FxHashSet::default();
+    let mut corpus_candidates = Vec::new();
 
-    println!(
+    outln!(
         "{} {}",
         "🎲 Random Seed:",
         seed.to_string().bold().bright_yellow(),
     );
+    if num_islands > 1 {
+        info!(
+            "🏝️ Islands: {} (migration of {} individuals every {} generations)",
+            num_islands, mutation_config.num_migrants, mutation_config.migration_interval
+        );
+    }
 
     let mut binary_input_mode = false;
     let mut partial_binary_mode = false;
@@ -274,7 +355,16 @@ where
     let potential_zero_div_positions = gather_potential_zero_division(symbolic_trace);
     let mut zero_div_cache = FxHashMap::default();
 
+    let mut num_generations = mutation_config.max_generations;
     for generation in 0..mutation_config.max_generations {
+        if mutation_config
+            .deadline
+            .map_or(false, |d| Instant::now() >= d)
+        {
+            num_generations = generation;
+            break;
+        }
+
         if partial_binary_mode
             && 1 < generation
             && generation
@@ -286,161 +376,382 @@ where
             mutation_config.binary_mode_prob = original_binary_mode_prob;
         }
 
-        // Generate input population for this generation
-        if generation % mutation_config.input_update_interval == 0 {
-            update_input_fn(
-                sexe,
-                &input_variables,
-                &mut input_population,
-                &fitness_scores_inputs,
-                &base_config,
-                &mutation_config,
-                &mut rng,
-            );
-        }
+        let mut generation_best_score: Option<BigInt> = None;
+        for island in islands.iter_mut() {
+            // Generate input population for this generation
+            if generation % mutation_config.input_update_interval == 0 {
+                update_input_fn(
+                    sexe,
+                    &input_variables,
+                    &mut island.input_population,
+                    &island.fitness_scores_inputs,
+                    &base_config,
+                    &mutation_config,
+                    &mut operator_stats,
+                    &mut island.rng,
+                );
+                for (inp, seed_input) in island
+                    .input_population
+                    .iter_mut()
+                    .zip(mutation_config.seed_inputs.iter())
+                {
+                    for var in input_variables.iter() {
+                        if let Some(value) = seed_input.get(var) {
+                            inp.insert(var.clone(), value.clone());
+                        }
+                    }
+                }
+                if generation == 0 {
+                    for (inp, seed_input) in island
+                        .input_population
+                        .iter_mut()
+                        .skip(mutation_config.seed_inputs.len())
+                        .zip(structural_seeds.iter())
+                    {
+                        for (var, value) in seed_input.iter() {
+                            inp.insert(var.clone(), value.clone());
+                        }
+                    }
+                }
+            }
 
-        // Evolve the trace population
-        if !trace_population.is_empty() {
-            trace_population = trace_evolution_fn(
-                &assign_pos,
-                &symbolic_trace,
-                &trace_population,
-                &fitness_scores,
-                base_config,
-                &mutation_config,
-                &mut rng,
-                &trace_mutation_fn,
-                &trace_crossover_fn,
-                &trace_selection_fn,
-            );
-        }
-        trace_population.push(FxHashMap::default());
-
-        // zero-division-pattern
-        if !potential_zero_div_positions.is_empty() {
-            for inp in input_population.iter_mut() {
-                if rng.gen::<f64>() < mutation_config.zero_div_attempt_prob {
-                    zero_div_attempt(
-                        inp,
-                        sexe,
-                        &mut zero_div_cache,
-                        base_config,
-                        &potential_zero_div_positions,
-                        &input_variables.iter().cloned().collect(),
-                        &mut rng,
-                    );
+            // Evolve the trace population
+            if !island.trace_population.is_empty() {
+                island.trace_population = trace_evolution_fn(
+                    &assign_pos,
+                    &symbolic_trace,
+                    &island.trace_population,
+                    &island.fitness_scores,
+                    base_config,
+                    &mutation_config,
+                    &mut island.rng,
+                    &trace_mutation_fn,
+                    &trace_crossover_fn,
+                    &trace_selection_fn,
+                );
+            }
+            island.trace_population.push(FxHashMap::default());
+
+            // zero-division-pattern
+            if !potential_zero_div_positions.is_empty() {
+                for inp in island.input_population.iter_mut() {
+                    if island.rng.gen::<f64>() < mutation_config.zero_div_attempt_prob {
+                        zero_div_attempt(
+                            inp,
+                            sexe,
+                            &mut zero_div_cache,
+                            base_config,
+                            &potential_zero_div_positions,
+                            &input_variables.iter().cloned().collect(),
+                            &mut island.rng,
+                        );
+                    }
                 }
             }
-        }
 
-        // Evaluate the trace population
-        let mut evaluations = Vec::new();
-        let mut is_extincted_due_to_illegal_subscript = true;
-        for individual in &trace_population {
-            let fitness = trace_fitness_fn(
-                sexe,
-                &base_config,
-                &mutation_config,
-                symbolic_trace,
-                side_constraints,
-                if rng.gen::<f64>() < mutation_config.runtime_mutation_rate {
-                    &dummy_runtime_mutable_positions
+            // Restrict the inputs to their declared domains or the domains of their tags
+            if !input_domains.is_empty() {
+                for inp in island.input_population.iter_mut() {
+                    for (var, domain) in input_domains.iter() {
+                        if inp.get(var).map_or(false, |v| !domain.contains(v)) {
+                            inp.insert(var.clone(), domain.sample(&mut island.rng));
+                        }
+                    }
+                }
+            }
+
+            // Evaluate the trace population
+            let mut evaluations = Vec::new();
+            let mut is_extincted_due_to_illegal_subscript = true;
+            for individual in &island.trace_population {
+                let individual_with_free_witnesses;
+                let individual = if free_witness_mutations.is_empty() {
+                    individual
                 } else {
-                    &runtime_mutable_positions
-                },
-                individual,
-                &input_population,
-                &mut fitness_scores_inputs,
-            );
-            if fitness.1.is_zero() {
+                    let mut gene = individual.clone();
+                    gene.extend(free_witness_mutations.clone());
+                    individual_with_free_witnesses = gene;
+                    &individual_with_free_witnesses
+                };
+                let fitness = trace_fitness_fn(
+                    sexe,
+                    &base_config,
+                    &mutation_config,
+                    symbolic_trace,
+                    side_constraints,
+                    if island.rng.gen::<f64>() < mutation_config.runtime_mutation_rate {
+                        &dummy_runtime_mutable_positions
+                    } else {
+                        &runtime_mutable_positions
+                    },
+                    individual,
+                    &island.input_population,
+                    &mut island.fitness_scores_inputs,
+                );
+                if fitness.1.is_zero() {
+                    evaluations.push(fitness);
+                    break;
+                }
+                is_extincted_due_to_illegal_subscript = is_extincted_due_to_illegal_subscript
+                    && fitness.3 == island.input_population.len();
                 evaluations.push(fitness);
-                break;
             }
-            is_extincted_due_to_illegal_subscript =
-                is_extincted_due_to_illegal_subscript && fitness.3 == input_population.len();
-            evaluations.push(fitness);
-        }
 
-        if !binary_input_mode
-            && is_extincted_due_to_illegal_subscript
-            && (!mutation_config.dissable_heuristic_for_invalid_array_subscript)
-        {
-            binary_input_mode = true;
-            let mindim = if sexe.mindim >= std::usize::MAX {
-                1
-            } else {
-                sexe.mindim
-            };
-            mutation_config.random_value_ranges = vec![(BigInt::from(0), BigInt::from(mindim))];
-            mutation_config.random_value_probs = vec![1.0];
-        }
+            if !binary_input_mode
+                && is_extincted_due_to_illegal_subscript
+                && (!mutation_config.dissable_heuristic_for_invalid_array_subscript)
+            {
+                binary_input_mode = true;
+                let mindim = if sexe.mindim >= std::usize::MAX {
+                    1
+                } else {
+                    sexe.mindim
+                };
+                mutation_config.random_value_ranges = vec![(BigInt::from(0), BigInt::from(mindim))];
+                mutation_config.random_value_probs = vec![1.0];
+            }
 
-        let mut evaluation_indices: Vec<usize> = (0..evaluations.len()).collect();
-        evaluation_indices.sort_by(|&i, &j| evaluations[i].1.cmp(&evaluations[j].1));
+            let mut evaluation_indices: Vec<usize> = (0..evaluations.len()).collect();
+            evaluation_indices.sort_by(|&i, &j| evaluations[i].1.cmp(&evaluations[j].1));
+
+            // Pick the best one
+            let best_idx = *evaluation_indices.last().unwrap();
+
+            // Credit the mutation operator that produced the best input when it improves the score
+            if evaluations[best_idx].1 > best_score_so_far {
+                best_score_so_far = evaluations[best_idx].1.clone();
+                operator_stats.record_success(evaluations[best_idx].0);
+                if let Some(input) = island.input_population.get(evaluations[best_idx].0) {
+                    if !best_score_so_far.is_zero() {
+                        corpus_candidates.push(CorpusCandidate {
+                            kind: CorpusEntryKind::NearViolation,
+                            input: input.clone(),
+                            fitness: best_score_so_far.clone(),
+                        });
+                    }
+                }
+            }
 
-        // Pick the best one
-        let best_idx = evaluation_indices.last().unwrap();
+            let is_solution_found = evaluations[best_idx].1.is_zero();
+            if is_solution_found {
+                out!(
+                    "\r\x1b[2K🧬 Generation: {}/{} ({:.3})",
+                    generation,
+                    mutation_config.max_generations,
+                    0
+                );
+                if let Some(ce) = &evaluations[best_idx].2 {
+                    if found_witnesses.insert(ce.witness_key(&base_config.prime)) {
+                        outln!("\n    └─ Solution found in generation {}", generation);
+                        counter_examples.push(ce.clone());
+                        on_counter_example(&*sexe.symbolic_library, ce);
+                        corpus_candidates.push(CorpusCandidate {
+                            kind: CorpusEntryKind::Counterexample,
+                            input: ce.assignment.clone(),
+                            fitness: BigInt::zero(),
+                        });
+                    }
+                }
 
-        if evaluations[*best_idx].1.is_zero() {
-            print!(
-                "\r\x1b[2K🧬 Generation: {}/{} ({:.3})",
-                generation, mutation_config.max_generations, 0
+                // The islands share the counterexamples found, and the search stops as soon as
+                // enough of them are found by any island.
+                if counter_examples.len() >= base_config.max_counterexamples {
+                    break;
+                }
+            }
+
+            // Extract the fitness scores
+            if mutation_config.fitness_function != "const" {
+                island.fitness_scores = evaluations.iter().map(|v| v.1.clone()).collect();
+            }
+
+            // Penalize the trace and the input that produced a counterexample, so that the search
+            // looks for a different one.
+            if is_solution_found {
+                if best_idx < island.fitness_scores.len() {
+                    island.fitness_scores[best_idx] = -base_config.prime.clone();
+                }
+                island.fitness_scores_inputs[evaluations[best_idx].0] = -base_config.prime.clone();
+                island.trace_population[best_idx] = trace_initialization_fn(
+                    &assign_pos,
+                    1,
+                    &symbolic_trace,
+                    base_config,
+                    &mutation_config,
+                    &mut island.rng,
+                )
+                .pop()
+                .unwrap_or_default();
+            }
+
+            let island_best_score = island.fitness_scores[best_idx].clone();
+            if generation_best_score
+                .as_ref()
+                .map_or(true, |s| island_best_score > *s)
+            {
+                generation_best_score = Some(island_best_score);
+            }
+
+            // Reset individuals with poor fitness score
+            let new_trace_population = trace_initialization_fn(
+                &assign_pos,
+                mutation_config.num_eliminated_individuals,
+                &symbolic_trace,
+                base_config,
+                &mutation_config,
+                &mut island.rng,
             );
-            println!("\n    └─ Solution found in generation {}", generation);
+            for (i, j) in evaluation_indices
+                .into_iter()
+                .take(mutation_config.num_eliminated_individuals)
+                .enumerate()
+            {
+                island.trace_population[j] = new_trace_population[i].clone();
+            }
+        }
 
+        if counter_examples.len() >= base_config.max_counterexamples {
+            print_operator_stats(&operator_stats);
+            corpus_candidates.extend(coverage_candidates(&islands, &mutation_config));
             return MutationTestResult {
                 random_seed: seed,
                 mutation_config: mutation_config.clone(),
-                counter_example: evaluations[*best_idx].2.clone(),
+                counter_example: counter_examples.first().cloned(),
+                counter_examples: counter_examples,
                 generation: generation,
                 fitness_score_log: fitness_score_log,
+                operator_stats: operator_stats,
+                corpus_candidates: corpus_candidates,
             };
         }
 
-        // Extract the fitness scores
-        if mutation_config.fitness_function != "const" {
-            fitness_scores = evaluations.iter().map(|v| v.1.clone()).collect();
-        }
-
-        print!(
+        let generation_best_score = generation_best_score.unwrap();
+        out!(
             "\r\x1b[2K🧬 Generation: {}/{} ({:.3})",
-            generation, mutation_config.max_generations, fitness_scores[*best_idx]
+            generation,
+            mutation_config.max_generations,
+            generation_best_score
         );
         io::stdout().flush().unwrap();
 
         if mutation_config.save_fitness_scores {
-            fitness_score_log.push(fitness_scores[*best_idx].clone());
+            fitness_score_log.push(generation_best_score);
         }
 
-        // Reset individuals with poor fitness score
-        let new_trace_population = trace_initialization_fn(
-            &assign_pos,
-            mutation_config.num_eliminated_individuals,
-            &symbolic_trace,
-            base_config,
-            &mutation_config,
-            &mut rng,
-        );
-        for (i, j) in evaluation_indices
-            .into_iter()
-            .take(mutation_config.num_eliminated_individuals)
-            .enumerate()
+        if num_islands > 1
+            && mutation_config.migration_interval > 0
+            && (generation + 1) % mutation_config.migration_interval == 0
         {
-            trace_population[j] = new_trace_population[i].clone();
+            migrate(&mut islands, mutation_config.num_migrants);
         }
     }
 
-    println!(
-        "\n └─ No solution found after {} generations",
-        mutation_config.max_generations
-    );
+    if counter_examples.is_empty() {
+        outln!(
+            "\n └─ No solution found after {} generations",
+            num_generations
+        );
+    } else {
+        outln!(
+            "\n └─ {} distinct solutions found after {} generations",
+            counter_examples.len(),
+            num_generations
+        );
+    }
+    print_operator_stats(&operator_stats);
+    corpus_candidates.extend(coverage_candidates(&islands, &mutation_config));
 
     MutationTestResult {
         random_seed: seed,
         mutation_config: mutation_config.clone(),
-        counter_example: None,
-        generation: mutation_config.max_generations,
+        counter_example: counter_examples.first().cloned(),
+        counter_examples: counter_examples,
+        generation: num_generations,
         fitness_score_log: fitness_score_log,
+        operator_stats: operator_stats,
+        corpus_candidates: corpus_candidates,
+    }
+}
+
+/// A population of the island model, evolved independently of the other islands between two
+/// migrations.
+struct Island {
+    trace_population: Vec<Gene>,
+    fitness_scores: Vec<BigInt>,
+    fitness_scores_inputs: Vec<BigInt>,
+    input_population: Vec<FxHashMap<SymbolicName, BigInt>>,
+    rng: StdRng,
+}
+
+/// Returns the inputs of the last populations when they are generated by maximizing the
+/// coverage, since each of them then covers a part of the circuit that the previous ones do not.
+fn coverage_candidates(
+    islands: &[Island],
+    mutation_config: &MutationConfig,
+) -> Vec<CorpusCandidate> {
+    if mutation_config.input_initialization_method != "coverage" {
+        return Vec::new();
+    }
+    islands
+        .iter()
+        .flat_map(|island| {
+            island
+                .input_population
+                .iter()
+                .zip(island.fitness_scores_inputs.iter())
+                .map(|(input, score)| CorpusCandidate {
+                    kind: CorpusEntryKind::Coverage,
+                    input: input.clone(),
+                    fitness: score.clone(),
+                })
+        })
+        .collect()
+}
+
+/// Sends copies of the `num_migrants` fittest traces of each island to the next island of the
+/// ring, where they replace the least fit traces.
+fn migrate(islands: &mut [Island], num_migrants: usize) {
+    let ranked = |island: &Island| {
+        let n = island
+            .trace_population
+            .len()
+            .min(island.fitness_scores.len());
+        let mut indices: Vec<usize> = (0..n).collect();
+        indices.sort_by(|&i, &j| island.fitness_scores[i].cmp(&island.fitness_scores[j]));
+        indices
+    };
+
+    let migrants: Vec<Vec<(Gene, BigInt)>> = islands
+        .iter()
+        .map(|island| {
+            ranked(island)
+                .into_iter()
+                .rev()
+                .take(num_migrants)
+                .map(|i| {
+                    (
+                        island.trace_population[i].clone(),
+                        island.fitness_scores[i].clone(),
+                    )
+                })
+                .collect()
+        })
+        .collect();
+
+    let num_islands = islands.len();
+    for (i, migrants) in migrants.into_iter().enumerate() {
+        let island = &mut islands[(i + 1) % num_islands];
+        for (j, (gene, score)) in ranked(island).into_iter().zip(migrants) {
+            island.trace_population[j] = gene;
+            island.fitness_scores[j] = score;
+        }
+    }
+}
+
+fn print_operator_stats(operator_stats: &MutationOperatorStats) {
+    if !operator_stats.applied.is_empty() {
+        outln!("{}", "🔧 Value Mutation Operators:".bright_yellow());
+        outln!("{}", operator_stats);
     }
 }
 
@@ -8,8 +8,9 @@ use crate::mutator::mutation_config::MutationConfig;
 use crate::mutator::mutation_utils::apply_trace_mutation;
 use crate::mutator::utils::{
     accumulate_error_of_constraints, count_error_constraints, emulate_symbolic_trace,
-    evaluate_constraints, is_equal_mod, max_error_of_constraints, BaseVerificationConfig,
-    CounterExample, Direction, UnderConstrainedType, VerificationResult,
+    evaluate_constraints, find_output_tag_violation, is_equal_mod, max_error_of_constraints,
+    BaseVerificationConfig, CounterExample, Direction, UnderConstrainedType, VerificationResult,
+    ViolatedConstraint,
 };
 
 /// Evaluates the fitness of a mutated symbolic execution trace by calculating the error score.
@@ -114,8 +115,10 @@ pub fn evaluate_trace_fitness_by_error(
             counter_example = Some(CounterExample {
                 flag: VerificationResult::UnderConstrained(UnderConstrainedType::UnexpectedInput(
                     original_program_failure_pos,
-                    symbolic_trace[original_program_failure_pos]
-                        .lookup_fmt(&sexe.symbolic_library.id2name),
+                    ViolatedConstraint::new(
+                        &symbolic_trace[original_program_failure_pos],
+                        &sexe.symbolic_library.id2name,
+                    ),
                 )),
                 target_output: None,
                 assignment: assignment_for_original.clone(),
@@ -125,6 +128,25 @@ pub fn evaluate_trace_fitness_by_error(
             break;
         }
 
+        // The original program satisfies the side constraints, but a tagged output does not
+        // satisfy its tag.
+        if base_config.check_output_tags && is_original_satisfy_sc {
+            if let Some((target, flag)) = find_output_tag_violation(
+                sexe.symbolic_library,
+                base_config,
+                &assignment_for_original,
+            ) {
+                counter_example = Some(CounterExample {
+                    flag: flag,
+                    target_output: Some(target),
+                    assignment: assignment_for_original.clone(),
+                });
+                max_idx = i;
+                max_score = BigInt::zero();
+                break;
+            }
+        }
+
         // Clone the input assignment for evaluating the mutated trace.
         let mut assignment_for_mutation = inp.clone();
 
@@ -176,8 +198,10 @@ pub fn evaluate_trace_fitness_by_error(
                     flag: VerificationResult::UnderConstrained(
                         UnderConstrainedType::UnexpectedInput(
                             original_program_failure_pos,
-                            symbolic_trace[original_program_failure_pos]
-                                .lookup_fmt(&sexe.symbolic_library.id2name),
+                            ViolatedConstraint::new(
+                                &symbolic_trace[original_program_failure_pos],
+                                &sexe.symbolic_library.id2name,
+                            ),
                         ),
                     ),
                     target_output: None,
@@ -187,6 +211,24 @@ pub fn evaluate_trace_fitness_by_error(
                 max_score = BigInt::zero();
                 break;
             } else {
+                // Verify that tagged outputs satisfy their tags for valid solutions.
+                if base_config.check_output_tags {
+                    if let Some((target, flag)) = find_output_tag_violation(
+                        sexe.symbolic_library,
+                        base_config,
+                        &assignment_for_mutation,
+                    ) {
+                        counter_example = Some(CounterExample {
+                            flag: flag,
+                            target_output: Some(target),
+                            assignment: assignment_for_mutation.clone(),
+                        });
+                        max_idx = i;
+                        max_score = BigInt::zero();
+                        break;
+                    }
+                }
+
                 // Verify consistency of outputs for valid solutions.
                 let mut keys: Vec<_> = assignment_for_original.keys().collect();
                 keys.sort();
@@ -9,7 +9,9 @@ use crate::executor::symbolic_value::{OwnerName, SymbolicName};
 use crate::mutator::mutation_config::MutationConfig;
 use crate::mutator::mutation_test_crossover_fn::random_crossover;
 use crate::mutator::mutation_test_trace_selection_fn::roulette_selection;
-use crate::mutator::mutation_utils::draw_bigint_with_probabilities;
+use crate::mutator::mutation_utils::{
+    draw_bigint_with_probabilities, mutate_input_value, MutationOperatorStats,
+};
 use crate::mutator::utils::BaseVerificationConfig;
 
 /// Updates the input population with randomly generated samples.
@@ -26,6 +28,8 @@ use crate::mutator::utils::BaseVerificationConfig;
 /// - `_base_config`: A reference to the base verification configuration. Not used in this implementation.
 /// - `mutation_config`: The configuration that defines mutation parameters, including population size
 ///   and random value ranges.
+/// - `operator_stats`: The per-operator statistics of value mutations. The provenance of the new
+///   population is reset since no mutation operator is applied.
 /// - `rng`: A mutable reference to the random number generator.
 ///
 /// # Behavior
@@ -39,6 +43,7 @@ pub fn update_input_population_with_random_sampling(
     _inputs_population_score: &Vec<BigInt>,
     _base_config: &BaseVerificationConfig,
     mutation_config: &MutationConfig,
+    operator_stats: &mut MutationOperatorStats,
     rng: &mut StdRng,
 ) {
     let mut new_inputs_population: Vec<_> = (0..mutation_config.input_population_size)
@@ -56,6 +61,7 @@ pub fn update_input_population_with_random_sampling(
         .collect();
     inputs_population.clear();
     inputs_population.append(&mut new_inputs_population);
+    operator_stats.provenance = vec![None; inputs_population.len()];
 }
 
 pub fn update_input_population_with_fitness_score(
@@ -65,6 +71,7 @@ pub fn update_input_population_with_fitness_score(
     inputs_population_score: &Vec<BigInt>,
     base_config: &BaseVerificationConfig,
     mutation_config: &MutationConfig,
+    operator_stats: &mut MutationOperatorStats,
     rng: &mut StdRng,
 ) {
     if inputs_population.is_empty() {
@@ -75,9 +82,11 @@ pub fn update_input_population_with_fitness_score(
             inputs_population_score,
             base_config,
             mutation_config,
+            operator_stats,
             rng,
         );
     }
+    let mut provenance = Vec::with_capacity(mutation_config.input_population_size);
     let mut updated_inputs_population = (0..mutation_config.input_population_size)
         .map(|_| {
             let parent1 = roulette_selection(inputs_population, inputs_population_score, rng);
@@ -87,23 +96,31 @@ pub fn update_input_population_with_fitness_score(
             } else {
                 parent1.clone()
             };
+            let mut last_operator = None;
             let mut keys: Vec<_> = child.keys().cloned().collect();
             keys.sort();
             for k in keys.iter() {
-                //let val = child.get(k).unwrap();
                 if rng.gen::<f64>() < mutation_config.mutation_rate {
-                    //*val = draw_bigint_with_probabilities(&mutation_config, rng).unwrap();
-                    child.insert(
-                        k.clone().clone(),
-                        draw_bigint_with_probabilities(&mutation_config, rng).unwrap(),
+                    let (value, operator) = mutate_input_value(
+                        k,
+                        &child,
+                        input_variables,
+                        &base_config.prime,
+                        mutation_config,
+                        rng,
                     );
+                    child.insert(k.clone(), value);
+                    operator_stats.record_applied(operator);
+                    last_operator = Some(operator);
                 }
             }
+            provenance.push(last_operator);
             child
         })
         .collect::<Vec<_>>();
     inputs_population.clear();
     inputs_population.append(&mut updated_inputs_population);
+    operator_stats.provenance = provenance;
 }
 
 /// Evaluates the coverage achieved by a given set of inputs.
@@ -158,6 +175,7 @@ pub fn evaluate_coverage(
 /// - `base_config`: A reference to the base verification configuration containing execution parameters.
 /// - `mutation_config`: The configuration that defines mutation parameters, including population size,
 ///   mutation rates, and random value ranges.
+/// - `operator_stats`: The per-operator statistics of value mutations.
 /// - `rng`: A mutable reference to the random number generator.
 ///
 /// # Behavior
@@ -174,6 +192,7 @@ pub fn update_input_population_with_coverage_maximization(
     inputs_population_score: &Vec<BigInt>,
     base_config: &BaseVerificationConfig,
     mutation_config: &MutationConfig,
+    operator_stats: &mut MutationOperatorStats,
     rng: &mut StdRng,
 ) {
     sexe.clear_coverage_tracker();
@@ -188,23 +207,28 @@ pub fn update_input_population_with_coverage_maximization(
         inputs_population_score,
         &base_config,
         &mutation_config,
+        operator_stats,
         rng,
     );
 
+    let mut provenance = Vec::new();
     for input in &initial_input_population {
         let new_coverage = evaluate_coverage(sexe, &input, base_config);
         if new_coverage > total_coverage {
             inputs_population.push(input.clone());
+            provenance.push(None);
             total_coverage = new_coverage;
         }
     }
 
     for _ in 0..mutation_config.input_generation_max_iteration {
         let mut new_inputs_population = Vec::new();
+        let mut new_provenance = Vec::new();
 
         // Iterate through the population and attempt mutations
         for input in inputs_population.iter() {
             let mut new_input = input.clone();
+            let mut last_operator = None;
 
             if rng.gen::<f64>() < mutation_config.input_generation_crossover_rate {
                 // Crossover
@@ -215,15 +239,32 @@ pub fn update_input_population_with_coverage_maximization(
                 if rng.gen::<f64>() < mutation_config.input_generation_singlepoint_mutation_rate {
                     // Mutate only one input variable
                     let var = &input_variables[rng.gen_range(0, input_variables.len())];
-                    let mutation = draw_bigint_with_probabilities(&mutation_config, rng).unwrap();
+                    let (mutation, operator) = mutate_input_value(
+                        var,
+                        &new_input,
+                        input_variables,
+                        &base_config.prime,
+                        &mutation_config,
+                        rng,
+                    );
                     new_input.insert(var.clone(), mutation);
+                    operator_stats.record_applied(operator);
+                    last_operator = Some(operator);
                 } else {
                     // Mutate each input variable with a small probability
                     for var in input_variables {
                         if rng.gen::<bool>() {
-                            let mutation =
-                                draw_bigint_with_probabilities(&mutation_config, rng).unwrap();
+                            let (mutation, operator) = mutate_input_value(
+                                var,
+                                &new_input,
+                                input_variables,
+                                &base_config.prime,
+                                &mutation_config,
+                                rng,
+                            );
                             new_input.insert(var.clone(), mutation);
+                            operator_stats.record_applied(operator);
+                            last_operator = Some(operator);
                         }
                     }
                 }
@@ -233,13 +274,16 @@ pub fn update_input_population_with_coverage_maximization(
             let new_coverage = evaluate_coverage(sexe, &new_input, base_config);
             if new_coverage > total_coverage {
                 new_inputs_population.push(new_input);
+                new_provenance.push(last_operator);
                 total_coverage = new_coverage;
             }
         }
         inputs_population.append(&mut new_inputs_population);
+        provenance.append(&mut new_provenance);
 
         if inputs_population.len() > mutation_config.input_population_size {
             break;
         }
     }
+    operator_stats.provenance = provenance;
 }
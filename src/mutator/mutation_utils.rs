@@ -1,17 +1,20 @@
+use std::fmt;
 use std::rc::Rc;
 
 use num_bigint_dig::BigInt;
 use num_bigint_dig::RandBigInt;
-use num_traits::Zero;
+use num_traits::{One, Zero};
 use program_structure::ast::ExpressionInfixOpcode;
 use rand::rngs::StdRng;
 use rand::seq::IteratorRandom;
 use rand::Rng;
 use rustc_hash::FxHashMap;
+use serde::Serialize;
 
 use crate::executor::debug_ast::DebuggableExpressionInfixOpcode;
 use crate::executor::symbolic_state::SymbolicTrace;
-use crate::executor::symbolic_value::SymbolicValue;
+use crate::executor::symbolic_value::{SymbolicName, SymbolicValue};
+use crate::executor::utils::modreduce;
 use crate::mutator::mutation_config::MutationConfig;
 
 /// Draws a random BigInt from specified ranges based on given probabilities.
@@ -57,6 +60,175 @@ pub fn draw_bigint_with_probabilities(
     }
 }
 
+/// Operators used to mutate the value of an input signal.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Serialize)]
+pub enum ValueMutationOperator {
+    Random,
+    Boundary,
+    BitFlip,
+    CopySignal,
+    SmallDelta,
+}
+
+impl ValueMutationOperator {
+    pub const ALL: [ValueMutationOperator; 5] = [
+        ValueMutationOperator::Random,
+        ValueMutationOperator::Boundary,
+        ValueMutationOperator::BitFlip,
+        ValueMutationOperator::CopySignal,
+        ValueMutationOperator::SmallDelta,
+    ];
+
+    fn weight(&self, mutation_config: &MutationConfig) -> f64 {
+        let weights = &mutation_config.value_mutation_operators;
+        match self {
+            ValueMutationOperator::Random => weights.random,
+            ValueMutationOperator::Boundary => weights.boundary,
+            ValueMutationOperator::BitFlip => weights.bit_flip,
+            ValueMutationOperator::CopySignal => weights.copy_signal,
+            ValueMutationOperator::SmallDelta => weights.small_delta,
+        }
+    }
+}
+
+impl fmt::Display for ValueMutationOperator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            ValueMutationOperator::Random => "random",
+            ValueMutationOperator::Boundary => "boundary",
+            ValueMutationOperator::BitFlip => "bit_flip",
+            ValueMutationOperator::CopySignal => "copy_signal",
+            ValueMutationOperator::SmallDelta => "small_delta",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Per-operator statistics of the value mutations applied during mutation testing.
+///
+/// `provenance[i]` records the operator that last mutated the `i`-th individual of the input
+/// population (`None` for freshly sampled individuals), so that the GA loop can credit the
+/// operator when that individual improves the best fitness score or yields a counterexample.
+#[derive(Clone, Default, Serialize)]
+pub struct MutationOperatorStats {
+    pub applied: FxHashMap<ValueMutationOperator, usize>,
+    pub succeeded: FxHashMap<ValueMutationOperator, usize>,
+    #[serde(skip)]
+    pub provenance: Vec<Option<ValueMutationOperator>>,
+}
+
+impl MutationOperatorStats {
+    pub fn record_applied(&mut self, operator: ValueMutationOperator) {
+        *self.applied.entry(operator).or_insert(0) += 1;
+    }
+
+    /// Credits the operator that produced the `idx`-th individual of the input population.
+    pub fn record_success(&mut self, idx: usize) {
+        if let Some(Some(operator)) = self.provenance.get(idx) {
+            *self.succeeded.entry(*operator).or_insert(0) += 1;
+        }
+    }
+}
+
+impl fmt::Display for MutationOperatorStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, " ┌──────────────┬─────────────┬─────────────┐")?;
+        writeln!(f, " │ Operator     │     Applied │   Succeeded │")?;
+        writeln!(f, " ├──────────────┼─────────────┼─────────────┤")?;
+        for operator in ValueMutationOperator::ALL.iter() {
+            writeln!(
+                f,
+                " │ {:<12} │ {:11} │ {:11} │",
+                operator.to_string(),
+                self.applied.get(operator).unwrap_or(&0),
+                self.succeeded.get(operator).unwrap_or(&0)
+            )?;
+        }
+        write!(f, " └──────────────┴─────────────┴─────────────┘")
+    }
+}
+
+/// Draws a new value for the input signal `var` with one of the value mutation operators.
+///
+/// The operator is chosen with probability proportional to its weight in
+/// `mutation_config.value_mutation_operators`. When only the `random` operator is enabled, this
+/// is equivalent to `draw_bigint_with_probabilities`.
+///
+/// # Parameters
+/// - `var`: The input signal to mutate.
+/// - `input`: The current assignment of the input signals.
+/// - `input_variables`: All input signals, used by the copy-from-another-signal operator.
+/// - `prime`: The prime modulus of the finite field.
+/// - `mutation_config`: The mutation configuration.
+/// - `rng`: A mutable reference to the random number generator.
+///
+/// # Returns
+/// The mutated value and the operator that produced it.
+pub fn mutate_input_value(
+    var: &SymbolicName,
+    input: &FxHashMap<SymbolicName, BigInt>,
+    input_variables: &[SymbolicName],
+    prime: &BigInt,
+    mutation_config: &MutationConfig,
+    rng: &mut StdRng,
+) -> (BigInt, ValueMutationOperator) {
+    let total_weight: f64 = ValueMutationOperator::ALL
+        .iter()
+        .map(|op| op.weight(mutation_config))
+        .sum();
+    let mut operator = ValueMutationOperator::Random;
+    if total_weight > ValueMutationOperator::Random.weight(mutation_config) {
+        let mut threshold = rng.gen::<f64>() * total_weight;
+        for op in ValueMutationOperator::ALL.iter() {
+            let weight = op.weight(mutation_config);
+            if weight > 0.0 {
+                operator = *op;
+                if threshold < weight {
+                    break;
+                }
+                threshold -= weight;
+            }
+        }
+    }
+
+    let current = input.get(var).cloned().unwrap_or_else(BigInt::zero);
+    let value = match operator {
+        ValueMutationOperator::Random => draw_bigint_with_probabilities(mutation_config, rng).unwrap(),
+        ValueMutationOperator::Boundary => {
+            let candidates = [
+                BigInt::zero(),
+                BigInt::one(),
+                prime - BigInt::one(),
+                prime / BigInt::from(2),
+            ];
+            candidates[rng.gen_range(0, candidates.len())].clone()
+        }
+        ValueMutationOperator::BitFlip => {
+            let bit = rng.gen_range(0, prime.bits().max(1));
+            modreduce(&(current ^ (BigInt::one() << bit)), prime)
+        }
+        ValueMutationOperator::CopySignal => {
+            let others: Vec<&SymbolicName> =
+                input_variables.iter().filter(|v| *v != var).collect();
+            if others.is_empty() {
+                current
+            } else {
+                let other = others[rng.gen_range(0, others.len())];
+                input.get(other).cloned().unwrap_or(current)
+            }
+        }
+        ValueMutationOperator::SmallDelta => {
+            let max_delta = mutation_config.value_mutation_operators.max_small_delta.max(1) as i64;
+            let mut delta = rng.gen_range(1, max_delta + 1);
+            if rng.gen::<bool>() {
+                delta = -delta;
+            }
+            modreduce(&(current + BigInt::from(delta)), prime)
+        }
+    };
+    (value, operator)
+}
+
 /// Applies trace mutations to a symbolic trace by replacing specific symbolic values.
 ///
 /// # Parameters
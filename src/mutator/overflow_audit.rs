@@ -0,0 +1,219 @@
+use num_bigint_dig::BigInt;
+use num_traits::{One, Signed, ToPrimitive};
+use program_structure::ast::{ExpressionInfixOpcode, ExpressionPrefixOpcode};
+use rustc_hash::{FxHashMap, FxHashSet};
+
+use crate::executor::structural_hash::{Equivalence, StructuralKey};
+use crate::executor::symbolic_value::{SymbolicValue, SymbolicValueRef};
+use crate::executor::utils::modreduce;
+
+/// An integer operation whose operand may leave the range where the arithmetic of the field
+/// agrees with the arithmetic of unbounded integers.
+///
+/// circom reduces every intermediate value modulo `p`, so an operand such as `a * b` that exceeds
+/// `p` has silently wrapped around before `\`, `%`, a shift, or a bitwise operation is applied.
+/// Relational operators additionally interpret values above `p / 2` as negative numbers.
+pub struct OverflowSite {
+    pub expression: String,
+    pub operand: String,
+    /// The number of bits of the upper bound on the magnitude of the operand before reduction.
+    pub bound_bits: usize,
+    pub reason: &'static str,
+}
+
+/// Returns an upper bound on the magnitude of `value` when it is evaluated over the integers,
+/// i.e., without reducing the intermediate values modulo `prime`.
+///
+/// Variables and the results of field operations that are always reduced (e.g. `/` and calls)
+/// are bounded by `prime - 1`, and constants by their distance to zero in the field. The bound
+/// saturates at `saturation` so that powers and shifts stay cheap to compute.
+fn magnitude_bound(value: &SymbolicValue, prime: &BigInt, saturation: &BigInt) -> BigInt {
+    let field_max = prime - BigInt::one();
+    let bound = match value {
+        SymbolicValue::ConstantInt(v) => {
+            let v = modreduce(v, prime);
+            std::cmp::min(prime - &v, v)
+        }
+        SymbolicValue::ConstantBool(_) => BigInt::one(),
+        SymbolicValue::Conditional(_, then_val, else_val) => std::cmp::max(
+            magnitude_bound(then_val, prime, saturation),
+            magnitude_bound(else_val, prime, saturation),
+        ),
+        SymbolicValue::UnaryOp(op, operand) => match &op.0 {
+            ExpressionPrefixOpcode::Sub => magnitude_bound(operand, prime, saturation),
+            ExpressionPrefixOpcode::BoolNot => BigInt::one(),
+            ExpressionPrefixOpcode::Complement => field_max,
+        },
+        SymbolicValue::BinaryOp(lhs, op, rhs) | SymbolicValue::AuxBinaryOp(lhs, op, rhs) => {
+            let lb = magnitude_bound(lhs, prime, saturation);
+            let rb = magnitude_bound(rhs, prime, saturation);
+            match &op.0 {
+                ExpressionInfixOpcode::Add | ExpressionInfixOpcode::Sub => lb + rb,
+                ExpressionInfixOpcode::Mul => lb * rb,
+                ExpressionInfixOpcode::Pow => match constant_exponent(rhs) {
+                    _ if lb <= BigInt::one() => BigInt::one(),
+                    Some(e) => {
+                        let mut bound = BigInt::one();
+                        for _ in 0..e {
+                            bound *= &lb;
+                            if bound >= *saturation {
+                                break;
+                            }
+                        }
+                        bound
+                    }
+                    None => field_max,
+                },
+                ExpressionInfixOpcode::IntDiv | ExpressionInfixOpcode::ShiftR => lb,
+                ExpressionInfixOpcode::Mod => match &**rhs {
+                    SymbolicValue::ConstantInt(c) if c.is_positive() => {
+                        std::cmp::min(lb, c - BigInt::one())
+                    }
+                    _ => lb,
+                },
+                ExpressionInfixOpcode::ShiftL => match constant_exponent(rhs) {
+                    Some(k) if k < saturation.bits() => lb << k,
+                    _ => saturation.clone(),
+                },
+                ExpressionInfixOpcode::BitAnd => std::cmp::min(lb, rb),
+                ExpressionInfixOpcode::BitOr | ExpressionInfixOpcode::BitXor => {
+                    (BigInt::one() << std::cmp::max(lb.bits(), rb.bits())) - BigInt::one()
+                }
+                ExpressionInfixOpcode::Div => field_max,
+                _ => BigInt::one(),
+            }
+        }
+        _ => field_max,
+    };
+    std::cmp::min(bound, saturation.clone())
+}
+
+fn constant_exponent(value: &SymbolicValue) -> Option<usize> {
+    match value {
+        SymbolicValue::ConstantInt(v) => v.to_usize(),
+        _ => None,
+    }
+}
+
+/// The sites are deduplicated as they are printed, i.e., regardless of the counters of the owners.
+const SITE_EQUIVALENCE: Equivalence = Equivalence {
+    ignore_owner_counters: true,
+    ignore_operand_order: false,
+    ignore_owners: false,
+    ignore_array_indices: false,
+};
+
+fn is_intermediate(value: &SymbolicValue) -> bool {
+    matches!(
+        value,
+        SymbolicValue::BinaryOp(..) | SymbolicValue::UnaryOp(..) | SymbolicValue::Conditional(..)
+    )
+}
+
+fn audit_symbolic_value<'a>(
+    value: &'a SymbolicValue,
+    prime: &BigInt,
+    saturation: &BigInt,
+    id2name: &'a FxHashMap<usize, String>,
+    visited: &mut FxHashSet<(StructuralKey<'a>, StructuralKey<'a>)>,
+    sites: &mut Vec<OverflowSite>,
+) {
+    match value {
+        SymbolicValue::BinaryOp(lhs, op, rhs) => {
+            let threshold_and_reason = match &op.0 {
+                ExpressionInfixOpcode::Lesser
+                | ExpressionInfixOpcode::Greater
+                | ExpressionInfixOpcode::LesserEq
+                | ExpressionInfixOpcode::GreaterEq => Some((
+                    (prime - BigInt::one()) / BigInt::from(2),
+                    "values above p/2 are compared as negative numbers",
+                )),
+                ExpressionInfixOpcode::IntDiv
+                | ExpressionInfixOpcode::Mod
+                | ExpressionInfixOpcode::ShiftL
+                | ExpressionInfixOpcode::ShiftR
+                | ExpressionInfixOpcode::BitAnd
+                | ExpressionInfixOpcode::BitOr
+                | ExpressionInfixOpcode::BitXor => Some((
+                    prime - BigInt::one(),
+                    "the operand wraps around p before the integer operation",
+                )),
+                _ => None,
+            };
+            if let Some((threshold, reason)) = threshold_and_reason {
+                for operand in [lhs, rhs] {
+                    if !is_intermediate(operand) {
+                        continue;
+                    }
+                    let bound = magnitude_bound(operand, prime, saturation);
+                    if bound > threshold {
+                        let site = (
+                            StructuralKey::new(operand, id2name, SITE_EQUIVALENCE),
+                            StructuralKey::new(value, id2name, SITE_EQUIVALENCE),
+                        );
+                        if visited.insert(site) {
+                            sites.push(OverflowSite {
+                                expression: value.lookup_fmt(id2name),
+                                operand: operand.lookup_fmt(id2name),
+                                bound_bits: bound.bits(),
+                                reason: reason,
+                            });
+                        }
+                    }
+                }
+            }
+            audit_symbolic_value(lhs, prime, saturation, id2name, visited, sites);
+            audit_symbolic_value(rhs, prime, saturation, id2name, visited, sites);
+        }
+        SymbolicValue::Assign(lhs, rhs, ..)
+        | SymbolicValue::AssignEq(lhs, rhs)
+        | SymbolicValue::AssignCall(lhs, rhs, _) => {
+            audit_symbolic_value(lhs, prime, saturation, id2name, visited, sites);
+            audit_symbolic_value(rhs, prime, saturation, id2name, visited, sites);
+        }
+        SymbolicValue::Conditional(cond, then_val, else_val) => {
+            audit_symbolic_value(cond, prime, saturation, id2name, visited, sites);
+            audit_symbolic_value(then_val, prime, saturation, id2name, visited, sites);
+            audit_symbolic_value(else_val, prime, saturation, id2name, visited, sites);
+        }
+        SymbolicValue::UnaryOp(_, operand) => {
+            audit_symbolic_value(operand, prime, saturation, id2name, visited, sites);
+        }
+        SymbolicValue::Array(elements) | SymbolicValue::Call(_, elements) => {
+            for e in elements {
+                audit_symbolic_value(e, prime, saturation, id2name, visited, sites);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Audits the constraints for integer operations whose semantics in the prime field differ from
+/// their semantics over unbounded integers.
+///
+/// The magnitude of every intermediate expression is bounded as if it were evaluated over the
+/// integers, assuming that each variable may take any value of the field. An operand of an
+/// integer division, a modulo, a shift, or a bitwise operation whose bound reaches `p`, or an
+/// operand of a relational operator whose bound exceeds `p / 2`, is reported, e.g. the `a * b`
+/// of `(a * b) \ c` when `a` and `b` are arbitrary signals.
+///
+/// # Parameters
+/// - `constraints`: The constraints to audit, typically the trace and side constraints.
+/// - `prime`: The prime of the field.
+/// - `id2name`: A map from ids to names used to format the expressions.
+///
+/// # Returns
+/// The reported sites, deduplicated by operand and expression, in the order they are found.
+pub fn audit_integer_overflows(
+    constraints: &[SymbolicValueRef],
+    prime: &BigInt,
+    id2name: &FxHashMap<usize, String>,
+) -> Vec<OverflowSite> {
+    let saturation = prime * prime;
+    let mut visited = FxHashSet::default();
+    let mut sites = Vec::new();
+    for c in constraints {
+        audit_symbolic_value(c, prime, &saturation, id2name, &mut visited, &mut sites);
+    }
+    sites
+}
@@ -0,0 +1,178 @@
+use num_bigint_dig::BigInt;
+use num_traits::Zero;
+use rustc_hash::{FxHashMap, FxHashSet};
+
+use crate::executor::symbolic_value::{
+    extract_variables_from_symbolic_value, SymbolicLibrary, SymbolicName, SymbolicValue,
+    SymbolicValueRef,
+};
+use crate::mutator::utils::{evaluate_symbolic_value, BaseVerificationConfig};
+
+/// The condition of a branch merged by the symbolic execution (`c ? then : else`).
+struct PathCondition {
+    condition: SymbolicValueRef,
+    /// `true` for the then-branch, `false` for the else-branch.
+    taken: bool,
+    variables: FxHashSet<SymbolicName>,
+}
+
+/// The path conditions under which the variables of the constraints matter.
+///
+/// The symbolic execution merges both branches of an `if` on a symbolic condition into guarded
+/// values `c ? then : else`, so a variable that only occurs in the branches whose condition is
+/// falsified by an assignment does not affect whether the assignment satisfies the constraints.
+/// Enumerating its values is then redundant and the search can fix it to a single value.
+pub struct PathConditions {
+    conditions: Vec<PathCondition>,
+    /// The path conditions of each occurrence of a variable, as indices into `conditions`. An
+    /// occurrence outside of any branch has no path condition.
+    occurrences: FxHashMap<SymbolicName, Vec<Vec<usize>>>,
+    /// The inputs and outputs of the main template, which are compared with the concrete
+    /// execution and therefore always matter.
+    main_signals: FxHashSet<usize>,
+}
+
+impl PathConditions {
+    /// Precomputes the path conditions of every occurrence of a variable in the constraints.
+    ///
+    /// # Parameters
+    /// - `symbolic_trace`: The symbolic trace.
+    /// - `side_constraints`: The side constraints.
+    /// - `symbolic_library`: The symbolic library containing the template definitions.
+    /// - `base_config`: The verification configuration specifying the target template.
+    pub fn gather(
+        symbolic_trace: &[SymbolicValueRef],
+        side_constraints: &[SymbolicValueRef],
+        symbolic_library: &SymbolicLibrary,
+        base_config: &BaseVerificationConfig,
+    ) -> Self {
+        let template = &symbolic_library.template_library
+            [&symbolic_library.name2id[&base_config.target_template_name]];
+        let mut path_conditions = PathConditions {
+            conditions: Vec::new(),
+            occurrences: FxHashMap::default(),
+            main_signals: template
+                .input_ids
+                .iter()
+                .chain(template.output_ids.iter())
+                .cloned()
+                .collect(),
+        };
+        let mut path = Vec::new();
+        for constraint in symbolic_trace.iter().chain(side_constraints.iter()) {
+            path_conditions.visit(constraint, &mut path);
+        }
+        path_conditions
+    }
+
+    fn visit(&mut self, value: &SymbolicValue, path: &mut Vec<usize>) {
+        match value {
+            SymbolicValue::Variable(name) => {
+                self.occurrences
+                    .entry(name.clone())
+                    .or_default()
+                    .push(path.clone());
+            }
+            SymbolicValue::Conditional(cond, then_val, else_val) => {
+                self.visit(cond, path);
+                let mut variables = FxHashSet::default();
+                extract_variables_from_symbolic_value(cond, &mut variables);
+                for (branch, taken) in [(then_val, true), (else_val, false)] {
+                    self.conditions.push(PathCondition {
+                        condition: cond.clone(),
+                        taken: taken,
+                        variables: variables.clone(),
+                    });
+                    path.push(self.conditions.len() - 1);
+                    self.visit(branch, path);
+                    path.pop();
+                }
+            }
+            SymbolicValue::Assign(lhs, rhs, _, _)
+            | SymbolicValue::AssignEq(lhs, rhs)
+            | SymbolicValue::AssignTemplParam(lhs, rhs)
+            | SymbolicValue::AssignCall(lhs, rhs, _)
+            | SymbolicValue::BinaryOp(lhs, _, rhs)
+            | SymbolicValue::AuxBinaryOp(lhs, _, rhs)
+            | SymbolicValue::UniformArray(lhs, rhs) => {
+                self.visit(lhs, path);
+                self.visit(rhs, path);
+            }
+            SymbolicValue::UnaryOp(_, expr) => self.visit(expr, path),
+            SymbolicValue::Array(elements) | SymbolicValue::Call(_, elements) => {
+                for elem in elements {
+                    self.visit(elem, path);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Returns `true` if no constraint occurs in a branch.
+    pub fn is_empty(&self) -> bool {
+        self.conditions.is_empty()
+    }
+
+    /// Sorts `variables` so that the variables of the path conditions come first, which lets the
+    /// conditions be decided as early as possible during the enumeration.
+    pub fn order_variables(&self, variables: &mut Vec<SymbolicName>) {
+        let condition_variables: FxHashSet<&SymbolicName> = self
+            .conditions
+            .iter()
+            .flat_map(|c| c.variables.iter())
+            .collect();
+        variables.sort_by_key(|var| !condition_variables.contains(var));
+    }
+
+    /// Returns `true` if every occurrence of `var` lies in a branch whose path condition is
+    /// falsified by the partial `assignment`, i.e., the value of `var` does not matter.
+    ///
+    /// Only the conditions whose variables are all assigned are evaluated.
+    pub fn is_infeasible(
+        &self,
+        var: &SymbolicName,
+        prime: &BigInt,
+        assignment: &FxHashMap<SymbolicName, BigInt>,
+        symbolic_library: &mut SymbolicLibrary,
+    ) -> bool {
+        if self.is_empty() || (var.owner.len() == 1 && self.main_signals.contains(&var.id)) {
+            return false;
+        }
+        let occurrences = match self.occurrences.get(var) {
+            Some(occurrences) => occurrences,
+            None => return false,
+        };
+        occurrences.iter().all(|path| {
+            path.iter().any(|i| {
+                self.is_falsified(&self.conditions[*i], prime, assignment, symbolic_library)
+            })
+        })
+    }
+
+    fn is_falsified(
+        &self,
+        path_condition: &PathCondition,
+        prime: &BigInt,
+        assignment: &FxHashMap<SymbolicName, BigInt>,
+        symbolic_library: &mut SymbolicLibrary,
+    ) -> bool {
+        if !path_condition
+            .variables
+            .iter()
+            .all(|v| assignment.contains_key(v))
+        {
+            return false;
+        }
+        let holds = match evaluate_symbolic_value(
+            prime,
+            &path_condition.condition,
+            assignment,
+            symbolic_library,
+        ) {
+            Some(SymbolicValue::ConstantBool(b)) => b,
+            Some(SymbolicValue::ConstantInt(v)) => !(v % prime).is_zero(),
+            _ => return false,
+        };
+        holds != path_condition.taken
+    }
+}
@@ -0,0 +1,336 @@
+use std::time::{Duration, Instant};
+
+use colored::Colorize;
+use num_bigint_dig::BigInt;
+use rustc_hash::FxHashMap;
+use serde_json::{json, Value};
+
+use crate::executor::symbolic_execution::SymbolicExecutor;
+use crate::executor::symbolic_value::{extract_variables, SymbolicName, SymbolicValueRef};
+use crate::mutator::assign_oracle::{
+    assignment_oracle_search_until, pair_assignments_with_constraints,
+};
+use crate::mutator::brute_force::brute_force_search;
+use crate::mutator::search_ranges::SearchRanges;
+use crate::mutator::utils::{
+    gather_input_domains, gather_input_variables, BaseVerificationConfig, CounterExample,
+};
+use crate::stats::symbolic_stats::polynomial_degree;
+
+/// Maximum number of assignments enumerated by a brute-force stage. Larger enumerations cannot
+/// be interrupted at the end of their time slice and are left to the other strategies.
+const MAX_EXHAUSTIVE_ASSIGNMENTS: usize = 1 << 16;
+
+/// The properties of the constraint system that decide the schedule of `--search_mode auto`.
+pub struct ConstraintFeatures {
+    /// The inputs of the main template appearing in the constraints.
+    pub num_inputs: usize,
+    /// The inputs restricted to a finite domain by their tags.
+    pub num_finite_inputs: usize,
+    /// The side constraints whose degree exceeds one.
+    pub num_nonlinear_constraints: usize,
+    pub num_side_constraints: usize,
+    /// The signals assigned with `<--` and checked by at least one side constraint.
+    pub num_checked_assignments: usize,
+    /// The number of assignments enumerated by `--search_mode quick`, if it fits in `usize`.
+    pub quick_assignments: Option<usize>,
+    /// The number of assignments enumerated by `--search_mode heuristics`, if it fits in `usize`.
+    pub heuristics_assignments: Option<usize>,
+}
+
+impl ConstraintFeatures {
+    pub fn to_json(&self) -> Value {
+        json!({
+            "num_inputs": self.num_inputs,
+            "num_finite_inputs": self.num_finite_inputs,
+            "num_nonlinear_constraints": self.num_nonlinear_constraints,
+            "num_side_constraints": self.num_side_constraints,
+            "num_checked_assignments": self.num_checked_assignments,
+            "quick_assignments": self.quick_assignments,
+            "heuristics_assignments": self.heuristics_assignments,
+        })
+    }
+}
+
+/// Inspects the constraint system searched by `--search_mode auto`.
+///
+/// # Parameters
+/// - `sexe`: The symbolic executor after the execution of the main template.
+/// - `symbolic_trace`: A vector of constraints representing the program trace.
+/// - `side_constraints`: A vector of additional constraints for validation.
+/// - `base_config`: The verification base_configs.
+pub fn extract_features(
+    sexe: &SymbolicExecutor,
+    symbolic_trace: &Vec<SymbolicValueRef>,
+    side_constraints: &Vec<SymbolicValueRef>,
+    base_config: &BaseVerificationConfig,
+) -> ConstraintFeatures {
+    let mut constraints = symbolic_trace.clone();
+    constraints.extend(side_constraints.iter().cloned());
+    let variables = extract_variables(&constraints);
+    let symbolic_library = &sexe.symbolic_library;
+    let inputs = gather_input_variables(symbolic_library, base_config, &variables);
+    let domains = gather_input_domains(symbolic_library, base_config, &variables);
+    let ranges = SearchRanges::infer(sexe, symbolic_trace, side_constraints, base_config);
+
+    // The brute-force search enumerates the domain of each tagged input, and otherwise the
+    // values scheduled within the range of the variable (without its structural seeds, which
+    // only add a few values).
+    let count_assignments = |quick_mode: bool| {
+        variables
+            .iter()
+            .try_fold(1_usize, |total, var| match domains.get(var) {
+                Some(domain) => total.checked_mul(domain.candidates().len()),
+                None => total.checked_mul(ranges.schedule(var, &[], quick_mode).len()),
+            })
+    };
+
+    ConstraintFeatures {
+        num_inputs: inputs.len(),
+        num_finite_inputs: inputs.iter().filter(|v| domains.contains_key(v)).count(),
+        num_nonlinear_constraints: side_constraints
+            .iter()
+            .filter(|c| polynomial_degree(c) > 1)
+            .count(),
+        num_side_constraints: side_constraints.len(),
+        num_checked_assignments: pair_assignments_with_constraints(
+            symbolic_trace,
+            side_constraints,
+        )
+        .iter()
+        .filter(|pair| !pair.constraints.is_empty())
+        .count(),
+        quick_assignments: count_assignments(true),
+        heuristics_assignments: count_assignments(false),
+    }
+}
+
+/// A strategy run by `--search_mode auto`.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Strategy {
+    Quick,
+    Heuristics,
+    Assign,
+    Ga,
+}
+
+impl Strategy {
+    /// The value of `--search_mode` running the strategy alone.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Strategy::Quick => "quick",
+            Strategy::Heuristics => "heuristics",
+            Strategy::Assign => "assign",
+            Strategy::Ga => "ga",
+        }
+    }
+}
+
+/// A strategy with the fraction of the time budget allotted to it.
+pub struct TimeSlice {
+    pub strategy: Strategy,
+    pub share: f64,
+}
+
+/// Allocates the time budget of `--search_mode auto` across the strategies.
+///
+/// The brute-force searches run first when their enumeration is small enough to finish, since
+/// they are exhaustive over their candidates. The assignment oracle follows if some signal is
+/// assigned with `<--` and checked by a side constraint, with a larger share when the checks are
+/// nonlinear, as the weak checks it looks for are typically nonlinear. The genetic search gets
+/// the rest of the budget. Time left over by a strategy is passed on to the next ones.
+pub fn plan_time_slices(features: &ConstraintFeatures) -> Vec<TimeSlice> {
+    let mut slices = Vec::new();
+    let is_small = |n: Option<usize>| n.map_or(false, |n| n <= MAX_EXHAUSTIVE_ASSIGNMENTS);
+    if is_small(features.quick_assignments) {
+        slices.push(TimeSlice {
+            strategy: Strategy::Quick,
+            share: 0.1,
+        });
+    }
+    if is_small(features.heuristics_assignments) {
+        slices.push(TimeSlice {
+            strategy: Strategy::Heuristics,
+            share: 0.1,
+        });
+    }
+    if features.num_checked_assignments > 0 {
+        let is_mostly_nonlinear =
+            2 * features.num_nonlinear_constraints > features.num_side_constraints;
+        slices.push(TimeSlice {
+            strategy: Strategy::Assign,
+            share: if is_mostly_nonlinear { 0.4 } else { 0.2 },
+        });
+    }
+    let used: f64 = slices.iter().map(|s| s.share).sum();
+    slices.push(TimeSlice {
+        strategy: Strategy::Ga,
+        share: 1.0 - used,
+    });
+    slices
+}
+
+/// How a strategy fared within its time slice.
+pub struct SliceReport {
+    pub strategy: Strategy,
+    pub budget: Duration,
+    pub elapsed: Duration,
+    pub found: bool,
+}
+
+pub struct PortfolioResult {
+    pub features: ConstraintFeatures,
+    pub slices: Vec<SliceReport>,
+    /// The strategy that found `counter_example`.
+    pub winner: Option<Strategy>,
+    pub counter_example: Option<CounterExample>,
+    /// The number of honest inputs shared between the strategies.
+    pub num_shared_inputs: usize,
+}
+
+impl PortfolioResult {
+    pub fn to_json(&self) -> Value {
+        json!({
+            "features": self.features.to_json(),
+            "slices": self.slices.iter().map(|s| json!({
+                "strategy": s.strategy.name(),
+                "budget_secs": s.budget.as_secs_f64(),
+                "elapsed_secs": s.elapsed.as_secs_f64(),
+                "found": s.found,
+            })).collect::<Vec<_>>(),
+            "winner": self.winner.map(|s| s.name()),
+            "num_shared_inputs": self.num_shared_inputs,
+        })
+    }
+}
+
+/// Runs the strategies planned by `plan_time_slices` in turn until one finds a counterexample
+/// or the time budget is exhausted.
+///
+/// The inputs whose honest witness satisfies every constraint, found by the assignment oracle,
+/// are partial witnesses of a counterexample: the genetic search seeds its input population with
+/// them, so that its mutated traces are tried first on inputs known to reach every constraint.
+///
+/// # Parameters
+/// - `sexe`: A mutable reference to the concrete executor.
+/// - `symbolic_trace`: A vector of constraints representing the program trace.
+/// - `side_constraints`: A vector of additional constraints for validation.
+/// - `base_config`: The verification base_configs.
+/// - `time_budget`: The total time allotted to the search.
+/// - `max_iterations`: The maximum number of inputs executed by the assignment oracle.
+/// - `seed`: The seed of the random number generator.
+/// - `ga`: Runs the genetic search, seeded with the shared inputs, until the given deadline.
+///
+/// # Returns
+/// A `PortfolioResult` with the counterexample, the strategy that found it, and the time spent
+/// by each strategy.
+pub fn portfolio_search<F>(
+    sexe: &mut SymbolicExecutor,
+    symbolic_trace: &Vec<SymbolicValueRef>,
+    side_constraints: &Vec<SymbolicValueRef>,
+    base_config: &BaseVerificationConfig,
+    time_budget: Duration,
+    max_iterations: usize,
+    seed: u64,
+    mut ga: F,
+) -> PortfolioResult
+where
+    F: FnMut(
+        &mut SymbolicExecutor,
+        &[FxHashMap<SymbolicName, BigInt>],
+        Instant,
+    ) -> Option<CounterExample>,
+{
+    let features = extract_features(sexe, symbolic_trace, side_constraints, base_config);
+    let plan = plan_time_slices(&features);
+
+    outln!(
+        "{} {}",
+        "🧭 Portfolio:".green(),
+        plan.iter()
+            .map(|s| format!("{} ({:.0}%)", s.strategy.name(), s.share * 100.0))
+            .collect::<Vec<_>>()
+            .join(" → ")
+    );
+
+    let start = Instant::now();
+    let mut allotted = 0.0;
+    let mut shared_inputs = Vec::new();
+    let mut slices = Vec::new();
+    let mut winner = None;
+    let mut counter_example = None;
+    for slice in &plan {
+        allotted += slice.share;
+        let deadline = start + time_budget.mul_f64(allotted.min(1.0));
+        let slice_start = Instant::now();
+        if slice_start >= deadline {
+            continue;
+        }
+        outln!(
+            "{} {}",
+            "🧭 Strategy:".green(),
+            slice.strategy.name().bright_yellow()
+        );
+
+        let found = match slice.strategy {
+            Strategy::Quick | Strategy::Heuristics => {
+                let mut config = base_config.clone();
+                config.quick_mode = slice.strategy == Strategy::Quick;
+                config.heuristics_mode = slice.strategy == Strategy::Heuristics;
+                brute_force_search(sexe, symbolic_trace, side_constraints, &config)
+            }
+            Strategy::Assign => assignment_oracle_search_until(
+                sexe,
+                symbolic_trace,
+                side_constraints,
+                base_config,
+                max_iterations,
+                seed,
+                Some(deadline),
+                &mut shared_inputs,
+            ),
+            Strategy::Ga => ga(sexe, &shared_inputs, deadline),
+        };
+
+        slices.push(SliceReport {
+            strategy: slice.strategy,
+            budget: deadline - slice_start,
+            elapsed: slice_start.elapsed(),
+            found: found.is_some(),
+        });
+        if found.is_some() {
+            winner = Some(slice.strategy);
+            counter_example = found;
+            break;
+        }
+    }
+
+    PortfolioResult {
+        features: features,
+        slices: slices,
+        winner: winner,
+        counter_example: counter_example,
+        num_shared_inputs: shared_inputs.len(),
+    }
+}
+
+/// Prints the time spent by each strategy of `--search_mode auto` and the one that found the
+/// counterexample.
+pub fn print_portfolio_result(result: &PortfolioResult) {
+    outln!("{}", "🧭 Portfolio Summary:".green());
+    for slice in &result.slices {
+        outln!(
+            "  ├─ {:<10} : {:.2}s / {:.2}s{}",
+            slice.strategy.name(),
+            slice.elapsed.as_secs_f64(),
+            slice.budget.as_secs_f64(),
+            if slice.found { " (found)" } else { "" }
+        );
+    }
+    outln!(
+        "  └─ Found by   : {}",
+        result
+            .winner
+            .map_or("none".normal(), |s| s.name().bright_yellow())
+    );
+}
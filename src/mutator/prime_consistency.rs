@@ -0,0 +1,266 @@
+use std::rc::Rc;
+use std::str::FromStr;
+
+use num_bigint_dig::BigInt;
+use num_traits::{One, Signed};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use rustc_hash::{FxHashMap, FxHashSet};
+
+use crate::executor::symbolic_value::{
+    extract_variables_from_symbolic_value, val_for_relational_operators, SymbolicLibrary,
+    SymbolicName, SymbolicValue, SymbolicValueRef,
+};
+use crate::executor::utils::modreduce;
+use crate::mutator::utils::{
+    emulate_symbolic_trace, evaluate_symbolic_value, gather_input_domains, gather_input_variables,
+    BaseVerificationConfig,
+};
+
+/// The magnitude of the small values drawn for the unrestricted inputs.
+const SMALL_INPUT_BOUND: i64 = 16;
+
+/// Returns the prime of the curve `name` accepted by `--prime` (e.g. `goldilocks`), or `name`
+/// itself parsed as a decimal prime.
+pub fn prime_modulus(name: &str) -> Option<BigInt> {
+    let decimal = match name {
+        "bn128" => "21888242871839275222246405745257275088548364400416034343698204186575808495617",
+        "bls12381" => {
+            "52435875175126190479447740508185965837690552500527637822603658699938581184513"
+        }
+        "goldilocks" => "18446744069414584321",
+        "grumpkin" => {
+            "21888242871839275222246405745257275088696311157297823662689037894645226208583"
+        }
+        "pallas" => "28948022309329048855892746252171976963363056481941560715954676764349967630337",
+        "vesta" => "28948022309329048855892746252171976963363056481941647379679742748393362948097",
+        "secq256r1" => {
+            "115792089210356248762697446949407573530086143415290314195533631308867097853951"
+        }
+        _ => name,
+    };
+    BigInt::from_str(decimal)
+        .ok()
+        .filter(|p| *p > BigInt::one())
+}
+
+/// A constraint whose truth value on a small witness depends on the prime of the field.
+pub struct PrimeDependentConstraint {
+    pub constraint: String,
+    /// `true` for a side constraint (`===`), `false` for a constraint of the trace.
+    pub is_side_constraint: bool,
+    /// Whether the constraint holds over the base prime on `witness`.
+    pub holds_under_base: bool,
+    /// The witness, as `name = value` with the values as signed integers.
+    pub witness: String,
+}
+
+/// Rewrites the constants of `value`, which are reduced modulo `from`, into the field of `to` by
+/// way of their signed representatives, so that e.g. `-1` stays `-1`.
+fn port_constants(value: &SymbolicValue, from: &BigInt, to: &BigInt) -> SymbolicValue {
+    let port = |v: &SymbolicValueRef| Rc::new(port_constants(v, from, to));
+    match value {
+        SymbolicValue::ConstantInt(v) => SymbolicValue::ConstantInt(modreduce(
+            &val_for_relational_operators(&modreduce(v, from), from),
+            to,
+        )),
+        SymbolicValue::Assign(lhs, rhs, is_safe, _) => {
+            SymbolicValue::Assign(port(lhs), port(rhs), *is_safe, None)
+        }
+        SymbolicValue::AssignEq(lhs, rhs) => SymbolicValue::AssignEq(port(lhs), port(rhs)),
+        SymbolicValue::AssignTemplParam(lhs, rhs) => {
+            SymbolicValue::AssignTemplParam(port(lhs), port(rhs))
+        }
+        SymbolicValue::AssignCall(lhs, rhs, is_mutable) => {
+            SymbolicValue::AssignCall(port(lhs), port(rhs), *is_mutable)
+        }
+        SymbolicValue::BinaryOp(lhs, op, rhs) => {
+            SymbolicValue::BinaryOp(port(lhs), op.clone(), port(rhs))
+        }
+        SymbolicValue::AuxBinaryOp(lhs, op, rhs) => {
+            SymbolicValue::AuxBinaryOp(port(lhs), op.clone(), port(rhs))
+        }
+        SymbolicValue::Conditional(cond, then_val, else_val) => {
+            SymbolicValue::Conditional(port(cond), port(then_val), port(else_val))
+        }
+        SymbolicValue::UnaryOp(op, expr) => SymbolicValue::UnaryOp(op.clone(), port(expr)),
+        SymbolicValue::Array(elements) => SymbolicValue::Array(elements.iter().map(port).collect()),
+        SymbolicValue::UniformArray(elem, counts) => {
+            SymbolicValue::UniformArray(port(elem), port(counts))
+        }
+        SymbolicValue::Call(id, args) => SymbolicValue::Call(*id, args.iter().map(port).collect()),
+        _ => value.clone(),
+    }
+}
+
+/// Evaluates `constraint` to its truth value, or `None` if it cannot be evaluated.
+fn truth_value(
+    prime: &BigInt,
+    constraint: &SymbolicValue,
+    assignment: &FxHashMap<SymbolicName, BigInt>,
+    symbolic_library: &mut SymbolicLibrary,
+) -> Option<bool> {
+    match evaluate_symbolic_value(prime, constraint, assignment, symbolic_library)? {
+        SymbolicValue::ConstantBool(b) => Some(b),
+        _ => None,
+    }
+}
+
+/// Executes the constraints under the base prime and under `other_prime`, and reports the
+/// constraints whose truth value on a small witness differs between the two fields.
+///
+/// Small inputs (within `±SMALL_INPUT_BOUND`, or drawn from their domains) are run through the
+/// trace semantics over each prime, and every constraint is evaluated over both primes on the
+/// resulting witness. A witness is carried over to the other field by the signed representatives
+/// of its values. Only the constraints whose variables all have at most half the bits of the
+/// smaller prime are compared, since a product of two such values does not wrap around in either
+/// field, whereas e.g. an inverse is a different integer in each field. A difference therefore
+/// points to field-size-dependent logic, such as a constant or a bound that exceeds the smaller
+/// prime, a comparison that relies on the signed interpretation of values, or a shift, integer
+/// division, or bitwise operation on a value that wraps around in only one of the fields.
+///
+/// # Parameters
+/// - `symbolic_trace`: The symbolic trace, computed over the base prime.
+/// - `side_constraints`: The side constraints, computed over the base prime.
+/// - `symbolic_library`: The symbolic library containing the template definitions.
+/// - `base_config`: The verification configuration specifying the target template and the base
+///   prime.
+/// - `other_prime`: The prime of the field the circuit is compared against.
+/// - `num_samples`: The number of small inputs drawn.
+/// - `seed`: The seed of the random number generator.
+///
+/// # Returns
+/// The prime-dependent constraints, each with the first witness on which it differs.
+pub fn check_prime_consistency(
+    symbolic_trace: &[SymbolicValueRef],
+    side_constraints: &[SymbolicValueRef],
+    symbolic_library: &mut SymbolicLibrary,
+    base_config: &BaseVerificationConfig,
+    other_prime: &BigInt,
+    num_samples: usize,
+    seed: u64,
+) -> Vec<PrimeDependentConstraint> {
+    let base_prime = &base_config.prime;
+    let constraints: Vec<(bool, SymbolicValueRef)> = symbolic_trace
+        .iter()
+        .map(|c| (false, c.clone()))
+        .chain(side_constraints.iter().map(|c| (true, c.clone())))
+        .collect();
+    let ported: Vec<SymbolicValueRef> = constraints
+        .iter()
+        .map(|(_, c)| Rc::new(port_constants(c, base_prime, other_prime)))
+        .collect();
+    let ported_trace = &ported[..symbolic_trace.len()];
+    let constraint_variables: Vec<FxHashSet<SymbolicName>> = constraints
+        .iter()
+        .map(|(_, c)| {
+            let mut variables = FxHashSet::default();
+            extract_variables_from_symbolic_value(c, &mut variables);
+            variables
+        })
+        .collect();
+
+    let mut variables: FxHashSet<SymbolicName> = FxHashSet::default();
+    for vars in &constraint_variables {
+        variables.extend(vars.iter().cloned());
+    }
+    let variables: Vec<SymbolicName> = variables.into_iter().collect();
+    let inputs = gather_input_variables(symbolic_library, base_config, &variables);
+    let domains = gather_input_domains(symbolic_library, base_config, &inputs);
+    let small_bits = std::cmp::min(base_prime.bits(), other_prime.bits()) / 2;
+
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut reported = FxHashSet::default();
+    let mut prime_dependent_constraints = Vec::new();
+    for _ in 0..num_samples {
+        let inputs: FxHashMap<SymbolicName, BigInt> = inputs
+            .iter()
+            .map(|name| {
+                let value = match domains.get(name) {
+                    Some(domain) => domain.sample(&mut rng),
+                    None => BigInt::from(rng.gen_range(-SMALL_INPUT_BOUND, SMALL_INPUT_BOUND + 1)),
+                };
+                (name.clone(), value)
+            })
+            .collect();
+
+        for (prime, trace) in [(base_prime, symbolic_trace), (other_prime, ported_trace)] {
+            let mut witness: FxHashMap<SymbolicName, BigInt> = inputs
+                .iter()
+                .map(|(name, value)| (name.clone(), modreduce(value, prime)))
+                .collect();
+            match emulate_symbolic_trace(
+                prime,
+                trace,
+                &FxHashMap::default(),
+                &mut witness,
+                symbolic_library,
+            ) {
+                Some((true, _)) => {}
+                _ => continue,
+            }
+            let signed: FxHashMap<SymbolicName, BigInt> = witness
+                .iter()
+                .map(|(name, value)| {
+                    (
+                        name.clone(),
+                        val_for_relational_operators(&modreduce(value, prime), prime),
+                    )
+                })
+                .collect();
+            let in_base: FxHashMap<SymbolicName, BigInt> = signed
+                .iter()
+                .map(|(name, value)| (name.clone(), modreduce(value, base_prime)))
+                .collect();
+            let in_other: FxHashMap<SymbolicName, BigInt> = signed
+                .iter()
+                .map(|(name, value)| (name.clone(), modreduce(value, other_prime)))
+                .collect();
+
+            for (i, (is_side_constraint, constraint)) in constraints.iter().enumerate() {
+                if reported.contains(&i)
+                    || !constraint_variables[i].iter().all(|v| {
+                        signed
+                            .get(v)
+                            .map_or(false, |value| value.abs().bits() <= small_bits)
+                    })
+                {
+                    continue;
+                }
+                let holds_under_base =
+                    match truth_value(base_prime, constraint, &in_base, symbolic_library) {
+                        Some(b) => b,
+                        None => continue,
+                    };
+                let holds_under_other =
+                    match truth_value(other_prime, &ported[i], &in_other, symbolic_library) {
+                        Some(b) => b,
+                        None => continue,
+                    };
+                if holds_under_base == holds_under_other {
+                    continue;
+                }
+
+                reported.insert(i);
+                let mut assigned: Vec<(&SymbolicName, &BigInt)> = constraint_variables[i]
+                    .iter()
+                    .map(|v| (v, &signed[v]))
+                    .collect();
+                assigned.sort();
+                prime_dependent_constraints.push(PrimeDependentConstraint {
+                    constraint: constraint.lookup_fmt(&symbolic_library.id2name),
+                    is_side_constraint: *is_side_constraint,
+                    holds_under_base: holds_under_base,
+                    witness: assigned
+                        .iter()
+                        .map(|(v, value)| {
+                            format!("{} = {}", v.lookup_fmt(&symbolic_library.id2name), value)
+                        })
+                        .collect::<Vec<_>>()
+                        .join(", "),
+                });
+            }
+        }
+    }
+    prime_dependent_constraints
+}
@@ -0,0 +1,103 @@
+use program_structure::file_definition::FileLibrary;
+
+use crate::executor::symbolic_execution::SymbolicExecutor;
+use crate::executor::symbolic_value::SymbolicValueRef;
+use crate::mutator::division::{gather_statement_metas, statement_location};
+
+/// The statement that produced a trace or side constraint, as printed by `zkfuzz why`.
+pub struct ConstraintOrigin {
+    pub constraint: SymbolicValueRef,
+    /// The components and function calls within which the statement was executed, outermost
+    /// first, e.g. `main.c[0].lt`.
+    pub owner: String,
+    /// The source location of the statement, e.g. `IsZero (line 12)`, or `None` if the
+    /// constraint was not produced by a statement of the circuit (e.g. a template argument of
+    /// the main component or a constraint added by the semantics of a template).
+    pub location: Option<String>,
+    /// The source code of the statement, if its file is known.
+    pub snippet: Option<String>,
+    /// The location of the `assert` within a function that produced the constraint, if any, in
+    /// which case the statement is the call site of the function.
+    pub assertion: Option<String>,
+}
+
+/// Resolves the statement that produced a constraint from the provenance recorded during
+/// symbolic execution.
+///
+/// Since the components and functions are inlined into the trace, the same statement produces
+/// a constraint for every instance of its template, and the owner chain tells them apart.
+///
+/// # Parameters
+/// - `sexe`: The symbolic executor after the execution of the main template.
+/// - `file_library`: The file library used to resolve the source of the statement.
+/// - `index`: The index of the constraint, in the order of `zkfuzz show-constraints`.
+/// - `side`: Whether `index` refers to the side constraints instead of the trace constraints.
+///
+/// # Returns
+/// The origin of the constraint, or `None` if `index` is out of range.
+pub fn explain_constraint(
+    sexe: &SymbolicExecutor,
+    file_library: &FileLibrary,
+    index: usize,
+    side: bool,
+) -> Option<ConstraintOrigin> {
+    let (constraints, provenance) = if side {
+        (
+            &sexe.cur_state.side_constraints,
+            &sexe.cur_state.side_constraint_provenance,
+        )
+    } else {
+        (
+            &sexe.cur_state.symbolic_trace,
+            &sexe.cur_state.trace_provenance,
+        )
+    };
+    let constraint = constraints.get(index)?;
+    let provenance = provenance.get(index)?;
+    let owner = provenance
+        .get_owner_stack(&sexe.symbolic_library.id2name)
+        .join(".");
+    if provenance.elem_id == usize::MAX {
+        return Some(ConstraintOrigin {
+            constraint: constraint.clone(),
+            owner: owner,
+            location: None,
+            snippet: None,
+            assertion: None,
+        });
+    }
+
+    let metas = gather_statement_metas(sexe.symbolic_library);
+    let snippet = metas.get(&provenance.elem_id).and_then(|meta| {
+        let file = file_library.to_storage().get(meta.file_id?)?;
+        file.source()
+            .get(meta.start..meta.end)
+            .map(|source| source.to_string())
+    });
+    let assertion = sexe
+        .function_assertions
+        .iter()
+        .find(|assertion| assertion.call_site == *provenance && assertion.condition == *constraint)
+        .map(|assertion| {
+            statement_location(
+                sexe.symbolic_library,
+                &metas,
+                file_library,
+                assertion.elem_id,
+                assertion.function_id,
+            )
+        });
+    Some(ConstraintOrigin {
+        constraint: constraint.clone(),
+        owner: owner,
+        location: Some(statement_location(
+            sexe.symbolic_library,
+            &metas,
+            file_library,
+            provenance.elem_id,
+            provenance.owner_id,
+        )),
+        snippet: snippet,
+        assertion: assertion,
+    })
+}
@@ -0,0 +1,468 @@
+use num_bigint_dig::BigInt;
+use num_traits::{Signed, ToPrimitive, Zero};
+use rustc_hash::{FxHashMap, FxHashSet};
+
+use program_structure::ast::{ExpressionInfixOpcode, ExpressionPrefixOpcode};
+
+use crate::executor::debug_ast::{
+    DebuggableExpressionInfixOpcode, DebuggableExpressionPrefixOpcode,
+};
+use crate::executor::symbolic_execution::SymbolicExecutor;
+use crate::executor::symbolic_value::{
+    extract_variables_from_symbolic_value, OwnerName, SymbolicName, SymbolicValue, SymbolicValueRef,
+};
+use crate::executor::utils::{modreduce, strip_ansi};
+use crate::mutator::input_spec::InputDomain;
+use crate::mutator::utils::{gather_input_domains, BaseVerificationConfig};
+
+/// The comparison templates of circomlib, whose inputs must fit in the bit width given as their
+/// first argument.
+const COMPARISON_TEMPLATES: [&str; 4] = ["LessThan", "LessEqThan", "GreaterThan", "GreaterEqThan"];
+
+/// The templates of circomlib whose inputs must be bits.
+const BITS_TEMPLATES: [&str; 2] = ["Bits2Num", "Bits2Num_strict"];
+
+/// The templates of circomlib that constrain their input to the bit width given as their first
+/// argument.
+const RANGE_CHECK_TEMPLATES: [&str; 2] = ["Num2Bits", "Num2Bits_strict"];
+
+/// The templates of circomlib whose output is a bit.
+const BINARY_OUTPUT_TEMPLATES: [&str; 6] = [
+    "LessThan",
+    "LessEqThan",
+    "GreaterThan",
+    "GreaterEqThan",
+    "IsZero",
+    "IsEqual",
+];
+
+/// An input of a comparison or bits template that is not range-checked to the width the template
+/// expects, found by `gather_unchecked_comparison_inputs`.
+pub struct UncheckedComparisonInput {
+    /// The owner-qualified name of the component, e.g. `main.lt`.
+    pub component: String,
+    /// The template of the component with its arguments, e.g. `LessThan(32)`.
+    pub template: String,
+    /// The owner-qualified names of the signals from the input of the component to the signal
+    /// that is not range-checked, each defined by the next one.
+    pub path: Vec<String>,
+    /// Why the last signal of `path` does not fit in the expected width.
+    pub reason: String,
+}
+
+impl UncheckedComparisonInput {
+    pub fn message(&self) -> String {
+        format!(
+            "{} `{}`: {} ({})",
+            self.template,
+            self.component,
+            self.path.join(" <- "),
+            self.reason
+        )
+    }
+}
+
+/// The facts about the signals of the trace needed to bound their widths.
+struct RangeContext<'a> {
+    /// The right-hand sides of the first `<==` assignment of each signal.
+    definitions: FxHashMap<&'a SymbolicName, &'a SymbolicValueRef>,
+    /// The signals assigned with `<--`.
+    hinted: FxHashSet<&'a SymbolicName>,
+    /// The widths in bits that the constraints force the signals to fit in.
+    checked: FxHashMap<SymbolicName, usize>,
+    prime: &'a BigInt,
+    id2name: &'a FxHashMap<usize, String>,
+}
+
+impl<'a> RangeContext<'a> {
+    fn signal_fmt(&self, name: &SymbolicName) -> String {
+        strip_ansi(&name.lookup_fmt(self.id2name))
+    }
+
+    /// Returns the width of `name`, or the reason it is not bounded. The signals defining `name`
+    /// are pushed to `path`, and left there if the reason is returned.
+    fn bound_of_signal(
+        &self,
+        name: &'a SymbolicName,
+        path: &mut Vec<String>,
+        visiting: &mut FxHashSet<&'a SymbolicName>,
+    ) -> Result<usize, String> {
+        if let Some(width) = self.checked.get(name) {
+            return Ok(*width);
+        }
+        path.push(self.signal_fmt(name));
+        if !visiting.insert(name) {
+            return Err(String::from("defined cyclically"));
+        }
+        let bound = match self.definitions.get(name) {
+            Some(rhs) => self.bound_of_value(*rhs, path, visiting)?,
+            None if self.hinted.contains(name) => {
+                return Err(String::from("assigned with `<--` and never range-checked"))
+            }
+            None => return Err(String::from("never range-checked")),
+        };
+        visiting.remove(name);
+        path.pop();
+        Ok(bound)
+    }
+
+    /// Returns the width of `value`, or the reason it is not bounded.
+    fn bound_of_value(
+        &self,
+        value: &'a SymbolicValueRef,
+        path: &mut Vec<String>,
+        visiting: &mut FxHashSet<&'a SymbolicName>,
+    ) -> Result<usize, String> {
+        match &**value {
+            SymbolicValue::ConstantInt(v) => Ok(modreduce(v, self.prime).bits()),
+            SymbolicValue::ConstantBool(_) => Ok(1),
+            SymbolicValue::Variable(name) => self.bound_of_signal(name, path, visiting),
+            SymbolicValue::Conditional(_, then_value, else_value) => Ok(self
+                .bound_of_value(then_value, path, visiting)?
+                .max(self.bound_of_value(else_value, path, visiting)?)),
+            SymbolicValue::BinaryOp(lhs, DebuggableExpressionInfixOpcode(op), rhs)
+                if matches!(op, ExpressionInfixOpcode::Add) =>
+            {
+                Ok(self
+                    .bound_of_value(lhs, path, visiting)?
+                    .max(self.bound_of_value(rhs, path, visiting)?)
+                    + 1)
+            }
+            SymbolicValue::BinaryOp(lhs, DebuggableExpressionInfixOpcode(op), rhs)
+                if matches!(op, ExpressionInfixOpcode::Mul) =>
+            {
+                Ok(self.bound_of_value(lhs, path, visiting)?
+                    + self.bound_of_value(rhs, path, visiting)?)
+            }
+            _ if is_boolean(value) => Ok(1),
+            SymbolicValue::BinaryOp(..) | SymbolicValue::UnaryOp(..) => Err(format!(
+                "`{}` may wrap around the prime",
+                strip_ansi(&value.lookup_fmt(self.id2name))
+            )),
+            _ => Err(format!(
+                "`{}` is not bounded",
+                strip_ansi(&value.lookup_fmt(self.id2name))
+            )),
+        }
+    }
+}
+
+/// Returns the name of the template of the component owning the signals of `owner`.
+fn template_name<'a>(sexe: &'a SymbolicExecutor, owner: &[OwnerName]) -> Option<&'a str> {
+    sexe.component_templates
+        .get(owner)
+        .map(|id| sexe.symbolic_library.id2name[id].as_str())
+}
+
+/// Returns the first argument of the template of the component owning the signals of `owner`,
+/// if it is a constant bit width.
+fn template_width(sexe: &SymbolicExecutor, owner: &[OwnerName]) -> Option<usize> {
+    match sexe.component_args.get(owner)?.first().map(|arg| &**arg) {
+        Some(SymbolicValue::ConstantInt(n)) if !n.is_negative() => n.to_usize(),
+        _ => None,
+    }
+}
+
+/// Returns `true` if `value` always evaluates to 0 or 1.
+fn is_boolean(value: &SymbolicValueRef) -> bool {
+    match &**value {
+        SymbolicValue::ConstantBool(_) => true,
+        SymbolicValue::UnaryOp(
+            DebuggableExpressionPrefixOpcode(ExpressionPrefixOpcode::BoolNot),
+            _,
+        ) => true,
+        SymbolicValue::BinaryOp(_, DebuggableExpressionInfixOpcode(op), _) => matches!(
+            op,
+            ExpressionInfixOpcode::Eq
+                | ExpressionInfixOpcode::NotEq
+                | ExpressionInfixOpcode::Lesser
+                | ExpressionInfixOpcode::LesserEq
+                | ExpressionInfixOpcode::Greater
+                | ExpressionInfixOpcode::GreaterEq
+                | ExpressionInfixOpcode::BoolAnd
+                | ExpressionInfixOpcode::BoolOr
+        ),
+        _ => false,
+    }
+}
+
+/// Returns the signal `x` if `constraint` is `x * (x - 1) == 0`, in any order of the operands.
+fn binary_checked_signal(constraint: &SymbolicValueRef) -> Option<&SymbolicName> {
+    let product = match &**constraint {
+        SymbolicValue::BinaryOp(
+            lhs,
+            DebuggableExpressionInfixOpcode(ExpressionInfixOpcode::Eq),
+            rhs,
+        ) => match (&**lhs, &**rhs) {
+            (_, SymbolicValue::ConstantInt(v)) if v.is_zero() => lhs,
+            (SymbolicValue::ConstantInt(v), _) if v.is_zero() => rhs,
+            _ => return None,
+        },
+        _ => return None,
+    };
+    let (factor, shifted) = match &**product {
+        SymbolicValue::BinaryOp(
+            lhs,
+            DebuggableExpressionInfixOpcode(ExpressionInfixOpcode::Mul),
+            rhs,
+        ) => match (&**lhs, &**rhs) {
+            (SymbolicValue::Variable(name), SymbolicValue::BinaryOp(..)) => (name, rhs),
+            (SymbolicValue::BinaryOp(..), SymbolicValue::Variable(name)) => (name, lhs),
+            _ => return None,
+        },
+        _ => return None,
+    };
+    match &**shifted {
+        SymbolicValue::BinaryOp(
+            lhs,
+            DebuggableExpressionInfixOpcode(ExpressionInfixOpcode::Sub),
+            rhs,
+        ) => match (&**lhs, &**rhs) {
+            (SymbolicValue::Variable(name), SymbolicValue::ConstantInt(v))
+            | (SymbolicValue::ConstantInt(v), SymbolicValue::Variable(name))
+                if name == factor && v == &BigInt::from(1) =>
+            {
+                Some(factor)
+            }
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Returns the signals of the constraints.
+fn constraint_variables(
+    symbolic_trace: &[SymbolicValueRef],
+    side_constraints: &[SymbolicValueRef],
+) -> Vec<SymbolicName> {
+    let mut variables = FxHashSet::default();
+    for constraint in symbolic_trace.iter().chain(side_constraints.iter()) {
+        extract_variables_from_symbolic_value(constraint, &mut variables);
+    }
+    variables.into_iter().collect()
+}
+
+/// Gathers the definitions of the signals of the trace and the widths forced by the templates,
+/// the binary checks, and the domains of the inputs, propagated through the `<==` assignments
+/// between signals.
+fn range_context<'a>(
+    sexe: &'a SymbolicExecutor,
+    symbolic_trace: &'a [SymbolicValueRef],
+    side_constraints: &[SymbolicValueRef],
+    base_config: &'a BaseVerificationConfig,
+    variables: &[SymbolicName],
+) -> RangeContext<'a> {
+    let library = &sexe.symbolic_library;
+    let mut context = RangeContext {
+        definitions: FxHashMap::default(),
+        hinted: FxHashSet::default(),
+        checked: FxHashMap::default(),
+        prime: &base_config.prime,
+        id2name: &library.id2name,
+    };
+    for constraint in symbolic_trace {
+        match &**constraint {
+            SymbolicValue::AssignEq(lhs, rhs) => {
+                if let SymbolicValue::Variable(name) = &**lhs {
+                    context.definitions.entry(name).or_insert(rhs);
+                }
+            }
+            SymbolicValue::Assign(lhs, ..) | SymbolicValue::AssignCall(lhs, ..) => {
+                if let SymbolicValue::Variable(name) = &**lhs {
+                    context.hinted.insert(name);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    // The widths forced by the templates, the binary checks, and the domains of the inputs
+    for var in variables {
+        let template = match template_name(sexe, &var.owner[..]) {
+            Some(template) => template,
+            None => continue,
+        };
+        let signal = library.id2name[&var.id].as_str();
+        if RANGE_CHECK_TEMPLATES.contains(&template) {
+            match (signal, template_width(sexe, &var.owner[..])) {
+                ("in", Some(n)) => {
+                    context.checked.insert(var.clone(), n);
+                }
+                ("out", _) => {
+                    context.checked.insert(var.clone(), 1);
+                }
+                _ => {}
+            }
+        } else if BINARY_OUTPUT_TEMPLATES.contains(&template) && signal == "out" {
+            context.checked.insert(var.clone(), 1);
+        }
+    }
+    for constraint in side_constraints {
+        if let Some(name) = binary_checked_signal(constraint) {
+            context.checked.insert(name.clone(), 1);
+        }
+    }
+    for (var, domain) in gather_input_domains(library, base_config, variables) {
+        let (min, max) = match &domain {
+            InputDomain::Values(values) => match (values.iter().min(), values.iter().max()) {
+                (Some(min), Some(max)) => (min.clone(), max.clone()),
+                _ => continue,
+            },
+            InputDomain::Range(min, max) => (min.clone(), max.clone()),
+        };
+        if !min.is_negative() {
+            context.checked.insert(var, max.bits());
+        }
+    }
+
+    // A signal equal to a checked signal is checked as well
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for (lhs, rhs) in &context.definitions {
+            if let SymbolicValue::Variable(rhs) = &***rhs {
+                let width = match (context.checked.get(*lhs), context.checked.get(rhs)) {
+                    (Some(l), Some(r)) if l == r => continue,
+                    (Some(l), Some(r)) => *l.min(r),
+                    (Some(w), None) | (None, Some(w)) => *w,
+                    (None, None) => continue,
+                };
+                context.checked.insert((*lhs).clone(), width);
+                context.checked.insert(rhs.clone(), width);
+                changed = true;
+            }
+        }
+    }
+    context
+}
+
+/// Infers the width in bits of each signal of the constraints whose width is forced by the
+/// constraints, following its `<==` definitions as `gather_unchecked_comparison_inputs` does.
+///
+/// # Parameters
+/// - `sexe`: The symbolic executor after the execution of the main template.
+/// - `symbolic_trace`: The constraints of the program trace.
+/// - `side_constraints`: The side constraints of the program.
+/// - `base_config`: The verification configuration specifying the target template.
+///
+/// # Returns
+/// A map from each bounded signal to its width. The signals that are not bounded are omitted.
+pub fn infer_signal_widths(
+    sexe: &SymbolicExecutor,
+    symbolic_trace: &[SymbolicValueRef],
+    side_constraints: &[SymbolicValueRef],
+    base_config: &BaseVerificationConfig,
+) -> FxHashMap<SymbolicName, usize> {
+    let variables = constraint_variables(symbolic_trace, side_constraints);
+    let mut context = range_context(
+        sexe,
+        symbolic_trace,
+        side_constraints,
+        base_config,
+        &variables,
+    );
+    for var in &variables {
+        // The widths are memoized so that the definitions shared by many signals are followed once
+        if let Ok(width) = context.bound_of_signal(var, &mut Vec::new(), &mut FxHashSet::default())
+        {
+            context.checked.insert(var.clone(), width);
+        }
+    }
+    context.checked
+}
+
+/// Checks that the inputs of the comparison and bits templates of circomlib are range-checked.
+///
+/// `LessThan(n)` and the other comparison templates only compare their inputs correctly if both
+/// fit in `n` bits, and `Bits2Num` only recombines its inputs correctly if each of them is a bit.
+/// Neither template checks this itself, so an input that is not range-checked by the caller lets
+/// the prover pass a large field element, for which the comparison or the recombination is
+/// wrong. The width of each input is bounded by following its `<==` definitions back through
+/// additions, multiplications, and conditionals, until reaching a signal whose width is forced
+/// by the constraints: the input of `Num2Bits(n)`, a bit output of a comparison, `IsZero`, or
+/// `Num2Bits`, a signal constrained by `x * (x - 1) === 0`, or an input of the main template
+/// restricted by `--input_spec` or its tags. Components nested in a template of circomlib are
+/// not checked, as circomlib guarantees the widths of their inputs from those of the template.
+///
+/// # Parameters
+/// - `sexe`: The symbolic executor after the execution of the main template.
+/// - `symbolic_trace`: The constraints of the program trace.
+/// - `side_constraints`: The side constraints of the program.
+/// - `base_config`: The verification configuration specifying the target template.
+///
+/// # Returns
+/// A vector of `UncheckedComparisonInput`s, one per input of a comparison or bits template that
+/// is not range-checked, sorted by component and input.
+pub fn gather_unchecked_comparison_inputs(
+    sexe: &SymbolicExecutor,
+    symbolic_trace: &[SymbolicValueRef],
+    side_constraints: &[SymbolicValueRef],
+    base_config: &BaseVerificationConfig,
+) -> Vec<UncheckedComparisonInput> {
+    let library = &sexe.symbolic_library;
+    let variables = constraint_variables(symbolic_trace, side_constraints);
+    let context = range_context(
+        sexe,
+        symbolic_trace,
+        side_constraints,
+        base_config,
+        &variables,
+    );
+
+    let is_nested = |owner: &[OwnerName]| {
+        (1..owner.len()).any(|len| {
+            template_name(sexe, &owner[..len]).map_or(false, |template| {
+                COMPARISON_TEMPLATES.contains(&template)
+                    || BITS_TEMPLATES.contains(&template)
+                    || RANGE_CHECK_TEMPLATES.contains(&template)
+                    || BINARY_OUTPUT_TEMPLATES.contains(&template)
+            })
+        })
+    };
+
+    let mut issues = Vec::new();
+    for var in &variables {
+        let template = match template_name(sexe, &var.owner[..]) {
+            Some(template) => template,
+            None => continue,
+        };
+        if library.id2name[&var.id] != "in" || is_nested(&var.owner[..]) {
+            continue;
+        }
+        let expected = if COMPARISON_TEMPLATES.contains(&template) {
+            match template_width(sexe, &var.owner[..]) {
+                Some(n) => n,
+                None => continue,
+            }
+        } else if BITS_TEMPLATES.contains(&template) {
+            1
+        } else {
+            continue;
+        };
+
+        let mut path = Vec::new();
+        let reason = match context.bound_of_signal(var, &mut path, &mut FxHashSet::default()) {
+            Ok(width) if width <= expected => continue,
+            Ok(width) => {
+                path = vec![context.signal_fmt(var)];
+                format!("may take {} bits, more than {}", width, expected)
+            }
+            Err(reason) => reason,
+        };
+        let args = sexe.component_args[&*var.owner]
+            .iter()
+            .map(|arg| strip_ansi(&arg.lookup_fmt(&library.id2name)))
+            .collect::<Vec<_>>();
+        let signal = context.signal_fmt(var);
+        issues.push(UncheckedComparisonInput {
+            component: signal
+                .rsplit_once('.')
+                .map_or(signal.clone(), |(component, _)| component.to_string()),
+            template: format!("{}({})", template, args.join(", ")),
+            path,
+            reason,
+        });
+    }
+    issues.sort_by(|a, b| (&a.component, &a.path[0]).cmp(&(&b.component, &b.path[0])));
+    issues
+}
@@ -0,0 +1,317 @@
+use std::rc::Rc;
+
+use num_bigint_dig::BigInt;
+use num_traits::{One, Zero};
+use program_structure::ast::ExpressionInfixOpcode;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use rustc_hash::FxHashMap;
+
+use crate::executor::debug_ast::DebuggableExpressionInfixOpcode;
+use crate::executor::symbolic_execution::SymbolicExecutor;
+use crate::executor::symbolic_value::{SymbolicName, SymbolicValue, SymbolicValueRef};
+use crate::mutator::assertion::InputSampler;
+use crate::mutator::assign_oracle::{
+    are_satisfied, pair_assignments_with_constraints, propose_alternative_values,
+};
+use crate::mutator::utils::{
+    emulate_symbolic_trace, is_equal_mod, BaseVerificationConfig, CounterExample,
+    UnderConstrainedType, VerificationResult,
+};
+
+/// The number of bits of the range checks tried by `suggest_repairs`, from the tightest one.
+pub const REPAIR_RANGE_BITS: [usize; 6] = [8, 16, 32, 64, 128, 252];
+
+/// The maximum number of honest witnesses on which the candidates are verified.
+const MAX_HONEST_WITNESSES: usize = 64;
+
+/// The shape of a candidate side constraint.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RepairKind {
+    /// `s * (s - 1) === 0`.
+    Binary,
+    /// `0 <= s < 2^bits`, e.g. enforced with `Num2Bits(bits)`.
+    Range(usize),
+    /// `s === e`, where `s <-- e` is the assignment of the trace. A division `s <-- a / b` yields
+    /// `s * b === a`, which stays quadratic.
+    Equality,
+}
+
+/// A side constraint on the divergent signal of a counterexample.
+pub struct RepairCandidate {
+    pub signal: SymbolicName,
+    pub kind: RepairKind,
+    pub constraint: SymbolicValueRef,
+}
+
+impl RepairCandidate {
+    /// Formats the candidate as the constraint to add to the circuit.
+    pub fn lookup_fmt(&self, lookup: &FxHashMap<usize, String>) -> String {
+        let signal = self.signal.lookup_fmt(lookup);
+        match (&self.kind, self.constraint.as_ref()) {
+            (RepairKind::Binary, _) => format!("{} * ({} - 1) === 0", signal, signal),
+            (RepairKind::Range(bits), _) => {
+                format!("0 <= {} < 2^{} (e.g. Num2Bits({}))", signal, bits, bits)
+            }
+            (RepairKind::Equality, SymbolicValue::BinaryOp(lhs, _, rhs)) => {
+                format!("{} === {}", lhs.lookup_fmt(lookup), rhs.lookup_fmt(lookup))
+            }
+            (RepairKind::Equality, c) => c.lookup_fmt(lookup),
+        }
+    }
+}
+
+fn binary_op(
+    lhs: SymbolicValueRef,
+    op: ExpressionInfixOpcode,
+    rhs: SymbolicValueRef,
+) -> SymbolicValueRef {
+    Rc::new(SymbolicValue::BinaryOp(
+        lhs,
+        DebuggableExpressionInfixOpcode(op),
+        rhs,
+    ))
+}
+
+fn constant(value: BigInt) -> SymbolicValueRef {
+    Rc::new(SymbolicValue::ConstantInt(value))
+}
+
+/// Synthesizes the candidate side constraints on `signal` from a small grammar: binarity, range
+/// checks of `REPAIR_RANGE_BITS` bits, and the equality to the expression assigned to `signal`
+/// with `<--` in `symbolic_trace`, if any.
+///
+/// # Returns
+/// The candidates, from the most to the least restrictive shape.
+pub fn synthesize_repair_candidates(
+    signal: &SymbolicName,
+    symbolic_trace: &[SymbolicValueRef],
+) -> Vec<RepairCandidate> {
+    let var = Rc::new(SymbolicValue::Variable(signal.clone()));
+    let mut candidates = vec![RepairCandidate {
+        signal: signal.clone(),
+        kind: RepairKind::Binary,
+        constraint: binary_op(
+            binary_op(
+                var.clone(),
+                ExpressionInfixOpcode::Mul,
+                binary_op(
+                    var.clone(),
+                    ExpressionInfixOpcode::Sub,
+                    constant(BigInt::one()),
+                ),
+            ),
+            ExpressionInfixOpcode::Eq,
+            constant(BigInt::zero()),
+        ),
+    }];
+    for bits in REPAIR_RANGE_BITS {
+        candidates.push(RepairCandidate {
+            signal: signal.clone(),
+            kind: RepairKind::Range(bits),
+            constraint: binary_op(
+                binary_op(
+                    constant(BigInt::zero()),
+                    ExpressionInfixOpcode::LesserEq,
+                    var.clone(),
+                ),
+                ExpressionInfixOpcode::BoolAnd,
+                binary_op(
+                    var.clone(),
+                    ExpressionInfixOpcode::Lesser,
+                    constant(BigInt::one() << bits),
+                ),
+            ),
+        });
+    }
+
+    let assigned_expression = symbolic_trace.iter().find_map(|inst| match inst.as_ref() {
+        SymbolicValue::Assign(lhs, rhs, ..) => match lhs.as_ref() {
+            SymbolicValue::Variable(name) if name == signal => Some(rhs.clone()),
+            _ => None,
+        },
+        _ => None,
+    });
+    if let Some(rhs) = assigned_expression {
+        let constraint = match rhs.as_ref() {
+            SymbolicValue::BinaryOp(num, op, den) if matches!(op.0, ExpressionInfixOpcode::Div) => {
+                binary_op(
+                    binary_op(var.clone(), ExpressionInfixOpcode::Mul, den.clone()),
+                    ExpressionInfixOpcode::Eq,
+                    num.clone(),
+                )
+            }
+            _ => binary_op(var.clone(), ExpressionInfixOpcode::Eq, rhs),
+        };
+        candidates.push(RepairCandidate {
+            signal: signal.clone(),
+            kind: RepairKind::Equality,
+            constraint: constraint,
+        });
+    }
+    candidates
+}
+
+/// Suggests side constraints that fix the divergent signal of a non-deterministic counterexample.
+///
+/// The candidates of `synthesize_repair_candidates` are checked against honest witnesses, i.e.,
+/// witnesses computed by the trace from the inputs of the counterexample and from sampled inputs.
+/// A candidate is suggested if
+/// 1. the counterexample violates it,
+/// 2. every honest witness satisfies it, so that it does not over-constrain the circuit, and
+/// 3. on every honest witness, no other value of the signal satisfies both the candidate and the
+///    side constraints mentioning the signal, so that it eliminates the class of the
+///    counterexample rather than the counterexample alone.
+///
+/// Only the divergent signal is perturbed in the last check, as in `assignment_oracle_search`,
+/// so a suggestion is evidence rather than a proof that the circuit becomes well-constrained.
+///
+/// # Parameters
+/// - `sexe`: A mutable reference to the concrete executor.
+/// - `symbolic_trace`: A vector of constraints representing the program trace.
+/// - `side_constraints`: A vector of additional constraints for validation.
+/// - `base_config`: The verification base_configs.
+/// - `counter_example`: The counterexample to repair.
+/// - `max_iterations`: The maximum number of inputs to sample for the honest witnesses.
+/// - `seed`: The seed of the random number generator.
+///
+/// # Returns
+/// The verified candidates: at most one of each kind, with the tightest range check. The result
+/// is empty if the counterexample is not non-deterministic.
+pub fn suggest_repairs(
+    sexe: &mut SymbolicExecutor,
+    symbolic_trace: &Vec<SymbolicValueRef>,
+    side_constraints: &Vec<SymbolicValueRef>,
+    base_config: &BaseVerificationConfig,
+    counter_example: &CounterExample,
+    max_iterations: usize,
+    seed: u64,
+) -> Vec<RepairCandidate> {
+    let signal = match &counter_example.flag {
+        VerificationResult::UnderConstrained(UnderConstrainedType::NonDeterministic(
+            signal,
+            ..,
+        )) => signal,
+        _ => return Vec::new(),
+    };
+    let prime = &base_config.prime;
+    let constraints = pair_assignments_with_constraints(symbolic_trace, side_constraints)
+        .into_iter()
+        .find(|pair| pair.signal == *signal)
+        .map_or_else(Vec::new, |pair| pair.constraints);
+
+    // Gather honest witnesses, starting with the one of the inputs of the counterexample
+    let mut sampler = InputSampler::new(
+        sexe.symbolic_library,
+        symbolic_trace,
+        side_constraints,
+        base_config,
+        max_iterations,
+        seed,
+    );
+    let counter_example_inputs: FxHashMap<SymbolicName, BigInt> = sampler
+        .input_variables
+        .iter()
+        .filter_map(|var| {
+            counter_example
+                .assignment
+                .get(var)
+                .map(|v| (var.clone(), v.clone()))
+        })
+        .collect();
+    let mut honest_witnesses = Vec::new();
+    for iteration in 0..=max_iterations {
+        if honest_witnesses.len() >= MAX_HONEST_WITNESSES {
+            break;
+        }
+        let mut assignment = if iteration == 0 {
+            counter_example_inputs.clone()
+        } else {
+            sampler.sample(iteration - 1, base_config)
+        };
+        match emulate_symbolic_trace(
+            prime,
+            symbolic_trace,
+            &FxHashMap::default(),
+            &mut assignment,
+            sexe.symbolic_library,
+        ) {
+            Some((true, _)) => {}
+            _ => continue,
+        }
+        if assignment.contains_key(signal)
+            && are_satisfied(prime, side_constraints, &assignment, sexe.symbolic_library)
+                == Some(true)
+        {
+            honest_witnesses.push(assignment);
+        }
+    }
+    if honest_witnesses.is_empty() {
+        return Vec::new();
+    }
+
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut suggestions: Vec<RepairCandidate> = Vec::new();
+    'candidates: for candidate in synthesize_repair_candidates(signal, symbolic_trace) {
+        if matches!(candidate.kind, RepairKind::Range(_))
+            && suggestions
+                .iter()
+                .any(|s| matches!(s.kind, RepairKind::Range(_)))
+        {
+            continue;
+        }
+        let candidate_constraints = [candidate.constraint.clone()];
+        if are_satisfied(
+            prime,
+            &candidate_constraints,
+            &counter_example.assignment,
+            sexe.symbolic_library,
+        ) != Some(false)
+        {
+            continue;
+        }
+
+        let mut repaired_constraints = constraints.clone();
+        repaired_constraints.push(candidate.constraint.clone());
+        for witness in &honest_witnesses {
+            if are_satisfied(
+                prime,
+                &candidate_constraints,
+                witness,
+                sexe.symbolic_library,
+            ) != Some(true)
+            {
+                continue 'candidates;
+            }
+            let honest_value = &witness[signal];
+            let mut alternative_values = propose_alternative_values(
+                prime,
+                signal,
+                honest_value,
+                &repaired_constraints,
+                witness,
+                sexe.symbolic_library,
+                &mut rng,
+            );
+            alternative_values.extend(counter_example.assignment.get(signal).cloned());
+            for alternative_value in alternative_values {
+                if is_equal_mod(&alternative_value, honest_value, prime) {
+                    continue;
+                }
+                let mut divergent_witness = witness.clone();
+                divergent_witness.insert(signal.clone(), alternative_value);
+                if are_satisfied(
+                    prime,
+                    &repaired_constraints,
+                    &divergent_witness,
+                    sexe.symbolic_library,
+                ) == Some(true)
+                {
+                    continue 'candidates;
+                }
+            }
+        }
+        suggestions.push(candidate);
+    }
+    suggestions
+}
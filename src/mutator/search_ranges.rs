@@ -0,0 +1,135 @@
+use num_bigint_dig::BigInt;
+use num_traits::{One, Zero};
+use rustc_hash::{FxHashMap, FxHashSet};
+
+use crate::executor::symbolic_execution::SymbolicExecutor;
+use crate::executor::symbolic_value::{SymbolicName, SymbolicValueRef};
+use crate::executor::utils::modreduce;
+use crate::mutator::range_checks::infer_signal_widths;
+use crate::mutator::utils::BaseVerificationConfig;
+
+/// The values tried for each variable by the quick and heuristics modes of the brute-force
+/// search.
+///
+/// A variable whose width is forced by the constraints (e.g. a bit of `Num2Bits`, or a signal
+/// defined from such bits with `<==`) takes the values of `[0, 2^width)`, and any other variable
+/// those of `[-r, r]`, where `r = --heuristics_range`. The values are scheduled so that the ones
+/// most likely to expose a bug are tried first: the special values `0`, `1`, and the largest
+/// value of the range (`-1` for an unbounded variable), then the structural seeds of the
+/// variable, then, in the heuristics mode, the other values of magnitude at most `r` by
+/// increasing magnitude.
+pub struct SearchRanges {
+    /// The largest value of each variable whose width is forced by the constraints.
+    pub bounds: FxHashMap<SymbolicName, BigInt>,
+    range: BigInt,
+    prime: BigInt,
+}
+
+impl SearchRanges {
+    /// Infers the ranges of the variables of the constraints.
+    ///
+    /// # Parameters
+    /// - `sexe`: The symbolic executor after the execution of the main template.
+    /// - `symbolic_trace`: The constraints of the program trace.
+    /// - `side_constraints`: The side constraints of the program.
+    /// - `base_config`: The verification configuration specifying the target template, the
+    ///   prime, and the fallback range.
+    pub fn infer(
+        sexe: &SymbolicExecutor,
+        symbolic_trace: &[SymbolicValueRef],
+        side_constraints: &[SymbolicValueRef],
+        base_config: &BaseVerificationConfig,
+    ) -> Self {
+        let bounds = infer_signal_widths(sexe, symbolic_trace, side_constraints, base_config)
+            .into_iter()
+            .filter_map(|(var, width)| {
+                let max = (BigInt::one() << width) - BigInt::one();
+                // A width covering the whole field does not restrict the variable
+                if max < base_config.prime {
+                    Some((var, max))
+                } else {
+                    None
+                }
+            })
+            .collect();
+        SearchRanges {
+            bounds,
+            range: base_config.range.clone(),
+            prime: base_config.prime.clone(),
+        }
+    }
+
+    /// Returns the values tried for `var`, in the order they are tried.
+    ///
+    /// # Parameters
+    /// - `var`: The variable.
+    /// - `seeds`: The structural seeds of `var`, tried after the special values. The seeds
+    ///   outside the inferred range of `var` are skipped.
+    /// - `quick_mode`: Whether only the special values and the seeds are tried.
+    ///
+    /// # Returns
+    /// The values without duplicates modulo the prime, each in the first form it is scheduled in
+    /// (e.g. `-1` rather than `p - 1`).
+    pub fn schedule(&self, var: &SymbolicName, seeds: &[BigInt], quick_mode: bool) -> Vec<BigInt> {
+        let mut values = Vec::new();
+        match self.bounds.get(var) {
+            Some(max) => {
+                values.push(BigInt::zero());
+                if max >= &BigInt::one() {
+                    values.push(BigInt::one());
+                }
+                values.push(max.clone());
+                values.extend(
+                    seeds
+                        .iter()
+                        .map(|seed| self.reduce(seed))
+                        .filter(|seed| seed <= max),
+                );
+                if !quick_mode {
+                    let limit = if &self.range < max {
+                        self.range.clone()
+                    } else {
+                        max.clone()
+                    };
+                    let mut magnitude = BigInt::from(2);
+                    while magnitude <= limit {
+                        values.push(magnitude.clone());
+                        magnitude += 1;
+                    }
+                }
+            }
+            None => {
+                values.push(BigInt::zero());
+                values.push(BigInt::one());
+                values.push(-BigInt::one());
+                values.extend(seeds.iter().cloned());
+                if !quick_mode {
+                    let mut magnitude = BigInt::from(2);
+                    while magnitude <= self.range {
+                        values.push(magnitude.clone());
+                        values.push(-magnitude.clone());
+                        magnitude += 1;
+                    }
+                }
+            }
+        }
+
+        let mut scheduled = FxHashSet::default();
+        values
+            .into_iter()
+            .filter(|value| scheduled.insert(self.reduce(value)))
+            .collect()
+    }
+
+    /// Returns the number of `variables` whose range is inferred from the constraints.
+    pub fn num_bounded(&self, variables: &[SymbolicName]) -> usize {
+        variables
+            .iter()
+            .filter(|var| self.bounds.contains_key(*var))
+            .count()
+    }
+
+    fn reduce(&self, value: &BigInt) -> BigInt {
+        modreduce(value, &self.prime)
+    }
+}
@@ -0,0 +1,100 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::mutator::utils::{CounterExample, VerificationResult};
+
+/// How severe a finding is, from the least to the most severe.
+///
+/// - `Critical`: an exploitable under-constraint demonstrated by a counterexample.
+/// - `High`: a counterexample that is not an under-constraint, e.g. an over-constraint or a
+///   reachable assertion failure.
+/// - `Medium`: a suspicious pattern that often hides a bug, e.g. a potential division by zero.
+/// - `Low`: a pattern that rarely hides a bug or a limitation of the analysis, e.g. dead code.
+/// - `Info`: an informative note, e.g. an assumption of the analysis or a repair suggestion.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+    Info,
+    Low,
+    Medium,
+    High,
+    Critical,
+}
+
+impl Severity {
+    /// The severities, from the least to the most severe.
+    pub const ALL: [Severity; 5] = [
+        Severity::Info,
+        Severity::Low,
+        Severity::Medium,
+        Severity::High,
+        Severity::Critical,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Severity::Info => "info",
+            Severity::Low => "low",
+            Severity::Medium => "medium",
+            Severity::High => "high",
+            Severity::Critical => "critical",
+        }
+    }
+
+    /// Returns the exit code of a run whose most severe finding is `self`, when it is at least
+    /// the severity of `--fail_on`: `2` for `Info` up to `6` for `Critical`, distinct from the
+    /// `1` of a run that fails with an error.
+    pub fn exit_code(&self) -> i32 {
+        2 + *self as i32
+    }
+
+    /// Returns the severity of the verdict `ce`.
+    pub fn of_counter_example(ce: &CounterExample) -> Severity {
+        match ce.flag {
+            VerificationResult::UnderConstrained(_) => Severity::Critical,
+            VerificationResult::WellConstrained => Severity::Info,
+            _ => Severity::High,
+        }
+    }
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl FromStr for Severity {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Severity::ALL
+            .iter()
+            .find(|severity| severity.as_str() == s.to_lowercase())
+            .copied()
+            .ok_or_else(|| {
+                format!(
+                    "unknown severity: {} (expected one of info, low, medium, high, critical)",
+                    s
+                )
+            })
+    }
+}
+
+/// Counts the findings of each severity, from the most to the least severe, omitting the
+/// severities without findings, e.g. `critical: 1, medium: 2`.
+pub fn format_severity_counts<I>(severities: I) -> String
+where
+    I: IntoIterator<Item = Severity>,
+{
+    let mut counts = [0usize; 5];
+    for severity in severities {
+        counts[severity as usize] += 1;
+    }
+    Severity::ALL
+        .iter()
+        .rev()
+        .filter(|severity| counts[**severity as usize] > 0)
+        .map(|severity| format!("{}: {}", severity, counts[*severity as usize]))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
@@ -0,0 +1,49 @@
+use program_structure::file_definition::FileLibrary;
+
+use crate::executor::symbolic_execution::SymbolicExecutor;
+use crate::mutator::division::{gather_statement_metas, statement_location};
+
+/// A multi-substitution such as `(a, b) <== A()(x)` whose targets do not match the number or the
+/// dimensions of the values it assigns.
+///
+/// circom rejects such a statement, and the symbolic execution does not assign it rather than
+/// pairing targets and values that do not correspond.
+pub struct ShapeMismatchSite {
+    pub owner_name: String,
+    pub location: String,
+    pub message: String,
+}
+
+/// Lists the multi-substitutions recorded during symbolic execution whose targets do not match
+/// the shape of the assigned values.
+///
+/// # Parameters
+/// - `sexe`: The symbolic executor after the execution of the main template.
+/// - `file_library`: The file library used to resolve the source locations of the statements.
+///
+/// # Returns
+/// A vector of `ShapeMismatchSite`s sorted by their owner and location.
+pub fn gather_shape_mismatches(
+    sexe: &SymbolicExecutor,
+    file_library: &FileLibrary,
+) -> Vec<ShapeMismatchSite> {
+    let lookup = &sexe.symbolic_library.id2name;
+    let metas = gather_statement_metas(sexe.symbolic_library);
+    let mut sites: Vec<_> = sexe
+        .shape_mismatches
+        .iter()
+        .map(|(elem_id, (owner_id, message))| ShapeMismatchSite {
+            owner_name: lookup.get(owner_id).cloned().unwrap_or_default(),
+            location: statement_location(
+                sexe.symbolic_library,
+                &metas,
+                file_library,
+                *elem_id,
+                *owner_id,
+            ),
+            message: message.clone(),
+        })
+        .collect();
+    sites.sort_by(|a, b| (&a.owner_name, &a.location).cmp(&(&b.owner_name, &b.location)));
+    sites
+}
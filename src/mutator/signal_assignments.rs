@@ -0,0 +1,340 @@
+use std::rc::Rc;
+
+use num_bigint_dig::BigInt;
+use program_structure::ast::{SignalType, VariableType};
+use program_structure::file_definition::FileLibrary;
+use rustc_hash::{FxHashMap, FxHashSet};
+
+use crate::executor::symbolic_execution::SymbolicExecutor;
+use crate::executor::symbolic_value::{
+    extract_variables_from_symbolic_value, OwnerName, SymbolicAccess, SymbolicName, SymbolicValue,
+    SymbolicValueRef,
+};
+use crate::executor::utils::strip_ansi;
+use crate::mutator::division::{gather_statement_metas, statement_location};
+
+/// The ways a signal can be misused that circom rejects, but that the symbolic execution silently
+/// accepts by overwriting the value of the signal.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum SignalAssignmentIssueKind {
+    /// The signal is assigned more than once.
+    DoubleAssignment,
+    /// An input signal is assigned by the template declaring it.
+    InputAssignment,
+    /// The signal appears in a constraint but is never assigned.
+    UnassignedRead,
+    /// Some elements of an output array are never assigned.
+    UnassignedOutputElements,
+}
+
+/// The number of elements listed in the message of an `UnassignedOutputElements` issue.
+const MAX_LISTED_ELEMENTS: usize = 8;
+
+/// A misuse of a signal found by `gather_signal_assignment_issues`.
+pub struct SignalAssignmentIssue {
+    pub kind: SignalAssignmentIssueKind,
+    /// The owner-qualified name of the signal, e.g. `main.c.in[0]`.
+    pub signal: String,
+    /// The source locations of the assignments of the signal, empty for `UnassignedRead` and
+    /// `UnassignedOutputElements`.
+    pub locations: Vec<String>,
+    /// The indices of the elements never assigned for `UnassignedOutputElements`, e.g. `[1][0]`.
+    pub elements: Vec<String>,
+}
+
+impl SignalAssignmentIssue {
+    pub fn message(&self) -> String {
+        match self.kind {
+            SignalAssignmentIssueKind::DoubleAssignment => format!(
+                "`{}` is assigned {} times, at {}",
+                self.signal,
+                self.locations.len(),
+                self.locations.join(", ")
+            ),
+            SignalAssignmentIssueKind::InputAssignment => format!(
+                "input `{}` is assigned by its own template at {}",
+                self.signal,
+                self.locations.join(", ")
+            ),
+            SignalAssignmentIssueKind::UnassignedRead => {
+                format!("`{}` is read but never assigned", self.signal)
+            }
+            SignalAssignmentIssueKind::UnassignedOutputElements => {
+                let mut listed = self
+                    .elements
+                    .iter()
+                    .take(MAX_LISTED_ELEMENTS)
+                    .cloned()
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                if self.elements.len() > MAX_LISTED_ELEMENTS {
+                    listed +=
+                        &format!(", ... ({} more)", self.elements.len() - MAX_LISTED_ELEMENTS);
+                }
+                format!(
+                    "{} element(s) of output `{}` are never assigned: {}",
+                    self.elements.len(),
+                    self.signal,
+                    listed
+                )
+            }
+        }
+    }
+}
+
+/// Returns `true` if `name`, or an array containing it, is in `assigned`. An element whose
+/// indices are not all constant is assumed to be assigned.
+fn is_assigned(name: &SymbolicName, assigned: &FxHashSet<SymbolicName>) -> bool {
+    let access = name.access.clone().unwrap_or_default();
+    if access.iter().any(|a| {
+        !matches!(
+            a,
+            SymbolicAccess::ArrayAccess(SymbolicValue::ConstantInt(_))
+        )
+    }) {
+        return true;
+    }
+    (0..=access.len()).any(|len| {
+        let prefix = SymbolicName::new(
+            name.id,
+            name.owner.clone(),
+            if len == 0 {
+                None
+            } else {
+                Some(access[..len].to_vec())
+            },
+        );
+        assigned.contains(&prefix)
+    })
+}
+
+/// Returns the indices of every element of an array of dimensions `dims`, in row-major order.
+fn element_indices(dims: &[usize]) -> Vec<Vec<usize>> {
+    let mut indices = vec![Vec::new()];
+    for dim in dims {
+        indices = indices
+            .into_iter()
+            .flat_map(|prefix: Vec<usize>| {
+                (0..*dim).map(move |i| {
+                    let mut index = prefix.clone();
+                    index.push(i);
+                    index
+                })
+            })
+            .collect();
+    }
+    indices
+}
+
+/// Finds the elements of the output arrays that are never assigned, in the main template and in
+/// its executed components, whose dimensions are stored with them. The components nested deeper
+/// are not in the component store of `sexe` and are skipped.
+///
+/// An array with an element assigned at a symbolic index is skipped, since the assigned element
+/// is unknown, and so are the arrays with an undetermined dimension.
+fn gather_unassigned_output_elements(
+    sexe: &SymbolicExecutor,
+    main_template_name: &str,
+    assigned: &FxHashSet<SymbolicName>,
+) -> Vec<SignalAssignmentIssue> {
+    let library = &sexe.symbolic_library;
+    let symbolically_assigned: FxHashSet<(&Vec<OwnerName>, usize)> = assigned
+        .iter()
+        .filter(|name| {
+            name.access.iter().flatten().any(|a| {
+                !matches!(
+                    a,
+                    SymbolicAccess::ArrayAccess(SymbolicValue::ConstantInt(_))
+                )
+            })
+        })
+        .map(|name| (&*name.owner, name.id))
+        .collect();
+
+    let mut instances = Vec::new();
+    if let Some(template_id) = library.name2id.get(main_template_name) {
+        instances.push((
+            sexe.cur_state.owner_name.clone(),
+            *template_id,
+            &sexe.id2dimensions,
+        ));
+    }
+    for (owner, template_id) in &sexe.component_templates {
+        let component = match owner.last() {
+            Some(component) => component,
+            None => continue,
+        };
+        let component_name = SymbolicName::new(
+            component.id,
+            Rc::new(owner[..owner.len() - 1].to_vec()),
+            component.access.clone(),
+        );
+        if let Some(c) = sexe.symbolic_store.components_store.get(&component_name) {
+            instances.push((Rc::new(owner.clone()), *template_id, &c.id2dimensions));
+        }
+    }
+
+    let mut issues = Vec::new();
+    for (owner, template_id, id2dimensions) in instances {
+        let template = match library.template_library.get(&template_id) {
+            Some(template) => template,
+            None => continue,
+        };
+        for id in &template.output_order {
+            let dims = match id2dimensions.get(id) {
+                Some(dims) if !dims.is_empty() && !dims.contains(&0) => dims,
+                _ => continue,
+            };
+            if symbolically_assigned.contains(&(&*owner, *id)) {
+                continue;
+            }
+            let elements: Vec<String> = element_indices(dims)
+                .into_iter()
+                .filter(|index| {
+                    let access = index
+                        .iter()
+                        .map(|i| {
+                            SymbolicAccess::ArrayAccess(SymbolicValue::ConstantInt(BigInt::from(
+                                *i,
+                            )))
+                        })
+                        .collect();
+                    !is_assigned(
+                        &SymbolicName::new(*id, owner.clone(), Some(access)),
+                        assigned,
+                    )
+                })
+                .map(|index| index.iter().map(|i| format!("[{}]", i)).collect())
+                .collect();
+            if !elements.is_empty() {
+                issues.push(SignalAssignmentIssue {
+                    kind: SignalAssignmentIssueKind::UnassignedOutputElements,
+                    signal: strip_ansi(
+                        &SymbolicName::new(*id, owner.clone(), None).lookup_fmt(&library.id2name),
+                    ),
+                    locations: Vec::new(),
+                    elements: elements,
+                });
+            }
+        }
+    }
+    issues
+}
+
+/// Checks the assignments of signals recorded during the symbolic execution of the main template.
+///
+/// Reports the signals assigned more than once (an assignment made in both branches of an `if`
+/// counts once), the inputs assigned by the template declaring them, and the signals read by the
+/// constraints without ever being assigned, e.g. an input of a sub-component that its parent
+/// never feeds. The inputs of the main template are assigned by the prover. The output arrays
+/// some of whose elements are never assigned are reported with the indices of those elements.
+///
+/// # Parameters
+/// - `sexe`: The symbolic executor after the execution of the main template.
+/// - `symbolic_trace`: The constraints of the program trace.
+/// - `main_template_name`: The name of the main template.
+/// - `file_library`: The file library used to resolve the source locations of the assignments.
+///
+/// # Returns
+/// A vector of `SignalAssignmentIssue`s sorted by their kind and signal.
+pub fn gather_signal_assignment_issues(
+    sexe: &SymbolicExecutor,
+    symbolic_trace: &[SymbolicValueRef],
+    main_template_name: &str,
+    file_library: &FileLibrary,
+) -> Vec<SignalAssignmentIssue> {
+    let library = &sexe.symbolic_library;
+    let metas = gather_statement_metas(library);
+    let location = |elem_id: usize, owner_id: usize| {
+        statement_location(library, &metas, file_library, elem_id, owner_id)
+    };
+    let signal_fmt = |name: &SymbolicName| strip_ansi(&name.lookup_fmt(&library.id2name));
+
+    let mut locations: FxHashMap<&SymbolicName, Vec<String>> = FxHashMap::default();
+    let mut issues = Vec::new();
+    for assignment in &sexe.signal_assignments {
+        locations
+            .entry(&assignment.name)
+            .or_default()
+            .push(location(assignment.elem_id, assignment.owner_id));
+        if assignment.is_input {
+            issues.push(SignalAssignmentIssue {
+                kind: SignalAssignmentIssueKind::InputAssignment,
+                signal: signal_fmt(&assignment.name),
+                locations: vec![location(assignment.elem_id, assignment.owner_id)],
+                elements: Vec::new(),
+            });
+        }
+    }
+    for (name, assignment_locations) in &locations {
+        if assignment_locations.len() > 1 {
+            issues.push(SignalAssignmentIssue {
+                kind: SignalAssignmentIssueKind::DoubleAssignment,
+                signal: signal_fmt(*name),
+                locations: assignment_locations.clone(),
+                elements: Vec::new(),
+            });
+        }
+    }
+
+    let assigned: FxHashSet<SymbolicName> = locations.keys().map(|name| (*name).clone()).collect();
+    let main_template_id = library.name2id.get(main_template_name);
+    let mut variables = FxHashSet::default();
+    for c in symbolic_trace {
+        extract_variables_from_symbolic_value(c, &mut variables);
+    }
+    let mut reported = FxHashSet::default();
+    for v in &variables {
+        let template_id = if v.owner.len() == 1 {
+            main_template_id.cloned()
+        } else {
+            sexe.component_templates
+                .get(&*v.owner)
+                .cloned()
+                .or_else(|| {
+                    // A component of the main template that is never executed, e.g. because one of
+                    // its inputs is never assigned
+                    let component = v.owner.last().unwrap();
+                    let component_name = SymbolicName::new(
+                        component.id,
+                        Rc::new(v.owner[..v.owner.len() - 1].to_vec()),
+                        component.access.clone(),
+                    );
+                    sexe.symbolic_store
+                        .components_store
+                        .get(&component_name)
+                        .map(|c| c.template_id)
+                })
+        };
+        let template = match template_id.and_then(|id| library.template_library.get(&id)) {
+            Some(template) => template,
+            None => continue,
+        };
+        let is_signal = matches!(template.id2type.get(&v.id), Some(VariableType::Signal(..)));
+        let is_main_input = v.owner.len() == 1
+            && matches!(
+                template.id2type.get(&v.id),
+                Some(VariableType::Signal(SignalType::Input, _))
+            );
+        if is_signal && !is_main_input && !is_assigned(v, &assigned) {
+            let signal = signal_fmt(v);
+            if reported.insert(signal.clone()) {
+                issues.push(SignalAssignmentIssue {
+                    kind: SignalAssignmentIssueKind::UnassignedRead,
+                    signal: signal,
+                    locations: Vec::new(),
+                    elements: Vec::new(),
+                });
+            }
+        }
+    }
+
+    issues.extend(gather_unassigned_output_elements(
+        sexe,
+        main_template_name,
+        &assigned,
+    ));
+
+    issues.sort_by(|a, b| (a.kind, &a.signal).cmp(&(b.kind, &b.signal)));
+    issues
+}
@@ -0,0 +1,198 @@
+use program_structure::ast::ExpressionInfixOpcode;
+use rustc_hash::{FxHashMap, FxHashSet};
+
+use crate::executor::structural_hash::{Equivalence, StructuralKey};
+use crate::executor::symbolic_value::{
+    extract_variables_from_symbolic_value, SymbolicName, SymbolicValue, SymbolicValueRef,
+};
+
+/// The constraints of a signal, as printed by `zkfuzz explain`.
+pub struct SignalExplanation {
+    /// The trace constraints assigning the signal (`<--`, `<==`, or the result of a function
+    /// call), i.e. how the witness generator computes it, with their indices.
+    pub definitions: Vec<(usize, SymbolicValueRef)>,
+    /// The side constraints in which the signal appears, i.e. what the verifier checks, with
+    /// their indices.
+    pub side_constraints: Vec<(usize, SymbolicValueRef)>,
+    /// The smallest sub-expressions of the definitions that no side constraint of the signal
+    /// contains, e.g. the condition of a `<--` ternary.
+    pub trace_only: Vec<SymbolicValueRef>,
+    /// The smallest sub-expressions of the side constraints that no definition contains.
+    pub side_only: Vec<SymbolicValueRef>,
+}
+
+impl SignalExplanation {
+    /// Returns `true` if no trace or side constraint mentions the signal.
+    pub fn is_empty(&self) -> bool {
+        self.definitions.is_empty() && self.side_constraints.is_empty()
+    }
+}
+
+/// Returns `true` if `name` is `signal` or one of its elements, e.g. `main.out[0]` for
+/// `main.out`.
+fn is_signal(name: &SymbolicName, signal: &str, id2name: &FxHashMap<usize, String>) -> bool {
+    let name = name.lookup_fmt(id2name);
+    name == signal || (name.starts_with(signal) && name[signal.len()..].starts_with('['))
+}
+
+/// Returns the operands of the relation at the root of a constraint, or the constraint itself if
+/// its root is not a relation.
+fn operands(constraint: &SymbolicValueRef) -> Vec<&SymbolicValueRef> {
+    match constraint.as_ref() {
+        SymbolicValue::Assign(lhs, rhs, _, _)
+        | SymbolicValue::AssignEq(lhs, rhs)
+        | SymbolicValue::AssignCall(lhs, rhs, _) => vec![lhs, rhs],
+        SymbolicValue::BinaryOp(lhs, op, rhs) if op.0 == ExpressionInfixOpcode::Eq => {
+            vec![lhs, rhs]
+        }
+        _ => vec![constraint],
+    }
+}
+
+fn children(value: &SymbolicValue) -> Vec<&SymbolicValueRef> {
+    match value {
+        SymbolicValue::Assign(lhs, rhs, _, _)
+        | SymbolicValue::AssignEq(lhs, rhs)
+        | SymbolicValue::AssignTemplParam(lhs, rhs)
+        | SymbolicValue::AssignCall(lhs, rhs, _)
+        | SymbolicValue::BinaryOp(lhs, _, rhs)
+        | SymbolicValue::AuxBinaryOp(lhs, _, rhs)
+        | SymbolicValue::UniformArray(lhs, rhs) => vec![lhs, rhs],
+        SymbolicValue::Conditional(cond, then_value, else_value) => {
+            vec![cond, then_value, else_value]
+        }
+        SymbolicValue::UnaryOp(_, operand) => vec![operand],
+        SymbolicValue::Array(elements) | SymbolicValue::Call(_, elements) => {
+            elements.iter().collect()
+        }
+        SymbolicValue::NOP
+        | SymbolicValue::ConstantInt(_)
+        | SymbolicValue::ConstantBool(_)
+        | SymbolicValue::Variable(_) => Vec::new(),
+    }
+}
+
+/// Operands are compared regardless of their order, so that `a * b` in the trace matches
+/// `b * a` in the side constraints.
+const EQUIVALENCE: Equivalence = Equivalence {
+    ignore_owner_counters: false,
+    ignore_operand_order: true,
+    ignore_owners: false,
+    ignore_array_indices: false,
+};
+
+fn gather_subterms<'a>(
+    value: &'a SymbolicValueRef,
+    id2name: &'a FxHashMap<usize, String>,
+    subterms: &mut FxHashSet<StructuralKey<'a>>,
+) {
+    subterms.insert(StructuralKey::new(value, id2name, EQUIVALENCE));
+    for child in children(value) {
+        gather_subterms(child, id2name, subterms);
+    }
+}
+
+/// Collects the sub-expressions of `value` that are not in `other` but whose operands all are,
+/// and returns whether `value` is in `other`. Constants are considered to be in `other`.
+fn gather_differing_subterms<'a>(
+    value: &'a SymbolicValueRef,
+    id2name: &'a FxHashMap<usize, String>,
+    other: &FxHashSet<StructuralKey<'a>>,
+    seen: &mut FxHashSet<StructuralKey<'a>>,
+    differing: &mut Vec<SymbolicValueRef>,
+) -> bool {
+    let mut are_operands_shared = true;
+    for child in children(value) {
+        are_operands_shared &= gather_differing_subterms(child, id2name, other, seen, differing);
+    }
+    if matches!(
+        value.as_ref(),
+        SymbolicValue::ConstantInt(_) | SymbolicValue::ConstantBool(_)
+    ) {
+        return true;
+    }
+    let key = StructuralKey::new(value, id2name, EQUIVALENCE);
+    let is_shared = other.contains(&key);
+    if !is_shared && are_operands_shared && seen.insert(key) {
+        differing.push(value.clone());
+    }
+    is_shared
+}
+
+/// Returns the sub-expressions of `constraints` that are missing from `other`, see
+/// `gather_differing_subterms`.
+fn differing_subterms<'a>(
+    constraints: &'a [(usize, SymbolicValueRef)],
+    other: &'a [(usize, SymbolicValueRef)],
+    id2name: &'a FxHashMap<usize, String>,
+) -> Vec<SymbolicValueRef> {
+    let mut other_subterms = FxHashSet::default();
+    for (_, constraint) in other {
+        for operand in operands(constraint) {
+            gather_subterms(operand, id2name, &mut other_subterms);
+        }
+    }
+    let mut seen = FxHashSet::default();
+    let mut differing = Vec::new();
+    for (_, constraint) in constraints {
+        for operand in operands(constraint) {
+            gather_differing_subterms(operand, id2name, &other_subterms, &mut seen, &mut differing);
+        }
+    }
+    differing
+}
+
+/// Gathers how the witness generator computes a signal and what the verifier checks about it.
+///
+/// A signal is under-constrained when its definition in the trace relies on sub-expressions
+/// that its side constraints do not pin down, e.g. the condition of `inv <-- in != 0 ? 1 / in :
+/// 0`, so the sub-expressions found on one side only are the first places to look at.
+///
+/// # Parameters
+/// - `symbolic_trace`: The trace constraints.
+/// - `side_constraints`: The side constraints.
+/// - `id2name`: A map from ids to the names of the symbols.
+/// - `signal`: The name of the signal, e.g. `main.out`, which also selects its elements if it
+///   is an array.
+pub fn explain_signal(
+    symbolic_trace: &[SymbolicValueRef],
+    side_constraints: &[SymbolicValueRef],
+    id2name: &FxHashMap<usize, String>,
+    signal: &str,
+) -> SignalExplanation {
+    let definitions: Vec<(usize, SymbolicValueRef)> = symbolic_trace
+        .iter()
+        .enumerate()
+        .filter(|(_, constraint)| match constraint.as_ref() {
+            SymbolicValue::Assign(lhs, _, _, _)
+            | SymbolicValue::AssignEq(lhs, _)
+            | SymbolicValue::AssignCall(lhs, _, _) => match lhs.as_ref() {
+                SymbolicValue::Variable(name) => is_signal(name, signal, id2name),
+                _ => false,
+            },
+            _ => false,
+        })
+        .map(|(i, constraint)| (i, constraint.clone()))
+        .collect();
+    let side_constraints: Vec<(usize, SymbolicValueRef)> = side_constraints
+        .iter()
+        .enumerate()
+        .filter(|(_, constraint)| {
+            let mut variables = FxHashSet::default();
+            extract_variables_from_symbolic_value(constraint, &mut variables);
+            variables
+                .iter()
+                .any(|name| is_signal(name, signal, id2name))
+        })
+        .map(|(i, constraint)| (i, constraint.clone()))
+        .collect();
+
+    let trace_only = differing_subterms(&definitions, &side_constraints, id2name);
+    let side_only = differing_subterms(&side_constraints, &definitions, id2name);
+    SignalExplanation {
+        definitions: definitions,
+        side_constraints: side_constraints,
+        trace_only: trace_only,
+        side_only: side_only,
+    }
+}
@@ -0,0 +1,94 @@
+use rustc_hash::{FxHashMap, FxHashSet};
+
+use crate::executor::constraint_store::ConstraintStore;
+use crate::executor::symbolic_state::{SymbolicConstraints, SymbolicTrace};
+use crate::executor::symbolic_value::{extract_variables_from_symbolic_value, SymbolicName};
+
+/// Determines whether a symbolic name matches the user-specified target signal.
+///
+/// A target without an index (e.g. `main.out`) matches every element of the array, while a
+/// target with an index (e.g. `main.out[3]`) only matches that element.
+fn is_target_signal(
+    name: &SymbolicName,
+    target: &str,
+    lookup: &FxHashMap<usize, String>,
+) -> bool {
+    let formatted = name.lookup_fmt(lookup);
+    formatted == target
+        || (formatted.starts_with(target) && formatted[target.len()..].starts_with('['))
+}
+
+/// Slices the symbolic trace and side constraints to the cone of influence of a target signal.
+///
+/// A constraint belongs to the cone of influence if it shares a variable with the target signal
+/// or, transitively, with another constraint in the cone. The relative order of the remaining
+/// constraints is preserved so that the sliced trace can still be emulated sequentially. The
+/// constraint stores are scanned twice and only the sliced constraints are loaded into memory.
+///
+/// # Parameters
+/// - `symbolic_trace`: The symbolic trace of the whole circuit.
+/// - `side_constraints`: The side constraints of the whole circuit.
+/// - `target`: The name of the target signal, e.g., `main.out[3]`.
+/// - `lookup`: A hash map associating variable IDs with their string representations.
+///
+/// # Returns
+/// `Some((sliced_trace, sliced_side_constraints))`, or `None` if the target signal does not
+/// appear in any constraint.
+pub fn slice_constraints_by_target_signal(
+    symbolic_trace: &dyn ConstraintStore,
+    side_constraints: &dyn ConstraintStore,
+    target: &str,
+    lookup: &FxHashMap<usize, String>,
+) -> Option<(SymbolicTrace, SymbolicConstraints)> {
+    let num_constraints = symbolic_trace.len() + side_constraints.len();
+
+    let mut var2constraints: FxHashMap<SymbolicName, Vec<usize>> = FxHashMap::default();
+    let mut variables_of_constraints = Vec::with_capacity(num_constraints);
+    for (i, c) in symbolic_trace.iter().chain(side_constraints.iter()).enumerate() {
+        let mut variables = FxHashSet::default();
+        extract_variables_from_symbolic_value(&c, &mut variables);
+        for v in variables.iter() {
+            var2constraints.entry(v.clone()).or_default().push(i);
+        }
+        variables_of_constraints.push(variables);
+    }
+
+    let mut worklist: Vec<SymbolicName> = var2constraints
+        .keys()
+        .filter(|v| is_target_signal(v, target, lookup))
+        .cloned()
+        .collect();
+    if worklist.is_empty() {
+        return None;
+    }
+
+    let mut visited: FxHashSet<SymbolicName> = worklist.iter().cloned().collect();
+    let mut is_relevant = vec![false; num_constraints];
+    while let Some(v) = worklist.pop() {
+        for &i in &var2constraints[&v] {
+            if is_relevant[i] {
+                continue;
+            }
+            is_relevant[i] = true;
+            for u in variables_of_constraints[i].iter() {
+                if visited.insert(u.clone()) {
+                    worklist.push(u.clone());
+                }
+            }
+        }
+    }
+
+    let sliced_trace = symbolic_trace
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| is_relevant[*i])
+        .map(|(_, c)| c)
+        .collect();
+    let sliced_side_constraints = side_constraints
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| is_relevant[symbolic_trace.len() + *i])
+        .map(|(_, c)| c)
+        .collect();
+    Some((sliced_trace, sliced_side_constraints))
+}
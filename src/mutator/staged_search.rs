@@ -0,0 +1,465 @@
+use std::time::{Duration, Instant};
+
+use colored::Colorize;
+use num_bigint_dig::BigInt;
+use num_traits::{One, Zero};
+use program_structure::ast::ExpressionInfixOpcode;
+use rustc_hash::{FxHashMap, FxHashSet};
+use serde_json::{json, Value};
+
+use crate::executor::debug_ast::DebuggableExpressionInfixOpcode;
+use crate::executor::symbolic_execution::SymbolicExecutor;
+use crate::executor::symbolic_value::{
+    extract_variables, extract_variables_from_symbolic_value, val_for_relational_operators,
+    SymbolicLibrary, SymbolicName, SymbolicValue, SymbolicValueRef,
+};
+use crate::executor::utils::modreduce;
+use crate::mutator::assign_oracle::pair_assignments_with_constraints;
+use crate::mutator::brute_force::brute_force_search_until;
+use crate::mutator::input_spec::InputDomain;
+use crate::mutator::utils::{evaluate_symbolic_value, BaseVerificationConfig, CounterExample};
+use crate::stats::symbolic_stats::{first_signal_owner, owner_fmt};
+
+/// The stages of `--search_mode heuristics` run by default, in order.
+pub const DEFAULT_HEURISTICS_STAGES: &str = "propagate,targeted,full";
+
+/// A stage of `--search_mode heuristics`.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Stage {
+    /// Enumerates the heuristics range, with the variables fixed by constant propagation and
+    /// bounded by the comparisons of the side constraints restricted accordingly.
+    Propagate,
+    /// Enumerates the heuristics range for the variables of the suspicious constraints only,
+    /// fixing every other variable.
+    Targeted,
+    /// Enumerates the heuristics range for every variable.
+    Full,
+}
+
+impl Stage {
+    /// The name of the stage in `--heuristics_stages`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Stage::Propagate => "propagate",
+            Stage::Targeted => "targeted",
+            Stage::Full => "full",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Stage> {
+        match name {
+            "propagate" => Some(Stage::Propagate),
+            "targeted" => Some(Stage::Targeted),
+            "full" => Some(Stage::Full),
+            _ => None,
+        }
+    }
+}
+
+/// A stage with the time it may run for.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct StageConfig {
+    pub stage: Stage,
+    pub timeout: Option<Duration>,
+}
+
+/// Parses `--heuristics_stages`, a comma-separated list of stages run in the given order, each
+/// optionally followed by `:<seconds>` to stop it after that many seconds, e.g.
+/// `propagate:10,targeted:60,full`.
+pub fn parse_stages(spec: &str) -> Result<Vec<StageConfig>, String> {
+    let mut stages: Vec<StageConfig> = Vec::new();
+    for entry in spec.split(',').map(|e| e.trim()).filter(|e| !e.is_empty()) {
+        let (name, timeout) = match entry.split_once(':') {
+            Some((name, secs)) => match secs.trim().parse::<u64>() {
+                Ok(secs) if secs > 0 => (name.trim(), Some(Duration::from_secs(secs))),
+                _ => return Err(format!("invalid time limit `{}` of stage `{}`", secs, name)),
+            },
+            None => (entry, None),
+        };
+        let stage = Stage::from_name(name).ok_or_else(|| {
+            format!(
+                "unknown stage `{}` (expected propagate, targeted, or full)",
+                name
+            )
+        })?;
+        if stages.iter().any(|s| s.stage == stage) {
+            return Err(format!("stage `{}` is listed twice", name));
+        }
+        stages.push(StageConfig {
+            stage: stage,
+            timeout: timeout,
+        });
+    }
+    if stages.is_empty() {
+        return Err("no stage is listed".to_string());
+    }
+    Ok(stages)
+}
+
+/// Returns `(v, op, c)` if `constraint` compares the variable `v` with the constant `c`, with the
+/// operator flipped if the constant is on the left.
+fn as_bound(constraint: &SymbolicValue) -> Option<(&SymbolicName, ExpressionInfixOpcode, &BigInt)> {
+    if let SymbolicValue::BinaryOp(lhs, DebuggableExpressionInfixOpcode(op), rhs) = constraint {
+        match (&**lhs, &**rhs) {
+            (SymbolicValue::Variable(v), SymbolicValue::ConstantInt(c)) => Some((v, *op, c)),
+            (SymbolicValue::ConstantInt(c), SymbolicValue::Variable(v)) => {
+                let flipped = match op {
+                    ExpressionInfixOpcode::Lesser => ExpressionInfixOpcode::Greater,
+                    ExpressionInfixOpcode::Greater => ExpressionInfixOpcode::Lesser,
+                    ExpressionInfixOpcode::LesserEq => ExpressionInfixOpcode::GreaterEq,
+                    ExpressionInfixOpcode::GreaterEq => ExpressionInfixOpcode::LesserEq,
+                    op => *op,
+                };
+                Some((v, flipped, c))
+            }
+            _ => None,
+        }
+    } else {
+        None
+    }
+}
+
+/// Derives the values of the variables that the side constraints fix or bound.
+///
+/// A variable equated to an expression whose variables are all fixed is fixed to the value of
+/// the expression, until no more variable can be fixed. A variable compared to a constant with
+/// `<`, `<=`, `>`, or `>=` is bounded, in the signed representation used by circom, and its
+/// domain is the part of `[-range, range]` within the bounds.
+///
+/// Every assignment satisfying the side constraints assigns these values, so a search over them
+/// misses no under-constrained witness.
+///
+/// # Parameters
+/// - `symbolic_library`: The symbolic library containing the template definitions.
+/// - `side_constraints`: A vector of additional constraints for validation.
+/// - `base_config`: The verification configuration specifying the prime and the range.
+///
+/// # Returns
+/// The domain of each fixed or bounded variable; an empty domain means that the bounds
+/// contradict each other within the range.
+pub fn propagate_side_constraints(
+    symbolic_library: &mut SymbolicLibrary,
+    side_constraints: &[SymbolicValueRef],
+    base_config: &BaseVerificationConfig,
+) -> FxHashMap<SymbolicName, InputDomain> {
+    let prime = &base_config.prime;
+    let mut fixed: FxHashMap<SymbolicName, BigInt> = FxHashMap::default();
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for c in side_constraints {
+            let (lhs, rhs) = match &**c {
+                SymbolicValue::AssignEq(lhs, rhs)
+                | SymbolicValue::BinaryOp(
+                    lhs,
+                    DebuggableExpressionInfixOpcode(ExpressionInfixOpcode::Eq),
+                    rhs,
+                ) => (lhs, rhs),
+                _ => continue,
+            };
+            for (target, expr) in [(lhs, rhs), (rhs, lhs)] {
+                let var = match &**target {
+                    SymbolicValue::Variable(var) if !fixed.contains_key(var) => var,
+                    _ => continue,
+                };
+                let mut variables = FxHashSet::default();
+                extract_variables_from_symbolic_value(expr, &mut variables);
+                if !variables.iter().all(|v| fixed.contains_key(v)) {
+                    continue;
+                }
+                if let Some(SymbolicValue::ConstantInt(value)) =
+                    evaluate_symbolic_value(prime, expr, &fixed, symbolic_library)
+                {
+                    fixed.insert(var.clone(), modreduce(&value, prime));
+                    changed = true;
+                }
+            }
+        }
+    }
+
+    let mut bounds: FxHashMap<SymbolicName, (BigInt, BigInt)> = FxHashMap::default();
+    for c in side_constraints {
+        let (var, op, bound) = match as_bound(c) {
+            Some(b) if !fixed.contains_key(b.0) => b,
+            _ => continue,
+        };
+        let (min, max) = bounds
+            .entry(var.clone())
+            .or_insert_with(|| (-&base_config.range, base_config.range.clone()));
+        let bound = val_for_relational_operators(&modreduce(bound, prime), prime);
+        let (new_min, new_max) = match op {
+            ExpressionInfixOpcode::Lesser => (None, Some(bound - BigInt::one())),
+            ExpressionInfixOpcode::LesserEq => (None, Some(bound)),
+            ExpressionInfixOpcode::Greater => (Some(bound + BigInt::one()), None),
+            ExpressionInfixOpcode::GreaterEq => (Some(bound), None),
+            _ => (None, None),
+        };
+        if let Some(new_min) = new_min.filter(|new_min| *new_min > *min) {
+            *min = new_min;
+        }
+        if let Some(new_max) = new_max.filter(|new_max| *new_max < *max) {
+            *max = new_max;
+        }
+    }
+
+    let mut domains: FxHashMap<SymbolicName, InputDomain> = fixed
+        .into_iter()
+        .map(|(var, value)| (var, InputDomain::Values(vec![value])))
+        .collect();
+    for (var, (min, max)) in bounds {
+        let is_bounded = min > -&base_config.range || max < base_config.range;
+        if min > max {
+            domains.insert(var, InputDomain::Values(Vec::new()));
+        } else if is_bounded {
+            domains.insert(var, InputDomain::Range(min, max));
+        }
+    }
+    domains
+}
+
+/// Gathers the variables of the constraints flagged as suspicious by the static analyses.
+///
+/// A side constraint is suspicious if it is owned by one of `suspicious_components`, i.e., a
+/// component whose compression rate is anomalous, or if it checks a signal assigned with `<--`,
+/// whose value is not enforced by the assignment. A signal assigned with `<--` that no side
+/// constraint checks is suspicious as well.
+///
+/// # Parameters
+/// - `symbolic_trace`: A vector of constraints representing the program trace.
+/// - `side_constraints`: A vector of additional constraints for validation.
+/// - `suspicious_components`: The formatted owner names of the anomalous components.
+/// - `id2name`: The mapping used to format the owner names.
+pub fn gather_suspicious_variables(
+    symbolic_trace: &[SymbolicValueRef],
+    side_constraints: &[SymbolicValueRef],
+    suspicious_components: &[String],
+    id2name: &FxHashMap<usize, String>,
+) -> FxHashSet<SymbolicName> {
+    let mut variables = FxHashSet::default();
+    for c in side_constraints {
+        let owner = first_signal_owner(c).map(|owner| owner_fmt(owner, id2name));
+        if owner.map_or(false, |owner| suspicious_components.contains(&owner)) {
+            extract_variables_from_symbolic_value(c, &mut variables);
+        }
+    }
+    for pair in pair_assignments_with_constraints(symbolic_trace, side_constraints) {
+        variables.insert(pair.signal.clone());
+        for c in &pair.constraints {
+            extract_variables_from_symbolic_value(c, &mut variables);
+        }
+    }
+    variables
+}
+
+/// How a stage of `--search_mode heuristics` ended.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum StageStatus {
+    /// The stage found the counterexample.
+    Found,
+    /// The stage enumerated all its candidates without finding a counterexample.
+    Exhausted,
+    /// The stage was stopped at its time limit.
+    TimedOut,
+    /// The stage had nothing to search beyond the following stages, e.g. `propagate` when the
+    /// side constraints neither fix nor bound any variable.
+    Skipped,
+}
+
+impl StageStatus {
+    pub fn name(&self) -> &'static str {
+        match self {
+            StageStatus::Found => "found",
+            StageStatus::Exhausted => "exhausted",
+            StageStatus::TimedOut => "timed out",
+            StageStatus::Skipped => "skipped",
+        }
+    }
+}
+
+/// How a stage fared.
+pub struct StageReport {
+    pub stage: Stage,
+    pub timeout: Option<Duration>,
+    pub elapsed: Duration,
+    /// The number of variables whose candidates the stage restricted.
+    pub num_restricted: usize,
+    pub status: StageStatus,
+}
+
+pub struct StagedSearchResult {
+    pub stages: Vec<StageReport>,
+    /// The stage that found `counter_example`.
+    pub winner: Option<Stage>,
+    pub counter_example: Option<CounterExample>,
+}
+
+impl StagedSearchResult {
+    pub fn to_json(&self) -> Value {
+        json!({
+            "stages": self.stages.iter().map(|s| json!({
+                "stage": s.stage.name(),
+                "timeout_secs": s.timeout.map(|t| t.as_secs()),
+                "elapsed_secs": s.elapsed.as_secs_f64(),
+                "num_restricted": s.num_restricted,
+                "status": s.status.name(),
+            })).collect::<Vec<_>>(),
+            "winner": self.winner.map(|s| s.name()),
+        })
+    }
+}
+
+/// Runs the stages of `--search_mode heuristics` in turn until one finds a counterexample.
+///
+/// Each stage is a brute-force search over the ranges of the variables (see `SearchRanges`)
+/// whose candidates are restricted by a static analysis: `propagate` by the values that the side
+/// constraints fix or bound (see `propagate_side_constraints`), and `targeted` to the variables
+/// of the suspicious constraints (see `gather_suspicious_variables`), every other variable taking
+/// its propagated value or `0`. `full` restricts nothing. A stage that would search the same
+/// candidates as `full` is skipped.
+///
+/// # Parameters
+/// - `sexe`: A mutable reference to the concrete executor.
+/// - `symbolic_trace`: A vector of constraints representing the program trace.
+/// - `side_constraints`: A vector of additional constraints for validation.
+/// - `base_config`: The verification base_configs.
+/// - `stages`: The stages to run, in order, with their time limits.
+/// - `suspicious_components`: The formatted owner names of the components whose compression rate
+///   is anomalous.
+///
+/// # Returns
+/// A `StagedSearchResult` with the counterexample, the stage that found it, and how each stage
+/// fared.
+pub fn staged_search(
+    sexe: &mut SymbolicExecutor,
+    symbolic_trace: &Vec<SymbolicValueRef>,
+    side_constraints: &Vec<SymbolicValueRef>,
+    base_config: &BaseVerificationConfig,
+    stages: &[StageConfig],
+    suspicious_components: &[String],
+) -> StagedSearchResult {
+    let mut config = base_config.clone();
+    config.quick_mode = false;
+    config.heuristics_mode = true;
+
+    let propagated = propagate_side_constraints(sexe.symbolic_library, side_constraints, &config);
+    let suspicious = gather_suspicious_variables(
+        symbolic_trace,
+        side_constraints,
+        suspicious_components,
+        &sexe.symbolic_library.id2name,
+    );
+    let mut constraints = symbolic_trace.clone();
+    constraints.extend(side_constraints.iter().cloned());
+    let variables = extract_variables(&constraints);
+
+    outln!(
+        "{} {}",
+        "🪜 Stages:".green(),
+        stages
+            .iter()
+            .map(|s| match s.timeout {
+                Some(timeout) => format!("{} ({}s)", s.stage.name(), timeout.as_secs()),
+                None => s.stage.name().to_string(),
+            })
+            .collect::<Vec<_>>()
+            .join(" → ")
+    );
+
+    let mut reports = Vec::new();
+    let mut winner = None;
+    let mut counter_example = None;
+    for stage_config in stages {
+        let domains: FxHashMap<SymbolicName, InputDomain> = match stage_config.stage {
+            Stage::Propagate => propagated.clone(),
+            Stage::Targeted if suspicious.is_empty() => FxHashMap::default(),
+            Stage::Targeted => variables
+                .iter()
+                .filter(|v| !suspicious.contains(v))
+                .map(|v| {
+                    let domain = propagated
+                        .get(v)
+                        .cloned()
+                        .unwrap_or_else(|| InputDomain::Values(vec![BigInt::zero()]));
+                    (v.clone(), domain)
+                })
+                .collect(),
+            Stage::Full => FxHashMap::default(),
+        };
+        if stage_config.stage != Stage::Full && domains.is_empty() {
+            reports.push(StageReport {
+                stage: stage_config.stage,
+                timeout: stage_config.timeout,
+                elapsed: Duration::ZERO,
+                num_restricted: 0,
+                status: StageStatus::Skipped,
+            });
+            continue;
+        }
+
+        outln!(
+            "{} {} ({} variable(s) restricted)",
+            "🪜 Stage:".green(),
+            stage_config.stage.name().bright_yellow(),
+            domains.len()
+        );
+        let start = Instant::now();
+        let deadline = stage_config.timeout.map(|timeout| start + timeout);
+        let found = brute_force_search_until(
+            sexe,
+            symbolic_trace,
+            side_constraints,
+            &config,
+            &domains,
+            deadline,
+        );
+        let status = if found.is_some() {
+            StageStatus::Found
+        } else if deadline.map_or(false, |d| Instant::now() >= d) {
+            StageStatus::TimedOut
+        } else {
+            StageStatus::Exhausted
+        };
+        reports.push(StageReport {
+            stage: stage_config.stage,
+            timeout: stage_config.timeout,
+            elapsed: start.elapsed(),
+            num_restricted: domains.len(),
+            status: status,
+        });
+        if found.is_some() {
+            winner = Some(stage_config.stage);
+            counter_example = found;
+            break;
+        }
+    }
+
+    StagedSearchResult {
+        stages: reports,
+        winner: winner,
+        counter_example: counter_example,
+    }
+}
+
+/// Prints how each stage of `--search_mode heuristics` fared and the one that found the
+/// counterexample.
+pub fn print_staged_search_result(result: &StagedSearchResult) {
+    outln!("{}", "🪜 Stage Summary:".green());
+    for report in &result.stages {
+        outln!(
+            "  ├─ {:<10} : {:.2}s{} ({}, {} variable(s) restricted)",
+            report.stage.name(),
+            report.elapsed.as_secs_f64(),
+            report
+                .timeout
+                .map_or(String::new(), |t| format!(" / {}s", t.as_secs())),
+            report.status.name(),
+            report.num_restricted
+        );
+    }
+    outln!(
+        "  └─ Found by   : {}",
+        result
+            .winner
+            .map_or("none".normal(), |s| s.name().bright_yellow())
+    );
+}
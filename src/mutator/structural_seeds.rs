@@ -0,0 +1,347 @@
+use num_bigint_dig::BigInt;
+use num_traits::{One, Zero};
+use program_structure::ast::{ExpressionInfixOpcode, ExpressionPrefixOpcode};
+use rustc_hash::{FxHashMap, FxHashSet};
+
+use crate::executor::symbolic_value::{
+    extract_variables_from_symbolic_value, SymbolicAccess, SymbolicLibrary, SymbolicName,
+    SymbolicValue, SymbolicValueRef,
+};
+use crate::executor::utils::{modpow, modreduce};
+use crate::mutator::input_spec::InputDomain;
+use crate::mutator::utils::{gather_input_domains, BaseVerificationConfig};
+
+/// Maximum number of seeds kept for each input.
+const MAX_SEEDS_PER_VARIABLE: usize = 16;
+/// Maximum number of seeds shared by all the inputs.
+const MAX_SHARED_SEEDS: usize = 16;
+
+fn is_comparison(op: &ExpressionInfixOpcode) -> bool {
+    matches!(
+        op,
+        ExpressionInfixOpcode::Eq
+            | ExpressionInfixOpcode::NotEq
+            | ExpressionInfixOpcode::LesserEq
+            | ExpressionInfixOpcode::GreaterEq
+            | ExpressionInfixOpcode::Lesser
+            | ExpressionInfixOpcode::Greater
+    )
+}
+
+fn is_zero_mod(value: &SymbolicValue, prime: &BigInt) -> bool {
+    matches!(value, SymbolicValue::ConstantInt(c) if (c % prime).is_zero())
+}
+
+/// Returns `(a, b)` such that `value` is `a * x + b` modulo `prime`, where `x` is the only
+/// variable of `value`, or `None` if `value` is not linear in `x`.
+fn linear_coefficients(value: &SymbolicValue, prime: &BigInt) -> Option<(BigInt, BigInt)> {
+    match value {
+        SymbolicValue::ConstantInt(c) => Some((BigInt::zero(), c % prime)),
+        SymbolicValue::Variable(_) => Some((BigInt::one(), BigInt::zero())),
+        SymbolicValue::BinaryOp(lhs, op, rhs) | SymbolicValue::AuxBinaryOp(lhs, op, rhs) => {
+            let (a1, b1) = linear_coefficients(lhs, prime)?;
+            let (a2, b2) = linear_coefficients(rhs, prime)?;
+            match op.0 {
+                ExpressionInfixOpcode::Add => Some(((a1 + a2) % prime, (b1 + b2) % prime)),
+                ExpressionInfixOpcode::Sub => Some(((a1 - a2) % prime, (b1 - b2) % prime)),
+                ExpressionInfixOpcode::Mul if a1.is_zero() => {
+                    Some(((&b1 * a2) % prime, (&b1 * b2) % prime))
+                }
+                ExpressionInfixOpcode::Mul if a2.is_zero() => {
+                    Some(((a1 * &b2) % prime, (b1 * &b2) % prime))
+                }
+                _ => None,
+            }
+        }
+        SymbolicValue::UnaryOp(op, expr) => match op.0 {
+            ExpressionPrefixOpcode::Sub => {
+                let (a, b) = linear_coefficients(expr, prime)?;
+                Some((-a, -b))
+            }
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Returns the root of `a * x + b` modulo `prime`, if `a` is not zero.
+fn linear_root(a: &BigInt, b: &BigInt, prime: &BigInt) -> Option<BigInt> {
+    let a = modreduce(a, prime);
+    if a.is_zero() {
+        return None;
+    }
+    let inverse = modpow(&a, &(prime - BigInt::from(2)), prime);
+    Some(modreduce(&(-b * inverse), prime))
+}
+
+/// Returns the roots of `value == 0` for a product of linear factors in a single variable, e.g.
+/// `0` and `1` for `x * (x - 1)`.
+fn roots_of_product(value: &SymbolicValue, prime: &BigInt) -> Vec<BigInt> {
+    match value {
+        SymbolicValue::BinaryOp(lhs, op, rhs) | SymbolicValue::AuxBinaryOp(lhs, op, rhs)
+            if op.0 == ExpressionInfixOpcode::Mul
+                && !matches!(lhs.as_ref(), SymbolicValue::ConstantInt(_))
+                && !matches!(rhs.as_ref(), SymbolicValue::ConstantInt(_)) =>
+        {
+            let mut roots = roots_of_product(lhs, prime);
+            roots.extend(roots_of_product(rhs, prime));
+            roots
+        }
+        _ => linear_coefficients(value, prime)
+            .and_then(|(a, b)| linear_root(&a, &b, prime))
+            .into_iter()
+            .collect(),
+    }
+}
+
+/// Values derived from the structure of the circuit, at which bugs are often triggered, used to
+/// seed the initial population of the genetic search and the candidates of the brute-force
+/// search.
+///
+/// The seeds of an input are:
+/// - the constants it is compared against, together with their neighbours (`c - 1`, `c`, and
+///   `c + 1` for `x < c`), i.e., the boundary values of the ranges inferred from the comparisons;
+/// - the last index and the size of the dimensions of the input arrays it indexes;
+/// - the bounds of its domain declared with `--input_spec`;
+/// - the roots of the constraints in which it is the only variable, when they are products of
+///   linear factors (e.g. `0` and `1` for `x * (x - 1) === 0`).
+///
+/// The dimensions of the input arrays and the constants compared against other signals are
+/// shared by all the inputs.
+pub struct StructuralSeeds {
+    prime: BigInt,
+    pub per_variable: FxHashMap<SymbolicName, Vec<BigInt>>,
+    pub shared: Vec<BigInt>,
+}
+
+impl StructuralSeeds {
+    /// Extracts the seeds of the inputs of the main template from the constraints.
+    ///
+    /// # Parameters
+    /// - `symbolic_trace`: The symbolic trace.
+    /// - `side_constraints`: The side constraints.
+    /// - `symbolic_library`: The symbolic library containing the template definitions.
+    /// - `base_config`: The verification configuration specifying the target template, the prime,
+    ///   and the shapes of the inputs.
+    /// - `input_variables`: The inputs of the main template assigned by the search.
+    pub fn gather(
+        symbolic_trace: &[SymbolicValueRef],
+        side_constraints: &[SymbolicValueRef],
+        symbolic_library: &SymbolicLibrary,
+        base_config: &BaseVerificationConfig,
+        input_variables: &[SymbolicName],
+    ) -> Self {
+        let inputs: FxHashSet<SymbolicName> = input_variables.iter().cloned().collect();
+        let mut seeds = StructuralSeeds {
+            prime: base_config.prime.clone(),
+            per_variable: FxHashMap::default(),
+            shared: Vec::new(),
+        };
+        for constraint in symbolic_trace.iter().chain(side_constraints.iter()) {
+            seeds.visit(constraint, &inputs, base_config);
+            seeds.add_roots(constraint, &inputs);
+        }
+        for shape in base_config.input_shapes.values() {
+            for dim in shape {
+                seeds.add_shared(BigInt::from(*dim) - BigInt::one());
+                seeds.add_shared(BigInt::from(*dim));
+            }
+        }
+        for (var, domain) in gather_input_domains(symbolic_library, base_config, input_variables) {
+            if let InputDomain::Range(min, max) = domain {
+                seeds.add(&var, min);
+                seeds.add(&var, max);
+            }
+        }
+        seeds
+    }
+
+    fn normalize(&self, value: BigInt) -> BigInt {
+        modreduce(&value, &self.prime)
+    }
+
+    fn add(&mut self, var: &SymbolicName, value: BigInt) {
+        let value = self.normalize(value);
+        let values = self.per_variable.entry(var.clone()).or_default();
+        if values.len() < MAX_SEEDS_PER_VARIABLE && !values.contains(&value) {
+            values.push(value);
+        }
+    }
+
+    fn add_shared(&mut self, value: BigInt) {
+        let value = self.normalize(value);
+        if self.shared.len() < MAX_SHARED_SEEDS && !self.shared.contains(&value) {
+            self.shared.push(value);
+        }
+    }
+
+    fn add_boundary(
+        &mut self,
+        value: &SymbolicValue,
+        constant: &BigInt,
+        inputs: &FxHashSet<SymbolicName>,
+    ) {
+        let mut variables = FxHashSet::default();
+        extract_variables_from_symbolic_value(value, &mut variables);
+        let compared_inputs: Vec<SymbolicName> = variables
+            .into_iter()
+            .filter(|v| inputs.contains(v))
+            .collect();
+        let constant = self.normalize(constant.clone());
+        if compared_inputs.is_empty() {
+            self.add_shared(constant);
+            return;
+        }
+        for var in compared_inputs {
+            self.add(&var, &constant - BigInt::one());
+            self.add(&var, constant.clone());
+            self.add(&var, &constant + BigInt::one());
+        }
+    }
+
+    fn visit(
+        &mut self,
+        value: &SymbolicValue,
+        inputs: &FxHashSet<SymbolicName>,
+        base_config: &BaseVerificationConfig,
+    ) {
+        match value {
+            SymbolicValue::BinaryOp(lhs, op, rhs) | SymbolicValue::AuxBinaryOp(lhs, op, rhs) => {
+                if is_comparison(&op.0) {
+                    if let SymbolicValue::ConstantInt(c) = rhs.as_ref() {
+                        self.add_boundary(lhs, c, inputs);
+                    }
+                    if let SymbolicValue::ConstantInt(c) = lhs.as_ref() {
+                        self.add_boundary(rhs, c, inputs);
+                    }
+                }
+                self.visit(lhs, inputs, base_config);
+                self.visit(rhs, inputs, base_config);
+            }
+            SymbolicValue::Variable(name) => {
+                // An input indexing an input array is seeded with the bounds of its dimension
+                let shape = match base_config.input_shapes.get(&name.id) {
+                    Some(shape) if name.owner.len() == 1 => shape,
+                    _ => return,
+                };
+                for (access, dim) in name.access.iter().flatten().zip(shape.iter()) {
+                    if let SymbolicAccess::ArrayAccess(index) = access {
+                        let mut variables = FxHashSet::default();
+                        extract_variables_from_symbolic_value(index, &mut variables);
+                        for var in variables.iter().filter(|v| inputs.contains(v)) {
+                            self.add(var, BigInt::from(*dim) - BigInt::one());
+                            self.add(var, BigInt::from(*dim));
+                        }
+                    }
+                }
+            }
+            SymbolicValue::Assign(lhs, rhs, _, _)
+            | SymbolicValue::AssignEq(lhs, rhs)
+            | SymbolicValue::AssignTemplParam(lhs, rhs)
+            | SymbolicValue::AssignCall(lhs, rhs, _)
+            | SymbolicValue::UniformArray(lhs, rhs) => {
+                self.visit(lhs, inputs, base_config);
+                self.visit(rhs, inputs, base_config);
+            }
+            SymbolicValue::Conditional(cond, then_val, else_val) => {
+                self.visit(cond, inputs, base_config);
+                self.visit(then_val, inputs, base_config);
+                self.visit(else_val, inputs, base_config);
+            }
+            SymbolicValue::UnaryOp(_, expr) => self.visit(expr, inputs, base_config),
+            SymbolicValue::Array(elements) | SymbolicValue::Call(_, elements) => {
+                for elem in elements {
+                    self.visit(elem, inputs, base_config);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn add_roots(&mut self, constraint: &SymbolicValue, inputs: &FxHashSet<SymbolicName>) {
+        let (lhs, rhs) = match constraint {
+            SymbolicValue::BinaryOp(lhs, op, rhs) if op.0 == ExpressionInfixOpcode::Eq => {
+                (lhs, rhs)
+            }
+            _ => return,
+        };
+        let mut variables = FxHashSet::default();
+        extract_variables_from_symbolic_value(constraint, &mut variables);
+        let var = match variables.into_iter().collect::<Vec<_>>().as_slice() {
+            [var] if inputs.contains(var) => var.clone(),
+            _ => return,
+        };
+
+        let roots = if is_zero_mod(rhs, &self.prime) {
+            roots_of_product(lhs, &self.prime)
+        } else if is_zero_mod(lhs, &self.prime) {
+            roots_of_product(rhs, &self.prime)
+        } else {
+            match (
+                linear_coefficients(lhs, &self.prime),
+                linear_coefficients(rhs, &self.prime),
+            ) {
+                (Some((a1, b1)), Some((a2, b2))) => {
+                    linear_root(&(a1 - a2), &(b1 - b2), &self.prime)
+                        .into_iter()
+                        .collect()
+                }
+                _ => Vec::new(),
+            }
+        };
+        for root in roots {
+            self.add(&var, root);
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.shared.is_empty() && self.per_variable.values().all(|values| values.is_empty())
+    }
+
+    /// Returns the seeds of `var` other than the shared ones.
+    pub fn candidates_of(&self, var: &SymbolicName) -> &[BigInt] {
+        self.per_variable
+            .get(var)
+            .map(|values| values.as_slice())
+            .unwrap_or(&[])
+    }
+
+    /// Returns the seeds of `var`, followed by the shared seeds.
+    pub fn values_of(&self, var: &SymbolicName) -> Vec<BigInt> {
+        let mut values = self.candidates_of(var).to_vec();
+        for value in &self.shared {
+            if !values.contains(value) {
+                values.push(value.clone());
+            }
+        }
+        values
+    }
+
+    /// Combines the seeds of `variables` into at most `max_assignments` partial assignments, the
+    /// `j`-th of which assigns the `j`-th seed of each variable (wrapping around its seeds).
+    pub fn assignments(
+        &self,
+        variables: &[SymbolicName],
+        max_assignments: usize,
+    ) -> Vec<FxHashMap<SymbolicName, BigInt>> {
+        let seeded: Vec<(&SymbolicName, Vec<BigInt>)> = variables
+            .iter()
+            .map(|var| (var, self.values_of(var)))
+            .filter(|(_, values)| !values.is_empty())
+            .collect();
+        let num_assignments = seeded
+            .iter()
+            .map(|(_, values)| values.len())
+            .max()
+            .unwrap_or(0)
+            .min(max_assignments);
+        (0..num_assignments)
+            .map(|j| {
+                seeded
+                    .iter()
+                    .map(|(var, values)| ((*var).clone(), values[j % values.len()].clone()))
+                    .collect()
+            })
+            .collect()
+    }
+}
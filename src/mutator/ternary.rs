@@ -0,0 +1,116 @@
+use program_structure::ast::ExpressionInfixOpcode;
+use program_structure::file_definition::FileLibrary;
+use rustc_hash::FxHashMap;
+
+use crate::executor::debug_ast::DebuggableExpressionInfixOpcode;
+use crate::executor::symbolic_execution::SymbolicExecutor;
+use crate::executor::symbolic_value::SymbolicValue;
+use crate::executor::utils::strip_ansi;
+use crate::mutator::division::{gather_statement_metas, statement_location};
+
+/// A `<==` assignment whose right-hand side selects a value with a ternary (`c ? a : b`) whose
+/// condition is not a constant.
+///
+/// circom only emits a constraint for a quadratic expression, and a selection by a
+/// signal-dependent condition is not one, so such an assignment leaves its signal
+/// under-constrained unless it is rewritten as an arithmetic selection.
+pub struct SymbolicTernarySite {
+    pub owner_name: String,
+    pub location: String,
+    pub condition: String,
+    /// The equivalent pattern whose selection is enforced by the constraints.
+    pub suggestion: String,
+}
+
+/// Suggests a constrained rewrite of `c ? a : b` for the condition `c`.
+///
+/// A condition that is a signal is used as a selector once it is constrained to be binary, and a
+/// comparison is first computed as a binary signal by a circomlib comparator.
+pub fn suggest_ternary_rewrite(
+    condition: &SymbolicValue,
+    lookup: &FxHashMap<usize, String>,
+) -> String {
+    let fmt = |value: &SymbolicValue| strip_ansi(&value.lookup_fmt(lookup));
+    let select = "then select with `s * (a - b) + b` instead of `... ? a : b`";
+    match condition {
+        SymbolicValue::Variable(_) => {
+            let c = fmt(condition);
+            format!(
+                "constrain `{c}` to be binary with `{c} * ({c} - 1) === 0` and select with `{c} * (a - b) + b` instead of `{c} ? a : b`",
+                c = c
+            )
+        }
+        SymbolicValue::BinaryOp(lhs, DebuggableExpressionInfixOpcode(op), rhs) => match op {
+            ExpressionInfixOpcode::Eq => format!(
+                "compute `s <== IsEqual()([{}, {}])`, {}",
+                fmt(lhs),
+                fmt(rhs),
+                select
+            ),
+            ExpressionInfixOpcode::NotEq => format!(
+                "compute `s <== 1 - IsEqual()([{}, {}])`, {}",
+                fmt(lhs),
+                fmt(rhs),
+                select
+            ),
+            ExpressionInfixOpcode::Lesser
+            | ExpressionInfixOpcode::Greater
+            | ExpressionInfixOpcode::LesserEq
+            | ExpressionInfixOpcode::GreaterEq => format!(
+                "compute `s` with a comparator such as `{}(n)([{}, {}])` after range-checking both operands, {}",
+                match op {
+                    ExpressionInfixOpcode::Lesser => "LessThan",
+                    ExpressionInfixOpcode::Greater => "GreaterThan",
+                    ExpressionInfixOpcode::LesserEq => "LessEqThan",
+                    _ => "GreaterEqThan",
+                },
+                fmt(lhs),
+                fmt(rhs),
+                select
+            ),
+            _ => format!(
+                "compute the condition as a signal `s` with `s * (s - 1) === 0`, {}",
+                select
+            ),
+        },
+        _ => format!(
+            "compute the condition as a signal `s` with `s * (s - 1) === 0`, {}",
+            select
+        ),
+    }
+}
+
+/// Lists the `<==` assignments recorded during symbolic execution whose right-hand side contains
+/// a ternary with a non-constant condition.
+///
+/// # Parameters
+/// - `sexe`: The symbolic executor after the execution of the main template.
+/// - `file_library`: The file library used to resolve the source locations of the assignments.
+///
+/// # Returns
+/// A vector of `SymbolicTernarySite`s sorted by their owner and location.
+pub fn gather_symbolic_ternaries(
+    sexe: &SymbolicExecutor,
+    file_library: &FileLibrary,
+) -> Vec<SymbolicTernarySite> {
+    let lookup = &sexe.symbolic_library.id2name;
+    let metas = gather_statement_metas(sexe.symbolic_library);
+    let mut sites: Vec<_> = sexe
+        .symbolic_ternaries
+        .iter()
+        .map(|(elem_id, (owner_id, condition))| SymbolicTernarySite {
+            owner_name: lookup.get(owner_id).cloned().unwrap_or_default(),
+            location: statement_location(
+                sexe.symbolic_library,
+                &metas,
+                file_library,
+                *elem_id,
+                *owner_id,
+            ),
+            condition: strip_ansi(&condition.lookup_fmt(lookup)),
+            suggestion: suggest_ternary_rewrite(condition, lookup),
+        })
+        .collect();
+    sites.sort_by(|a, b| (&a.owner_name, &a.location).cmp(&(&b.owner_name, &b.location)));
+    sites
+}
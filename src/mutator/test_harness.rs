@@ -0,0 +1,327 @@
+use std::str::FromStr;
+
+use num_bigint_dig::BigInt;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use rustc_hash::FxHashMap;
+use serde_json::Value;
+
+use crate::executor::symbolic_execution::SymbolicExecutor;
+use crate::executor::symbolic_value::SymbolicValueRef;
+use crate::executor::utils::modreduce;
+use crate::mutator::assign_oracle::are_satisfied;
+use crate::mutator::differential_testing::{
+    draw_input_assignment, flatten_main_signals, gather_tagged_input_domains, run_zkfuzz,
+};
+use crate::mutator::utils::{emulate_symbolic_trace, BaseVerificationConfig};
+use crate::mutator::witness_export::{build_input_json, build_output_json};
+
+/// The number of inputs drawn per requested test case before giving up, since the drawn inputs
+/// that a circuit rejects (e.g. with an `assert`) are discarded.
+const MAX_ATTEMPTS_PER_CASE: usize = 20;
+
+/// The language of a test harness generated by `zkfuzz gen-tests` (`--lang`).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HarnessLanguage {
+    /// A mocha test suite driving the witness calculator of `circom --wasm` with `circom_tester`.
+    Js,
+    /// A `cargo test` driving the witness calculator and the R1CS of circom with `ark-circom`.
+    Rust,
+}
+
+impl FromStr for HarnessLanguage {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "js" => Ok(HarnessLanguage::Js),
+            "rust" => Ok(HarnessLanguage::Rust),
+            _ => Err(format!(
+                "unknown harness language `{}` (expected js or rust)",
+                s
+            )),
+        }
+    }
+}
+
+impl HarnessLanguage {
+    /// The extension of the generated file.
+    pub fn extension(&self) -> &'static str {
+        match self {
+            HarnessLanguage::Js => "js",
+            HarnessLanguage::Rust => "rs",
+        }
+    }
+}
+
+/// An input of the main template accepted by the circuit, with the outputs it is expected to
+/// compute.
+pub struct TestCase {
+    /// The `input.json` of the case.
+    pub input: Value,
+    /// The outputs of the main template, nested like the inputs.
+    pub outputs: Value,
+    /// The flattened inputs in declaration and row-major order, as names without indices.
+    pub flat_inputs: Vec<(String, BigInt)>,
+    /// The flattened outputs in declaration and row-major order, the order of circom's witness.
+    pub flat_outputs: Vec<BigInt>,
+}
+
+/// Generates test cases for the main template from random inputs.
+///
+/// Each input is drawn as in the differential testing, run through the concrete executor, and
+/// kept only if the concrete executor accepts it, the emulation of the symbolic trace succeeds
+/// and computes the same outputs, and the side constraints accept the resulting witness. The
+/// outputs of a case are the ones computed by the trace semantics.
+///
+/// # Parameters
+/// - `sexe`: A mutable reference to the concrete executor.
+/// - `symbolic_trace`: The symbolic trace of the main template.
+/// - `side_constraints`: The side constraints of the main template.
+/// - `base_config`: The verification configuration specifying the target template and prime.
+/// - `id2dimensions`: The dimensions of the signals declared in the main template.
+/// - `num_cases`: The number of test cases to generate.
+/// - `seed`: The seed of the random number generator.
+///
+/// # Returns
+/// The generated test cases, fewer than `num_cases` if too many drawn inputs are rejected.
+pub fn generate_test_cases(
+    sexe: &mut SymbolicExecutor,
+    symbolic_trace: &[SymbolicValueRef],
+    side_constraints: &[SymbolicValueRef],
+    base_config: &BaseVerificationConfig,
+    id2dimensions: &FxHashMap<usize, Vec<usize>>,
+    num_cases: usize,
+    seed: u64,
+) -> Vec<TestCase> {
+    let inputs = flatten_main_signals(sexe, base_config, id2dimensions, true);
+    let outputs = flatten_main_signals(sexe, base_config, id2dimensions, false);
+    let domains = gather_tagged_input_domains(sexe, base_config);
+    let prime = &base_config.prime;
+
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut cases = Vec::new();
+    for _ in 0..num_cases * MAX_ATTEMPTS_PER_CASE {
+        if cases.len() == num_cases {
+            break;
+        }
+        let assignment = draw_input_assignment(&inputs, &domains, prime, &mut rng);
+        let outcome = run_zkfuzz(sexe, base_config, &assignment, &outputs);
+        if !outcome.accepted {
+            continue;
+        }
+
+        let mut witness = assignment.clone();
+        match emulate_symbolic_trace(
+            prime,
+            symbolic_trace,
+            &FxHashMap::default(),
+            &mut witness,
+            sexe.symbolic_library,
+        ) {
+            Some((true, _)) => {}
+            _ => continue,
+        }
+        if are_satisfied(prime, side_constraints, &witness, sexe.symbolic_library) != Some(true) {
+            continue;
+        }
+        let flat_outputs: Option<Vec<BigInt>> = outputs
+            .iter()
+            .map(|name| witness.get(name).map(|v| modreduce(v, prime)))
+            .collect();
+        let flat_outputs = match flat_outputs {
+            Some(values) => values,
+            None => continue,
+        };
+        if outcome.outputs.iter().cloned().collect::<Option<Vec<_>>>() != Some(flat_outputs.clone())
+        {
+            continue;
+        }
+
+        cases.push(TestCase {
+            input: build_input_json(
+                &assignment,
+                sexe.symbolic_library,
+                base_config,
+                id2dimensions,
+            ),
+            outputs: build_output_json(&witness, sexe.symbolic_library, base_config, id2dimensions),
+            flat_inputs: inputs
+                .iter()
+                .map(|name| {
+                    (
+                        sexe.symbolic_library.id2name[&name.id].clone(),
+                        modreduce(&assignment[name], prime),
+                    )
+                })
+                .collect(),
+            flat_outputs: flat_outputs,
+        });
+    }
+    cases
+}
+
+/// Renders a mocha test suite checking the cases with `circom_tester`.
+///
+/// # Parameters
+/// - `template_name`: The name of the main template.
+/// - `circuit_path`: The path of the circuit, relative to the generated file.
+/// - `cases`: The test cases.
+pub fn render_js_harness(template_name: &str, circuit_path: &str, cases: &[TestCase]) -> String {
+    let cases_json = Value::Array(
+        cases
+            .iter()
+            .map(|case| serde_json::json!({"input": case.input, "outputs": case.outputs}))
+            .collect(),
+    );
+    format!(
+        r#"// Generated by zkFuzz: property-based tests of the template `{template}`.
+//
+// The inputs were drawn at random and the outputs computed with the trace semantics of zkFuzz.
+// Each case checks that the witness calculator of circom computes the same outputs and that the
+// constraints of the circuit accept the witness.
+// Usage: npm install --save-dev mocha circom_tester && npx mocha <this_file>
+const path = require("path");
+const wasm_tester = require("circom_tester").wasm;
+
+const CIRCUIT = path.join(__dirname, {circuit});
+
+const CASES = {cases};
+
+describe("{template}", function () {{
+    this.timeout(100000);
+    let circuit;
+
+    before(async () => {{
+        circuit = await wasm_tester(CIRCUIT);
+    }});
+
+    CASES.forEach((testCase, i) => {{
+        it(`accepts the honest witness of case ${{i}}`, async () => {{
+            const witness = await circuit.calculateWitness(testCase.input, true);
+            await circuit.checkConstraints(witness);
+            await circuit.assertOut(witness, testCase.outputs);
+        }});
+    }});
+}});
+"#,
+        template = template_name,
+        circuit = serde_json::to_string(circuit_path).unwrap(),
+        cases = serde_json::to_string_pretty(&cases_json).unwrap(),
+    )
+}
+
+/// Renders a `cargo test` checking the cases with `ark-circom`.
+///
+/// # Parameters
+/// - `template_name`: The name of the main template.
+/// - `circuit_path`: The path of the circuit, relative to the generated file.
+/// - `cases`: The test cases.
+pub fn render_rust_harness(template_name: &str, circuit_path: &str, cases: &[TestCase]) -> String {
+    let stem = circuit_path
+        .rsplit('/')
+        .next()
+        .unwrap_or(circuit_path)
+        .trim_end_matches(".circom");
+    let dir = match circuit_path.rfind('/') {
+        Some(pos) => &circuit_path[..pos + 1],
+        None => "",
+    };
+    let mut cases_src = String::new();
+    for case in cases {
+        let inputs = case
+            .flat_inputs
+            .iter()
+            .map(|(name, value)| format!("(\"{}\", \"{}\")", name, value))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let outputs = case
+            .flat_outputs
+            .iter()
+            .map(|value| format!("\"{}\"", value))
+            .collect::<Vec<_>>()
+            .join(", ");
+        cases_src += &format!("    (&[{}], &[{}]),\n", inputs, outputs);
+    }
+    format!(
+        r#"//! Generated by zkFuzz: property-based tests of the template `{template}`.
+//!
+//! The inputs were drawn at random and the outputs computed with the trace semantics of zkFuzz.
+//! Each case checks that the witness calculator of circom computes the same outputs and that the
+//! constraints of the circuit accept the witness. Compile the circuit first with
+//! `circom {circuit} --r1cs --wasm` (over bn128), and add `ark-circom`, `ark-bn254`,
+//! `ark-relations`, and `num-bigint` to the dev-dependencies.
+
+use std::path::{{Path, PathBuf}};
+use std::str::FromStr;
+
+use ark_bn254::Fr;
+use ark_circom::{{CircomBuilder, CircomConfig}};
+use ark_relations::r1cs::{{ConstraintSynthesizer, ConstraintSystem}};
+use num_bigint::BigInt;
+
+/// The paths of the witness calculator and of the R1CS, relative to this file.
+const WASM: &str = "{dir}{stem}_js/{stem}.wasm";
+const R1CS: &str = "{dir}{stem}.r1cs";
+
+/// The flattened inputs of each case in declaration and row-major order, and its expected
+/// outputs in the order of the witness.
+const CASES: &[(&[(&str, &str)], &[&str])] = &[
+{cases}];
+
+fn resolve(relative: &str) -> PathBuf {{
+    Path::new(file!()).parent().unwrap().join(relative)
+}}
+
+#[test]
+fn test_{template_snake}_accepts_honest_witnesses() {{
+    for (i, (inputs, outputs)) in CASES.iter().enumerate() {{
+        let cfg = CircomConfig::<Fr>::new(resolve(WASM), resolve(R1CS)).unwrap();
+        let mut builder = CircomBuilder::new(cfg);
+        for (name, value) in inputs.iter() {{
+            builder.push_input(*name, BigInt::from_str(value).unwrap());
+        }}
+        let circom = builder.build().unwrap();
+
+        let public_signals = circom.get_public_inputs().unwrap();
+        for (j, expected) in outputs.iter().enumerate() {{
+            assert_eq!(
+                public_signals[j],
+                Fr::from_str(expected).unwrap(),
+                "case {{}}: output {{}}",
+                i,
+                j
+            );
+        }}
+
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        circom.generate_constraints(cs.clone()).unwrap();
+        assert!(
+            cs.is_satisfied().unwrap(),
+            "case {{}}: the constraints reject the witness",
+            i
+        );
+    }}
+}}
+"#,
+        template = template_name,
+        template_snake = template_name.to_lowercase(),
+        circuit = circuit_path,
+        dir = dir,
+        stem = stem,
+        cases = cases_src,
+    )
+}
+
+/// Renders the test harness of `cases` in `language`.
+pub fn render_harness(
+    language: HarnessLanguage,
+    template_name: &str,
+    circuit_path: &str,
+    cases: &[TestCase],
+) -> String {
+    match language {
+        HarnessLanguage::Js => render_js_harness(template_name, circuit_path, cases),
+        HarnessLanguage::Rust => render_rust_harness(template_name, circuit_path, cases),
+    }
+}
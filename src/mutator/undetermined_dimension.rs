@@ -0,0 +1,49 @@
+use program_structure::file_definition::FileLibrary;
+
+use crate::executor::symbolic_execution::SymbolicExecutor;
+use crate::mutator::division::{gather_statement_metas, statement_location};
+
+/// A declaration such as `var arr[n]` within a function or a template whose size is not a
+/// constant once the arguments of the call or the parameters of the template are bound, e.g.,
+/// because `n` depends on a signal, or is negative.
+///
+/// circom rejects a circuit whose array sizes are not known at compile time, and the symbolic
+/// execution treats such an array as empty.
+pub struct UndeterminedDimensionSite {
+    pub owner_name: String,
+    pub location: String,
+    pub message: String,
+}
+
+/// Lists the array declarations whose size could not be determined during symbolic execution.
+///
+/// # Parameters
+/// - `sexe`: The symbolic executor after the execution of the main template.
+/// - `file_library`: The file library used to resolve the source locations of the statements.
+///
+/// # Returns
+/// A vector of `UndeterminedDimensionSite`s sorted by their owner and location.
+pub fn gather_undetermined_dimensions(
+    sexe: &SymbolicExecutor,
+    file_library: &FileLibrary,
+) -> Vec<UndeterminedDimensionSite> {
+    let lookup = &sexe.symbolic_library.id2name;
+    let metas = gather_statement_metas(sexe.symbolic_library);
+    let mut sites: Vec<_> = sexe
+        .undetermined_dimensions
+        .iter()
+        .map(|(elem_id, (owner_id, message))| UndeterminedDimensionSite {
+            owner_name: lookup.get(owner_id).cloned().unwrap_or_default(),
+            location: statement_location(
+                sexe.symbolic_library,
+                &metas,
+                file_library,
+                *elem_id,
+                *owner_id,
+            ),
+            message: message.clone(),
+        })
+        .collect();
+    sites.sort_by(|a, b| (&a.owner_name, &a.location).cmp(&(&b.owner_name, &b.location)));
+    sites
+}
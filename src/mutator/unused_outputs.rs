@@ -1,13 +1,19 @@
-use std::collections::HashSet;
-
 use num_bigint_dig::BigInt;
-use num_traits::Zero;
-use rustc_hash::FxHashMap;
+use num_traits::{One, Zero};
+use program_structure::ast::{AssignOp, Meta};
+use rustc_hash::{FxHashMap, FxHashSet};
 
+use crate::executor::constraint_store::ConstraintStore;
+use crate::executor::debug_ast::{DebuggableAssignOp, DebuggableStatement};
 use crate::executor::symbolic_execution::SymbolicExecutor;
-use crate::executor::symbolic_value::{extract_variables, register_array_elements, SymbolicName};
+use crate::executor::symbolic_setting::get_default_setting_for_concrete_execution;
+use crate::executor::symbolic_value::{
+    extract_variables_from_symbolic_value, register_array_elements, OwnerName, SymbolicLibrary,
+    SymbolicName, SymbolicValue,
+};
 use crate::mutator::utils::{
-    BaseVerificationConfig, CounterExample, UnderConstrainedType, VerificationResult,
+    evaluate_constraints, gather_input_variables, verify_assignment, BaseVerificationConfig,
+    CounterExample, UnderConstrainedType, VerificationResult,
 };
 
 /// Checks for unused outputs in the symbolic execution trace and returns a counterexample if any are found.
@@ -46,16 +52,59 @@ pub fn check_unused_outputs(
     sexe: &mut SymbolicExecutor,
     base_config: &BaseVerificationConfig,
 ) -> Option<CounterExample> {
-    let mut variables: Vec<SymbolicName> = Vec::new();
-    variables.append(&mut extract_variables(
-        &sexe.cur_state.symbolic_trace.clone(),
-    ));
-    variables.append(&mut extract_variables(
-        &sexe.cur_state.symbolic_trace.clone(),
-    ));
-    let variables_set: HashSet<SymbolicName> = variables.iter().cloned().collect();
-
-    let mut used_outputs: FxHashMap<SymbolicName, Option<bool>> = FxHashMap::default();
+    let symbolic_trace = sexe.cur_state.symbolic_trace.clone();
+    check_unused_outputs_in_store(sexe, &symbolic_trace, base_config)
+}
+
+/// Checks for unused outputs in the given constraint store and returns a counterexample if any are found.
+///
+/// This is the same as `check_unused_outputs`, except that the symbolic trace is scanned through a
+/// `ConstraintStore` instead of being read from the current state of `sexe`.
+///
+/// # Parameters
+/// - `sexe`: A mutable reference to the `SymbolicExecutor` holding the symbolic library.
+/// - `symbolic_trace`: The constraint store containing the symbolic trace.
+/// - `base_config`: A reference to the `BaseVerificationConfig`.
+///
+/// # Returns
+/// An `Option<CounterExample>` containing the unused outputs, or `None` if all outputs are used.
+pub fn check_unused_outputs_in_store(
+    sexe: &mut SymbolicExecutor,
+    symbolic_trace: &dyn ConstraintStore,
+    base_config: &BaseVerificationConfig,
+) -> Option<CounterExample> {
+    let mut variables_set: FxHashSet<SymbolicName> = FxHashSet::default();
+    for c in symbolic_trace.iter() {
+        extract_variables_from_symbolic_value(&c, &mut variables_set);
+    }
+
+    let used_outputs = gather_main_outputs(sexe, base_config);
+    let unused_outputs: Vec<SymbolicName> = used_outputs
+        .keys()
+        .filter(|key| !variables_set.contains(*key))
+        .cloned()
+        .collect();
+    if !unused_outputs.is_empty() {
+        let dummy_assignment: FxHashMap<SymbolicName, BigInt> = unused_outputs
+            .iter()
+            .map(|uo| (uo.clone(), BigInt::zero()))
+            .collect();
+        Some(CounterExample {
+            flag: VerificationResult::UnderConstrained(UnderConstrainedType::UnusedOutput),
+            target_output: None,
+            assignment: dummy_assignment,
+        })
+    } else {
+        None
+    }
+}
+
+/// Returns every element of the outputs of the main template, owned by the main component.
+fn gather_main_outputs(
+    sexe: &mut SymbolicExecutor,
+    base_config: &BaseVerificationConfig,
+) -> FxHashMap<SymbolicName, Option<bool>> {
+    let mut outputs: FxHashMap<SymbolicName, Option<bool>> = FxHashMap::default();
     for oup_name in &sexe.symbolic_library.template_library
         [&sexe.symbolic_library.name2id[&base_config.target_template_name]]
         .output_ids
@@ -72,25 +121,525 @@ pub fn check_unused_outputs(
             *oup_name,
             &dims,
             Some(sexe.cur_state.owner_name.clone()),
-            &mut used_outputs,
+            &mut outputs,
         );
     }
-    let unused_outputs: Vec<SymbolicName> = used_outputs
-        .keys()
-        .filter(|key| !variables_set.contains(*key))
-        .cloned()
+    outputs
+}
+
+/// The number of unchanged lines shown around the change of an `OutputPatch`.
+const PATCH_CONTEXT_LINES: usize = 3;
+
+/// How an `OutputPatch` fixes an unused output.
+#[derive(Clone, Debug, PartialEq)]
+pub enum OutputPatchKind {
+    /// Replaces `out <-- e` with `out <== e`, binding the output to the expression it is assigned.
+    BindAssignment,
+    /// Removes the declaration of the output, which is never assigned.
+    RemoveDeclaration,
+}
+
+/// A source patch fixing an unused output of the main template.
+pub struct OutputPatch {
+    pub output: String,
+    pub kind: OutputPatchKind,
+    /// The patch as a unified diff against the source file.
+    pub diff: String,
+}
+
+/// The statements of a template involving its outputs.
+#[derive(Default)]
+struct OutputStatements {
+    declarations: FxHashMap<usize, Meta>,
+    /// The first `<--` assignment of each output.
+    signal_assignments: FxHashMap<usize, Meta>,
+    /// The outputs assigned by any statement.
+    assigned: FxHashSet<usize>,
+}
+
+fn gather_output_statements(
+    statements: &[DebuggableStatement],
+    output_ids: &FxHashSet<usize>,
+    gathered: &mut OutputStatements,
+) {
+    for stmt in statements {
+        match stmt {
+            DebuggableStatement::Declaration { meta, id, .. } if output_ids.contains(id) => {
+                gathered
+                    .declarations
+                    .entry(*id)
+                    .or_insert_with(|| meta.clone());
+            }
+            DebuggableStatement::Substitution { meta, var, op, .. } if output_ids.contains(var) => {
+                gathered.assigned.insert(*var);
+                if matches!(op, DebuggableAssignOp(AssignOp::AssignSignal)) {
+                    gathered
+                        .signal_assignments
+                        .entry(*var)
+                        .or_insert_with(|| meta.clone());
+                }
+            }
+            DebuggableStatement::IfThenElse {
+                if_case, else_case, ..
+            } => {
+                gather_output_statements(
+                    std::slice::from_ref(if_case.as_ref()),
+                    output_ids,
+                    gathered,
+                );
+                if let Some(else_case) = else_case {
+                    gather_output_statements(
+                        std::slice::from_ref(else_case.as_ref()),
+                        output_ids,
+                        gathered,
+                    );
+                }
+            }
+            DebuggableStatement::While { stmt, .. } => {
+                gather_output_statements(std::slice::from_ref(stmt.as_ref()), output_ids, gathered)
+            }
+            DebuggableStatement::Block { stmts, .. } => {
+                gather_output_statements(stmts, output_ids, gathered)
+            }
+            DebuggableStatement::InitializationBlock {
+                initializations, ..
+            } => gather_output_statements(initializations, output_ids, gathered),
+            _ => {}
+        }
+    }
+}
+
+/// Formats a unified diff replacing the line at `index` of `lines` with `replacement`, or
+/// removing it if `replacement` is `None`.
+fn unified_diff(path: &str, lines: &[&str], index: usize, replacement: Option<&str>) -> String {
+    let start = index.saturating_sub(PATCH_CONTEXT_LINES);
+    let end = (index + PATCH_CONTEXT_LINES + 1).min(lines.len());
+    let old_len = end - start;
+    let new_len = old_len - replacement.map_or(1, |_| 0);
+    let range = |len: usize| {
+        if len == 0 {
+            format!("{},0", start)
+        } else {
+            format!("{},{}", start + 1, len)
+        }
+    };
+
+    let mut diff = format!(
+        "--- a/{}\n+++ b/{}\n@@ -{} +{} @@\n",
+        path,
+        path,
+        range(old_len),
+        range(new_len)
+    );
+    for (i, line) in lines.iter().enumerate().take(end).skip(start) {
+        if i == index {
+            diff += &format!("-{}\n", line);
+            if let Some(replacement) = replacement {
+                diff += &format!("+{}\n", replacement);
+            }
+        } else {
+            diff += &format!(" {}\n", line);
+        }
+    }
+    diff
+}
+
+/// Suggests a source patch for each unused output reported by `check_unused_outputs`.
+///
+/// An output assigned with `<--`, e.g. in a branch that the symbolic execution does not reach,
+/// is bound to the assigned expression by turning the assignment into `<==`. An output that is
+/// never assigned is removed from the template, unless its declaration shares its line with
+/// other statements. Partially assigned output arrays are not patched, and neither are the
+/// outputs declared outside of `source`.
+///
+/// # Parameters
+/// - `symbolic_library`: The symbolic library containing the template definitions.
+/// - `base_config`: The verification configuration specifying the target template.
+/// - `counter_example`: The counterexample of `check_unused_outputs`.
+/// - `source_path`: The path of the source file, shown in the diff.
+/// - `source`: The contents of the source file.
+/// - `file_id`: The id of the source file in the file library of the program.
+///
+/// # Returns
+/// The patches, sorted by the names of the outputs.
+pub fn suggest_unused_output_patches(
+    symbolic_library: &SymbolicLibrary,
+    base_config: &BaseVerificationConfig,
+    counter_example: &CounterExample,
+    source_path: &str,
+    source: &str,
+    file_id: usize,
+) -> Vec<OutputPatch> {
+    if !matches!(
+        counter_example.flag,
+        VerificationResult::UnderConstrained(UnderConstrainedType::UnusedOutput)
+    ) {
+        return Vec::new();
+    }
+    let template = &symbolic_library.template_library
+        [&symbolic_library.name2id[&base_config.target_template_name]];
+    let mut gathered = OutputStatements::default();
+    gather_output_statements(&template.body, &template.output_ids, &mut gathered);
+
+    let mut output_ids: Vec<usize> = counter_example.assignment.keys().map(|o| o.id).collect();
+    output_ids.sort_by_key(|id| &symbolic_library.id2name[id]);
+    output_ids.dedup();
+
+    let lines: Vec<&str> = source.split('\n').collect();
+    let line_of = |meta: &Meta| {
+        source
+            .get(..meta.start)
+            .filter(|_| meta.file_id == Some(file_id))
+            .map(|prefix| prefix.matches('\n').count())
+    };
+
+    let mut patches = Vec::new();
+    for id in output_ids {
+        let output = symbolic_library.id2name[&id].clone();
+        if let Some(meta) = gathered.signal_assignments.get(&id) {
+            let statement = source.get(meta.start..meta.end).unwrap_or_default();
+            if let (Some(index), true) = (line_of(meta), statement.contains("<--")) {
+                let line_start = source[..meta.start].rfind('\n').map_or(0, |i| i + 1);
+                let offset = meta.start - line_start;
+                let line = lines[index];
+                let replacement = format!(
+                    "{}{}",
+                    &line[..offset],
+                    line[offset..].replacen("<--", "<==", 1)
+                );
+                patches.push(OutputPatch {
+                    output: output,
+                    kind: OutputPatchKind::BindAssignment,
+                    diff: unified_diff(source_path, &lines, index, Some(&replacement)),
+                });
+                continue;
+            }
+        }
+        if gathered.assigned.contains(&id) {
+            continue;
+        }
+        if let Some(meta) = gathered.declarations.get(&id) {
+            let declaration = source.get(meta.start..meta.end).unwrap_or_default();
+            if let Some(index) = line_of(meta) {
+                // The line only holds the declaration of this output
+                let rest = lines[index].trim().trim_end_matches(';').trim_end();
+                if declaration.starts_with("signal")
+                    && !declaration.contains(',')
+                    && rest == declaration.trim_end_matches(';').trim_end()
+                {
+                    patches.push(OutputPatch {
+                        output: output,
+                        kind: OutputPatchKind::RemoveDeclaration,
+                        diff: unified_diff(source_path, &lines, index, None),
+                    });
+                }
+            }
+        }
+    }
+    patches
+}
+
+/// An under-constraint across a component boundary found by `check_component_boundaries`.
+pub enum ComponentBoundaryIssue {
+    /// An output of a sub-component that is not consumed by any constraint outside the component.
+    DeadOutput(SymbolicName),
+    /// A signal assigned with `<--` from an output of a sub-component that no constraint relates to
+    /// that output. The second element is the output of the sub-component.
+    UnconstrainedAssignment(SymbolicName, SymbolicName),
+}
+
+impl ComponentBoundaryIssue {
+    pub fn lookup_fmt(&self, lookup: &FxHashMap<usize, String>) -> String {
+        match self {
+            ComponentBoundaryIssue::DeadOutput(name) => format!(
+                "dead output: `{}` is not consumed by its parent",
+                name.lookup_fmt(lookup)
+            ),
+            ComponentBoundaryIssue::UnconstrainedAssignment(name, source) => format!(
+                "unconstrained assignment: `{}` <-- `{}` is never constrained against it",
+                name.lookup_fmt(lookup),
+                source.lookup_fmt(lookup)
+            ),
+        }
+    }
+}
+
+/// Returns `true` if `name` belongs to the component owned by `owner` or to one of its descendants.
+fn is_owned_by(name: &SymbolicName, owner: &[OwnerName]) -> bool {
+    name.owner.len() >= owner.len() && name.owner[..owner.len()] == *owner
+}
+
+/// Returns `true` if `name` is an output of the (non-main) component owning it.
+fn is_component_output(sexe: &SymbolicExecutor, name: &SymbolicName) -> bool {
+    name.owner.len() > 1
+        && sexe
+            .component_templates
+            .get(&*name.owner)
+            .map_or(false, |template_id| {
+                sexe.symbolic_library.template_library[template_id]
+                    .output_ids
+                    .contains(&name.id)
+            })
+}
+
+/// Checks the boundaries between components for under-constraints that the check of the main
+/// template's outputs cannot see.
+///
+/// The whole component tree recorded during symbolic execution is walked, and two patterns are
+/// reported with owner-qualified names:
+/// - Outputs of sub-components that appear in the trace but in no constraint involving a signal
+///   outside the sub-component, i.e., outputs that the parent never consumes.
+/// - Signals assigned with `<--` from an output of a direct sub-component that no side constraint
+///   relates to that output, i.e., values computed by a sub-component that the parent never
+///   constrains against it.
+///
+/// # Parameters
+/// - `sexe`: The symbolic executor after symbolic execution of the main template.
+/// - `symbolic_trace`: The constraint store containing the symbolic trace.
+/// - `side_constraints`: The constraint store containing the side constraints.
+///
+/// # Returns
+/// The issues found, sorted by the owner-qualified names of the involved signals.
+pub fn check_component_boundaries(
+    sexe: &SymbolicExecutor,
+    symbolic_trace: &dyn ConstraintStore,
+    side_constraints: &dyn ConstraintStore,
+) -> Vec<ComponentBoundaryIssue> {
+    let mut outputs: FxHashSet<SymbolicName> = FxHashSet::default();
+    let mut consumed_outputs: FxHashSet<SymbolicName> = FxHashSet::default();
+    let mut assignments: Vec<(SymbolicName, Vec<SymbolicName>)> = Vec::new();
+    for c in symbolic_trace.iter() {
+        let mut variables = FxHashSet::default();
+        extract_variables_from_symbolic_value(&c, &mut variables);
+        for v in variables.iter().filter(|v| is_component_output(sexe, v)) {
+            outputs.insert(v.clone());
+            if variables.iter().any(|w| !is_owned_by(w, &v.owner)) {
+                consumed_outputs.insert(v.clone());
+            }
+        }
+
+        if let SymbolicValue::Assign(lhs, rhs, ..) = &*c {
+            if let SymbolicValue::Variable(name) = &**lhs {
+                let mut sources = FxHashSet::default();
+                extract_variables_from_symbolic_value(rhs, &mut sources);
+                let mut sources: Vec<_> = sources
+                    .into_iter()
+                    .filter(|s| {
+                        s.owner.len() == name.owner.len() + 1
+                            && is_owned_by(s, &name.owner)
+                            && is_component_output(sexe, s)
+                    })
+                    .collect();
+                if !sources.is_empty() {
+                    sources.sort();
+                    assignments.push((name.clone(), sources));
+                }
+            }
+        }
+    }
+
+    // An assignment is tied to the sub-component if a side constraint relates the assigned
+    // signal to one of the outputs it was computed from.
+    let mut is_tied = vec![false; assignments.len()];
+    if !assignments.is_empty() {
+        for c in side_constraints.iter() {
+            let mut variables = FxHashSet::default();
+            extract_variables_from_symbolic_value(&c, &mut variables);
+            for (i, (name, sources)) in assignments.iter().enumerate() {
+                if !is_tied[i]
+                    && variables.contains(name)
+                    && sources.iter().any(|s| variables.contains(s))
+                {
+                    is_tied[i] = true;
+                }
+            }
+        }
+    }
+
+    let mut dead_outputs: Vec<_> = outputs.difference(&consumed_outputs).cloned().collect();
+    dead_outputs.sort();
+    let mut unconstrained_assignments: Vec<_> = assignments
+        .into_iter()
+        .zip(is_tied)
+        .filter(|(_, tied)| !tied)
+        .map(|((name, sources), _)| (name, sources[0].clone()))
         .collect();
-    if !unused_outputs.is_empty() {
-        let dummy_assignment: FxHashMap<SymbolicName, BigInt> = unused_outputs
-            .iter()
-            .map(|uo| (uo.clone(), BigInt::zero()))
-            .collect();
-        Some(CounterExample {
-            flag: VerificationResult::UnderConstrained(UnderConstrainedType::UnusedOutput),
-            target_output: None,
-            assignment: dummy_assignment,
+    unconstrained_assignments.sort();
+
+    dead_outputs
+        .into_iter()
+        .map(ComponentBoundaryIssue::DeadOutput)
+        .chain(
+            unconstrained_assignments.into_iter().map(|(name, source)| {
+                ComponentBoundaryIssue::UnconstrainedAssignment(name, source)
+            }),
+        )
+        .collect()
+}
+
+/// The values tried for every input of the main template when computing the honest witness of
+/// the counterexample of an unconstrained output.
+const UNCONSTRAINED_OUTPUT_INPUT_CANDIDATES: [i64; 3] = [0, 1, 2];
+
+/// An output of the main template that is assigned with `<--` and appears in no side constraint,
+/// found by `check_unconstrained_outputs`.
+pub struct UnconstrainedOutput {
+    pub output: SymbolicName,
+    /// An assignment where the output differs from the value computed by the witness generator
+    /// while every side constraint still holds, if one is found.
+    pub counter_example: Option<CounterExample>,
+}
+
+impl UnconstrainedOutput {
+    pub fn lookup_fmt(&self, lookup: &FxHashMap<usize, String>) -> String {
+        format!(
+            "`{}` is assigned with `<--` but appears in no constraint",
+            self.output.lookup_fmt(lookup)
+        )
+    }
+}
+
+/// Checks for outputs of the main template that are assigned with `<--` but never constrained,
+/// i.e., appear in the symbolic trace but in no side constraint, which lets a prover choose their
+/// values freely.
+///
+/// Unlike `check_unused_outputs`, the outputs are computed by the circuit, so a counterexample is
+/// built by executing the circuit concretely on a few inputs until its witness satisfies the side
+/// constraints, and replacing the value of the output with a different one. Since the output does
+/// not appear in the side constraints, they still hold, and the assignment is verified like those
+/// of the search, which reports it as `UnderConstrained-NonDeterministic`.
+///
+/// # Parameters
+/// - `sexe`: The symbolic executor after symbolic execution of the main template.
+/// - `symbolic_trace`: The constraint store containing the symbolic trace.
+/// - `side_constraints`: The constraint store containing the side constraints.
+/// - `base_config`: The verification configuration specifying the target template.
+///
+/// # Returns
+/// The unconstrained outputs, sorted by their names.
+pub fn check_unconstrained_outputs(
+    sexe: &mut SymbolicExecutor,
+    symbolic_trace: &dyn ConstraintStore,
+    side_constraints: &dyn ConstraintStore,
+    base_config: &BaseVerificationConfig,
+) -> Vec<UnconstrainedOutput> {
+    let outputs = gather_main_outputs(sexe, base_config);
+    let mut signal_assigned: FxHashSet<SymbolicName> = FxHashSet::default();
+    for c in symbolic_trace.iter() {
+        if let SymbolicValue::Assign(lhs, ..) = &*c {
+            if let SymbolicValue::Variable(name) = &**lhs {
+                if outputs.contains_key(name) {
+                    signal_assigned.insert(name.clone());
+                }
+            }
+        }
+    }
+    let mut constrained: FxHashSet<SymbolicName> = FxHashSet::default();
+    for c in side_constraints.iter() {
+        extract_variables_from_symbolic_value(&c, &mut constrained);
+    }
+    let mut unconstrained: Vec<SymbolicName> = signal_assigned
+        .into_iter()
+        .filter(|name| !constrained.contains(name))
+        .collect();
+    unconstrained.sort();
+    if unconstrained.is_empty() {
+        return Vec::new();
+    }
+
+    let symbolic_trace = symbolic_trace.to_vec();
+    let side_constraints = side_constraints.to_vec();
+    let mut variables: FxHashSet<SymbolicName> = FxHashSet::default();
+    for c in symbolic_trace.iter().chain(side_constraints.iter()) {
+        extract_variables_from_symbolic_value(c, &mut variables);
+    }
+    let variables: Vec<SymbolicName> = variables.into_iter().collect();
+    let inputs = gather_input_variables(sexe.symbolic_library, base_config, &variables);
+
+    let setting = get_default_setting_for_concrete_execution(
+        base_config.prime.clone(),
+        sexe.setting.constraint_assert_dissabled,
+    );
+    let mut conc_executor = SymbolicExecutor::new(&mut sexe.symbolic_library, &setting);
+    let honest_witness = UNCONSTRAINED_OUTPUT_INPUT_CANDIDATES
+        .iter()
+        .find_map(|value| {
+            let input: FxHashMap<SymbolicName, BigInt> = inputs
+                .iter()
+                .map(|name| (name.clone(), BigInt::from(*value)))
+                .collect();
+            let witness = execute_witness(&mut conc_executor, base_config, &input, &variables)?;
+            evaluate_constraints(
+                &base_config.prime,
+                &side_constraints,
+                &witness,
+                &mut conc_executor.symbolic_library,
+            )
+            .then_some(witness)
+        });
+
+    unconstrained
+        .into_iter()
+        .map(|output| {
+            let counter_example = honest_witness.as_ref().and_then(|witness| {
+                let mut assignment = witness.clone();
+                let value = witness.get(&output)?;
+                assignment.insert(output.clone(), (value + BigInt::one()) % &base_config.prime);
+                match verify_assignment(
+                    &mut conc_executor,
+                    &symbolic_trace,
+                    &side_constraints,
+                    &assignment,
+                    base_config,
+                ) {
+                    flag @ VerificationResult::UnderConstrained(_) => Some(CounterExample {
+                        flag,
+                        target_output: Some(output.clone()),
+                        assignment,
+                    }),
+                    _ => None,
+                }
+            });
+            UnconstrainedOutput {
+                output,
+                counter_example,
+            }
         })
-    } else {
-        None
+        .collect()
+}
+
+/// Executes the main template concretely on `input` and returns the values of `variables`, or
+/// `None` if the execution fails or leaves one of them undetermined.
+fn execute_witness(
+    sexe: &mut SymbolicExecutor,
+    base_config: &BaseVerificationConfig,
+    input: &FxHashMap<SymbolicName, BigInt>,
+    variables: &[SymbolicName],
+) -> Option<FxHashMap<SymbolicName, BigInt>> {
+    sexe.clear();
+    sexe.cur_state.add_owner(&OwnerName {
+        id: sexe.symbolic_library.name2id["main"],
+        counter: 0,
+        access: None,
+    });
+    sexe.feed_arguments(
+        &base_config.template_param_names,
+        &base_config.template_param_values,
+    );
+    sexe.concrete_execute(&base_config.target_template_name, input);
+    if sexe.cur_state.is_failed {
+        return None;
     }
+    variables
+        .iter()
+        .map(
+            |name| match sexe.cur_state.get_sym_val(name).map(|v| &**v) {
+                Some(SymbolicValue::ConstantInt(value)) => Some((name.clone(), value.clone())),
+                Some(SymbolicValue::ConstantBool(b)) => {
+                    Some((name.clone(), BigInt::from(*b as u8)))
+                }
+                _ => None,
+            },
+        )
+        .collect()
 }
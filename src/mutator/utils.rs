@@ -14,19 +14,53 @@ use program_structure::ast::ExpressionPrefixOpcode;
 use serde_json::{json, Value};
 
 use crate::executor::debug_ast::DebuggableExpressionInfixOpcode;
+use crate::executor::structural_hash::{structural_hash, Equivalence};
 use crate::executor::symbolic_execution::SymbolicExecutor;
-use crate::executor::symbolic_setting::SymbolicExecutorSetting;
+use crate::executor::symbolic_setting::{
+    SymbolicExecutorSetting, DEFAULT_MAX_CALL_DEPTH, DEFAULT_MAX_INDEX_CASE_SPLITS,
+};
 use crate::executor::symbolic_value::{
     evaluate_binary_op, evaluate_binary_op_integer_mode, extract_variables_from_symbolic_value,
     normalize_to_bool, normalize_to_int, val_for_relational_operators, OwnerName, QuadraticPoly,
     SymbolicAccess, SymbolicLibrary, SymbolicName, SymbolicValue, SymbolicValueRef,
 };
+use crate::executor::utils::{complement, generate_cartesian_product_indices, modreduce};
+use crate::mutator::compiled_constraints::CompiledConstraints;
+use crate::mutator::constraint_scheduler::ConstraintScheduler;
+use crate::mutator::input_spec::{InputDomain, InputSpec};
+use crate::mutator::witness_export::{group_assignment, AssignmentFormat};
+
+/// A constraint, assertion, or denominator violated by a counterexample.
+#[derive(Clone, Debug)]
+pub struct ViolatedConstraint {
+    /// The constraint formatted with the names of its symbols (see `SymbolicValue::lookup_fmt`).
+    pub formatted: String,
+    /// The structural hash of the constraint under `Equivalence::FINDINGS`, shared by the
+    /// findings of the same root cause.
+    pub structure: u64,
+}
+
+impl ViolatedConstraint {
+    pub fn new(value: &SymbolicValue, id2name: &FxHashMap<usize, String>) -> Self {
+        ViolatedConstraint {
+            formatted: value.lookup_fmt(id2name),
+            structure: structural_hash(value, id2name, Equivalence::FINDINGS),
+        }
+    }
+}
+
+impl fmt::Display for ViolatedConstraint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.formatted)
+    }
+}
 
 #[derive(Clone)]
 pub enum UnderConstrainedType {
     UnusedOutput,
-    UnexpectedInput(usize, String),
+    UnexpectedInput(usize, ViolatedConstraint),
     NonDeterministic(SymbolicName, String, BigInt),
+    TagViolation(SymbolicName, String, String),
 }
 
 /// Represents the result of a constraint verification process.
@@ -34,6 +68,8 @@ pub enum UnderConstrainedType {
 pub enum VerificationResult {
     UnderConstrained(UnderConstrainedType),
     OverConstrained,
+    AssertionFailure(String, ViolatedConstraint),
+    DivisionByZero(String, ViolatedConstraint),
     WellConstrained,
 }
 
@@ -57,8 +93,26 @@ impl fmt::Display for VerificationResult {
                 )
                 .red()
                 .bold().to_string(),
+                UnderConstrainedType::TagViolation(_sym_name, name, tag) => format!(
+                    "🏷️ UnderConstrained (Tag-Violation) 🏷️\n║           ➡️ `{}` does not satisfy its tag `{}`",
+                    name, tag
+                )
+                .red()
+                .bold().to_string(),
             },
             VerificationResult::OverConstrained => "💣 OverConstrained 💣".yellow().bold().to_string(),
+            VerificationResult::AssertionFailure(location, condition) => format!(
+                "🚨 Assertion Failure 🚨\n║           ➡️ `assert({})` fails at {}",
+                condition, location
+            )
+            .red()
+            .bold().to_string(),
+            VerificationResult::DivisionByZero(location, denominator) => format!(
+                "➗ Division by Zero ➗\n║           ➡️ `{}` is zero at {}",
+                denominator, location
+            )
+            .red()
+            .bold().to_string(),
             VerificationResult::WellConstrained => "✅ WellConstrained ✅".green().bold().to_string(),
         };
         write!(f, "{output}")
@@ -78,8 +132,17 @@ impl VerificationResult {
                 UnderConstrainedType::NonDeterministic(_sym_name, name, value) => {
                     json!({"1_type": "UnderConstrained-NonDeterministic", "2_expected_output": json!({"name": name, "value":value.to_string()})})
                 }
+                UnderConstrainedType::TagViolation(_sym_name, name, tag) => {
+                    json!({"1_type": "UnderConstrained-TagViolation", "2_violated_tag": json!({"name": name, "tag": tag})})
+                }
             },
             VerificationResult::OverConstrained => json!({"1_type": "OverConstrained"}),
+            VerificationResult::AssertionFailure(location, condition) => {
+                json!({"1_type": "AssertionFailure", "2_violated_assertion": json!({"location": location, "condition": condition.formatted})})
+            }
+            VerificationResult::DivisionByZero(location, denominator) => {
+                json!({"1_type": "DivisionByZero", "2_division": json!({"location": location, "denominator": denominator.formatted})})
+            }
             VerificationResult::WellConstrained => json!({"1_type": "WellConstrained"}),
         }
     }
@@ -94,10 +157,15 @@ pub struct CounterExample {
 }
 
 impl CounterExample {
+    /// Serializes the counterexample together with the metadata of the run.
+    ///
+    /// `7_assignment` maps each signal to its decimal value, while `7_grouped_assignment` groups
+    /// the elements of array signals into arrays and writes the values with `format`.
     pub fn to_json_with_meta(
         &self,
         lookup: &FxHashMap<usize, String>,
         meta: &FxHashMap<String, String>,
+        format: &AssignmentFormat,
     ) -> Value {
         let mut base_json = json!({
             "5_flag": self.flag.to_json(),
@@ -116,6 +184,7 @@ impl CounterExample {
             .iter()
             .map(|(var_name, value)| (var_name.lookup_fmt(lookup), value.to_string()))
             .collect::<FxHashMap<String, String>>());
+        base_json["7_grouped_assignment"] = group_assignment(&self.assignment, lookup, format);
 
         base_json
     }
@@ -190,6 +259,65 @@ impl CounterExample {
 
         s
     }
+
+    /// Returns a key identifying what the counterexample violates, e.g. the position of the
+    /// violated constraint for an unexpected input or the output for a non-deterministic one.
+    ///
+    /// Counterexamples sharing the same key are likely to stem from the same bug.
+    pub fn classification(&self) -> String {
+        match &self.flag {
+            VerificationResult::UnderConstrained(typ) => match typ {
+                UnderConstrainedType::UnusedOutput => "UnderConstrained-UnusedOutput".to_string(),
+                UnderConstrainedType::UnexpectedInput(_pos, violated_condition) => format!(
+                    "UnderConstrained-UnexpectedInput: {}",
+                    violated_condition
+                ),
+                UnderConstrainedType::NonDeterministic(_sym_name, name, _value) => {
+                    format!("UnderConstrained-NonDeterministic: {}", name)
+                }
+                UnderConstrainedType::TagViolation(_sym_name, name, tag) => {
+                    format!("UnderConstrained-TagViolation: {} ({})", name, tag)
+                }
+            },
+            VerificationResult::OverConstrained => "OverConstrained".to_string(),
+            VerificationResult::AssertionFailure(location, _condition) => {
+                format!("AssertionFailure: {}", location)
+            }
+            VerificationResult::DivisionByZero(location, _denominator) => {
+                format!("DivisionByZero: {}", location)
+            }
+            VerificationResult::WellConstrained => "WellConstrained".to_string(),
+        }
+    }
+
+    /// Returns the witness of the counterexample as a sorted list of assignments reduced modulo
+    /// `prime`, so that two counterexamples with the same witness compare equal.
+    pub fn witness_key(&self, prime: &BigInt) -> Vec<(SymbolicName, BigInt)> {
+        let mut key: Vec<_> = self
+            .assignment
+            .iter()
+            .map(|(name, value)| (name.clone(), modreduce(value, prime)))
+            .collect();
+        key.sort();
+        key
+    }
+}
+
+/// Groups counterexamples by their classification.
+///
+/// # Returns
+/// The classifications paired with the indices of their counterexamples, in the order in which
+/// each classification first appears.
+pub fn classify_counterexamples(counter_examples: &[CounterExample]) -> Vec<(String, Vec<usize>)> {
+    let mut classes: Vec<(String, Vec<usize>)> = Vec::new();
+    for (i, ce) in counter_examples.iter().enumerate() {
+        let class = ce.classification();
+        match classes.iter_mut().find(|(c, _)| *c == class) {
+            Some((_, indices)) => indices.push(i),
+            None => classes.push((class, vec![i])),
+        }
+    }
+    classes
 }
 
 /// Determines if a given verification result indicates a vulnerability.
@@ -203,11 +331,14 @@ pub fn is_vulnerable(vr: &VerificationResult) -> bool {
     match vr {
         VerificationResult::UnderConstrained(_) => true,
         VerificationResult::OverConstrained => true,
+        VerificationResult::AssertionFailure(_, _) => true,
+        VerificationResult::DivisionByZero(_, _) => true,
         VerificationResult::WellConstrained => false,
     }
 }
 
 /// Configures the settings for the verification process.
+#[derive(Clone)]
 pub struct BaseVerificationConfig {
     pub target_template_name: String,
     pub prime: BigInt,
@@ -217,6 +348,202 @@ pub struct BaseVerificationConfig {
     pub progress_interval: usize,
     pub template_param_names: Vec<String>,
     pub template_param_values: Vec<Expression>,
+    pub check_output_tags: bool,
+    pub max_counterexamples: usize,
+    /// The dimensions of the inputs of the target template, keyed by signal id. A scalar input
+    /// has no dimensions. Inputs without a known shape are only assigned where they appear in
+    /// the constraints.
+    pub input_shapes: FxHashMap<usize, Vec<usize>>,
+    /// The domains of the inputs of the target template declared with `--input_spec`.
+    pub input_spec: InputSpec,
+}
+
+/// Returns the finite set of values permitted by the given signal tags.
+///
+/// # Parameters
+/// - `tags`: The tags attached to a signal declaration.
+///
+/// # Returns
+/// `Some(values)` if one of the tags restricts the signal to a finite domain (e.g. `binary`
+/// restricts it to `{0, 1}`), and `None` otherwise.
+pub fn get_domain_of_tags(tags: &[String]) -> Option<Vec<BigInt>> {
+    if tags.iter().any(|tag| tag == "binary") {
+        Some(vec![BigInt::zero(), BigInt::one()])
+    } else {
+        None
+    }
+}
+
+/// Gathers the input domains of the main template declared with `--input_spec` or induced by
+/// signal tags.
+///
+/// Restricted inputs of the main template are treated as preconditions: e.g., an input declared
+/// as `signal input {binary} in` only takes `0` or `1` during the search. A domain declared in
+/// the input specification takes precedence over the tags of the input.
+///
+/// # Parameters
+/// - `symbolic_library`: The symbolic library containing the template definitions.
+/// - `base_config`: The verification configuration specifying the target template.
+/// - `variables`: The variables to be assigned during the search.
+///
+/// # Returns
+/// A map from each restricted input variable to the values it may take.
+pub fn gather_input_domains(
+    symbolic_library: &SymbolicLibrary,
+    base_config: &BaseVerificationConfig,
+    variables: &[SymbolicName],
+) -> FxHashMap<SymbolicName, InputDomain> {
+    let template = &symbolic_library.template_library
+        [&symbolic_library.name2id[&base_config.target_template_name]];
+    let mut domains = FxHashMap::default();
+    for var in variables {
+        if var.owner.len() == 1 && template.input_ids.contains(&var.id) {
+            if let Some(domain) = base_config
+                .input_spec
+                .domain_of(var, &symbolic_library.id2name)
+            {
+                domains.insert(var.clone(), domain.clone());
+            } else if let Some(domain) = get_domain_of_tags(var.get_tags(template)) {
+                domains.insert(var.clone(), InputDomain::Values(domain));
+            }
+        }
+    }
+    domains
+}
+
+/// Gathers the input variables of the main template that the search assigns.
+///
+/// Besides the inputs appearing in the constraints, every element of each input with a known
+/// shape (see `BaseVerificationConfig::input_shapes`) is included, so that the sampled inputs
+/// always form a structurally valid input of the main template, e.g. all the elements of
+/// `in[4]` even if only `in[0]` and `in[2]` are constrained.
+///
+/// # Parameters
+/// - `symbolic_library`: The symbolic library containing the template definitions.
+/// - `base_config`: The verification configuration specifying the target template.
+/// - `variables`: The variables appearing in the constraints.
+///
+/// # Returns
+/// The sorted and deduplicated input variables.
+pub fn gather_input_variables(
+    symbolic_library: &SymbolicLibrary,
+    base_config: &BaseVerificationConfig,
+    variables: &[SymbolicName],
+) -> Vec<SymbolicName> {
+    let template = &symbolic_library.template_library
+        [&symbolic_library.name2id[&base_config.target_template_name]];
+    let mut input_variables: FxHashSet<SymbolicName> = variables
+        .iter()
+        .filter(|v| v.owner.len() == 1 && template.input_ids.contains(&v.id))
+        .cloned()
+        .collect();
+
+    if let Some(main_id) = symbolic_library.name2id.get("main") {
+        let owner = Rc::new(vec![OwnerName {
+            id: *main_id,
+            counter: 0,
+            access: None,
+        }]);
+        for (id, shape) in base_config.input_shapes.iter() {
+            if !template.input_ids.contains(id) {
+                continue;
+            }
+            for indices in generate_cartesian_product_indices(shape) {
+                let access: Vec<_> = indices
+                    .into_iter()
+                    .map(|i| {
+                        SymbolicAccess::ArrayAccess(SymbolicValue::ConstantInt(BigInt::from(i)))
+                    })
+                    .collect();
+                input_variables.insert(SymbolicName::new(
+                    *id,
+                    owner.clone(),
+                    if access.is_empty() {
+                        None
+                    } else {
+                        Some(access)
+                    },
+                ));
+            }
+        }
+    }
+
+    let mut input_variables: Vec<_> = input_variables.into_iter().collect();
+    input_variables.sort();
+    input_variables
+}
+
+/// Gathers the free witnesses of the trace, i.e. the signals assigned with `<--` anywhere in the
+/// component tree, outside of the whitelisted templates.
+///
+/// The witness generator computes their values, but nothing forces a malicious prover to use
+/// them: any value satisfying the constraints yields a valid proof. These are also the positions
+/// that the search mutates.
+///
+/// # Parameters
+/// - `symbolic_trace`: The constraints of the program trace.
+///
+/// # Returns
+/// The positions of the `<--` assignments in the trace and the signals they assign, in the order
+/// of the trace and without duplicated signals.
+pub fn gather_free_witnesses(symbolic_trace: &[SymbolicValueRef]) -> Vec<(usize, SymbolicName)> {
+    let mut seen = FxHashSet::default();
+    symbolic_trace
+        .iter()
+        .enumerate()
+        .filter_map(|(i, sv)| match sv.as_ref() {
+            SymbolicValue::Assign(lhs, _, false, _) | SymbolicValue::AssignCall(lhs, _, true) => {
+                match lhs.as_ref() {
+                    SymbolicValue::Variable(name) if seen.insert(name.clone()) => {
+                        Some((i, name.clone()))
+                    }
+                    _ => None,
+                }
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// Checks whether the tagged outputs of the main template satisfy their tags under the given
+/// assignment.
+///
+/// # Parameters
+/// - `symbolic_library`: The symbolic library containing the template definitions.
+/// - `base_config`: The verification configuration specifying the target template and prime.
+/// - `assignment`: An assignment satisfying the side constraints.
+///
+/// # Returns
+/// `Some((output, VerificationResult::UnderConstrained(TagViolation)))` if a tagged output takes
+/// a value outside of the domain of its tags, and `None` otherwise.
+pub fn find_output_tag_violation(
+    symbolic_library: &SymbolicLibrary,
+    base_config: &BaseVerificationConfig,
+    assignment: &FxHashMap<SymbolicName, BigInt>,
+) -> Option<(SymbolicName, VerificationResult)> {
+    let template = &symbolic_library.template_library
+        [&symbolic_library.name2id[&base_config.target_template_name]];
+    let mut keys: Vec<_> = assignment.keys().collect();
+    keys.sort();
+    for k in keys {
+        if k.owner.len() == 1 && template.output_ids.contains(&k.id) {
+            let tags = k.get_tags(template);
+            if let Some(domain) = get_domain_of_tags(tags) {
+                let v = &assignment[k];
+                if !domain.iter().any(|d| is_equal_mod(v, d, &base_config.prime)) {
+                    return Some((
+                        k.clone(),
+                        VerificationResult::UnderConstrained(UnderConstrainedType::TagViolation(
+                            k.clone(),
+                            k.lookup_fmt(&symbolic_library.id2name),
+                            tags.join(","),
+                        )),
+                    ));
+                }
+            }
+        }
+    }
+    None
 }
 
 /// Determines whether a collection of symbolic values contains a binary equality check against zero.  
@@ -869,12 +1196,14 @@ pub fn evaluate_symbolic_value(
             }
 
             match (&lhs_val.unwrap(), &rhs_val.unwrap()) {
-                (SymbolicValue::ConstantInt(lv), SymbolicValue::ConstantInt(rv)) => {
-                    Some(SymbolicValue::ConstantBool(lv % prime == rv % prime))
-                }
+                (SymbolicValue::ConstantInt(lv), SymbolicValue::ConstantInt(rv)) => Some(
+                    SymbolicValue::ConstantBool(modreduce(lv, prime) == modreduce(rv, prime)),
+                ),
                 (SymbolicValue::ConstantInt(lv), SymbolicValue::ConstantBool(rv)) => {
                     let rv_to_int = if *rv { BigInt::one() } else { BigInt::zero() };
-                    Some(SymbolicValue::ConstantBool(lv % prime == rv_to_int))
+                    Some(SymbolicValue::ConstantBool(
+                        modreduce(lv, prime) == rv_to_int,
+                    ))
                 }
                 _ => panic!(
                     "Unassigned variables exist: {}",
@@ -919,7 +1248,12 @@ pub fn evaluate_symbolic_value(
 
             match &expr_val.unwrap() {
                 SymbolicValue::ConstantInt(rv) => match op.0 {
-                    ExpressionPrefixOpcode::Sub => Some(SymbolicValue::ConstantInt(-1 * rv)),
+                    ExpressionPrefixOpcode::Sub => {
+                        Some(SymbolicValue::ConstantInt(modreduce(&-rv, prime)))
+                    }
+                    ExpressionPrefixOpcode::Complement => {
+                        Some(SymbolicValue::ConstantInt(complement(rv, prime)))
+                    }
                     _ => panic!(
                         "Unassigned variables exist: {}",
                         value.lookup_fmt(&symbolic_library.id2name)
@@ -971,8 +1305,15 @@ pub fn evaluate_symbolic_value(
                 substitute_output: false,
                 propagate_assignments: true,
                 constraint_assert_dissabled: false,
+                max_index_case_splits: DEFAULT_MAX_INDEX_CASE_SPLITS,
+                function_summary_capacity: 0,
+                max_call_depth: DEFAULT_MAX_CALL_DEPTH,
+                trace_log: None,
+                concolic_guide: None,
+                log_sink: None,
             };
             let mut subse = SymbolicExecutor::new(symbolic_library, &setting);
+            subse.cur_state.set_template_id(*id);
 
             let func = subse.symbolic_library.function_library[id].clone();
             for i in 0..(func.function_argument_names.len()) {
@@ -1271,6 +1612,73 @@ pub fn verify_assignment(
         assignment,
         &mut sexe.symbolic_library,
     );
+    classify_evaluated_assignment(sexe, is_satisfy_st, is_satisfy_sc, assignment, setting)
+}
+
+/// Verifies an assignment like `verify_assignment`, but evaluates the symbolic trace and the side
+/// constraints on their compiled tapes.
+///
+/// # Parameters
+/// - `sexe`: A mutable reference to the symbolic executor.
+/// - `compiled_trace`: The compiled symbolic trace.
+/// - `compiled_side_constraints`: The compiled side constraints.
+/// - `assignment`: A mapping from symbolic names to concrete integer values representing the assignment to be verified.
+/// - `setting`: Configuration settings (`BaseVerificationConfig`).
+///
+/// # Returns
+/// The same `VerificationResult` as `verify_assignment` on the original constraints.
+pub fn verify_assignment_with_compiled_constraints(
+    sexe: &mut SymbolicExecutor,
+    compiled_trace: &CompiledConstraints,
+    compiled_side_constraints: &CompiledConstraints,
+    assignment: &FxHashMap<SymbolicName, BigInt>,
+    setting: &BaseVerificationConfig,
+) -> VerificationResult {
+    let is_satisfy_st =
+        compiled_trace.evaluate(&setting.prime, assignment, &mut sexe.symbolic_library);
+    let is_satisfy_sc =
+        compiled_side_constraints.evaluate(&setting.prime, assignment, &mut sexe.symbolic_library);
+    classify_evaluated_assignment(sexe, is_satisfy_st, is_satisfy_sc, assignment, setting)
+}
+
+/// Verifies an assignment like `verify_assignment_with_compiled_constraints`, but lets
+/// `scheduler` decide in which order the constraints are evaluated.
+///
+/// # Parameters
+/// - `sexe`: A mutable reference to the symbolic executor.
+/// - `scheduler`: The scheduler of the symbolic trace and the side constraints.
+/// - `assignment`: A mapping from symbolic names to concrete integer values representing the assignment to be verified.
+/// - `setting`: Configuration settings (`BaseVerificationConfig`).
+///
+/// # Returns
+/// The same `VerificationResult` as `verify_assignment` on the original constraints.
+pub fn verify_assignment_with_scheduler(
+    sexe: &mut SymbolicExecutor,
+    scheduler: &mut ConstraintScheduler,
+    assignment: &FxHashMap<SymbolicName, BigInt>,
+    setting: &BaseVerificationConfig,
+) -> VerificationResult {
+    let (is_satisfy_st, is_satisfy_sc) =
+        scheduler.evaluate(&setting.prime, assignment, &mut sexe.symbolic_library);
+    classify_evaluated_assignment(sexe, is_satisfy_st, is_satisfy_sc, assignment, setting)
+}
+
+/// Classifies an assignment from whether it satisfies the symbolic trace and the side
+/// constraints, re-executing the circuit concretely if only the side constraints are satisfied.
+pub(crate) fn classify_evaluated_assignment(
+    sexe: &mut SymbolicExecutor,
+    is_satisfy_st: bool,
+    is_satisfy_sc: bool,
+    assignment: &FxHashMap<SymbolicName, BigInt>,
+    setting: &BaseVerificationConfig,
+) -> VerificationResult {
+    if setting.check_output_tags && is_satisfy_sc {
+        if let Some((_, result)) =
+            find_output_tag_violation(sexe.symbolic_library, setting, assignment)
+        {
+            return result;
+        }
+    }
 
     if is_satisfy_st && !is_satisfy_sc {
         VerificationResult::OverConstrained
@@ -1291,7 +1699,7 @@ pub fn verify_assignment(
             let vc = sexe.violated_condition.clone().unwrap();
             return VerificationResult::UnderConstrained(UnderConstrainedType::UnexpectedInput(
                 vc.0,
-                vc.1.lookup_fmt(&sexe.symbolic_library.id2name),
+                ViolatedConstraint::new(&vc.1, &sexe.symbolic_library.id2name),
             ));
         }
 
@@ -1302,7 +1710,7 @@ pub fn verify_assignment(
                 .output_ids
                 .contains(&k.id)
             {
-                let original_sym_value = sexe.cur_state.symbol_binding_map[&k].clone();
+                let original_sym_value = sexe.cur_state.symbol_binding_map[k].clone();
                 let mut memo = FxHashSet::default();
                 let simplified_sym_value = sexe.simplify_variables(
                     &original_sym_value,
@@ -0,0 +1,202 @@
+use std::str::FromStr;
+
+use num_bigint_dig::BigInt;
+use num_bigint_dig::RandBigInt;
+use num_traits::{One, Zero};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use rustc_hash::{FxHashMap, FxHashSet};
+use serde_json::{json, Value};
+
+use crate::executor::symbolic_execution::SymbolicExecutor;
+use crate::executor::symbolic_value::{extract_variables, SymbolicName, SymbolicValueRef};
+use crate::mutator::search_ranges::SearchRanges;
+use crate::mutator::utils::{
+    classify_evaluated_assignment, evaluate_constraints, gather_input_domains,
+    BaseVerificationConfig, VerificationResult,
+};
+
+/// The number of standard deviations of the 95% confidence interval of a normal distribution.
+const Z_95: f64 = 1.959964;
+
+/// How the values of the sampled assignments are drawn.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DensitySampling {
+    /// Every variable takes a value of the whole field.
+    Uniform,
+    /// A variable whose width is forced by the constraints (see `SearchRanges`) takes a value of
+    /// `[0, 2^width)`, and any other variable a value of the whole field.
+    Ranges,
+}
+
+impl DensitySampling {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DensitySampling::Uniform => "uniform",
+            DensitySampling::Ranges => "ranges",
+        }
+    }
+}
+
+impl FromStr for DensitySampling {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "uniform" => Ok(DensitySampling::Uniform),
+            "ranges" => Ok(DensitySampling::Ranges),
+            _ => Err(format!(
+                "unknown density sampling `{}` (expected uniform or ranges)",
+                s
+            )),
+        }
+    }
+}
+
+/// The fraction of random assignments that the verifier accepts but the witness generator never
+/// computes, i.e. how wide the under-constraint holes of the circuit are.
+pub struct ViolationDensity {
+    pub sampling: DensitySampling,
+    pub num_samples: usize,
+    /// The number of samples satisfying the side constraints.
+    pub num_accepted: usize,
+    /// The number of samples satisfying the side constraints whose values differ from those
+    /// computed by the concrete execution of their inputs.
+    pub num_violations: usize,
+}
+
+impl ViolationDensity {
+    /// Returns the fraction of the samples that are violations.
+    pub fn density(&self) -> f64 {
+        if self.num_samples == 0 {
+            0.0
+        } else {
+            self.num_violations as f64 / self.num_samples as f64
+        }
+    }
+
+    /// Returns the 95% Wilson score interval of the density, which remains meaningful when no or
+    /// every sample is a violation.
+    pub fn confidence_interval(&self) -> (f64, f64) {
+        if self.num_samples == 0 {
+            return (0.0, 1.0);
+        }
+        let n = self.num_samples as f64;
+        let p = self.density();
+        let z2 = Z_95 * Z_95;
+        let denominator = 1.0 + z2 / n;
+        let center = (p + z2 / (2.0 * n)) / denominator;
+        let half_width = Z_95 * (p * (1.0 - p) / n + z2 / (4.0 * n * n)).sqrt() / denominator;
+        (
+            (center - half_width).max(0.0),
+            (center + half_width).min(1.0),
+        )
+    }
+
+    pub fn summary(&self) -> String {
+        let (lower, upper) = self.confidence_interval();
+        format!(
+            "{} / {} samples ({} accepted by the side constraints), density {:.4}%, 95% CI [{:.4}%, {:.4}%] ({} sampling)",
+            self.num_violations,
+            self.num_samples,
+            self.num_accepted,
+            self.density() * 100.0,
+            lower * 100.0,
+            upper * 100.0,
+            self.sampling.as_str()
+        )
+    }
+
+    pub fn to_json(&self) -> Value {
+        let (lower, upper) = self.confidence_interval();
+        json!({
+            "sampling": self.sampling.as_str(),
+            "num_samples": self.num_samples,
+            "num_accepted": self.num_accepted,
+            "num_violations": self.num_violations,
+            "density": self.density(),
+            "confidence_interval": [lower, upper],
+        })
+    }
+}
+
+/// Estimates the density of the violations among random assignments of the variables of the
+/// constraints.
+///
+/// Each sample assigns every variable of the symbolic trace and of the side constraints. It is a
+/// violation if it satisfies the side constraints but not the symbolic trace, and the concrete
+/// execution of its inputs computes other values, as for the counterexamples of the search. The
+/// inputs restricted with `--input_spec` or by tags take values of their domains.
+///
+/// # Parameters
+/// - `sexe`: The symbolic executor used for the concrete executions.
+/// - `symbolic_trace`: The constraints of the program trace.
+/// - `side_constraints`: The side constraints of the program.
+/// - `base_config`: The verification configuration specifying the target template and prime.
+/// - `sampling`: How the values of the variables are drawn.
+/// - `num_samples`: The number of sampled assignments.
+/// - `seed`: The seed of the random assignments.
+pub fn estimate_violation_density(
+    sexe: &mut SymbolicExecutor,
+    symbolic_trace: &[SymbolicValueRef],
+    side_constraints: &[SymbolicValueRef],
+    base_config: &BaseVerificationConfig,
+    sampling: DensitySampling,
+    num_samples: usize,
+    seed: u64,
+) -> ViolationDensity {
+    let mut seen = FxHashSet::default();
+    let variables: Vec<SymbolicName> = extract_variables(symbolic_trace)
+        .into_iter()
+        .chain(extract_variables(side_constraints))
+        .filter(|var| seen.insert(var.clone()))
+        .collect();
+    let domains = gather_input_domains(sexe.symbolic_library, base_config, &variables);
+    let ranges = SearchRanges::infer(sexe, symbolic_trace, side_constraints, base_config);
+
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut density = ViolationDensity {
+        sampling: sampling,
+        num_samples: num_samples,
+        num_accepted: 0,
+        num_violations: 0,
+    };
+    for _ in 0..num_samples {
+        let assignment: FxHashMap<SymbolicName, BigInt> = variables
+            .iter()
+            .map(|var| {
+                let value = match (domains.get(var), ranges.bounds.get(var)) {
+                    (Some(domain), _) => domain.sample(&mut rng),
+                    (None, Some(max)) if sampling == DensitySampling::Ranges => {
+                        rng.gen_bigint_range(&BigInt::zero(), &(max + BigInt::one()))
+                    }
+                    _ => rng.gen_bigint_range(&BigInt::zero(), &base_config.prime),
+                };
+                (var.clone(), value)
+            })
+            .collect();
+
+        if !evaluate_constraints(
+            &base_config.prime,
+            side_constraints,
+            &assignment,
+            &mut sexe.symbolic_library,
+        ) {
+            continue;
+        }
+        density.num_accepted += 1;
+        let is_satisfy_st = evaluate_constraints(
+            &base_config.prime,
+            symbolic_trace,
+            &assignment,
+            &mut sexe.symbolic_library,
+        );
+        if matches!(
+            classify_evaluated_assignment(sexe, is_satisfy_st, true, &assignment, base_config),
+            VerificationResult::UnderConstrained(_)
+        ) {
+            density.num_violations += 1;
+        }
+    }
+    density
+}
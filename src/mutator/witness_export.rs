@@ -0,0 +1,571 @@
+use std::fs::File;
+use std::io::{self, Write};
+use std::path::Path;
+use std::rc::Rc;
+use std::str::FromStr;
+
+use num_bigint_dig::BigInt;
+use num_traits::{One, ToPrimitive};
+use rustc_hash::{FxHashMap, FxHashSet};
+use serde_json::{json, Map, Value};
+
+use crate::executor::symbolic_value::{
+    OwnerName, SymbolicAccess, SymbolicLibrary, SymbolicName, SymbolicValue,
+};
+use crate::executor::utils::{generate_cartesian_product_indices, modreduce};
+use crate::mutator::utils::{
+    BaseVerificationConfig, CounterExample, UnderConstrainedType, VerificationResult,
+};
+
+fn zero_filled_value(dims: &[usize]) -> Value {
+    if dims.is_empty() {
+        json!("0")
+    } else {
+        Value::Array(vec![zero_filled_value(&dims[1..]); dims[0]])
+    }
+}
+
+fn set_nested_value(target: &mut Value, indices: &[usize], value: Value, filler: &Value) {
+    if indices.is_empty() {
+        *target = value;
+        return;
+    }
+    if !target.is_array() {
+        *target = Value::Array(Vec::new());
+    }
+    let array = target.as_array_mut().unwrap();
+    if array.len() <= indices[0] {
+        array.resize(indices[0] + 1, filler.clone());
+    }
+    set_nested_value(&mut array[indices[0]], &indices[1..], value, filler);
+}
+
+fn concrete_indices(access: &Option<Vec<SymbolicAccess>>) -> Option<Vec<usize>> {
+    let mut indices = Vec::new();
+    if let Some(access) = access {
+        for a in access {
+            match a {
+                SymbolicAccess::ArrayAccess(SymbolicValue::ConstantInt(i)) => {
+                    indices.push(i.to_usize()?)
+                }
+                _ => return None,
+            }
+        }
+    }
+    Some(indices)
+}
+
+fn build_signal_json(
+    assignment: &FxHashMap<SymbolicName, BigInt>,
+    symbolic_library: &SymbolicLibrary,
+    prime: &BigInt,
+    id2dimensions: &FxHashMap<usize, Vec<usize>>,
+    ids: &FxHashSet<usize>,
+) -> Value {
+    let mut signals = Map::new();
+    let mut sorted_ids: Vec<_> = ids.iter().collect();
+    sorted_ids.sort_by(|a, b| symbolic_library.id2name[a].cmp(&symbolic_library.id2name[b]));
+    for id in sorted_ids {
+        let dims = id2dimensions.get(id).cloned().unwrap_or_default();
+        signals.insert(symbolic_library.id2name[id].clone(), zero_filled_value(&dims));
+    }
+
+    let mut keys: Vec<_> = assignment.keys().collect();
+    keys.sort();
+    for k in keys {
+        if k.owner.len() != 1 || !ids.contains(&k.id) {
+            continue;
+        }
+        if let Some(indices) = concrete_indices(&k.access) {
+            let value = json!(modreduce(&assignment[k], prime).to_string());
+            let entry = signals
+                .entry(symbolic_library.id2name[&k.id].clone())
+                .or_insert(json!("0"));
+            set_nested_value(entry, &indices, value, &json!("0"));
+        }
+    }
+    Value::Object(signals)
+}
+
+/// Builds the `input.json` of the main component from the assignment of a counterexample.
+///
+/// Array inputs are nested following their dimensions, and the elements that do not appear in
+/// the assignment are filled with `"0"`. Only the inputs of the main template are included,
+/// since those are the only signals a witness generator accepts.
+///
+/// # Parameters
+/// - `assignment`: The assignment of the counterexample.
+/// - `symbolic_library`: The symbolic library containing the template definitions.
+/// - `base_config`: The verification configuration specifying the target template and prime.
+/// - `id2dimensions`: The dimensions of the signals declared in the main template.
+///
+/// # Returns
+/// A JSON object mapping each input name to its (possibly nested) decimal value.
+pub fn build_input_json(
+    assignment: &FxHashMap<SymbolicName, BigInt>,
+    symbolic_library: &SymbolicLibrary,
+    base_config: &BaseVerificationConfig,
+    id2dimensions: &FxHashMap<usize, Vec<usize>>,
+) -> Value {
+    let template = &symbolic_library.template_library
+        [&symbolic_library.name2id[&base_config.target_template_name]];
+    build_signal_json(
+        assignment,
+        symbolic_library,
+        &base_config.prime,
+        id2dimensions,
+        &template.input_ids,
+    )
+}
+
+/// Builds the outputs of the main component from an assignment, nested like `build_input_json`,
+/// as expected by the `assertOut` of `circom_tester`.
+pub fn build_output_json(
+    assignment: &FxHashMap<SymbolicName, BigInt>,
+    symbolic_library: &SymbolicLibrary,
+    base_config: &BaseVerificationConfig,
+    id2dimensions: &FxHashMap<usize, Vec<usize>>,
+) -> Value {
+    let template = &symbolic_library.template_library
+        [&symbolic_library.name2id[&base_config.target_template_name]];
+    build_signal_json(
+        assignment,
+        symbolic_library,
+        &base_config.prime,
+        id2dimensions,
+        &template.output_ids,
+    )
+}
+
+fn flatten_input_value(
+    name: &str,
+    value: &Value,
+    indices: &mut Vec<usize>,
+    elements: &mut Vec<(Vec<usize>, BigInt)>,
+) -> Result<(), String> {
+    let number = match value {
+        Value::Array(array) => {
+            for (i, elem) in array.iter().enumerate() {
+                indices.push(i);
+                flatten_input_value(name, elem, indices, elements)?;
+                indices.pop();
+            }
+            return Ok(());
+        }
+        Value::String(s) => s.clone(),
+        Value::Number(n) => n.to_string(),
+        _ => return Err(format!("`{}` is neither a number nor an array", name)),
+    };
+    let number = BigInt::from_str(&number)
+        .map_err(|_| format!("`{}` is not an integer: {}", name, number))?;
+    elements.push((indices.clone(), number));
+    Ok(())
+}
+
+/// The number of elements listed per kind in the error of an input that does not match its
+/// declared shape.
+const MAX_LISTED_ELEMENTS: usize = 8;
+
+/// Formats an element of an input, e.g. `in[1][0]`.
+fn element_name(name: &str, indices: &[usize]) -> String {
+    indices
+        .iter()
+        .fold(name.to_string(), |s, i| format!("{}[{}]", s, i))
+}
+
+fn list_elements(name: &str, elements: &[Vec<usize>]) -> String {
+    let mut listed: Vec<_> = elements
+        .iter()
+        .take(MAX_LISTED_ELEMENTS)
+        .map(|indices| element_name(name, indices))
+        .collect();
+    if elements.len() > MAX_LISTED_ELEMENTS {
+        listed.push(format!("and {} more", elements.len() - MAX_LISTED_ELEMENTS));
+    }
+    listed.join(", ")
+}
+
+/// Checks that the elements given for an input are exactly the elements of its declared
+/// dimensions.
+///
+/// # Returns
+/// An error listing the missing and extra elements, e.g. ``the input `in` does not match its
+/// declared shape in[2][2]: missing in[1][1]; extra in[2][0]``.
+fn check_input_shape(
+    name: &str,
+    dims: &[usize],
+    elements: &[(Vec<usize>, BigInt)],
+) -> Result<(), String> {
+    let expected = generate_cartesian_product_indices(dims);
+    let expected_set: FxHashSet<&Vec<usize>> = expected.iter().collect();
+    let given: FxHashSet<&Vec<usize>> = elements.iter().map(|(indices, _)| indices).collect();
+    let missing: Vec<_> = expected
+        .iter()
+        .filter(|indices| !given.contains(indices))
+        .cloned()
+        .collect();
+    let extra: Vec<_> = elements
+        .iter()
+        .map(|(indices, _)| indices)
+        .filter(|indices| !expected_set.contains(indices))
+        .cloned()
+        .collect();
+    if missing.is_empty() && extra.is_empty() {
+        return Ok(());
+    }
+
+    let mut problems = Vec::new();
+    if !missing.is_empty() {
+        problems.push(format!("missing {}", list_elements(name, &missing)));
+    }
+    if !extra.is_empty() {
+        problems.push(format!("extra {}", list_elements(name, &extra)));
+    }
+    Err(format!(
+        "the input `{}` does not match its declared shape {}: {}",
+        name,
+        element_name(name, dims),
+        problems.join("; ")
+    ))
+}
+
+/// Reads the assignment of the inputs of the main component from an `input.json`, the inverse of
+/// `build_input_json`.
+///
+/// # Parameters
+/// - `input_json`: A JSON object mapping each input name to its (possibly nested) value, given
+///   as a decimal string or a number.
+/// - `symbolic_library`: The symbolic library containing the template definitions.
+/// - `base_config`: The verification configuration specifying the target template and prime. The
+///   value of each input is checked against its dimensions in `input_shapes`, evaluated with the
+///   template arguments of the main component.
+///
+/// # Returns
+/// The assignment of every element of the inputs, or an error if a value is not an integer, or
+/// listing every input that is missing, whose elements do not match its declared dimensions, or
+/// that is not an input of the main template. Without the check, an element left out would be
+/// unassigned during the concrete execution instead of being reported.
+pub fn parse_input_json(
+    input_json: &Value,
+    symbolic_library: &SymbolicLibrary,
+    base_config: &BaseVerificationConfig,
+) -> Result<FxHashMap<SymbolicName, BigInt>, String> {
+    let template = &symbolic_library.template_library
+        [&symbolic_library.name2id[&base_config.target_template_name]];
+    let owner = Rc::new(vec![OwnerName {
+        id: symbolic_library.name2id["main"],
+        counter: 0,
+        access: None,
+    }]);
+
+    let mut assignment = FxHashMap::default();
+    let mut problems = Vec::new();
+    for id in template.input_ids.iter() {
+        let name = &symbolic_library.id2name[id];
+        let value = match input_json.get(name) {
+            Some(value) => value,
+            None => {
+                problems.push(format!("the input `{}` is missing", name));
+                continue;
+            }
+        };
+        let mut elements = Vec::new();
+        flatten_input_value(name, value, &mut Vec::new(), &mut elements)?;
+        if let Some(dims) = base_config.input_shapes.get(id) {
+            if let Err(e) = check_input_shape(name, dims, &elements) {
+                problems.push(e);
+                continue;
+            }
+        }
+        for (indices, number) in elements {
+            let access = if indices.is_empty() {
+                None
+            } else {
+                Some(
+                    indices
+                        .into_iter()
+                        .map(|i| {
+                            SymbolicAccess::ArrayAccess(SymbolicValue::ConstantInt(BigInt::from(i)))
+                        })
+                        .collect(),
+                )
+            };
+            assignment.insert(
+                SymbolicName::new(*id, owner.clone(), access),
+                modreduce(&number, &base_config.prime),
+            );
+        }
+    }
+    if let Some(object) = input_json.as_object() {
+        for key in object.keys() {
+            let is_input = symbolic_library
+                .name2id
+                .get(key)
+                .map_or(false, |id| template.input_ids.contains(id));
+            if !is_input {
+                problems.push(format!(
+                    "`{}` is not an input of `{}`",
+                    key, base_config.target_template_name
+                ));
+            }
+        }
+    }
+    if !problems.is_empty() {
+        return Err(problems.join("\n"));
+    }
+    Ok(assignment)
+}
+
+/// Collects the values that the counterexample assigns to the outputs of the main template.
+///
+/// For a non-deterministic counterexample, the value expected by the honest computation is
+/// reported next to the output.
+///
+/// # Returns
+/// A JSON object mapping each output name (e.g. `main.out[0]`) to its decimal value.
+pub fn collect_counterexample_outputs(
+    counter_example: &CounterExample,
+    symbolic_library: &SymbolicLibrary,
+    base_config: &BaseVerificationConfig,
+) -> Value {
+    let template = &symbolic_library.template_library
+        [&symbolic_library.name2id[&base_config.target_template_name]];
+
+    let mut outputs = Map::new();
+    let mut keys: Vec<_> = counter_example.assignment.keys().collect();
+    keys.sort();
+    for k in keys {
+        if k.owner.len() == 1 && template.output_ids.contains(&k.id) {
+            let value = modreduce(&counter_example.assignment[k], &base_config.prime);
+            outputs.insert(k.lookup_fmt(&symbolic_library.id2name), json!(value.to_string()));
+        }
+    }
+    if let VerificationResult::UnderConstrained(UnderConstrainedType::NonDeterministic(
+        _,
+        name,
+        expected,
+    )) = &counter_example.flag
+    {
+        outputs.insert(
+            format!("{} (expected)", name),
+            json!(modreduce(expected, &base_config.prime).to_string()),
+        );
+    }
+    Value::Object(outputs)
+}
+
+/// The representation of the values of a counterexample in its JSON output (`--ce_format`).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ValueFormat {
+    /// Decimal strings, as in `input.json`.
+    Decimal,
+    /// Hexadecimal strings prefixed with `0x`.
+    Hex,
+    /// Little-endian bit arrays of the width of the prime, except for the `{binary}` signals of
+    /// the main template, which are written as `0` or `1`.
+    Bits,
+    /// Little-endian byte arrays of the width of the prime.
+    BytesLe,
+    /// Big-endian byte arrays of the width of the prime.
+    BytesBe,
+}
+
+impl FromStr for ValueFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "decimal" => Ok(ValueFormat::Decimal),
+            "hex" => Ok(ValueFormat::Hex),
+            "bits" => Ok(ValueFormat::Bits),
+            "bytes_le" => Ok(ValueFormat::BytesLe),
+            "bytes_be" => Ok(ValueFormat::BytesBe),
+            _ => Err(format!(
+                "unknown value format `{}` (expected decimal, hex, bits, bytes_le, or bytes_be)",
+                s
+            )),
+        }
+    }
+}
+
+/// How `CounterExample::to_json_with_meta` writes the grouped assignment of a counterexample.
+pub struct AssignmentFormat {
+    pub value_format: ValueFormat,
+    pub prime: BigInt,
+    /// The IDs of the signals of the main template tagged with `{binary}`.
+    pub binary_ids: FxHashSet<usize>,
+}
+
+impl AssignmentFormat {
+    /// Creates the format of the counterexamples of the main template `main_template_name`.
+    pub fn new(
+        value_format: ValueFormat,
+        prime: BigInt,
+        symbolic_library: &SymbolicLibrary,
+        main_template_name: &str,
+    ) -> Self {
+        let binary_ids = symbolic_library
+            .name2id
+            .get(main_template_name)
+            .and_then(|id| symbolic_library.template_library.get(id))
+            .map(|template| {
+                template
+                    .id2tags
+                    .iter()
+                    .filter(|(_, tags)| tags.iter().any(|tag| tag == "binary"))
+                    .map(|(id, _)| *id)
+                    .collect()
+            })
+            .unwrap_or_default();
+        AssignmentFormat {
+            value_format: value_format,
+            prime: prime,
+            binary_ids: binary_ids,
+        }
+    }
+
+    /// Formats the value that a counterexample assigns to `name`.
+    pub fn format_value(&self, name: &SymbolicName, value: &BigInt) -> Value {
+        let value = modreduce(value, &self.prime);
+        let width = self.prime.bits();
+        let mut bytes = value.to_bytes_le().1;
+        bytes.resize((width + 7) / 8, 0);
+        match self.value_format {
+            ValueFormat::Decimal => json!(value.to_string()),
+            ValueFormat::Hex => json!(format!("0x{}", value.to_str_radix(16))),
+            ValueFormat::Bits
+                if name.owner.len() == 1
+                    && self.binary_ids.contains(&name.id)
+                    && value <= BigInt::one() =>
+            {
+                json!(bytes[0])
+            }
+            ValueFormat::Bits => json!((0..width)
+                .map(|i| (bytes[i / 8] >> (i % 8)) & 1)
+                .collect::<Vec<_>>()),
+            ValueFormat::BytesLe => json!(bytes),
+            ValueFormat::BytesBe => {
+                bytes.reverse();
+                json!(bytes)
+            }
+        }
+    }
+}
+
+/// Groups the elements of the array signals of an assignment into (nested) arrays, e.g.
+/// `main.out[0]` and `main.out[1]` into `main.out`, with the values formatted by `format`.
+///
+/// Elements with a symbolic index are kept under their own names, and the elements of an array
+/// that do not appear in the assignment are `null`.
+///
+/// # Returns
+/// A JSON object mapping each signal name to its (possibly nested) formatted value.
+pub fn group_assignment(
+    assignment: &FxHashMap<SymbolicName, BigInt>,
+    lookup: &FxHashMap<usize, String>,
+    format: &AssignmentFormat,
+) -> Value {
+    let mut grouped = Map::new();
+    let mut keys: Vec<_> = assignment.keys().collect();
+    keys.sort();
+    for k in keys {
+        let value = format.format_value(k, &assignment[k]);
+        match concrete_indices(&k.access) {
+            Some(indices) => {
+                let name = SymbolicName::new(k.id, k.owner.clone(), None).lookup_fmt(lookup);
+                let entry = grouped.entry(name).or_insert(Value::Null);
+                set_nested_value(entry, &indices, value, &Value::Null);
+            }
+            None => {
+                grouped.insert(k.lookup_fmt(lookup), value);
+            }
+        }
+    }
+    Value::Object(grouped)
+}
+
+/// Generates a Node.js script that feeds `input.json` into the witness generator produced by
+/// `circom --wasm` and prints its outputs next to the ones of the counterexample.
+///
+/// # Parameters
+/// - `input_json_file_name`: The file name of the `input.json`, relative to the script.
+/// - `counterexample_outputs`: The outputs of the counterexample (see
+///   `collect_counterexample_outputs`).
+///
+/// # Returns
+/// The source code of the script.
+pub fn build_witness_script(input_json_file_name: &str, counterexample_outputs: &Value) -> String {
+    format!(
+        r#"// Generated by zkFuzz: replays a counterexample with the witness generator of `circom --wasm`.
+// Usage: node <this_script> <path/to/circuit_js/circuit.wasm> [output.wtns]
+const fs = require("fs");
+const path = require("path");
+
+const wasmPath = path.resolve(process.argv[2]);
+const wtnsPath = process.argv[3] || "counterexample.wtns";
+const builder = require(path.join(path.dirname(wasmPath), "witness_calculator.js"));
+const input = JSON.parse(fs.readFileSync(path.join(__dirname, "{input}"), "utf8"));
+
+// Outputs assigned by the counterexample found by zkFuzz
+const counterexampleOutputs = {outputs};
+
+builder(fs.readFileSync(wasmPath)).then(async (witnessCalculator) => {{
+    const witness = await witnessCalculator.calculateWitness(input, 0);
+    console.log("Outputs computed by the witness generator (w[1], w[2], ...):");
+    const numOutputs = Object.keys(counterexampleOutputs).filter((name) => !name.endsWith(" (expected)")).length;
+    for (let i = 1; i <= numOutputs && i < witness.length; i++) {{
+        console.log(`  w[${{i}}] = ${{witness[i]}}`);
+    }}
+    console.log("Outputs in the counterexample:");
+    for (const [name, value] of Object.entries(counterexampleOutputs)) {{
+        console.log(`  ${{name}} = ${{value}}`);
+    }}
+    fs.writeFileSync(wtnsPath, await witnessCalculator.calculateWTNSBin(input, 0));
+    console.log(`Witness written to ${{wtnsPath}}`);
+}});
+"#,
+        input = input_json_file_name,
+        outputs = serde_json::to_string_pretty(counterexample_outputs).unwrap(),
+    )
+}
+
+/// Writes `<prefix>_input.json` and `<prefix>_witness.js` for the given counterexample.
+///
+/// # Parameters
+/// - `prefix`: The common prefix of the generated files.
+/// - `counter_example`: The counterexample to replay.
+/// - `symbolic_library`: The symbolic library containing the template definitions.
+/// - `base_config`: The verification configuration specifying the target template and prime.
+/// - `id2dimensions`: The dimensions of the signals declared in the main template.
+///
+/// # Returns
+/// The paths of the generated `input.json` and script.
+pub fn save_witness_generator_files(
+    prefix: &str,
+    counter_example: &CounterExample,
+    symbolic_library: &SymbolicLibrary,
+    base_config: &BaseVerificationConfig,
+    id2dimensions: &FxHashMap<usize, Vec<usize>>,
+) -> io::Result<(String, String)> {
+    let input_json_path = format!("{}_input.json", prefix);
+    let script_path = format!("{}_witness.js", prefix);
+
+    let input_json = build_input_json(
+        &counter_example.assignment,
+        symbolic_library,
+        base_config,
+        id2dimensions,
+    );
+    let mut file = File::create(&input_json_path)?;
+    file.write_all(serde_json::to_string_pretty(&input_json)?.as_bytes())?;
+
+    let input_json_file_name = Path::new(&input_json_path)
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| input_json_path.clone());
+    let outputs = collect_counterexample_outputs(counter_example, symbolic_library, base_config);
+    let mut file = File::create(&script_path)?;
+    file.write_all(build_witness_script(&input_json_file_name, &outputs).as_bytes())?;
+
+    Ok((input_json_path, script_path))
+}
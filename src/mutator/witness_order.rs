@@ -0,0 +1,262 @@
+use std::collections::VecDeque;
+
+use program_structure::file_definition::FileLibrary;
+use rustc_hash::{FxHashMap, FxHashSet};
+
+use crate::executor::symbolic_execution::SymbolicExecutor;
+use crate::executor::symbolic_value::{
+    extract_variables_from_symbolic_value, SymbolicName, SymbolicValue, SymbolicValueRef,
+};
+use crate::executor::utils::strip_ansi;
+use crate::mutator::division::{gather_statement_metas, statement_location};
+
+/// The ways the order of the assignments of the trace prevents the witness generator from
+/// computing a signal deterministically.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum WitnessOrderIssueKind {
+    /// The assignments of the signals depend on each other, so none of them can be computed
+    /// first.
+    Cycle,
+    /// An assignment reads a signal that is only assigned later, so the witness generator reads
+    /// a value that is not determined yet.
+    UseBeforeAssignment,
+}
+
+/// An issue of the order of the assignments found by `gather_witness_order_issues`.
+pub struct WitnessOrderIssue {
+    pub kind: WitnessOrderIssueKind,
+    /// The owner-qualified names of the signals, each computed from the next one. For `Cycle`,
+    /// the cyclic chain starting from the signal assigned first, whose last signal is computed
+    /// from the first one. For `UseBeforeAssignment`, the assigned signal and the signal it reads.
+    pub chain: Vec<String>,
+    /// The source locations of the first assignments of the signals of `chain`.
+    pub locations: Vec<String>,
+}
+
+impl WitnessOrderIssue {
+    pub fn message(&self) -> String {
+        match self.kind {
+            WitnessOrderIssueKind::Cycle => {
+                let mut chain: Vec<String> = self
+                    .chain
+                    .iter()
+                    .map(|signal| format!("`{}`", signal))
+                    .collect();
+                chain.push(chain[0].clone());
+                format!(
+                    "cyclic assignments: {} (at {})",
+                    chain.join(", which depends on "),
+                    self.locations.join(", ")
+                )
+            }
+            WitnessOrderIssueKind::UseBeforeAssignment => format!(
+                "`{}` is computed at {} from `{}`, which is only assigned later at {}",
+                self.chain[0], self.locations[0], self.chain[1], self.locations[1]
+            ),
+        }
+    }
+}
+
+/// Returns the strongly connected component of each node of the graph whose outgoing edges are
+/// `edges`, with Tarjan's algorithm unrolled into an explicit stack.
+fn strongly_connected_components(edges: &[Vec<usize>]) -> Vec<usize> {
+    let num_nodes = edges.len();
+    let mut index = vec![usize::MAX; num_nodes];
+    let mut lowlink = vec![0; num_nodes];
+    let mut on_stack = vec![false; num_nodes];
+    let mut component = vec![usize::MAX; num_nodes];
+    let mut stack = Vec::new();
+    let mut next_index = 0;
+    let mut num_components = 0;
+
+    for root in 0..num_nodes {
+        if index[root] != usize::MAX {
+            continue;
+        }
+        index[root] = next_index;
+        lowlink[root] = next_index;
+        next_index += 1;
+        stack.push(root);
+        on_stack[root] = true;
+        let mut work = vec![(root, 0)];
+        while let Some((node, edge)) = work.last().cloned() {
+            if edge < edges[node].len() {
+                work.last_mut().unwrap().1 += 1;
+                let next = edges[node][edge];
+                if index[next] == usize::MAX {
+                    index[next] = next_index;
+                    lowlink[next] = next_index;
+                    next_index += 1;
+                    stack.push(next);
+                    on_stack[next] = true;
+                    work.push((next, 0));
+                } else if on_stack[next] {
+                    lowlink[node] = lowlink[node].min(index[next]);
+                }
+                continue;
+            }
+            work.pop();
+            if let Some(&(parent, _)) = work.last() {
+                lowlink[parent] = lowlink[parent].min(lowlink[node]);
+            }
+            if lowlink[node] == index[node] {
+                loop {
+                    let member = stack.pop().unwrap();
+                    on_stack[member] = false;
+                    component[member] = num_components;
+                    if member == node {
+                        break;
+                    }
+                }
+                num_components += 1;
+            }
+        }
+    }
+    component
+}
+
+/// Returns the shortest cycle from `start` back to itself through the nodes of its strongly
+/// connected component, or `None` if `start` is not on a cycle.
+fn shortest_cycle(edges: &[Vec<usize>], component: &[usize], start: usize) -> Option<Vec<usize>> {
+    let mut parent: FxHashMap<usize, usize> = FxHashMap::default();
+    let mut queue = VecDeque::from(vec![start]);
+    while let Some(node) = queue.pop_front() {
+        for &next in &edges[node] {
+            if next == start {
+                // The parents lead back to `start`, which has none
+                let mut cycle = vec![node];
+                let mut current = node;
+                while let Some(&prev) = parent.get(&current) {
+                    cycle.push(prev);
+                    current = prev;
+                }
+                cycle.reverse();
+                return Some(cycle);
+            }
+            if component[next] == component[start] && !parent.contains_key(&next) {
+                parent.insert(next, node);
+                queue.push_back(next);
+            }
+        }
+    }
+    None
+}
+
+/// Checks that the assignments of the trace can be computed in order by the witness generator.
+///
+/// circom requires every signal read by the right-hand side of `<--` or `<==` to be determined
+/// when the assignment runs. The assignments (including those of the sub-components, which run
+/// once their inputs are fed) are ordered as they appear in the trace, and the signals they read
+/// form a dependency graph. A cycle of the graph cannot be computed in any order, and an
+/// assignment reading a signal assigned later reads an undetermined value, which makes the
+/// witness generator fail or depend on its implementation. Signals that are never assigned,
+/// such as the inputs of the main template, are not part of the graph.
+///
+/// # Parameters
+/// - `sexe`: The symbolic executor after the execution of the main template.
+/// - `symbolic_trace`: The constraints of the program trace.
+/// - `file_library`: The file library used to resolve the source locations of the assignments.
+///
+/// # Returns
+/// A vector of `WitnessOrderIssue`s: the cycles, one per strongly connected component of the
+/// graph, followed by the other uses of signals before their assignments, in trace order.
+pub fn gather_witness_order_issues(
+    sexe: &SymbolicExecutor,
+    symbolic_trace: &[SymbolicValueRef],
+    file_library: &FileLibrary,
+) -> Vec<WitnessOrderIssue> {
+    let library = &sexe.symbolic_library;
+    let metas = gather_statement_metas(library);
+    let mut locations: FxHashMap<&SymbolicName, String> = FxHashMap::default();
+    for assignment in &sexe.signal_assignments {
+        locations.entry(&assignment.name).or_insert_with(|| {
+            statement_location(
+                library,
+                &metas,
+                file_library,
+                assignment.elem_id,
+                assignment.owner_id,
+            )
+        });
+    }
+    let signal_fmt = |name: &SymbolicName| strip_ansi(&name.lookup_fmt(&library.id2name));
+    let location_of = |name: &SymbolicName| {
+        locations
+            .get(name)
+            .cloned()
+            .unwrap_or_else(|| String::from("an unknown location"))
+    };
+
+    // The nodes are the assigned signals, numbered in the order of their first assignments
+    let mut nodes: Vec<&SymbolicName> = Vec::new();
+    let mut node_ids: FxHashMap<&SymbolicName, usize> = FxHashMap::default();
+    let mut first_positions: Vec<usize> = Vec::new();
+    let mut assignments: Vec<(usize, usize, FxHashSet<SymbolicName>)> = Vec::new();
+    for (position, constraint) in symbolic_trace.iter().enumerate() {
+        match &**constraint {
+            SymbolicValue::Assign(lhs, rhs, ..)
+            | SymbolicValue::AssignEq(lhs, rhs)
+            | SymbolicValue::AssignCall(lhs, rhs, _) => {
+                if let SymbolicValue::Variable(name) = &**lhs {
+                    let id = *node_ids.entry(name).or_insert_with(|| {
+                        nodes.push(name);
+                        first_positions.push(position);
+                        nodes.len() - 1
+                    });
+                    let mut read = FxHashSet::default();
+                    extract_variables_from_symbolic_value(rhs, &mut read);
+                    assignments.push((position, id, read));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let mut edges: Vec<Vec<usize>> = vec![Vec::new(); nodes.len()];
+    for (_, id, read) in &assignments {
+        edges[*id].extend(read.iter().filter_map(|name| node_ids.get(name)));
+    }
+    for targets in edges.iter_mut() {
+        targets.sort();
+        targets.dedup();
+    }
+    let component = strongly_connected_components(&edges);
+
+    let mut issues = Vec::new();
+    let mut reported_components = FxHashSet::default();
+    for start in 0..nodes.len() {
+        if !reported_components.insert(component[start]) {
+            continue;
+        }
+        if let Some(cycle) = shortest_cycle(&edges, &component, start) {
+            issues.push(WitnessOrderIssue {
+                kind: WitnessOrderIssueKind::Cycle,
+                chain: cycle.iter().map(|id| signal_fmt(nodes[*id])).collect(),
+                locations: cycle.iter().map(|id| location_of(nodes[*id])).collect(),
+            });
+        }
+    }
+
+    let mut reported_reads = FxHashSet::default();
+    for (position, id, read) in &assignments {
+        let mut read: Vec<usize> = read
+            .iter()
+            .filter_map(|name| node_ids.get(name))
+            .cloned()
+            .collect();
+        read.sort();
+        for read in read {
+            if first_positions[read] > *position
+                && component[read] != component[*id]
+                && reported_reads.insert((*id, read))
+            {
+                issues.push(WitnessOrderIssue {
+                    kind: WitnessOrderIssueKind::UseBeforeAssignment,
+                    chain: vec![signal_fmt(nodes[*id]), signal_fmt(nodes[read])],
+                    locations: vec![location_of(nodes[*id]), location_of(nodes[read])],
+                });
+            }
+        }
+    }
+    issues
+}
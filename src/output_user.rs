@@ -0,0 +1,100 @@
+use std::env;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::executor::utils::strip_ansi;
+
+/// Whether the reports are printed without colors and emoji. The `plain` feature makes it the
+/// default.
+static PLAIN: AtomicBool = AtomicBool::new(cfg!(feature = "plain"));
+
+/// Prints to the standard output like `print!`, without colors and emoji in plain mode.
+macro_rules! out {
+    ($($arg:tt)*) => {
+        print!("{}", $crate::output_user::render(format!($($arg)*)))
+    };
+}
+
+/// Prints to the standard output like `println!`, without colors and emoji in plain mode.
+macro_rules! outln {
+    () => {
+        println!()
+    };
+    ($($arg:tt)*) => {
+        println!("{}", $crate::output_user::render(format!($($arg)*)))
+    };
+}
+
+/// Prints to the standard error like `eprint!`, without colors and emoji in plain mode.
+#[allow(unused_macros)] // Only used by the batch mode of the binary
+macro_rules! eout {
+    ($($arg:tt)*) => {
+        eprint!("{}", $crate::output_user::render(format!($($arg)*)))
+    };
+}
+
+/// Prints to the standard error like `eprintln!`, without colors and emoji in plain mode.
+macro_rules! eoutln {
+    () => {
+        eprintln!()
+    };
+    ($($arg:tt)*) => {
+        eprintln!("{}", $crate::output_user::render(format!($($arg)*)))
+    };
+}
+
+/// Switches to the plain mode if `flag_plain` (`--plain`) is set, the `plain` feature is enabled,
+/// or the `NO_COLOR` environment variable is set to a non-empty value
+/// (see <https://no-color.org>).
+pub fn init_output(flag_plain: bool) {
+    let no_color = env::var_os("NO_COLOR").map_or(false, |v| !v.is_empty());
+    set_plain(flag_plain || no_color || cfg!(feature = "plain"));
+}
+
+pub fn set_plain(plain: bool) {
+    PLAIN.store(plain, Ordering::Relaxed);
+    if plain {
+        colored::control::set_override(false);
+    } else {
+        colored::control::unset_override();
+    }
+}
+
+pub fn is_plain() -> bool {
+    PLAIN.load(Ordering::Relaxed)
+}
+
+/// Returns `text` as it should be printed, i.e. without colors and emoji in plain mode.
+pub fn render(text: String) -> String {
+    if is_plain() {
+        to_plain(&text)
+    } else {
+        text
+    }
+}
+
+fn is_emoji(c: char) -> bool {
+    matches!(
+        c as u32,
+        0x2300..=0x23FF | 0x2600..=0x27BF | 0x2B00..=0x2BFF | 0x1F000..=0x1FAFF
+    )
+}
+
+/// Removes the ANSI escape sequences and the emoji of `text`, together with the variation
+/// selectors and joiners of the emoji and the space separating an emoji from the text it
+/// decorates (e.g. `🎲 Random Seed: 7` becomes `Random Seed: 7`). Box-drawing characters, arrows,
+/// and the other symbols are kept.
+pub fn to_plain(text: &str) -> String {
+    let mut plain = String::with_capacity(text.len());
+    let mut after_emoji = false;
+    for c in strip_ansi(text).chars() {
+        if is_emoji(c) || (after_emoji && matches!(c, '\u{FE0F}' | '\u{200D}' | '\u{20E3}')) {
+            after_emoji = true;
+            continue;
+        }
+        if !(after_emoji && c == ' ') {
+            plain.push(c);
+        }
+        after_emoji = false;
+    }
+    plain
+}
@@ -1,29 +1,890 @@
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
 
+use colored::Colorize;
+use num_bigint_dig::BigInt;
+use rustc_hash::{FxHashMap, FxHashSet};
+use serde_json::Value;
+
+use program_structure::ast::{Expression, ExpressionPrefixOpcode, Meta, Statement};
 use program_structure::constants::UsefulConstants;
 use program_structure::error_definition::Report;
+use program_structure::file_definition::FileLibrary;
 use program_structure::program_archive::ProgramArchive;
 
-use super::input_user::Input;
 use crate::VERSION;
 
-pub fn parse_project(input_info: &Input) -> Result<ProgramArchive, ()> {
-    let initial_file = input_info.input_file().to_string();
+/// Parses the circuit at `input_file` together with the files it includes.
+///
+/// The parser of circom reads the files itself, so the files it cannot read as they are (see
+/// `stage_sources`) are replaced by normalized copies, which are removed once parsed. The copies
+/// keep the names of the files they were copied from in the file library and the reports.
+///
+/// # Parameters
+/// - `input_file`: Path to a circuit with a main component.
+/// - `prime`: Name of the curve whose prime is used by the parser (e.g. `bn128`).
+/// - `link_libraries`: Directories added to the library search path.
+pub fn parse_project(
+    input_file: &Path,
+    prime: &str,
+    link_libraries: &[PathBuf],
+) -> Result<ProgramArchive, ()> {
+    parse_project_with_originals(input_file, prime, link_libraries, FxHashMap::default())
+}
+
+/// Parses the circuit at `input_file` as `parse_project` does, where `originals` maps the copies
+/// staged by the caller to the files they were copied from.
+fn parse_project_with_originals(
+    input_file: &Path,
+    prime: &str,
+    link_libraries: &[PathBuf],
+    mut originals: FxHashMap<PathBuf, PathBuf>,
+) -> Result<ProgramArchive, ()> {
+    let staging_dir = env::temp_dir().join(format!("zkfuzz_sources_{}", std::process::id()));
+    // A file that cannot be read is left to the parser, which reports it
+    let initial_file = match stage_sources(input_file, link_libraries, &staging_dir) {
+        Ok((initial_file, staged_originals)) => {
+            originals.extend(staged_originals);
+            initial_file
+        }
+        Err(_) => input_file.to_path_buf(),
+    };
     //We get the prime number from the input
-    let prime = UsefulConstants::new(&input_info.prime()).get_p().clone();
+    let prime = UsefulConstants::new(&prime.to_string()).get_p().clone();
     let result_program_archive = parser::run_parser(
-        initial_file,
+        initial_file.to_string_lossy().to_string(),
         VERSION,
-        input_info.get_link_libraries().to_vec(),
+        link_libraries.to_vec(),
         &prime,
     );
+    let _ = fs::remove_dir_all(&staging_dir);
     match result_program_archive {
         Result::Err((file_library, report_collection)) => {
+            let file_library = rename_staged_files(&file_library, &originals);
             Report::print_reports(&report_collection, &file_library);
             Result::Err(())
         }
-        Result::Ok((program_archive, warnings)) => {
+        Result::Ok((mut program_archive, warnings)) => {
+            program_archive.file_library =
+                rename_staged_files(&program_archive.file_library, &originals);
             Report::print_reports(&warnings, &program_archive.file_library);
             Result::Ok(program_archive)
         }
     }
 }
+
+/// Returns a copy of `file_library` where the files of `originals`, i.e., the copies staged
+/// before parsing, are named after the files they were copied from, so that the reports do not
+/// refer to the copies once they are removed. The files keep their ids.
+fn rename_staged_files(
+    file_library: &FileLibrary,
+    originals: &FxHashMap<PathBuf, PathBuf>,
+) -> FileLibrary {
+    let storage = file_library.to_storage();
+    let mut renamed = FileLibrary::new();
+    let mut file_id = 0;
+    while let Some(file) = storage.get(file_id) {
+        // The parser names a file after its path, formatted with `Debug`
+        let name = match originals.get(Path::new(file.name().trim_matches('"'))) {
+            Some(original) if file.name().starts_with('"') => format!("{:?}", original),
+            Some(original) => original.display().to_string(),
+            None => file.name().clone(),
+        };
+        renamed.add_file(name, file.source().clone());
+        file_id += 1;
+    }
+    renamed
+}
+
+/// Counts the calls of `parse_source` and `parse_entries` in this process, so that each call stages
+/// its sources in a directory of its own.
+static STAGING_DIR_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+/// Parses a circuit given as a string, e.g. a circuit read from stdin or the unsaved buffer of an
+/// editor, together with the files it includes.
+///
+/// The path of each `include` directive, as written, is passed to `resolver`, which returns the
+/// contents of the included file, or `None` to let the parser resolve the directive against
+/// `link_libraries`. The directives of the files returned by `resolver` are resolved the same
+/// way. Since the parser of circom only reads files, the sources are copied to a private
+/// temporary directory (see `stage_inline_sources`), which is removed once parsed. Their
+/// locations in the reports refer to the copies.
+///
+/// # Parameters
+/// - `source`: A circuit with a main component.
+/// - `resolver`: Returns the contents of the file of an `include` directive, if it provides it.
+/// - `prime`: Name of the curve whose prime is used by the parser (e.g. `bn128`).
+/// - `link_libraries`: Directories added to the library search path.
+pub fn parse_source<F>(
+    source: &str,
+    resolver: F,
+    prime: &str,
+    link_libraries: &[PathBuf],
+) -> Result<ProgramArchive, ()>
+where
+    F: Fn(&str) -> Option<String>,
+{
+    let staging_dir = env::temp_dir().join(format!(
+        "zkfuzz_inline_{}_{}",
+        std::process::id(),
+        STAGING_DIR_COUNTER.fetch_add(1, Ordering::SeqCst)
+    ));
+    let result = stage_inline_sources(source, &resolver, &staging_dir)
+        .map_err(|e| eoutln!("{} {}", "Unable to stage the sources:".red(), e))
+        .and_then(|main_file| parse_project(&main_file, prime, link_libraries));
+    let _ = fs::remove_dir_all(&staging_dir);
+    result
+}
+
+/// Reads a source file, decoding it lossily if it is not valid UTF-8 (e.g. a comment written in
+/// latin-1), since the parser of circom only accepts UTF-8. The invalid bytes are replaced with
+/// `U+FFFD` and a warning is printed.
+pub fn read_source(path: &Path) -> io::Result<String> {
+    Ok(decode_source(path, fs::read(path)?).0)
+}
+
+/// Decodes the contents of the source file at `path`, and returns whether it had to be decoded
+/// lossily.
+fn decode_source(path: &Path, bytes: Vec<u8>) -> (String, bool) {
+    match String::from_utf8(bytes) {
+        Ok(source) => (source, false),
+        Err(e) => {
+            eoutln!(
+                "{} {}",
+                "⚠️ Decoding a source file that is not valid UTF-8 lossily:".yellow(),
+                path.display()
+            );
+            (String::from_utf8_lossy(e.as_bytes()).into_owned(), true)
+        }
+    }
+}
+
+/// Converts the path of an `include` directive to a path of the host, accepting both `/` and
+/// `\` as separators.
+pub fn normalize_include_path(included: &str) -> PathBuf {
+    if cfg!(windows) {
+        // Windows accepts both separators, and splitting would break prefixes such as `C:`
+        return PathBuf::from(included);
+    }
+    let mut path = PathBuf::new();
+    if included.starts_with('/') || included.starts_with('\\') {
+        path.push("/");
+    }
+    for component in included.split(|c| c == '/' || c == '\\') {
+        if !component.is_empty() {
+            path.push(component);
+        }
+    }
+    path
+}
+
+/// Formats `path` as the path of an `include` directive, with `/` as the separator and without
+/// the verbatim prefix (`\\?\`) that `canonicalize` adds on Windows.
+pub fn include_path_literal(path: &Path) -> String {
+    let path = path.to_string_lossy();
+    path.strip_prefix(r"\\?\")
+        .unwrap_or(&path)
+        .replace('\\', "/")
+}
+
+/// Returns the path of the `include` directive on `line`, if any.
+fn included_path(line: &str) -> Option<&str> {
+    let trimmed = line.trim();
+    if trimmed.starts_with("include") {
+        trimmed.split('"').nth(1)
+    } else {
+        None
+    }
+}
+
+/// Resolves the path of an `include` directive against the directory of the including file and
+/// then the link libraries, as the parser of circom does.
+///
+/// # Returns
+/// The canonical path of the included file, or `None` if it does not exist.
+pub fn resolve_include(
+    included: &str,
+    base_dir: &Path,
+    link_libraries: &[PathBuf],
+) -> Option<PathBuf> {
+    let included = normalize_include_path(included);
+    std::iter::once(base_dir)
+        .chain(link_libraries.iter().map(|lib| lib.as_path()))
+        .filter_map(|dir| dir.join(&included).canonicalize().ok())
+        .next()
+}
+
+/// A source file reached from the circuit under analysis.
+struct SourceFile {
+    source: String,
+    /// Whether the file has to be copied before being parsed.
+    needs_staging: bool,
+    /// The line and the canonical path of each resolved `include` directive.
+    includes: Vec<(usize, PathBuf)>,
+}
+
+/// Reads `canonical_input_file` and the files it includes, transitively.
+///
+/// # Returns
+/// The canonical paths of the files in the order they were reached, and the files keyed by them.
+fn read_sources(
+    canonical_input_file: &Path,
+    link_libraries: &[PathBuf],
+) -> io::Result<(Vec<PathBuf>, FxHashMap<PathBuf, SourceFile>)> {
+    let mut files: FxHashMap<PathBuf, SourceFile> = FxHashMap::default();
+    let mut order = Vec::new();
+    let mut stack = vec![canonical_input_file.to_path_buf()];
+    while let Some(path) = stack.pop() {
+        if files.contains_key(&path) {
+            continue;
+        }
+        let (source, mut needs_staging) = decode_source(&path, fs::read(&path)?);
+        let base_dir = path.parent().unwrap_or(Path::new("."));
+        let mut includes = Vec::new();
+        for (i, line) in source.lines().enumerate() {
+            if let Some(included) = included_path(line) {
+                if let Some(resolved) = resolve_include(included, base_dir, link_libraries) {
+                    needs_staging |= included.contains('\\');
+                    stack.push(resolved.clone());
+                    includes.push((i, resolved));
+                }
+            }
+        }
+        order.push(path.clone());
+        files.insert(
+            path,
+            SourceFile {
+                source: source,
+                needs_staging: needs_staging,
+                includes: includes,
+            },
+        );
+    }
+    Ok((order, files))
+}
+
+/// Returns the sources of `input_file` and of the files it includes, transitively, i.e., the
+/// preprocessed program, as pairs of canonical paths and contents in the order they are reached.
+pub fn read_included_sources(
+    input_file: &Path,
+    link_libraries: &[PathBuf],
+) -> io::Result<Vec<(PathBuf, String)>> {
+    let (order, mut files) = read_sources(&input_file.canonicalize()?, link_libraries)?;
+    Ok(order
+        .into_iter()
+        .map(|path| {
+            let source = files.remove(&path).unwrap().source;
+            (path, source)
+        })
+        .collect())
+}
+
+/// Prepares `input_file` and the files it includes, transitively, for the parser of circom,
+/// which only accepts UTF-8 and resolves includes with the separator of the host.
+///
+/// The files that are not valid UTF-8 or include a path written with `\\`, together with the
+/// files including them, are copied to `staging_dir`. The copies are decoded lossily (see
+/// `read_source`), and their `include` directives are rewritten to the absolute paths of the
+/// copies or of the original files. Line numbers are preserved.
+///
+/// # Returns
+/// The path to parse: `input_file` itself if no file has to be copied, and the copies mapped to
+/// the files they were copied from.
+fn stage_sources(
+    input_file: &Path,
+    link_libraries: &[PathBuf],
+    staging_dir: &Path,
+) -> io::Result<(PathBuf, FxHashMap<PathBuf, PathBuf>)> {
+    let canonical_input_file = input_file.canonicalize()?;
+    let (order, files) = read_sources(&canonical_input_file, link_libraries)?;
+
+    // A file including a copied file has to include the copy instead
+    let mut staged: FxHashSet<PathBuf> = order
+        .iter()
+        .filter(|path| files[*path].needs_staging)
+        .cloned()
+        .collect();
+    loop {
+        let includers: Vec<PathBuf> = order
+            .iter()
+            .filter(|path| !staged.contains(*path))
+            .filter(|path| {
+                files[*path]
+                    .includes
+                    .iter()
+                    .any(|(_, target)| staged.contains(target))
+            })
+            .cloned()
+            .collect();
+        if includers.is_empty() {
+            break;
+        }
+        staged.extend(includers);
+    }
+    if staged.is_empty() {
+        return Ok((input_file.to_path_buf(), FxHashMap::default()));
+    }
+
+    fs::create_dir_all(staging_dir)?;
+    // The parser refers to the copies by their canonical paths
+    let staging_dir = staging_dir.canonicalize()?;
+    let staged_paths: FxHashMap<&PathBuf, PathBuf> = order
+        .iter()
+        .filter(|path| staged.contains(*path))
+        .enumerate()
+        .map(|(i, path)| {
+            let file_name = path
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_default();
+            (path, staging_dir.join(format!("{}_{}", i, file_name)))
+        })
+        .collect();
+    for (path, staged_path) in &staged_paths {
+        let file = &files[*path];
+        let mut lines: Vec<String> = file.source.lines().map(|line| line.to_string()).collect();
+        for (i, target) in &file.includes {
+            let literal = include_path_literal(staged_paths.get(target).unwrap_or(target));
+            if let Some(rewritten) = rewrite_include(&lines[*i], &literal) {
+                lines[*i] = rewritten;
+            }
+        }
+        fs::write(staged_path, lines.join("\n") + "\n")?;
+    }
+    let originals = staged_paths
+        .iter()
+        .map(|(path, staged_path)| {
+            let original = if **path == canonical_input_file {
+                input_file.to_path_buf()
+            } else {
+                (*path).clone()
+            };
+            (staged_path.clone(), original)
+        })
+        .collect();
+    Ok((staged_paths[&canonical_input_file].clone(), originals))
+}
+
+/// Writes `source` and the files that `resolver` returns for its `include` directives,
+/// transitively, to `staging_dir`. The directives resolved by `resolver` are rewritten to the
+/// absolute paths of the copies, and the others are kept as written. Line numbers are preserved.
+///
+/// # Returns
+/// The path of the copy of `source`.
+fn stage_inline_sources(
+    source: &str,
+    resolver: &dyn Fn(&str) -> Option<String>,
+    staging_dir: &Path,
+) -> io::Result<PathBuf> {
+    fs::create_dir_all(staging_dir)?;
+    let staging_dir = staging_dir.canonicalize()?;
+    let main_file = staging_dir.join("0_main.circom");
+
+    // The copies of the files returned by `resolver`, keyed by their paths as written
+    let mut staged: FxHashMap<String, PathBuf> = FxHashMap::default();
+    let mut stack = vec![(main_file.clone(), source.to_string())];
+    while let Some((path, source)) = stack.pop() {
+        let mut lines: Vec<String> = source.lines().map(|line| line.to_string()).collect();
+        for line in lines.iter_mut() {
+            let included = match included_path(line) {
+                Some(included) => included.to_string(),
+                None => continue,
+            };
+            let staged_path = match staged.get(&included) {
+                Some(staged_path) => staged_path.clone(),
+                None => match resolver(&included) {
+                    Some(contents) => {
+                        let file_name = normalize_include_path(&included)
+                            .file_name()
+                            .map(|name| name.to_string_lossy().to_string())
+                            .unwrap_or_default();
+                        let staged_path =
+                            staging_dir.join(format!("{}_{}", staged.len() + 1, file_name));
+                        staged.insert(included, staged_path.clone());
+                        stack.push((staged_path.clone(), contents));
+                        staged_path
+                    }
+                    None => continue,
+                },
+            };
+            if let Some(rewritten) = rewrite_include(line, &include_path_literal(&staged_path)) {
+                *line = rewritten;
+            }
+        }
+        fs::write(&path, lines.join("\n") + "\n")?;
+    }
+    Ok(main_file)
+}
+
+/// Name of the template serving as the main component of the wrapper that `parse_library`
+/// compiles. It is not part of the library.
+pub const LIBRARY_ROOT_TEMPLATE: &str = "ZkFuzzLibraryRoot";
+
+/// Recursively collects all `.circom` files under `dir`, sorted by path.
+pub fn collect_circom_files(dir: &Path) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    let mut stack = vec![dir.to_path_buf()];
+    while let Some(cur) = stack.pop() {
+        let entries = match fs::read_dir(&cur) {
+            Ok(entries) => entries,
+            Err(_) => continue,
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                stack.push(path);
+            } else if path.extension().map_or(false, |ext| ext == "circom") {
+                files.push(path);
+            }
+        }
+    }
+    files.sort();
+    files
+}
+
+/// Parses every circuit of a library, such as circomlib, together with the files they include.
+///
+/// Since the parser of circom requires a main component, a wrapper including every `.circom`
+/// file under `library_dir` and instantiating an empty `LIBRARY_ROOT_TEMPLATE` is parsed instead.
+/// Files declaring their own main component (e.g. tests) are skipped.
+///
+/// # Returns
+/// The parsed program and the canonical paths of the source files of the library.
+pub fn parse_library(
+    library_dir: &Path,
+    prime: &str,
+    link_libraries: &[PathBuf],
+) -> Result<(ProgramArchive, Vec<PathBuf>), ()> {
+    let source_files: Vec<PathBuf> = collect_circom_files(library_dir)
+        .into_iter()
+        .filter(|path| read_source(path).map_or(false, |source| !source.contains("component main")))
+        .filter_map(|path| path.canonicalize().ok())
+        .collect();
+
+    // Custom templates can only be included by a circuit that enables them as well
+    let uses_custom_templates = source_files.iter().any(|path| {
+        read_source(path).map_or(false, |source| source.contains("pragma custom_templates"))
+    });
+    let mut wrapper = if uses_custom_templates {
+        String::from("pragma circom 2.0.6;\npragma custom_templates;\n")
+    } else {
+        String::from("pragma circom 2.0.0;\n")
+    };
+    for path in &source_files {
+        wrapper.push_str(&format!("include \"{}\";\n", include_path_literal(path)));
+    }
+    wrapper.push_str(&format!(
+        "template {}() {{}}\ncomponent main = {}();\n",
+        LIBRARY_ROOT_TEMPLATE, LIBRARY_ROOT_TEMPLATE
+    ));
+    let wrapper_path =
+        env::temp_dir().join(format!("zkfuzz_library_{}.circom", std::process::id()));
+    fs::write(&wrapper_path, wrapper).map_err(|e| eoutln!("{}", e))?;
+
+    let result = parse_project(&wrapper_path, prime, link_libraries);
+    let _ = fs::remove_file(&wrapper_path);
+    result.map(|program_archive| (program_archive, source_files))
+}
+
+/// Writes a copy of `input_file` without the `include` directives resolving to one of
+/// `linked_files`, so that the templates and functions of a linked library archive are not
+/// parsed again.
+///
+/// The copy is written next to `input_file` so that its remaining relative includes resolve as
+/// before, and the removed directives are replaced by empty lines to preserve line numbers.
+///
+/// # Parameters
+/// - `input_file`: Path to a circuit with a main component.
+/// - `linked_files`: The canonical paths of the source files of the linked archive.
+/// - `link_libraries`: Directories added to the library search path.
+///
+/// # Returns
+/// The path of the copy, which the caller is responsible for removing.
+pub fn strip_linked_includes(
+    input_file: &Path,
+    linked_files: &[PathBuf],
+    link_libraries: &[PathBuf],
+) -> io::Result<PathBuf> {
+    let base_dir = input_file.parent().unwrap_or(Path::new("."));
+    let source = read_source(input_file)?;
+
+    let is_linked = |included: &str| {
+        resolve_include(included, base_dir, link_libraries)
+            .map_or(false, |path| linked_files.contains(&path))
+    };
+    let stripped: Vec<&str> = source
+        .lines()
+        .map(|line| match included_path(line) {
+            Some(included) if is_linked(included) => "",
+            _ => line,
+        })
+        .collect();
+
+    let file_name = input_file
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let stripped_path = base_dir.join(format!(".{}.zkfuzz_linked.circom", file_name));
+    fs::write(&stripped_path, stripped.join("\n") + "\n")?;
+    Ok(stripped_path)
+}
+
+/// Name of the template whose components instantiate the main components of the entries that
+/// `parse_entries` parses. It is not part of any entry.
+pub const ENTRY_ROOT_TEMPLATE: &str = "ZkFuzzEntryRoot";
+
+/// A circuit given to `--entry`, with the main component it declares.
+pub struct EntryPoint {
+    pub path: PathBuf,
+    /// The call instantiating the main component, e.g. `Main(4)`.
+    pub main_call: Expression,
+    /// The public inputs of the main component.
+    pub public_inputs: Vec<String>,
+}
+
+/// Returns the byte range of the declaration of the main component of the entry at `path`,
+/// e.g. `component main {public [a]} = Main(4);`, the byte range of the call it instantiates, and
+/// its public inputs.
+///
+/// The declaration is located in the AST of the entry, so that a `component main` written in a
+/// comment or a string is not mistaken for it. The entry is first parsed without its includes,
+/// which are blanked out in a copy written to `staging_dir`, and is parsed with them only if that
+/// fails, e.g. because it instantiates an included template anonymously. The reports of the
+/// parser are not printed, since the entries are parsed again together.
+///
+/// # Parameters
+/// - `path`: The canonical path of the entry.
+/// - `source`: The contents of the entry.
+/// - `prime`: The prime used by the parser.
+/// - `link_libraries`: Directories added to the library search path.
+/// - `staging_dir`: Directory where the copy without includes is written.
+fn find_main_component(
+    path: &Path,
+    source: &str,
+    prime: &BigInt,
+    link_libraries: &[PathBuf],
+    staging_dir: &Path,
+) -> Option<((usize, usize), (usize, usize), Vec<String>)> {
+    // The includes are replaced by spaces to preserve the offsets of the other tokens
+    let without_includes: String = source
+        .split_inclusive('\n')
+        .map(|line| match included_path(line) {
+            Some(_) => line
+                .bytes()
+                .map(|b| match b {
+                    b'\r' | b'\n' => b as char,
+                    _ => ' ',
+                })
+                .collect(),
+            None => line.to_string(),
+        })
+        .collect();
+    let file_name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let standalone = staging_dir.join(format!("standalone_{}", file_name));
+    let (meta, public_inputs) = fs::write(&standalone, without_includes)
+        .ok()
+        .and_then(|_| parse_main_call(&standalone, prime, &[]))
+        .or_else(|| parse_main_call(path, prime, link_libraries))?;
+
+    let start = source.get(..meta.start)?.rfind("component")?;
+    let end = meta.end + source.get(meta.end..)?.find(';')? + 1;
+    Some(((start, end), (meta.start, meta.end), public_inputs))
+}
+
+/// Parses `input_file` without printing the reports of the parser, and returns the location of
+/// the call instantiating its main component and the public inputs of the latter.
+fn parse_main_call(
+    input_file: &Path,
+    prime: &BigInt,
+    link_libraries: &[PathBuf],
+) -> Option<(Meta, Vec<String>)> {
+    parser::run_parser(
+        input_file.to_string_lossy().to_string(),
+        VERSION,
+        link_libraries.to_vec(),
+        prime,
+    )
+    .ok()
+    .map(|(program_archive, _)| {
+        (
+            program_archive.initial_template_call.get_meta().clone(),
+            program_archive.get_public_inputs_main_component().clone(),
+        )
+    })
+}
+
+/// Rewrites the path of the `include` directive on `line` to `literal`.
+fn rewrite_include(line: &str, literal: &str) -> Option<String> {
+    let parts: Vec<&str> = line.splitn(3, '"').collect();
+    if parts.len() == 3 {
+        Some(format!("{}\"{}\"{}", parts[0], literal, parts[2]))
+    } else {
+        None
+    }
+}
+
+/// Collects the right-hand sides of the substitutions of `stmt` by the name of their variable.
+fn gather_substitutions(stmt: &Statement, substitutions: &mut FxHashMap<String, Expression>) {
+    match stmt {
+        Statement::Block { stmts, .. } => {
+            for stmt in stmts {
+                gather_substitutions(stmt, substitutions);
+            }
+        }
+        Statement::InitializationBlock {
+            initializations, ..
+        } => {
+            for stmt in initializations {
+                gather_substitutions(stmt, substitutions);
+            }
+        }
+        Statement::Substitution { var, rhe, .. } => {
+            substitutions.insert(var.clone(), rhe.clone());
+        }
+        _ => {}
+    }
+}
+
+/// Writes the copies of `entries` and the wrapper that `parse_entries` parses to `staging_dir`.
+///
+/// # Returns
+/// The path of the wrapper, the public inputs of the main component of each entry, and the
+/// copies mapped to the entries they were copied from.
+fn stage_entries(
+    entries: &[PathBuf],
+    prime: &str,
+    link_libraries: &[PathBuf],
+    staging_dir: &Path,
+) -> Result<(PathBuf, Vec<Vec<String>>, FxHashMap<PathBuf, PathBuf>), ()> {
+    let write_error = |e: io::Error| eoutln!("{} {}", "Unable to write the entries:".red(), e);
+    fs::create_dir_all(staging_dir).map_err(write_error)?;
+    // The parser refers to the copies by their canonical paths
+    let staging_dir = &staging_dir.canonicalize().map_err(write_error)?;
+    let prime_number = UsefulConstants::new(&prime.to_string()).get_p().clone();
+
+    let mut includes = String::new();
+    let mut uses_custom_templates = false;
+    let mut root_body = String::new();
+    let mut public_inputs = Vec::new();
+    let mut originals = FxHashMap::default();
+    for (i, entry) in entries.iter().enumerate() {
+        let canonical_entry = entry
+            .canonicalize()
+            .map_err(|e| eoutln!("{} {}", "Unable to read the entry:".red(), e))?;
+        let source = read_source(&canonical_entry)
+            .map_err(|e| eoutln!("{} {}", "Unable to read the entry:".red(), e))?;
+        let ((start, end), (call_start, call_end), entry_public_inputs) = find_main_component(
+            &canonical_entry,
+            &source,
+            &prime_number,
+            link_libraries,
+            staging_dir,
+        )
+        .ok_or_else(|| {
+            eoutln!(
+                "{} {}",
+                "Cannot find the main component of the entry:".red(),
+                entry.display()
+            )
+        })?;
+        uses_custom_templates |= source.contains("pragma custom_templates");
+        public_inputs.push(entry_public_inputs);
+        root_body.push_str(&format!(
+            "    component entry_{} = {};\n",
+            i,
+            &source[call_start..call_end]
+        ));
+
+        // The declaration is replaced by spaces to preserve the locations of the other lines, and
+        // the includes are resolved against the directory of the entry instead of the copy
+        let blanked: String = source[start..end]
+            .chars()
+            .map(|c| if c == '\n' { c } else { ' ' })
+            .collect();
+        let base_dir = canonical_entry.parent().unwrap_or(Path::new("."));
+        let lines: Vec<String> = format!("{}{}{}", &source[..start], blanked, &source[end..])
+            .lines()
+            .map(|line| {
+                included_path(line)
+                    .and_then(|included| resolve_include(included, base_dir, link_libraries))
+                    .and_then(|target| rewrite_include(line, &include_path_literal(&target)))
+                    .unwrap_or_else(|| line.to_string())
+            })
+            .collect();
+        let file_name = canonical_entry
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let copy = staging_dir.join(format!("{}_{}", i, file_name));
+        fs::write(&copy, lines.join("\n") + "\n").map_err(write_error)?;
+        includes.push_str(&format!("include \"{}\";\n", include_path_literal(&copy)));
+        originals.insert(copy, entry.clone());
+    }
+
+    let mut wrapper = if uses_custom_templates {
+        String::from("pragma circom 2.0.6;\npragma custom_templates;\n")
+    } else {
+        String::from("pragma circom 2.0.0;\n")
+    };
+    wrapper.push_str(&includes);
+    wrapper.push_str(&format!(
+        "template {}() {{\n{}}}\ncomponent main = {}();\n",
+        ENTRY_ROOT_TEMPLATE, root_body, ENTRY_ROOT_TEMPLATE
+    ));
+    let wrapper_path = staging_dir.join("entries.circom");
+    fs::write(&wrapper_path, wrapper).map_err(write_error)?;
+    Ok((wrapper_path, public_inputs, originals))
+}
+
+/// Parses several circuits, each declaring its own main component, as a single program, so that
+/// the files they include in common are parsed once.
+///
+/// Since a program has only one main component, every entry is copied to a private temporary
+/// directory with its main component blanked out (see `find_main_component`) and its `include`
+/// directives rewritten to the absolute paths of the included files, and a wrapper including the
+/// copies and instantiating the main component of each entry in `ENTRY_ROOT_TEMPLATE` is parsed
+/// instead. The directory is removed once parsed, and the copies keep the paths of the entries in
+/// the file library and the reports. As in a single circuit, the templates and functions of the entries must
+/// have distinct names.
+///
+/// # Parameters
+/// - `entries`: Paths to circuits with a main component.
+/// - `prime`: Name of the curve whose prime is used by the parser (e.g. `bn128`).
+/// - `link_libraries`: Directories added to the library search path.
+///
+/// # Returns
+/// The parsed program and the entries, in the order of `entries`.
+pub fn parse_entries(
+    entries: &[PathBuf],
+    prime: &str,
+    link_libraries: &[PathBuf],
+) -> Result<(ProgramArchive, Vec<EntryPoint>), ()> {
+    let staging_dir = env::temp_dir().join(format!(
+        "zkfuzz_entries_{}_{}",
+        std::process::id(),
+        STAGING_DIR_COUNTER.fetch_add(1, Ordering::SeqCst)
+    ));
+    let result = stage_entries(entries, prime, link_libraries, &staging_dir).and_then(
+        |(wrapper_path, public_inputs, originals)| {
+            parse_project_with_originals(&wrapper_path, prime, link_libraries, originals)
+                .map(|program_archive| (program_archive, public_inputs))
+        },
+    );
+    let _ = fs::remove_dir_all(&staging_dir);
+    let (program_archive, mut public_inputs) = result?;
+
+    let mut main_calls = FxHashMap::default();
+    if let Some(root) = program_archive.templates.get(ENTRY_ROOT_TEMPLATE) {
+        gather_substitutions(root.get_body(), &mut main_calls);
+    }
+    let entries = entries
+        .iter()
+        .enumerate()
+        .map(
+            |(i, path)| match main_calls.remove(&format!("entry_{}", i)) {
+                Some(main_call) => Ok(EntryPoint {
+                    path: path.clone(),
+                    main_call,
+                    public_inputs: std::mem::take(&mut public_inputs[i]),
+                }),
+                None => Result::Err(eoutln!(
+                    "{} {}",
+                    "Cannot Find Main Call of the entry:".red(),
+                    path.display()
+                )),
+            },
+        )
+        .collect::<Result<Vec<_>, ()>>()?;
+    Ok((program_archive, entries))
+}
+
+/// Replaces the arguments of the main component (`component main = T(...)`) with the values of
+/// `params`, a JSON object mapping the names of the parameters of the main template to numbers,
+/// strings of decimal (or `0x`-prefixed hexadecimal) numbers, or arrays of them. The parameters
+/// missing from `params` keep the arguments written in the circuit.
+///
+/// # Parameters
+/// - `program_archive`: The parsed program whose main component is overridden.
+/// - `param_names`: The names of the parameters of the main template, in order.
+/// - `params`: The JSON object of the overriding values.
+pub fn override_main_params(
+    program_archive: &mut ProgramArchive,
+    param_names: &[String],
+    params: &Value,
+) -> Result<(), String> {
+    let params = params
+        .as_object()
+        .ok_or_else(|| "expected an object mapping parameter names to values".to_string())?;
+    let (meta, args) = match &mut program_archive.initial_template_call {
+        Expression::Call { meta, args, .. } => (meta.clone(), args),
+        _ => return Err("cannot find the main component".to_string()),
+    };
+    for (name, value) in params {
+        let index = param_names
+            .iter()
+            .position(|param_name| param_name == name)
+            .ok_or_else(|| format!("`{}` is not a parameter of the main template", name))?;
+        args[index] = json_to_expression(&meta, value).map_err(|e| format!("{}: {}", name, e))?;
+    }
+    Ok(())
+}
+
+/// Converts a number, a numeric string, or a (nested) array of them into the expression of a
+/// template argument.
+fn json_to_expression(meta: &Meta, value: &Value) -> Result<Expression, String> {
+    let number = match value {
+        Value::Array(values) => {
+            return Ok(Expression::ArrayInLine {
+                meta: meta.clone(),
+                values: values
+                    .iter()
+                    .map(|v| json_to_expression(meta, v))
+                    .collect::<Result<Vec<_>, _>>()?,
+            })
+        }
+        Value::Number(n) => match (n.as_u64(), n.as_i64()) {
+            (Some(n), _) => BigInt::from(n),
+            (None, Some(n)) => BigInt::from(n),
+            // Integers beyond 64 bits have to be written as strings
+            _ => return Err(format!("{} is not an integer that fits in 64 bits", n)),
+        },
+        Value::String(s) => parse_numeral(s).ok_or_else(|| format!("{} is not a number", s))?,
+        _ => return Err(format!("{} is neither a number nor an array", value)),
+    };
+    if number < BigInt::from(0) {
+        // A negative literal is parsed by circom as a negated number
+        Ok(Expression::PrefixOp {
+            meta: meta.clone(),
+            prefix_op: ExpressionPrefixOpcode::Sub,
+            rhe: Box::new(Expression::Number(meta.clone(), -number)),
+        })
+    } else {
+        Ok(Expression::Number(meta.clone(), number))
+    }
+}
+
+/// Parses a decimal or `0x`-prefixed hexadecimal integer, optionally negated.
+fn parse_numeral(s: &str) -> Option<BigInt> {
+    let s = s.trim();
+    let (negative, digits) = match s.strip_prefix('-') {
+        Some(digits) => (true, digits),
+        None => (false, s),
+    };
+    if digits.starts_with(|c| c == '+' || c == '-') {
+        return None;
+    }
+    let magnitude = match digits
+        .strip_prefix("0x")
+        .or_else(|| digits.strip_prefix("0X"))
+    {
+        Some(hex) => BigInt::parse_bytes(hex.as_bytes(), 16)?,
+        None => BigInt::parse_bytes(digits.as_bytes(), 10)?,
+    };
+    Some(if negative { -magnitude } else { magnitude })
+}
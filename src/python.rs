@@ -0,0 +1,97 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use num_bigint_dig::BigInt;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use rustc_hash::FxHashMap;
+
+use crate::mutator::constraint_oracle::ConstraintOracle;
+
+/// The Python class `zkfuzz.ConstraintOracle`, which wraps `ConstraintOracle`.
+///
+/// ```python
+/// import zkfuzz
+///
+/// oracle = zkfuzz.ConstraintOracle("circuit.circom", prime="bn128")
+/// print(oracle.variables())  # e.g. ["main.in", "main.inv", "main.out"]
+/// verdicts = oracle.evaluate_batch([{"main.in": 5, "main.inv": 0, "main.out": 1}])
+/// ```
+#[pyclass(name = "ConstraintOracle", unsendable)]
+struct PyConstraintOracle {
+    oracle: ConstraintOracle,
+}
+
+#[pymethods]
+impl PyConstraintOracle {
+    #[new]
+    #[pyo3(signature = (circuit, prime = "bn128", link_libraries = Vec::new()))]
+    fn new(circuit: &str, prime: &str, link_libraries: Vec<String>) -> PyResult<Self> {
+        let link_libraries: Vec<PathBuf> = link_libraries.iter().map(PathBuf::from).collect();
+        ConstraintOracle::load(Path::new(circuit), prime, &link_libraries)
+            .map(|oracle| PyConstraintOracle { oracle: oracle })
+            .map_err(PyValueError::new_err)
+    }
+
+    /// Returns the names of the variables that every assignment has to assign.
+    fn variables(&self) -> Vec<String> {
+        self.oracle.variable_names()
+    }
+
+    /// Returns the prime as a decimal string.
+    fn prime(&self) -> String {
+        self.oracle.prime().to_string()
+    }
+
+    /// Evaluates a list of assignments, each a dict mapping the name of every variable to an
+    /// integer or a decimal string, and returns a dict per assignment with the keys
+    /// `satisfies_trace`, `satisfies_side_constraints`, `is_vulnerable`, and `result`.
+    fn evaluate_batch(
+        &mut self,
+        py: Python<'_>,
+        assignments: Vec<HashMap<String, &PyAny>>,
+    ) -> PyResult<Vec<PyObject>> {
+        let mut parsed = Vec::with_capacity(assignments.len());
+        for values in assignments {
+            let mut named_values = FxHashMap::default();
+            for (name, value) in values {
+                let text = value.str()?.to_string();
+                let value = BigInt::from_str(text.trim()).map_err(|_| {
+                    PyValueError::new_err(format!("`{}` is not an integer: {}", name, text))
+                })?;
+                named_values.insert(name, value);
+            }
+            parsed.push(
+                self.oracle
+                    .assignment_from_names(&named_values)
+                    .map_err(PyValueError::new_err)?,
+            );
+        }
+
+        let mut verdicts = Vec::with_capacity(parsed.len());
+        for verdict in self.oracle.evaluate_batch(&parsed) {
+            let dict = PyDict::new(py);
+            dict.set_item("satisfies_trace", verdict.satisfies_trace)?;
+            dict.set_item(
+                "satisfies_side_constraints",
+                verdict.satisfies_side_constraints,
+            )?;
+            dict.set_item("is_vulnerable", verdict.is_vulnerable())?;
+            dict.set_item(
+                "result",
+                verdict.result.to_json()["1_type"].as_str().unwrap_or(""),
+            )?;
+            verdicts.push(dict.to_object(py));
+        }
+        Ok(verdicts)
+    }
+}
+
+/// The Python module `zkfuzz`, built with `maturin build --features python`.
+#[pymodule]
+fn zkfuzz(_py: Python<'_>, m: &PyModule) -> PyResult<()> {
+    m.add_class::<PyConstraintOracle>()?;
+    Ok(())
+}
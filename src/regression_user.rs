@@ -0,0 +1,469 @@
+use std::any::Any;
+use std::fs;
+use std::io;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+use rustc_hash::{FxHashMap, FxHashSet};
+use serde::{Deserialize, Serialize};
+
+use program_structure::ast::Expression;
+use program_structure::constants::UsefulConstants;
+
+use crate::executor::symbolic_execution::SymbolicExecutor;
+use crate::executor::symbolic_setting::get_default_setting_for_symbolic_execution;
+use crate::executor::symbolic_value::{OwnerName, SymbolicLibrary};
+use crate::executor::template_semantics::LessThanSemantics;
+use crate::parser_user::{collect_circom_files, include_path_literal, parse_source, read_source};
+use crate::type_analysis_user::analyse_project;
+
+/// The version of the format of the regression snapshots.
+const REGRESSION_SNAPSHOT_VERSION: usize = 1;
+
+/// The argument given to each parameter of a template that has no arguments in the snapshot.
+const DEFAULT_TEMPLATE_ARGUMENT: &str = "2";
+
+/// How far the analysis of a template of the corpus went.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RegressionStatus {
+    /// The template was executed symbolically.
+    Executed,
+    /// The parser of circom rejected the circuit instantiating the template.
+    ParseError,
+    /// The type checker of circom rejected the circuit instantiating the template.
+    TypeError,
+    /// zkFuzz panicked while registering or executing the template.
+    Panic,
+}
+
+impl RegressionStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RegressionStatus::Executed => "executed",
+            RegressionStatus::ParseError => "parse_error",
+            RegressionStatus::TypeError => "type_error",
+            RegressionStatus::Panic => "panic",
+        }
+    }
+}
+
+/// The result of the symbolic execution of a template of the corpus.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RegressionEntry {
+    /// The path of the file declaring the template, relative to the corpus directory.
+    pub file: String,
+    pub template: String,
+    /// The arguments of the template, as circom expressions (e.g. `2` or `[1, 2]`).
+    pub params: Vec<String>,
+    pub status: RegressionStatus,
+    pub num_trace_constraints: usize,
+    pub num_side_constraints: usize,
+    /// The message of the panic, which is not compared since it may contain source locations.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub panic_message: Option<String>,
+}
+
+impl RegressionEntry {
+    /// Returns `true` if `other` has the same status and numbers of constraints.
+    pub fn is_same_outcome(&self, other: &RegressionEntry) -> bool {
+        self.status == other.status
+            && self.num_trace_constraints == other.num_trace_constraints
+            && self.num_side_constraints == other.num_side_constraints
+    }
+
+    /// Formats the outcome, e.g. `executed (12 trace / 10 side constraints)`.
+    pub fn outcome(&self) -> String {
+        match self.status {
+            RegressionStatus::Executed => format!(
+                "executed ({} trace / {} side constraints)",
+                self.num_trace_constraints, self.num_side_constraints
+            ),
+            status => status.as_str().to_string(),
+        }
+    }
+}
+
+/// The expected results of the templates of a corpus, e.g. circomlib, checked in so that a
+/// change of the parser or of the symbolic execution that breaks one of them is caught.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct RegressionSnapshot {
+    pub version: usize,
+    /// The curve whose prime the corpus was analysed with (e.g. `bn128`).
+    pub prime: String,
+    pub entries: Vec<RegressionEntry>,
+}
+
+/// The differences between the results of a run on a corpus and its snapshot.
+pub struct RegressionComparison {
+    /// The templates whose outcome changed, with their expected and actual entries.
+    pub changed: Vec<(RegressionEntry, RegressionEntry)>,
+    /// The templates that are not in the snapshot.
+    pub added: Vec<RegressionEntry>,
+    /// The templates of the snapshot that are no longer found in the corpus.
+    pub removed: Vec<RegressionEntry>,
+}
+
+impl RegressionComparison {
+    /// Returns `true` if a template of the snapshot changed or disappeared. New templates are
+    /// not regressions, but should be recorded by updating the snapshot.
+    pub fn is_regression(&self) -> bool {
+        !self.changed.is_empty() || !self.removed.is_empty()
+    }
+}
+
+impl RegressionSnapshot {
+    pub fn new(prime: &str, entries: Vec<RegressionEntry>) -> Self {
+        RegressionSnapshot {
+            version: REGRESSION_SNAPSHOT_VERSION,
+            prime: prime.to_string(),
+            entries: entries,
+        }
+    }
+
+    /// Reads a snapshot written by `save`.
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let snapshot: RegressionSnapshot = serde_json::from_str(&fs::read_to_string(path)?)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        if snapshot.version != REGRESSION_SNAPSHOT_VERSION {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "unsupported regression snapshot version {} (expected {})",
+                    snapshot.version, REGRESSION_SNAPSHOT_VERSION
+                ),
+            ));
+        }
+        Ok(snapshot)
+    }
+
+    /// Writes the snapshot as indented JSON, so that its changes can be reviewed.
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        fs::write(path, json + "\n")
+    }
+
+    /// Returns the arguments recorded for `template` of `file`, if any.
+    pub fn params_of(&self, file: &str, template: &str) -> Option<&Vec<String>> {
+        self.entries
+            .iter()
+            .find(|entry| entry.file == file && entry.template == template)
+            .map(|entry| &entry.params)
+    }
+
+    /// Compares the entries of a run on the corpus with the snapshot.
+    pub fn compare(&self, entries: &[RegressionEntry]) -> RegressionComparison {
+        let expected: FxHashMap<(&str, &str), &RegressionEntry> = self
+            .entries
+            .iter()
+            .map(|entry| ((entry.file.as_str(), entry.template.as_str()), entry))
+            .collect();
+        let found: FxHashSet<(&str, &str)> = entries
+            .iter()
+            .map(|entry| (entry.file.as_str(), entry.template.as_str()))
+            .collect();
+
+        let mut changed = Vec::new();
+        let mut added = Vec::new();
+        for entry in entries {
+            match expected.get(&(entry.file.as_str(), entry.template.as_str())) {
+                Some(expected) if !expected.is_same_outcome(entry) => {
+                    changed.push(((*expected).clone(), entry.clone()))
+                }
+                Some(_) => {}
+                None => added.push(entry.clone()),
+            }
+        }
+        let removed = self
+            .entries
+            .iter()
+            .filter(|entry| !found.contains(&(entry.file.as_str(), entry.template.as_str())))
+            .cloned()
+            .collect();
+        RegressionComparison {
+            changed,
+            added,
+            removed,
+        }
+    }
+}
+
+/// Removes the `//` and `/* */` comments of a circom source, keeping its line breaks.
+fn strip_comments(source: &str) -> String {
+    let mut result = String::with_capacity(source.len());
+    let mut rest = source;
+    loop {
+        let line_comment = rest.find("//");
+        let block_comment = rest.find("/*");
+        match (line_comment, block_comment) {
+            (Some(l), b) if b.map_or(true, |b| l < b) => {
+                result.push_str(&rest[..l]);
+                rest = &rest[l..];
+                rest = &rest[rest.find('\n').unwrap_or(rest.len())..];
+            }
+            (_, Some(b)) => {
+                result.push_str(&rest[..b]);
+                rest = &rest[b + 2..];
+                let end = rest.find("*/").map_or(rest.len(), |e| e + 2);
+                result.extend(rest[..end].chars().filter(|c| *c == '\n'));
+                rest = &rest[end..];
+            }
+            _ => {
+                result.push_str(rest);
+                return result;
+            }
+        }
+    }
+}
+
+fn is_identifier_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_' || c == '$'
+}
+
+/// Returns the names and the numbers of parameters of the templates declared in a circom
+/// source, including the `custom` and `parallel` ones.
+pub fn declared_templates(source: &str) -> Vec<(String, usize)> {
+    let source = strip_comments(source);
+    let mut templates = Vec::new();
+    let mut offset = 0;
+    while let Some(pos) = source[offset..].find("template") {
+        let start = offset + pos;
+        offset = start + "template".len();
+        let is_keyword = source[..start]
+            .chars()
+            .last()
+            .map_or(true, |c| !is_identifier_char(c))
+            && source[offset..].starts_with(char::is_whitespace);
+        if !is_keyword {
+            continue;
+        }
+        let mut rest = source[offset..].trim_start();
+        for modifier in ["custom", "parallel"] {
+            if rest.starts_with(modifier) && rest[modifier.len()..].starts_with(char::is_whitespace)
+            {
+                rest = rest[modifier.len()..].trim_start();
+            }
+        }
+        let name_len = rest.len() - rest.trim_start_matches(is_identifier_char).len();
+        let name = &rest[..name_len];
+        let params = match rest[name_len..].trim_start().strip_prefix('(') {
+            Some(params) => match params.find(')') {
+                Some(end) => &params[..end],
+                None => continue,
+            },
+            None => continue,
+        };
+        if !name.is_empty() {
+            templates.push((
+                name.to_string(),
+                params.split(',').filter(|p| !p.trim().is_empty()).count(),
+            ));
+        }
+    }
+    templates
+}
+
+/// Registers the templates and functions of a parsed circuit and executes its main template
+/// symbolically.
+///
+/// # Returns
+/// The numbers of trace and side constraints of the main template.
+fn execute_main_template(
+    program_archive: &program_structure::program_archive::ProgramArchive,
+    prime_name: &str,
+) -> (usize, usize) {
+    let mut symbolic_library = SymbolicLibrary::default();
+    symbolic_library.register_template_semantics(Rc::new(LessThanSemantics));
+    let whitelist = FxHashSet::default();
+    let mut template_names = program_archive
+        .templates
+        .keys()
+        .cloned()
+        .collect::<Vec<_>>();
+    template_names.sort();
+    for name in template_names {
+        let template = &program_archive.templates[&name];
+        symbolic_library.register_template(
+            name.clone(),
+            template.get_body(),
+            template.get_name_of_params(),
+            &whitelist,
+            template.is_custom_gate(),
+        );
+    }
+    let mut function_names = program_archive
+        .functions
+        .keys()
+        .cloned()
+        .collect::<Vec<_>>();
+    function_names.sort();
+    for name in function_names {
+        let function = &program_archive.functions[&name];
+        symbolic_library.register_function(
+            name.clone(),
+            function.get_body().clone(),
+            function.get_name_of_params(),
+        );
+    }
+
+    let (id, args) = match &program_archive.initial_template_call {
+        Expression::Call { id, args, .. } => (id, args),
+        _ => panic!("Cannot Find Main Call"),
+    };
+    let template_param_names = program_archive.templates[id].get_name_of_params().clone();
+    let prime = UsefulConstants::new(&prime_name.to_string())
+        .get_p()
+        .clone();
+    let setting = get_default_setting_for_symbolic_execution(prime, false);
+    let mut sexe = SymbolicExecutor::new(&mut symbolic_library, &setting);
+
+    sexe.symbolic_library
+        .name2id
+        .insert("main".to_string(), sexe.symbolic_library.name2id.len());
+    sexe.symbolic_library
+        .id2name
+        .insert(sexe.symbolic_library.name2id["main"], "main".to_string());
+    sexe.cur_state.add_owner(&OwnerName {
+        id: sexe.symbolic_library.name2id["main"],
+        counter: 0,
+        access: None,
+    });
+    sexe.cur_state
+        .set_template_id(sexe.symbolic_library.name2id[id]);
+    sexe.feed_arguments(&template_param_names, args);
+    let body = sexe.symbolic_library.template_library[&sexe.symbolic_library.name2id[id]]
+        .body
+        .clone();
+    sexe.execute(&body, 0);
+    (
+        sexe.cur_state.symbolic_trace.len(),
+        sexe.cur_state.side_constraints.len(),
+    )
+}
+
+/// Returns the message of a caught panic.
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "unknown panic".to_string()
+    }
+}
+
+/// Instantiates `template` of the file at `path` with `params` as the main component and
+/// executes it symbolically, catching the panics of the parser and of the executor.
+fn run_template(
+    path: &Path,
+    template: &str,
+    params: &[String],
+    uses_custom_templates: bool,
+    prime: &str,
+    link_libraries: &[PathBuf],
+) -> (RegressionStatus, usize, usize, Option<String>) {
+    let mut source = if uses_custom_templates {
+        String::from("pragma circom 2.0.6;\npragma custom_templates;\n")
+    } else {
+        String::from("pragma circom 2.0.0;\n")
+    };
+    source.push_str(&format!(
+        "include \"{}\";\ncomponent main = {}({});\n",
+        include_path_literal(&path.canonicalize().unwrap_or_else(|_| path.to_path_buf())),
+        template,
+        params.join(", ")
+    ));
+
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        let mut program_archive = match parse_source(&source, |_| None, prime, link_libraries) {
+            Ok(program_archive) => program_archive,
+            Err(()) => return (RegressionStatus::ParseError, 0, 0),
+        };
+        if analyse_project(&mut program_archive).is_err() {
+            return (RegressionStatus::TypeError, 0, 0);
+        }
+        let (num_trace_constraints, num_side_constraints) =
+            execute_main_template(&program_archive, prime);
+        (
+            RegressionStatus::Executed,
+            num_trace_constraints,
+            num_side_constraints,
+        )
+    }));
+    match result {
+        Ok((status, num_trace_constraints, num_side_constraints)) => {
+            (status, num_trace_constraints, num_side_constraints, None)
+        }
+        Err(payload) => (
+            RegressionStatus::Panic,
+            0,
+            0,
+            Some(panic_message(&*payload)),
+        ),
+    }
+}
+
+/// Executes symbolically every template of a corpus of circuits, such as a local checkout of
+/// circomlib, with small arguments.
+///
+/// Each template declared in a `.circom` file under `corpus_dir` is instantiated as the main
+/// component of a circuit including its file, with the arguments recorded for it in `snapshot`
+/// or `2` for each of its parameters. The files declaring their own main component (e.g.
+/// tests) are skipped. The panics are caught and recorded, and not printed.
+///
+/// # Parameters
+/// - `corpus_dir`: The directory of the corpus.
+/// - `prime`: Name of the curve whose prime is used (e.g. `bn128`).
+/// - `link_libraries`: Directories added to the library search path.
+/// - `snapshot`: The snapshot whose arguments are used, if any.
+///
+/// # Returns
+/// The entries of the templates, sorted by file and by order of declaration.
+pub fn run_regression_corpus(
+    corpus_dir: &Path,
+    prime: &str,
+    link_libraries: &[PathBuf],
+    snapshot: Option<&RegressionSnapshot>,
+) -> Vec<RegressionEntry> {
+    let mut entries = Vec::new();
+    let default_hook = panic::take_hook();
+    panic::set_hook(Box::new(|_| {}));
+    for path in collect_circom_files(corpus_dir) {
+        let source = match read_source(&path) {
+            Ok(source) => source,
+            Err(_) => continue,
+        };
+        if source.contains("component main") {
+            continue;
+        }
+        let file = include_path_literal(path.strip_prefix(corpus_dir).unwrap_or(&path));
+        let uses_custom_templates = source.contains("pragma custom_templates");
+        for (template, num_params) in declared_templates(&source) {
+            let params = snapshot
+                .and_then(|snapshot| snapshot.params_of(&file, &template))
+                .cloned()
+                .unwrap_or_else(|| vec![DEFAULT_TEMPLATE_ARGUMENT.to_string(); num_params]);
+            let (status, num_trace_constraints, num_side_constraints, message) = run_template(
+                &path,
+                &template,
+                &params,
+                uses_custom_templates,
+                prime,
+                link_libraries,
+            );
+            entries.push(RegressionEntry {
+                file: file.clone(),
+                template: template,
+                params: params,
+                status: status,
+                num_trace_constraints: num_trace_constraints,
+                num_side_constraints: num_side_constraints,
+                panic_message: message,
+            });
+        }
+    }
+    panic::set_hook(default_hook);
+    entries
+}
@@ -0,0 +1,966 @@
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+use std::str::FromStr;
+
+use colored::Colorize;
+use log::warn;
+use num_bigint_dig::BigInt;
+use rustc_hash::FxHashMap;
+use serde_json::{json, Value};
+
+use program_structure::program_archive::ProgramArchive;
+
+use crate::artifact_user::ArtifactWriter;
+use crate::batch_user::EntryResult;
+use crate::cache_user::{CachedResult, ResultCache};
+use crate::executor::constraint_store::ConstraintStore;
+use crate::executor::symbolic_execution::SymbolicExecutor;
+use crate::input_user::Input;
+use crate::mutator::baseline::{Baseline, BaselineComparison, BaselineEntry};
+use crate::mutator::circom_inspect::{run_circom_inspect, InspectCrossCheck};
+use crate::mutator::clustering::FindingSignature;
+use crate::mutator::dead_code::{
+    gather_unreachable_branches, gather_unused_definitions, UnreachableBranch,
+};
+use crate::mutator::division::{gather_division_sites, DivisionSite};
+use crate::mutator::exploitability::assessed_severity;
+use crate::mutator::finding_stream::FindingStream;
+use crate::mutator::overflow_audit::audit_integer_overflows;
+use crate::mutator::severity::{format_severity_counts, Severity};
+use crate::mutator::shape_mismatch::{gather_shape_mismatches, ShapeMismatchSite};
+use crate::mutator::signal_assignments::{gather_signal_assignment_issues, SignalAssignmentIssue};
+use crate::mutator::ternary::{gather_symbolic_ternaries, SymbolicTernarySite};
+use crate::mutator::undetermined_dimension::{
+    gather_undetermined_dimensions, UndeterminedDimensionSite,
+};
+use crate::mutator::utils::CounterExample;
+use crate::mutator::witness_export::{AssignmentFormat, ValueFormat};
+use crate::mutator::witness_order::{gather_witness_order_issues, WitnessOrderIssue};
+use crate::search_user::{ExaminedCounterExamples, SearchOutcome};
+use crate::stats::cost_estimate::{print_cost_estimate_pretty, CostEstimate};
+use crate::stats::html_report::HtmlReport;
+use crate::stats::symbolic_stats::{
+    owner_fmt, print_component_statistics_pretty, print_constraint_summary_statistics_csv,
+    print_constraint_summary_statistics_pretty, CompressionAnomaly, ConstraintStatistics,
+};
+use crate::AnalysedCircuit;
+
+/// The findings of the passes over the executed circuit that run before its constraints are
+/// searched.
+pub struct StaticFindings {
+    pub division_sites: Vec<DivisionSite>,
+    pub ternary_sites: Vec<SymbolicTernarySite>,
+    pub shape_mismatches: Vec<ShapeMismatchSite>,
+    pub undetermined_dimensions: Vec<UndeterminedDimensionSite>,
+    pub unreachable_branches: Vec<UnreachableBranch>,
+    pub unused_definitions: Vec<String>,
+    pub signal_assignment_issues: Vec<SignalAssignmentIssue>,
+    pub witness_order_issues: Vec<WitnessOrderIssue>,
+    /// The custom gates, which are assumed to enforce the witness computation of their outputs.
+    pub custom_gate_assumptions: Vec<String>,
+}
+
+impl StaticFindings {
+    /// Gathers the findings of the circuit executed by `sym_executor`, whose main template is
+    /// `main_template_name`, and prints them.
+    pub fn gather(
+        sym_executor: &SymbolicExecutor,
+        program_archive: &ProgramArchive,
+        main_template_name: &str,
+    ) -> Self {
+        let id = main_template_name;
+        let division_sites = gather_division_sites(sym_executor, &program_archive.file_library);
+        if !division_sites.is_empty() {
+            eoutln!(
+                "{} {}",
+                "➗ Potential Division-by-Zero:".yellow(),
+                division_sites.len()
+            );
+            for site in &division_sites {
+                eoutln!("  ├─ `{}` at {}", site.denominator, site.location);
+            }
+        }
+        let ternary_sites = gather_symbolic_ternaries(sym_executor, &program_archive.file_library);
+        if !ternary_sites.is_empty() {
+            eoutln!(
+                "{} {}",
+                "❓ Ternaries with Symbolic Conditions in `<==`:".yellow(),
+                ternary_sites.len()
+            );
+            for site in &ternary_sites {
+                eoutln!("  ├─ `{}` at {}", site.condition, site.location);
+                eoutln!("  │   └─ {}", site.suggestion);
+            }
+        }
+        let shape_mismatches = gather_shape_mismatches(sym_executor, &program_archive.file_library);
+        if !shape_mismatches.is_empty() {
+            eoutln!(
+                "{} {}",
+                "🔲 Shape Mismatches in Multi-Substitutions:".yellow(),
+                shape_mismatches.len()
+            );
+            for site in &shape_mismatches {
+                eoutln!("  ├─ {} at {}", site.message, site.location);
+            }
+        }
+        let undetermined_dimensions =
+            gather_undetermined_dimensions(sym_executor, &program_archive.file_library);
+        if !undetermined_dimensions.is_empty() {
+            eoutln!(
+                "{} {}",
+                "📎 Undetermined Array Sizes:".yellow(),
+                undetermined_dimensions.len()
+            );
+            for site in &undetermined_dimensions {
+                eoutln!("  ├─ {} at {}", site.message, site.location);
+            }
+        }
+
+        let unreachable_branches =
+            gather_unreachable_branches(sym_executor, &program_archive.file_library);
+        if !unreachable_branches.is_empty() {
+            eoutln!(
+                "{} {}",
+                "🚧 Unreachable Branches:".yellow(),
+                unreachable_branches.len()
+            );
+            for branch in &unreachable_branches {
+                eoutln!("  ├─ {} branch at {}", branch.branch, branch.location);
+            }
+        }
+        let unused_definitions = gather_unused_definitions(sym_executor, id);
+        if !unused_definitions.is_empty() {
+            eoutln!(
+                "{} {}",
+                "🪦 Unused Templates/Functions:".yellow(),
+                unused_definitions.len()
+            );
+            for definition in &unused_definitions {
+                eoutln!("  ├─ {} is never used from main", definition);
+            }
+        }
+        let signal_assignment_issues = gather_signal_assignment_issues(
+            sym_executor,
+            &sym_executor.cur_state.symbolic_trace,
+            id,
+            &program_archive.file_library,
+        );
+        if !signal_assignment_issues.is_empty() {
+            eoutln!(
+                "{} {}",
+                "✍️ Signal Assignment Issues:".yellow(),
+                signal_assignment_issues.len()
+            );
+            for issue in &signal_assignment_issues {
+                eoutln!("  ├─ {}", issue.message());
+            }
+        }
+        let witness_order_issues = gather_witness_order_issues(
+            sym_executor,
+            &sym_executor.cur_state.symbolic_trace,
+            &program_archive.file_library,
+        );
+        if !witness_order_issues.is_empty() {
+            eoutln!(
+                "{} {}",
+                "🔃 Witness Generation Order Issues:".yellow(),
+                witness_order_issues.len()
+            );
+            for issue in &witness_order_issues {
+                eoutln!("  ├─ {}", issue.message());
+            }
+        }
+
+        // The constraints of custom gates are defined by the proving backend, so the gates
+        // are assumed to enforce the witness computation of their outputs.
+        let mut custom_gate_assumptions: Vec<String> = sym_executor
+            .component_templates
+            .iter()
+            .filter(|(_, template_id)| {
+                sym_executor.symbolic_library.template_library[template_id].is_custom_gate
+            })
+            .map(|(owner, template_id)| {
+                format!(
+                    "{} ({})",
+                    owner_fmt(owner, &sym_executor.symbolic_library.id2name),
+                    sym_executor.symbolic_library.id2name[template_id]
+                )
+            })
+            .collect();
+        custom_gate_assumptions.sort();
+        if !custom_gate_assumptions.is_empty() {
+            eoutln!(
+                "{} {}",
+                "🔌 Unverified Assumptions (Custom Gates):".yellow(),
+                custom_gate_assumptions.len()
+            );
+            for assumption in &custom_gate_assumptions {
+                eoutln!(
+                    "  ├─ {} is assumed to constrain its outputs to their `<--` assignments",
+                    assumption
+                );
+            }
+        }
+
+        StaticFindings {
+            division_sites,
+            ternary_sites,
+            shape_mismatches,
+            undetermined_dimensions,
+            unreachable_branches,
+            unused_definitions,
+            signal_assignment_issues,
+            witness_order_issues,
+            custom_gate_assumptions,
+        }
+    }
+
+    /// Records the findings in `findings`, with the unbounded recursion of the execution, if
+    /// any.
+    pub fn stream(&self, findings: &mut FindingStream, recursion_error: Option<String>) {
+        findings.extend(self.division_sites.iter().map(|site| {
+            (
+                Severity::Medium,
+                "Potential Division-by-Zero",
+                format!("`{}` at {}", site.denominator, site.location),
+            )
+        }));
+        findings.extend(self.ternary_sites.iter().map(|site| {
+            (
+                Severity::Medium,
+                "Symbolic Ternary",
+                format!(
+                    "`{}` at {}: {}",
+                    site.condition, site.location, site.suggestion
+                ),
+            )
+        }));
+        findings.extend(self.shape_mismatches.iter().map(|site| {
+            (
+                Severity::Medium,
+                "Shape Mismatch",
+                format!("{} at {}", site.message, site.location),
+            )
+        }));
+        findings.extend(self.undetermined_dimensions.iter().map(|site| {
+            (
+                Severity::Medium,
+                "Undetermined Array Size",
+                format!("{} at {}", site.message, site.location),
+            )
+        }));
+        findings.extend(
+            self.custom_gate_assumptions
+                .iter()
+                .map(|assumption| (Severity::Info, "Unverified Assumption", assumption.clone())),
+        );
+        findings.extend(
+            recursion_error
+                .into_iter()
+                .map(|message| (Severity::Low, "Unbounded Recursion", message)),
+        );
+        findings.extend(
+            self.signal_assignment_issues
+                .iter()
+                .map(|issue| (Severity::Medium, "Signal Assignment Issue", issue.message())),
+        );
+        findings.extend(
+            self.witness_order_issues
+                .iter()
+                .map(|issue| (Severity::Medium, "Witness Order Issue", issue.message())),
+        );
+        findings.extend(self.unreachable_branches.iter().map(|branch| {
+            (
+                Severity::Low,
+                "Unreachable Branch",
+                format!("{} branch at {}", branch.branch, branch.location),
+            )
+        }));
+        findings.extend(
+            self.unused_definitions
+                .iter()
+                .map(|definition| (Severity::Info, "Unused Definition", definition.clone())),
+        );
+    }
+
+    /// Returns the details of the findings saved with a counterexample (`8_auxiliary_result`).
+    pub fn auxiliary_result(&self, recursion_error: Option<String>) -> Value {
+        json!({
+            "unverified_assumptions": self.custom_gate_assumptions,
+            "recursion_error": recursion_error,
+            "potential_division_by_zero": self
+                .division_sites
+                .iter()
+                .map(|site| json!({"location": site.location, "denominator": site.denominator}))
+                .collect::<Vec<_>>(),
+            "symbolic_ternaries": self
+                .ternary_sites
+                .iter()
+                .map(|site| json!({
+                    "location": site.location,
+                    "condition": site.condition,
+                    "suggestion": site.suggestion,
+                }))
+                .collect::<Vec<_>>(),
+            "shape_mismatches": self
+                .shape_mismatches
+                .iter()
+                .map(|site| json!({"location": site.location, "message": site.message}))
+                .collect::<Vec<_>>(),
+            "undetermined_dimensions": self
+                .undetermined_dimensions
+                .iter()
+                .map(|site| json!({"location": site.location, "message": site.message}))
+                .collect::<Vec<_>>(),
+        })
+    }
+}
+
+/// Prints the integer operations of the constraints of `sym_executor` that may overflow the
+/// prime of `--debug_prime` (`--overflow_audit`) and records them in `findings`.
+pub fn report_integer_overflows(
+    user_input: &Input,
+    sym_executor: &SymbolicExecutor,
+    findings: &mut FindingStream,
+) {
+    let mut constraints = sym_executor.cur_state.symbolic_trace.clone();
+    constraints.extend(sym_executor.cur_state.side_constraints.iter().cloned());
+    let overflow_sites = audit_integer_overflows(
+        &constraints,
+        &BigInt::from_str(&user_input.debug_prime()).unwrap(),
+        &sym_executor.symbolic_library.id2name,
+    );
+    eoutln!(
+        "{} {}",
+        "🧮 Potential Integer Overflows:".yellow(),
+        overflow_sites.len()
+    );
+    for site in &overflow_sites {
+        eoutln!(
+            "  ├─ `{}` in `{}` may reach {} bits ({})",
+            site.operand,
+            site.expression,
+            site.bound_bits,
+            site.reason
+        );
+    }
+    findings.extend(overflow_sites.iter().map(|site| {
+        (
+            Severity::Medium,
+            "Potential Integer Overflow",
+            format!(
+                "`{}` in `{}` may reach {} bits ({})",
+                site.operand, site.expression, site.bound_bits, site.reason
+            ),
+        )
+    }));
+}
+
+/// What the analysis of a circuit found, which `report_circuit` presents.
+pub struct CircuitAnalysis {
+    pub static_findings: StaticFindings,
+    pub trace_stats: ConstraintStatistics,
+    pub side_stats: ConstraintStatistics,
+    pub cost_estimate: CostEstimate,
+    /// Components whose side constraints are drastically fewer than their trace constraints.
+    pub compression_anomalies: Vec<CompressionAnomaly>,
+    /// `Skipped` without a search, `NoCounterExample`, or the kind of the counterexample.
+    pub verdict: String,
+    /// The search, unless `--search_mode off` is given.
+    pub search: Option<SearchOutcome>,
+    pub counter_examples: ExaminedCounterExamples,
+}
+
+/// Reports the analysis of `circuit`: prints the report, compares the findings with the
+/// baseline of `--baseline`, and writes the summary, the HTML report, the statistics, the cached
+/// result and the artifacts manifest requested by `user_input`.
+///
+/// # Parameters
+/// - `findings`: The stream of the findings of the detectors, which is finished here.
+/// - `result_cache`: The cache where the result is stored, if the run is cacheable.
+///
+/// # Returns
+/// The result of the circuit, whose most severe finding decides the exit code.
+pub fn report_circuit(
+    user_input: &Input,
+    sym_executor: &SymbolicExecutor,
+    circuit: &AnalysedCircuit,
+    trace_store: &dyn ConstraintStore,
+    analysis: CircuitAnalysis,
+    findings: FindingStream,
+    artifacts: &mut ArtifactWriter,
+    result_cache: Option<&ResultCache>,
+) -> Result<EntryResult, ()> {
+    let (id, start_time) = (circuit.template_name, circuit.start_time);
+    let min_severity = Severity::from_str(&user_input.min_severity()).unwrap();
+    let CircuitAnalysis {
+        static_findings,
+        trace_stats: ts,
+        side_stats: ss,
+        cost_estimate,
+        compression_anomalies,
+        verdict,
+        search,
+        counter_examples,
+    } = analysis;
+    let StaticFindings {
+        unreachable_branches,
+        unused_definitions,
+        custom_gate_assumptions,
+        signal_assignment_issues,
+        witness_order_issues,
+        ..
+    } = static_findings;
+    let ExaminedCounterExamples {
+        counter_examples: reported_counter_examples,
+        exploitabilities,
+        repair_suggestions,
+        circom_validation,
+        groth16_poc,
+        counterexample_path,
+    } = counter_examples;
+    let is_safe = reported_counter_examples.is_empty();
+    let random_seed = search.as_ref().map(|outcome| outcome.seed);
+    let heuristics_stage = search.as_ref().and_then(|outcome| outcome.heuristics_stage);
+    let violation_density = search.and_then(|outcome| outcome.violation_density);
+
+    let mut report_lines = vec![
+        "╔═══════════════════════════════════════════════════════════════╗"
+            .green()
+            .to_string(),
+        "║                        zkFuzz Report                        ║"
+            .green()
+            .to_string(),
+        "╚═══════════════════════════════════════════════════════════════╝"
+            .green()
+            .to_string(),
+        "📊 Execution Summary:".cyan().bold().to_string(),
+        format!(" ├─ Prime Number      : {}", user_input.debug_prime()),
+        format!(
+            " ├─ Compression Rate  : {:.2}% ({}/{})",
+            (ss.total_constraints as f64 / ts.total_constraints as f64) * 100 as f64,
+            ss.total_constraints,
+            ts.total_constraints
+        ),
+    ];
+    if !compression_anomalies.is_empty() {
+        report_lines.push(format!(
+            " ├─ Low Compression   : {} {}",
+            compression_anomalies.len(),
+            "component(s) with far fewer side constraints than trace constraints".yellow()
+        ));
+        for anomaly in &compression_anomalies {
+            report_lines.push(format!(
+                " │   • {} : {:.2}% ({}/{})",
+                anomaly.owner,
+                anomaly.compression_rate() * 100 as f64,
+                anomaly.side_constraints,
+                anomaly.trace_constraints
+            ));
+        }
+    }
+    report_lines.push(format!(
+        " ├─ Verification      : {}",
+        if is_safe {
+            "🆗 No Counter Example Found".green().bold()
+        } else {
+            "💥 NOT SAFE 💥".red().bold()
+        }
+    ));
+    if let Some(stage) = heuristics_stage {
+        report_lines.push(format!(" ├─ Found by Stage    : {}", stage));
+    }
+    if let Some(validation) = &circom_validation {
+        report_lines.push(format!(" ├─ Circom Validation : {}", validation.label()));
+    }
+    if let Some(poc) = &groth16_poc {
+        report_lines.push(format!(" ├─ Groth16 PoC       : {}", poc.label()));
+    }
+    if let Some(Some(exploitability)) = exploitabilities.first() {
+        report_lines.push(format!(
+            " ├─ Exploitability    : {}",
+            exploitability.controllability.description()
+        ));
+    }
+    if let Some(density) = &violation_density {
+        let (lower, upper) = density.confidence_interval();
+        report_lines.push(format!(
+            " ├─ Violation Density : {:.4}% (95% CI [{:.4}%, {:.4}%])",
+            density.density() * 100.0,
+            lower * 100.0,
+            upper * 100.0
+        ));
+    }
+    if !unreachable_branches.is_empty() || !unused_definitions.is_empty() {
+        report_lines.push(format!(
+            " ├─ Dead Code         : {} {}",
+            unreachable_branches.len() + unused_definitions.len(),
+            "unreachable branch(es) and unused template(s)/function(s)".yellow()
+        ));
+    }
+    if !custom_gate_assumptions.is_empty() {
+        report_lines.push(format!(
+            " ├─ Assumptions       : {} {}",
+            custom_gate_assumptions.len(),
+            "custom gate(s) assumed to be correct".yellow()
+        ));
+    }
+    // The counterexamples are findings of their own, and every finding decides the exit
+    // code, while only those of at least `--min_severity` are reported
+    let mut findings = findings.finish();
+    let mut severities: Vec<Severity> = reported_counter_examples
+        .iter()
+        .zip(exploitabilities.iter())
+        .map(|(ce, exploitability)| assessed_severity(ce, exploitability.as_ref()))
+        .chain(findings.iter().map(|(severity, _, _)| *severity))
+        .collect();
+    // The findings in the baseline of `--baseline` are known, so they are neither
+    // reported nor decide the exit code
+    let baseline_entries: Vec<BaselineEntry> = reported_counter_examples
+        .iter()
+        .map(|ce| {
+            BaselineEntry::of_counter_example(
+                id,
+                &FindingSignature::of(ce, &sym_executor.symbolic_library.id2name),
+            )
+        })
+        .chain(
+            findings
+                .iter()
+                .map(|(_, category, message)| BaselineEntry::of_finding(id, category, message)),
+        )
+        .collect();
+    let baseline = apply_baseline(user_input, id, &baseline_entries)?;
+    if let Some(comparison) = &baseline {
+        let mut is_suppressed = comparison.is_suppressed.iter();
+        severities.retain(|_| !is_suppressed.next().copied().unwrap_or(false));
+        let mut is_suppressed = comparison.is_suppressed[reported_counter_examples.len()..].iter();
+        findings.retain(|_| !is_suppressed.next().copied().unwrap_or(false));
+        report_lines.push(format!(
+            " ├─ Baseline          : {} known finding(s) suppressed, {} new, {} no longer found",
+            comparison.is_suppressed.len() - comparison.num_new(),
+            comparison.num_new(),
+            comparison.unmatched.len()
+        ));
+    }
+    let max_severity = severities.iter().max().copied();
+    let circom_inspect = if user_input.inspect_with_circom() != "none" {
+        cross_check_with_circom_inspect(
+            user_input,
+            &reported_counter_examples,
+            &findings,
+            &sym_executor.symbolic_library.id2name,
+        )
+    } else {
+        None
+    };
+    if let Some(cross_check) = &circom_inspect {
+        report_lines.push(format!(
+            " ├─ Circom Inspect    : {} agreement(s), {} circom only, {} zkFuzz only",
+            cross_check.agreements.len(),
+            cross_check.circom_only.len(),
+            cross_check.zkfuzz_only.len()
+        ));
+    }
+    findings.retain(|(severity, _, _)| *severity >= min_severity);
+    let reported_severities: Vec<Severity> = severities
+        .into_iter()
+        .filter(|severity| *severity >= min_severity)
+        .collect();
+    if !reported_severities.is_empty() {
+        report_lines.push(format!(
+            " ├─ Findings          : {} ({})",
+            reported_severities.len(),
+            format_severity_counts(reported_severities)
+        ));
+    }
+    for line in &report_lines {
+        eoutln!("{}", line);
+    }
+    eoutln!(" └─ Execution Time    : {:?}", start_time.elapsed());
+
+    let summary = json!({
+        "target_path": user_input.input_file(),
+        "verdict": verdict,
+        "execution_time_ms": start_time.elapsed().as_millis() as u64,
+        "num_trace_constraints": ts.total_constraints,
+        "num_side_constraints": ss.total_constraints,
+        "cost_estimate": cost_estimate.to_json(),
+        "compression_anomalies": compression_anomalies
+            .iter()
+            .map(|a| json!({
+                "component": a.owner,
+                "num_trace_constraints": a.trace_constraints,
+                "num_side_constraints": a.side_constraints,
+            }))
+            .collect::<Vec<_>>(),
+        "counterexample_path": counterexample_path,
+        "circom_validation": circom_validation.as_ref().map(|v| v.to_json()),
+        "groth16_poc": groth16_poc.as_ref().map(|poc| poc.to_json()),
+        "violation_density": violation_density.as_ref().map(|d| d.to_json()),
+        "circom_inspect": circom_inspect.as_ref().map(|c| c.to_json()),
+        "heuristics_stage": heuristics_stage,
+        "exploitability": exploitabilities
+            .first()
+            .and_then(|exploitability| exploitability.as_ref())
+            .map(|exploitability| {
+                exploitability.to_json(&sym_executor.symbolic_library.id2name)
+            }),
+        "random_seed": random_seed,
+        "unverified_assumptions": custom_gate_assumptions,
+        "unreachable_branches": unreachable_branches
+            .iter()
+            .map(|b| json!({"location": b.location, "branch": b.branch}))
+            .collect::<Vec<_>>(),
+        "unused_definitions": unused_definitions,
+        "signal_assignment_issues": signal_assignment_issues
+            .iter()
+            .map(|issue| issue.message())
+            .collect::<Vec<_>>(),
+        "witness_order_issues": witness_order_issues
+            .iter()
+            .map(|issue| issue.message())
+            .collect::<Vec<_>>(),
+        "recursion_error": sym_executor
+            .recursion_error
+            .as_ref()
+            .map(|error| error.message()),
+        "repair_suggestions": repair_suggestions,
+        "findings": findings
+            .iter()
+            .map(|(severity, category, message)| json!({
+                "severity": severity.to_string(),
+                "category": category,
+                "message": message,
+            }))
+            .collect::<Vec<_>>(),
+        "max_severity": max_severity.map(|severity| severity.to_string()),
+        "baseline": baseline.as_ref().map(|comparison| comparison.to_json()),
+        "finding_signatures": reported_counter_examples
+            .iter()
+            .map(|ce| FindingSignature::of(ce, &sym_executor.symbolic_library.id2name))
+            .collect::<Vec<_>>(),
+    });
+    if user_input.path_to_summary() != "none" {
+        let mut file = File::create(user_input.path_to_summary()).expect("Unable to create file");
+        file.write_all(summary.to_string().as_bytes())
+            .expect("Unable to write data");
+        artifacts.record("summary", &user_input.path_to_summary());
+    }
+
+    let report_html = if user_input.report_html() != "none" {
+        eoutln!(
+            "{} {}",
+            "💾 Saving the HTML report to:",
+            user_input.report_html().cyan()
+        );
+        let symbolic_trace = trace_store.to_vec();
+        let report = HtmlReport {
+            target_path: user_input.input_file(),
+            main_template: id,
+            prime: &user_input.debug_prime(),
+            verdict: &verdict,
+            execution_time: start_time.elapsed(),
+            trace_stats: &ts,
+            side_stats: &ss,
+            cost_estimate: &cost_estimate,
+            compression_anomalies: &compression_anomalies,
+            findings: findings.clone(),
+            counter_examples: &reported_counter_examples,
+            exploitabilities: &exploitabilities,
+            symbolic_trace: &symbolic_trace,
+            id2name: &sym_executor.symbolic_library.id2name,
+        };
+        let report_html = report.render();
+        let mut file = File::create(user_input.report_html()).expect("Unable to create file");
+        file.write_all(report_html.as_bytes())
+            .expect("Unable to write data");
+        artifacts.record("html_report", &user_input.report_html());
+        Some(report_html)
+    } else {
+        None
+    };
+
+    if let Some(cache) = &result_cache {
+        let cached = CachedResult {
+            verdict: verdict.clone(),
+            report: report_lines,
+            execution_time_ms: start_time.elapsed().as_millis() as u64,
+            symbolic_trace: trace_store
+                .iter()
+                .map(|c| c.lookup_fmt(&sym_executor.symbolic_library.id2name))
+                .collect(),
+            summary: summary,
+            report_html: report_html,
+        };
+        if let Err(e) = cache.store(&cached) {
+            warn!("Unable to store the result in the cache: {}", e);
+        }
+    }
+
+    print_constraint_stats(
+        user_input,
+        &ts,
+        &ss,
+        &cost_estimate,
+        &sym_executor.symbolic_library.id2name,
+    );
+
+    if user_input.component_stats_csv() != "none" {
+        artifacts.save_component_stats(
+            &user_input.component_stats_csv(),
+            &ts,
+            &ss,
+            &sym_executor.symbolic_library.id2name,
+            &user_input.stats_sort_by(),
+        );
+    }
+    save_artifacts_manifest(user_input, artifacts, &verdict);
+    eoutln!(
+        "{}",
+        "════════════════════════════════════════════════════════════════".green()
+    );
+
+    let assignment_format = AssignmentFormat::new(
+        ValueFormat::from_str(&user_input.ce_format()).unwrap(),
+        BigInt::from_str(&user_input.debug_prime()).unwrap(),
+        &sym_executor.symbolic_library,
+        id,
+    );
+    let id2name = &sym_executor.symbolic_library.id2name;
+    Ok(EntryResult {
+        entry_path: user_input.input_file().to_string(),
+        main_template_name: id.clone(),
+        status: "ok".to_string(),
+        verdict,
+        execution_time_ms: start_time.elapsed().as_millis(),
+        num_trace_constraints: ts.total_constraints,
+        num_side_constraints: ss.total_constraints,
+        findings,
+        counter_example: reported_counter_examples
+            .first()
+            .map(|ce| ce.to_json_with_meta(id2name, &FxHashMap::default(), &assignment_format)),
+        finding_signatures: reported_counter_examples
+            .iter()
+            .map(|ce| FindingSignature::of(ce, id2name))
+            .collect(),
+        max_severity,
+    })
+}
+
+/// Prints the statistics of the trace and side constraints with `--printout_stats` or
+/// `--printout_stats_csv`.
+fn print_constraint_stats(
+    user_input: &Input,
+    trace_stats: &ConstraintStatistics,
+    side_stats: &ConstraintStatistics,
+    cost_estimate: &CostEstimate,
+    id2name: &FxHashMap<usize, String>,
+) {
+    if user_input.flag_printout_stats {
+        outln!(
+            "\n{}",
+            "🪶 Stats of Symbolic Trace  ══════════════════════"
+                .yellow()
+                .bold()
+        );
+        print_constraint_summary_statistics_pretty(trace_stats);
+        print_component_statistics_pretty(trace_stats, id2name, &user_input.stats_sort_by());
+        outln!(
+            "\n{}",
+            "⛓️ Stats of Side Constraint ══════════════════════"
+                .yellow()
+                .bold()
+        );
+        print_constraint_summary_statistics_pretty(side_stats);
+        print_component_statistics_pretty(side_stats, id2name, &user_input.stats_sort_by());
+        print_cost_estimate_pretty(cost_estimate);
+    } else if user_input.flag_printout_stats_csv {
+        let headers = vec![
+            "Total_Constraints",
+            "Constant_Counts",
+            "Conditional_Counts",
+            "Array_Counts",
+            "Avg_Depth",
+            "Max_Depth",
+            "Count_Assign",
+            "Count_AssignEq",
+            "Count_AssignCall",
+            "Count_QuadZeroDiv",
+            "Count_Mul",
+            "Count_Div",
+            "Count_Add",
+            "Count_Sub",
+            "Count_Pow",
+            "Count_IntDiv",
+            "Count_Mod",
+            "Count_ShiftL",
+            "Count_ShiftR",
+            "Count_LesserEq",
+            "Count_GreaterEq",
+            "Count_Lesser",
+            "Count_Greater",
+            "Count_Eq",
+            "Count_NotEq",
+            "Count_BoolOr",
+            "Count_BoolAnd",
+            "Count_BitOr",
+            "Count_BitAnd",
+            "Count_BitXor",
+            "Number_of_Variable",
+            "Variable_Avg_Count",
+            "Variable_Max_Count",
+            "Function_Avg_Count",
+            "Function_Max_Count",
+        ];
+        outln!("{}", headers.join(","));
+        print_constraint_summary_statistics_csv(trace_stats);
+        print_constraint_summary_statistics_csv(side_stats);
+    }
+}
+
+/// Writes the manifest of the files written by the run to `--out_dir`, if it is given.
+fn save_artifacts_manifest(user_input: &Input, artifacts: &ArtifactWriter, verdict: &str) {
+    match artifacts.write_manifest(user_input.input_file(), verdict) {
+        Ok(Some(path)) => eoutln!("{} {}", "💾 Saving the artifacts manifest to:", path.cyan()),
+        Ok(None) => {}
+        Err(e) => warn!("Unable to save the artifacts manifest: {}", e),
+    }
+}
+
+/// Runs `circom --inspect` on the circuit and prints which of its warnings agree with the
+/// counterexamples and findings of zkFuzz, and which are reported by only one of the tools.
+///
+/// # Returns
+/// The cross-check, or `None` if circom could not be run.
+fn cross_check_with_circom_inspect(
+    user_input: &Input,
+    counter_examples: &[CounterExample],
+    findings: &[(Severity, &str, String)],
+    id2name: &FxHashMap<usize, String>,
+) -> Option<InspectCrossCheck> {
+    let warnings = match run_circom_inspect(
+        &user_input.inspect_with_circom(),
+        user_input.input_file(),
+        user_input.get_link_libraries(),
+        &user_input.prime(),
+    ) {
+        Ok(warnings) => warnings,
+        Err(e) => {
+            eoutln!("{} {}", "⚠️ circom --inspect unavailable:".yellow(), e);
+            return None;
+        }
+    };
+    let descriptions: Vec<String> = counter_examples
+        .iter()
+        .map(|ce| {
+            let signature = FindingSignature::of(ce, id2name);
+            format!("{}: {}", signature.kind, signature.signal)
+        })
+        .chain(
+            findings
+                .iter()
+                .map(|(_, category, message)| format!("{}: {}", category, message)),
+        )
+        .collect();
+    let cross_check = InspectCrossCheck::new(warnings, &descriptions);
+
+    eoutln!(
+        "{} {} agreement(s), {} circom only, {} zkFuzz only",
+        "🔍 Circom Inspect:".yellow(),
+        cross_check.agreements.len(),
+        cross_check.circom_only.len(),
+        cross_check.zkfuzz_only.len()
+    );
+    for (warning, finding) in &cross_check.agreements {
+        eoutln!(
+            "  ├─ {} [{}] {} ↔ {}",
+            "both      ".green(),
+            warning.code,
+            warning.message,
+            finding.lines().next().unwrap_or("")
+        );
+    }
+    for warning in &cross_check.circom_only {
+        eoutln!(
+            "  ├─ {} [{}] {}",
+            "circom only".cyan(),
+            warning.code,
+            warning.message
+        );
+    }
+    for finding in &cross_check.zkfuzz_only {
+        eoutln!(
+            "  ├─ {} {}",
+            "zkFuzz only".cyan(),
+            finding.lines().next().unwrap_or("")
+        );
+    }
+    Some(cross_check)
+}
+
+/// Updates the baseline of `--baseline` with the findings of the run if the subcommand is
+/// `baseline update`, and otherwise compares them with the baseline, printing the suppressed
+/// ones.
+///
+/// # Returns
+/// The comparison, or `None` if no baseline is given. After an update, every finding is in the
+/// baseline and is thus suppressed.
+fn apply_baseline(
+    user_input: &Input,
+    template: &str,
+    findings: &[BaselineEntry],
+) -> Result<Option<BaselineComparison>, ()> {
+    let path = user_input.baseline();
+    if user_input.subcommand() == "baseline update" {
+        if path == "none" {
+            eoutln!("{}", "`zkfuzz baseline update` requires --baseline".red());
+            return Err(());
+        }
+        let baseline = if Path::new(&path).exists() {
+            Baseline::load(Path::new(&path))
+                .map_err(|e| eoutln!("{} {}", "Unable to read the baseline:".red(), e))?
+        } else {
+            Baseline::default()
+        };
+        let updated = baseline.update(template, findings);
+        updated
+            .save(Path::new(&path))
+            .map_err(|e| eoutln!("{} {}", "Unable to write the baseline:".red(), e))?;
+        eoutln!(
+            "{} {} ({} finding(s) of {})",
+            "💾 Updating the baseline:",
+            path.cyan(),
+            findings.len(),
+            template
+        );
+        return Ok(Some(updated.compare(template, findings)));
+    }
+    if path == "none" {
+        return Ok(None);
+    }
+
+    let baseline = Baseline::load(Path::new(&path))
+        .map_err(|e| eoutln!("{} {}", "Unable to read the baseline:".red(), e))?;
+    let comparison = baseline.compare(template, findings);
+    for entry in &comparison.suppressed {
+        eoutln!(
+            "  ├─ {} {} [{}] {}",
+            "known".cyan(),
+            entry.label(),
+            entry.kind,
+            entry.description
+        );
+    }
+    for entry in &comparison.unmatched {
+        eoutln!(
+            "  ├─ {} {} [{}] {}",
+            "no longer found".green(),
+            entry.label(),
+            entry.kind,
+            entry.description
+        );
+    }
+    Ok(Some(comparison))
+}
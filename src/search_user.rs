@@ -0,0 +1,1100 @@
+use std::rc::Rc;
+use std::str::FromStr;
+use std::time;
+
+use colored::Colorize;
+use log::{info, warn};
+use num_bigint_dig::BigInt;
+use num_traits::Zero;
+use rand::{thread_rng, Rng};
+use rustc_hash::{FxHashMap, FxHashSet};
+use serde_json::{json, Value};
+
+use crate::artifact_user::ArtifactWriter;
+use crate::executor::constraint_store::ConstraintStore;
+use crate::executor::symbolic_execution::SymbolicExecutor;
+use crate::executor::symbolic_setting::{
+    get_default_setting_for_concrete_execution, SymbolicExecutorSetting,
+};
+use crate::executor::symbolic_value::{SymbolicLibrary, SymbolicName, SymbolicValueRef};
+use crate::input_user::Input;
+use crate::manifest_user::parse_template_timeouts;
+use crate::mutator::assertion::assertion_reachability_search;
+use crate::mutator::assign_oracle::assignment_oracle_search;
+use crate::mutator::batch_evaluation::{batch_brute_force_search, EvaluationBackend};
+use crate::mutator::brute_force::brute_force_search_until;
+use crate::mutator::circom_validation::{validate_with_circom, CircomValidation, ValidationStatus};
+use crate::mutator::concolic::concolic_search;
+use crate::mutator::corpus::{
+    corpus_dir, load_corpus, update_corpus, CorpusCandidate, CorpusEntry, CorpusEntryKind,
+};
+use crate::mutator::detector::Detector;
+use crate::mutator::differential_testing::{differential_test_against_wasm, print_divergences};
+use crate::mutator::division::division_by_zero_search;
+use crate::mutator::exploitability::{assess_exploitability, assessed_severity, Exploitability};
+use crate::mutator::finding_stream::FindingStream;
+use crate::mutator::groth16_poc::{generate_groth16_poc, Groth16Poc, Groth16PocConfig, PocStatus};
+use crate::mutator::input_spec::InputSpec;
+use crate::mutator::log_replay::replay_logs;
+use crate::mutator::mutation_config::{load_mutation_config, MutationConfig};
+use crate::mutator::mutation_test::{mutation_test_search, MutationTestResult};
+use crate::mutator::mutation_test_crossover_fn::random_crossover;
+use crate::mutator::mutation_test_evolution_fn::simple_evolution;
+use crate::mutator::mutation_test_trace_fitness_fn::evaluate_trace_fitness_by_error;
+use crate::mutator::mutation_test_trace_initialization_fn::{
+    initialize_population_with_constant_replacement,
+    initialize_population_with_operator_or_const_replacement,
+    initialize_population_with_operator_or_const_replacement_or_addition,
+    initialize_population_with_operator_or_const_replacement_or_deletion,
+};
+use crate::mutator::mutation_test_trace_mutation_fn::{
+    mutate_trace_with_constant_replacement, mutate_trace_with_operator_or_const_replacement,
+    mutate_trace_with_operator_or_const_replacement_or_addition,
+    mutate_trace_with_operator_or_const_replacement_or_deletion,
+};
+use crate::mutator::mutation_test_trace_selection_fn::roulette_selection;
+use crate::mutator::mutation_test_update_input_fn::{
+    update_input_population_with_coverage_maximization, update_input_population_with_fitness_score,
+    update_input_population_with_random_sampling,
+};
+use crate::mutator::portfolio::{portfolio_search, print_portfolio_result};
+use crate::mutator::prime_consistency::{check_prime_consistency, prime_modulus};
+use crate::mutator::repair::suggest_repairs;
+use crate::mutator::severity::Severity;
+use crate::mutator::slicing::slice_constraints_by_target_signal;
+use crate::mutator::staged_search::{parse_stages, print_staged_search_result, staged_search};
+use crate::mutator::unused_outputs::suggest_unused_output_patches;
+use crate::mutator::utils::{classify_counterexamples, BaseVerificationConfig, CounterExample};
+use crate::mutator::violation_density::{
+    estimate_violation_density, DensitySampling, ViolationDensity,
+};
+use crate::mutator::witness_export::{
+    build_input_json, parse_input_json, AssignmentFormat, ValueFormat,
+};
+use crate::stats::symbolic_stats::CompressionAnomaly;
+use crate::type_analysis_user;
+use crate::AnalysedCircuit;
+
+/// The result of the search of a circuit for counterexamples (see `search_counter_examples`).
+pub struct SearchOutcome {
+    pub verification_base_config: BaseVerificationConfig,
+    /// The constraints under analysis, sliced by `--target_signal` if it is given.
+    pub symbolic_trace: Vec<SymbolicValueRef>,
+    pub side_constraints: Vec<SymbolicValueRef>,
+    /// The seed from which every random choice of the run derives.
+    pub seed: u64,
+    /// The counterexample of the verdict, constructed by a detector or found by the search.
+    pub counter_example: Option<CounterExample>,
+    /// The detector or the search that found the counterexample, which names its files.
+    pub counter_example_source: String,
+    /// The other distinct counterexamples found by the search.
+    pub additional_counter_examples: Vec<CounterExample>,
+    /// The details of the run saved with the counterexample (`8_auxiliary_result`).
+    pub auxiliary_result: Value,
+    /// The stage of `--search_mode heuristics` that found the counterexample.
+    pub heuristics_stage: Option<&'static str>,
+    pub violation_density: Option<ViolationDensity>,
+}
+
+/// The counterexamples of a search and what `examine_counter_examples` found out about them.
+#[derive(Default)]
+pub struct ExaminedCounterExamples {
+    /// The counterexample of the verdict, followed by the additional ones.
+    pub counter_examples: Vec<CounterExample>,
+    /// The exploitability of each counterexample, if it could be assessed.
+    pub exploitabilities: Vec<Option<Exploitability>>,
+    pub repair_suggestions: Vec<String>,
+    pub circom_validation: Option<CircomValidation>,
+    pub groth16_poc: Option<Groth16Poc>,
+    /// The file of the first counterexample, or an empty string if it is not saved.
+    pub counterexample_path: String,
+}
+
+/// Prints the distinct counterexamples grouped by the constraint they violate, and records the
+/// groups in the auxiliary result.
+fn print_counterexample_classes(counter_examples: &[CounterExample], auxiliary_result: &mut Value) {
+    let classes = classify_counterexamples(counter_examples);
+    eoutln!(
+        "{} {} counterexamples in {} classes",
+        "🗂️ Distinct Counterexamples:".yellow(),
+        counter_examples.len(),
+        classes.len()
+    );
+    for (class, indices) in &classes {
+        eoutln!("  ├─ {} : {}", class, indices.len());
+    }
+    auxiliary_result["counterexample_classes"] = json!(classes
+        .iter()
+        .map(|(class, indices)| (class.clone(), indices.len()))
+        .collect::<FxHashMap<String, usize>>());
+}
+
+/// Runs the genetic search of `--search_mode ga` with the operators selected in
+/// `mutation_config`, passing each counterexample to `on_counter_example` as soon as it is found.
+fn run_mutation_test_search<F>(
+    conc_executor: &mut SymbolicExecutor,
+    symbolic_trace: &Vec<SymbolicValueRef>,
+    side_constraints: &Vec<SymbolicValueRef>,
+    verification_base_config: &BaseVerificationConfig,
+    mutation_config: &MutationConfig,
+    on_counter_example: F,
+) -> MutationTestResult
+where
+    F: FnMut(&SymbolicLibrary, &CounterExample),
+{
+    let trace_initialization_fn = match mutation_config.trace_mutation_method.as_str() {
+        "naive" => initialize_population_with_constant_replacement,
+        "constant" => initialize_population_with_constant_replacement,
+        "constant_operator" => initialize_population_with_operator_or_const_replacement,
+        "constant_operator_add" => initialize_population_with_operator_or_const_replacement_or_addition,
+        "constant_operator_delete" => initialize_population_with_operator_or_const_replacement_or_deletion,
+        _ => panic!("`trace_mutation_method` should be one of [`constant`, `constant_operator`, `constant_operator_add`, `constant_operator_delete`]")
+    };
+
+    let trace_mutation_fn = match mutation_config.trace_mutation_method.as_str() {
+        "naive" => mutate_trace_with_constant_replacement,
+        "constant" => mutate_trace_with_constant_replacement,
+        "constant_operator" => mutate_trace_with_operator_or_const_replacement,
+        "constant_operator_add" => mutate_trace_with_operator_or_const_replacement_or_addition,
+        "constant_operator_delete" => mutate_trace_with_operator_or_const_replacement_or_deletion,
+        _ => panic!("`trace_mutation_method` should be one of [`constant`, `constant_operator`, `constant_operator_add`, `constant_operator_delete`]")
+    };
+
+    let update_input_fn = match mutation_config.input_initialization_method.as_str() {
+        "random" => update_input_population_with_random_sampling,
+        "fitness" => update_input_population_with_fitness_score,
+        "coverage" => update_input_population_with_coverage_maximization,
+        _ => panic!(
+            "`input_initialization_method` should be one of [`random`, `fitness`, `coverage`]"
+        ),
+    };
+
+    mutation_test_search(
+        conc_executor,
+        symbolic_trace,
+        side_constraints,
+        verification_base_config,
+        mutation_config,
+        trace_initialization_fn,
+        update_input_fn,
+        evaluate_trace_fitness_by_error,
+        simple_evolution,
+        trace_mutation_fn,
+        random_crossover,
+        roulette_selection,
+        on_counter_example,
+    )
+}
+
+/// Records `ce` in `findings` with the severity assessed from its exploitability, unless a
+/// counterexample with the same witness is already recorded, so that each counterexample is
+/// streamed once, as soon as it is found.
+fn stream_counter_example(
+    user_input: &Input,
+    ce: &CounterExample,
+    trace_store: &dyn ConstraintStore,
+    symbolic_library: &SymbolicLibrary,
+    verification_base_config: &BaseVerificationConfig,
+    public_inputs: &[String],
+    streamed: &mut FxHashSet<Vec<(SymbolicName, BigInt)>>,
+    findings: &mut FindingStream,
+) {
+    if !streamed.insert(ce.witness_key(&verification_base_config.prime)) {
+        return;
+    }
+    let exploitability = assess_exploitability(
+        ce,
+        trace_store,
+        symbolic_library,
+        verification_base_config,
+        public_inputs,
+    );
+    let assignment_format = AssignmentFormat::new(
+        ValueFormat::from_str(&user_input.ce_format()).unwrap(),
+        verification_base_config.prime.clone(),
+        symbolic_library,
+        &verification_base_config.target_template_name,
+    );
+    findings.push_counter_example(
+        assessed_severity(ce, exploitability.as_ref()),
+        &ce.classification(),
+        ce.to_json_with_meta(
+            &symbolic_library.id2name,
+            &FxHashMap::default(),
+            &assignment_format,
+        ),
+    );
+}
+
+/// Runs the detectors that construct counterexamples and, if none does, the search selected by
+/// `--search_mode` on the constraints gathered from `circuit`, together with the analyses that
+/// depend on the search (the differential test of `--path_to_wasm`, `--compare_prime`,
+/// `--density_samples`, and `--corpus`).
+///
+/// # Parameters
+/// - `search_setting`: The setting of `sym_executor` while the constraints are searched.
+/// - `compression_anomalies`: The components searched first by `--search_mode heuristics`.
+/// - `auxiliary_result`: The details of the run gathered before the search, which the search
+///   completes.
+/// - `findings`: The stream of the findings of the detectors, where each counterexample is
+///   recorded as soon as it is found.
+pub fn search_counter_examples<'a>(
+    user_input: &Input,
+    sym_executor: &mut SymbolicExecutor<'a>,
+    search_setting: &'a SymbolicExecutorSetting,
+    circuit: &AnalysedCircuit,
+    detectors: &[Rc<dyn Detector>],
+    input_spec: &InputSpec,
+    trace_store: &dyn ConstraintStore,
+    side_store: &dyn ConstraintStore,
+    compression_anomalies: &[CompressionAnomaly],
+    mut auxiliary_result: Value,
+    findings: &mut FindingStream,
+) -> Result<SearchOutcome, ()> {
+    let program_archive = circuit.program_archive;
+    let min_severity = Severity::from_str(&user_input.min_severity()).unwrap();
+    let mut heuristics_stage = None;
+    let mut violation_density = None;
+
+    eoutln!("{}", "══════════════════════════════════".green());
+    eoutln!("{}", "🩺 Scanning TCCT Instances...".green());
+
+    let (main_template_name, template_param_names, template_param_values) = (
+        circuit.template_name,
+        circuit.param_names.clone(),
+        circuit.args.clone(),
+    );
+
+    let verification_base_config = BaseVerificationConfig {
+        target_template_name: main_template_name.to_string(),
+        prime: BigInt::from_str(&user_input.debug_prime()).unwrap(),
+        range: BigInt::from_str(&user_input.heuristics_range()).unwrap(),
+        quick_mode: &*user_input.search_mode == "quick",
+        heuristics_mode: &*user_input.search_mode == "heuristics",
+        progress_interval: 10000,
+        template_param_names: template_param_names,
+        template_param_values: template_param_values,
+        check_output_tags: user_input.flag_check_output_tags,
+        max_counterexamples: user_input.max_counterexamples().parse().unwrap(),
+        input_shapes: type_analysis_user::gather_input_shapes(
+            sym_executor.symbolic_library,
+            main_template_name,
+            &sym_executor.id2dimensions,
+        ),
+        input_spec: input_spec.clone(),
+    };
+    for key in input_spec.unknown_inputs(
+        &sym_executor.symbolic_library.template_library
+            [&sym_executor.symbolic_library.name2id[main_template_name]],
+        &sym_executor.symbolic_library.name2id,
+    ) {
+        eoutln!(
+            "{} `{}` is not an input of `{}`",
+            "⚠️ Input Spec:".yellow(),
+            key,
+            main_template_name
+        );
+    }
+
+    // A seed of 0, the default of both `--seed` and the mutation setting, means that
+    // the seed is drawn at random. Every random choice of the run derives from the
+    // resolved seed, which is reported so that the run can be replayed with `--seed`.
+    let mut mutation_config = load_mutation_config(&user_input.path_to_mutation_setting()).unwrap();
+    let seed = match user_input.seed().parse::<u64>().unwrap() {
+        0 if mutation_config.seed != 0 => mutation_config.seed,
+        0 => thread_rng().gen_range(1, u64::MAX),
+        seed => seed,
+    };
+    mutation_config.seed = seed;
+    if user_input.flag_free_witnesses {
+        mutation_config.free_witnesses = true;
+    }
+    eoutln!(
+        "{} {}",
+        "🎲 Random Seed:".green(),
+        seed.to_string().bright_yellow()
+    );
+
+    // The inputs kept by the previous runs seed the genetic search. At most half of
+    // the input population is seeded, so that the search keeps exploring new inputs.
+    let corpus_path = if user_input.corpus() == "none" {
+        None
+    } else {
+        Some(corpus_dir(&user_input.corpus(), main_template_name))
+    };
+    if let Some(dir) = &corpus_path {
+        let (inputs, num_skipped) = load_corpus(
+            dir,
+            sym_executor.symbolic_library,
+            &verification_base_config,
+            mutation_config.input_population_size / 2,
+        );
+        eoutln!(
+            "{} {} inputs loaded from {} ({} skipped)",
+            "📦 Corpus:".green(),
+            inputs.len().to_string().bright_yellow(),
+            dir.display(),
+            num_skipped
+        );
+        mutation_config.seed_inputs = inputs;
+    }
+
+    // The solvers evaluate the constraints repeatedly, so only the (sliced)
+    // constraints under analysis are loaded back into memory.
+    let (symbolic_trace, side_constraints) = if user_input.target_signal() == "none" {
+        (trace_store.to_vec(), side_store.to_vec())
+    } else {
+        match slice_constraints_by_target_signal(
+            trace_store,
+            side_store,
+            &user_input.target_signal(),
+            &sym_executor.symbolic_library.id2name,
+        ) {
+            Some((sliced_trace, sliced_side_constraints)) => {
+                eoutln!(
+                    "{} {} (Trace: {}/{}, Side: {}/{})",
+                    "✂️ Slicing Constraints for".green(),
+                    user_input.target_signal().cyan(),
+                    sliced_trace.len(),
+                    trace_store.len(),
+                    sliced_side_constraints.len(),
+                    side_store.len()
+                );
+                (sliced_trace, sliced_side_constraints)
+            }
+            None => {
+                return Result::Err(eoutln!(
+                    "{} {}",
+                    "Target signal does not appear in any constraint:".red(),
+                    user_input.target_signal()
+                ));
+            }
+        }
+    };
+
+    // The concolic search executes the circuit again with the setting that gathered the trace
+    let base_config = sym_executor.setting;
+    sym_executor.setting = search_setting;
+
+    if user_input.path_to_wasm() != "none" {
+        let subse_base_config = get_default_setting_for_concrete_execution(
+            BigInt::from_str(&user_input.debug_prime()).unwrap(),
+            user_input.constraint_assert_dissabled_flag(),
+        );
+        let id2dimensions = sym_executor.id2dimensions.clone();
+        let mut conc_executor =
+            SymbolicExecutor::new(&mut sym_executor.symbolic_library, &subse_base_config);
+        let num_iterations = user_input.differential_iterations().parse().unwrap();
+        match differential_test_against_wasm(
+            &mut conc_executor,
+            &verification_base_config,
+            &id2dimensions,
+            &user_input.path_to_wasm(),
+            num_iterations,
+            seed,
+        ) {
+            Ok(divergences) => print_divergences(&divergences, num_iterations),
+            Err(e) => warn!("Unable to run the WASM witness calculator: {}", e),
+        }
+    }
+
+    // The first counterexample constructed by a detector (e.g. unused outputs) is
+    // the verdict of the run, and the other findings are reported as warnings.
+    let mut counter_example = None;
+    // The witnesses of the counterexamples streamed to `findings` as they were found
+    let mut streamed = FxHashSet::default();
+    // The detector or the search that found the counterexample, which names its files
+    let mut counter_example_source = user_input.search_mode();
+    let mut additional_counter_examples = Vec::new();
+    let mut corpus_candidates = Vec::new();
+    let mut boundary_issues = Vec::new();
+    for detector in detectors {
+        let mut messages = Vec::new();
+        let mut reported_messages = Vec::new();
+        for finding in detector.run(
+            sym_executor,
+            trace_store,
+            side_store,
+            &verification_base_config,
+        ) {
+            match finding.counter_example {
+                Some(ce) => {
+                    if counter_example.is_none() {
+                        stream_counter_example(
+                            user_input,
+                            &ce,
+                            trace_store,
+                            sym_executor.symbolic_library,
+                            &verification_base_config,
+                            circuit.public_inputs,
+                            &mut streamed,
+                            findings,
+                        );
+                        counter_example = Some(ce);
+                        counter_example_source = detector.name().to_string();
+                    }
+                }
+                None => {
+                    messages.push(finding.message.clone());
+                    if finding.severity >= min_severity {
+                        reported_messages
+                            .push(format!("[{}] {}", finding.severity, finding.message));
+                    }
+                    findings.push((finding.severity, finding.category, finding.message));
+                }
+            }
+        }
+        if !reported_messages.is_empty() {
+            eoutln!(
+                "{} {}",
+                format!("{}:", detector.heading()).yellow(),
+                reported_messages.len()
+            );
+            for message in &reported_messages {
+                eoutln!("  ├─ {}", message);
+            }
+        }
+        if detector.name() == "component_boundaries" {
+            boundary_issues = messages.clone();
+        }
+        auxiliary_result["detectors"][detector.name()] = json!(messages);
+    }
+    auxiliary_result["component_boundary_issues"] = json!(boundary_issues);
+    if user_input.compare_prime() != "none" {
+        let other_prime = prime_modulus(&user_input.compare_prime()).unwrap();
+        let prime_dependent_constraints = check_prime_consistency(
+            &symbolic_trace,
+            &side_constraints,
+            sym_executor.symbolic_library,
+            &verification_base_config,
+            &other_prime,
+            user_input.prime_samples().parse().unwrap(),
+            seed,
+        );
+        eoutln!(
+            "{} {} (against {})",
+            "⚖️ Prime-Dependent Constraints:".yellow(),
+            prime_dependent_constraints.len(),
+            user_input.compare_prime()
+        );
+        for c in &prime_dependent_constraints {
+            let (holds, fails) = if c.holds_under_base {
+                ("the base prime".to_string(), user_input.compare_prime())
+            } else {
+                (user_input.compare_prime(), "the base prime".to_string())
+            };
+            eoutln!(
+                "  ├─ `{}` holds over {} but not over {} on {}",
+                c.constraint,
+                holds,
+                fails,
+                c.witness
+            );
+        }
+        findings.extend(prime_dependent_constraints.iter().map(|c| {
+            (
+                Severity::Medium,
+                "Prime-Dependent Constraint",
+                format!("`{}` on {}", c.constraint, c.witness),
+            )
+        }));
+        auxiliary_result["prime_dependent_constraints"] = json!({
+            "compare_prime": other_prime.to_string(),
+            "constraints": prime_dependent_constraints
+                .iter()
+                .map(|c| json!({
+                    "constraint": c.constraint,
+                    "is_side_constraint": c.is_side_constraint,
+                    "holds_under_base": c.holds_under_base,
+                    "witness": c.witness,
+                }))
+                .collect::<Vec<_>>(),
+        });
+    }
+    if counter_example.is_none() {
+        let subse_base_config = get_default_setting_for_concrete_execution(
+            BigInt::from_str(&user_input.debug_prime()).unwrap(),
+            user_input.constraint_assert_dissabled_flag(),
+        );
+        let mut conc_executor =
+            SymbolicExecutor::new(&mut sym_executor.symbolic_library, &subse_base_config);
+        conc_executor.feed_arguments(
+            &verification_base_config.template_param_names,
+            &verification_base_config.template_param_values,
+        );
+        // The brute-force searches infer the ranges of the signals of the components
+        conc_executor.component_templates = sym_executor.component_templates.clone();
+        conc_executor.component_args = sym_executor.component_args.clone();
+
+        // The time limit of the main template, from `--template_timeouts` or the
+        // `[timeouts]` of the project manifest, bounds the search.
+        let search_timeout = parse_template_timeouts(&user_input.template_timeouts())
+            .unwrap()
+            .get(main_template_name.as_str())
+            .map(|secs| time::Duration::from_secs(*secs));
+        let search_deadline = search_timeout.map(|t| time::Instant::now() + t);
+        if let Some(timeout) = search_timeout {
+            eoutln!(
+                "{} {}s",
+                "⏱️ Search Timeout:".green(),
+                timeout.as_secs().to_string().bright_yellow()
+            );
+            mutation_config.deadline = search_deadline;
+        }
+
+        counter_example = match &*user_input.search_mode() {
+            "quick" | "full" if user_input.eval_backend() == "gpu" => batch_brute_force_search(
+                &mut conc_executor,
+                &symbolic_trace,
+                &side_constraints,
+                &verification_base_config,
+                &FxHashMap::default(),
+                search_deadline,
+                EvaluationBackend::Gpu,
+            ),
+            "quick" | "full" => brute_force_search_until(
+                &mut conc_executor,
+                &symbolic_trace,
+                &side_constraints,
+                &verification_base_config,
+                &FxHashMap::default(),
+                search_deadline,
+            ),
+            "heuristics" => {
+                let suspicious_components: Vec<String> = compression_anomalies
+                    .iter()
+                    .map(|anomaly| anomaly.owner.clone())
+                    .collect();
+                let result = staged_search(
+                    &mut conc_executor,
+                    &symbolic_trace,
+                    &side_constraints,
+                    &verification_base_config,
+                    &parse_stages(&user_input.heuristics_stages()).unwrap(),
+                    &suspicious_components,
+                );
+                print_staged_search_result(&result);
+                auxiliary_result["heuristics_stages"] = result.to_json();
+                heuristics_stage = result.winner.map(|stage| stage.name());
+                result.counter_example
+            }
+            "assert" => assertion_reachability_search(
+                &mut conc_executor,
+                &symbolic_trace,
+                &side_constraints,
+                &verification_base_config,
+                &program_archive.file_library,
+                user_input.assert_search_iterations().parse().unwrap(),
+                seed,
+            ),
+            "assign" => assignment_oracle_search(
+                &mut conc_executor,
+                &symbolic_trace,
+                &side_constraints,
+                &verification_base_config,
+                user_input.assert_search_iterations().parse().unwrap(),
+                seed,
+            ),
+            "ga" => {
+                info!("\n{}", mutation_config);
+
+                let result = run_mutation_test_search(
+                    &mut conc_executor,
+                    &symbolic_trace,
+                    &side_constraints,
+                    &verification_base_config,
+                    &mutation_config,
+                    |library, ce| {
+                        stream_counter_example(
+                            user_input,
+                            ce,
+                            trace_store,
+                            library,
+                            &verification_base_config,
+                            circuit.public_inputs,
+                            &mut streamed,
+                            findings,
+                        )
+                    },
+                );
+                auxiliary_result["mutation_test_config"] =
+                    serde_json::to_value(result.mutation_config)
+                        .expect("Failed to serialize to JSON");
+                auxiliary_result["mutation_test_log"] = json!({"random_seed":result.random_seed,"generation":result.generation, "fitness_score_log":result.fitness_score_log});
+                auxiliary_result["mutation_operator_stats"] =
+                    serde_json::to_value(&result.operator_stats)
+                        .expect("Failed to serialize to JSON");
+                if result.counter_examples.len() > 1 {
+                    print_counterexample_classes(&result.counter_examples, &mut auxiliary_result);
+                    additional_counter_examples = result.counter_examples[1..].to_vec();
+                }
+                corpus_candidates = result.corpus_candidates;
+                result.counter_example
+            }
+            "concolic" => {
+                let seed_json = if user_input.concolic_seed() == "none" {
+                    build_input_json(
+                        &FxHashMap::default(),
+                        conc_executor.symbolic_library,
+                        &verification_base_config,
+                        &sym_executor.id2dimensions,
+                    )
+                } else {
+                    match std::fs::read_to_string(user_input.concolic_seed())
+                        .map_err(|e| e.to_string())
+                        .and_then(|s| serde_json::from_str(&s).map_err(|e| e.to_string()))
+                    {
+                        Ok(json) => json,
+                        Err(e) => {
+                            return Result::Err(eoutln!(
+                                "{} {}",
+                                "Unable to read the concolic seed:".red(),
+                                e
+                            ));
+                        }
+                    }
+                };
+                let seed_input = match parse_input_json(
+                    &seed_json,
+                    conc_executor.symbolic_library,
+                    &verification_base_config,
+                ) {
+                    Ok(input) => input,
+                    Err(e) => {
+                        return Result::Err(eoutln!("{} {}", "Invalid concolic seed:".red(), e));
+                    }
+                };
+                concolic_search(
+                    &mut conc_executor,
+                    base_config,
+                    &verification_base_config,
+                    seed_input,
+                    user_input.concolic_max_paths().parse().unwrap(),
+                    user_input.assert_search_iterations().parse().unwrap(),
+                    seed,
+                    |conc, trace, side| {
+                        run_mutation_test_search(
+                            conc,
+                            trace,
+                            side,
+                            &verification_base_config,
+                            &mutation_config,
+                            |library, ce| {
+                                stream_counter_example(
+                                    user_input,
+                                    ce,
+                                    trace_store,
+                                    library,
+                                    &verification_base_config,
+                                    circuit.public_inputs,
+                                    &mut streamed,
+                                    findings,
+                                )
+                            },
+                        )
+                        .counter_example
+                    },
+                )
+            }
+            "auto" => {
+                let result = portfolio_search(
+                    &mut conc_executor,
+                    &symbolic_trace,
+                    &side_constraints,
+                    &verification_base_config,
+                    search_timeout.into_iter().fold(
+                        time::Duration::from_secs(user_input.auto_time_budget().parse().unwrap()),
+                        time::Duration::min,
+                    ),
+                    user_input.assert_search_iterations().parse().unwrap(),
+                    seed,
+                    |conc, shared_inputs, deadline| {
+                        let mut ga_config = mutation_config.clone();
+                        ga_config.seed_inputs = shared_inputs
+                            .iter()
+                            .chain(mutation_config.seed_inputs.iter())
+                            .cloned()
+                            .collect();
+                        ga_config.deadline = Some(deadline);
+                        run_mutation_test_search(
+                            conc,
+                            &symbolic_trace,
+                            &side_constraints,
+                            &verification_base_config,
+                            &ga_config,
+                            |library, ce| {
+                                stream_counter_example(
+                                    user_input,
+                                    ce,
+                                    trace_store,
+                                    library,
+                                    &verification_base_config,
+                                    circuit.public_inputs,
+                                    &mut streamed,
+                                    findings,
+                                )
+                            },
+                        )
+                        .counter_example
+                    },
+                );
+                print_portfolio_result(&result);
+                auxiliary_result["portfolio"] = result.to_json();
+                result.counter_example
+            }
+            _ => panic!(
+                "search_mode={} is not supported",
+                user_input.search_mode.to_string()
+            ),
+        };
+        if counter_example.is_none() && user_input.flag_check_division_by_zero {
+            eoutln!("{}", "➗ Searching for Divisions by Zero...".green());
+            counter_example = division_by_zero_search(
+                &mut conc_executor,
+                &symbolic_trace,
+                &side_constraints,
+                &verification_base_config,
+                &program_archive.file_library,
+                user_input.assert_search_iterations().parse().unwrap(),
+                seed,
+            );
+            counter_example_source = "division_by_zero".to_string();
+        }
+        // The genetic search streams its counterexamples as it finds them, and the other
+        // searches as soon as they return
+        if let Some(ce) = &counter_example {
+            stream_counter_example(
+                user_input,
+                ce,
+                trace_store,
+                conc_executor.symbolic_library,
+                &verification_base_config,
+                circuit.public_inputs,
+                &mut streamed,
+                findings,
+            );
+        }
+        let num_density_samples: usize = user_input.density_samples().parse().unwrap();
+        if num_density_samples > 0 {
+            eoutln!("{}", "📐 Estimating the Violation Density...".green());
+            let density = estimate_violation_density(
+                &mut conc_executor,
+                &symbolic_trace,
+                &side_constraints,
+                &verification_base_config,
+                DensitySampling::from_str(&user_input.density_sampling()).unwrap(),
+                num_density_samples,
+                seed,
+            );
+            eoutln!("{} {}", "📐 Violation Density:".green(), density.summary());
+            auxiliary_result["violation_density"] = density.to_json();
+            violation_density = Some(density);
+        }
+        if let Some(dir) = &corpus_path {
+            let entries: Vec<CorpusEntry> = counter_example
+                .iter()
+                .chain(additional_counter_examples.iter())
+                .map(|ce| CorpusCandidate {
+                    kind: CorpusEntryKind::Counterexample,
+                    input: ce.assignment.clone(),
+                    fitness: BigInt::zero(),
+                })
+                .chain(corpus_candidates.into_iter())
+                .map(|candidate| {
+                    CorpusEntry::from_candidate(
+                        &candidate,
+                        conc_executor.symbolic_library,
+                        &verification_base_config,
+                        &sym_executor.id2dimensions,
+                    )
+                })
+                .collect();
+            match update_corpus(dir, &entries, mutation_config.input_population_size / 2) {
+                Ok(update) => {
+                    eoutln!(
+                        "{} {} added, {} removed by minimization, {} in {}",
+                        "📦 Corpus:".green(),
+                        update.num_added.to_string().bright_yellow(),
+                        update.num_removed,
+                        update.num_entries,
+                        dir.display()
+                    );
+                    auxiliary_result["corpus"] = json!({
+                        "path": dir.display().to_string(),
+                        "num_added": update.num_added,
+                        "num_removed": update.num_removed,
+                        "num_entries": update.num_entries,
+                    });
+                }
+                Err(e) => warn!("Unable to update the corpus: {}", e),
+            }
+        }
+    }
+
+    Ok(SearchOutcome {
+        verification_base_config,
+        symbolic_trace,
+        side_constraints,
+        seed,
+        counter_example,
+        counter_example_source,
+        additional_counter_examples,
+        auxiliary_result,
+        heuristics_stage,
+        violation_density,
+    })
+}
+
+/// Examines the counterexample of `outcome`, if any: assesses the exploitability of each
+/// counterexample, already streamed to `findings` by the search, then suggests repairs,
+/// validates it with circom, generates a Groth16 proof of concept and replays its logs as
+/// requested by `user_input`, and finally saves it with `--save_output` or prints it.
+pub fn examine_counter_examples(
+    user_input: &Input,
+    sym_executor: &mut SymbolicExecutor,
+    circuit: &AnalysedCircuit,
+    trace_store: &dyn ConstraintStore,
+    outcome: &mut SearchOutcome,
+    artifacts: &mut ArtifactWriter,
+    findings: &mut FindingStream,
+) -> ExaminedCounterExamples {
+    let ce = match &outcome.counter_example {
+        Some(ce) => ce,
+        None => return ExaminedCounterExamples::default(),
+    };
+    let program_archive = circuit.program_archive;
+    let (id, start_time, seed) = (circuit.template_name, circuit.start_time, outcome.seed);
+    let verification_base_config = &outcome.verification_base_config;
+    let auxiliary_result = &mut outcome.auxiliary_result;
+    let mut repair_suggestions: Vec<String> = Vec::new();
+    let mut circom_validation: Option<CircomValidation> = None;
+    let mut groth16_poc: Option<Groth16Poc> = None;
+    let mut counterexample_path = String::new();
+
+    let mut reported_counter_examples = vec![ce.clone()];
+    reported_counter_examples.extend(outcome.additional_counter_examples.iter().cloned());
+    let exploitabilities: Vec<Option<Exploitability>> = reported_counter_examples
+        .iter()
+        .map(|ce| {
+            assess_exploitability(
+                ce,
+                trace_store,
+                &sym_executor.symbolic_library,
+                verification_base_config,
+                circuit.public_inputs,
+            )
+        })
+        .collect();
+    if let Some(exploitability) = &exploitabilities[0] {
+        eoutln!(
+            "{} {}",
+            "🎯 Exploitability:".yellow(),
+            exploitability.lookup_fmt(&sym_executor.symbolic_library.id2name)
+        );
+        auxiliary_result["exploitability"] =
+            exploitability.to_json(&sym_executor.symbolic_library.id2name);
+    }
+    let assignment_format = AssignmentFormat::new(
+        ValueFormat::from_str(&user_input.ce_format()).unwrap(),
+        verification_base_config.prime.clone(),
+        &sym_executor.symbolic_library,
+        id,
+    );
+    if user_input.flag_suggest_repair {
+        let subse_base_config = get_default_setting_for_concrete_execution(
+            BigInt::from_str(&user_input.debug_prime()).unwrap(),
+            user_input.constraint_assert_dissabled_flag(),
+        );
+        let mut conc_executor =
+            SymbolicExecutor::new(&mut sym_executor.symbolic_library, &subse_base_config);
+        repair_suggestions = suggest_repairs(
+            &mut conc_executor,
+            &outcome.symbolic_trace,
+            &outcome.side_constraints,
+            verification_base_config,
+            ce,
+            user_input.assert_search_iterations().parse().unwrap(),
+            seed,
+        )
+        .iter()
+        .map(|candidate| candidate.lookup_fmt(&sym_executor.symbolic_library.id2name))
+        .collect();
+        if let Some(source) = program_archive
+            .file_library
+            .to_storage()
+            .get(circuit.file_id)
+            .map(|file| file.source().clone())
+        {
+            repair_suggestions.extend(
+                suggest_unused_output_patches(
+                    sym_executor.symbolic_library,
+                    verification_base_config,
+                    ce,
+                    user_input.input_file(),
+                    &source,
+                    circuit.file_id,
+                )
+                .into_iter()
+                .map(|patch| patch.diff),
+            );
+        }
+        eoutln!(
+            "{} {}",
+            "🩹 Repair Suggestions:".yellow(),
+            repair_suggestions.len()
+        );
+        for suggestion in &repair_suggestions {
+            eoutln!("  ├─ {}", suggestion.trim_end());
+        }
+        findings.extend(
+            repair_suggestions
+                .iter()
+                .map(|suggestion| (Severity::Info, "Repair Suggestion", suggestion.clone())),
+        );
+        auxiliary_result["repair_suggestions"] = json!(repair_suggestions);
+    }
+    if user_input.validate_with_circom() != "none" {
+        let validation = validate_with_circom(
+            &user_input.validate_with_circom(),
+            user_input.input_file(),
+            user_input.get_link_libraries(),
+            &user_input.prime(),
+            ce,
+            &sym_executor.symbolic_library,
+            verification_base_config,
+            &sym_executor.id2dimensions,
+        );
+        let label = match validation.status {
+            ValidationStatus::Confirmed => validation.label().red().bold(),
+            ValidationStatus::Unconfirmed => validation.label().yellow().bold(),
+        };
+        eoutln!(
+            "{} {} ({})",
+            "🔬 Circom Validation:".yellow(),
+            label,
+            validation.reason
+        );
+        findings.push((
+            Severity::Info,
+            "Circom Validation",
+            format!("{}: {}", validation.label(), validation.reason),
+        ));
+        auxiliary_result["circom_validation"] = validation.to_json();
+        circom_validation = Some(validation);
+    }
+    if user_input.groth16_zkey() != "none" {
+        match &circom_validation {
+            Some(validation) if validation.status == ValidationStatus::Confirmed => {
+                let poc = generate_groth16_poc(
+                    &Groth16PocConfig {
+                        path_to_circom: &user_input.validate_with_circom(),
+                        path_to_snarkjs: &user_input.snarkjs(),
+                        path_to_zkey: &user_input.groth16_zkey(),
+                        input_file: user_input.input_file(),
+                        link_libraries: user_input.get_link_libraries(),
+                        prime_name: &user_input.prime(),
+                    },
+                    ce,
+                    &sym_executor.symbolic_library,
+                    verification_base_config,
+                    &sym_executor.id2dimensions,
+                    &artifacts.prefix(&outcome.counter_example_source, 1),
+                );
+                let label = match poc.status {
+                    PocStatus::Verified => poc.label().red().bold(),
+                    _ => poc.label().yellow().bold(),
+                };
+                eoutln!("{} {} ({})", "🧾 Groth16 PoC:".yellow(), label, poc.reason);
+                for (kind, path) in &poc.artifacts {
+                    eoutln!("  ├─ {}", path.cyan());
+                    artifacts.record(*kind, path);
+                }
+                findings.push((
+                    Severity::Info,
+                    "Groth16 PoC",
+                    format!("{}: {}", poc.label(), poc.reason),
+                ));
+                auxiliary_result["groth16_poc"] = poc.to_json();
+                groth16_poc = Some(poc);
+            }
+            Some(_) => eoutln!(
+                "{}",
+                "🧾 Groth16 PoC: skipped, since circom does not confirm the counterexample"
+                    .yellow()
+            ),
+            None => warn!("--groth16_zkey requires --validate_with_circom"),
+        }
+    }
+    if user_input.flag_replay_logs {
+        let subse_base_config = get_default_setting_for_concrete_execution(
+            BigInt::from_str(&user_input.debug_prime()).unwrap(),
+            user_input.constraint_assert_dissabled_flag(),
+        );
+        let logged_lines = replay_logs(
+            &mut sym_executor.symbolic_library,
+            &subse_base_config,
+            verification_base_config,
+            ce,
+            &program_archive.file_library,
+        );
+        eoutln!(
+            "{} {}",
+            "📝 Logs of the Counterexample:".yellow(),
+            logged_lines.len()
+        );
+        for line in &logged_lines {
+            eoutln!("  ├─ {}", line.lookup_fmt());
+        }
+        auxiliary_result["logs"] = json!(logged_lines
+            .iter()
+            .map(|line| line.to_json())
+            .collect::<Vec<_>>());
+    }
+    if user_input.flag_save_output {
+        // Save the output as JSON
+        let ce_meta = FxHashMap::from_iter([
+            (
+                "0_target_path".to_string(),
+                user_input.input_file().to_string(),
+            ),
+            ("1_main_template".to_string(), id.to_string()),
+            ("2_search_mode".to_string(), user_input.search_mode()),
+            (
+                "3_execution_time".to_string(),
+                format!("{:?}", start_time.elapsed()),
+            ),
+            (
+                "4_git_hash_of_zkfuzz".to_string(),
+                format!("{}", option_env!("GIT_HASH").unwrap_or("unknown")),
+            ),
+            ("4_random_seed".to_string(), seed.to_string()),
+        ]);
+        counterexample_path = artifacts.save_counter_examples(
+            &outcome.counter_example_source,
+            &reported_counter_examples,
+            &ce_meta,
+            auxiliary_result.clone(),
+            &sym_executor.symbolic_library,
+            verification_base_config,
+            &sym_executor.id2dimensions,
+            &assignment_format,
+        );
+    } else {
+        eoutln!("{}", ce.lookup_fmt(&sym_executor.symbolic_library.id2name));
+        for extra_ce in &outcome.additional_counter_examples {
+            eoutln!(
+                "{}",
+                extra_ce.lookup_fmt(&sym_executor.symbolic_library.id2name)
+            );
+        }
+    }
+
+    ExaminedCounterExamples {
+        counter_examples: reported_counter_examples,
+        exploitabilities,
+        repair_suggestions,
+        circom_validation,
+        groth16_poc,
+        counterexample_path,
+    }
+}
@@ -0,0 +1,573 @@
+use std::collections::BTreeMap;
+use std::env;
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::thread;
+use std::time;
+
+use colored::Colorize;
+use serde_json::{json, Value};
+
+use crate::input_user::Input;
+
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+/// The error of a request on a job that is unknown or whose results are not available.
+const JOB_ERROR: i64 = -32000;
+
+/// The maximum size of the body of a request in bytes.
+const MAX_REQUEST_SIZE: usize = 1 << 20;
+
+type RpcError = (i64, String);
+
+#[derive(Clone, Copy, PartialEq)]
+enum JobStatus {
+    Running,
+    Done,
+    Failed,
+    Timeout,
+    Cancelled,
+}
+
+impl JobStatus {
+    fn as_str(&self) -> &'static str {
+        match self {
+            JobStatus::Running => "running",
+            JobStatus::Done => "done",
+            JobStatus::Failed => "failed",
+            JobStatus::Timeout => "timeout",
+            JobStatus::Cancelled => "cancelled",
+        }
+    }
+}
+
+/// An analysis submitted to the server, run in a child process as in batch mode.
+struct Job {
+    target_path: String,
+    child: Option<Child>,
+    status: JobStatus,
+    start_time: time::Instant,
+    execution_time_ms: u128,
+    timeout: Option<time::Duration>,
+    /// The directory of the log, the summary, and the constraints written by the child process.
+    dir: PathBuf,
+}
+
+impl Job {
+    fn log_path(&self) -> PathBuf {
+        self.dir.join("log.txt")
+    }
+
+    fn summary_path(&self) -> PathBuf {
+        self.dir.join("summary.json")
+    }
+
+    fn constraints_path(&self) -> PathBuf {
+        self.dir.join("constraints.json")
+    }
+
+    /// Updates the status of a running job, killing it once its timeout is exceeded.
+    fn poll(&mut self) {
+        if let Some(child) = &mut self.child {
+            let status = match child.try_wait() {
+                Ok(Some(exit_status)) if exit_status.success() => Some(JobStatus::Done),
+                Ok(Some(_)) | Err(_) => Some(JobStatus::Failed),
+                Ok(None)
+                    if self
+                        .timeout
+                        .map_or(false, |t| self.start_time.elapsed() > t) =>
+                {
+                    let _ = child.kill();
+                    let _ = child.wait();
+                    Some(JobStatus::Timeout)
+                }
+                Ok(None) => None,
+            };
+            if let Some(status) = status {
+                self.finish(status);
+            }
+        }
+    }
+
+    fn cancel(&mut self) {
+        if let Some(child) = &mut self.child {
+            let _ = child.kill();
+            let _ = child.wait();
+            self.finish(JobStatus::Cancelled);
+        }
+    }
+
+    fn finish(&mut self, status: JobStatus) {
+        self.child = None;
+        self.status = status;
+        self.execution_time_ms = self.start_time.elapsed().as_millis();
+    }
+
+    /// Returns the last line printed by the analysis, e.g. `🩺 Scanning TCCT Instances...`.
+    fn progress(&self) -> String {
+        fs::read(self.log_path())
+            .map(|log| {
+                String::from_utf8_lossy(&log)
+                    .lines()
+                    .rev()
+                    .map(str::trim)
+                    .find(|line| !line.is_empty())
+                    .unwrap_or("")
+                    .to_string()
+            })
+            .unwrap_or_default()
+    }
+
+    fn to_json(&self, id: usize) -> Value {
+        let execution_time_ms = if self.status == JobStatus::Running {
+            self.start_time.elapsed().as_millis()
+        } else {
+            self.execution_time_ms
+        };
+        json!({
+            "job_id": id,
+            "target_path": self.target_path,
+            "status": self.status.as_str(),
+            "execution_time_ms": execution_time_ms as u64,
+            "progress": self.progress(),
+        })
+    }
+}
+
+fn read_json(path: &Path) -> Option<Value> {
+    serde_json::from_slice(&fs::read(path).ok()?).ok()
+}
+
+/// Keeps the jobs of the server and answers the JSON-RPC methods on them.
+struct JobManager {
+    exe: PathBuf,
+    work_dir: PathBuf,
+    jobs: BTreeMap<usize, Job>,
+    next_id: usize,
+    is_shutdown_requested: bool,
+}
+
+impl JobManager {
+    /// Starts the analysis of `params.path` with the command-line options `params.args`, bounded
+    /// by `params.timeout` seconds if given.
+    fn submit(&mut self, params: &Value) -> Result<Value, RpcError> {
+        let target = params["path"]
+            .as_str()
+            .ok_or((INVALID_PARAMS, "`path` must be a string".to_string()))?;
+        if !Path::new(target).is_file() {
+            return Err((INVALID_PARAMS, format!("circuit not found: {}", target)));
+        }
+        let args = match &params["args"] {
+            Value::Null => Vec::new(),
+            Value::Array(args) => args
+                .iter()
+                .map(|arg| arg.as_str().map(str::to_string))
+                .collect::<Option<Vec<_>>>()
+                .ok_or((INVALID_PARAMS, "`args` must be strings".to_string()))?,
+            _ => return Err((INVALID_PARAMS, "`args` must be an array".to_string())),
+        };
+        let timeout = match &params["timeout"] {
+            Value::Null => None,
+            timeout => match timeout.as_u64() {
+                Some(0) => None,
+                Some(secs) => Some(time::Duration::from_secs(secs)),
+                None => {
+                    return Err((
+                        INVALID_PARAMS,
+                        "`timeout` must be a number of seconds".to_string(),
+                    ))
+                }
+            },
+        };
+
+        let id = self.next_id;
+        let dir = self.work_dir.join(format!("job_{}", id));
+        fs::create_dir_all(&dir).map_err(|e| (JOB_ERROR, e.to_string()))?;
+        let log = File::create(dir.join("log.txt")).map_err(|e| (JOB_ERROR, e.to_string()))?;
+        let child = Command::new(&self.exe)
+            .arg(target)
+            .args(&args)
+            .arg("--path_to_summary")
+            .arg(dir.join("summary.json"))
+            .arg("--path_to_constraints")
+            .arg(dir.join("constraints.json"))
+            .env("CLICOLOR", "0")
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::from(log))
+            .spawn()
+            .map_err(|e| (JOB_ERROR, e.to_string()))?;
+        self.next_id += 1;
+        self.jobs.insert(
+            id,
+            Job {
+                target_path: target.to_string(),
+                child: Some(child),
+                status: JobStatus::Running,
+                start_time: time::Instant::now(),
+                execution_time_ms: 0,
+                timeout: timeout,
+                dir: dir,
+            },
+        );
+        Ok(json!({ "job_id": id }))
+    }
+
+    fn job(&mut self, params: &Value) -> Result<(usize, &mut Job), RpcError> {
+        let id = params["job_id"]
+            .as_u64()
+            .ok_or((INVALID_PARAMS, "`job_id` must be a number".to_string()))?
+            as usize;
+        let job = self
+            .jobs
+            .get_mut(&id)
+            .ok_or((JOB_ERROR, format!("unknown job: {}", id)))?;
+        job.poll();
+        Ok((id, job))
+    }
+
+    fn poll(&mut self) {
+        for job in self.jobs.values_mut() {
+            job.poll();
+        }
+    }
+
+    fn handle(&mut self, method: &str, params: &Value) -> Result<Value, RpcError> {
+        match method {
+            "submit" => self.submit(params),
+            "status" => {
+                let (id, job) = self.job(params)?;
+                Ok(job.to_json(id))
+            }
+            "list" => {
+                self.poll();
+                Ok(Value::Array(
+                    self.jobs.iter().map(|(id, job)| job.to_json(*id)).collect(),
+                ))
+            }
+            "constraints" => {
+                let (_, job) = self.job(params)?;
+                read_json(&job.constraints_path()).ok_or((
+                    JOB_ERROR,
+                    format!(
+                        "the constraints are not available (the job is {})",
+                        job.status.as_str()
+                    ),
+                ))
+            }
+            "findings" => {
+                let (_, job) = self.job(params)?;
+                let mut summary = match (job.status, read_json(&job.summary_path())) {
+                    (JobStatus::Done, Some(summary)) => summary,
+                    _ => {
+                        return Err((
+                            JOB_ERROR,
+                            format!(
+                                "the findings are not available (the job is {})",
+                                job.status.as_str()
+                            ),
+                        ))
+                    }
+                };
+                let counterexample = summary["counterexample_path"]
+                    .as_str()
+                    .filter(|path| !path.is_empty())
+                    .and_then(|path| read_json(Path::new(path)));
+                if let Some(counterexample) = counterexample {
+                    summary["counterexample"] = counterexample;
+                }
+                Ok(summary)
+            }
+            "cancel" => {
+                let (id, job) = self.job(params)?;
+                job.cancel();
+                Ok(job.to_json(id))
+            }
+            "shutdown" => {
+                self.is_shutdown_requested = true;
+                Ok(Value::Null)
+            }
+            _ => Err((METHOD_NOT_FOUND, format!("unknown method: {}", method))),
+        }
+    }
+}
+
+fn error_response(id: Value, code: i64, message: &str) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "error": { "code": code, "message": message },
+        "id": id,
+    })
+}
+
+fn handle_rpc(manager: &mut JobManager, body: &[u8]) -> Value {
+    let request: Value = match serde_json::from_slice(body) {
+        Ok(request) => request,
+        Err(e) => return error_response(Value::Null, PARSE_ERROR, &e.to_string()),
+    };
+    let id = request["id"].clone();
+    let method = match request["method"].as_str() {
+        Some(method) => method,
+        None => return error_response(id, INVALID_REQUEST, "`method` must be a string"),
+    };
+    match manager.handle(method, &request["params"]) {
+        Ok(result) => json!({ "jsonrpc": "2.0", "result": result, "id": id }),
+        Err((code, message)) => error_response(id, code, &message),
+    }
+}
+
+/// Reads the body of an HTTP request, whose length is given by its `Content-Length` header.
+fn read_request(stream: &TcpStream) -> Option<Vec<u8>> {
+    let mut reader = BufReader::new(stream);
+    let mut line = String::new();
+    let mut content_length = 0;
+    reader.read_line(&mut line).ok()?;
+    loop {
+        line.clear();
+        if reader.read_line(&mut line).ok()? == 0 {
+            return None;
+        }
+        let header = line.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.trim().eq_ignore_ascii_case("content-length") {
+                content_length = value.trim().parse().ok()?;
+            }
+        }
+    }
+    if content_length > MAX_REQUEST_SIZE {
+        return None;
+    }
+    let mut body = vec![0; content_length];
+    reader.read_exact(&mut body).ok()?;
+    Some(body)
+}
+
+fn write_response(mut stream: &TcpStream, status: &str, body: &Value) {
+    let body = body.to_string();
+    let _ = write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    );
+}
+
+/// Runs a JSON-RPC 2.0 server over HTTP on `--host`:`--port` until the `shutdown` method is
+/// called.
+///
+/// Each submitted circuit is analysed in a child process with the given command-line options, so
+/// that jobs run concurrently and can be cancelled. The methods are `submit`, `status`, `list`,
+/// `constraints`, `findings`, `cancel`, and `shutdown`.
+pub fn run_server(user_input: &Input) -> Result<(), ()> {
+    let exe = env::current_exe().map_err(|e| eoutln!("{} {}", "Cannot locate zkfuzz:".red(), e))?;
+    let address = format!("{}:{}", user_input.host(), user_input.port());
+    let listener = TcpListener::bind(&address)
+        .map_err(|e| eoutln!("{} {}", "Unable to listen on the address:".red(), e))?;
+    let mut manager = JobManager {
+        exe: exe,
+        work_dir: env::temp_dir().join(format!("zkfuzz_serve_{}", std::process::id())),
+        jobs: BTreeMap::new(),
+        next_id: 0,
+        is_shutdown_requested: false,
+    };
+
+    eoutln!(
+        "{} {}",
+        "🛰️ Serving JSON-RPC on:".green(),
+        format!("http://{}", address).cyan()
+    );
+    // The listener does not block so that the timeouts of the jobs are enforced between requests
+    listener
+        .set_nonblocking(true)
+        .map_err(|e| eoutln!("{} {}", "Unable to listen on the address:".red(), e))?;
+    while !manager.is_shutdown_requested {
+        let stream = match listener.accept() {
+            Ok((stream, _)) => stream,
+            Err(e) if e.kind() == io::ErrorKind::WouldBlock => {
+                manager.poll();
+                thread::sleep(time::Duration::from_millis(100));
+                continue;
+            }
+            Err(_) => continue,
+        };
+        let _ = stream.set_nonblocking(false);
+        let _ = stream.set_read_timeout(Some(time::Duration::from_secs(30)));
+        match read_request(&stream) {
+            Some(body) => write_response(&stream, "200 OK", &handle_rpc(&mut manager, &body)),
+            None => write_response(
+                &stream,
+                "400 Bad Request",
+                &error_response(Value::Null, INVALID_REQUEST, "malformed HTTP request"),
+            ),
+        }
+    }
+
+    for job in manager.jobs.values_mut() {
+        job.cancel();
+    }
+    let _ = fs::remove_dir_all(&manager.work_dir);
+    eoutln!("{}", "🛰️ Server stopped".green());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A manager whose jobs run `sh` on the submitted path in place of zkFuzz.
+    fn manager(name: &str) -> JobManager {
+        JobManager {
+            exe: PathBuf::from("sh"),
+            work_dir: env::temp_dir().join(format!(
+                "zkfuzz_serve_test_{}_{}",
+                name,
+                std::process::id()
+            )),
+            jobs: BTreeMap::new(),
+            next_id: 0,
+            is_shutdown_requested: false,
+        }
+    }
+
+    /// Writes a shell script, submitted in place of a circuit, to the work directory of
+    /// `manager`. The script is run with the options added by `submit`, so that `$2` is the path
+    /// to the summary and `$4` the path to the constraints.
+    fn script(manager: &JobManager, content: &str) -> String {
+        fs::create_dir_all(&manager.work_dir).unwrap();
+        let path = manager.work_dir.join("circuit.sh");
+        fs::write(&path, content).unwrap();
+        path.to_string_lossy().to_string()
+    }
+
+    fn call(manager: &mut JobManager, method: &str, params: Value) -> Value {
+        let request = json!({ "jsonrpc": "2.0", "method": method, "params": params, "id": 1 });
+        handle_rpc(manager, request.to_string().as_bytes())
+    }
+
+    /// Polls the status of the job until it is no longer running.
+    fn wait(manager: &mut JobManager, id: usize) -> Value {
+        let start = time::Instant::now();
+        loop {
+            let status = call(manager, "status", json!({ "job_id": id }))["result"].clone();
+            if status["status"] != "running" || start.elapsed() > time::Duration::from_secs(10) {
+                return status;
+            }
+            thread::sleep(time::Duration::from_millis(20));
+        }
+    }
+
+    #[test]
+    fn test_handle_rpc_errors() {
+        let mut manager = manager("errors");
+
+        let response = handle_rpc(&mut manager, b"{");
+        assert_eq!(response["error"]["code"], PARSE_ERROR);
+        assert_eq!(response["id"], Value::Null);
+
+        let response = handle_rpc(&mut manager, br#"{"jsonrpc": "2.0", "id": 7}"#);
+        assert_eq!(response["error"]["code"], INVALID_REQUEST);
+        assert_eq!(response["id"], 7);
+
+        let response = call(&mut manager, "analyse", json!({}));
+        assert_eq!(response["error"]["code"], METHOD_NOT_FOUND);
+        assert_eq!(response["id"], 1);
+
+        let response = call(&mut manager, "shutdown", Value::Null);
+        assert!(response.get("error").is_none());
+        assert!(manager.is_shutdown_requested);
+    }
+
+    #[test]
+    fn test_submit_validates_params() {
+        let mut manager = manager("submit");
+        let path = script(&manager, "exit 0\n");
+        for params in [
+            json!({}),
+            json!({ "path": 1 }),
+            json!({ "path": "./tests/sample/missing.circom" }),
+            json!({ "path": path, "args": "--search_mode" }),
+            json!({ "path": path, "args": [1] }),
+            json!({ "path": path, "timeout": "10" }),
+        ] {
+            let response = call(&mut manager, "submit", params.clone());
+            assert_eq!(response["error"]["code"], INVALID_PARAMS, "{}", params);
+        }
+        assert!(manager.jobs.is_empty());
+        let _ = fs::remove_dir_all(&manager.work_dir);
+    }
+
+    #[test]
+    fn test_job_status() {
+        let mut manager = manager("status");
+        let path = script(
+            &manager,
+            "echo 'Scanning TCCT Instances...' >&2\n\
+             echo '{\"verdict\": \"NoCounterExample\", \"counterexample_path\": \"\"}' > \"$2\"\n\
+             echo '{\"trace_constraints\": [], \"side_constraints\": []}' > \"$4\"\n",
+        );
+
+        let response = call(
+            &mut manager,
+            "submit",
+            json!({ "path": path, "args": ["--search_mode", "quick"] }),
+        );
+        assert_eq!(response["result"]["job_id"], 0);
+        let status = wait(&mut manager, 0);
+        assert_eq!(status["status"], "done");
+        assert_eq!(status["target_path"], path.as_str());
+        assert_eq!(status["progress"], "Scanning TCCT Instances...");
+
+        let response = call(&mut manager, "findings", json!({ "job_id": 0 }));
+        assert_eq!(response["result"]["verdict"], "NoCounterExample");
+        let response = call(&mut manager, "constraints", json!({ "job_id": 0 }));
+        assert_eq!(response["result"]["side_constraints"], json!([]));
+        let response = call(&mut manager, "list", Value::Null);
+        assert_eq!(response["result"].as_array().unwrap().len(), 1);
+
+        let response = call(&mut manager, "status", json!({ "job_id": 1 }));
+        assert_eq!(response["error"]["code"], JOB_ERROR);
+        let response = call(&mut manager, "status", json!({ "job_id": "0" }));
+        assert_eq!(response["error"]["code"], INVALID_PARAMS);
+        let _ = fs::remove_dir_all(&manager.work_dir);
+    }
+
+    #[test]
+    fn test_failed_job_has_no_findings() {
+        let mut manager = manager("failed");
+        let path = script(&manager, "exit 1\n");
+        call(&mut manager, "submit", json!({ "path": path }));
+        assert_eq!(wait(&mut manager, 0)["status"], "failed");
+        let response = call(&mut manager, "findings", json!({ "job_id": 0 }));
+        assert_eq!(response["error"]["code"], JOB_ERROR);
+        let _ = fs::remove_dir_all(&manager.work_dir);
+    }
+
+    #[test]
+    fn test_cancel_and_timeout() {
+        let mut manager = manager("cancel");
+        let path = script(&manager, "exec sleep 30\n");
+
+        call(&mut manager, "submit", json!({ "path": path }));
+        let response = call(&mut manager, "cancel", json!({ "job_id": 0 }));
+        assert_eq!(response["result"]["status"], "cancelled");
+        assert_eq!(wait(&mut manager, 0)["status"], "cancelled");
+
+        call(
+            &mut manager,
+            "submit",
+            json!({ "path": path, "timeout": 1 }),
+        );
+        thread::sleep(time::Duration::from_millis(1100));
+        assert_eq!(wait(&mut manager, 1)["status"], "timeout");
+        let _ = fs::remove_dir_all(&manager.work_dir);
+    }
+}
@@ -1,4 +1,7 @@
-use program_structure::abstract_syntax_tree::ast::{AssignOp, Statement};
+use std::fmt::Write;
+
+use program_structure::abstract_syntax_tree::ast::{AssignOp, Expression, Statement, VariableType};
+use rustc_hash::FxHashSet;
 
 #[derive(Default)]
 pub struct ASTStats {
@@ -99,3 +102,239 @@ impl ASTStats {
         .to_string()
     }
 }
+
+/// The control-flow graph of a template, whose nodes are basic blocks of statements and the
+/// headers of its branches and loops.
+pub struct ControlFlowGraph {
+    /// The labels of the nodes, i.e., the statements of each block.
+    pub nodes: Vec<String>,
+    /// The edges between the nodes, labelled with `true` or `false` out of a branch or a loop.
+    pub edges: Vec<(usize, usize, &'static str)>,
+    pub max_loop_depth: usize,
+    /// The number of statements instantiating a component. A statement in a loop is counted once.
+    pub num_component_instantiations: usize,
+}
+
+impl ControlFlowGraph {
+    /// Builds the control-flow graph of the body of a template.
+    pub fn build(body: &Statement) -> Self {
+        let mut cfg = ControlFlowGraph {
+            nodes: vec!["entry".to_string()],
+            edges: Vec::new(),
+            max_loop_depth: 0,
+            num_component_instantiations: 0,
+        };
+        let mut components = FxHashSet::default();
+        let last = cfg.visit(body, 0, 0, &mut components);
+        let exit = cfg.add_node("exit");
+        cfg.add_edge(last, exit, "");
+        cfg
+    }
+
+    fn add_node(&mut self, label: &str) -> usize {
+        self.nodes.push(label.to_string());
+        self.nodes.len() - 1
+    }
+
+    fn add_edge(&mut self, from: usize, to: usize, label: &'static str) {
+        self.edges.push((from, to, label));
+    }
+
+    /// Appends `statement` to the block `current`, and returns the block where the control
+    /// continues after it.
+    fn visit(
+        &mut self,
+        statement: &Statement,
+        current: usize,
+        loop_depth: usize,
+        components: &mut FxHashSet<String>,
+    ) -> usize {
+        match statement {
+            Statement::Block { stmts, .. } => stmts.iter().fold(current, |block, stmt| {
+                self.visit(stmt, block, loop_depth, components)
+            }),
+            Statement::InitializationBlock {
+                initializations, ..
+            } => initializations.iter().fold(current, |block, stmt| {
+                self.visit(stmt, block, loop_depth, components)
+            }),
+            Statement::IfThenElse {
+                if_case, else_case, ..
+            } => {
+                let branch = self.add_node("if");
+                self.add_edge(current, branch, "");
+                let then_block = self.add_node("then");
+                self.add_edge(branch, then_block, "true");
+                let then_last = self.visit(if_case, then_block, loop_depth, components);
+                let join = self.add_node("end if");
+                self.add_edge(then_last, join, "");
+                match else_case {
+                    Some(else_case) => {
+                        let else_block = self.add_node("else");
+                        self.add_edge(branch, else_block, "false");
+                        let else_last = self.visit(else_case, else_block, loop_depth, components);
+                        self.add_edge(else_last, join, "");
+                    }
+                    None => self.add_edge(branch, join, "false"),
+                }
+                join
+            }
+            Statement::While { stmt, .. } => {
+                self.max_loop_depth = std::cmp::max(self.max_loop_depth, loop_depth + 1);
+                let header = self.add_node("while");
+                self.add_edge(current, header, "");
+                let body = self.add_node("loop body");
+                self.add_edge(header, body, "true");
+                let body_last = self.visit(stmt, body, loop_depth + 1, components);
+                self.add_edge(body_last, header, "");
+                let exit = self.add_node("end while");
+                self.add_edge(header, exit, "false");
+                exit
+            }
+            _ => {
+                self.count_component_instantiations(statement, components);
+                let label = describe_statement(statement);
+                self.nodes[current] += &format!("\n{}", label);
+                current
+            }
+        }
+    }
+
+    fn count_component_instantiations(
+        &mut self,
+        statement: &Statement,
+        components: &mut FxHashSet<String>,
+    ) {
+        match statement {
+            Statement::Declaration {
+                xtype: VariableType::Component | VariableType::AnonymousComponent,
+                name,
+                ..
+            } => {
+                components.insert(name.clone());
+            }
+            Statement::Substitution { var, op, rhe, .. } => {
+                if matches!(op, AssignOp::AssignVar)
+                    && components.contains(var)
+                    && matches!(rhe, Expression::Call { .. })
+                {
+                    self.num_component_instantiations += 1;
+                }
+                self.num_component_instantiations += count_anonymous_components(rhe);
+            }
+            Statement::MultSubstitution { rhe, .. }
+            | Statement::UnderscoreSubstitution { rhe, .. } => {
+                self.num_component_instantiations += count_anonymous_components(rhe);
+            }
+            Statement::ConstraintEquality { lhe, rhe, .. } => {
+                self.num_component_instantiations +=
+                    count_anonymous_components(lhe) + count_anonymous_components(rhe);
+            }
+            _ => {}
+        }
+    }
+
+    /// The cyclomatic complexity `E - N + 2`, i.e., one more than the number of branches and
+    /// loops.
+    pub fn cyclomatic_complexity(&self) -> usize {
+        self.edges.len() + 2 - self.nodes.len()
+    }
+
+    pub fn get_csv(&self) -> String {
+        format!(
+            "{},{},{}",
+            self.cyclomatic_complexity(),
+            self.max_loop_depth,
+            self.num_component_instantiations
+        )
+    }
+
+    /// Renders the graph in the DOT format of Graphviz.
+    pub fn to_dot(&self, template_name: &str) -> String {
+        let mut dot = format!("digraph \"{}\" {{\n    node [shape=box];\n", template_name);
+        for (i, label) in self.nodes.iter().enumerate() {
+            writeln!(
+                dot,
+                "    n{} [label=\"{}\"];",
+                i,
+                label.replace('"', "\\\"").replace('\n', "\\n")
+            )
+            .unwrap();
+        }
+        for (from, to, label) in &self.edges {
+            if label.is_empty() {
+                writeln!(dot, "    n{} -> n{};", from, to).unwrap();
+            } else {
+                writeln!(dot, "    n{} -> n{} [label=\"{}\"];", from, to, label).unwrap();
+            }
+        }
+        dot += "}\n";
+        dot
+    }
+}
+
+/// Returns a one-line summary of a statement other than a block, a branch, or a loop.
+fn describe_statement(statement: &Statement) -> String {
+    match statement {
+        Statement::Declaration { xtype, name, .. } => {
+            let kind = match xtype {
+                VariableType::Var => "var",
+                VariableType::Signal(..) => "signal",
+                VariableType::Component | VariableType::AnonymousComponent => "component",
+                VariableType::Bus(..) => "bus",
+            };
+            format!("{} {}", kind, name)
+        }
+        Statement::Substitution { var, op, .. } => format!("{} {}", var, describe_assign_op(op)),
+        Statement::MultSubstitution { op, .. } => format!("(...) {}", describe_assign_op(op)),
+        Statement::UnderscoreSubstitution { op, .. } => format!("_ {}", describe_assign_op(op)),
+        Statement::ConstraintEquality { .. } => "===".to_string(),
+        Statement::LogCall { .. } => "log".to_string(),
+        Statement::Assert { .. } => "assert".to_string(),
+        Statement::Return { .. } => "return".to_string(),
+        _ => String::new(),
+    }
+}
+
+fn describe_assign_op(op: &AssignOp) -> &'static str {
+    match op {
+        AssignOp::AssignVar => "=",
+        AssignOp::AssignSignal => "<--",
+        AssignOp::AssignConstraintSignal => "<==",
+    }
+}
+
+fn count_anonymous_components(expression: &Expression) -> usize {
+    match expression {
+        Expression::AnonymousComp {
+            params, signals, ..
+        } => {
+            1 + params
+                .iter()
+                .chain(signals.iter())
+                .map(count_anonymous_components)
+                .sum::<usize>()
+        }
+        Expression::InfixOp { lhe, rhe, .. } => {
+            count_anonymous_components(lhe) + count_anonymous_components(rhe)
+        }
+        Expression::PrefixOp { rhe, .. } | Expression::ParallelOp { rhe, .. } => {
+            count_anonymous_components(rhe)
+        }
+        Expression::InlineSwitchOp {
+            cond,
+            if_true,
+            if_false,
+            ..
+        } => {
+            count_anonymous_components(cond)
+                + count_anonymous_components(if_true)
+                + count_anonymous_components(if_false)
+        }
+        Expression::Call { args, .. } => args.iter().map(count_anonymous_components).sum(),
+        Expression::ArrayInLine { values, .. } | Expression::Tuple { values, .. } => {
+            values.iter().map(count_anonymous_components).sum()
+        }
+        _ => 0,
+    }
+}
@@ -0,0 +1,340 @@
+use std::collections::BTreeMap;
+
+use program_structure::ast::{ExpressionInfixOpcode, ExpressionPrefixOpcode};
+use rustc_hash::FxHashMap;
+
+use crate::executor::structural_hash::{
+    flatten_operands, is_associative_commutative, structural_hash, Equivalence,
+};
+use crate::executor::symbolic_execution::SymbolicExecutor;
+use crate::executor::symbolic_value::{OwnerName, SymbolicValue, SymbolicValueRef};
+use crate::stats::symbolic_stats::first_signal_owner;
+
+/// The constraints of one kind (trace or side) that differ between two versions of a template.
+#[derive(Default)]
+pub struct ConstraintDiff {
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+    /// Pairs of an old and a new constraint on the same signal.
+    pub changed: Vec<(String, String)>,
+}
+
+impl ConstraintDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+/// The differences of the trace and side constraints owned by the instances of a template.
+pub struct TemplateDiff {
+    pub template_name: String,
+    pub trace: ConstraintDiff,
+    pub side: ConstraintDiff,
+}
+
+/// A constraint with its canonical form, aligned across versions by its structural hash.
+struct CanonicalConstraint {
+    hash: u64,
+    text: String,
+    /// The signal constrained by the constraint, if its left-hand side is a single signal.
+    signal: Option<String>,
+}
+
+pub(crate) fn operator_symbol(op: &ExpressionInfixOpcode) -> &'static str {
+    match op {
+        ExpressionInfixOpcode::Mul => "*",
+        ExpressionInfixOpcode::Div => "/",
+        ExpressionInfixOpcode::Add => "+",
+        ExpressionInfixOpcode::Sub => "-",
+        ExpressionInfixOpcode::Pow => "**",
+        ExpressionInfixOpcode::IntDiv => "\\",
+        ExpressionInfixOpcode::Mod => "%",
+        ExpressionInfixOpcode::ShiftL => "<<",
+        ExpressionInfixOpcode::ShiftR => ">>",
+        ExpressionInfixOpcode::LesserEq => "<=",
+        ExpressionInfixOpcode::GreaterEq => ">=",
+        ExpressionInfixOpcode::Lesser => "<",
+        ExpressionInfixOpcode::Greater => ">",
+        ExpressionInfixOpcode::Eq => "==",
+        ExpressionInfixOpcode::NotEq => "!=",
+        ExpressionInfixOpcode::BoolOr => "||",
+        ExpressionInfixOpcode::BoolAnd => "&&",
+        ExpressionInfixOpcode::BitOr => "|",
+        ExpressionInfixOpcode::BitAnd => "&",
+        ExpressionInfixOpcode::BitXor => "^",
+    }
+}
+
+/// Formats a symbolic value in a canonical infix form, where the operands of commutative
+/// operators are sorted and the chains of associative operators are flattened, so that
+/// refactorings such as `b * a + c` to `c + a * b` do not change the form.
+pub fn canonical_fmt(value: &SymbolicValue, id2name: &FxHashMap<usize, String>) -> String {
+    match value {
+        SymbolicValue::NOP => "nop".to_string(),
+        SymbolicValue::ConstantInt(v) => v.to_string(),
+        SymbolicValue::ConstantBool(b) => b.to_string(),
+        SymbolicValue::Variable(sym_name) => sym_name.lookup_fmt(id2name),
+        SymbolicValue::Assign(lhs, rhs, ..) => format!(
+            "{} <-- {}",
+            canonical_fmt(lhs, id2name),
+            canonical_fmt(rhs, id2name)
+        ),
+        SymbolicValue::AssignEq(lhs, rhs) => format!(
+            "{} <== {}",
+            canonical_fmt(lhs, id2name),
+            canonical_fmt(rhs, id2name)
+        ),
+        SymbolicValue::AssignTemplParam(lhs, rhs) | SymbolicValue::AssignCall(lhs, rhs, _) => {
+            format!(
+                "{} = {}",
+                canonical_fmt(lhs, id2name),
+                canonical_fmt(rhs, id2name)
+            )
+        }
+        SymbolicValue::BinaryOp(lhs, op, rhs) | SymbolicValue::AuxBinaryOp(lhs, op, rhs) => {
+            let mut operands = if is_associative_commutative(&op.0) {
+                let mut operands = Vec::new();
+                flatten_operands(value, &op.0, &mut operands);
+                operands
+                    .into_iter()
+                    .map(|operand| canonical_fmt(operand, id2name))
+                    .collect::<Vec<_>>()
+            } else {
+                vec![canonical_fmt(lhs, id2name), canonical_fmt(rhs, id2name)]
+            };
+            if matches!(
+                op.0,
+                ExpressionInfixOpcode::Eq | ExpressionInfixOpcode::NotEq
+            ) || is_associative_commutative(&op.0)
+            {
+                operands.sort();
+            }
+            format!(
+                "({})",
+                operands.join(&format!(" {} ", operator_symbol(&op.0)))
+            )
+        }
+        SymbolicValue::Conditional(cond, if_true, if_false) => format!(
+            "({} ? {} : {})",
+            canonical_fmt(cond, id2name),
+            canonical_fmt(if_true, id2name),
+            canonical_fmt(if_false, id2name)
+        ),
+        SymbolicValue::UnaryOp(op, expr) => {
+            let symbol = match op.0 {
+                ExpressionPrefixOpcode::Sub => "-",
+                ExpressionPrefixOpcode::BoolNot => "!",
+                ExpressionPrefixOpcode::Complement => "~",
+            };
+            format!("{}{}", symbol, canonical_fmt(expr, id2name))
+        }
+        SymbolicValue::Array(elems) => format!(
+            "[{}]",
+            elems
+                .iter()
+                .map(|e| canonical_fmt(e, id2name))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        SymbolicValue::UniformArray(elem, count) => format!(
+            "[{}; {}]",
+            canonical_fmt(elem, id2name),
+            canonical_fmt(count, id2name)
+        ),
+        SymbolicValue::Call(id, args) => format!(
+            "{}({})",
+            id2name[id],
+            args.iter()
+                .map(|a| canonical_fmt(a, id2name))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+    }
+}
+
+fn canonicalize(
+    constraint: &SymbolicValue,
+    id2name: &FxHashMap<usize, String>,
+) -> CanonicalConstraint {
+    let (text, lhs) = match constraint {
+        // A top-level `==` is a `===` constraint
+        SymbolicValue::BinaryOp(lhs, op, rhs) if op.0 == ExpressionInfixOpcode::Eq => {
+            let mut operands = vec![canonical_fmt(lhs, id2name), canonical_fmt(rhs, id2name)];
+            operands.sort();
+            (operands.join(" === "), Some(lhs))
+        }
+        SymbolicValue::Assign(lhs, ..)
+        | SymbolicValue::AssignEq(lhs, _)
+        | SymbolicValue::AssignCall(lhs, ..)
+        | SymbolicValue::AssignTemplParam(lhs, _) => {
+            (canonical_fmt(constraint, id2name), Some(lhs))
+        }
+        _ => (canonical_fmt(constraint, id2name), None),
+    };
+    let signal = match lhs.map(|lhs| lhs.as_ref()) {
+        Some(SymbolicValue::Variable(sym_name)) => Some(sym_name.lookup_fmt(id2name)),
+        _ => None,
+    };
+    // Like the canonical form, the hash ignores the counters of the owners and the order of the
+    // operands
+    let equivalence = Equivalence {
+        ignore_owner_counters: true,
+        ignore_operand_order: true,
+        ignore_owners: false,
+        ignore_array_indices: false,
+    };
+    CanonicalConstraint {
+        hash: structural_hash(constraint, id2name, equivalence),
+        text: text,
+        signal: signal,
+    }
+}
+
+/// Returns the name of the template owning `owner`, i.e., of its innermost component.
+fn template_name(sexe: &SymbolicExecutor, owner: &[OwnerName], main_template_name: &str) -> String {
+    for len in (1..=owner.len()).rev() {
+        if let Some(id) = sexe.component_templates.get(&owner[..len].to_vec()) {
+            return sexe.symbolic_library.id2name[id].clone();
+        }
+    }
+    if owner.is_empty() {
+        "(none)".to_string()
+    } else {
+        main_template_name.to_string()
+    }
+}
+
+/// Groups the canonical forms of `constraints` by the template owning them (see
+/// `ComponentStatistics`).
+fn group_by_template(
+    sexe: &SymbolicExecutor,
+    constraints: &[SymbolicValueRef],
+    main_template_name: &str,
+) -> BTreeMap<String, Vec<CanonicalConstraint>> {
+    let id2name = &sexe.symbolic_library.id2name;
+    let mut groups: BTreeMap<String, Vec<CanonicalConstraint>> = BTreeMap::new();
+    for constraint in constraints {
+        let owner = first_signal_owner(constraint)
+            .map(|owner| owner.as_slice())
+            .unwrap_or(&[]);
+        groups
+            .entry(template_name(sexe, owner, main_template_name))
+            .or_default()
+            .push(canonicalize(constraint, id2name));
+    }
+    groups
+}
+
+/// Returns the constraints of `constraints` that are not matched by `others`, counting
+/// duplicates, in the order of `constraints`.
+fn unmatched<'a>(
+    constraints: &'a [CanonicalConstraint],
+    others: &[CanonicalConstraint],
+) -> Vec<&'a CanonicalConstraint> {
+    let mut counts: FxHashMap<u64, usize> = FxHashMap::default();
+    for other in others {
+        *counts.entry(other.hash).or_insert(0) += 1;
+    }
+    constraints
+        .iter()
+        .filter(|c| match counts.get_mut(&c.hash) {
+            Some(count) if *count > 0 => {
+                *count -= 1;
+                false
+            }
+            _ => true,
+        })
+        .collect()
+}
+
+fn diff_group(old: &[CanonicalConstraint], new: &[CanonicalConstraint]) -> ConstraintDiff {
+    let removed = unmatched(old, new);
+    let mut added: Vec<Option<&CanonicalConstraint>> =
+        unmatched(new, old).into_iter().map(Some).collect();
+
+    let mut diff = ConstraintDiff::default();
+    for old_constraint in removed {
+        let counterpart = match &old_constraint.signal {
+            Some(signal) => added.iter().position(|new_constraint| {
+                new_constraint.map_or(false, |c| c.signal.as_ref() == Some(signal))
+            }),
+            None => None,
+        };
+        match counterpart.and_then(|i| added[i].take()) {
+            Some(new_constraint) => diff
+                .changed
+                .push((old_constraint.text.clone(), new_constraint.text.clone())),
+            None => diff.removed.push(old_constraint.text.clone()),
+        }
+    }
+    diff.added = added
+        .into_iter()
+        .flatten()
+        .map(|new_constraint| new_constraint.text.clone())
+        .collect();
+    diff
+}
+
+/// Compares the trace and side constraints of two versions of a circuit, template by template.
+///
+/// The constraints are aligned by their structural hash (see `structural_hash`), which ignores
+/// the order of the operands as `canonical_fmt` does and includes the names of their signals, so a constraint is unchanged only if it constrains the
+/// same signals of the same components in the same way. A removed and an added constraint on the
+/// same signal are reported as a change.
+///
+/// # Parameters
+/// - `old`: The symbolic executor after the execution of the main template of the old version.
+/// - `old_main_template_name`: The name of the main template of the old version.
+/// - `new`: The symbolic executor after the execution of the main template of the new version.
+/// - `new_main_template_name`: The name of the main template of the new version.
+///
+/// # Returns
+/// The differences of the templates whose constraints differ, sorted by the template names.
+pub fn diff_constraints(
+    old: &SymbolicExecutor,
+    old_main_template_name: &str,
+    new: &SymbolicExecutor,
+    new_main_template_name: &str,
+) -> Vec<TemplateDiff> {
+    let mut old_trace =
+        group_by_template(old, &old.cur_state.symbolic_trace, old_main_template_name);
+    let mut old_side =
+        group_by_template(old, &old.cur_state.side_constraints, old_main_template_name);
+    let mut new_trace =
+        group_by_template(new, &new.cur_state.symbolic_trace, new_main_template_name);
+    let mut new_side =
+        group_by_template(new, &new.cur_state.side_constraints, new_main_template_name);
+
+    let mut template_names: Vec<String> = old_trace
+        .keys()
+        .chain(old_side.keys())
+        .chain(new_trace.keys())
+        .chain(new_side.keys())
+        .cloned()
+        .collect();
+    template_names.sort();
+    template_names.dedup();
+
+    template_names
+        .into_iter()
+        .filter_map(|template_name| {
+            let trace = diff_group(
+                &old_trace.remove(&template_name).unwrap_or_default(),
+                &new_trace.remove(&template_name).unwrap_or_default(),
+            );
+            let side = diff_group(
+                &old_side.remove(&template_name).unwrap_or_default(),
+                &new_side.remove(&template_name).unwrap_or_default(),
+            );
+            if trace.is_empty() && side.is_empty() {
+                None
+            } else {
+                Some(TemplateDiff {
+                    template_name: template_name,
+                    trace: trace,
+                    side: side,
+                })
+            }
+        })
+        .collect()
+}
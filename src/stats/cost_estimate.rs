@@ -0,0 +1,517 @@
+use std::hash::{Hash, Hasher};
+use std::time;
+
+use num_traits::{ToPrimitive, Zero};
+use rustc_hash::{FxHashSet, FxHasher};
+use serde_json::{json, Value};
+
+use program_structure::ast::{ExpressionInfixOpcode, ExpressionPrefixOpcode};
+
+use crate::executor::constraint_store::ConstraintStore;
+use crate::executor::symbolic_value::{SymbolicName, SymbolicValue};
+
+/// Nanoseconds per point of a multi-scalar multiplication on G1 of BN254 (single-threaded).
+pub const G1_MSM_NS_PER_POINT: f64 = 3500.0;
+/// Nanoseconds per point of a multi-scalar multiplication on G2 of BN254 (single-threaded).
+pub const G2_MSM_NS_PER_POINT: f64 = 10000.0;
+/// Nanoseconds per element and per layer of a number-theoretic transform.
+pub const FFT_NS_PER_BUTTERFLY: f64 = 15.0;
+/// Bytes of an affine point on G1 of BN254.
+const G1_POINT_BYTES: usize = 64;
+/// Bytes of an affine point on G2 of BN254.
+const G2_POINT_BYTES: usize = 128;
+/// Bytes of a field element of BN254.
+const FIELD_ELEMENT_BYTES: usize = 32;
+
+/// A linear combination of signals, optionally with a constant term.
+#[derive(Clone, Default)]
+struct LinearCombination {
+    signals: FxHashSet<SymbolicName>,
+    has_constant: bool,
+}
+
+impl LinearCombination {
+    /// Returns the number of non-zero coefficients.
+    fn len(&self) -> usize {
+        self.signals.len() + self.has_constant as usize
+    }
+
+    fn is_zero(&self) -> bool {
+        self.signals.is_empty() && !self.has_constant
+    }
+
+    fn union(mut self, other: LinearCombination) -> LinearCombination {
+        self.signals.extend(other.signals);
+        self.has_constant |= other.has_constant;
+        self
+    }
+}
+
+/// The shape of an expression in the algebra of the constraint generation of circom, where a
+/// constraint has to be of the form `a * b + c` with linear `a`, `b`, and `c`.
+#[derive(Clone)]
+enum Shape {
+    Linear(LinearCombination),
+    Quadratic(LinearCombination, LinearCombination, LinearCombination),
+    /// An expression that has to be split with intermediate signals, one per multiplication.
+    NonQuadratic {
+        multiplications: usize,
+        signals: FxHashSet<SymbolicName>,
+    },
+}
+
+impl Shape {
+    fn constant(is_nonzero: bool) -> Shape {
+        Shape::Linear(LinearCombination {
+            signals: FxHashSet::default(),
+            has_constant: is_nonzero,
+        })
+    }
+
+    fn is_constant(&self) -> bool {
+        matches!(self, Shape::Linear(l) if l.signals.is_empty())
+    }
+
+    fn multiplications(&self) -> usize {
+        match self {
+            Shape::Linear(_) => 0,
+            Shape::Quadratic(..) => 1,
+            Shape::NonQuadratic {
+                multiplications, ..
+            } => *multiplications,
+        }
+    }
+
+    fn into_signals(self) -> FxHashSet<SymbolicName> {
+        match self {
+            Shape::Linear(l) => l.signals,
+            Shape::Quadratic(a, b, c) => a.union(b).union(c).signals,
+            Shape::NonQuadratic { signals, .. } => signals,
+        }
+    }
+
+    /// Combines `shapes` into a non-quadratic shape with `extra` more multiplications.
+    fn non_quadratic(shapes: Vec<Shape>, extra: usize) -> Shape {
+        let mut multiplications = extra;
+        let mut signals = FxHashSet::default();
+        for shape in shapes {
+            multiplications += shape.multiplications();
+            signals.extend(shape.into_signals());
+        }
+        Shape::NonQuadratic {
+            multiplications,
+            signals,
+        }
+    }
+
+    fn add(self, other: Shape) -> Shape {
+        match (self, other) {
+            (Shape::Linear(x), Shape::Linear(y)) => Shape::Linear(x.union(y)),
+            (Shape::Quadratic(a, b, c), Shape::Linear(y))
+            | (Shape::Linear(y), Shape::Quadratic(a, b, c)) => Shape::Quadratic(a, b, c.union(y)),
+            (x, y) => Shape::non_quadratic(vec![x, y], 0),
+        }
+    }
+
+    fn mul(self, other: Shape) -> Shape {
+        match (self, other) {
+            (Shape::Linear(x), _) | (_, Shape::Linear(x)) if x.is_zero() => Shape::constant(false),
+            (Shape::Linear(x), y) | (y, Shape::Linear(x)) if x.signals.is_empty() => y,
+            (Shape::Linear(x), Shape::Linear(y)) => {
+                Shape::Quadratic(x, y, LinearCombination::default())
+            }
+            (x, y) => Shape::non_quadratic(vec![x, y], 1),
+        }
+    }
+}
+
+/// Returns the shape of `value` as a polynomial over the signals.
+///
+/// Operations without an arithmetic counterpart (e.g. comparisons, bitwise operations, or
+/// conditionals on signals) are counted as one multiplication each, which is the least they cost
+/// once rewritten with auxiliary signals.
+fn shape_of(value: &SymbolicValue) -> Shape {
+    match value {
+        SymbolicValue::ConstantInt(v) => Shape::constant(!v.is_zero()),
+        SymbolicValue::ConstantBool(b) => Shape::constant(*b),
+        SymbolicValue::NOP => Shape::constant(false),
+        SymbolicValue::Variable(sym_name) => {
+            let mut signals = FxHashSet::default();
+            signals.insert(sym_name.clone());
+            Shape::Linear(LinearCombination {
+                signals,
+                has_constant: false,
+            })
+        }
+        SymbolicValue::Assign(lhs, rhs, ..)
+        | SymbolicValue::AssignEq(lhs, rhs)
+        | SymbolicValue::AssignTemplParam(lhs, rhs)
+        | SymbolicValue::AssignCall(lhs, rhs, _) => shape_of(lhs).add(shape_of(rhs)),
+        SymbolicValue::BinaryOp(lhs, op, rhs) | SymbolicValue::AuxBinaryOp(lhs, op, rhs) => {
+            let (l, r) = (shape_of(lhs), shape_of(rhs));
+            if l.is_constant() && r.is_constant() {
+                return Shape::constant(true);
+            }
+            match op.0 {
+                ExpressionInfixOpcode::Add | ExpressionInfixOpcode::Sub => l.add(r),
+                ExpressionInfixOpcode::Mul => l.mul(r),
+                ExpressionInfixOpcode::Div if r.is_constant() => l,
+                ExpressionInfixOpcode::Pow => match &**rhs {
+                    SymbolicValue::ConstantInt(e) => match (e.to_usize(), l) {
+                        (Some(0), _) => Shape::constant(true),
+                        (Some(1), l) => l,
+                        (Some(2), Shape::Linear(base)) => {
+                            Shape::Quadratic(base.clone(), base, LinearCombination::default())
+                        }
+                        // Square-and-multiply
+                        (Some(e), l) => Shape::non_quadratic(
+                            vec![l],
+                            (usize::BITS - e.leading_zeros() + e.count_ones()) as usize - 2,
+                        ),
+                        (None, l) => Shape::non_quadratic(vec![l], 1),
+                    },
+                    _ => Shape::non_quadratic(vec![l, r], 1),
+                },
+                _ => Shape::non_quadratic(vec![l, r], 1),
+            }
+        }
+        SymbolicValue::UnaryOp(op, expr) => {
+            let shape = shape_of(expr);
+            match op.0 {
+                ExpressionPrefixOpcode::Sub => shape,
+                _ if shape.is_constant() => Shape::constant(true),
+                _ => Shape::non_quadratic(vec![shape], 1),
+            }
+        }
+        SymbolicValue::Conditional(cond, if_true, if_false) => {
+            let cond = shape_of(cond);
+            let branches = vec![shape_of(if_true), shape_of(if_false)];
+            if cond.is_constant() {
+                // Only one branch is taken, so the more expensive one is an upper bound
+                branches
+                    .into_iter()
+                    .max_by_key(|b| (b.multiplications(), b.clone().into_signals().len()))
+                    .unwrap()
+            } else {
+                let mut shapes = branches;
+                shapes.push(cond);
+                Shape::non_quadratic(shapes, 1)
+            }
+        }
+        SymbolicValue::Array(elements) | SymbolicValue::Call(_, elements) => {
+            let shapes: Vec<_> = elements.iter().map(|e| shape_of(e)).collect();
+            if shapes.iter().all(|s| s.is_constant()) {
+                Shape::constant(true)
+            } else {
+                Shape::non_quadratic(shapes, 1)
+            }
+        }
+        SymbolicValue::UniformArray(elem, _) => shape_of(elem),
+    }
+}
+
+/// Returns the shape of a side constraint, i.e. of `lhs - rhs` for `lhs === rhs` and
+/// `lhs <== rhs`.
+fn constraint_shape(constraint: &SymbolicValue) -> Shape {
+    match constraint {
+        SymbolicValue::BinaryOp(lhs, op, rhs) if matches!(op.0, ExpressionInfixOpcode::Eq) => {
+            shape_of(lhs).add(shape_of(rhs))
+        }
+        // A constraint guarded by a condition on signals (see `split_conditional_constraint`)
+        // is multiplied by the selector of its branch, and the other branch is `true`
+        SymbolicValue::Conditional(cond, if_true, if_false) => {
+            let mut shapes = vec![shape_of(cond)];
+            shapes.extend(
+                [if_true, if_false]
+                    .into_iter()
+                    .map(|c| constraint_shape(c))
+                    .filter(|s| !s.is_constant()),
+            );
+            Shape::non_quadratic(shapes, 1)
+        }
+        _ => shape_of(constraint),
+    }
+}
+
+/// Returns the number of gates of width `width` summing `num_wires` wires to zero.
+fn linear_gates(num_wires: usize, width: usize) -> usize {
+    if num_wires == 0 {
+        0
+    } else if num_wires <= width {
+        1
+    } else {
+        1 + (num_wires - width + width - 2) / (width - 1)
+    }
+}
+
+/// Returns the number of gates of width `width` that a constraint of the given shape needs in a
+/// Plonk arithmetization, where a gate multiplies two of its wires and adds the others.
+fn plonk_gates(shape: &Shape, width: usize) -> usize {
+    // A linear factor of a product is first summed into a wire of its own
+    let factor_gates = |l: &LinearCombination| {
+        if l.len() > 1 {
+            linear_gates(l.signals.len() + 1, width)
+        } else {
+            0
+        }
+    };
+    match shape {
+        Shape::Linear(l) => linear_gates(l.signals.len(), width),
+        Shape::Quadratic(a, b, c) => {
+            factor_gates(a) + factor_gates(b) + linear_gates(2 + c.signals.len(), width)
+        }
+        Shape::NonQuadratic {
+            multiplications,
+            signals,
+        } => multiplications + linear_gates(signals.len(), width),
+    }
+}
+
+/// The estimated cost of generating a proof with a backend.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ProverEstimate {
+    /// The size of the evaluation domain, a power of two.
+    pub domain_size: usize,
+    pub proving_time: time::Duration,
+    /// The size of the proving key (or the SRS) and of the buffers of the prover.
+    pub memory_bytes: usize,
+}
+
+impl ProverEstimate {
+    fn new(domain_size: usize, proving_time_ns: f64, memory_bytes: usize) -> ProverEstimate {
+        ProverEstimate {
+            domain_size,
+            proving_time: time::Duration::from_nanos(proving_time_ns as u64),
+            memory_bytes,
+        }
+    }
+
+    fn to_json(&self) -> Value {
+        json!({
+            "domain_size": self.domain_size,
+            "proving_time_ms": self.proving_time.as_millis() as u64,
+            "memory_bytes": self.memory_bytes,
+        })
+    }
+}
+
+/// The estimated cost of the circuit in a Plonk arithmetization with gates of a given width.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PlonkEstimate {
+    pub width: usize,
+    pub gates: usize,
+    pub prover: ProverEstimate,
+}
+
+/// The estimated size of the constraint system compiled from the side constraints, and the cost
+/// of proving it with common backends.
+///
+/// The estimate assumes that non-quadratic constraints are rewritten with one intermediate
+/// signal per multiplication, and does not account for the linear constraints that the
+/// optimizations of circom (`--O1`, `--O2`) eliminate. The proving times extrapolate the
+/// per-point and per-butterfly costs above, so only their order of magnitude is meaningful.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CostEstimate {
+    pub r1cs_constraints: usize,
+    pub linear_constraints: usize,
+    pub non_quadratic_constraints: usize,
+    pub intermediate_signals: usize,
+    /// The signals of the constraint system, including the constant `1`.
+    pub wires: usize,
+    /// The number of non-zero entries of the matrices A, B, and C.
+    pub nonzero_entries: usize,
+    pub groth16: ProverEstimate,
+    pub plonk: Vec<PlonkEstimate>,
+}
+
+/// Estimates the cost of the constraint system made of the side constraints in `store`.
+///
+/// # Arguments
+///
+/// * `store` - The side constraints
+/// * `plonk_widths` - The widths of the Plonk gates to estimate the number of gates for
+pub fn estimate_cost(store: &dyn ConstraintStore, plonk_widths: &[usize]) -> CostEstimate {
+    let mut seen = FxHashSet::default();
+    let mut signals = FxHashSet::default();
+    let mut r1cs_constraints = 0;
+    let mut linear_constraints = 0;
+    let mut non_quadratic_constraints = 0;
+    let mut intermediate_signals = 0;
+    let mut nonzero_entries = 0;
+    let mut gates = vec![0; plonk_widths.len()];
+    for constraint in store.iter() {
+        let mut hasher = FxHasher::default();
+        constraint.hash(&mut hasher);
+        if !seen.insert(hasher.finish()) {
+            continue;
+        }
+        let shape = constraint_shape(&constraint);
+        if shape.is_constant() {
+            continue;
+        }
+        for (g, width) in gates.iter_mut().zip(plonk_widths) {
+            *g += plonk_gates(&shape, *width);
+        }
+        match &shape {
+            Shape::Linear(l) => {
+                r1cs_constraints += 1;
+                linear_constraints += 1;
+                nonzero_entries += l.len();
+            }
+            Shape::Quadratic(a, b, c) => {
+                r1cs_constraints += 1;
+                nonzero_entries += a.len() + b.len() + c.len();
+            }
+            Shape::NonQuadratic {
+                multiplications,
+                signals,
+            } => {
+                let rows = (*multiplications).max(1);
+                r1cs_constraints += rows;
+                non_quadratic_constraints += 1;
+                intermediate_signals += rows - 1;
+                // Two factors per multiplication, and each intermediate signal is both the
+                // output of its row and a term of the final row
+                nonzero_entries += 2 * multiplications + 2 * (rows - 1) + signals.len();
+            }
+        }
+        signals.extend(shape.into_signals());
+    }
+    let wires = signals.len() + intermediate_signals + 1;
+
+    CostEstimate {
+        r1cs_constraints,
+        linear_constraints,
+        non_quadratic_constraints,
+        intermediate_signals,
+        wires,
+        nonzero_entries,
+        groth16: estimate_groth16(r1cs_constraints, wires),
+        plonk: plonk_widths
+            .iter()
+            .zip(gates)
+            .map(|(width, gates)| PlonkEstimate {
+                width: *width,
+                gates,
+                prover: estimate_plonk(gates, *width),
+            })
+            .collect(),
+    }
+}
+
+/// Returns the cost of `num_ffts` transforms of size `n`.
+fn fft_ns(num_ffts: usize, n: usize) -> f64 {
+    num_ffts as f64 * n as f64 * n.trailing_zeros().max(1) as f64 * FFT_NS_PER_BUTTERFLY
+}
+
+/// The Groth16 prover computes the quotient polynomial with 7 transforms over the domain, and
+/// commits to it with three MSMs on G1 and one on G2 over the wires, and one on G1 over the
+/// domain.
+fn estimate_groth16(constraints: usize, wires: usize) -> ProverEstimate {
+    let n = (constraints + 1).next_power_of_two();
+    let time_ns = (3 * wires + n) as f64 * G1_MSM_NS_PER_POINT
+        + wires as f64 * G2_MSM_NS_PER_POINT
+        + fft_ns(7, n);
+    let memory =
+        (3 * wires + n) * G1_POINT_BYTES + wires * G2_POINT_BYTES + 3 * n * FIELD_ELEMENT_BYTES;
+    ProverEstimate::new(n, time_ns, memory)
+}
+
+/// The Plonk prover commits to one polynomial per wire column, the permutation, and the
+/// quotient split into `width` pieces, and opens them at two points, while the quotient is
+/// computed on a domain four times larger.
+fn estimate_plonk(gates: usize, width: usize) -> ProverEstimate {
+    let n = (gates + 1).next_power_of_two();
+    let num_commitments = 2 * width + 3;
+    let num_ffts = 2 * width + 4;
+    let time_ns = (num_commitments * n) as f64 * G1_MSM_NS_PER_POINT + fft_ns(num_ffts, 4 * n);
+    let memory = (n + 5) * G1_POINT_BYTES + num_ffts * 4 * n * FIELD_ELEMENT_BYTES;
+    ProverEstimate::new(n, time_ns, memory)
+}
+
+/// Formats a number of bytes with a binary unit, e.g. `1.5 MiB`.
+fn bytes_fmt(bytes: usize) -> String {
+    let units = ["B", "KiB", "MiB", "GiB", "TiB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit + 1 < units.len() {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} B", bytes)
+    } else {
+        format!("{:.1} {}", value, units[unit])
+    }
+}
+
+impl CostEstimate {
+    pub fn to_json(&self) -> Value {
+        json!({
+            "r1cs_constraints": self.r1cs_constraints,
+            "linear_constraints": self.linear_constraints,
+            "non_quadratic_constraints": self.non_quadratic_constraints,
+            "intermediate_signals": self.intermediate_signals,
+            "wires": self.wires,
+            "nonzero_entries": self.nonzero_entries,
+            "groth16": self.groth16.to_json(),
+            "plonk": self.plonk
+                .iter()
+                .map(|p| json!({
+                    "width": p.width,
+                    "gates": p.gates,
+                    "prover": p.prover.to_json(),
+                }))
+                .collect::<Vec<_>>(),
+        })
+    }
+
+    /// Returns the rows of the estimate as pairs of a label and a formatted value.
+    pub fn rows(&self) -> Vec<(String, String)> {
+        let prover_fmt = |p: &ProverEstimate| {
+            format!(
+                "domain 2^{}, ~{:?}, ~{}",
+                p.domain_size.trailing_zeros(),
+                p.proving_time,
+                bytes_fmt(p.memory_bytes)
+            )
+        };
+        let mut rows = vec![
+            (
+                "R1CS Constraints".to_string(),
+                format!(
+                    "{} (linear: {}, non-quadratic: {})",
+                    self.r1cs_constraints, self.linear_constraints, self.non_quadratic_constraints
+                ),
+            ),
+            (
+                "Wires".to_string(),
+                format!(
+                    "{} (intermediate: {})",
+                    self.wires, self.intermediate_signals
+                ),
+            ),
+            (
+                "Non-zero Entries".to_string(),
+                self.nonzero_entries.to_string(),
+            ),
+            ("Groth16".to_string(), prover_fmt(&self.groth16)),
+        ];
+        for p in &self.plonk {
+            rows.push((
+                format!("Plonk (width {})", p.width),
+                format!("{} gates, {}", p.gates, prover_fmt(&p.prover)),
+            ));
+        }
+        rows
+    }
+}
+
+pub fn print_cost_estimate_pretty(estimate: &CostEstimate) {
+    outln!("\n💰 Estimated Proving Cost:");
+    let rows = estimate.rows();
+    let width = rows.iter().map(|(k, _)| k.len()).max().unwrap_or(0);
+    for (label, value) in rows {
+        outln!(" • {:<width$}: {}", label, value, width = width);
+    }
+}
@@ -0,0 +1,389 @@
+use std::time;
+
+use rustc_hash::FxHashMap;
+
+use crate::executor::symbolic_value::SymbolicValueRef;
+use crate::executor::utils::strip_ansi;
+use crate::mutator::exploitability::{assessed_severity, Exploitability};
+use crate::mutator::severity::Severity;
+use crate::mutator::utils::CounterExample;
+use crate::stats::cost_estimate::CostEstimate;
+use crate::stats::symbolic_stats::{
+    first_signal_owner, owner_fmt, CompressionAnomaly, ConstraintStatistics,
+};
+
+const OPERATORS: [&str; 24] = [
+    "Assign",
+    "AssignEq",
+    "AssignCall",
+    "QuadZeroDiv",
+    "Mul",
+    "Div",
+    "Add",
+    "Sub",
+    "Pow",
+    "IntDiv",
+    "Mod",
+    "ShL",
+    "ShR",
+    "LEq",
+    "GEq",
+    "Lt",
+    "Gt",
+    "Eq",
+    "NEq",
+    "BoolOr",
+    "BoolAnd",
+    "BitOr",
+    "BitAnd",
+    "BitXor",
+];
+
+const STYLE: &str = "
+body { font-family: -apple-system, 'Segoe UI', Helvetica, Arial, sans-serif; margin: 2em auto; max-width: 1100px; color: #24292f; }
+h1 { border-bottom: 2px solid #2da44e; padding-bottom: .3em; }
+h2 { border-bottom: 1px solid #d0d7de; padding-bottom: .2em; margin-top: 2em; }
+table { border-collapse: collapse; margin: .5em 0; }
+th, td { border: 1px solid #d0d7de; padding: .3em .7em; text-align: left; vertical-align: top; }
+th { background: #f6f8fa; }
+td.num { text-align: right; font-variant-numeric: tabular-nums; }
+code, pre { font-family: SFMono-Regular, Consolas, 'Liberation Mono', monospace; font-size: 90%; }
+pre { background: #f6f8fa; padding: .7em; overflow-x: auto; }
+.safe { color: #1a7f37; font-weight: bold; }
+.unsafe { color: #cf222e; font-weight: bold; }
+.bar { display: inline-block; height: .8em; vertical-align: middle; }
+.bar.trace { background: #0969da; }
+.bar.side { background: #bf8700; }
+.target { background: #fbefff; font-weight: bold; }
+details { margin: .3em 0; }
+summary { cursor: pointer; }
+li code { white-space: pre-wrap; word-break: break-all; }
+";
+
+/// The contents of the HTML report of a run.
+pub struct HtmlReport<'a> {
+    pub target_path: &'a str,
+    pub main_template: &'a str,
+    pub prime: &'a str,
+    pub verdict: &'a str,
+    pub execution_time: time::Duration,
+    pub trace_stats: &'a ConstraintStatistics,
+    pub side_stats: &'a ConstraintStatistics,
+    pub cost_estimate: &'a CostEstimate,
+    /// Components whose side constraints are drastically fewer than their trace constraints.
+    pub compression_anomalies: &'a [CompressionAnomaly],
+    /// Findings of the detectors other than the search, as triples of a severity, a category, and
+    /// a description.
+    pub findings: Vec<(Severity, &'a str, String)>,
+    pub counter_examples: &'a [CounterExample],
+    /// The exploitability of each counterexample, if it was assessed.
+    pub exploitabilities: &'a [Option<Exploitability>],
+    pub symbolic_trace: &'a [SymbolicValueRef],
+    pub id2name: &'a FxHashMap<usize, String>,
+}
+
+/// Escapes the characters of `s` that have a special meaning in HTML.
+fn escape_html(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Renders `value` relative to `max` as a horizontal bar of at most 300 pixels.
+fn bar(value: usize, max: usize, class: &str) -> String {
+    let width = if max == 0 {
+        0
+    } else {
+        (value as f64 / max as f64 * 300.0).round() as usize
+    };
+    format!(
+        "<span class=\"bar {}\" style=\"width: {}px\"></span>",
+        class, width
+    )
+}
+
+impl<'a> HtmlReport<'a> {
+    /// Renders the report as a single HTML document without external resources, so that it can
+    /// be attached to an audit report as is.
+    pub fn render(&self) -> String {
+        let mut html = String::new();
+        html += "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n";
+        html += &format!(
+            "<title>zkFuzz Report: {}</title>\n<style>{}</style>\n</head>\n<body>\n",
+            escape_html(self.target_path),
+            STYLE
+        );
+        html += "<h1>zkFuzz Report</h1>\n";
+        html += &self.render_summary();
+        html += &self.render_statistics();
+        html += &self.render_findings();
+        html += &self.render_counter_examples();
+        html += &self.render_symbolic_trace();
+        html += "</body>\n</html>\n";
+        html
+    }
+
+    fn render_summary(&self) -> String {
+        let is_safe = self.counter_examples.is_empty();
+        let mut html = String::from("<h2>📊 Execution Summary</h2>\n<table>\n");
+        let mut row = |key: &str, value: String| {
+            html += &format!("<tr><th>{}</th><td>{}</td></tr>\n", key, value);
+        };
+        row(
+            "Target",
+            format!("<code>{}</code>", escape_html(self.target_path)),
+        );
+        row("Main Template", escape_html(self.main_template));
+        row(
+            "Prime Number",
+            format!("<code>{}</code>", escape_html(self.prime)),
+        );
+        row(
+            "Compression Rate",
+            format!(
+                "{:.2}% ({}/{})",
+                (self.side_stats.total_constraints as f64
+                    / self.trace_stats.total_constraints as f64)
+                    * 100 as f64,
+                self.side_stats.total_constraints,
+                self.trace_stats.total_constraints
+            ),
+        );
+        row(
+            "Verification",
+            format!(
+                "<span class=\"{}\">{}</span> ({})",
+                if is_safe { "safe" } else { "unsafe" },
+                if is_safe {
+                    "🆗 No Counter Example Found"
+                } else {
+                    "💥 NOT SAFE 💥"
+                },
+                escape_html(self.verdict)
+            ),
+        );
+        row("Execution Time", format!("{:?}", self.execution_time));
+        html += "</table>\n";
+        html
+    }
+
+    fn render_statistics(&self) -> String {
+        let (ts, ss) = (self.trace_stats, self.side_stats);
+        let mut html = String::from("<h2>🪶 Constraint Statistics</h2>\n");
+
+        html += "<table>\n<tr><th>Constraint Type</th><th>Symbolic Trace</th><th>Side Constraints</th></tr>\n";
+        for (name, t, s) in [
+            ("Total", ts.total_constraints, ss.total_constraints),
+            ("Constant", ts.constant_counts, ss.constant_counts),
+            ("Conditional", ts.conditional_counts, ss.conditional_counts),
+            ("Array", ts.array_counts, ss.array_counts),
+            (
+                "Variables",
+                ts.variable_counts.len(),
+                ss.variable_counts.len(),
+            ),
+        ] {
+            html += &format!(
+                "<tr><th>{}</th><td class=\"num\">{}</td><td class=\"num\">{}</td></tr>\n",
+                name, t, s
+            );
+        }
+        html += "</table>\n";
+
+        html += "<h3>🔢 Operator Counts</h3>\n";
+        html += "<p><span class=\"bar trace\" style=\"width: 1em\"></span> Symbolic Trace &nbsp; <span class=\"bar side\" style=\"width: 1em\"></span> Side Constraints</p>\n";
+        let count =
+            |stats: &ConstraintStatistics, op: &str| *stats.operator_counts.get(op).unwrap_or(&0);
+        let max = OPERATORS
+            .into_iter()
+            .map(|op| count(ts, op).max(count(ss, op)))
+            .max()
+            .unwrap_or(0);
+        html += "<table>\n<tr><th>Operator</th><th>Trace</th><th>Side</th><th></th></tr>\n";
+        for op in OPERATORS {
+            let (t, s) = (count(ts, op), count(ss, op));
+            if t == 0 && s == 0 {
+                continue;
+            }
+            html += &format!(
+                "<tr><th>{}</th><td class=\"num\">{}</td><td class=\"num\">{}</td><td>{}<br>{}</td></tr>\n",
+                op,
+                t,
+                s,
+                bar(t, max, "trace"),
+                bar(s, max, "side")
+            );
+        }
+        html += "</table>\n";
+
+        html += "<h3>🧩 Per-Component Statistics</h3>\n";
+        let trace_rows = ts.sorted_component_stats(self.id2name, "constraints");
+        let side_rows: FxHashMap<String, usize> = ss
+            .sorted_component_stats(self.id2name, "constraints")
+            .into_iter()
+            .map(|(name, c)| (name, c.num_constraints))
+            .collect();
+        let max = trace_rows
+            .iter()
+            .map(|(_, c)| c.num_constraints)
+            .chain(side_rows.values().cloned())
+            .max()
+            .unwrap_or(0);
+        html += "<table>\n<tr><th>Component</th><th>Trace</th><th>Side</th><th>Nonlinear (Trace)</th><th>Max Depth (Trace)</th><th></th></tr>\n";
+        for (name, c) in &trace_rows {
+            let side = *side_rows.get(name).unwrap_or(&0);
+            html += &format!(
+                "<tr><td><code>{}</code></td><td class=\"num\">{}</td><td class=\"num\">{}</td><td class=\"num\">{}</td><td class=\"num\">{}</td><td>{}<br>{}</td></tr>\n",
+                escape_html(name),
+                c.num_constraints,
+                side,
+                c.nonlinear_counts,
+                c.max_depth,
+                bar(c.num_constraints, max, "trace"),
+                bar(side, max, "side")
+            );
+        }
+        html += "</table>\n";
+
+        html += "<h3>💰 Estimated Proving Cost</h3>\n<table>\n";
+        for (label, value) in self.cost_estimate.rows() {
+            html += &format!(
+                "<tr><th>{}</th><td>{}</td></tr>\n",
+                label,
+                escape_html(&value)
+            );
+        }
+        html += "</table>\n";
+
+        if !self.compression_anomalies.is_empty() {
+            html += "<h3>⚠️ Low-Compression Components</h3>\n";
+            html += "<p>Components whose side constraints are drastically fewer than their trace constraints, a common symptom of under-constrained signals.</p>\n";
+            html += "<table>\n<tr><th>Component</th><th>Trace</th><th>Side</th><th>Compression Rate</th></tr>\n";
+            for anomaly in self.compression_anomalies {
+                html += &format!(
+                    "<tr><td><code>{}</code></td><td class=\"num\">{}</td><td class=\"num\">{}</td><td class=\"num\">{:.2}%</td></tr>\n",
+                    escape_html(&anomaly.owner),
+                    anomaly.trace_constraints,
+                    anomaly.side_constraints,
+                    anomaly.compression_rate() * 100 as f64
+                );
+            }
+            html += "</table>\n";
+        }
+        html
+    }
+
+    fn render_findings(&self) -> String {
+        let mut html = String::from("<h2>🔎 Findings</h2>\n");
+        if self.findings.is_empty() && self.counter_examples.is_empty() {
+            html += "<p>No findings.</p>\n";
+            return html;
+        }
+        html += "<ul>\n";
+        for (i, ce) in self.counter_examples.iter().enumerate() {
+            let exploitability = self.exploitabilities.get(i).and_then(|e| e.as_ref());
+            html += &format!(
+                "<li>[{}] <b>Counterexample</b>: {}{}</li>\n",
+                assessed_severity(ce, exploitability),
+                escape_html(&ce.classification()),
+                exploitability
+                    .map(|e| format!(" ({})", e.controllability.description()))
+                    .unwrap_or_default()
+            );
+        }
+        for (severity, category, description) in &self.findings {
+            html += &format!(
+                "<li>[{}] <b>{}</b>: {}</li>\n",
+                severity,
+                escape_html(category),
+                escape_html(&strip_ansi(description))
+            );
+        }
+        html += "</ul>\n";
+        html
+    }
+
+    fn render_counter_examples(&self) -> String {
+        let mut html = String::from("<h2>🚨 Counterexamples</h2>\n");
+        if self.counter_examples.is_empty() {
+            html += "<p>No counterexample was found.</p>\n";
+            return html;
+        }
+        for (i, ce) in self.counter_examples.iter().enumerate() {
+            html += &format!(
+                "<h3>#{} {}</h3>\n<pre>{}</pre>\n",
+                i + 1,
+                escape_html(&ce.classification()),
+                escape_html(&serde_json::to_string_pretty(&ce.flag.to_json()).unwrap())
+            );
+
+            // The signals of the main template come first, followed by those of sub-components
+            let mut assignment: Vec<_> = ce
+                .assignment
+                .iter()
+                .map(|(name, value)| {
+                    (
+                        name.owner.len() != 1,
+                        strip_ansi(&name.lookup_fmt(self.id2name)),
+                        value,
+                        ce.target_output.as_ref() == Some(name),
+                    )
+                })
+                .collect();
+            assignment.sort_by(|a, b| (a.0, &a.1).cmp(&(b.0, &b.1)));
+
+            html += "<table>\n<tr><th>Signal</th><th>Value</th></tr>\n";
+            for (_, name, value, is_target) in assignment {
+                html += &format!(
+                    "<tr{}><td><code>{}</code></td><td><code>{}</code></td></tr>\n",
+                    if is_target { " class=\"target\"" } else { "" },
+                    escape_html(&name),
+                    value
+                );
+            }
+            html += "</table>\n";
+        }
+        html
+    }
+
+    fn render_symbolic_trace(&self) -> String {
+        let mut html = String::from("<h2>🛠️ Symbolic Trace</h2>\n");
+
+        // Group the constraints by the component owning them, in order of first appearance
+        let mut groups: Vec<(String, Vec<String>)> = Vec::new();
+        let mut index: FxHashMap<String, usize> = FxHashMap::default();
+        for constraint in self.symbolic_trace {
+            let owner = first_signal_owner(constraint)
+                .map_or("(none)".to_string(), |owner| owner_fmt(owner, self.id2name));
+            let i = *index.entry(owner.clone()).or_insert_with(|| {
+                groups.push((owner, Vec::new()));
+                groups.len() - 1
+            });
+            groups[i]
+                .1
+                .push(strip_ansi(&constraint.lookup_fmt(self.id2name)));
+        }
+
+        for (owner, constraints) in &groups {
+            html += &format!(
+                "<details>\n<summary><code>{}</code> ({} constraints)</summary>\n<ol>\n",
+                escape_html(owner),
+                constraints.len()
+            );
+            for c in constraints {
+                html += &format!("<li><code>{}</code></li>\n", escape_html(c));
+            }
+            html += "</ol>\n</details>\n";
+        }
+        html
+    }
+}
@@ -1,2 +1,6 @@
 pub mod ast_stats;
+pub mod constraint_diff;
+pub mod cost_estimate;
+pub mod html_report;
+pub mod pretty_print;
 pub mod symbolic_stats;
@@ -0,0 +1,497 @@
+use std::str::FromStr;
+
+use num_bigint_dig::BigInt;
+use num_traits::{One, Signed};
+use program_structure::ast::{ExpressionInfixOpcode, ExpressionPrefixOpcode};
+use rustc_hash::FxHashMap;
+
+use crate::executor::structural_hash::is_associative_commutative;
+use crate::executor::symbolic_value::{
+    val_for_relational_operators, SymbolicAccess, SymbolicName, SymbolicValue, SymbolicValueRef,
+};
+use crate::executor::utils::modreduce;
+use crate::stats::constraint_diff::operator_symbol;
+
+/// The output format of the constraint pretty-printer (`show-constraints --format`).
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum PrettyFormat {
+    /// Plain text with the operators of circom, e.g. `main.c <== main.a * main.b`.
+    Text,
+    /// LaTeX math, e.g. `\mathtt{main.c} \Longleftarrow \mathtt{main.a} \cdot \mathtt{main.b}`.
+    Latex,
+}
+
+impl FromStr for PrettyFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(PrettyFormat::Text),
+            "latex" => Ok(PrettyFormat::Latex),
+            _ => Err(format!(
+                "unknown constraint format `{}` (expected text or latex)",
+                s
+            )),
+        }
+    }
+}
+
+// The precedence levels of the operators of circom, from the loosest to the tightest binding.
+const PREC_CONSTRAINT: u8 = 0;
+const PREC_CONDITIONAL: u8 = 1;
+const PREC_POWER: u8 = 12;
+const PREC_PREFIX: u8 = 13;
+const PREC_ATOM: u8 = 14;
+
+fn precedence(op: &ExpressionInfixOpcode) -> u8 {
+    match op {
+        ExpressionInfixOpcode::BoolOr => 2,
+        ExpressionInfixOpcode::BoolAnd => 3,
+        ExpressionInfixOpcode::BitOr => 4,
+        ExpressionInfixOpcode::BitXor => 5,
+        ExpressionInfixOpcode::BitAnd => 6,
+        ExpressionInfixOpcode::Eq | ExpressionInfixOpcode::NotEq => 7,
+        ExpressionInfixOpcode::LesserEq
+        | ExpressionInfixOpcode::GreaterEq
+        | ExpressionInfixOpcode::Lesser
+        | ExpressionInfixOpcode::Greater => 8,
+        ExpressionInfixOpcode::ShiftL | ExpressionInfixOpcode::ShiftR => 9,
+        ExpressionInfixOpcode::Add | ExpressionInfixOpcode::Sub => 10,
+        ExpressionInfixOpcode::Mul
+        | ExpressionInfixOpcode::Div
+        | ExpressionInfixOpcode::IntDiv
+        | ExpressionInfixOpcode::Mod => 11,
+        ExpressionInfixOpcode::Pow => PREC_POWER,
+    }
+}
+
+fn latex_operator_symbol(op: &ExpressionInfixOpcode) -> &'static str {
+    match op {
+        ExpressionInfixOpcode::Mul => "\\cdot",
+        ExpressionInfixOpcode::Div => "/",
+        ExpressionInfixOpcode::Add => "+",
+        ExpressionInfixOpcode::Sub => "-",
+        ExpressionInfixOpcode::Pow => "^",
+        ExpressionInfixOpcode::IntDiv => "\\mathbin{\\backslash}",
+        ExpressionInfixOpcode::Mod => "\\bmod",
+        ExpressionInfixOpcode::ShiftL => "\\ll",
+        ExpressionInfixOpcode::ShiftR => "\\gg",
+        ExpressionInfixOpcode::LesserEq => "\\leq",
+        ExpressionInfixOpcode::GreaterEq => "\\geq",
+        ExpressionInfixOpcode::Lesser => "<",
+        ExpressionInfixOpcode::Greater => ">",
+        ExpressionInfixOpcode::Eq => "=",
+        ExpressionInfixOpcode::NotEq => "\\neq",
+        ExpressionInfixOpcode::BoolOr => "\\lor",
+        ExpressionInfixOpcode::BoolAnd => "\\land",
+        ExpressionInfixOpcode::BitOr => "\\mathbin{|}",
+        ExpressionInfixOpcode::BitAnd => "\\mathbin{\\&}",
+        ExpressionInfixOpcode::BitXor => "\\oplus",
+    }
+}
+
+/// The binary operators of both formats, before which `wrap` prefers to break a line.
+const OPERATOR_SYMBOLS: [&str; 44] = [
+    "*",
+    "/",
+    "+",
+    "-",
+    "**",
+    "\\",
+    "%",
+    "<<",
+    ">>",
+    "<=",
+    ">=",
+    "<",
+    ">",
+    "==",
+    "!=",
+    "||",
+    "&&",
+    "|",
+    "&",
+    "^",
+    "?",
+    ":",
+    "<--",
+    "<==",
+    "===",
+    "=",
+    "\\cdot",
+    "\\mathbin{\\backslash}",
+    "\\bmod",
+    "\\ll",
+    "\\gg",
+    "\\leq",
+    "\\geq",
+    "\\neq",
+    "\\lor",
+    "\\land",
+    "\\mathbin{|}",
+    "\\mathbin{\\&}",
+    "\\oplus",
+    "\\mathbin{?}",
+    "\\leftarrow",
+    "\\Longleftarrow",
+    "\\equiv",
+    ":=",
+];
+
+fn escape_latex(name: &str) -> String {
+    let mut escaped = String::new();
+    for c in name.chars() {
+        if matches!(c, '_' | '$' | '#' | '%' | '&' | '{' | '}') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Formats symbolic values as conventional infix math with minimal parentheses.
+///
+/// Unlike `lookup_fmt`, which prints the tree of a value (e.g. `(AssignEq main.c (Mul main.a
+/// main.b))`), the pretty-printer prints what an auditor would write by hand (`main.c <== main.a *
+/// main.b`): operators follow the precedence of circom, `a + (-c)` is shown as `a - c`, and the
+/// constants above half of the prime are shown as negative numbers.
+pub struct PrettyPrinter<'a> {
+    pub format: PrettyFormat,
+    pub id2name: &'a FxHashMap<usize, String>,
+    pub prime: &'a BigInt,
+}
+
+impl<'a> PrettyPrinter<'a> {
+    pub fn new(
+        format: PrettyFormat,
+        id2name: &'a FxHashMap<usize, String>,
+        prime: &'a BigInt,
+    ) -> Self {
+        PrettyPrinter {
+            format: format,
+            id2name: id2name,
+            prime: prime,
+        }
+    }
+
+    fn is_latex(&self) -> bool {
+        self.format == PrettyFormat::Latex
+    }
+
+    fn keyword(&self, word: &str) -> String {
+        if self.is_latex() {
+            format!("\\mathrm{{{}}}", escape_latex(word))
+        } else {
+            word.to_string()
+        }
+    }
+
+    fn identifier(&self, id: &usize) -> String {
+        if self.is_latex() {
+            escape_latex(&self.id2name[id])
+        } else {
+            self.id2name[id].clone()
+        }
+    }
+
+    fn fmt_accesses(&self, accesses: &Option<Vec<SymbolicAccess>>) -> String {
+        accesses
+            .iter()
+            .flatten()
+            .map(|access| match access {
+                SymbolicAccess::ComponentAccess(id) => format!(".{}", self.identifier(id)),
+                SymbolicAccess::ArrayAccess(index) => format!("[{}]", self.fmt(index)),
+            })
+            .collect()
+    }
+
+    fn fmt_name(&self, name: &SymbolicName) -> String {
+        let mut segments: Vec<String> = name
+            .owner
+            .iter()
+            .map(|owner| self.identifier(&owner.id) + &self.fmt_accesses(&owner.access))
+            .collect();
+        segments.push(self.identifier(&name.id) + &self.fmt_accesses(&name.access));
+        if self.is_latex() {
+            format!("\\mathtt{{{}}}", segments.join("."))
+        } else {
+            segments.join(".")
+        }
+    }
+
+    fn fmt_list(&self, values: &[SymbolicValueRef]) -> String {
+        values
+            .iter()
+            .map(|v| self.operand(v, PREC_CONDITIONAL))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// Formats `value`, parenthesized if it binds looser than `min_precedence`.
+    fn operand(&self, value: &SymbolicValue, min_precedence: u8) -> String {
+        let (text, precedence) = self.fmt_with_precedence(value);
+        if precedence < min_precedence {
+            format!("({})", text)
+        } else {
+            text
+        }
+    }
+
+    fn fmt_assignment(&self, lhs: &SymbolicValue, symbol: &str, rhs: &SymbolicValue) -> String {
+        format!(
+            "{} {} {}",
+            self.operand(lhs, PREC_CONDITIONAL),
+            symbol,
+            self.operand(rhs, PREC_CONDITIONAL)
+        )
+    }
+
+    fn fmt_binary(
+        &self,
+        lhs: &SymbolicValue,
+        op: &ExpressionInfixOpcode,
+        rhs: &SymbolicValue,
+    ) -> (String, u8) {
+        if let SymbolicValue::ConstantInt(v) = rhs {
+            let v = val_for_relational_operators(&modreduce(v, self.prime), self.prime);
+            if *op == ExpressionInfixOpcode::Add && v.is_negative() {
+                return self.fmt_binary(
+                    lhs,
+                    &ExpressionInfixOpcode::Sub,
+                    &SymbolicValue::ConstantInt(-v),
+                );
+            }
+        }
+        if let SymbolicValue::ConstantInt(v) = lhs {
+            if *op == ExpressionInfixOpcode::Mul
+                && val_for_relational_operators(&modreduce(v, self.prime), self.prime)
+                    == -BigInt::one()
+            {
+                return (format!("-{}", self.operand(rhs, PREC_ATOM)), PREC_PREFIX);
+            }
+        }
+
+        let precedence = precedence(op);
+        if self.is_latex() {
+            match op {
+                ExpressionInfixOpcode::Div => {
+                    return (
+                        format!("\\frac{{{}}}{{{}}}", self.fmt(lhs), self.fmt(rhs)),
+                        PREC_POWER,
+                    );
+                }
+                ExpressionInfixOpcode::Pow => {
+                    return (
+                        format!("{}^{{{}}}", self.operand(lhs, PREC_ATOM), self.fmt(rhs)),
+                        PREC_POWER,
+                    );
+                }
+                _ => {}
+            }
+        }
+        let is_same_operator = |value: &SymbolicValue| match value {
+            SymbolicValue::BinaryOp(_, inner_op, _)
+            | SymbolicValue::AuxBinaryOp(_, inner_op, _) => inner_op.0 == *op,
+            _ => false,
+        };
+        let (lhs_precedence, rhs_precedence) = match op {
+            // `**` is right-associative
+            ExpressionInfixOpcode::Pow => (PREC_ATOM, precedence),
+            // Comparisons do not chain
+            ExpressionInfixOpcode::Eq
+            | ExpressionInfixOpcode::NotEq
+            | ExpressionInfixOpcode::LesserEq
+            | ExpressionInfixOpcode::GreaterEq
+            | ExpressionInfixOpcode::Lesser
+            | ExpressionInfixOpcode::Greater => (precedence + 1, precedence + 1),
+            _ if is_associative_commutative(op) && is_same_operator(rhs) => {
+                (precedence, precedence)
+            }
+            _ => (precedence, precedence + 1),
+        };
+        let symbol = if self.is_latex() {
+            latex_operator_symbol(op)
+        } else {
+            operator_symbol(op)
+        };
+        (
+            format!(
+                "{} {} {}",
+                self.operand(lhs, lhs_precedence),
+                symbol,
+                self.operand(rhs, rhs_precedence)
+            ),
+            precedence,
+        )
+    }
+
+    fn fmt_with_precedence(&self, value: &SymbolicValue) -> (String, u8) {
+        let latex = self.is_latex();
+        match value {
+            SymbolicValue::NOP => (self.keyword("nop"), PREC_ATOM),
+            SymbolicValue::ConstantInt(v) => {
+                let v = val_for_relational_operators(&modreduce(v, self.prime), self.prime);
+                let precedence = if v.is_negative() {
+                    PREC_PREFIX
+                } else {
+                    PREC_ATOM
+                };
+                (v.to_string(), precedence)
+            }
+            SymbolicValue::ConstantBool(b) => (self.keyword(&b.to_string()), PREC_ATOM),
+            SymbolicValue::Variable(name) => (self.fmt_name(name), PREC_ATOM),
+            SymbolicValue::Assign(lhs, rhs, ..) => (
+                self.fmt_assignment(lhs, if latex { "\\leftarrow" } else { "<--" }, rhs),
+                PREC_CONSTRAINT,
+            ),
+            SymbolicValue::AssignEq(lhs, rhs) => (
+                self.fmt_assignment(lhs, if latex { "\\Longleftarrow" } else { "<==" }, rhs),
+                PREC_CONSTRAINT,
+            ),
+            SymbolicValue::AssignTemplParam(lhs, rhs) | SymbolicValue::AssignCall(lhs, rhs, _) => (
+                self.fmt_assignment(lhs, if latex { ":=" } else { "=" }, rhs),
+                PREC_CONSTRAINT,
+            ),
+            SymbolicValue::BinaryOp(lhs, op, rhs) | SymbolicValue::AuxBinaryOp(lhs, op, rhs) => {
+                self.fmt_binary(lhs, &op.0, rhs)
+            }
+            SymbolicValue::Conditional(cond, if_true, if_false) => (
+                format!(
+                    "{} {} {} : {}",
+                    self.operand(cond, PREC_CONDITIONAL + 1),
+                    if latex { "\\mathbin{?}" } else { "?" },
+                    self.operand(if_true, PREC_CONDITIONAL),
+                    self.operand(if_false, PREC_CONDITIONAL)
+                ),
+                PREC_CONDITIONAL,
+            ),
+            SymbolicValue::UnaryOp(op, expr) => {
+                let symbol = match op.0 {
+                    ExpressionPrefixOpcode::Sub => "-",
+                    ExpressionPrefixOpcode::BoolNot if latex => "\\lnot",
+                    ExpressionPrefixOpcode::BoolNot => "!",
+                    ExpressionPrefixOpcode::Complement if latex => "\\sim",
+                    ExpressionPrefixOpcode::Complement => "~",
+                };
+                (
+                    format!("{}{}", symbol, self.operand(expr, PREC_ATOM)),
+                    PREC_PREFIX,
+                )
+            }
+            SymbolicValue::Array(elems) => (format!("[{}]", self.fmt_list(elems)), PREC_ATOM),
+            SymbolicValue::UniformArray(elem, count) => (
+                format!("[{}; {}]", self.fmt(elem), self.fmt(count)),
+                PREC_ATOM,
+            ),
+            SymbolicValue::Call(id, args) => {
+                let name = if latex {
+                    format!("\\mathrm{{{}}}", self.identifier(id))
+                } else {
+                    self.identifier(id)
+                };
+                (format!("{}({})", name, self.fmt_list(args)), PREC_ATOM)
+            }
+        }
+    }
+
+    /// Formats `value` in infix notation.
+    pub fn fmt(&self, value: &SymbolicValue) -> String {
+        self.fmt_with_precedence(value).0
+    }
+
+    /// Formats a trace or side constraint, where a top-level `==` is a `===` constraint.
+    pub fn fmt_constraint(&self, constraint: &SymbolicValue) -> String {
+        match constraint {
+            SymbolicValue::BinaryOp(lhs, op, rhs) if op.0 == ExpressionInfixOpcode::Eq => {
+                self.fmt_assignment(lhs, if self.is_latex() { "\\equiv" } else { "===" }, rhs)
+            }
+            _ => self.fmt(constraint),
+        }
+    }
+
+    /// Formats a constraint (see `fmt_constraint`) into lines of at most `width` characters (see
+    /// `wrap`). The continuation lines are indented in the text format, and aligned in an
+    /// `aligned` environment in the LaTeX format.
+    pub fn render(&self, constraint: &SymbolicValue, width: usize) -> Vec<String> {
+        let lines = wrap(&self.fmt_constraint(constraint), width);
+        if lines.len() == 1 {
+            return lines;
+        }
+        let last = lines.len() - 1;
+        if self.is_latex() {
+            let mut rendered = vec!["\\begin{aligned}".to_string()];
+            for (i, line) in lines.iter().enumerate() {
+                rendered.push(format!(
+                    "  &{}{}{}",
+                    if i == 0 { " " } else { " \\quad " },
+                    line,
+                    if i == last { "" } else { " \\\\" }
+                ));
+            }
+            rendered.push("\\end{aligned}".to_string());
+            rendered
+        } else {
+            lines
+                .into_iter()
+                .enumerate()
+                .map(|(i, line)| {
+                    if i == 0 {
+                        line
+                    } else {
+                        format!("    {}", line)
+                    }
+                })
+                .collect()
+        }
+    }
+}
+
+/// Splits `text` at its spaces outside of brackets and braces, filling lines of at most `width`
+/// characters greedily. A line is broken before a binary operator rather than after it, so that
+/// each continuation line starts with the operator. A word longer than `width` gets a line of its
+/// own, and a `width` of 0 disables the wrapping.
+pub fn wrap(text: &str, width: usize) -> Vec<String> {
+    if width == 0 {
+        return vec![text.to_string()];
+    }
+
+    let mut words = Vec::new();
+    let mut depth = 0i32;
+    let mut start = 0;
+    for (i, c) in text.char_indices() {
+        match c {
+            '[' | '{' => depth += 1,
+            ']' | '}' => depth -= 1,
+            ' ' if depth == 0 => {
+                words.push(&text[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    words.push(&text[start..]);
+
+    let mut lines = Vec::new();
+    let mut line: Vec<&str> = Vec::new();
+    let mut line_width = 0;
+    for word in words {
+        let word_width = word.chars().count();
+        if !line.is_empty() && line_width + 1 + word_width > width {
+            let mut next = Vec::new();
+            if line.len() > 1 && OPERATOR_SYMBOLS.contains(line.last().unwrap()) {
+                next.push(line.pop().unwrap());
+            }
+            lines.push(line.join(" "));
+            line = next;
+            line_width = line.first().map_or(0, |w| w.chars().count());
+        }
+        line_width += if line.is_empty() {
+            word_width
+        } else {
+            word_width + 1
+        };
+        line.push(word);
+    }
+    lines.push(line.join(" "));
+    lines
+}
@@ -1,6 +1,14 @@
 use std::collections::{HashMap, HashSet};
+use std::hash::{Hash, Hasher};
 
-use crate::executor::symbolic_value::{SymbolicName, SymbolicValue};
+use rustc_hash::{FxHashMap, FxHasher};
+
+use program_structure::ast::{ExpressionInfixOpcode, ExpressionPrefixOpcode};
+
+use crate::executor::constraint_store::ConstraintStore;
+use crate::executor::symbolic_value::{
+    OwnerName, SymbolicAccess, SymbolicName, SymbolicValue, SymbolicValueRef,
+};
 
 const RESET: &str = "\x1b[0m";
 const WHITE: &str = "\x1b[37m";
@@ -17,7 +25,163 @@ pub struct ConstraintStatistics {
     pub conditional_counts: usize,
     pub array_counts: usize,
     pub function_call_counts: HashMap<usize, usize>,
-    pub cache: HashSet<SymbolicValue>,
+    pub component_stats: HashMap<Vec<OwnerName>, ComponentStatistics>,
+    pub cache: HashSet<u64>,
+}
+
+/// Statistics of the constraints owned by a single component instance.
+///
+/// A constraint is owned by the component of the signal it assigns, or, if it is not an
+/// assignment, by the component of the first signal appearing in it.
+#[derive(Default, Clone)]
+pub struct ComponentStatistics {
+    pub num_constraints: usize,
+    pub operator_counts: HashMap<String, usize>,
+    pub max_depth: usize,
+    pub nonlinear_counts: usize,
+}
+
+impl ComponentStatistics {
+    fn update(&mut self, constraint: &SymbolicValue) {
+        self.num_constraints += 1;
+        if polynomial_degree(constraint) > 1 {
+            self.nonlinear_counts += 1;
+        }
+        self.update_from_symbolic_value(constraint, 0);
+    }
+
+    fn update_from_symbolic_value(&mut self, value: &SymbolicValue, depth: usize) {
+        self.max_depth = self.max_depth.max(depth);
+        if let Some(op_name) = operator_name(value) {
+            *self.operator_counts.entry(op_name).or_insert(0) += 1;
+        }
+        for child in children(value) {
+            self.update_from_symbolic_value(child, depth + 1);
+        }
+    }
+}
+
+/// Returns the name under which `value` is counted in the operator histogram, if any.
+fn operator_name(value: &SymbolicValue) -> Option<String> {
+    match value {
+        SymbolicValue::Assign(..) => Some("Assign".to_string()),
+        SymbolicValue::AssignEq(..) | SymbolicValue::AssignTemplParam(..) => {
+            Some("AssignEq".to_string())
+        }
+        SymbolicValue::AssignCall(..) => Some("AssignCall".to_string()),
+        SymbolicValue::BinaryOp(_, op, _) | SymbolicValue::AuxBinaryOp(_, op, _) => {
+            Some(format!("{:?}", op))
+        }
+        SymbolicValue::UnaryOp(op, _) => Some(format!("{:?}", op)),
+        _ => None,
+    }
+}
+
+fn children(value: &SymbolicValue) -> Vec<&SymbolicValueRef> {
+    match value {
+        SymbolicValue::Assign(lhs, rhs, ..)
+        | SymbolicValue::AssignEq(lhs, rhs)
+        | SymbolicValue::AssignTemplParam(lhs, rhs)
+        | SymbolicValue::AssignCall(lhs, rhs, _)
+        | SymbolicValue::BinaryOp(lhs, _, rhs)
+        | SymbolicValue::AuxBinaryOp(lhs, _, rhs)
+        | SymbolicValue::UniformArray(lhs, rhs) => vec![lhs, rhs],
+        SymbolicValue::Conditional(cond, if_true, if_false) => vec![cond, if_true, if_false],
+        SymbolicValue::UnaryOp(_, expr) => vec![expr],
+        SymbolicValue::Array(elements) | SymbolicValue::Call(_, elements) => {
+            elements.iter().collect()
+        }
+        _ => Vec::new(),
+    }
+}
+
+/// Returns an upper bound of the degree of `value` as a polynomial over the signals.
+///
+/// Operations other than addition, subtraction, multiplication, and division by a constant
+/// are regarded as nonlinear as soon as one of their operands is not constant.
+pub(crate) fn polynomial_degree(value: &SymbolicValue) -> usize {
+    let nonlinear_unless_constant = |degrees: &[usize]| {
+        let max_degree = degrees.iter().cloned().max().unwrap_or(0);
+        if max_degree == 0 {
+            0
+        } else {
+            max_degree.max(2)
+        }
+    };
+    match value {
+        SymbolicValue::NOP | SymbolicValue::ConstantInt(_) | SymbolicValue::ConstantBool(_) => 0,
+        SymbolicValue::Variable(_) => 1,
+        SymbolicValue::Assign(lhs, rhs, ..)
+        | SymbolicValue::AssignEq(lhs, rhs)
+        | SymbolicValue::AssignTemplParam(lhs, rhs)
+        | SymbolicValue::AssignCall(lhs, rhs, _) => {
+            polynomial_degree(lhs).max(polynomial_degree(rhs))
+        }
+        SymbolicValue::BinaryOp(lhs, op, rhs) | SymbolicValue::AuxBinaryOp(lhs, op, rhs) => {
+            let (l, r) = (polynomial_degree(lhs), polynomial_degree(rhs));
+            match op.0 {
+                ExpressionInfixOpcode::Add | ExpressionInfixOpcode::Sub => l.max(r),
+                ExpressionInfixOpcode::Mul => l.saturating_add(r),
+                ExpressionInfixOpcode::Div if r == 0 => l,
+                _ => nonlinear_unless_constant(&[l, r]),
+            }
+        }
+        SymbolicValue::UnaryOp(op, expr) => match op.0 {
+            ExpressionPrefixOpcode::Sub => polynomial_degree(expr),
+            _ => nonlinear_unless_constant(&[polynomial_degree(expr)]),
+        },
+        SymbolicValue::Conditional(cond, if_true, if_false) => {
+            let branches = polynomial_degree(if_true).max(polynomial_degree(if_false));
+            if polynomial_degree(cond) == 0 {
+                branches
+            } else {
+                nonlinear_unless_constant(&[branches, 1])
+            }
+        }
+        SymbolicValue::Array(elements) => elements
+            .iter()
+            .map(|e| polynomial_degree(e))
+            .max()
+            .unwrap_or(0),
+        SymbolicValue::UniformArray(elem, _) => polynomial_degree(elem),
+        SymbolicValue::Call(_, args) => {
+            let degrees: Vec<_> = args.iter().map(|a| polynomial_degree(a)).collect();
+            nonlinear_unless_constant(&degrees)
+        }
+    }
+}
+
+/// Returns the owner of the first signal in `value`, searching the left-hand side first.
+pub(crate) fn first_signal_owner(value: &SymbolicValue) -> Option<&Vec<OwnerName>> {
+    match value {
+        SymbolicValue::Variable(sym_name) => Some(sym_name.owner.as_ref()),
+        _ => children(value)
+            .into_iter()
+            .find_map(|child| first_signal_owner(child)),
+    }
+}
+
+/// Formats the owner of a constraint as a dotted component path, e.g. `main.sq[1]`.
+pub fn owner_fmt(owner: &[OwnerName], id2name: &FxHashMap<usize, String>) -> String {
+    if owner.is_empty() {
+        return "(none)".to_string();
+    }
+    owner
+        .iter()
+        .map(|e: &OwnerName| {
+            let access_str: String = if let Some(accesses) = &e.access {
+                accesses
+                    .iter()
+                    .map(|s: &SymbolicAccess| s.lookup_fmt(id2name))
+                    .collect::<Vec<_>>()
+                    .join("")
+            } else {
+                "".to_string()
+            };
+            id2name[&e.id].clone() + &access_str
+        })
+        .collect::<Vec<_>>()
+        .join(".")
 }
 
 impl ConstraintStatistics {
@@ -129,40 +293,271 @@ impl ConstraintStatistics {
     ///
     /// * `constraint` - The symbolic value representing the constraint to add
     pub fn update(&mut self, constraint: &SymbolicValue) {
-        if !self.cache.contains(constraint) {
+        // Only the fingerprints of the constraints are cached so that the statistics of a
+        // disk-backed constraint store can be collected without loading it into memory.
+        let mut hasher = FxHasher::default();
+        constraint.hash(&mut hasher);
+        if self.cache.insert(hasher.finish()) {
             self.total_constraints += 1;
-            self.cache.insert(constraint.clone());
             self.update_from_symbolic_value(constraint, 0);
+
+            let owner = first_signal_owner(constraint).cloned().unwrap_or_default();
+            self.component_stats
+                .entry(owner)
+                .or_default()
+                .update(constraint);
+        }
+    }
+
+    /// Updates overall statistics with every constraint in a constraint store.
+    ///
+    /// # Arguments
+    ///
+    /// * `store` - The constraint store to scan
+    pub fn update_from_store(&mut self, store: &dyn ConstraintStore) {
+        for constraint in store.iter() {
+            self.update(&constraint);
+        }
+    }
+
+    /// Returns the per-component statistics with formatted owner names, sorted by `sort_by`.
+    ///
+    /// # Arguments
+    ///
+    /// * `id2name` - The mapping used to format the owner names
+    /// * `sort_by` - One of `constraints`, `nonlinear`, `depth` (all descending), or `component`
+    pub fn sorted_component_stats(
+        &self,
+        id2name: &FxHashMap<usize, String>,
+        sort_by: &str,
+    ) -> Vec<(String, &ComponentStatistics)> {
+        let mut rows: Vec<_> = self
+            .component_stats
+            .iter()
+            .map(|(owner, stats)| (owner_fmt(owner, id2name), stats))
+            .collect();
+        rows.sort_by(|(a_name, a), (b_name, b)| {
+            let key = match sort_by {
+                "nonlinear" => b.nonlinear_counts.cmp(&a.nonlinear_counts),
+                "depth" => b.max_depth.cmp(&a.max_depth),
+                "component" => std::cmp::Ordering::Equal,
+                _ => b.num_constraints.cmp(&a.num_constraints),
+            };
+            key.then_with(|| a_name.cmp(b_name))
+        });
+        rows
+    }
+}
+
+/// Components whose side constraints are fewer than this fraction of their trace constraints are
+/// reported as compression anomalies.
+pub const COMPRESSION_ANOMALY_THRESHOLD: f64 = 0.5;
+
+/// The number of compression anomalies listed in the report.
+pub const COMPRESSION_ANOMALY_TOP_K: usize = 5;
+
+/// A component whose side constraints are drastically fewer than its trace constraints.
+///
+/// Every `<--` in the trace that is not followed by a `===` on the same signals lowers the
+/// compression rate of its component, so a low rate hints that the component is under-constrained
+/// even when the global rate looks healthy.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CompressionAnomaly {
+    pub owner: String,
+    pub trace_constraints: usize,
+    pub side_constraints: usize,
+}
+
+impl CompressionAnomaly {
+    /// Returns the ratio of side constraints to trace constraints.
+    pub fn compression_rate(&self) -> f64 {
+        self.side_constraints as f64 / self.trace_constraints as f64
+    }
+
+    /// Returns how many more constraints the trace has than the side constraints.
+    pub fn num_missing(&self) -> usize {
+        self.trace_constraints.saturating_sub(self.side_constraints)
+    }
+}
+
+/// Finds the components whose compression rate is below `threshold`.
+///
+/// # Arguments
+///
+/// * `trace_stats` - The statistics of the symbolic trace
+/// * `side_stats` - The statistics of the side constraints
+/// * `id2name` - The mapping used to format the owner names
+/// * `threshold` - The compression rate below which a component is reported
+/// * `top_k` - The maximum number of components to return
+///
+/// # Returns
+///
+/// The `top_k` most suspicious components, sorted by ascending compression rate and then by the
+/// number of constraints missing from the side constraints.
+pub fn find_compression_anomalies(
+    trace_stats: &ConstraintStatistics,
+    side_stats: &ConstraintStatistics,
+    id2name: &FxHashMap<usize, String>,
+    threshold: f64,
+    top_k: usize,
+) -> Vec<CompressionAnomaly> {
+    let mut anomalies: Vec<_> = trace_stats
+        .component_stats
+        .iter()
+        .filter(|(owner, stats)| !owner.is_empty() && stats.num_constraints > 0)
+        .map(|(owner, stats)| CompressionAnomaly {
+            owner: owner_fmt(owner, id2name),
+            trace_constraints: stats.num_constraints,
+            side_constraints: side_stats
+                .component_stats
+                .get(owner)
+                .map_or(0, |s| s.num_constraints),
+        })
+        .filter(|a| a.compression_rate() < threshold)
+        .collect();
+    anomalies.sort_by(|a, b| {
+        a.compression_rate()
+            .partial_cmp(&b.compression_rate())
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then_with(|| a.num_missing().cmp(&b.num_missing()).reverse())
+            .then_with(|| a.owner.cmp(&b.owner))
+    });
+    anomalies.truncate(top_k);
+    anomalies
+}
+
+/// Formats the most frequent operators of a histogram, e.g. `Mul:12 Add:3`.
+fn top_operators(operator_counts: &HashMap<String, usize>, n: usize) -> String {
+    let mut counts: Vec<_> = operator_counts.iter().collect();
+    counts.sort_by(|(a_op, a), (b_op, b)| b.cmp(a).then_with(|| a_op.cmp(b_op)));
+    counts
+        .iter()
+        .take(n)
+        .map(|(op, c)| format!("{}:{}", op, c))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+pub fn print_component_statistics_pretty(
+    stats: &ConstraintStatistics,
+    id2name: &FxHashMap<usize, String>,
+    sort_by: &str,
+) {
+    let rows = stats.sorted_component_stats(id2name, sort_by);
+    let width = rows
+        .iter()
+        .map(|(name, _)| name.chars().count())
+        .max()
+        .unwrap_or(0)
+        .max("Component".len());
+
+    outln!("\n🧩 Per-Component Statistics (sorted by {}):", sort_by);
+    outln!(
+        " {:<width$} │ {:>11} │ {:>9} │ {:>9} │ Top Operators",
+        "Component",
+        "Constraints",
+        "Nonlinear",
+        "Max Depth",
+        width = width
+    );
+    outln!(
+        " {}─┼─────────────┼───────────┼───────────┼──────────────",
+        "─".repeat(width)
+    );
+    for (name, c) in rows {
+        outln!(
+            " {:<width$} │ {:>11} │ {:>9} │ {:>9} │ {}",
+            name,
+            c.num_constraints,
+            c.nonlinear_counts,
+            c.max_depth,
+            top_operators(&c.operator_counts, 3),
+            width = width
+        );
+    }
+}
+
+/// Formats the per-component statistics of the symbolic trace and of the side constraints as a
+/// single CSV table, one row per component and constraint kind (`trace` or `side`).
+///
+/// The operator histograms are flattened into `Count_<Operator>` columns.
+pub fn component_statistics_csv(
+    trace_stats: &ConstraintStatistics,
+    side_stats: &ConstraintStatistics,
+    id2name: &FxHashMap<usize, String>,
+    sort_by: &str,
+) -> String {
+    let trace_rows = trace_stats.sorted_component_stats(id2name, sort_by);
+    let side_rows = side_stats.sorted_component_stats(id2name, sort_by);
+    let mut operators: Vec<&String> = trace_rows
+        .iter()
+        .chain(side_rows.iter())
+        .flat_map(|(_, c)| c.operator_counts.keys())
+        .collect::<HashSet<_>>()
+        .into_iter()
+        .collect();
+    operators.sort();
+
+    let mut headers = vec![
+        "Kind".to_string(),
+        "Component".to_string(),
+        "Constraints".to_string(),
+        "Nonlinear".to_string(),
+        "Max_Depth".to_string(),
+    ];
+    headers.extend(operators.iter().map(|op| format!("Count_{}", op)));
+
+    let mut lines = vec![headers.join(",")];
+    for (kind, rows) in [("trace", &trace_rows), ("side", &side_rows)] {
+        for (name, c) in rows.iter() {
+            let mut values = vec![
+                kind.to_string(),
+                if name.contains(',') || name.contains('"') {
+                    format!("\"{}\"", name.replace('"', "\"\""))
+                } else {
+                    name.clone()
+                },
+                c.num_constraints.to_string(),
+                c.nonlinear_counts.to_string(),
+                c.max_depth.to_string(),
+            ];
+            values.extend(
+                operators
+                    .iter()
+                    .map(|op| c.operator_counts.get(*op).unwrap_or(&0).to_string()),
+            );
+            lines.push(values.join(","));
         }
     }
+    lines.join("\n") + "\n"
 }
 
 pub fn print_constraint_summary_statistics_pretty(stats: &ConstraintStatistics) {
-    println!(" ┌─────────────────────┬─────────────┐");
-    println!(" │ Constraint Type     │     Count   │");
-    println!(" ├─────────────────────┼─────────────┤");
-    println!(" │ Total               │ {:11} │", stats.total_constraints);
-    println!(" │ Constant            │ {:11} │", stats.constant_counts);
-    println!(" │ Conditional         │ {:11} │", stats.conditional_counts);
-    println!(" │ Array               │ {:11} │", stats.array_counts);
-    println!(" └─────────────────────┴─────────────┘");
+    outln!(" ┌─────────────────────┬─────────────┐");
+    outln!(" │ Constraint Type     │     Count   │");
+    outln!(" ├─────────────────────┼─────────────┤");
+    outln!(" │ Total               │ {:11} │", stats.total_constraints);
+    outln!(" │ Constant            │ {:11} │", stats.constant_counts);
+    outln!(" │ Conditional         │ {:11} │", stats.conditional_counts);
+    outln!(" │ Array               │ {:11} │", stats.array_counts);
+    outln!(" └─────────────────────┴─────────────┘");
 
     let avg_depth = if !stats.constraint_depths.is_empty() {
         stats.constraint_depths.iter().sum::<usize>() as f64 / stats.constraint_depths.len() as f64
     } else {
         0.0
     };
-    println!("\n📊 Constraint Depth Statistics:");
-    println!(" • Average Depth: {:.2}", avg_depth);
-    println!(
+    outln!("\n📊 Constraint Depth Statistics:");
+    outln!(" • Average Depth: {:.2}", avg_depth);
+    outln!(
         " • Maximum Depth: {}",
         stats.constraint_depths.iter().max().unwrap_or(&0)
     );
 
-    println!("\n🔢 Assign Counts:");
+    outln!("\n🔢 Assign Counts:");
     for op in &["Assign", "AssignEq", "AssignCall", "QuadZeroDiv"] {
         let c = stats.operator_counts.get(*op).unwrap_or(&0);
-        println!(
+        outln!(
             " • {:<13}: {}{}{}",
             op,
             if *c != 0 { WHITE } else { BBLACK },
@@ -171,13 +566,13 @@ pub fn print_constraint_summary_statistics_pretty(stats: &ConstraintStatistics)
         );
     }
 
-    println!("\n🔢 Operator Counts:");
+    outln!("\n🔢 Operator Counts:");
     for op in &[
         "Mul", "Div", "Add", "Sub", "Pow", "IntDiv", "Mod", "ShL", "ShR", "LEq", "GEq", "Lt", "Gt",
         "Eq", "NEq", "BoolOr", "BoolAnd", "BitOr", "BitAnd", "BitXor",
     ] {
         let c = stats.operator_counts.get(*op).unwrap_or(&0);
-        println!(
+        outln!(
             " • {:<8}: {}{}{}",
             op,
             if *c != 0 { WHITE } else { BBLACK },
@@ -186,29 +581,29 @@ pub fn print_constraint_summary_statistics_pretty(stats: &ConstraintStatistics)
         );
     }
 
-    println!("\n📈 Variable Statistics:");
+    outln!("\n📈 Variable Statistics:");
     let var_counts: Vec<usize> = stats.variable_counts.values().cloned().collect();
     let var_avg = if !var_counts.is_empty() {
         var_counts.iter().sum::<usize>() as f64 / var_counts.len() as f64
     } else {
         0.0
     };
-    println!(" • Total Number of Variables: {}", var_counts.len());
-    println!(" • Average Number of Usage  : {:.2}", var_avg);
-    println!(
+    outln!(" • Total Number of Variables: {}", var_counts.len());
+    outln!(" • Average Number of Usage  : {:.2}", var_avg);
+    outln!(
         " • Maximum Number of Usage  : {}",
         var_counts.iter().max().unwrap_or(&0)
     );
 
-    println!("\n📞 Function Call Statistics:");
+    outln!("\n📞 Function Call Statistics:");
     let func_counts: Vec<usize> = stats.function_call_counts.values().cloned().collect();
     let func_avg = if !func_counts.is_empty() {
         func_counts.iter().sum::<usize>() as f64 / func_counts.len() as f64
     } else {
         0.0
     };
-    println!(" • Average Count: {:.2}", func_avg);
-    println!(
+    outln!(" • Average Count: {:.2}", func_avg);
+    outln!(
         " • Maximum Count: {}",
         func_counts.iter().max().unwrap_or(&0)
     );
@@ -296,5 +691,5 @@ pub fn print_constraint_summary_statistics_csv(constraint_stats: &ConstraintStat
     values.push(format!("{:.2}", func_avg));
     values.push(func_counts.iter().max().unwrap_or(&0).to_string());
 
-    println!("{}", values.join(","));
+    outln!("{}", values.join(","));
 }
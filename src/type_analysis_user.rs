@@ -1,7 +1,10 @@
 use program_structure::error_definition::Report;
 use program_structure::program_archive::ProgramArchive;
+use rustc_hash::FxHashMap;
 use type_analysis::check_types::check_types;
 
+use crate::executor::symbolic_value::SymbolicLibrary;
+
 pub fn analyse_project(program_archive: &mut ProgramArchive) -> Result<(), ()> {
     let analysis_result = check_types(program_archive);
     match analysis_result {
@@ -15,3 +18,35 @@ pub fn analyse_project(program_archive: &mut ProgramArchive) -> Result<(), ()> {
         }
     }
 }
+
+/// Collects the shapes of the inputs of the main template.
+///
+/// Once the type checker of circom has accepted the program, every input of the main template is
+/// declared with a consistent number of dimensions, whose sizes are evaluated by the symbolic
+/// executor when it runs the declarations of the main template.
+///
+/// # Parameters
+/// - `symbolic_library`: The symbolic library containing the template definitions.
+/// - `main_template_name`: The name of the main template.
+/// - `id2dimensions`: The evaluated dimensions of the declarations of the main template.
+///
+/// # Returns
+/// A map from the id of each input to its dimensions. Inputs whose dimensions could not be
+/// evaluated (e.g. with symbolic template parameters) are omitted.
+pub fn gather_input_shapes(
+    symbolic_library: &SymbolicLibrary,
+    main_template_name: &str,
+    id2dimensions: &FxHashMap<usize, Vec<usize>>,
+) -> FxHashMap<usize, Vec<usize>> {
+    let template =
+        &symbolic_library.template_library[&symbolic_library.name2id[main_template_name]];
+    template
+        .input_ids
+        .iter()
+        .filter_map(|id| match id2dimensions.get(id) {
+            // An undetermined dimension is evaluated to zero.
+            Some(dims) if !dims.contains(&0) => Some((*id, dims.clone())),
+            _ => None,
+        })
+        .collect()
+}
@@ -0,0 +1,258 @@
+use std::rc::Rc;
+use std::str::FromStr;
+
+use num_bigint_dig::BigInt;
+use rustc_hash::FxHashMap;
+
+use program_structure::ast::{ExpressionInfixOpcode, ExpressionPrefixOpcode};
+
+use zkfuzz::executor::algebraic_simplifier::{
+    count_nodes, simplify_constraints, simplify_value, SimplificationLevel,
+};
+use zkfuzz::executor::debug_ast::{
+    DebuggableExpressionInfixOpcode, DebuggableExpressionPrefixOpcode,
+};
+use zkfuzz::executor::symbolic_value::{
+    OwnerName, SymbolicLibrary, SymbolicName, SymbolicValue, SymbolicValueRef,
+};
+use zkfuzz::executor::utils::modreduce;
+use zkfuzz::mutator::utils::evaluate_constraints;
+
+const BN128: &str = "21888242871839275222246405745257275088548364400416034343698204186575808495617";
+
+fn var(id: usize) -> SymbolicValueRef {
+    let owner = OwnerName {
+        id: 0,
+        access: None,
+        counter: 0,
+    };
+    Rc::new(SymbolicValue::Variable(SymbolicName::new(
+        id,
+        Rc::new(vec![owner]),
+        None,
+    )))
+}
+
+fn int(v: i64) -> SymbolicValueRef {
+    Rc::new(SymbolicValue::ConstantInt(BigInt::from(v)))
+}
+
+fn op(lhs: SymbolicValueRef, op: ExpressionInfixOpcode, rhs: SymbolicValueRef) -> SymbolicValueRef {
+    Rc::new(SymbolicValue::BinaryOp(
+        lhs,
+        DebuggableExpressionInfixOpcode(op),
+        rhs,
+    ))
+}
+
+fn neg(value: SymbolicValueRef) -> SymbolicValueRef {
+    Rc::new(SymbolicValue::UnaryOp(
+        DebuggableExpressionPrefixOpcode(ExpressionPrefixOpcode::Sub),
+        value,
+    ))
+}
+
+fn add(lhs: SymbolicValueRef, rhs: SymbolicValueRef) -> SymbolicValueRef {
+    op(lhs, ExpressionInfixOpcode::Add, rhs)
+}
+
+fn sub(lhs: SymbolicValueRef, rhs: SymbolicValueRef) -> SymbolicValueRef {
+    op(lhs, ExpressionInfixOpcode::Sub, rhs)
+}
+
+fn mul(lhs: SymbolicValueRef, rhs: SymbolicValueRef) -> SymbolicValueRef {
+    op(lhs, ExpressionInfixOpcode::Mul, rhs)
+}
+
+#[test]
+fn test_simplification_level_from_str() {
+    for level in [
+        SimplificationLevel::Off,
+        SimplificationLevel::Basic,
+        SimplificationLevel::Aggressive,
+    ] {
+        assert_eq!(SimplificationLevel::from_str(level.as_str()), Ok(level));
+    }
+    assert!(SimplificationLevel::from_str("maximal").is_err());
+}
+
+#[test]
+fn test_basic_simplification() {
+    let prime = BigInt::from_str(BN128).unwrap();
+    let simplify = |v: &SymbolicValueRef| simplify_value(v, &prime, SimplificationLevel::Basic);
+    let (x, y) = (var(1), var(2));
+
+    assert_eq!(simplify(&add(mul(x.clone(), int(1)), int(0))), x);
+    assert_eq!(simplify(&add(int(0), mul(int(1), x.clone()))), x);
+    assert_eq!(simplify(&sub(x.clone(), int(0))), x);
+    assert_eq!(simplify(&neg(neg(x.clone()))), x);
+    assert_eq!(simplify(&sub(int(0), x.clone())), neg(x.clone()));
+    assert_eq!(
+        simplify(&mul(add(int(2), int(3)), y.clone())),
+        mul(int(5), y.clone())
+    );
+    assert_eq!(
+        simplify(&neg(int(1))),
+        Rc::new(SymbolicValue::ConstantInt(&prime - BigInt::from(1)))
+    );
+    let cond = Rc::new(SymbolicValue::Conditional(
+        op(int(1), ExpressionInfixOpcode::Lesser, int(2)),
+        x.clone(),
+        y.clone(),
+    ));
+    assert_eq!(simplify(&cond), x);
+
+    // The basic rewrites do not reassociate
+    let chain = add(add(x.clone(), int(2)), int(3));
+    assert_eq!(simplify(&chain), chain);
+    assert_eq!(
+        simplify_value(&chain, &prime, SimplificationLevel::Off),
+        chain
+    );
+}
+
+#[test]
+fn test_aggressive_simplification() {
+    let prime = BigInt::from_str(BN128).unwrap();
+    let simplify =
+        |v: &SymbolicValueRef| simplify_value(v, &prime, SimplificationLevel::Aggressive);
+    let (x, y) = (var(1), var(2));
+
+    // (x + 2) + (3 * x - 5) = 4 * x - 3
+    let value = add(add(x.clone(), int(2)), sub(mul(int(3), x.clone()), int(5)));
+    assert_eq!(simplify(&value), sub(mul(int(4), x.clone()), int(3)));
+
+    // The like terms are found regardless of the order of their factors
+    let value = add(
+        sub(mul(x.clone(), y.clone()), mul(y.clone(), x.clone())),
+        y.clone(),
+    );
+    assert_eq!(simplify(&value), y);
+
+    assert_eq!(simplify(&mul(mul(x.clone(), int(0)), y.clone())), int(0));
+    assert_eq!(
+        simplify(&mul(int(2), mul(x.clone(), int(3)))),
+        mul(int(6), x.clone())
+    );
+    assert_eq!(simplify(&sub(int(0), neg(neg(x.clone())))), neg(x.clone()));
+
+    // (p - 1) * x = -x
+    let minus_one = Rc::new(SymbolicValue::ConstantInt(&prime - BigInt::from(1)));
+    assert_eq!(simplify(&mul(minus_one, x.clone())), neg(x.clone()));
+
+    // The coefficients are combined modulo the prime
+    let p7 = BigInt::from(7);
+    let value = add(
+        mul(int(3), x.clone()),
+        add(mul(int(4), x.clone()), y.clone()),
+    );
+    assert_eq!(
+        simplify_value(&value, &p7, SimplificationLevel::Aggressive),
+        y
+    );
+
+    // Products are not distributed over sums, whose own terms are still combined
+    let value = mul(add(x.clone(), add(x.clone(), int(1))), y.clone());
+    assert_eq!(
+        simplify(&value),
+        mul(add(mul(int(2), x.clone()), int(1)), y.clone())
+    );
+}
+
+#[test]
+fn test_simplification_preserves_constraints() {
+    let prime = BigInt::from_str(BN128).unwrap();
+    let (x, y, z) = (var(1), var(2), var(3));
+    let eq = |lhs, rhs| op(lhs, ExpressionInfixOpcode::Eq, rhs);
+
+    let constraints = vec![
+        Rc::new(SymbolicValue::AssignEq(
+            z.clone(),
+            add(
+                mul(x.clone(), int(1)),
+                sub(mul(int(2), y.clone()), y.clone()),
+            ),
+        )),
+        eq(
+            mul(sub(x.clone(), int(1)), add(x.clone(), int(0))),
+            sub(
+                mul(y.clone(), int(3)),
+                add(y.clone(), mul(int(2), y.clone())),
+            ),
+        ),
+        eq(
+            neg(neg(add(z.clone(), int(4)))),
+            add(int(2), add(int(2), z.clone())),
+        ),
+    ];
+
+    let mut symbolic_library = SymbolicLibrary::default();
+    for level in [SimplificationLevel::Basic, SimplificationLevel::Aggressive] {
+        let simplified = simplify_constraints(&constraints, &prime, level);
+        assert_eq!(simplified.len(), constraints.len());
+        assert!(
+            simplified.iter().map(|c| count_nodes(c)).sum::<usize>()
+                < constraints.iter().map(|c| count_nodes(c)).sum::<usize>()
+        );
+        match simplified[0].as_ref() {
+            SymbolicValue::AssignEq(lhs, _) => assert_eq!(*lhs, z),
+            _ => panic!("the assignment should be kept"),
+        }
+
+        // Each simplified value equals the original one for any assignment
+        for (xv, yv) in [(0, 0), (1, 5), (7, -3), (-1, 2)] {
+            let mut assignment = FxHashMap::default();
+            for (name, v) in [(&x, xv), (&y, yv), (&z, xv + yv)] {
+                if let SymbolicValue::Variable(name) = name.as_ref() {
+                    assignment.insert(name.clone(), modreduce(&BigInt::from(v), &prime));
+                }
+            }
+            let rhs = |c: &SymbolicValueRef| match c.as_ref() {
+                SymbolicValue::AssignEq(_, rhs) => rhs.clone(),
+                _ => c.clone(),
+            };
+            let equalities: Vec<SymbolicValueRef> = constraints
+                .iter()
+                .zip(simplified.iter())
+                .map(|(original, simplified)| eq(rhs(original), rhs(simplified)))
+                .collect();
+            assert!(evaluate_constraints(
+                &prime,
+                &equalities,
+                &assignment,
+                &mut symbolic_library
+            ));
+        }
+    }
+}
+
+#[test]
+fn test_simplification_keeps_quadratic_forms() {
+    let prime = BigInt::from_str(BN128).unwrap();
+    let (x, y, z) = (var(1), var(2), var(3));
+    let name = match x.as_ref() {
+        SymbolicValue::Variable(name) => name.clone(),
+        _ => unreachable!(),
+    };
+    let rhs = op(
+        add(x.clone(), int(0)),
+        ExpressionInfixOpcode::Div,
+        mul(y.clone(), int(1)),
+    );
+    let polys = (
+        vec![(name.clone(), [int(0), add(int(1), int(0)), int(0)])],
+        vec![],
+    );
+    let assign = Rc::new(SymbolicValue::Assign(z.clone(), rhs, true, Some(polys)));
+
+    let simplified = simplify_value(&assign, &prime, SimplificationLevel::Basic);
+    assert_eq!(
+        simplified,
+        Rc::new(SymbolicValue::Assign(
+            z,
+            op(x, ExpressionInfixOpcode::Div, y),
+            true,
+            Some((vec![(name, [int(0), int(1), int(0)])], vec![])),
+        ))
+    );
+}
@@ -0,0 +1,68 @@
+use std::env;
+use std::fs;
+use std::path::Path;
+
+use serde_json::Value;
+
+use zkfuzz::artifact_user::ArtifactWriter;
+
+#[test]
+fn test_artifact_names() {
+    let writer =
+        ArtifactWriter::new("none", Path::new("circuits/test_vuln_iszero.circom")).unwrap();
+    assert_eq!(
+        writer.prefix("ga", 1),
+        Path::new("circuits")
+            .join("test_vuln_iszero_ga_1")
+            .to_string_lossy()
+    );
+    assert_eq!(
+        writer.prefix("custom:my_invariant", 2),
+        Path::new("circuits")
+            .join("test_vuln_iszero_custom_my_invariant_2")
+            .to_string_lossy()
+    );
+    // Without `--out_dir`, the files are named the same way but no manifest is written.
+    assert_eq!(
+        writer
+            .write_manifest("circuits/test_vuln_iszero.circom", "Skipped")
+            .unwrap(),
+        None
+    );
+}
+
+#[test]
+fn test_artifact_manifest() {
+    let dir = env::temp_dir().join("zkfuzz_artifact_test_out");
+    let _ = fs::remove_dir_all(&dir);
+
+    let mut writer = ArtifactWriter::new(
+        &dir.to_string_lossy(),
+        Path::new("./tests/sample/test_vuln_iszero.circom"),
+    )
+    .unwrap();
+    assert!(dir.is_dir());
+
+    let counterexample_path = format!("{}_counterexample.json", writer.prefix("quick", 1));
+    writer.record("counterexample", &counterexample_path);
+    writer.record("summary", "summary.json");
+
+    let manifest_path = writer
+        .write_manifest("./tests/sample/test_vuln_iszero.circom", "UnderConstrained")
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        manifest_path,
+        dir.join("test_vuln_iszero_manifest.json").to_string_lossy()
+    );
+    let manifest: Value =
+        serde_json::from_str(&fs::read_to_string(&manifest_path).unwrap()).unwrap();
+    assert_eq!(manifest["verdict"], "UnderConstrained");
+    assert_eq!(manifest["artifacts"][0]["kind"], "counterexample");
+    assert_eq!(
+        manifest["artifacts"][0]["path"],
+        counterexample_path.as_str()
+    );
+    assert_eq!(manifest["artifacts"][1]["path"], "summary.json");
+    assert_eq!(manifest["artifacts"].as_array().unwrap().len(), 2);
+}
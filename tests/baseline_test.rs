@@ -0,0 +1,135 @@
+use std::env;
+use std::fs;
+
+use zkfuzz::executor::structural_hash::StableHasher;
+use zkfuzz::mutator::baseline::{Baseline, BaselineEntry};
+use zkfuzz::mutator::clustering::FindingSignature;
+
+fn unused_output(signal: &str) -> BaselineEntry {
+    BaselineEntry::of_counter_example(
+        "Main",
+        &FindingSignature {
+            kind: "UnderConstrained-UnusedOutput".to_string(),
+            structure: 0,
+            constraint: "".to_string(),
+            signal: signal.to_string(),
+        },
+    )
+}
+
+#[test]
+fn test_baseline_fingerprint_ignores_line_numbers() {
+    let before = BaselineEntry::of_finding(
+        "Main",
+        "Potential Division-by-Zero",
+        "Div (line 7): `a / b` where `b` may be zero",
+    );
+    let after = BaselineEntry::of_finding(
+        "Main",
+        "Potential Division-by-Zero",
+        "Div (line 12): `a / b` where `b` may be zero",
+    );
+    assert_eq!(before.fingerprint, after.fingerprint);
+    assert_eq!(
+        before.description,
+        "Div (line ?): `a / b` where `b` may be zero"
+    );
+
+    let other = BaselineEntry::of_finding(
+        "Other",
+        "Potential Division-by-Zero",
+        "Div (line 7): `a / b` where `b` may be zero",
+    );
+    assert_ne!(before.fingerprint, other.fingerprint);
+    assert_ne!(
+        unused_output("main.out").fingerprint,
+        unused_output("main.y").fingerprint
+    );
+}
+
+#[test]
+fn test_baseline_fingerprint_is_stable() {
+    // The test vectors of FNV-1a
+    let mut hasher = StableHasher::default();
+    assert_eq!(hasher.finish(), 0xcbf29ce484222325);
+    hasher.write_bytes(b"a");
+    assert_eq!(hasher.finish(), 0xaf63dc4c8601ec8c);
+
+    // The fingerprints of the checked-in baselines must not change across builds
+    let entry = BaselineEntry::of_finding(
+        "Main",
+        "Potential Division-by-Zero",
+        "Div (line 7): `a / b` where `b` may be zero",
+    );
+    assert_eq!(entry.fingerprint, "c3c875e9d2e9218b");
+}
+
+#[test]
+fn test_baseline_compare_and_update() {
+    let out = unused_output("main.out");
+    let y = unused_output("main.y");
+    let z = unused_output("main.z");
+
+    let mut baseline = Baseline::default().update("Main", &[out.clone(), y.clone()]);
+    baseline.findings[0].name = Some("accepted".to_string());
+    baseline.findings[0].reason = Some("read off-chain".to_string());
+    baseline.findings.push(BaselineEntry::of_finding(
+        "Other",
+        "Unused Definition",
+        "template `Unused`",
+    ));
+
+    let comparison = baseline.compare("Main", &[out.clone(), z.clone()]);
+    assert_eq!(comparison.is_suppressed, vec![true, false]);
+    assert_eq!(comparison.num_new(), 1);
+    assert_eq!(comparison.suppressed.len(), 1);
+    assert_eq!(comparison.suppressed[0].label(), "accepted");
+    // The entries of the other templates are not reported as no longer found
+    assert_eq!(comparison.unmatched.len(), 1);
+    assert_eq!(comparison.unmatched[0].fingerprint, y.fingerprint);
+
+    let updated = baseline.update("Main", &[out.clone(), z.clone()]);
+    let fingerprints: Vec<&str> = updated
+        .findings
+        .iter()
+        .map(|entry| entry.fingerprint.as_str())
+        .collect();
+    assert_eq!(updated.findings.len(), 3);
+    assert!(fingerprints.contains(&out.fingerprint.as_str()));
+    assert!(fingerprints.contains(&z.fingerprint.as_str()));
+    assert!(!fingerprints.contains(&y.fingerprint.as_str()));
+    let kept = updated
+        .findings
+        .iter()
+        .find(|entry| entry.fingerprint == out.fingerprint)
+        .unwrap();
+    assert_eq!(kept.name.as_deref(), Some("accepted"));
+    assert_eq!(kept.reason.as_deref(), Some("read off-chain"));
+    assert!(updated
+        .findings
+        .iter()
+        .any(|entry| entry.template == "Other"));
+
+    // A run without findings removes the entries of its template only
+    let cleared = updated.update("Main", &[]);
+    assert_eq!(cleared.findings.len(), 1);
+    assert_eq!(cleared.findings[0].template, "Other");
+}
+
+#[test]
+fn test_baseline_save_and_load() {
+    let path = env::temp_dir().join("zkfuzz_baseline_test.json");
+    let mut baseline = Baseline::default().update("Main", &[unused_output("main.out")]);
+    baseline.findings[0].reason = Some("read off-chain".to_string());
+    baseline.save(&path).unwrap();
+
+    let json = fs::read_to_string(&path).unwrap();
+    assert!(json.contains("\"reason\": \"read off-chain\""));
+    assert!(!json.contains("\"name\""));
+    let loaded = Baseline::load(&path).unwrap();
+    assert_eq!(loaded.findings, baseline.findings);
+
+    fs::write(&path, "{\"version\": 2, \"findings\": []}").unwrap();
+    assert!(Baseline::load(&path).is_err());
+    fs::remove_file(&path).unwrap();
+}
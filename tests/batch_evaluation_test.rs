@@ -0,0 +1,174 @@
+mod utils;
+
+use std::str::FromStr;
+
+use num_bigint_dig::BigInt;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use rustc_hash::FxHashMap;
+
+use program_structure::ast::Expression;
+
+use zkfuzz::executor::symbolic_execution::SymbolicExecutor;
+use zkfuzz::executor::symbolic_setting::{
+    get_default_setting_for_concrete_execution, get_default_setting_for_symbolic_execution,
+};
+use zkfuzz::executor::symbolic_value::extract_variables;
+use zkfuzz::mutator::batch_evaluation::{
+    batch_brute_force_search, BatchEvaluator, CpuBatchEvaluator, EvaluationBackend, FieldProgram,
+};
+use zkfuzz::mutator::compiled_constraints::CompiledConstraints;
+use zkfuzz::mutator::input_spec::InputSpec;
+use zkfuzz::mutator::utils::{BaseVerificationConfig, CounterExample, VerificationResult};
+
+use crate::utils::{execute, prepare_symbolic_library};
+
+#[test]
+fn test_cpu_batch_evaluator_agrees_with_compiled_constraints() {
+    let prime = BigInt::from_str(
+        "21888242871839275222246405745257275088548364400416034343698204186575808495617",
+    )
+    .unwrap();
+
+    for path in [
+        "./tests/sample/test_if_else.circom",
+        "./tests/sample/test_lessthan.circom",
+        "./tests/sample/test_vuln_iszero.circom",
+        "./tests/sample/test_division_by_zero.circom",
+    ] {
+        let (mut symbolic_library, program_archive) =
+            prepare_symbolic_library(path.to_string(), prime.clone());
+        let setting = get_default_setting_for_symbolic_execution(prime.clone(), false);
+
+        let mut sexe = SymbolicExecutor::new(&mut symbolic_library, &setting);
+        execute(&mut sexe, &program_archive);
+
+        let symbolic_trace = sexe.cur_state.symbolic_trace.clone();
+        let side_constraints = sexe.cur_state.side_constraints.clone();
+        let mut variables = extract_variables(&symbolic_trace);
+        for var in extract_variables(&side_constraints) {
+            if !variables.contains(&var) {
+                variables.push(var);
+            }
+        }
+
+        let program = FieldProgram::lower(&symbolic_trace, &side_constraints, &variables, &prime);
+        assert_eq!(
+            program.num_lowered()
+                + program.num_unlowered_trace
+                + program.num_unlowered_side_constraints,
+            symbolic_trace.len() + side_constraints.len(),
+            "{}",
+            path
+        );
+        let compiled_trace = CompiledConstraints::compile(&symbolic_trace);
+        let compiled_side_constraints = CompiledConstraints::compile(&side_constraints);
+        let mut evaluator = CpuBatchEvaluator::new(program.clone());
+
+        let mut rng = StdRng::seed_from_u64(0);
+        let assignments: Vec<BigInt> = (0..200 * variables.len())
+            .map(|_| {
+                let v = BigInt::from(rng.gen_range(-3, 4));
+                ((v % &prime) + &prime) % &prime
+            })
+            .collect();
+        let verdicts = evaluator.evaluate_batch(&assignments);
+        assert_eq!(verdicts.len(), 200, "{}", path);
+
+        for (values, (is_satisfy_st, is_satisfy_sc)) in
+            assignments.chunks(variables.len()).zip(verdicts)
+        {
+            let assignment: FxHashMap<_, _> = variables
+                .iter()
+                .cloned()
+                .zip(values.iter().cloned())
+                .collect();
+            let expected_st = compiled_trace.evaluate(&prime, &assignment, sexe.symbolic_library);
+            let expected_sc =
+                compiled_side_constraints.evaluate(&prime, &assignment, sexe.symbolic_library);
+            // A violated lowered constraint is always a violation, and the verdicts agree if every
+            // constraint is lowered.
+            if !is_satisfy_st || program.num_unlowered_trace == 0 {
+                assert_eq!(is_satisfy_st, expected_st, "{}", path);
+            }
+            if !is_satisfy_sc || program.num_unlowered_side_constraints == 0 {
+                assert_eq!(is_satisfy_sc, expected_sc, "{}", path);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_batch_brute_force_search() {
+    let path = "./tests/sample/test_vuln_iszero.circom".to_string();
+    let prime = BigInt::from_str(
+        "21888242871839275222246405745257275088548364400416034343698204186575808495617",
+    )
+    .unwrap();
+
+    let (mut symbolic_library, program_archive) = prepare_symbolic_library(path, prime.clone());
+    let setting = get_default_setting_for_symbolic_execution(prime.clone(), false);
+
+    let mut sexe = SymbolicExecutor::new(&mut symbolic_library, &setting);
+    execute(&mut sexe, &program_archive);
+
+    let (main_template_name, template_param_names, template_param_values) =
+        match &program_archive.initial_template_call {
+            Expression::Call { id, args, .. } => {
+                let template = &program_archive.templates[id];
+                (id, template.get_name_of_params().clone(), args.clone())
+            }
+            _ => unimplemented!(),
+        };
+
+    let verification_setting = BaseVerificationConfig {
+        target_template_name: main_template_name.to_string(),
+        prime: prime.clone(),
+        range: BigInt::from(100),
+        quick_mode: true,
+        heuristics_mode: false,
+        progress_interval: 10000,
+        template_param_names: template_param_names,
+        template_param_values: template_param_values,
+        check_output_tags: false,
+        max_counterexamples: 1,
+        input_shapes: FxHashMap::default(),
+        input_spec: InputSpec::default(),
+    };
+
+    let symbolic_trace = sexe.cur_state.symbolic_trace.clone();
+    let side_constraints = sexe.cur_state.side_constraints.clone();
+    let conc_setting = get_default_setting_for_concrete_execution(prime, false);
+    let mut conc_executor = SymbolicExecutor::new(&mut sexe.symbolic_library, &conc_setting);
+    let counter_example = batch_brute_force_search(
+        &mut conc_executor,
+        &symbolic_trace,
+        &side_constraints,
+        &verification_setting,
+        &FxHashMap::default(),
+        None,
+        EvaluationBackend::Cpu,
+    );
+
+    assert!(matches!(
+        counter_example,
+        Some(CounterExample {
+            flag: VerificationResult::UnderConstrained(..),
+            ..
+        })
+    ));
+}
+
+#[test]
+fn test_evaluation_backend_from_str() {
+    assert_eq!(
+        EvaluationBackend::from_str("cpu"),
+        Ok(EvaluationBackend::Cpu)
+    );
+    assert_eq!(
+        EvaluationBackend::from_str("GPU"),
+        Ok(EvaluationBackend::Gpu)
+    );
+    assert!(EvaluationBackend::from_str("tpu").is_err());
+    assert!(EvaluationBackend::Cpu.is_available());
+}
@@ -0,0 +1,222 @@
+mod utils;
+
+use std::env;
+use std::fs;
+use std::process::{self, Command};
+use std::rc::Rc;
+use std::str::FromStr;
+
+use num_bigint_dig::BigInt;
+use rustc_hash::FxHashMap;
+use serde_json::json;
+
+use program_structure::ast::{ExpressionInfixOpcode, ExpressionPrefixOpcode};
+
+use zkfuzz::executor::debug_ast::{
+    DebuggableExpressionInfixOpcode, DebuggableExpressionPrefixOpcode,
+};
+use zkfuzz::executor::symbolic_execution::SymbolicExecutor;
+use zkfuzz::executor::symbolic_setting::get_default_setting_for_symbolic_execution;
+use zkfuzz::executor::symbolic_value::{evaluate_binary_op, SymbolicLibrary, SymbolicValue};
+use zkfuzz::mutator::circom_validation::{compile_with_circom, parse_sym, run_witness_calculator};
+use zkfuzz::mutator::compiled_constraints::CompiledConstraints;
+use zkfuzz::mutator::utils::evaluate_constraints;
+
+use crate::utils::{execute, prepare_symbolic_library};
+
+const BN128: &str = "21888242871839275222246405745257275088548364400416034343698204186575808495617";
+const MINUS_ONE: &str =
+    "21888242871839275222246405745257275088548364400416034343698204186575808495616";
+
+/// `(p - 1) >> 1`, which is also `p / 2`
+const HALF: &str = "10944121435919637611123202872628637544274182200208017171849102093287904247808";
+/// `2^253`, the highest bit of the 254 bits of bn128
+const TOP_BIT: &str =
+    "14474011154664524427946373126085988481658748083205070504932198000989141204992";
+/// `(2^254 - 1) mod p`, i.e. `~0`
+const ALL_BITS: &str =
+    "7059779437489773633646340506914701874769131765994106666166191815402473914366";
+/// `((p - 1) << 1) & (2^254 - 1)`, i.e. `-1 << 1`
+const MINUS_ONE_SHL: &str =
+    "14828463434349501588600065238342573213779232634421927677532012371173334581248";
+
+fn big(v: &str) -> BigInt {
+    BigInt::from_str(v).unwrap()
+}
+
+/// The results of the witness generator of circom on bn128, as `lhs op rhs = result`, derived
+/// from its semantics of the operators. Those of the outputs of `test_bitwise_semantics.circom`
+/// are checked against circom itself by `test_bitwise_semantics_against_circom`.
+const BN128_CASES: [(&str, ExpressionInfixOpcode, &str, &str); 18] = [
+    (MINUS_ONE, ExpressionInfixOpcode::ShiftR, "1", HALF),
+    ("-1", ExpressionInfixOpcode::ShiftR, "1", HALF),
+    ("3", ExpressionInfixOpcode::ShiftL, "253", TOP_BIT),
+    ("1", ExpressionInfixOpcode::ShiftL, "254", "0"),
+    ("1", ExpressionInfixOpcode::ShiftL, "1000", "0"),
+    ("5", ExpressionInfixOpcode::ShiftR, "254", "0"),
+    (TOP_BIT, ExpressionInfixOpcode::ShiftL, "1", "0"),
+    ("-1", ExpressionInfixOpcode::ShiftL, "1", MINUS_ONE_SHL),
+    // Amounts above `p / 2` stand for negative ones, which shift the other way
+    ("6", ExpressionInfixOpcode::ShiftR, "-1", "12"),
+    ("6", ExpressionInfixOpcode::ShiftL, "-1", "3"),
+    ("6", ExpressionInfixOpcode::ShiftL, MINUS_ONE, "3"),
+    ("1", ExpressionInfixOpcode::ShiftR, "-254", "0"),
+    // `p / 2` itself is a positive amount
+    ("1", ExpressionInfixOpcode::ShiftL, HALF, "0"),
+    ("-1", ExpressionInfixOpcode::BitAnd, "1", "0"),
+    ("-1", ExpressionInfixOpcode::BitOr, "1", "0"),
+    ("-1", ExpressionInfixOpcode::BitXor, "-2", "536870911"),
+    ("-1", ExpressionInfixOpcode::BitAnd, "-1", MINUS_ONE),
+    ("12", ExpressionInfixOpcode::BitXor, "10", "6"),
+];
+
+/// The outputs of `tests/sample/test_bitwise_semantics.circom`.
+const CIRCUIT_OUTPUTS: [&str; 8] = [
+    HALF,
+    TOP_BIT,
+    "0",
+    "12",
+    ALL_BITS,
+    "0",
+    "536870911",
+    MINUS_ONE_SHL,
+];
+
+#[test]
+fn test_bitwise_semantics_of_bn128() {
+    let prime = big(BN128);
+    let mut symbolic_library = SymbolicLibrary::default();
+    for (lhs, op, rhs, expected) in BN128_CASES {
+        let (lhs, rhs, expected) = (big(lhs), big(rhs), big(expected));
+        let result = evaluate_binary_op(
+            &SymbolicValue::ConstantInt(lhs.clone()),
+            &SymbolicValue::ConstantInt(rhs.clone()),
+            &prime,
+            &DebuggableExpressionInfixOpcode(op),
+        );
+        assert_eq!(
+            result,
+            SymbolicValue::ConstantInt(expected.clone()),
+            "{} {:?} {}",
+            lhs,
+            DebuggableExpressionInfixOpcode(op),
+            rhs
+        );
+
+        // The interpreter and the compiled tapes of the search agree with the executor
+        let constraints = vec![Rc::new(SymbolicValue::BinaryOp(
+            Rc::new(SymbolicValue::BinaryOp(
+                Rc::new(SymbolicValue::ConstantInt(lhs)),
+                DebuggableExpressionInfixOpcode(op),
+                Rc::new(SymbolicValue::ConstantInt(rhs)),
+            )),
+            DebuggableExpressionInfixOpcode(ExpressionInfixOpcode::Eq),
+            Rc::new(SymbolicValue::ConstantInt(expected)),
+        ))];
+        let assignment = FxHashMap::default();
+        assert!(evaluate_constraints(
+            &prime,
+            &constraints,
+            &assignment,
+            &mut symbolic_library
+        ));
+        assert!(CompiledConstraints::compile(&constraints).evaluate(
+            &prime,
+            &assignment,
+            &mut symbolic_library
+        ));
+    }
+
+    let complement = |v: &str| {
+        SymbolicValue::UnaryOp(
+            DebuggableExpressionPrefixOpcode(ExpressionPrefixOpcode::Complement),
+            Rc::new(SymbolicValue::ConstantInt(big(v))),
+        )
+    };
+    for (v, expected) in [
+        ("0", ALL_BITS),
+        (
+            "-1",
+            "7059779437489773633646340506914701874769131765994106666166191815402473914367",
+        ),
+    ] {
+        let constraints = vec![Rc::new(SymbolicValue::BinaryOp(
+            Rc::new(complement(v)),
+            DebuggableExpressionInfixOpcode(ExpressionInfixOpcode::Eq),
+            Rc::new(SymbolicValue::ConstantInt(big(expected))),
+        ))];
+        let assignment = FxHashMap::default();
+        assert!(evaluate_constraints(
+            &prime,
+            &constraints,
+            &assignment,
+            &mut symbolic_library
+        ));
+        assert!(CompiledConstraints::compile(&constraints).evaluate(
+            &prime,
+            &assignment,
+            &mut symbolic_library
+        ));
+    }
+}
+
+#[test]
+fn test_bitwise_semantics_of_circuit() {
+    let prime = big(BN128);
+    let path = "./tests/sample/test_bitwise_semantics.circom".to_string();
+    let (mut symbolic_library, program_archive) = prepare_symbolic_library(path, prime.clone());
+    let setting = get_default_setting_for_symbolic_execution(prime, false);
+    let mut sexe = SymbolicExecutor::new(&mut symbolic_library, &setting);
+    execute(&mut sexe, &program_archive);
+
+    let values: Vec<BigInt> = sexe
+        .cur_state
+        .symbolic_trace
+        .iter()
+        .filter_map(|constraint| match &**constraint {
+            SymbolicValue::AssignEq(_, rhs) => match &**rhs {
+                SymbolicValue::ConstantInt(v) => Some(v.clone()),
+                _ => None,
+            },
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        values,
+        CIRCUIT_OUTPUTS.iter().map(|v| big(v)).collect::<Vec<_>>()
+    );
+}
+
+/// Compares the outputs of the circuit with the witness computed by circom, which requires
+/// `circom` and `node`. The test passes without checking anything if circom is not installed.
+#[test]
+fn test_bitwise_semantics_against_circom() {
+    if Command::new("circom").arg("--version").output().is_err() {
+        eprintln!("circom is not installed, skipping the comparison with its witness generator");
+        return;
+    }
+
+    let out_dir = env::temp_dir().join(format!("zkfuzz_bitwise_semantics_{}", process::id()));
+    let (_, sym_path, wasm_path) = compile_with_circom(
+        "circom",
+        "./tests/sample/test_bitwise_semantics.circom",
+        &[],
+        "bn128",
+        &out_dir,
+    )
+    .unwrap();
+    let wires = parse_sym(&fs::read_to_string(sym_path).unwrap());
+    let witness = run_witness_calculator(&wasm_path, &json!({}))
+        .unwrap()
+        .expect("circom rejected the circuit");
+    let _ = fs::remove_dir_all(&out_dir);
+
+    for (i, expected) in CIRCUIT_OUTPUTS.iter().enumerate() {
+        assert_eq!(
+            witness[wires[&format!("main.out[{}]", i)]],
+            big(expected),
+            "out[{}]",
+            i
+        );
+    }
+}
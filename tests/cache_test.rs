@@ -0,0 +1,100 @@
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde_json::json;
+
+use zkfuzz::cache_user::{CachedResult, ResultCache};
+
+fn write_project(name: &str) -> PathBuf {
+    let dir = env::temp_dir().join(format!("zkfuzz_cache_test_{}", name));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(
+        dir.join("main.circom"),
+        "pragma circom 2.0.0;\ninclude \"lib.circom\";\ncomponent main = Double();\n",
+    )
+    .unwrap();
+    fs::write(
+        dir.join("lib.circom"),
+        "template Double() {\n    signal input a;\n    signal output b;\n    b <== 2 * a;\n}\n",
+    )
+    .unwrap();
+    fs::write(dir.join("unrelated.circom"), "template Unused() {}\n").unwrap();
+    dir
+}
+
+fn cache_key(dir: &Path, args: &[&str]) -> String {
+    let args: Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
+    ResultCache::new(&dir.join("cache"), &dir.join("main.circom"), &[], &args)
+        .unwrap()
+        .key
+}
+
+#[test]
+fn test_cache_key() {
+    let dir = write_project("key");
+    let key = cache_key(&dir, &["fuzz", "--search_mode", "quick"]);
+    assert_eq!(key, cache_key(&dir, &["fuzz", "--search_mode", "quick"]));
+
+    // The options that only affect where the results are written are not part of the key.
+    assert_eq!(
+        key,
+        cache_key(
+            &dir,
+            &[
+                "fuzz",
+                "--no-cache",
+                "--search_mode",
+                "quick",
+                "--path_to_summary",
+                "summary.json",
+                "--cache_dir=elsewhere",
+            ]
+        )
+    );
+    assert_ne!(key, cache_key(&dir, &["fuzz", "--search_mode", "ga"]));
+
+    // Editing a file the circuit does not include keeps the key.
+    fs::write(
+        dir.join("unrelated.circom"),
+        "template Unused() { signal x; }\n",
+    )
+    .unwrap();
+    assert_eq!(key, cache_key(&dir, &["fuzz", "--search_mode", "quick"]));
+
+    // Editing an included file invalidates it.
+    fs::write(
+        dir.join("lib.circom"),
+        "template Double() {\n    signal input a;\n    signal output b;\n    b <-- 2 * a;\n}\n",
+    )
+    .unwrap();
+    assert_ne!(key, cache_key(&dir, &["fuzz", "--search_mode", "quick"]));
+}
+
+#[test]
+fn test_cache_roundtrip() {
+    let dir = write_project("roundtrip");
+    let args = vec!["fuzz".to_string()];
+    let cache = ResultCache::new(&dir.join("cache"), &dir.join("main.circom"), &[], &args).unwrap();
+    assert!(cache.load().is_none());
+
+    cache
+        .store(&CachedResult {
+            verdict: "NoCounterExample".to_string(),
+            report: vec![" ├─ Prime Number      : 7".to_string()],
+            execution_time_ms: 42,
+            symbolic_trace: vec!["(AssignEq main.b (Mul 2 main.a))".to_string()],
+            summary: json!({"verdict": "NoCounterExample"}),
+            report_html: None,
+        })
+        .unwrap();
+    let cached = cache.load().unwrap();
+    assert_eq!(cached.verdict, "NoCounterExample");
+    assert_eq!(cached.report.len(), 1);
+    assert_eq!(cached.execution_time_ms, 42);
+    assert_eq!(cached.symbolic_trace.len(), 1);
+    assert_eq!(cached.summary["verdict"], "NoCounterExample");
+    assert!(cached.report_html.is_none());
+    assert!(cache.path().starts_with(dir.join("cache")));
+}
@@ -0,0 +1,191 @@
+use std::str::FromStr;
+
+use num_bigint_dig::BigInt;
+use rustc_hash::FxHashMap;
+
+use zkfuzz::mutator::circom_inspect::{parse_inspect_warnings, InspectCrossCheck};
+use zkfuzz::mutator::circom_validation::{
+    judge_counterexample, parse_r1cs, parse_sym, ValidationStatus,
+};
+use zkfuzz::mutator::groth16_poc::write_wtns;
+use zkfuzz::mutator::utils::{
+    CounterExample, UnderConstrainedType, VerificationResult, ViolatedConstraint,
+};
+
+const SYM: &str = "1,1,0,main.out\n2,2,0,main.in\n3,-1,0,main.tmp\n";
+
+fn bn128() -> BigInt {
+    BigInt::from_str(
+        "21888242871839275222246405745257275088548364400416034343698204186575808495617",
+    )
+    .unwrap()
+}
+
+fn push_field(bytes: &mut Vec<u8>, value: &BigInt) {
+    let (_, mut le) = value.to_bytes_le();
+    le.resize(32, 0);
+    bytes.extend(le);
+}
+
+fn push_term(bytes: &mut Vec<u8>, wire: u32) {
+    bytes.extend(1u32.to_le_bytes());
+    bytes.extend(wire.to_le_bytes());
+    push_field(bytes, &BigInt::from(1));
+}
+
+/// The R1CS of `out <== in * in` over the wires `[1, out, in]`, or of `out <-- in * in` if
+/// `constrained` is false.
+fn square_r1cs(constrained: bool) -> Vec<u8> {
+    let num_constraints: u32 = if constrained { 1 } else { 0 };
+    let mut header = Vec::new();
+    header.extend(32u32.to_le_bytes());
+    push_field(&mut header, &bn128());
+    for n in [3u32, 1, 0, 1] {
+        header.extend(n.to_le_bytes());
+    }
+    header.extend(3u64.to_le_bytes());
+    header.extend(num_constraints.to_le_bytes());
+
+    let mut constraints = Vec::new();
+    if constrained {
+        push_term(&mut constraints, 2);
+        push_term(&mut constraints, 2);
+        push_term(&mut constraints, 1);
+    }
+
+    let mut bytes = b"r1cs".to_vec();
+    bytes.extend(1u32.to_le_bytes());
+    bytes.extend(2u32.to_le_bytes());
+    for (section_type, section) in [(1u32, header), (2u32, constraints)] {
+        bytes.extend(section_type.to_le_bytes());
+        bytes.extend((section.len() as u64).to_le_bytes());
+        bytes.extend(section);
+    }
+    bytes
+}
+
+fn counter_example(flag: VerificationResult) -> CounterExample {
+    CounterExample {
+        flag: flag,
+        target_output: None,
+        assignment: FxHashMap::default(),
+    }
+}
+
+#[test]
+fn test_parse_r1cs_and_sym() {
+    let r1cs = parse_r1cs(&square_r1cs(true)).unwrap();
+    assert_eq!(r1cs.prime, bn128());
+    assert_eq!(r1cs.num_wires, 3);
+    assert_eq!(r1cs.num_outputs, 1);
+    assert_eq!(r1cs.constraints.len(), 1);
+
+    let ok = vec![BigInt::from(1), BigInt::from(4), BigInt::from(2)];
+    let ko = vec![BigInt::from(1), BigInt::from(5), BigInt::from(2)];
+    assert_eq!(r1cs.first_violated(&ok), None);
+    assert_eq!(r1cs.first_violated(&ko), Some(0));
+
+    assert!(parse_r1cs(b"not an r1cs").is_err());
+
+    let wires = parse_sym(SYM);
+    assert_eq!(wires.len(), 2);
+    assert_eq!(wires["main.out"], 1);
+    assert_eq!(wires["main.in"], 2);
+}
+
+#[test]
+fn test_judge_counterexample() {
+    let wires = parse_sym(SYM);
+    let honest_witness = vec![BigInt::from(1), BigInt::from(4), BigInt::from(2)];
+    let ce_values = FxHashMap::from_iter([
+        ("main.in".to_string(), BigInt::from(2)),
+        ("main.out".to_string(), BigInt::from(5)),
+    ]);
+    let ce = counter_example(VerificationResult::UnderConstrained(
+        UnderConstrainedType::UnusedOutput,
+    ));
+
+    let unconstrained = parse_r1cs(&square_r1cs(false)).unwrap();
+    let validation = judge_counterexample(
+        &ce,
+        &unconstrained,
+        &wires,
+        Some(&honest_witness),
+        &ce_values,
+    );
+    assert_eq!(validation.status, ValidationStatus::Confirmed);
+    assert_eq!(validation.label(), "CONFIRMED");
+    assert!(validation.reason.contains("main.out"));
+
+    let constrained = parse_r1cs(&square_r1cs(true)).unwrap();
+    let validation =
+        judge_counterexample(&ce, &constrained, &wires, Some(&honest_witness), &ce_values);
+    assert_eq!(validation.status, ValidationStatus::Unconfirmed);
+    assert_eq!(validation.label(), "UNCONFIRMED");
+
+    let assertion = counter_example(VerificationResult::AssertionFailure(
+        "Main (line 3)".to_string(),
+        ViolatedConstraint {
+            formatted: "in != 2".to_string(),
+            structure: 0,
+        },
+    ));
+    let validation = judge_counterexample(&assertion, &constrained, &wires, None, &ce_values);
+    assert_eq!(validation.status, ValidationStatus::Confirmed);
+    let validation = judge_counterexample(
+        &assertion,
+        &constrained,
+        &wires,
+        Some(&honest_witness),
+        &ce_values,
+    );
+    assert_eq!(validation.status, ValidationStatus::Unconfirmed);
+}
+
+#[test]
+fn test_circom_inspect_cross_check() {
+    let output = "\x1b[33mwarning[CA01]\x1b[0m: In template \"Main(2)\": Local signal out does not appear in any constraint\n   ┌─ \"circuit.circom\":7:5\n   │\n 7 │     signal output out;\n   │     ^^^^^^^^^^^^^^^^^^ out\n\nwarning[CA02]: In template \"Main(2)\": Subcomponent input/output signal n2b.in does not appear in any constraint of the father component\n";
+    let warnings = parse_inspect_warnings(output);
+    assert_eq!(warnings.len(), 2);
+    assert_eq!(warnings[0].code, "CA01");
+    assert_eq!(warnings[0].template.as_deref(), Some("Main"));
+    assert_eq!(warnings[0].signal.as_deref(), Some("out"));
+    assert_eq!(warnings[0].line, Some(7));
+    assert_eq!(warnings[1].signal.as_deref(), Some("n2b.in"));
+    assert_eq!(warnings[1].line, None);
+
+    let findings = vec![
+        "UnderConstrained-NonDeterministic: main.out[1]".to_string(),
+        "Division Issue: main.inv may divide by zero".to_string(),
+    ];
+    let cross_check = InspectCrossCheck::new(warnings.clone(), &findings);
+    assert_eq!(cross_check.agreements.len(), 1);
+    assert_eq!(cross_check.agreements[0].0, warnings[0]);
+    assert_eq!(cross_check.agreements[0].1, findings[0]);
+    assert_eq!(cross_check.circom_only, vec![warnings[1].clone()]);
+    assert_eq!(cross_check.zkfuzz_only, vec![findings[1].clone()]);
+}
+
+#[test]
+fn test_write_wtns() {
+    let prime = bn128();
+    let witness = vec![BigInt::from(1), BigInt::from(9), BigInt::from(-3)];
+    let bytes = write_wtns(&witness, &prime);
+
+    let mut expected = Vec::new();
+    expected.extend(b"wtns");
+    expected.extend(2u32.to_le_bytes());
+    expected.extend(2u32.to_le_bytes());
+    expected.extend(1u32.to_le_bytes());
+    expected.extend(40u64.to_le_bytes());
+    expected.extend(32u32.to_le_bytes());
+    push_field(&mut expected, &prime);
+    expected.extend(3u32.to_le_bytes());
+    expected.extend(2u32.to_le_bytes());
+    expected.extend(96u64.to_le_bytes());
+    push_field(&mut expected, &BigInt::from(1));
+    push_field(&mut expected, &BigInt::from(9));
+    // The values are reduced to [0, p)
+    push_field(&mut expected, &(&prime - BigInt::from(3)));
+    assert_eq!(bytes, expected);
+}
@@ -0,0 +1,137 @@
+mod utils;
+
+use std::str::FromStr;
+
+use num_bigint_dig::BigInt;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use rustc_hash::FxHashMap;
+
+use zkfuzz::executor::symbolic_execution::SymbolicExecutor;
+use zkfuzz::executor::symbolic_setting::get_default_setting_for_symbolic_execution;
+use zkfuzz::executor::symbolic_value::extract_variables;
+use zkfuzz::mutator::compiled_constraints::CompiledConstraints;
+use zkfuzz::mutator::constraint_scheduler::ConstraintScheduler;
+use zkfuzz::mutator::utils::evaluate_constraints;
+
+use crate::utils::{execute, prepare_symbolic_library};
+
+#[test]
+fn test_compiled_constraints_agree_with_evaluate_constraints() {
+    let prime = BigInt::from_str(
+        "21888242871839275222246405745257275088548364400416034343698204186575808495617",
+    )
+    .unwrap();
+
+    for path in [
+        "./tests/sample/test_if_else.circom",
+        "./tests/sample/test_lessthan.circom",
+        "./tests/sample/test_vuln_iszero.circom",
+        "./tests/sample/test_division_by_zero.circom",
+    ] {
+        let (mut symbolic_library, program_archive) =
+            prepare_symbolic_library(path.to_string(), prime.clone());
+        let setting = get_default_setting_for_symbolic_execution(prime.clone(), false);
+
+        let mut sexe = SymbolicExecutor::new(&mut symbolic_library, &setting);
+        execute(&mut sexe, &program_archive);
+
+        let symbolic_trace = sexe.cur_state.symbolic_trace.clone();
+        let side_constraints = sexe.cur_state.side_constraints.clone();
+        let compiled_trace = CompiledConstraints::compile(&symbolic_trace);
+        let compiled_side_constraints = CompiledConstraints::compile(&side_constraints);
+        assert_eq!(compiled_trace.len(), symbolic_trace.len());
+        assert!(compiled_trace.num_compiled() > 0);
+
+        let mut variables = extract_variables(&symbolic_trace);
+        variables.append(&mut extract_variables(&side_constraints));
+
+        let mut rng = StdRng::seed_from_u64(0);
+        for _ in 0..200 {
+            let assignment: FxHashMap<_, _> = variables
+                .iter()
+                .map(|v| (v.clone(), BigInt::from(rng.gen_range(-3, 4))))
+                .collect();
+            assert_eq!(
+                compiled_trace.evaluate(&prime, &assignment, sexe.symbolic_library),
+                evaluate_constraints(&prime, &symbolic_trace, &assignment, sexe.symbolic_library),
+                "{}",
+                path
+            );
+            assert_eq!(
+                compiled_side_constraints.evaluate(&prime, &assignment, sexe.symbolic_library),
+                evaluate_constraints(
+                    &prime,
+                    &side_constraints,
+                    &assignment,
+                    sexe.symbolic_library
+                ),
+                "{}",
+                path
+            );
+        }
+    }
+}
+
+#[test]
+fn test_constraint_scheduler_agrees_with_compiled_constraints() {
+    let prime = BigInt::from_str(
+        "21888242871839275222246405745257275088548364400416034343698204186575808495617",
+    )
+    .unwrap();
+
+    for path in [
+        "./tests/sample/test_lessthan.circom",
+        "./tests/sample/test_vuln_iszero.circom",
+    ] {
+        let (mut symbolic_library, program_archive) =
+            prepare_symbolic_library(path.to_string(), prime.clone());
+        let setting = get_default_setting_for_symbolic_execution(prime.clone(), false);
+
+        let mut sexe = SymbolicExecutor::new(&mut symbolic_library, &setting);
+        execute(&mut sexe, &program_archive);
+
+        let symbolic_trace = sexe.cur_state.symbolic_trace.clone();
+        let side_constraints = sexe.cur_state.side_constraints.clone();
+        let compiled_trace = CompiledConstraints::compile(&symbolic_trace);
+        let compiled_side_constraints = CompiledConstraints::compile(&side_constraints);
+        let mut scheduler = ConstraintScheduler::new(&symbolic_trace, &side_constraints);
+
+        let mut variables = extract_variables(&symbolic_trace);
+        variables.append(&mut extract_variables(&side_constraints));
+
+        // Enough candidates for the constraints to be reordered several times
+        let mut rng = StdRng::seed_from_u64(0);
+        for _ in 0..1000 {
+            let assignment: FxHashMap<_, _> = variables
+                .iter()
+                .map(|v| (v.clone(), BigInt::from(rng.gen_range(-3, 4))))
+                .collect();
+            assert_eq!(
+                scheduler.evaluate(&prime, &assignment, sexe.symbolic_library),
+                (
+                    compiled_trace.evaluate(&prime, &assignment, sexe.symbolic_library),
+                    compiled_side_constraints.evaluate(&prime, &assignment, sexe.symbolic_library)
+                ),
+                "{}",
+                path
+            );
+        }
+
+        assert_eq!(scheduler.num_candidates(), 1000);
+        assert_eq!(
+            scheduler.num_constraints(),
+            symbolic_trace.len() + side_constraints.len()
+        );
+        assert!(scheduler.average_evaluated() <= scheduler.num_constraints() as f64);
+        let mut trace_order = scheduler.trace_order().to_vec();
+        trace_order.sort();
+        assert_eq!(trace_order, (0..symbolic_trace.len()).collect::<Vec<_>>());
+        let mut side_constraint_order = scheduler.side_constraint_order().to_vec();
+        side_constraint_order.sort();
+        assert_eq!(
+            side_constraint_order,
+            (0..side_constraints.len()).collect::<Vec<_>>()
+        );
+    }
+}
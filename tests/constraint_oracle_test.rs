@@ -0,0 +1,148 @@
+mod utils;
+
+use std::str::FromStr;
+
+use num_bigint_dig::BigInt;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use rustc_hash::FxHashMap;
+
+use program_structure::ast::Expression;
+
+use zkfuzz::executor::symbolic_execution::SymbolicExecutor;
+use zkfuzz::executor::symbolic_setting::{
+    get_default_setting_for_concrete_execution, get_default_setting_for_symbolic_execution,
+};
+use zkfuzz::mutator::constraint_oracle::{Assignment, ConstraintOracle};
+use zkfuzz::mutator::input_spec::InputSpec;
+use zkfuzz::mutator::utils::{is_vulnerable, verify_assignment, BaseVerificationConfig};
+use zkfuzz::type_analysis_user::analyse_project;
+
+use crate::utils::{execute, parse_project, prepare_symbolic_library};
+
+#[test]
+fn test_constraint_oracle_agrees_with_verify_assignment() {
+    let prime = BigInt::from_str(
+        "21888242871839275222246405745257275088548364400416034343698204186575808495617",
+    )
+    .unwrap();
+
+    for path in [
+        "./tests/sample/test_vuln_iszero.circom",
+        "./tests/sample/test_unconstrained_output.circom",
+        "./tests/sample/test_lessthan.circom",
+    ] {
+        let (mut symbolic_library, program_archive) =
+            prepare_symbolic_library(path.to_string(), prime.clone());
+        let setting = get_default_setting_for_symbolic_execution(prime.clone(), false);
+        let mut sexe = SymbolicExecutor::new(&mut symbolic_library, &setting);
+        execute(&mut sexe, &program_archive);
+        let symbolic_trace = sexe.cur_state.symbolic_trace.clone();
+        let side_constraints = sexe.cur_state.side_constraints.clone();
+
+        let (template_name, template_param_names, template_param_values) =
+            match &program_archive.initial_template_call {
+                Expression::Call { id, args, .. } => (
+                    id.clone(),
+                    program_archive.templates[id].get_name_of_params().clone(),
+                    args.clone(),
+                ),
+                _ => unreachable!(),
+            };
+        let base_config = BaseVerificationConfig {
+            target_template_name: template_name,
+            prime: prime.clone(),
+            range: BigInt::from(100),
+            quick_mode: false,
+            heuristics_mode: false,
+            progress_interval: 10000,
+            template_param_names: template_param_names,
+            template_param_values: template_param_values,
+            check_output_tags: false,
+            max_counterexamples: 1,
+            input_shapes: FxHashMap::default(),
+            input_spec: InputSpec::default(),
+        };
+
+        let mut oracle = ConstraintOracle::new(
+            symbolic_library.clone(),
+            &symbolic_trace,
+            &side_constraints,
+            base_config.clone(),
+        );
+        let mut rng = StdRng::seed_from_u64(0);
+        let assignments: Vec<Assignment> = (0..100)
+            .map(|_| {
+                oracle
+                    .variables()
+                    .iter()
+                    .map(|var| {
+                        let v = BigInt::from(rng.gen_range(-2, 3));
+                        (var.clone(), ((v % &prime) + &prime) % &prime)
+                    })
+                    .collect()
+            })
+            .collect();
+        let verdicts = oracle.evaluate_batch(&assignments);
+        assert_eq!(verdicts.len(), assignments.len(), "{}", path);
+
+        let conc_setting = get_default_setting_for_concrete_execution(prime.clone(), false);
+        let mut conc_executor = SymbolicExecutor::new(&mut symbolic_library, &conc_setting);
+        for (assignment, verdict) in assignments.iter().zip(verdicts) {
+            let result = verify_assignment(
+                &mut conc_executor,
+                &symbolic_trace,
+                &side_constraints,
+                assignment,
+                &base_config,
+            );
+            assert_eq!(verdict.is_vulnerable(), is_vulnerable(&result), "{}", path);
+            assert_eq!(verdict.result.to_json(), result.to_json(), "{}", path);
+        }
+    }
+}
+
+#[test]
+fn test_constraint_oracle_from_program() {
+    let prime = BigInt::from_str(
+        "21888242871839275222246405745257275088548364400416034343698204186575808495617",
+    )
+    .unwrap();
+    let mut program_archive = parse_project(
+        "./tests/sample/test_vuln_iszero.circom".to_string(),
+        prime.clone(),
+    )
+    .unwrap();
+    analyse_project(&mut program_archive).unwrap();
+    let mut oracle = ConstraintOracle::from_program(&program_archive, &prime).unwrap();
+    let mut names = oracle.variable_names();
+    names.sort();
+    assert_eq!(names, vec!["main.in", "main.inv", "main.out"]);
+
+    let named = |values: [(&str, i64); 3]| -> FxHashMap<String, BigInt> {
+        values
+            .iter()
+            .map(|(name, value)| (name.to_string(), BigInt::from(*value)))
+            .collect()
+    };
+    // `inv` is not the inverse of `in`, yet `out` satisfies the constraints
+    let forged = oracle
+        .assignment_from_names(&named([("main.in", 5), ("main.inv", 0), ("main.out", 1)]))
+        .unwrap();
+    let honest = oracle
+        .assignment_from_names(&named([("main.in", 0), ("main.inv", 0), ("main.out", 1)]))
+        .unwrap();
+    let verdicts = oracle.evaluate_batch(&[forged, honest]);
+    assert!(!verdicts[0].satisfies_trace);
+    assert!(verdicts[0].satisfies_side_constraints);
+    assert!(verdicts[0].is_vulnerable());
+    assert!(verdicts[1].satisfies_trace);
+    assert!(verdicts[1].satisfies_side_constraints);
+    assert!(!verdicts[1].is_vulnerable());
+
+    let error = oracle
+        .assignment_from_names(&named([("main.in", 5), ("main.inv", 0), ("main.x", 1)]))
+        .unwrap_err();
+    assert!(error.contains("`main.x` is not a variable of the constraints"));
+    assert!(error.contains("`main.out` is not assigned"));
+}
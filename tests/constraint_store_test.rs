@@ -0,0 +1,62 @@
+mod utils;
+
+use std::env;
+use std::str::FromStr;
+
+use num_bigint_dig::BigInt;
+
+use zkfuzz::executor::constraint_store::{
+    new_constraint_store, ConstraintStore, DiskConstraintStore,
+};
+use zkfuzz::executor::symbolic_execution::SymbolicExecutor;
+use zkfuzz::executor::symbolic_setting::get_default_setting_for_symbolic_execution;
+
+use crate::utils::{execute, prepare_symbolic_library};
+
+#[test]
+fn test_disk_constraint_store() {
+    let prime = BigInt::from_str(
+        "21888242871839275222246405745257275088548364400416034343698204186575808495617",
+    )
+    .unwrap();
+
+    for path in [
+        "./tests/sample/test_vuln_iszero.circom",
+        "./tests/sample/test_lessthan.circom",
+        "./tests/sample/test_2d_array_component.circom",
+    ] {
+        let (mut symbolic_library, program_archive) =
+            prepare_symbolic_library(path.to_string(), prime.clone());
+        let setting = get_default_setting_for_symbolic_execution(prime.clone(), false);
+
+        let mut sexe = SymbolicExecutor::new(&mut symbolic_library, &setting);
+        execute(&mut sexe, &program_archive);
+
+        let mut store = DiskConstraintStore::new(&env::temp_dir()).unwrap();
+        for c in &sexe.cur_state.symbolic_trace {
+            store.push(c);
+        }
+        let backing_file = store.path().to_path_buf();
+        assert!(backing_file.exists());
+
+        assert_eq!(store.len(), sexe.cur_state.symbolic_trace.len());
+        assert_eq!(store.to_vec(), sexe.cur_state.symbolic_trace);
+        assert_eq!(store.get(store.len() - 1), sexe.cur_state.symbolic_trace.last().cloned());
+        assert_eq!(store.get(store.len()), None);
+
+        let iterated: Vec<_> = store.iter().collect();
+        assert_eq!(iterated, sexe.cur_state.symbolic_trace);
+
+        drop(store);
+        assert!(!backing_file.exists());
+    }
+}
+
+#[test]
+fn test_new_constraint_store() {
+    for kind in ["memory", "disk"] {
+        let store = new_constraint_store(kind).unwrap();
+        assert!(store.is_empty());
+    }
+    assert!(new_constraint_store("mmap").is_err());
+}
@@ -0,0 +1,406 @@
+mod utils;
+
+use std::env;
+use std::fs;
+use std::rc::Rc;
+use std::str::FromStr;
+
+use num_bigint_dig::BigInt;
+use rustc_hash::FxHashMap;
+use serde_json::{json, Value};
+
+use program_structure::ast::Expression;
+
+use zkfuzz::analysis_user::{analyze_source, SourceAnalysisOptions};
+use zkfuzz::executor::constraint_store::ConstraintStore;
+use zkfuzz::executor::symbolic_execution::SymbolicExecutor;
+use zkfuzz::executor::symbolic_setting::get_default_setting_for_symbolic_execution;
+use zkfuzz::executor::symbolic_value::{OwnerName, SymbolicName};
+use zkfuzz::mutator::detector::{Detector, DetectorRegistry, Finding, DEFAULT_DETECTORS};
+use zkfuzz::mutator::exploitability::{assess_exploitability, assessed_severity, Controllability};
+use zkfuzz::mutator::finding_stream::FindingStream;
+use zkfuzz::mutator::input_spec::InputSpec;
+use zkfuzz::mutator::severity::{format_severity_counts, Severity};
+use zkfuzz::mutator::utils::{
+    BaseVerificationConfig, CounterExample, UnderConstrainedType, VerificationResult,
+};
+
+use crate::utils::{execute, prepare_symbolic_library};
+
+struct TraceSizeDetector;
+
+impl Detector for TraceSizeDetector {
+    fn name(&self) -> &'static str {
+        "trace_size"
+    }
+
+    fn description(&self) -> &'static str {
+        "Reports the number of trace constraints"
+    }
+
+    fn heading(&self) -> &'static str {
+        "Trace Size"
+    }
+
+    fn run(
+        &self,
+        _sexe: &mut SymbolicExecutor,
+        symbolic_trace: &dyn ConstraintStore,
+        _side_constraints: &dyn ConstraintStore,
+        _base_config: &BaseVerificationConfig,
+    ) -> Vec<Finding> {
+        vec![Finding {
+            category: "Trace Size",
+            message: symbolic_trace.len().to_string(),
+            severity: Severity::Info,
+            counter_example: None,
+        }]
+    }
+}
+
+fn run_detectors(path: &str, names: &str) -> Vec<Finding> {
+    let prime = BigInt::from_str(
+        "21888242871839275222246405745257275088548364400416034343698204186575808495617",
+    )
+    .unwrap();
+
+    let (mut symbolic_library, program_archive) =
+        prepare_symbolic_library(path.to_string(), prime.clone());
+    let setting = get_default_setting_for_symbolic_execution(prime.clone(), false);
+
+    let mut sexe = SymbolicExecutor::new(&mut symbolic_library, &setting);
+    execute(&mut sexe, &program_archive);
+
+    let (main_template_name, template_param_names, template_param_values) =
+        match &program_archive.initial_template_call {
+            Expression::Call { id, args, .. } => {
+                let template = &program_archive.templates[id];
+                (id, template.get_name_of_params().clone(), args.clone())
+            }
+            _ => unimplemented!(),
+        };
+    let verification_base_config = BaseVerificationConfig {
+        target_template_name: main_template_name.to_string(),
+        prime: prime.clone(),
+        range: BigInt::from(100),
+        quick_mode: false,
+        heuristics_mode: false,
+        progress_interval: 10000,
+        template_param_names: template_param_names,
+        template_param_values: template_param_values,
+        check_output_tags: false,
+        max_counterexamples: 1,
+        input_shapes: FxHashMap::default(),
+        input_spec: InputSpec::default(),
+    };
+
+    let mut registry = DetectorRegistry::default();
+    registry.register(Rc::new(TraceSizeDetector));
+    let symbolic_trace = sexe.cur_state.symbolic_trace.clone();
+    let side_constraints = sexe.cur_state.side_constraints.clone();
+    registry
+        .select(names)
+        .unwrap()
+        .iter()
+        .flat_map(|detector| {
+            detector.run(
+                &mut sexe,
+                &symbolic_trace,
+                &side_constraints,
+                &verification_base_config,
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn test_detector_registry_select() {
+    let mut registry = DetectorRegistry::default();
+    registry.register(Rc::new(TraceSizeDetector));
+
+    let names = |selected: Vec<Rc<dyn Detector>>| -> Vec<&'static str> {
+        selected.iter().map(|d| d.name()).collect()
+    };
+    assert_eq!(
+        names(registry.select(DEFAULT_DETECTORS).unwrap()),
+        vec!["unused_outputs", "component_boundaries"]
+    );
+    assert_eq!(
+        names(registry.select("custom:trace_size,unused_outputs").unwrap()),
+        vec!["trace_size", "unused_outputs"]
+    );
+    assert_eq!(registry.select("all,unused_outputs").unwrap().len(), 5);
+    assert!(registry.select("none").unwrap().is_empty());
+    assert_eq!(
+        registry.select("trace_size").err(),
+        Some("trace_size".to_string())
+    );
+    assert_eq!(registry.list().len(), 5);
+}
+
+#[test]
+fn test_builtin_detectors() {
+    let findings = run_detectors("./tests/sample/test_unused_output.circom", "unused_outputs");
+    assert_eq!(findings.len(), 1);
+    assert!(findings[0].counter_example.is_some());
+    assert_eq!(findings[0].severity, Severity::Critical);
+
+    let findings = run_detectors(
+        "./tests/sample/test_component_boundary.circom",
+        "component_boundaries",
+    );
+    assert_eq!(findings.len(), 2);
+    assert!(findings.iter().all(|f| f.counter_example.is_none()));
+    assert!(findings.iter().all(|f| f.severity == Severity::Medium));
+
+    let findings = run_detectors(
+        "./tests/sample/test_component_boundary.circom",
+        "custom:trace_size",
+    );
+    assert_eq!(findings.len(), 1);
+    assert_eq!(findings[0].category, "Trace Size");
+}
+
+#[test]
+fn test_comparison_range_checks_detector() {
+    let findings = run_detectors(
+        "./tests/sample/test_unchecked_comparison.circom",
+        "comparison_range_checks",
+    );
+    let messages: Vec<&str> = findings.iter().map(|f| f.message.as_str()).collect();
+    assert_eq!(
+        messages,
+        vec![
+            "Bits2Num(2) `main.b2n`: main.b2n.in[1] <- main.c (never range-checked)",
+            "LessThan(8) `main.lt1`: main.lt1.in[1] <- main.b (never range-checked)",
+            "LessThan(4) `main.lt2`: main.lt2.in[0] (may take 8 bits, more than 4)",
+        ]
+    );
+    assert!(findings.iter().all(|f| f.severity == Severity::Medium));
+}
+
+#[test]
+fn test_unconstrained_outputs_detector() {
+    let findings = run_detectors(
+        "./tests/sample/test_unconstrained_output.circom",
+        "unconstrained_outputs",
+    );
+    assert_eq!(findings.len(), 1);
+    assert_eq!(
+        findings[0].message,
+        "`main.sum` is assigned with `<--` but appears in no constraint"
+    );
+    assert_eq!(findings[0].severity, Severity::Critical);
+
+    // The counterexample changes `main.sum` while the side constraints still hold
+    let counter_example = findings[0].counter_example.as_ref().unwrap();
+    let output = counter_example.target_output.as_ref().unwrap();
+    match &counter_example.flag {
+        VerificationResult::UnderConstrained(UnderConstrainedType::NonDeterministic(
+            name,
+            _,
+            expected,
+        )) => {
+            assert_eq!(name, output);
+            assert_ne!(&counter_example.assignment[output], expected);
+        }
+        _ => panic!("expected a non-deterministic output"),
+    }
+
+    let findings = run_detectors(
+        "./tests/sample/test_unused_output.circom",
+        "unconstrained_outputs",
+    );
+    assert!(findings.is_empty());
+}
+
+#[test]
+fn test_severity() {
+    assert_eq!(Severity::from_str("High"), Ok(Severity::High));
+    assert!(Severity::from_str("severe").is_err());
+    assert!(Severity::Info < Severity::Low && Severity::High < Severity::Critical);
+
+    // The exit codes are distinct from each other and from the one of errors
+    let exit_codes: Vec<i32> = Severity::ALL.iter().map(|s| s.exit_code()).collect();
+    assert_eq!(exit_codes, vec![2, 3, 4, 5, 6]);
+
+    assert_eq!(
+        format_severity_counts(vec![Severity::Medium, Severity::Critical, Severity::Medium]),
+        "critical: 1, medium: 2"
+    );
+    assert_eq!(format_severity_counts(Vec::new()), "");
+}
+
+#[test]
+fn test_exploitability() {
+    let prime = BigInt::from_str(
+        "21888242871839275222246405745257275088548364400416034343698204186575808495617",
+    )
+    .unwrap();
+
+    let (mut symbolic_library, program_archive) = prepare_symbolic_library(
+        "./tests/sample/test_exploitability.circom".to_string(),
+        prime.clone(),
+    );
+    let setting = get_default_setting_for_symbolic_execution(prime.clone(), false);
+
+    let mut sexe = SymbolicExecutor::new(&mut symbolic_library, &setting);
+    execute(&mut sexe, &program_archive);
+
+    let verification_base_config = BaseVerificationConfig {
+        target_template_name: "Exploitability".to_string(),
+        prime: prime.clone(),
+        range: BigInt::from(100),
+        quick_mode: false,
+        heuristics_mode: false,
+        progress_interval: 10000,
+        template_param_names: Vec::new(),
+        template_param_values: Vec::new(),
+        check_output_tags: false,
+        max_counterexamples: 1,
+        input_shapes: FxHashMap::default(),
+        input_spec: InputSpec::default(),
+    };
+
+    let name2id = &sexe.symbolic_library.name2id;
+    let id2name = &sexe.symbolic_library.id2name;
+    let main_owner = Rc::new(vec![OwnerName {
+        id: name2id["main"],
+        counter: 0,
+        access: None,
+    }]);
+    let non_deterministic = |signal: &str| CounterExample {
+        flag: VerificationResult::UnderConstrained(UnderConstrainedType::NonDeterministic(
+            SymbolicName::new(name2id[signal], main_owner.clone(), None),
+            String::new(),
+            BigInt::from(0),
+        )),
+        target_output: None,
+        assignment: FxHashMap::default(),
+    };
+    let symbolic_trace = sexe.cur_state.symbolic_trace.clone();
+    let assess = |ce: &CounterExample| {
+        assess_exploitability(
+            ce,
+            &symbolic_trace,
+            &sexe.symbolic_library,
+            &verification_base_config,
+            program_archive.get_public_inputs_main_component(),
+        )
+    };
+
+    // `sum <-- a + b` is computed from the public `a` and the private `b`
+    let ce = non_deterministic("sum");
+    let exploitability = assess(&ce).unwrap();
+    assert_eq!(
+        exploitability.controllability,
+        Controllability::PublicAndPrivate
+    );
+    assert_eq!(
+        exploitability.lookup_fmt(id2name),
+        "requires both public and private inputs: `main.sum` is computed from main.a (public), main.b (private)"
+    );
+    assert_eq!(
+        assessed_severity(&ce, Some(&exploitability)),
+        Severity::High
+    );
+
+    let ce = non_deterministic("doubled");
+    let exploitability = assess(&ce).unwrap();
+    assert_eq!(exploitability.controllability, Controllability::PublicOnly);
+    assert_eq!(
+        assessed_severity(&ce, Some(&exploitability)),
+        Severity::Medium
+    );
+
+    let ce = non_deterministic("tripled");
+    let exploitability = assess(&ce).unwrap();
+    assert_eq!(exploitability.controllability, Controllability::PrivateOnly);
+    assert_eq!(
+        assessed_severity(&ce, Some(&exploitability)),
+        Severity::Critical
+    );
+
+    // Only under-constraints are assessed
+    let ce = CounterExample {
+        flag: VerificationResult::OverConstrained,
+        target_output: None,
+        assignment: FxHashMap::default(),
+    };
+    assert!(assess(&ce).is_none());
+    assert_eq!(assessed_severity(&ce, None), Severity::High);
+}
+
+#[test]
+fn test_finding_stream() {
+    let path = env::temp_dir().join(format!(
+        "zkfuzz_finding_stream_test_{}.jsonl",
+        std::process::id()
+    ));
+    let mut stream = FindingStream::create(Some(path.as_path())).unwrap();
+    stream.push((Severity::Medium, "Shape Mismatch", "`main.t`".to_string()));
+
+    // Each event is readable as soon as it is recorded
+    let lines = fs::read_to_string(&path).unwrap();
+    assert_eq!(lines.lines().count(), 1);
+
+    stream.push_counter_example(
+        Severity::Critical,
+        "UnderConstrained-NonDeterministic: main.out",
+        json!({}),
+    );
+    let findings = stream.finish();
+    assert_eq!(
+        findings,
+        vec![(Severity::Medium, "Shape Mismatch", "`main.t`".to_string())]
+    );
+
+    let events: Vec<Value> = fs::read_to_string(&path)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    fs::remove_file(&path).unwrap();
+    assert_eq!(events.len(), 3);
+    assert_eq!(events[0]["event"], "finding");
+    assert_eq!(events[0]["severity"], "medium");
+    assert_eq!(events[1]["event"], "counterexample");
+    assert_eq!(events[2]["event"], "end");
+    assert_eq!(events[2]["seq"], 2);
+    assert_eq!(events[2]["num_findings"], 1);
+    assert_eq!(events[2]["max_severity"], "critical");
+}
+
+#[test]
+fn test_analyze_source() {
+    let source = "pragma circom 2.0.0;\n\
+                  include \"unused.circom\";\n\
+                  component main = UnusedOutput();\n";
+    let resolver = |included: &str| {
+        if included == "unused.circom" {
+            fs::read_to_string("./tests/sample/test_unused_output.circom")
+                .ok()
+                .map(|s| s.replace("component main = UnusedOutput();", ""))
+        } else {
+            None
+        }
+    };
+
+    let mut registry = DetectorRegistry::default();
+    registry.register(Rc::new(TraceSizeDetector));
+    let options = SourceAnalysisOptions {
+        detectors: "unused_outputs,custom:trace_size".to_string(),
+        ..SourceAnalysisOptions::default()
+    };
+    let findings = analyze_source(source, resolver, &registry, &options).unwrap();
+    assert_eq!(findings.len(), 2);
+    assert_eq!(findings[0].category, "Unused Output");
+    assert!(findings[0].counter_example.is_some());
+    assert_eq!(findings[1].category, "Trace Size");
+
+    let options = SourceAnalysisOptions {
+        detectors: "custom:unknown".to_string(),
+        ..SourceAnalysisOptions::default()
+    };
+    assert!(analyze_source(source, resolver, &registry, &options).is_err());
+}
@@ -0,0 +1,167 @@
+mod utils;
+
+use std::env;
+use std::fs;
+use std::process::{self, Command};
+use std::str::FromStr;
+
+use num_bigint_dig::BigInt;
+use rustc_hash::FxHashMap;
+
+use zkfuzz::executor::symbolic_execution::SymbolicExecutor;
+use zkfuzz::executor::symbolic_setting::{
+    get_default_setting_for_concrete_execution, get_default_setting_for_symbolic_execution,
+};
+use zkfuzz::mutator::differential_testing::{
+    differential_test_against_wasm, flatten_main_signals, parse_wasm_outcomes, WitnessOutcome,
+};
+use zkfuzz::mutator::input_spec::InputSpec;
+use zkfuzz::mutator::utils::BaseVerificationConfig;
+
+use crate::utils::{execute, prepare_symbolic_library};
+
+const BN128: &str = "21888242871839275222246405745257275088548364400416034343698204186575808495617";
+
+/// A stand-in for the `witness_calculator.js` of `circom --wasm` that computes the witness of
+/// `test_differential.circom` in JavaScript, except that `x[1]` is `a[1] + b` instead of
+/// `a[1] * b`.
+const BUGGY_WITNESS_CALCULATOR: &str = r#"const p = 21888242871839275222246405745257275088548364400416034343698204186575808495617n;
+
+module.exports = async function builder(code) {
+    return {
+        calculateWitness: async (input, sanityCheck) => {
+            const b = BigInt(input.b);
+            const a = input.a.map(BigInt);
+            return [1n, (a[0] * a[1]) % p, (a[0] + b) % p, (a[1] + b) % p, b, a[0], a[1]];
+        },
+    };
+};
+"#;
+
+fn base_config(prime: &BigInt) -> BaseVerificationConfig {
+    BaseVerificationConfig {
+        target_template_name: "Differential".to_string(),
+        prime: prime.clone(),
+        range: prime.clone(),
+        quick_mode: false,
+        heuristics_mode: false,
+        progress_interval: 10000,
+        template_param_names: Vec::new(),
+        template_param_values: Vec::new(),
+        check_output_tags: false,
+        max_counterexamples: 1,
+        input_shapes: FxHashMap::default(),
+        input_spec: InputSpec::default(),
+    }
+}
+
+#[test]
+fn test_flatten_main_signals_in_declaration_order() {
+    let prime = BigInt::from_str(BN128).unwrap();
+    let path = "./tests/sample/test_differential.circom".to_string();
+    let (mut symbolic_library, program_archive) = prepare_symbolic_library(path, prime.clone());
+    let setting = get_default_setting_for_symbolic_execution(prime.clone(), false);
+    let mut sexe = SymbolicExecutor::new(&mut symbolic_library, &setting);
+    execute(&mut sexe, &program_archive);
+
+    let base_config = base_config(&prime);
+    let names = |is_input: bool| -> Vec<String> {
+        flatten_main_signals(&sexe, &base_config, &sexe.id2dimensions, is_input)
+            .iter()
+            .map(|name| name.lookup_fmt(&sexe.symbolic_library.id2name))
+            .collect()
+    };
+    assert_eq!(names(true), vec!["main.b", "main.a[0]", "main.a[1]"]);
+    assert_eq!(names(false), vec!["main.y", "main.x[0]", "main.x[1]"]);
+}
+
+#[test]
+fn test_parse_wasm_outcomes() {
+    let stdout = br#"[
+        {"accepted": true, "outputs": ["7", "0", "not a number"]},
+        {"accepted": false, "error": "Error: Assert Failed."}
+    ]"#;
+    let outcomes = parse_wasm_outcomes(stdout).unwrap();
+    assert_eq!(
+        outcomes,
+        vec![
+            WitnessOutcome {
+                accepted: true,
+                outputs: vec![Some(BigInt::from(7)), Some(BigInt::from(0)), None],
+            },
+            WitnessOutcome {
+                accepted: false,
+                outputs: Vec::new(),
+            },
+        ]
+    );
+    assert!(parse_wasm_outcomes(b"Error: Cannot find module").is_err());
+}
+
+#[test]
+fn test_witness_outcome_divergence() {
+    let accepted = |outputs: &[i64]| WitnessOutcome {
+        accepted: true,
+        outputs: outputs.iter().map(|o| Some(BigInt::from(*o))).collect(),
+    };
+    let rejected = WitnessOutcome {
+        accepted: false,
+        outputs: Vec::new(),
+    };
+
+    assert!(!accepted(&[1, 2]).diverges_from(&accepted(&[1, 2])));
+    assert!(!rejected.diverges_from(&rejected));
+    assert!(accepted(&[1, 2]).diverges_from(&accepted(&[1, 3])));
+    assert!(accepted(&[1, 2]).diverges_from(&rejected));
+    assert!(rejected.diverges_from(&accepted(&[1, 2])));
+}
+
+/// Runs the differential testing against a witness calculator that computes one output wrongly.
+/// The test passes without checking anything if node is not installed.
+#[test]
+fn test_differential_test_against_wasm() {
+    if Command::new("node").arg("--version").output().is_err() {
+        eprintln!("node is not installed, skipping the differential testing");
+        return;
+    }
+
+    let out_dir = env::temp_dir().join(format!("zkfuzz_differential_test_{}", process::id()));
+    fs::create_dir_all(&out_dir).unwrap();
+    fs::write(
+        out_dir.join("witness_calculator.js"),
+        BUGGY_WITNESS_CALCULATOR,
+    )
+    .unwrap();
+    let wasm_path = out_dir.join("test_differential.wasm");
+    fs::write(&wasm_path, b"").unwrap();
+
+    let prime = BigInt::from_str(BN128).unwrap();
+    let path = "./tests/sample/test_differential.circom".to_string();
+    let (mut symbolic_library, program_archive) = prepare_symbolic_library(path, prime.clone());
+    let setting = get_default_setting_for_symbolic_execution(prime.clone(), false);
+    let mut sexe = SymbolicExecutor::new(&mut symbolic_library, &setting);
+    execute(&mut sexe, &program_archive);
+
+    let base_config = base_config(&prime);
+    let id2dimensions = sexe.id2dimensions.clone();
+    let conc_setting = get_default_setting_for_concrete_execution(prime, false);
+    let mut conc_executor = SymbolicExecutor::new(&mut sexe.symbolic_library, &conc_setting);
+    let divergences = differential_test_against_wasm(
+        &mut conc_executor,
+        &base_config,
+        &id2dimensions,
+        wasm_path.to_str().unwrap(),
+        20,
+        1,
+    );
+    let _ = fs::remove_dir_all(&out_dir);
+
+    // Both sides accept every input and only disagree on `x[1]`
+    let divergences = divergences.unwrap();
+    assert!(!divergences.is_empty());
+    for divergence in &divergences {
+        assert!(divergence.zkfuzz.accepted && divergence.wasm.accepted);
+        assert_eq!(divergence.zkfuzz.outputs[..2], divergence.wasm.outputs[..2]);
+        assert_ne!(divergence.zkfuzz.outputs[2], divergence.wasm.outputs[2]);
+    }
+}
@@ -0,0 +1,298 @@
+use std::env;
+use std::rc::Rc;
+use std::str::FromStr;
+
+use num_bigint_dig::BigInt;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use rustc_hash::FxHashMap;
+
+use program_structure::ast::{ExpressionInfixOpcode, ExpressionPrefixOpcode};
+
+use zkfuzz::executor::debug_ast::{
+    DebuggableExpressionInfixOpcode, DebuggableExpressionPrefixOpcode,
+};
+use zkfuzz::executor::symbolic_value::{
+    evaluate_binary_op, OwnerName, SymbolicLibrary, SymbolicName, SymbolicValue,
+};
+use zkfuzz::mutator::compiled_constraints::CompiledConstraints;
+use zkfuzz::mutator::utils::evaluate_constraints;
+
+/// The number of operations drawn per prime, which can be raised with
+/// `ZKFUZZ_FIELD_AUDIT_ITERATIONS` to audit the evaluator more thoroughly.
+const DEFAULT_AUDIT_ITERATIONS: usize = 2000;
+
+const OPERATORS: [ExpressionInfixOpcode; 18] = [
+    ExpressionInfixOpcode::Add,
+    ExpressionInfixOpcode::Sub,
+    ExpressionInfixOpcode::Mul,
+    ExpressionInfixOpcode::Div,
+    ExpressionInfixOpcode::Pow,
+    ExpressionInfixOpcode::IntDiv,
+    ExpressionInfixOpcode::Mod,
+    ExpressionInfixOpcode::BitAnd,
+    ExpressionInfixOpcode::BitOr,
+    ExpressionInfixOpcode::BitXor,
+    ExpressionInfixOpcode::ShiftL,
+    ExpressionInfixOpcode::ShiftR,
+    ExpressionInfixOpcode::Lesser,
+    ExpressionInfixOpcode::Greater,
+    ExpressionInfixOpcode::LesserEq,
+    ExpressionInfixOpcode::GreaterEq,
+    ExpressionInfixOpcode::Eq,
+    ExpressionInfixOpcode::NotEq,
+];
+
+/// GF(p) arithmetic on machine integers for primes below 2^64, written independently of the
+/// `BigInt` arithmetic of the evaluator. Booleans are returned as 0 and 1.
+struct ReferenceField {
+    p: u128,
+}
+
+impl ReferenceField {
+    fn reduce(&self, v: i128) -> u128 {
+        v.rem_euclid(self.p as i128) as u128
+    }
+
+    fn mul(&self, a: u128, b: u128) -> u128 {
+        a * b % self.p
+    }
+
+    fn pow(&self, base: u128, exp: u128) -> u128 {
+        let mut result = 1 % self.p;
+        let mut base = base % self.p;
+        let mut exp = exp;
+        while exp > 0 {
+            if exp & 1 == 1 {
+                result = self.mul(result, base);
+            }
+            base = self.mul(base, base);
+            exp >>= 1;
+        }
+        result
+    }
+
+    /// The mask of the bits kept by the bitwise operators, `2^b - 1` where `b` is the bit length
+    /// of `p`.
+    fn mask(&self) -> u128 {
+        (1 << (128 - self.p.leading_zeros())) - 1
+    }
+
+    /// `a << k` (or `a >> k`) as computed by the witness generator of circom: an amount above
+    /// `p/2` shifts the other way by `p - k`, and a left shift is truncated to the bits of `p`.
+    fn shift(&self, a: u128, k: u128, is_left: bool) -> u128 {
+        if k > self.p / 2 {
+            self.shift(a, self.p - k, !is_left)
+        } else if k >= (128 - self.p.leading_zeros()) as u128 {
+            0
+        } else if is_left {
+            ((a << k) & self.mask()) % self.p
+        } else {
+            a >> k
+        }
+    }
+
+    fn complement(&self, a: u128) -> u128 {
+        (self.mask() - a) % self.p
+    }
+
+    /// The representative in `(-p/2, p/2]` compared by the relational operators.
+    fn signed(&self, a: u128) -> i128 {
+        if a > self.p / 2 {
+            a as i128 - self.p as i128
+        } else {
+            a as i128
+        }
+    }
+
+    fn eval(&self, op: &ExpressionInfixOpcode, lhs: i128, rhs: i128) -> u128 {
+        let (a, b) = (self.reduce(lhs), self.reduce(rhs));
+        match op {
+            ExpressionInfixOpcode::Add => (a + b) % self.p,
+            ExpressionInfixOpcode::Sub => (a + self.p - b) % self.p,
+            ExpressionInfixOpcode::Mul => self.mul(a, b),
+            ExpressionInfixOpcode::Div => {
+                if b == 0 {
+                    0
+                } else {
+                    self.mul(a, self.pow(b, self.p - 2))
+                }
+            }
+            ExpressionInfixOpcode::Pow => self.pow(a, b),
+            ExpressionInfixOpcode::IntDiv => {
+                if b == 0 {
+                    0
+                } else {
+                    a / b
+                }
+            }
+            ExpressionInfixOpcode::Mod => {
+                if b == 0 {
+                    0
+                } else {
+                    a % b
+                }
+            }
+            ExpressionInfixOpcode::BitAnd => a & b,
+            ExpressionInfixOpcode::BitOr => (a | b) % self.p,
+            ExpressionInfixOpcode::BitXor => (a ^ b) % self.p,
+            ExpressionInfixOpcode::ShiftL => self.shift(a, b, true),
+            ExpressionInfixOpcode::ShiftR => self.shift(a, b, false),
+            ExpressionInfixOpcode::Lesser => (self.signed(a) < self.signed(b)) as u128,
+            ExpressionInfixOpcode::Greater => (self.signed(a) > self.signed(b)) as u128,
+            ExpressionInfixOpcode::LesserEq => (self.signed(a) <= self.signed(b)) as u128,
+            ExpressionInfixOpcode::GreaterEq => (self.signed(a) >= self.signed(b)) as u128,
+            ExpressionInfixOpcode::Eq => (a == b) as u128,
+            ExpressionInfixOpcode::NotEq => (a != b) as u128,
+            ExpressionInfixOpcode::BoolAnd | ExpressionInfixOpcode::BoolOr => unreachable!(),
+        }
+    }
+}
+
+fn big(v: i128) -> BigInt {
+    BigInt::from_str(&v.to_string()).unwrap()
+}
+
+/// Draws a constant as written in a circuit, which may be negative or exceed `p`.
+fn draw_constant(rng: &mut StdRng, p: u128) -> i128 {
+    let sp = p as i128;
+    match rng.gen_range(0, 3) {
+        0 => {
+            let boundaries = [0, 1, -1, 2, -2, sp - 1, sp, sp + 1, -sp, sp / 2, sp / 2 + 1];
+            boundaries[rng.gen_range(0, boundaries.len())]
+        }
+        1 => rng.gen_range(-16i64, 17i64) as i128,
+        _ => {
+            let raw = ((rng.gen::<u64>() as u128) << 64) | rng.gen::<u64>() as u128;
+            (raw % (4 * p)) as i128 - 2 * sp
+        }
+    }
+}
+
+fn variable(id: usize) -> SymbolicName {
+    SymbolicName::new(
+        id,
+        Rc::new(vec![OwnerName {
+            id: 0,
+            counter: 0,
+            access: None,
+        }]),
+        None,
+    )
+}
+
+/// Checks that the constraint holds under both `evaluate_constraints` and the compiled tapes.
+fn assert_holds(
+    prime: &BigInt,
+    constraint: SymbolicValue,
+    assignment: &FxHashMap<SymbolicName, BigInt>,
+    symbolic_library: &mut SymbolicLibrary,
+) {
+    let constraints = vec![Rc::new(constraint)];
+    assert!(
+        evaluate_constraints(prime, &constraints, assignment, symbolic_library),
+        "{:?}",
+        constraints[0]
+    );
+    assert!(
+        CompiledConstraints::compile(&constraints).evaluate(prime, assignment, symbolic_library),
+        "{:?}",
+        constraints[0]
+    );
+}
+
+#[test]
+fn test_field_arithmetic_audit() {
+    let iterations = env::var("ZKFUZZ_FIELD_AUDIT_ITERATIONS")
+        .ok()
+        .and_then(|n| n.parse().ok())
+        .unwrap_or(DEFAULT_AUDIT_ITERATIONS);
+    let mut symbolic_library = SymbolicLibrary::default();
+    let mut rng = StdRng::seed_from_u64(0);
+
+    for p in [17u128, 65521, 2147483647, 18446744073709551557] {
+        let field = ReferenceField { p };
+        let prime = big(p as i128);
+
+        for _ in 0..iterations {
+            let op = OPERATORS[rng.gen_range(0, OPERATORS.len())];
+            let lhs = draw_constant(&mut rng, p);
+            let rhs = match op {
+                // Negative amounts and amounts beyond the bit length of `p` included
+                ExpressionInfixOpcode::ShiftL | ExpressionInfixOpcode::ShiftR => {
+                    rng.gen_range(-70i64, 300i64) as i128
+                }
+                _ => draw_constant(&mut rng, p),
+            };
+            let expected = big(field.eval(&op, lhs, rhs) as i128);
+
+            let result = evaluate_binary_op(
+                &SymbolicValue::ConstantInt(big(lhs)),
+                &SymbolicValue::ConstantInt(big(rhs)),
+                &prime,
+                &DebuggableExpressionInfixOpcode(op),
+            );
+            let actual = match result {
+                SymbolicValue::ConstantInt(v) => v,
+                SymbolicValue::ConstantBool(b) => BigInt::from(b as u8),
+                _ => unreachable!(),
+            };
+            assert_eq!(
+                actual,
+                expected,
+                "{} {:?} {} (mod {})",
+                lhs,
+                DebuggableExpressionInfixOpcode(op),
+                rhs,
+                p
+            );
+
+            // `-c` is canonical, and equals any representative of `-c` assigned to a signal
+            let negated = SymbolicValue::UnaryOp(
+                DebuggableExpressionPrefixOpcode(ExpressionPrefixOpcode::Sub),
+                Rc::new(SymbolicValue::ConstantInt(big(lhs))),
+            );
+            let negated_expected = big(field.reduce(-lhs) as i128);
+            assert_holds(
+                &prime,
+                SymbolicValue::BinaryOp(
+                    Rc::new(negated.clone()),
+                    DebuggableExpressionInfixOpcode(ExpressionInfixOpcode::Eq),
+                    Rc::new(SymbolicValue::ConstantInt(negated_expected)),
+                ),
+                &FxHashMap::default(),
+                &mut symbolic_library,
+            );
+            // `~c` flips the bits of the canonical representative of `c`
+            assert_holds(
+                &prime,
+                SymbolicValue::BinaryOp(
+                    Rc::new(SymbolicValue::UnaryOp(
+                        DebuggableExpressionPrefixOpcode(ExpressionPrefixOpcode::Complement),
+                        Rc::new(SymbolicValue::ConstantInt(big(lhs))),
+                    )),
+                    DebuggableExpressionInfixOpcode(ExpressionInfixOpcode::Eq),
+                    Rc::new(SymbolicValue::ConstantInt(big(
+                        field.complement(field.reduce(lhs)) as i128,
+                    ))),
+                ),
+                &FxHashMap::default(),
+                &mut symbolic_library,
+            );
+
+            let assignment = FxHashMap::from_iter([(
+                variable(1),
+                big(-lhs + rng.gen_range(-2i64, 3i64) as i128 * p as i128),
+            )]);
+            assert_holds(
+                &prime,
+                SymbolicValue::AssignEq(
+                    Rc::new(SymbolicValue::Variable(variable(1))),
+                    Rc::new(negated),
+                ),
+                &assignment,
+                &mut symbolic_library,
+            );
+        }
+    }
+}
@@ -0,0 +1,87 @@
+mod utils;
+
+use std::str::FromStr;
+
+use num_bigint_dig::BigInt;
+
+use zkfuzz::executor::symbolic_execution::SymbolicExecutor;
+use zkfuzz::executor::symbolic_setting::get_default_setting_for_symbolic_execution;
+use zkfuzz::mutator::formal_export::{collect_constraint_system, ExportFormat};
+
+use crate::utils::{execute, prepare_symbolic_library};
+
+#[test]
+fn test_export_iszero() {
+    let prime = BigInt::from_str(
+        "21888242871839275222246405745257275088548364400416034343698204186575808495617",
+    )
+    .unwrap();
+    let (mut symbolic_library, program_archive) = prepare_symbolic_library(
+        "./tests/sample/test_vuln_iszero.circom".to_string(),
+        prime.clone(),
+    );
+    let setting = get_default_setting_for_symbolic_execution(prime.clone(), false);
+
+    let mut sexe = SymbolicExecutor::new(&mut symbolic_library, &setting);
+    execute(&mut sexe, &program_archive);
+
+    let system = collect_constraint_system(
+        &sexe.cur_state.side_constraints,
+        sexe.symbolic_library,
+        "VulnerableIsZero",
+        &prime,
+    );
+    assert_eq!(system.inputs, vec!["main.in".to_string()]);
+    assert_eq!(system.outputs, vec!["main.out".to_string()]);
+    assert!(system.signals.contains(&"main.inv".to_string()));
+    assert_eq!(system.constraints.len(), 2);
+    assert!(system.unsupported.is_empty());
+
+    let json = system.to_json();
+    assert_eq!(json["format"], "zkfuzz-constraints");
+    assert_eq!(json["prime"], prime.to_string());
+    assert_eq!(json["constraints"].as_array().unwrap().len(), 2);
+
+    // The inputs are shared by both copies of the witness, the other signals are duplicated
+    let smt2 = system.to_smt2();
+    assert!(smt2.contains("(set-logic QF_FF)"));
+    assert!(smt2.contains("(declare-const |main.in| F)"));
+    assert!(!smt2.contains("|main.in'|"));
+    assert!(smt2.contains("(declare-const |main.inv'| F)"));
+    assert!(smt2.contains("(assert (not (= |main.out| |main.out'|)))"));
+    assert!(smt2.ends_with("(check-sat)\n"));
+
+    assert_eq!(ExportFormat::from_path("iszero.smt2"), ExportFormat::Smt2);
+    assert_eq!(ExportFormat::from_path("iszero.json"), ExportFormat::Json);
+}
+
+#[test]
+fn test_export_montgomery_double() {
+    let prime = BigInt::from_str(
+        "21888242871839275222246405745257275088548364400416034343698204186575808495617",
+    )
+    .unwrap();
+    let (mut symbolic_library, program_archive) = prepare_symbolic_library(
+        "./tests/sample/test_montgomerydouble.circom".to_string(),
+        prime.clone(),
+    );
+    let setting = get_default_setting_for_symbolic_execution(prime.clone(), false);
+
+    let mut sexe = SymbolicExecutor::new(&mut symbolic_library, &setting);
+    execute(&mut sexe, &program_archive);
+
+    let system = collect_constraint_system(
+        &sexe.cur_state.side_constraints,
+        sexe.symbolic_library,
+        "CallMontogmeryDouble",
+        &prime,
+    );
+    assert_eq!(
+        system.inputs,
+        vec!["main.a".to_string(), "main.b".to_string()]
+    );
+    assert_eq!(system.outputs, vec!["main.c".to_string()]);
+    assert!(system.signals.contains(&"main.md.lamda".to_string()));
+    assert!(system.unsupported.is_empty());
+    assert!(system.constraints.iter().all(|c| !c.source.is_empty()));
+}
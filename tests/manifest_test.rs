@@ -0,0 +1,62 @@
+use std::path::{Path, PathBuf};
+
+use zkfuzz::manifest_user::{parse_manifest, parse_template_timeouts};
+
+const MANIFEST: &str = r#"
+entry = "circuits/main.circom"
+include = ["node_modules/circomlib/circuits", "circuits/lib"]
+prime = "bn128"
+search_mode = "quick"
+detectors = ["unused_outputs", "component_boundaries"]
+
+[timeouts]
+Main = 600
+MerkleProof = 120
+"#;
+
+#[test]
+fn test_parse_manifest() {
+    let manifest = parse_manifest(MANIFEST, Path::new("project")).unwrap();
+    assert_eq!(
+        manifest.entry,
+        PathBuf::from("project/circuits/main.circom")
+    );
+    assert_eq!(
+        manifest.include,
+        vec![
+            PathBuf::from("project/node_modules/circomlib/circuits"),
+            PathBuf::from("project/circuits/lib"),
+        ]
+    );
+    assert_eq!(manifest.prime.as_deref(), Some("bn128"));
+    assert_eq!(manifest.search_mode.as_deref(), Some("quick"));
+    assert_eq!(
+        manifest.detectors.unwrap().join(","),
+        "unused_outputs,component_boundaries"
+    );
+
+    let manifest = parse_manifest(MANIFEST, Path::new("")).unwrap();
+    assert_eq!(manifest.entry, PathBuf::from("circuits/main.circom"));
+    assert_eq!(manifest.template_timeouts(), "Main:600,MerkleProof:120");
+
+    let minimal = parse_manifest("entry = \"main.circom\"", Path::new("")).unwrap();
+    assert!(minimal.include.is_empty());
+    assert!(minimal.prime.is_none());
+    assert_eq!(minimal.template_timeouts(), "none");
+
+    assert!(parse_manifest("include = [\"lib\"]", Path::new("")).is_err());
+    assert!(parse_manifest("entry = \"main.circom\"\nmode = \"ga\"", Path::new("")).is_err());
+}
+
+#[test]
+fn test_parse_template_timeouts() {
+    let timeouts = parse_template_timeouts("Main:600,MerkleProof:120").unwrap();
+    assert_eq!(timeouts.len(), 2);
+    assert_eq!(timeouts["Main"], 600);
+    assert_eq!(timeouts["MerkleProof"], 120);
+
+    assert!(parse_template_timeouts("none").unwrap().is_empty());
+    assert!(parse_template_timeouts("Main").is_err());
+    assert!(parse_template_timeouts("Main:0").is_err());
+    assert!(parse_template_timeouts("Main:ten").is_err());
+}
@@ -1,20 +1,33 @@
 mod utils;
 
+use std::collections::HashSet;
+use std::env;
+use std::fs;
+use std::rc::Rc;
 use std::str::FromStr;
 
 use num_bigint_dig::BigInt;
+use rustc_hash::FxHashMap;
+use serde_json::json;
 
-use program_structure::ast::Expression;
+use program_structure::ast::{Expression, ExpressionInfixOpcode};
 
+use zkfuzz::executor::debug_ast::DebuggableExpressionInfixOpcode;
 use zkfuzz::executor::symbolic_execution::SymbolicExecutor;
 use zkfuzz::executor::symbolic_setting::{
     get_default_setting_for_concrete_execution, get_default_setting_for_symbolic_execution,
 };
+use zkfuzz::executor::symbolic_value::{OwnerName, SymbolicAccess, SymbolicName, SymbolicValue};
+use zkfuzz::executor::utils::strip_ansi;
 use zkfuzz::mutator::utils::{
-    BaseVerificationConfig, CounterExample, UnderConstrainedType, VerificationResult,
+    classify_counterexamples, gather_free_witnesses, BaseVerificationConfig, CounterExample,
+    UnderConstrainedType, VerificationResult, ViolatedConstraint,
 };
 
-use zkfuzz::mutator::mutation_config::load_config_from_json;
+use zkfuzz::mutator::clustering::{cluster_findings, FindingSignature};
+use zkfuzz::mutator::corpus::{update_corpus, CorpusEntry, CorpusEntryKind, CorpusUpdate};
+use zkfuzz::mutator::input_spec::InputSpec;
+use zkfuzz::mutator::mutation_config::load_mutation_config;
 use zkfuzz::mutator::mutation_test::{mutation_test_search, MutationTestResult};
 use zkfuzz::mutator::mutation_test_crossover_fn::random_crossover;
 use zkfuzz::mutator::mutation_test_evolution_fn::simple_evolution;
@@ -25,10 +38,28 @@ use zkfuzz::mutator::mutation_test_trace_selection_fn::roulette_selection;
 use zkfuzz::mutator::mutation_test_update_input_fn::{
     update_input_population_with_fitness_score, update_input_population_with_random_sampling,
 };
+use zkfuzz::mutator::test_harness::{
+    generate_test_cases, render_js_harness, render_rust_harness, HarnessLanguage,
+};
+use zkfuzz::type_analysis_user::gather_input_shapes;
 
 use crate::utils::{execute, prepare_symbolic_library};
 
 fn conduct_mutation_testing(path: String, update_input_method: String) -> MutationTestResult {
+    conduct_mutation_testing_with_config(
+        path,
+        update_input_method,
+        "./tests/parameters/test.json",
+        1,
+    )
+}
+
+fn conduct_mutation_testing_with_config(
+    path: String,
+    update_input_method: String,
+    config_path: &str,
+    max_counterexamples: usize,
+) -> MutationTestResult {
     let prime = BigInt::from_str(
         "21888242871839275222246405745257275088548364400416034343698204186575808495617",
     )
@@ -58,16 +89,24 @@ fn conduct_mutation_testing(path: String, update_input_method: String) -> Mutati
         progress_interval: 10000,
         template_param_names: template_param_names,
         template_param_values: template_param_values,
+        check_output_tags: false,
+        max_counterexamples: max_counterexamples,
+        input_shapes: gather_input_shapes(
+            sexe.symbolic_library,
+            main_template_name,
+            &sexe.id2dimensions,
+        ),
+        input_spec: InputSpec::default(),
     };
 
-    let subse_base_config = get_default_setting_for_concrete_execution(prime, false);
+    let subse_base_config = get_default_setting_for_concrete_execution(prime.clone(), false);
     let mut conc_executor = SymbolicExecutor::new(&mut sexe.symbolic_library, &subse_base_config);
     conc_executor.feed_arguments(
         &verification_base_config.template_param_names,
         &verification_base_config.template_param_values,
     );
 
-    let mutation_config = load_config_from_json("./tests/parameters/test.json").unwrap();
+    let mutation_config = load_mutation_config(config_path).unwrap();
 
     let update_func = if update_input_method == "fitness" {
         update_input_population_with_fitness_score
@@ -75,7 +114,8 @@ fn conduct_mutation_testing(path: String, update_input_method: String) -> Mutati
         update_input_population_with_random_sampling
     };
 
-    mutation_test_search(
+    let mut found = Vec::new();
+    let result = mutation_test_search(
         &mut conc_executor,
         &sexe.cur_state.symbolic_trace.clone(),
         &sexe.cur_state.side_constraints.clone(),
@@ -88,7 +128,19 @@ fn conduct_mutation_testing(path: String, update_input_method: String) -> Mutati
         mutate_trace_with_operator_or_const_replacement,
         random_crossover,
         roulette_selection,
-    )
+        |_, ce| found.push(ce.witness_key(&prime)),
+    );
+
+    // Each counterexample is passed on as soon as it is found, in the order of the result
+    assert_eq!(
+        found,
+        result
+            .counter_examples
+            .iter()
+            .map(|ce| ce.witness_key(&prime))
+            .collect::<Vec<_>>()
+    );
+    result
 }
 
 #[test]
@@ -123,6 +175,259 @@ fn test_vuln_iszero_fitness() {
     ));
 }
 
+#[test]
+fn test_vuln_iszero_value_mutation_operators() {
+    let result = conduct_mutation_testing_with_config(
+        "./tests/sample/test_vuln_iszero.circom".to_string(),
+        "fitness".to_string(),
+        "./tests/parameters/test_operators.toml",
+        1,
+    );
+
+    assert_eq!(result.mutation_config.value_mutation_operators.boundary, 1.0);
+    assert!(!result.operator_stats.applied.is_empty());
+    assert!(matches!(
+        result.counter_example,
+        Some(CounterExample {
+            flag: VerificationResult::UnderConstrained(UnderConstrainedType::NonDeterministic(..)),
+            ..
+        })
+    ));
+}
+
+#[test]
+fn test_vuln_iszero_islands() {
+    let result = conduct_mutation_testing_with_config(
+        "./tests/sample/test_vuln_iszero.circom".to_string(),
+        "random".to_string(),
+        "./tests/parameters/test_islands.toml",
+        1,
+    );
+
+    assert_eq!(result.mutation_config.num_islands, 3);
+    assert_eq!(result.mutation_config.migration_interval, 2);
+    assert!(matches!(
+        result.counter_example,
+        Some(CounterExample {
+            flag: VerificationResult::UnderConstrained(UnderConstrainedType::NonDeterministic(..)),
+            ..
+        })
+    ));
+}
+
+#[test]
+fn test_vuln_free_witness() {
+    let path = "./tests/sample/test_vuln_free_witness.circom".to_string();
+    let prime = BigInt::from_str(
+        "21888242871839275222246405745257275088548364400416034343698204186575808495617",
+    )
+    .unwrap();
+    let (mut symbolic_library, program_archive) =
+        prepare_symbolic_library(path.clone(), prime.clone());
+    let setting = get_default_setting_for_symbolic_execution(prime, false);
+    let mut sexe = SymbolicExecutor::new(&mut symbolic_library, &setting);
+    execute(&mut sexe, &program_archive);
+
+    let free_witnesses: Vec<String> = gather_free_witnesses(&sexe.cur_state.symbolic_trace)
+        .into_iter()
+        .map(|(_, name)| name.lookup_fmt(&sexe.symbolic_library.id2name))
+        .collect();
+    assert_eq!(free_witnesses, vec!["main.iz.inv".to_string()]);
+
+    let result = conduct_mutation_testing_with_config(
+        path,
+        "random".to_string(),
+        "./tests/parameters/test_free_witnesses.toml",
+        1,
+    );
+
+    assert!(result.mutation_config.free_witnesses);
+    assert!(matches!(
+        result.counter_example,
+        Some(CounterExample {
+            flag: VerificationResult::UnderConstrained(UnderConstrainedType::NonDeterministic(..)),
+            ..
+        })
+    ));
+}
+
+#[test]
+fn test_corpus() {
+    let result = conduct_mutation_testing(
+        "./tests/sample/test_vuln_iszero.circom".to_string(),
+        "random".to_string(),
+    );
+    assert!(result
+        .corpus_candidates
+        .iter()
+        .any(|c| c.kind == CorpusEntryKind::Counterexample));
+
+    let entry = |kind: CorpusEntryKind, fitness: &str, value: &str| CorpusEntry {
+        kind: kind,
+        fitness: fitness.to_string(),
+        input: json!({ "in": value }),
+    };
+    let dir = env::temp_dir().join(format!("zkfuzz_corpus_test_{}", std::process::id()));
+    let update = update_corpus(
+        &dir,
+        &[
+            entry(CorpusEntryKind::NearViolation, "-5", "1"),
+            entry(CorpusEntryKind::NearViolation, "-2", "2"),
+            entry(CorpusEntryKind::Coverage, "-1", "3"),
+            entry(CorpusEntryKind::Counterexample, "0", "4"),
+        ],
+        1,
+    );
+    // The same input is stored once, with its best kind
+    let reupdate = update_corpus(&dir, &[entry(CorpusEntryKind::Counterexample, "0", "2")], 1);
+    let num_files = fs::read_dir(&dir).map(|d| d.count());
+    fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(
+        update.unwrap(),
+        CorpusUpdate {
+            num_added: 4,
+            num_removed: 2,
+            num_entries: 2,
+        }
+    );
+    assert_eq!(
+        reupdate.unwrap(),
+        CorpusUpdate {
+            num_added: 0,
+            num_removed: 0,
+            num_entries: 2,
+        }
+    );
+    assert_eq!(num_files.unwrap(), 2);
+}
+
+#[test]
+fn test_vuln_iszero_multiple_counterexamples() {
+    let result = conduct_mutation_testing_with_config(
+        "./tests/sample/test_vuln_iszero.circom".to_string(),
+        "random".to_string(),
+        "./tests/parameters/test.json",
+        3,
+    );
+
+    assert_eq!(result.counter_examples.len(), 3);
+    assert!(result.counter_examples.iter().all(|ce| matches!(
+        ce.flag,
+        VerificationResult::UnderConstrained(UnderConstrainedType::NonDeterministic(..))
+    )));
+
+    let prime = BigInt::from_str(
+        "21888242871839275222246405745257275088548364400416034343698204186575808495617",
+    )
+    .unwrap();
+    let witnesses: HashSet<_> = result
+        .counter_examples
+        .iter()
+        .map(|ce| ce.witness_key(&prime))
+        .collect();
+    assert_eq!(witnesses.len(), 3);
+
+    let classes = classify_counterexamples(&result.counter_examples);
+    assert_eq!(
+        classes.iter().map(|(_, indices)| indices.len()).sum::<usize>(),
+        3
+    );
+}
+
+#[test]
+fn test_cluster_findings() {
+    let lookup = FxHashMap::from_iter([
+        (0, "main".to_string()),
+        (1, "a".to_string()),
+        (2, "b".to_string()),
+        (3, "x".to_string()),
+        (4, "y".to_string()),
+    ]);
+    let index = |i: i64| SymbolicAccess::ArrayAccess(SymbolicValue::ConstantInt(BigInt::from(i)));
+    // `(Eq main.<component>[<i>].<signal>[<j>] 1)`
+    let condition = |component: usize, i: i64, signal: usize, j: i64| {
+        let owner = vec![
+            OwnerName {
+                id: 0,
+                access: None,
+                counter: 0,
+            },
+            OwnerName {
+                id: component,
+                access: Some(vec![index(i)]),
+                counter: 0,
+            },
+        ];
+        SymbolicValue::BinaryOp(
+            Rc::new(SymbolicValue::Variable(SymbolicName::new(
+                signal,
+                Rc::new(owner),
+                Some(vec![index(j)]),
+            ))),
+            DebuggableExpressionInfixOpcode(ExpressionInfixOpcode::Eq),
+            Rc::new(SymbolicValue::ConstantInt(BigInt::from(1))),
+        )
+    };
+    let unexpected_input = |condition: &SymbolicValue| CounterExample {
+        flag: VerificationResult::UnderConstrained(UnderConstrainedType::UnexpectedInput(
+            0,
+            ViolatedConstraint::new(condition, &lookup),
+        )),
+        target_output: None,
+        assignment: FxHashMap::default(),
+    };
+    let conditions = vec![
+        condition(1, 1, 3, 0),
+        condition(2, 3, 3, 2),
+        condition(2, 3, 4, 2),
+        condition(1, 0, 3, 1),
+    ];
+    let counter_examples: Vec<_> = conditions.iter().map(unexpected_input).collect();
+    let clusters = cluster_findings(
+        counter_examples
+            .iter()
+            .enumerate()
+            .map(|(i, ce)| (FindingSignature::of(ce, &lookup), i)),
+    );
+
+    // The same signal of another component or at another index violates the same constraint
+    assert_eq!(clusters.len(), 2);
+    assert_eq!(clusters[0].count, 3);
+    assert_eq!(clusters[0].representative, 0);
+    assert_eq!(
+        clusters[0].signature.constraint,
+        strip_ansi(&conditions[0].lookup_fmt(&lookup))
+    );
+    assert_eq!(clusters[1].count, 1);
+    assert_eq!(clusters[1].representative, 2);
+    assert_ne!(
+        clusters[0].signature.structural_hash(),
+        clusters[1].signature.structural_hash()
+    );
+}
+
+#[test]
+fn test_vuln_iszero_reproducible_with_seed() {
+    let run = || {
+        conduct_mutation_testing(
+            "./tests/sample/test_vuln_iszero.circom".to_string(),
+            "random".to_string(),
+        )
+    };
+    let first = run();
+    let second = run();
+
+    assert_eq!(first.random_seed, 42);
+    assert_eq!(first.random_seed, second.random_seed);
+    assert_eq!(first.generation, second.generation);
+    assert_eq!(first.fitness_score_log, second.fitness_score_log);
+    assert_eq!(
+        first.counter_example.map(|ce| ce.assignment),
+        second.counter_example.map(|ce| ce.assignment)
+    );
+}
+
 #[test]
 fn test_vuln_average() {
     let result = conduct_mutation_testing(
@@ -266,3 +571,80 @@ fn test_array_template_parameter() {
         })
     ));
 }
+
+#[test]
+fn test_generate_test_harness() {
+    let prime = BigInt::from_str(
+        "21888242871839275222246405745257275088548364400416034343698204186575808495617",
+    )
+    .unwrap();
+
+    let (mut symbolic_library, program_archive) = prepare_symbolic_library(
+        "./tests/sample/test_vuln_iszero.circom".to_string(),
+        prime.clone(),
+    );
+    let setting = get_default_setting_for_symbolic_execution(prime.clone(), false);
+    let mut sexe = SymbolicExecutor::new(&mut symbolic_library, &setting);
+    execute(&mut sexe, &program_archive);
+
+    let verification_base_config = BaseVerificationConfig {
+        target_template_name: "VulnerableIsZero".to_string(),
+        prime: prime.clone(),
+        range: prime.clone(),
+        quick_mode: false,
+        heuristics_mode: false,
+        progress_interval: 10000,
+        template_param_names: Vec::new(),
+        template_param_values: Vec::new(),
+        check_output_tags: false,
+        max_counterexamples: 1,
+        input_shapes: gather_input_shapes(
+            sexe.symbolic_library,
+            "VulnerableIsZero",
+            &sexe.id2dimensions,
+        ),
+        input_spec: InputSpec::default(),
+    };
+    let symbolic_trace = sexe.cur_state.symbolic_trace.clone();
+    let side_constraints = sexe.cur_state.side_constraints.clone();
+    let id2dimensions = sexe.id2dimensions.clone();
+
+    let subse_base_config = get_default_setting_for_concrete_execution(prime, false);
+    let mut conc_executor = SymbolicExecutor::new(&mut sexe.symbolic_library, &subse_base_config);
+    let cases = generate_test_cases(
+        &mut conc_executor,
+        &symbolic_trace,
+        &side_constraints,
+        &verification_base_config,
+        &id2dimensions,
+        5,
+        1,
+    );
+
+    assert_eq!(cases.len(), 5);
+    for case in &cases {
+        let expected = if case.input["in"] == json!("0") {
+            "1"
+        } else {
+            "0"
+        };
+        assert_eq!(case.outputs, json!({"out": expected}));
+        assert_eq!(case.flat_inputs.len(), 1);
+        assert_eq!(case.flat_inputs[0].0, "in");
+        assert_eq!(case.flat_outputs, vec![BigInt::from_str(expected).unwrap()]);
+    }
+
+    let js = render_js_harness("VulnerableIsZero", "../circuit.circom", &cases);
+    assert!(js.contains("describe(\"VulnerableIsZero\""));
+    assert!(js.contains("path.join(__dirname, \"../circuit.circom\")"));
+    assert!(js.contains("circuit.assertOut(witness, testCase.outputs)"));
+
+    let rust = render_rust_harness("VulnerableIsZero", "../circuit.circom", &cases);
+    assert!(rust.contains("fn test_vulnerableiszero_accepts_honest_witnesses()"));
+    assert!(rust.contains("const WASM: &str = \"../circuit_js/circuit.wasm\";"));
+    assert!(rust.contains("const R1CS: &str = \"../circuit.r1cs\";"));
+    assert!(rust.contains(&format!("(&[(\"in\", \"{}\")]", cases[0].flat_inputs[0].1)));
+
+    assert_eq!(HarnessLanguage::from_str("rust"), Ok(HarnessLanguage::Rust));
+    assert!(HarnessLanguage::from_str("python").is_err());
+}
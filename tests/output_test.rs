@@ -0,0 +1,29 @@
+use zkfuzz::output_user::{render, set_plain, to_plain};
+
+#[test]
+fn test_to_plain() {
+    assert_eq!(to_plain("🎲 Random Seed: 7"), "Random Seed: 7");
+    assert_eq!(
+        to_plain("\x1b[33m⚖️ Constraints Summary:\x1b[0m"),
+        "Constraints Summary:"
+    );
+    assert_eq!(
+        to_plain("    ├─ 🏝️ Islands: 3 → 2"),
+        "    ├─ Islands: 3 → 2"
+    );
+    assert_eq!(
+        to_plain("\r\x1b[2K🧬 Generation: 1/10"),
+        "\rGeneration: 1/10"
+    );
+    // Spaces not following an emoji are kept
+    assert_eq!(to_plain("a  b • c ✅"), "a  b • c ");
+}
+
+#[test]
+fn test_render() {
+    set_plain(false);
+    assert_eq!(render("💾 Saving".to_string()), "💾 Saving");
+    set_plain(true);
+    assert_eq!(render("💾 Saving".to_string()), "Saving");
+    set_plain(false);
+}
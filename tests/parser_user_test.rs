@@ -0,0 +1,196 @@
+use std::env;
+use std::fs;
+
+use program_structure::ast::Expression;
+
+use zkfuzz::parser_user::{
+    include_path_literal, normalize_include_path, parse_entries, parse_project, parse_source,
+};
+
+#[test]
+fn test_parse_project_with_backslash_include_and_latin1_comment() {
+    let dir = env::temp_dir().join(format!("zkfuzz_parser_test_{}", std::process::id()));
+    fs::create_dir_all(dir.join("lib")).unwrap();
+    // `©` encoded in latin-1, which is not valid UTF-8
+    fs::write(
+        dir.join("lib").join("square.circom"),
+        b"pragma circom 2.0.0;\n\
+          // Copyright \xa9 zkFuzz\n\
+          template Square() {\n\
+              signal input in;\n\
+              signal output out;\n\
+              out <== in * in;\n\
+          }\n",
+    )
+    .unwrap();
+    fs::write(
+        dir.join("main.circom"),
+        "pragma circom 2.0.0;\n\
+         include \"lib\\\\square.circom\";\n\
+         template Main() {\n\
+             signal input in;\n\
+             signal output out;\n\
+             component sq = Square();\n\
+             sq.in <== in;\n\
+             out <== sq.out;\n\
+         }\n\
+         component main = Main();\n",
+    )
+    .unwrap();
+
+    let program_archive = parse_project(&dir.join("main.circom"), "bn128", &[]);
+    fs::remove_dir_all(&dir).unwrap();
+
+    let program_archive = program_archive.unwrap();
+    assert!(program_archive.templates.contains_key("Main"));
+    assert!(program_archive.templates.contains_key("Square"));
+}
+
+#[test]
+fn test_include_path_normalization() {
+    assert_eq!(
+        include_path_literal(&normalize_include_path("lib\\square.circom")),
+        "lib/square.circom"
+    );
+    assert_eq!(
+        include_path_literal(&normalize_include_path(
+            "../circomlib\\circuits/poseidon.circom"
+        )),
+        "../circomlib/circuits/poseidon.circom"
+    );
+}
+
+#[test]
+fn test_parse_source_with_virtual_includes() {
+    let main = "pragma circom 2.0.0;\n\
+                include \"lib/square.circom\";\n\
+                template Main() {\n\
+                    signal input in;\n\
+                    signal output out;\n\
+                    component sq = Square();\n\
+                    sq.in <== in;\n\
+                    out <== sq.out;\n\
+                }\n\
+                component main = Main();\n";
+    let resolver = |included: &str| match included {
+        "lib/square.circom" => Some(
+            "pragma circom 2.0.0;\n\
+             include \"mul.circom\";\n\
+             template Square() {\n\
+                 signal input in;\n\
+                 signal output out;\n\
+                 component m = Mul();\n\
+                 m.a <== in;\n\
+                 m.b <== in;\n\
+                 out <== m.out;\n\
+             }\n"
+            .to_string(),
+        ),
+        "mul.circom" => Some(
+            "pragma circom 2.0.0;\n\
+             template Mul() {\n\
+                 signal input a;\n\
+                 signal input b;\n\
+                 signal output out;\n\
+                 out <== a * b;\n\
+             }\n"
+            .to_string(),
+        ),
+        _ => None,
+    };
+
+    let program_archive = parse_source(main, resolver, "bn128", &[]).unwrap();
+    assert!(program_archive.templates.contains_key("Main"));
+    assert!(program_archive.templates.contains_key("Square"));
+    assert!(program_archive.templates.contains_key("Mul"));
+
+    // An include that the resolver does not provide is left to the parser
+    assert!(parse_source(main, |_| None, "bn128", &[]).is_err());
+}
+
+#[test]
+fn test_parse_entries_with_shared_include() {
+    let dir = env::temp_dir().join(format!("zkfuzz_entries_test_{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(
+        dir.join("square.circom"),
+        "pragma circom 2.0.0;\n\
+         template Square() {\n\
+             signal input in;\n\
+             signal output out;\n\
+             out <== in * in;\n\
+         }\n",
+    )
+    .unwrap();
+    fs::write(
+        dir.join("main_a.circom"),
+        "pragma circom 2.0.0;\n\
+         include \"square.circom\";\n\
+         /* component main = Square(); */\n\
+         template MainA() {\n\
+             signal input in;\n\
+             signal output out;\n\
+             component sq = Square();\n\
+             sq.in <== in;\n\
+             out <== sq.out;\n\
+         }\n\
+         component main = MainA();\n",
+    )
+    .unwrap();
+    fs::write(
+        dir.join("main_b.circom"),
+        "pragma circom 2.0.0;\n\
+         include \"square.circom\";\n\
+         template MainB(n) {\n\
+             signal input in[n];\n\
+             signal output out[n];\n\
+             component sq[n];\n\
+             for (var i = 0; i < n; i++) {\n\
+                 sq[i] = Square();\n\
+                 sq[i].in <== in[i];\n\
+                 out[i] <== sq[i].out;\n\
+             }\n\
+         }\n\
+         component main {public [in]} = MainB(2);\n",
+    )
+    .unwrap();
+
+    let parsed = parse_entries(
+        &[dir.join("main_a.circom"), dir.join("main_b.circom")],
+        "bn128",
+        &[],
+    );
+    let leftovers = fs::read_dir(&dir).unwrap().count();
+    fs::remove_dir_all(&dir).unwrap();
+
+    let (program_archive, entries) = parsed.unwrap();
+    // The copies without the main components are not written next to the entries
+    assert_eq!(leftovers, 3);
+    assert!(program_archive.templates.contains_key("MainA"));
+    assert!(program_archive.templates.contains_key("MainB"));
+    assert!(program_archive.templates.contains_key("Square"));
+
+    let main_templates: Vec<(String, usize)> = entries
+        .iter()
+        .map(|entry| match &entry.main_call {
+            Expression::Call { id, args, .. } => (id.clone(), args.len()),
+            _ => unreachable!(),
+        })
+        .collect();
+    assert_eq!(
+        main_templates,
+        vec![("MainA".to_string(), 0), ("MainB".to_string(), 1)]
+    );
+    assert_eq!(entries[1].path, dir.join("main_b.circom"));
+
+    // The copies are named after the entries rather than the removed temporary files
+    let file_id = program_archive.templates["MainA"].get_file_id();
+    let file_name = program_archive
+        .file_library
+        .to_storage()
+        .get(file_id)
+        .unwrap()
+        .name()
+        .clone();
+    assert!(file_name.contains(&dir.join("main_a.circom").display().to_string()));
+}
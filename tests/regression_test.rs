@@ -0,0 +1,180 @@
+use std::env;
+use std::fs;
+
+use zkfuzz::regression_user::{
+    declared_templates, run_regression_corpus, RegressionEntry, RegressionSnapshot,
+    RegressionStatus,
+};
+
+fn executed(file: &str, template: &str, num_trace: usize, num_side: usize) -> RegressionEntry {
+    RegressionEntry {
+        file: file.to_string(),
+        template: template.to_string(),
+        params: Vec::new(),
+        status: RegressionStatus::Executed,
+        num_trace_constraints: num_trace,
+        num_side_constraints: num_side,
+        panic_message: None,
+    }
+}
+
+#[test]
+fn test_declared_templates() {
+    let source = "pragma circom 2.0.6;\n\
+                  // template Commented(a) {}\n\
+                  /* template Blocked(a, b) {} */\n\
+                  template Square() {}\n\
+                  template custom Gate(a) {}\n\
+                  template parallel Sum(n, m) {}\n\
+                  function mytemplate(x) { return x; }\n";
+    assert_eq!(
+        declared_templates(source),
+        vec![
+            ("Square".to_string(), 0),
+            ("Gate".to_string(), 1),
+            ("Sum".to_string(), 2)
+        ]
+    );
+}
+
+#[test]
+fn test_run_regression_corpus() {
+    let dir = env::temp_dir().join(format!("zkfuzz_regression_test_{}", std::process::id()));
+    fs::create_dir_all(dir.join("arith")).unwrap();
+    fs::write(
+        dir.join("arith").join("sum.circom"),
+        "pragma circom 2.0.0;\n\
+         template Square() {\n\
+             signal input in;\n\
+             signal output out;\n\
+             out <== in * in;\n\
+         }\n\
+         template Sum(n) {\n\
+             signal input in[n];\n\
+             signal output out;\n\
+             var acc = 0;\n\
+             for (var i = 0; i < n; i++) {\n\
+                 acc += in[i];\n\
+             }\n\
+             out <== acc;\n\
+         }\n",
+    )
+    .unwrap();
+    fs::write(
+        dir.join("iszero.circom"),
+        "pragma circom 2.0.0;\n\
+         template IsZero() {\n\
+             signal input in;\n\
+             signal output out;\n\
+             signal inv;\n\
+             inv <-- in != 0 ? 1 / in : 0;\n\
+             out <== -in * inv + 1;\n\
+             in * out === 0;\n\
+         }\n",
+    )
+    .unwrap();
+    // Tests instantiating their own main component are skipped
+    fs::write(
+        dir.join("iszero_test.circom"),
+        "pragma circom 2.0.0;\n\
+         include \"iszero.circom\";\n\
+         component main = IsZero();\n",
+    )
+    .unwrap();
+
+    let entries = run_regression_corpus(&dir, "bn128", &[], None);
+    let names: Vec<(&str, &str)> = entries
+        .iter()
+        .map(|entry| (entry.file.as_str(), entry.template.as_str()))
+        .collect();
+    assert_eq!(
+        names,
+        vec![
+            ("arith/sum.circom", "Square"),
+            ("arith/sum.circom", "Sum"),
+            ("iszero.circom", "IsZero")
+        ]
+    );
+    for entry in &entries {
+        assert_eq!(entry.status, RegressionStatus::Executed);
+        assert!(entry.num_side_constraints > 0);
+    }
+    assert_eq!(entries[1].params, vec!["2".to_string()]);
+
+    // The arguments recorded in the snapshot are reused
+    let mut snapshot = RegressionSnapshot::new("bn128", entries.clone());
+    snapshot.entries[1].params = vec!["3".to_string()];
+    let rerun = run_regression_corpus(&dir, "bn128", &[], Some(&snapshot));
+    fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(rerun[1].params, vec!["3".to_string()]);
+    assert_eq!(rerun[1].status, RegressionStatus::Executed);
+    let comparison = RegressionSnapshot::new("bn128", entries).compare(&rerun);
+    assert!(!comparison.is_regression());
+}
+
+#[test]
+fn test_regression_snapshot_compare() {
+    let snapshot = RegressionSnapshot::new(
+        "bn128",
+        vec![
+            executed("a.circom", "A", 3, 2),
+            executed("a.circom", "B", 1, 1),
+            executed("b.circom", "C", 4, 4),
+        ],
+    );
+
+    let comparison = snapshot.compare(&snapshot.entries);
+    assert!(!comparison.is_regression());
+    assert!(comparison.added.is_empty());
+
+    let mut panicked = executed("a.circom", "B", 0, 0);
+    panicked.status = RegressionStatus::Panic;
+    panicked.panic_message = Some("index out of bounds".to_string());
+    let comparison = snapshot.compare(&[
+        executed("a.circom", "A", 3, 1),
+        panicked,
+        executed("c.circom", "D", 1, 1),
+    ]);
+    assert!(comparison.is_regression());
+    assert_eq!(comparison.changed.len(), 2);
+    assert_eq!(comparison.changed[0].0.num_side_constraints, 2);
+    assert_eq!(comparison.changed[0].1.num_side_constraints, 1);
+    assert_eq!(comparison.changed[1].1.outcome(), "panic");
+    assert_eq!(comparison.removed.len(), 1);
+    assert_eq!(comparison.removed[0].template, "C");
+    assert_eq!(comparison.added.len(), 1);
+    assert_eq!(comparison.added[0].template, "D");
+
+    // New templates alone are not regressions
+    let mut entries = snapshot.entries.clone();
+    entries.push(executed("c.circom", "D", 1, 1));
+    assert!(!snapshot.compare(&entries).is_regression());
+}
+
+#[test]
+fn test_regression_snapshot_save_and_load() {
+    let path = env::temp_dir().join("zkfuzz_regression_snapshot_test.json");
+    let mut panicked = executed("a.circom", "B", 0, 0);
+    panicked.status = RegressionStatus::Panic;
+    panicked.panic_message = Some("index out of bounds".to_string());
+    let snapshot =
+        RegressionSnapshot::new("bn128", vec![executed("a.circom", "A", 3, 2), panicked]);
+    snapshot.save(&path).unwrap();
+
+    let json = fs::read_to_string(&path).unwrap();
+    assert!(json.contains("\"status\": \"executed\""));
+    assert!(json.contains("\"status\": \"panic\""));
+    assert_eq!(json.matches("panic_message").count(), 1);
+    let loaded = RegressionSnapshot::load(&path).unwrap();
+    assert_eq!(loaded.prime, "bn128");
+    assert_eq!(loaded.entries, snapshot.entries);
+
+    fs::write(
+        &path,
+        "{\"version\": 2, \"prime\": \"bn128\", \"entries\": []}",
+    )
+    .unwrap();
+    assert!(RegressionSnapshot::load(&path).is_err());
+    fs::remove_file(&path).unwrap();
+}
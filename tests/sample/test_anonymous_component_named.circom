@@ -0,0 +1,29 @@
+pragma circom 2.1.5;
+
+template A(n){
+   signal input a;
+   signal input b;
+   signal output c;
+   c <== n*a + b;
+}
+
+template B(){
+   signal input in;
+   signal output o1;
+   signal output o2;
+   signal output o3;
+   o1 <== in + 1;
+   o2 <== in * in;
+   o3 <== in + 2;
+}
+
+template C(){
+   signal input x;
+   signal input y;
+   signal output out1;
+   signal output out2;
+   out1 <== A(2)(b <== y, a <== x);
+   (out2, _, _) <== B()(x);
+}
+
+component main = C();
@@ -0,0 +1,30 @@
+pragma circom 2.0.0;
+
+/**
+ * @template Inverse
+ * @description Computes the inverse of a non-zero input. The precondition `in != 0` is only
+ *              checked by `assert`, so it fails when the caller passes two equal values.
+ *
+ * @input {signal} in - A field element expected to be non-zero.
+ * @output {signal} out - The inverse of `in`.
+ */
+template Inverse() {
+    signal input in;
+    signal output out;
+
+    assert(in != 0);
+    out <-- 1 / in;
+    out * in === 1;
+}
+
+template InverseOfDifference() {
+    signal input a;
+    signal input b;
+    signal output out;
+
+    component inv = Inverse();
+    inv.in <== a - b;
+    out <== inv.out;
+}
+
+component main = InverseOfDifference();
@@ -0,0 +1,19 @@
+pragma circom 2.0.0;
+
+/**
+ * @template WeakSquare
+ * @description Computes `in * in` with `<--`, but only checks its square. Both `in * in` and
+ *              `-in * in` satisfy the constraint.
+ *
+ * @input {signal} in - The value to square.
+ * @output {signal} out - The square of `in`.
+ */
+template WeakSquare() {
+    signal input in;
+    signal output out;
+
+    out <-- in * in;
+    out * out === in * in * in * in;
+}
+
+component main = WeakSquare();
@@ -0,0 +1,18 @@
+pragma circom 2.0.0;
+
+template BitwiseSemantics() {
+    signal output out[8];
+
+    out[0] <== -1 >> 1;
+    // Truncated to the 254 bits of the prime before being reduced
+    out[1] <== 3 << 253;
+    out[2] <== 1 << 254;
+    // A negative amount shifts the other way
+    out[3] <== 6 >> -1;
+    out[4] <== ~0;
+    out[5] <== -1 | 1;
+    out[6] <== -1 ^ -2;
+    out[7] <== -1 << 1;
+}
+
+component main = BitwiseSemantics();
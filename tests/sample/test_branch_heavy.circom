@@ -0,0 +1,27 @@
+pragma circom 2.0.0;
+
+// Every iteration branches on an input signal, so that the symbolic execution merges `n`
+// branches, each within a state holding the whole `table`.
+template BranchHeavy(n) {
+    signal input in[n];
+    signal output out;
+
+    var table[256];
+    for (var i = 0; i < 256; i++) {
+        table[i] = i * i;
+    }
+
+    var acc = 0;
+    var parity = 0;
+    for (var i = 0; i < n; i++) {
+        if (in[i] == 0) {
+            acc += table[i];
+        } else {
+            acc += in[i];
+            parity = 1 - parity;
+        }
+    }
+    out <-- acc + parity;
+}
+
+component main = BranchHeavy(128);
@@ -0,0 +1,27 @@
+pragma circom 2.0.0;
+
+template Bit() {
+    signal input in;
+    in * (in - 1) === 0;
+}
+
+// Two nested loops and a branch, with one component instantiated in the inner loop.
+template Grid(n) {
+    signal input in[n][n];
+    signal output out;
+    component bits[n][n];
+
+    var sum = 0;
+    for (var i = 0; i < n; i++) {
+        for (var j = 0; j < n; j++) {
+            bits[i][j] = Bit();
+            bits[i][j].in <== in[i][j];
+            if (i == j) {
+                sum += in[i][j];
+            }
+        }
+    }
+    out <== sum;
+}
+
+component main = Grid(2);
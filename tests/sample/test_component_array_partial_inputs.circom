@@ -0,0 +1,31 @@
+pragma circom 2.0.0;
+
+template Sum() {
+    signal input a;
+    signal input b;
+    signal output c;
+    c <== a + b;
+}
+
+// The inputs of each instance of `s` are assigned across two loops, so that an instance is only
+// complete once the second loop reaches it.
+template Grid() {
+    signal input x[2][3];
+    signal output y[2][3];
+
+    component s[2][3];
+    for (var i = 0; i < 2; i++) {
+        for (var j = 0; j < 3; j++) {
+            s[i][j] = Sum();
+            s[i][j].a <== x[i][j];
+        }
+    }
+    for (var i = 0; i < 2; i++) {
+        for (var j = 0; j < 3; j++) {
+            s[i][j].b <== x[1 - i][2 - j];
+            y[i][j] <== s[i][j].c;
+        }
+    }
+}
+
+component main = Grid();
@@ -0,0 +1,28 @@
+pragma circom 2.0.0;
+
+template Square() {
+    signal input a;
+    signal output x;
+    signal output y;
+
+    x <== a * a;
+    y <== a + 1;
+}
+
+/**
+ * @template UncheckedSquare
+ * @description `tmp` is assigned from `sq.x` with `<--` but never constrained against it,
+ *              and `sq.y` is never consumed.
+ */
+template UncheckedSquare() {
+    signal input in;
+    signal output out;
+    signal tmp;
+
+    component sq = Square();
+    sq.a <== in;
+    tmp <-- sq.x;
+    out <== tmp * 2;
+}
+
+component main = UncheckedSquare();
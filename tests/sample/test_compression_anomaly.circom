@@ -0,0 +1,32 @@
+pragma circom 2.0.0;
+
+template Square() {
+    signal input in;
+    signal output out;
+
+    out <== in * in;
+}
+
+template Split() {
+    signal input in;
+    signal output hi;
+    signal output lo;
+
+    // Neither output is constrained
+    hi <-- in \ 256;
+    lo <-- in % 256;
+}
+
+template Main() {
+    signal input x;
+    signal output y;
+
+    component sq = Square();
+    sq.in <== x;
+    component split = Split();
+    split.in <== x;
+
+    y <== sq.out + split.hi + split.lo;
+}
+
+component main = Main();
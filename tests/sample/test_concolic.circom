@@ -0,0 +1,15 @@
+pragma circom 2.0.0;
+
+template Branch() {
+    signal input in;
+    signal output out;
+
+    if (in < 5) {
+        out <-- 1;
+    } else {
+        out <-- 0;
+    }
+    out * (out - 1) === 0;
+}
+
+component main = Branch();
@@ -0,0 +1,23 @@
+pragma circom 2.0.0;
+
+template IsZero() {
+    signal input in;
+    signal output out;
+    signal inv;
+
+    inv <-- in != 0 ? 1 / in : 0;
+    out <== -in * inv + 1;
+    in * out === 0;
+}
+
+template Main() {
+    signal input a;
+    signal input b;
+    signal output c;
+
+    component iz = IsZero();
+    iz.in <== a - b;
+    c <== iz.out;
+}
+
+component main = Main();
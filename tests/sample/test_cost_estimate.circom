@@ -0,0 +1,17 @@
+pragma circom 2.0.0;
+
+template Cost() {
+    signal input a;
+    signal input b;
+    signal input c;
+    signal output x;
+    signal output y;
+    signal output z;
+
+    x <== a * b + c;
+    y <== a + b + 1;
+    z <== x * (a - b);
+    a * b * c === z;
+}
+
+component main = Cost();
@@ -0,0 +1,30 @@
+pragma circom 2.0.6;
+pragma custom_templates;
+
+/**
+ * @template Square
+ * @description A custom gate whose constraint is provided by the proving backend.
+ *
+ * @input {signal} in - The value to square.
+ * @output {signal} out - The square of `in`.
+ */
+template custom Square() {
+    signal input in;
+    signal output out;
+
+    out <-- in * in;
+}
+
+template parallel SumOfSquares() {
+    signal input a;
+    signal input b;
+    signal output out;
+
+    component sa = parallel Square();
+    component sb = Square();
+    sa.in <== a;
+    sb.in <== b;
+    out <== sa.out + sb.out;
+}
+
+component main = SumOfSquares();
@@ -0,0 +1,24 @@
+pragma circom 2.0.0;
+
+// `Chain(n)` instantiates itself with the same parameter instead of `Chain(n - 1)`
+template Chain(n) {
+    signal input in;
+    signal output out;
+    if (n == 0) {
+        out <== in;
+    } else {
+        component next = Chain(n);
+        next.in <== in;
+        out <== next.out;
+    }
+}
+
+template Main() {
+    signal input in;
+    signal output out;
+    component chain = Chain(2);
+    chain.in <== in;
+    out <== chain.out;
+}
+
+component main = Main();
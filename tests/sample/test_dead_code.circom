@@ -0,0 +1,46 @@
+pragma circom 2.0.0;
+
+function double(x) {
+    return 2 * x;
+}
+
+function increment(x) {
+    return x + 1;
+}
+
+template Unused() {
+    signal input in;
+    signal output out;
+    out <== in;
+}
+
+template Pick(n) {
+    signal input in;
+    signal output out;
+
+    var k = 0;
+    if (n == 2) {
+        k = 1;
+    } else {
+        k = 2;
+    }
+
+    if (n > 1) {
+        out <== double(n) * in + k;
+    } else {
+        out <== in;
+    }
+}
+
+template Main() {
+    signal input in;
+    signal output out;
+
+    component p1 = Pick(2);
+    component p2 = Pick(3);
+    p1.in <== in;
+    p2.in <== in;
+    out <== p1.out + p2.out;
+}
+
+component main = Main();
@@ -0,0 +1,19 @@
+pragma circom 2.0.0;
+
+template ZeroCheck() {
+    signal input in;
+    signal output out;
+    signal inv;
+    inv <-- in != 0 ? 1 / in : 0;
+    out <== -in * inv + 1;
+}
+
+template Main() {
+    signal input a;
+    signal output b;
+    component z = ZeroCheck();
+    z.in <== a;
+    b <== a + z.out;
+}
+
+component main = Main();
@@ -0,0 +1,16 @@
+pragma circom 2.0.0;
+
+// The signals are declared out of alphabetical order, so that the order of circom's witness can
+// only be recovered from the declarations
+template Differential() {
+    signal input b;
+    signal input a[2];
+    signal output y;
+    signal output x[2];
+
+    x[0] <== a[0] + b;
+    x[1] <== a[1] * b;
+    y <== a[0] * a[1];
+}
+
+component main = Differential();
@@ -0,0 +1,22 @@
+pragma circom 2.0.0;
+
+/**
+ * @template Divide
+ * @description Computes `a / b`. When both inputs are zero, the witness generator evaluates
+ *              `0 / 0` to `0`, and `c * b === a` holds for any value of `c`.
+ *
+ * @input {signal} a - The dividend.
+ * @input {signal} b - The divisor.
+ * @output {signal} c - The quotient.
+ */
+template Divide() {
+    signal input a;
+    signal input b;
+    signal output c;
+
+    var two = 6 / 3;
+    c <-- a / b;
+    c * b === a * (two - 1);
+}
+
+component main = Divide();
@@ -0,0 +1,15 @@
+pragma circom 2.0.0;
+
+template Exploitability() {
+    signal input a;
+    signal input b;
+    signal output sum;
+    signal output doubled;
+    signal output tripled;
+
+    sum <-- a + b;
+    doubled <-- a * 2;
+    tripled <-- b * 3;
+}
+
+component main {public [a]} = Exploitability();
@@ -0,0 +1,16 @@
+pragma circom 2.0.0;
+
+function checked_half(x) {
+    assert(x < 100);
+    return x \ 2;
+}
+
+template Main() {
+    signal input in;
+    signal output out;
+
+    out <-- checked_half(in);
+    out * 2 === in;
+}
+
+component main = Main();
@@ -0,0 +1,15 @@
+pragma circom 2.0.0;
+
+function square(x) {
+    return x * x;
+}
+
+template Main() {
+    signal input in[4];
+    signal output out[4];
+    for (var i = 0; i < 4; i++) {
+        out[i] <== in[i] * square(3);
+    }
+}
+
+component main = Main();
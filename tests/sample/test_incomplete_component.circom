@@ -0,0 +1,25 @@
+pragma circom 2.0.0;
+
+template Sum() {
+    signal input a;
+    signal input b[2];
+    signal output c;
+    c <== a + b[0] + b[1];
+}
+
+// `s[1].b[1]` is never assigned, so `s[1]` is never executed.
+template Incomplete() {
+    signal input x;
+    signal output y;
+
+    component s[2];
+    for (var i = 0; i < 2; i++) {
+        s[i] = Sum();
+        s[i].a <== x;
+        s[i].b[0] <== x;
+    }
+    s[0].b[1] <== x;
+    y <== s[0].c;
+}
+
+component main = Incomplete();
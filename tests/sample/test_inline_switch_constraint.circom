@@ -0,0 +1,12 @@
+pragma circom 2.0.0;
+
+template Select() {
+    signal input s;
+    signal input a;
+    signal input b;
+    signal output out;
+
+    out <== s == 0 ? a : b;
+}
+
+component main = Select();
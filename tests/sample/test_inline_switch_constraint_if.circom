@@ -0,0 +1,16 @@
+pragma circom 2.0.0;
+
+template Select() {
+    signal input s;
+    signal input a;
+    signal input b;
+    signal output out;
+
+    if (s == 0) {
+        out <== a;
+    } else {
+        out <== b;
+    }
+}
+
+component main = Select();
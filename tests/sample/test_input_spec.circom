@@ -0,0 +1,23 @@
+pragma circom 2.0.0;
+
+/**
+ * @template Guarded
+ * @description `out` is only constrained when `a != 1`: for `a = 1`, the last constraint holds
+ *              whatever `out` is.
+ *
+ * @input {signal} a - An input expected to be at least 2.
+ * @output {signal} out - The square of `a`.
+ */
+template Guarded() {
+    signal input a;
+    signal output out;
+    signal sq;
+    signal diff;
+
+    sq <== a * a;
+    out <-- sq;
+    diff <== out - sq;
+    diff * (a - 1) === 0;
+}
+
+component main = Guarded();
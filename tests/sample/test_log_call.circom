@@ -0,0 +1,22 @@
+pragma circom 2.0.0;
+
+function double(x) {
+    log("double", x);
+    return x + x;
+}
+
+template LoggedIsZero() {
+    signal input in;
+    signal output out;
+    signal inv;
+
+    inv <-- in != 0 ? 1 / in : 0;
+    log("in =", in);
+    out <-- -in * inv + 1;
+    in * out === 0;
+
+    var d = double(in);
+    log("d =", d, "out =", out);
+}
+
+component main = LoggedIsZero();
@@ -0,0 +1,14 @@
+pragma circom 2.0.0;
+
+template WeightedSum(n, coeffs) {
+    signal input in[n];
+    signal output out;
+
+    var acc = 0;
+    for (var i = 0; i < n; i++) {
+        acc += coeffs[i] * in[i];
+    }
+    out <== acc;
+}
+
+component main = WeightedSum(2, [1, 1]);
@@ -0,0 +1,22 @@
+pragma circom 2.1.5;
+
+template Pair(){
+   signal input in;
+   signal output a[2];
+   signal output b;
+   a[0] <== in;
+   a[1] <== in * in;
+   b <== in + 1;
+}
+
+template Main(){
+   signal input x;
+   signal output arr[2];
+   signal output s;
+   signal output t[3];
+   signal output u;
+   (arr, s) <== Pair()(x);
+   (t, u) <== Pair()(x);
+}
+
+component main = Main();
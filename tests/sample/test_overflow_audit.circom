@@ -0,0 +1,15 @@
+pragma circom 2.0.0;
+
+template IntDivOverflow() {
+    signal input a;
+    signal input b;
+    signal input c;
+    signal output q;
+    signal output r;
+
+    q <-- (a * b) \ c;
+    r <-- a \ c;
+    q * c === a * b - r;
+}
+
+component main = IntDivOverflow();
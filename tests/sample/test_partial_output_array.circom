@@ -0,0 +1,31 @@
+pragma circom 2.0.0;
+
+template Pair() {
+    signal input a;
+    signal output out[2][2];
+
+    // `out[1][0]` and `out[1][1]` are never assigned
+    out[0][0] <== a;
+    out[0][1] <== a * a;
+}
+
+template Main() {
+    signal input in;
+    signal output out[3];
+    signal output full[4];
+    signal output sum;
+
+    // `out[1]` is never assigned
+    out[0] <== in;
+    out[2] <== in * in;
+
+    for (var i = 0; i < 4; i++) {
+        full[i] <== in + i;
+    }
+
+    component pair = Pair();
+    pair.a <== in;
+    sum <== pair.out[0][0] + pair.out[0][1];
+}
+
+component main = Main();
@@ -0,0 +1,10 @@
+pragma circom 2.0.0;
+
+template PartiallyUsedInput(n) {
+    signal input in[n][2];
+    signal input s;
+    signal output out;
+    out <== in[0][0] * in[n - 1][1];
+}
+
+component main = PartiallyUsedInput(3);
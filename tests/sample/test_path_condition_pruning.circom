@@ -0,0 +1,20 @@
+pragma circom 2.0.0;
+
+// `tmp` only matters when `s == 1`, so the search does not enumerate its values for the other
+// values of `s`. It is not constrained to `a * a`, so `out` is free when `s == 1`.
+template PrunedBranch() {
+    signal input s;
+    signal input a;
+    signal output out;
+    signal tmp;
+
+    var t = 0;
+    if (s == 1) {
+        tmp <-- a * a;
+        t = tmp;
+    }
+    out <-- t;
+    out * 1 === t;
+}
+
+component main = PrunedBranch();
@@ -0,0 +1,14 @@
+pragma circom 2.0.0;
+
+template Pretty() {
+    signal input a;
+    signal input b;
+    signal output c;
+    signal output d;
+
+    c <== (a + b) * (a - 1);
+    d <-- a / b;
+    d * b === a;
+}
+
+component main = Pretty();
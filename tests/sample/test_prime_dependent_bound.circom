@@ -0,0 +1,14 @@
+pragma circom 2.0.0;
+
+// `2 ** 63` is below `p / 2` over bn128, but above it over Goldilocks, where the comparison reads
+// it as a negative number. `out` is thus `1` for small inputs over bn128 and `0` over Goldilocks.
+template SignedBound() {
+    signal input in;
+    signal output out;
+
+    var bound = 2 ** 63;
+    out <-- in < bound ? 1 : 0;
+    out * (out - 1) === 0;
+}
+
+component main = SignedBound();
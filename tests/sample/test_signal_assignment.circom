@@ -0,0 +1,35 @@
+pragma circom 2.0.0;
+
+template Mul() {
+    signal input a;
+    signal input b;
+    signal output c;
+    c <== a * b;
+}
+
+template Main() {
+    signal input in;
+    signal input flag;
+    signal output out;
+    signal tmp;
+    signal sel;
+
+    // `tmp` is assigned twice, and the input `flag` by its own template
+    tmp <-- in + 1;
+    tmp <-- in + 2;
+    flag <-- 1;
+
+    // An assignment in both branches of an `if` counts once
+    if (in == 0) {
+        sel <-- 1;
+    } else {
+        sel <-- 0;
+    }
+
+    // `mul.b` is never fed, so `mul` is never executed and `mul.c` is never assigned
+    component mul = Mul();
+    mul.a <== in;
+    out <== mul.c + tmp * sel;
+}
+
+component main = Main();
@@ -0,0 +1,13 @@
+pragma circom 2.0.0;
+
+template TwoOutputs() {
+    signal input a;
+    signal input b;
+    signal output x;
+    signal output y;
+
+    x <== a * a;
+    y <== b + 1;
+}
+
+component main = TwoOutputs();
@@ -0,0 +1,22 @@
+pragma circom 2.0.0;
+
+/**
+ * @template StagedSquare
+ * @description Computes `in * in` with `<--`, but only checks its square, for a `mode` fixed to
+ *              `3` by a constraint. Both `in * in` and `-in * in` satisfy the constraints.
+ *
+ * @input {signal} mode - The mode, which has to be `3`.
+ * @input {signal} in - The value to square.
+ * @output {signal} out - The square of `in`.
+ */
+template StagedSquare() {
+    signal input mode;
+    signal input in;
+    signal output out;
+
+    mode === 3;
+    out <-- in * in;
+    out * out === in * in * in * in;
+}
+
+component main = StagedSquare();
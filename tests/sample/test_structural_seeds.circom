@@ -0,0 +1,15 @@
+pragma circom 2.0.0;
+
+// `out` is only free when `a == 1234` and `b` is a root of `(b - 7) * (b - 9)`, which the
+// quick mode only tries because they are derived from the constraints.
+template MagicValue() {
+    signal input a;
+    signal input b;
+    signal output out;
+
+    (b - 7) * (b - 9) === 0;
+    out <-- a == 1234 ? 5 : 0;
+    (a - 1234) * out === 0;
+}
+
+component main = MagicValue();
@@ -0,0 +1,29 @@
+pragma circom 2.0.0;
+
+template Select() {
+    signal input s;
+    signal input a;
+    signal output out;
+
+    var t = 0;
+    if (s == 0) {
+        t = a;
+        out <-- a;
+    } else {
+        t = a + 1;
+        out <-- a + 1;
+    }
+    out * 1 === t;
+}
+
+template Main() {
+    signal input s;
+    signal input a;
+    signal output out;
+    component c = Select();
+    c.s <== s;
+    c.a <== a;
+    out <== c.out;
+}
+
+component main = Main();
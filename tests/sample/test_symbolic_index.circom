@@ -0,0 +1,12 @@
+pragma circom 2.0.0;
+
+template Lookup() {
+    signal input in;
+    signal output out;
+
+    var table[3] = [5, 7, 11];
+    out <-- table[in];
+    (out - 5) * (out - 7) * (out - 11) === 0;
+}
+
+component main = Lookup();
@@ -0,0 +1,45 @@
+pragma circom 2.0.0;
+
+/**
+ * @template Select
+ * @description Selects `a` or `b` with an inline ternary. The condition depends on the signal
+ *              `sel`, so circom cannot constrain the selection as written.
+ *
+ * @input {signal} sel - The selector.
+ * @input {signal} a - The value selected when `sel` is nonzero.
+ * @input {signal} b - The value selected when `sel` is zero.
+ * @output {signal} out - The selected value.
+ */
+template Select() {
+    signal input sel;
+    signal input a;
+    signal input b;
+    signal output out;
+
+    out <== sel ? a : b;
+}
+
+/**
+ * @template Main
+ * @description Feeds `Select`, and selects with a ternary whose condition only depends on the
+ *              template parameter `n`, which is evaluated during compilation.
+ *
+ * @param {number} n - The template parameter.
+ */
+template Main(n) {
+    signal input sel;
+    signal input a;
+    signal input b;
+    signal output out;
+    signal output fixed;
+
+    component s = Select();
+    s.sel <== sel;
+    s.a <== a;
+    s.b <== b;
+    out <== s.out;
+
+    fixed <== n > 1 ? a : b;
+}
+
+component main = Main(2);
@@ -0,0 +1,18 @@
+pragma circom 2.1.0;
+
+/**
+ * @template TaggedOutput
+ * @description The output is tagged as `binary`, but nothing constrains it to be 0 or 1.
+ *              For `in = 0`, the output becomes `-1`, which violates the tag.
+ *
+ * @input {signal} in - A binary-tagged input.
+ * @output {signal} out - A binary-tagged output computed as `2 * in - 1`.
+ */
+template TaggedOutput() {
+    signal input {binary} in;
+    signal output {binary} out;
+
+    out <== in * 2 - 1;
+}
+
+component main = TaggedOutput();
@@ -0,0 +1,15 @@
+pragma circom 2.0.0;
+
+// The recursion never reaches its base case, whatever the input
+function countdown(x) {
+    return countdown(x - 1) + 1;
+}
+
+template Main() {
+    signal input in;
+    signal output out;
+    out <-- countdown(in);
+    out * (out - 1) === 0;
+}
+
+component main = Main();
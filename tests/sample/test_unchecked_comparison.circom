@@ -0,0 +1,76 @@
+pragma circom 2.0.0;
+
+template Num2Bits(n) {
+    signal input in;
+    signal output out[n];
+    var lc1=0;
+
+    var e2=1;
+    for (var i = 0; i<n; i++) {
+        out[i] <-- (in >> i) & 1;
+        out[i] * (out[i] -1 ) === 0;
+        lc1 += out[i] * e2;
+        e2 = e2+e2;
+    }
+
+    lc1 === in;
+}
+
+template Bits2Num(n) {
+    signal input in[n];
+    signal output out;
+    var lc1=0;
+
+    var e2 = 1;
+    for (var i = 0; i<n; i++) {
+        lc1 += in[i] * e2;
+        e2 = e2 + e2;
+    }
+
+    lc1 ==> out;
+}
+
+template LessThan(n) {
+    assert(n <= 252);
+    signal input in[2];
+    signal output out;
+
+    component n2b = Num2Bits(n+1);
+
+    n2b.in <== in[0]+ (1<<n) - in[1];
+
+    out <== 1-n2b.out[n];
+}
+
+/**
+ * @template UncheckedComparison
+ * @description `a` is range-checked to 8 bits, while `b` and `c` are never range-checked and
+ *              `a` is compared as a 4-bit integer.
+ */
+template UncheckedComparison() {
+    signal input a;
+    signal input b;
+    signal input c;
+    signal input d;
+    signal output out;
+
+    component n2b = Num2Bits(8);
+    n2b.in <== a;
+
+    component lt1 = LessThan(8);
+    lt1.in[0] <== a;
+    lt1.in[1] <== b;
+
+    component lt2 = LessThan(4);
+    lt2.in[0] <== a;
+    lt2.in[1] <== 3;
+
+    d * (d - 1) === 0;
+    component b2n = Bits2Num(2);
+    b2n.in[0] <== d;
+    b2n.in[1] <== c;
+
+    out <== lt1.out + lt2.out + b2n.out;
+}
+
+component main = UncheckedComparison();
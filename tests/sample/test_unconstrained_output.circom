@@ -0,0 +1,15 @@
+pragma circom 2.0.0;
+
+template UnconstrainedOutput() {
+    signal input a;
+    signal input b;
+    signal output sum;
+    signal output prod;
+
+    // `sum` is computed but never constrained
+    sum <-- a + b;
+    prod <-- a * b;
+    prod === a * b;
+}
+
+component main = UnconstrainedOutput();
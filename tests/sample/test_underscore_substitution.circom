@@ -0,0 +1,21 @@
+pragma circom 2.1.5;
+
+template Boolean() {
+    signal input in;
+    signal output out;
+
+    in * (in - 1) === 0;
+    out <== 1 - in;
+}
+
+template Main() {
+    signal input a;
+    signal input b;
+    signal output c;
+
+    // Only the constraints of the component are needed
+    _ <== Boolean()(a);
+    c <== a * b;
+}
+
+component main = Main();
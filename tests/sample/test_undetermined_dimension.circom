@@ -0,0 +1,45 @@
+pragma circom 2.0.0;
+
+function sumUpTo(n) {
+    var half = n \ 2;
+    var len = half + 1;
+    var arr[len];
+    var total = 0;
+    for (var i = 0; i < len; i++) {
+        arr[i] = i;
+        total += arr[i];
+    }
+    return total;
+}
+
+function identity(x) {
+    var arr[x];
+    return x;
+}
+
+template Negative(n) {
+    signal input in;
+    signal output out;
+
+    var arr[n - 5];
+    out <== in;
+}
+
+template UndeterminedDimension() {
+    signal input in;
+    signal output out1;
+    signal output out2;
+
+    // `arr` of `sumUpTo` has the size 3
+    out1 <== in * sumUpTo(4);
+
+    // `arr` of `identity` has the size `in`
+    out2 <-- identity(in);
+    out2 === in;
+
+    // `arr` of `Negative(2)` has the size -3
+    component neg = Negative(2);
+    neg.in <== in;
+}
+
+component main = UndeterminedDimension();
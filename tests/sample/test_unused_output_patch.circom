@@ -0,0 +1,16 @@
+pragma circom 2.0.0;
+
+template UnusedOutputPatch(n) {
+    signal input a;
+    signal input b;
+    signal output out;
+    signal output prod;
+    signal output unused;
+
+    out <== a + b;
+    if (n > 5) {
+        prod <-- a * b;
+    }
+}
+
+component main = UnusedOutputPatch(2);
@@ -0,0 +1,13 @@
+pragma circom 2.0.0;
+
+// `out` is only checked to be a bit, so a bit other than the one computed by the witness
+// generator is accepted whenever `in` is not zero
+template LooseIsZero() {
+    signal input in;
+    signal output out;
+
+    out <-- in == 0 ? 1 : 0;
+    out * (out - 1) === 0;
+}
+
+component main = LooseIsZero();
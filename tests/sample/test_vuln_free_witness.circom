@@ -0,0 +1,32 @@
+pragma circom 2.0.0;
+
+/**
+ * @template VulnerableIsZero
+ * @description The inverse `inv` is assigned with `<--` and only bound by `out <== -in*inv + 1`, so a malicious
+ *              prover can pick any `inv` and make `out` differ from its honest value, even though `in` is fixed
+ *              by the parent component.
+ */
+template VulnerableIsZero() {
+    signal input in;
+    signal output out;
+    signal inv;
+
+    inv <-- in!=0 ? 1/in : 0;
+    out <== -in*inv +1;
+}
+
+/**
+ * @template Main
+ * @description Only the input of the main component is chosen by the verifier, while the free witness `inv`
+ *              lives in the sub-component `iz`.
+ */
+template Main() {
+    signal input a;
+    signal output b;
+
+    component iz = VulnerableIsZero();
+    iz.in <== a;
+    b <== iz.out;
+}
+
+component main = Main();
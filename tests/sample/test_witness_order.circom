@@ -0,0 +1,28 @@
+pragma circom 2.0.0;
+
+template Double() {
+    signal input in;
+    signal output out;
+    out <== 2 * in;
+}
+
+template Main() {
+    signal input in;
+    signal output out;
+    signal a;
+    signal b;
+    signal early;
+
+    // `a` and `b` are computed from each other
+    a <-- b + in;
+    b <-- a * 2;
+
+    // `early` reads the output of `d` before `d` is fed
+    component d = Double();
+    early <-- d.out + 1;
+    d.in <== in;
+
+    out <== a + b + early;
+}
+
+component main = Main();
@@ -0,0 +1,76 @@
+mod utils;
+
+use std::str::FromStr;
+
+use num_bigint_dig::BigInt;
+
+use zkfuzz::executor::symbolic_execution::SymbolicExecutor;
+use zkfuzz::executor::symbolic_setting::get_default_setting_for_symbolic_execution;
+use zkfuzz::mutator::signal_explanation::explain_signal;
+use zkfuzz::stats::pretty_print::{PrettyFormat, PrettyPrinter};
+
+use crate::utils::{execute, prepare_symbolic_library};
+
+#[test]
+fn test_explain_signal_of_vuln_iszero() {
+    let prime = BigInt::from_str(
+        "21888242871839275222246405745257275088548364400416034343698204186575808495617",
+    )
+    .unwrap();
+    let path = "./tests/sample/test_vuln_iszero.circom".to_string();
+    let (mut symbolic_library, program_archive) = prepare_symbolic_library(path, prime.clone());
+    let setting = get_default_setting_for_symbolic_execution(prime.clone(), false);
+    let mut sexe = SymbolicExecutor::new(&mut symbolic_library, &setting);
+    execute(&mut sexe, &program_archive);
+
+    let id2name = &sexe.symbolic_library.id2name;
+    let explanation = explain_signal(
+        &sexe.cur_state.symbolic_trace,
+        &sexe.cur_state.side_constraints,
+        id2name,
+        "main.inv",
+    );
+    let printer = PrettyPrinter::new(PrettyFormat::Text, id2name, &prime);
+
+    // `inv <-- in != 0 ? 1 / in : 0` is only checked through `out <== -in * inv + 1`
+    assert_eq!(explanation.definitions.len(), 1);
+    assert!(printer
+        .fmt_constraint(&explanation.definitions[0].1)
+        .starts_with("main.inv <--"));
+    assert!(!explanation.side_constraints.is_empty());
+    let trace_only: Vec<String> = explanation
+        .trace_only
+        .iter()
+        .map(|subterm| printer.fmt(subterm))
+        .collect();
+    assert!(trace_only.iter().any(|subterm| subterm.contains("!=")));
+    assert!(trace_only.iter().any(|subterm| subterm.contains('/')));
+    assert!(!trace_only.iter().any(|subterm| subterm == "main.inv"));
+
+    // `out` is constrained with the same expression it is computed with
+    let explanation = explain_signal(
+        &sexe.cur_state.symbolic_trace,
+        &sexe.cur_state.side_constraints,
+        id2name,
+        "main.out",
+    );
+    assert_eq!(explanation.definitions.len(), 1);
+    assert!(explanation.trace_only.is_empty());
+
+    let explanation = explain_signal(
+        &sexe.cur_state.symbolic_trace,
+        &sexe.cur_state.side_constraints,
+        id2name,
+        "main.in",
+    );
+    assert!(explanation.definitions.is_empty());
+    assert!(!explanation.side_constraints.is_empty());
+
+    let explanation = explain_signal(
+        &sexe.cur_state.symbolic_trace,
+        &sexe.cur_state.side_constraints,
+        id2name,
+        "main.i",
+    );
+    assert!(explanation.is_empty());
+}
@@ -0,0 +1,344 @@
+mod utils;
+
+use std::str::FromStr;
+use std::time;
+
+use num_bigint_dig::BigInt;
+
+use zkfuzz::executor::symbolic_execution::SymbolicExecutor;
+use zkfuzz::executor::symbolic_setting::get_default_setting_for_symbolic_execution;
+use zkfuzz::mutator::severity::Severity;
+use zkfuzz::stats::ast_stats::ControlFlowGraph;
+use zkfuzz::stats::constraint_diff::diff_constraints;
+use zkfuzz::stats::cost_estimate::estimate_cost;
+use zkfuzz::stats::html_report::HtmlReport;
+use zkfuzz::stats::pretty_print::{wrap, PrettyFormat, PrettyPrinter};
+use zkfuzz::stats::symbolic_stats::{
+    component_statistics_csv, find_compression_anomalies, ConstraintStatistics,
+    COMPRESSION_ANOMALY_THRESHOLD,
+};
+
+use crate::utils::{execute, parse_project, prepare_symbolic_library};
+
+#[test]
+fn test_component_statistics() {
+    let path = "./tests/sample/test_component_boundary.circom".to_string();
+    let prime = BigInt::from_str(
+        "21888242871839275222246405745257275088548364400416034343698204186575808495617",
+    )
+    .unwrap();
+
+    let (mut symbolic_library, program_archive) = prepare_symbolic_library(path, prime.clone());
+    let setting = get_default_setting_for_symbolic_execution(prime, false);
+
+    let mut sexe = SymbolicExecutor::new(&mut symbolic_library, &setting);
+    execute(&mut sexe, &program_archive);
+
+    let mut ts = ConstraintStatistics::new();
+    let ss = ConstraintStatistics::new();
+    for c in &sexe.cur_state.symbolic_trace {
+        ts.update(c);
+    }
+
+    let id2name = &sexe.symbolic_library.id2name;
+    let rows = ts.sorted_component_stats(id2name, "component");
+    let names: Vec<_> = rows.iter().map(|(name, _)| name.clone()).collect();
+    assert_eq!(names, vec!["main".to_string(), "main.sq".to_string()]);
+    assert_eq!(
+        rows.iter().map(|(_, c)| c.num_constraints).sum::<usize>(),
+        ts.total_constraints
+    );
+
+    // `x <== a * a` is the only nonlinear constraint, and it is owned by `sq`.
+    let (_, main_stats) = &rows[0];
+    let (_, sq_stats) = &rows[1];
+    assert_eq!(main_stats.nonlinear_counts, 0);
+    assert_eq!(sq_stats.nonlinear_counts, 1);
+    assert_eq!(sq_stats.operator_counts.get("Mul"), Some(&1));
+
+    let sorted_by_nonlinear = ts.sorted_component_stats(id2name, "nonlinear");
+    assert_eq!(sorted_by_nonlinear[0].0, "main.sq");
+
+    let csv = component_statistics_csv(&ts, &ss, id2name, "component");
+    let lines: Vec<_> = csv.lines().collect();
+    assert_eq!(lines.len(), 3);
+    assert!(lines[0].starts_with("Kind,Component,Constraints,Nonlinear,Max_Depth,"));
+    assert!(lines[2].starts_with("trace,main.sq,"));
+}
+
+#[test]
+fn test_html_report() {
+    let path = "./tests/sample/test_component_boundary.circom".to_string();
+    let prime = BigInt::from_str(
+        "21888242871839275222246405745257275088548364400416034343698204186575808495617",
+    )
+    .unwrap();
+
+    let (mut symbolic_library, program_archive) = prepare_symbolic_library(path, prime.clone());
+    let setting = get_default_setting_for_symbolic_execution(prime, false);
+
+    let mut sexe = SymbolicExecutor::new(&mut symbolic_library, &setting);
+    execute(&mut sexe, &program_archive);
+
+    let mut ts = ConstraintStatistics::new();
+    let mut ss = ConstraintStatistics::new();
+    for c in &sexe.cur_state.symbolic_trace {
+        ts.update(c);
+    }
+    for c in &sexe.cur_state.side_constraints {
+        ss.update(c);
+    }
+    let cost_estimate = estimate_cost(&sexe.cur_state.side_constraints, &[3]);
+
+    // Give the component and a signal names with the characters that have a meaning in HTML
+    let mut id2name = sexe.symbolic_library.id2name.clone();
+    id2name.insert(sexe.symbolic_library.name2id["sq"], "sq<&>".to_string());
+    id2name.insert(sexe.symbolic_library.name2id["tmp"], "tmp\"'".to_string());
+
+    let report = HtmlReport {
+        target_path: "circuits/<a>.circom",
+        main_template: "Unchecked<Square>",
+        prime: "bn128",
+        verdict: "<script>",
+        execution_time: time::Duration::from_millis(1),
+        trace_stats: &ts,
+        side_stats: &ss,
+        cost_estimate: &cost_estimate,
+        compression_anomalies: &[],
+        findings: vec![(
+            Severity::Low,
+            "Unused<Output>",
+            "main.sq.y is never consumed".to_string(),
+        )],
+        counter_examples: &[],
+        exploitabilities: &[],
+        symbolic_trace: &sexe.cur_state.symbolic_trace,
+        id2name: &id2name,
+    };
+    let html = report.render();
+
+    assert!(html.contains("<title>zkFuzz Report: circuits/&lt;a&gt;.circom</title>"));
+    assert!(html.contains("<td>Unchecked&lt;Square&gt;</td>"));
+    assert!(html.contains("(&lt;script&gt;)"));
+    assert!(html.contains("<b>Unused&lt;Output&gt;</b>: main.sq.y is never consumed"));
+    assert!(html.contains("<code>main.sq&lt;&amp;&gt;</code>"));
+    assert!(html.contains("main.tmp&quot;&#39;"));
+    for raw in ["<a>", "Unchecked<Square>", "<script>", "sq<&>", "tmp\"'"] {
+        assert!(!html.contains(raw), "{} is not escaped", raw);
+    }
+
+    // The sections are rendered in order
+    let sections: Vec<usize> = [
+        "<h2>📊 Execution Summary</h2>",
+        "<h2>🪶 Constraint Statistics</h2>",
+        "<h3>🧩 Per-Component Statistics</h3>",
+        "<h3>💰 Estimated Proving Cost</h3>",
+        "<h2>🔎 Findings</h2>",
+        "<h2>🚨 Counterexamples</h2>",
+        "<h2>🛠️ Symbolic Trace</h2>",
+    ]
+    .iter()
+    .map(|section| html.find(section).expect(section))
+    .collect();
+    assert!(sections.windows(2).all(|w| w[0] < w[1]));
+    assert!(html.contains("<p>No counterexample was found.</p>"));
+    assert!(!html.contains("Low-Compression Components"));
+    assert!(html.ends_with("</body>\n</html>\n"));
+}
+
+#[test]
+fn test_compression_anomalies() {
+    let path = "./tests/sample/test_compression_anomaly.circom".to_string();
+    let prime = BigInt::from_str(
+        "21888242871839275222246405745257275088548364400416034343698204186575808495617",
+    )
+    .unwrap();
+
+    let (mut symbolic_library, program_archive) = prepare_symbolic_library(path, prime.clone());
+    let setting = get_default_setting_for_symbolic_execution(prime, false);
+
+    let mut sexe = SymbolicExecutor::new(&mut symbolic_library, &setting);
+    execute(&mut sexe, &program_archive);
+
+    let mut ts = ConstraintStatistics::new();
+    let mut ss = ConstraintStatistics::new();
+    for c in &sexe.cur_state.symbolic_trace {
+        ts.update(c);
+    }
+    for c in &sexe.cur_state.side_constraints {
+        ss.update(c);
+    }
+
+    let id2name = &sexe.symbolic_library.id2name;
+    let anomalies = find_compression_anomalies(&ts, &ss, id2name, COMPRESSION_ANOMALY_THRESHOLD, 5);
+
+    // `split` constrains only its input, while `sq` and `main` constrain every signal.
+    assert_eq!(anomalies.len(), 1);
+    assert_eq!(anomalies[0].owner, "main.split");
+    assert_eq!(anomalies[0].trace_constraints, 3);
+    assert_eq!(anomalies[0].side_constraints, 1);
+    assert_eq!(anomalies[0].num_missing(), 2);
+
+    assert!(
+        find_compression_anomalies(&ts, &ss, id2name, COMPRESSION_ANOMALY_THRESHOLD, 0).is_empty()
+    );
+}
+
+#[test]
+fn test_constraint_diff() {
+    let prime = BigInt::from_str(
+        "21888242871839275222246405745257275088548364400416034343698204186575808495617",
+    )
+    .unwrap();
+    let setting = get_default_setting_for_symbolic_execution(prime.clone(), false);
+
+    let (mut old_library, old_archive) = prepare_symbolic_library(
+        "./tests/sample/test_diff_old.circom".to_string(),
+        prime.clone(),
+    );
+    let mut old_sexe = SymbolicExecutor::new(&mut old_library, &setting);
+    execute(&mut old_sexe, &old_archive);
+
+    let (mut new_library, new_archive) = prepare_symbolic_library(
+        "./tests/sample/test_diff_new.circom".to_string(),
+        prime.clone(),
+    );
+    let mut new_sexe = SymbolicExecutor::new(&mut new_library, &setting);
+    execute(&mut new_sexe, &new_archive);
+
+    assert!(diff_constraints(&old_sexe, "Main", &old_sexe, "Main").is_empty());
+
+    let diffs = diff_constraints(&old_sexe, "Main", &new_sexe, "Main");
+    let names: Vec<_> = diffs.iter().map(|d| d.template_name.clone()).collect();
+    assert_eq!(names, vec!["Main".to_string(), "ZeroCheck".to_string()]);
+
+    // `b <== a * z.out` became `b <== a + z.out`
+    let main_diff = &diffs[0];
+    assert!(main_diff.side.added.is_empty() && main_diff.side.removed.is_empty());
+    assert_eq!(main_diff.side.changed.len(), 1);
+    assert!(main_diff.side.changed[0].0.contains('*'));
+    assert!(main_diff.side.changed[0].1.contains('+'));
+
+    // `in * out === 0` was dropped
+    let zero_check_diff = &diffs[1];
+    assert!(zero_check_diff.side.added.is_empty() && zero_check_diff.side.changed.is_empty());
+    assert_eq!(zero_check_diff.side.removed.len(), 1);
+    assert!(zero_check_diff.side.removed[0].ends_with("=== 0"));
+    assert_eq!(zero_check_diff.trace.removed, zero_check_diff.side.removed);
+}
+
+#[test]
+fn test_cost_estimate() {
+    let path = "./tests/sample/test_cost_estimate.circom".to_string();
+    let prime = BigInt::from_str(
+        "21888242871839275222246405745257275088548364400416034343698204186575808495617",
+    )
+    .unwrap();
+
+    let (mut symbolic_library, program_archive) = prepare_symbolic_library(path, prime.clone());
+    let setting = get_default_setting_for_symbolic_execution(prime, false);
+
+    let mut sexe = SymbolicExecutor::new(&mut symbolic_library, &setting);
+    execute(&mut sexe, &program_archive);
+
+    let estimate = estimate_cost(&sexe.cur_state.side_constraints, &[3, 4]);
+    // `a * b * c === z` needs an intermediate signal for `a * b`
+    assert_eq!(estimate.r1cs_constraints, 5);
+    assert_eq!(estimate.linear_constraints, 1);
+    assert_eq!(estimate.non_quadratic_constraints, 1);
+    assert_eq!(estimate.intermediate_signals, 1);
+    // `a`, `b`, `c`, `x`, `y`, `z`, the intermediate signal, and the constant `1`
+    assert_eq!(estimate.wires, 8);
+    assert_eq!(estimate.nonzero_entries, 22);
+    assert_eq!(estimate.groth16.domain_size, 8);
+
+    assert_eq!(estimate.plonk.len(), 2);
+    assert_eq!(estimate.plonk[0].width, 3);
+    assert_eq!(estimate.plonk[0].gates, 9);
+    // Wider gates sum more wires at once
+    assert!(estimate.plonk[1].gates < estimate.plonk[0].gates);
+    assert!(estimate.plonk[0].prover.memory_bytes > 0);
+}
+
+#[test]
+fn test_control_flow_graph() {
+    let path = "./tests/sample/test_cfg_metrics.circom".to_string();
+    let prime = BigInt::from_str(
+        "21888242871839275222246405745257275088548364400416034343698204186575808495617",
+    )
+    .unwrap();
+    let program_archive = parse_project(path, prime).unwrap();
+
+    let grid = ControlFlowGraph::build(program_archive.templates["Grid"].get_body());
+    assert_eq!(grid.cyclomatic_complexity(), 4);
+    assert_eq!(grid.max_loop_depth, 2);
+    assert_eq!(grid.num_component_instantiations, 1);
+    assert_eq!(grid.get_csv(), "4,2,1");
+
+    let dot = grid.to_dot("Grid");
+    assert!(dot.starts_with("digraph \"Grid\" {"));
+    assert_eq!(dot.matches("[label=\"true\"]").count(), 3);
+    assert_eq!(dot.matches("[label=\"false\"]").count(), 3);
+    assert!(dot.contains("bits ="));
+
+    let bit = ControlFlowGraph::build(program_archive.templates["Bit"].get_body());
+    assert_eq!(bit.get_csv(), "1,0,0");
+}
+
+#[test]
+fn test_pretty_print() {
+    let path = "./tests/sample/test_pretty_print.circom".to_string();
+    let prime = BigInt::from_str(
+        "21888242871839275222246405745257275088548364400416034343698204186575808495617",
+    )
+    .unwrap();
+
+    let (mut symbolic_library, program_archive) = prepare_symbolic_library(path, prime.clone());
+    let setting = get_default_setting_for_symbolic_execution(prime.clone(), false);
+
+    let mut sexe = SymbolicExecutor::new(&mut symbolic_library, &setting);
+    execute(&mut sexe, &program_archive);
+
+    let id2name = &sexe.symbolic_library.id2name;
+    let text = PrettyPrinter::new(PrettyFormat::Text, id2name, &prime);
+    let trace: Vec<String> = sexe
+        .cur_state
+        .symbolic_trace
+        .iter()
+        .map(|c| text.fmt_constraint(c))
+        .collect();
+    assert!(trace.contains(&"main.c <== (main.a + main.b) * (main.a - 1)".to_string()));
+    assert!(trace.contains(&"main.d <-- main.a / main.b".to_string()));
+    let side: Vec<String> = sexe
+        .cur_state
+        .side_constraints
+        .iter()
+        .map(|c| text.fmt_constraint(c))
+        .collect();
+    assert!(side.contains(&"main.c === (main.a + main.b) * (main.a - 1)".to_string()));
+    assert!(side.contains(&"main.d * main.b === main.a".to_string()));
+
+    let latex = PrettyPrinter::new(PrettyFormat::Latex, id2name, &prime);
+    let trace: Vec<String> = sexe
+        .cur_state
+        .symbolic_trace
+        .iter()
+        .map(|c| latex.fmt_constraint(c))
+        .collect();
+    assert!(trace.contains(
+        &"\\mathtt{main.c} \\Longleftarrow (\\mathtt{main.a} + \\mathtt{main.b}) \\cdot (\\mathtt{main.a} - 1)"
+            .to_string()
+    ));
+    assert!(trace.contains(
+        &"\\mathtt{main.d} \\leftarrow \\frac{\\mathtt{main.a}}{\\mathtt{main.b}}".to_string()
+    ));
+
+    // Lines are broken before an operator, and never within brackets.
+    assert_eq!(wrap("a + b * c + d", 7), vec!["a + b", "* c + d"]);
+    assert_eq!(wrap("x[i + 1] + y", 5), vec!["x[i + 1]", "+ y"]);
+    assert_eq!(wrap("a + b * c + d", 0), vec!["a + b * c + d"]);
+    assert_eq!(
+        text.render(&sexe.cur_state.side_constraints[0], 1000).len(),
+        1
+    );
+}
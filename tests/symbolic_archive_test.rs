@@ -0,0 +1,70 @@
+mod utils;
+
+use std::env;
+use std::rc::Rc;
+use std::str::FromStr;
+
+use num_bigint_dig::BigInt;
+use rustc_hash::FxHashSet;
+
+use zkfuzz::executor::symbolic_archive::SymbolicLibraryArchive;
+use zkfuzz::executor::symbolic_execution::SymbolicExecutor;
+use zkfuzz::executor::symbolic_setting::get_default_setting_for_symbolic_execution;
+use zkfuzz::executor::symbolic_value::SymbolicLibrary;
+use zkfuzz::executor::template_semantics::LessThanSemantics;
+
+use crate::utils::{execute, prepare_symbolic_library};
+
+#[test]
+fn test_symbolic_library_archive_roundtrip() {
+    let prime = BigInt::from_str(
+        "21888242871839275222246405745257275088548364400416034343698204186575808495617",
+    )
+    .unwrap();
+
+    for path in [
+        "./tests/sample/test_lessthan.circom",
+        "./tests/sample/test_multidimensional_array_function.circom",
+    ] {
+        let (mut symbolic_library, program_archive) =
+            prepare_symbolic_library(path.to_string(), prime.clone());
+
+        let archive_path = env::temp_dir().join(format!(
+            "zkfuzz_archive_test_{}.zklib",
+            std::process::id()
+        ));
+        SymbolicLibraryArchive::from_library(&symbolic_library, Vec::new(), &[])
+            .save(&archive_path)
+            .unwrap();
+        let archive = SymbolicLibraryArchive::load(&archive_path).unwrap();
+        std::fs::remove_file(&archive_path).unwrap();
+
+        let mut linked_library = SymbolicLibrary::default();
+        archive
+            .link_into(&mut linked_library, &FxHashSet::default())
+            .unwrap();
+        linked_library.register_template_semantics(Rc::new(LessThanSemantics));
+
+        // An archive cannot be linked into a library that already has names registered
+        assert!(
+            SymbolicLibraryArchive::from_library(&linked_library, Vec::new(), &[])
+                .link_into(&mut linked_library.clone(), &FxHashSet::default())
+                .is_err()
+        );
+
+        let setting = get_default_setting_for_symbolic_execution(prime.clone(), false);
+        let mut sexe = SymbolicExecutor::new(&mut symbolic_library, &setting);
+        execute(&mut sexe, &program_archive);
+        let mut linked_sexe = SymbolicExecutor::new(&mut linked_library, &setting);
+        execute(&mut linked_sexe, &program_archive);
+
+        assert_eq!(
+            sexe.cur_state.symbolic_trace,
+            linked_sexe.cur_state.symbolic_trace
+        );
+        assert_eq!(
+            sexe.cur_state.side_constraints,
+            linked_sexe.cur_state.side_constraints
+        );
+    }
+}
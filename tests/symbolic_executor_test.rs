@@ -2,21 +2,67 @@ mod utils;
 
 use std::rc::Rc;
 use std::str::FromStr;
+use std::time::Duration;
 
 use num_bigint_dig::BigInt;
 use num_traits::identities::Zero;
 use num_traits::One;
+use rustc_hash::FxHashMap;
 
 use program_structure::ast::{Expression, ExpressionInfixOpcode, ExpressionPrefixOpcode};
 
 use zkfuzz::executor::debug_ast::{
     DebuggableExpressionInfixOpcode, DebuggableExpressionPrefixOpcode,
 };
-use zkfuzz::executor::symbolic_execution::SymbolicExecutor;
-use zkfuzz::executor::symbolic_setting::get_default_setting_for_symbolic_execution;
-use zkfuzz::executor::symbolic_value::{OwnerName, SymbolicAccess, SymbolicName, SymbolicValue};
-use zkfuzz::mutator::unused_outputs::check_unused_outputs;
-use zkfuzz::mutator::utils::BaseVerificationConfig;
+use zkfuzz::executor::symbolic_execution::{RecursionErrorKind, SymbolicExecutor};
+use zkfuzz::executor::symbolic_setting::{
+    get_default_setting_for_concrete_execution, get_default_setting_for_symbolic_execution,
+};
+use zkfuzz::executor::symbolic_value::{
+    extract_variables, OwnerName, SymbolicAccess, SymbolicName, SymbolicTemplate, SymbolicValue,
+};
+use zkfuzz::executor::template_semantics::TemplateSemantics;
+use zkfuzz::executor::utils::strip_ansi;
+use zkfuzz::mutator::assertion::{assertion_reachability_search, gather_assertions};
+use zkfuzz::mutator::assign_oracle::{assignment_oracle_search, pair_assignments_with_constraints};
+use zkfuzz::mutator::brute_force::brute_force_search;
+use zkfuzz::mutator::concolic::execute_along_path;
+use zkfuzz::mutator::dead_code::{gather_unreachable_branches, gather_unused_definitions};
+use zkfuzz::mutator::division::{division_by_zero_search, gather_division_sites};
+use zkfuzz::mutator::input_spec::{parse_input_spec, InputDomain, InputSpec};
+use zkfuzz::mutator::log_replay::replay_logs;
+use zkfuzz::mutator::overflow_audit::audit_integer_overflows;
+use zkfuzz::mutator::path_conditions::PathConditions;
+use zkfuzz::mutator::portfolio::{plan_time_slices, portfolio_search, Strategy};
+use zkfuzz::mutator::prime_consistency::{check_prime_consistency, prime_modulus};
+use zkfuzz::mutator::provenance::explain_constraint;
+use zkfuzz::mutator::repair::{
+    suggest_repairs, synthesize_repair_candidates, RepairKind, REPAIR_RANGE_BITS,
+};
+use zkfuzz::mutator::search_ranges::SearchRanges;
+use zkfuzz::mutator::shape_mismatch::gather_shape_mismatches;
+use zkfuzz::mutator::signal_assignments::{
+    gather_signal_assignment_issues, SignalAssignmentIssueKind,
+};
+use zkfuzz::mutator::slicing::slice_constraints_by_target_signal;
+use zkfuzz::mutator::staged_search::{parse_stages, staged_search, Stage, StageStatus};
+use zkfuzz::mutator::structural_seeds::StructuralSeeds;
+use zkfuzz::mutator::ternary::gather_symbolic_ternaries;
+use zkfuzz::mutator::undetermined_dimension::gather_undetermined_dimensions;
+use zkfuzz::mutator::unused_outputs::{
+    check_component_boundaries, check_unused_outputs, suggest_unused_output_patches,
+    ComponentBoundaryIssue, OutputPatchKind,
+};
+use zkfuzz::mutator::utils::{
+    evaluate_symbolic_value, gather_input_variables, verify_assignment, BaseVerificationConfig,
+    CounterExample, UnderConstrainedType, VerificationResult,
+};
+use zkfuzz::mutator::witness_export::{
+    build_input_json, group_assignment, parse_input_json, AssignmentFormat, ValueFormat,
+};
+use zkfuzz::mutator::witness_order::{gather_witness_order_issues, WitnessOrderIssueKind};
+use zkfuzz::parser_user::override_main_params;
+use zkfuzz::type_analysis_user::gather_input_shapes;
 
 use crate::utils::{execute, prepare_symbolic_library};
 
@@ -1045,6 +1091,69 @@ fn test_2d_array_component() {
     }
 }
 
+#[test]
+fn test_component_array_partial_inputs() {
+    let path = "./tests/sample/test_component_array_partial_inputs.circom".to_string();
+    let prime = BigInt::from_str(
+        "21888242871839275222246405745257275088548364400416034343698204186575808495617",
+    )
+    .unwrap();
+
+    let (mut symbolic_library, program_archive) = prepare_symbolic_library(path, prime.clone());
+    let setting = get_default_setting_for_symbolic_execution(prime, false);
+
+    let mut sexe = SymbolicExecutor::new(&mut symbolic_library, &setting);
+    execute(&mut sexe, &program_archive);
+
+    assert!(sexe.incomplete_components.is_empty());
+    // Each instance is executed exactly once, after its last input is assigned
+    let assigned: Vec<String> = sexe
+        .cur_state
+        .symbolic_trace
+        .iter()
+        .filter_map(|constraint| match constraint.as_ref() {
+            SymbolicValue::AssignEq(lhs, _) => match lhs.as_ref() {
+                SymbolicValue::Variable(name) => {
+                    Some(name.lookup_fmt(&sexe.symbolic_library.id2name))
+                }
+                _ => None,
+            },
+            _ => None,
+        })
+        .collect();
+    for i in 0..2 {
+        for j in 0..3 {
+            let output = format!("main.s[{}][{}].c", i, j);
+            assert_eq!(assigned.iter().filter(|name| **name == output).count(), 1);
+        }
+    }
+}
+
+#[test]
+fn test_incomplete_component() {
+    let path = "./tests/sample/test_incomplete_component.circom".to_string();
+    let prime = BigInt::from_str(
+        "21888242871839275222246405745257275088548364400416034343698204186575808495617",
+    )
+    .unwrap();
+
+    let (mut symbolic_library, program_archive) = prepare_symbolic_library(path, prime.clone());
+    let setting = get_default_setting_for_symbolic_execution(prime, false);
+
+    let mut sexe = SymbolicExecutor::new(&mut symbolic_library, &setting);
+    execute(&mut sexe, &program_archive);
+
+    assert_eq!(sexe.incomplete_components.len(), 1);
+    let incomplete = &sexe.incomplete_components[0];
+    assert_eq!(incomplete.name, "main.s[1]");
+    assert_eq!(incomplete.template_name, "Sum");
+    assert_eq!(incomplete.missing_inputs, vec!["main.s[1].b[1]"]);
+    assert_eq!(
+        incomplete.message(),
+        "`main.s[1]` of template `Sum` is never executed: `main.s[1].b[1]` not assigned"
+    );
+}
+
 #[test]
 fn test_recursive_function() {
     let path = "./tests/sample/test_recursive_function.circom".to_string();
@@ -1623,6 +1732,90 @@ fn test_branch_within_callee() {
     assert_eq!(sexe.cur_state.symbolic_trace.len(), 5)
 }
 
+#[test]
+fn test_symbolic_branch_within_callee() {
+    let path = "./tests/sample/test_symbolic_branch_within_callee.circom".to_string();
+    let prime = BigInt::from_str(
+        "21888242871839275222246405745257275088548364400416034343698204186575808495617",
+    )
+    .unwrap();
+
+    let (mut symbolic_library, program_archive) = prepare_symbolic_library(path, prime.clone());
+    let setting = get_default_setting_for_symbolic_execution(prime, false);
+
+    let mut sexe = SymbolicExecutor::new(&mut symbolic_library, &setting);
+    execute(&mut sexe, &program_archive);
+
+    let merged_assignments = sexe
+        .cur_state
+        .symbolic_trace
+        .iter()
+        .filter(|c| match &***c {
+            SymbolicValue::Assign(_, rhs, ..) => {
+                matches!(&**rhs, SymbolicValue::Conditional(..))
+            }
+            _ => false,
+        })
+        .count();
+    assert_eq!(merged_assignments, 1);
+    assert!(sexe.cur_state.side_constraints.iter().any(|c| match &**c {
+        SymbolicValue::BinaryOp(_, _, rhs) => matches!(&**rhs, SymbolicValue::Conditional(..)),
+        _ => false,
+    }));
+}
+
+#[test]
+fn test_inline_switch_constraint() {
+    let prime = BigInt::from_str(
+        "21888242871839275222246405745257275088548364400416034343698204186575808495617",
+    )
+    .unwrap();
+
+    let mut constraints = Vec::new();
+    for path in [
+        "./tests/sample/test_inline_switch_constraint.circom",
+        "./tests/sample/test_inline_switch_constraint_if.circom",
+    ] {
+        let (mut symbolic_library, program_archive) =
+            prepare_symbolic_library(path.to_string(), prime.clone());
+        let setting = get_default_setting_for_symbolic_execution(prime.clone(), false);
+
+        let mut sexe = SymbolicExecutor::new(&mut symbolic_library, &setting);
+        execute(&mut sexe, &program_archive);
+
+        let fmt = |cs: &Vec<Rc<SymbolicValue>>| {
+            cs.iter()
+                .map(|c| c.lookup_fmt(&sexe.symbolic_library.id2name))
+                .collect::<Vec<_>>()
+        };
+        constraints.push((
+            fmt(&sexe.cur_state.symbolic_trace),
+            fmt(&sexe.cur_state.side_constraints),
+        ));
+
+        // `out <== s == 0 ? a : b` is recorded as one guarded constraint per branch
+        let guarded = |then_branch: bool| {
+            sexe.cur_state
+                .side_constraints
+                .iter()
+                .filter(|c| match &***c {
+                    SymbolicValue::Conditional(_, t, e) => {
+                        let (branch, other) = if then_branch { (t, e) } else { (e, t) };
+                        matches!(&**branch, SymbolicValue::AssignEq(..))
+                            && matches!(&**other, SymbolicValue::ConstantBool(true))
+                    }
+                    _ => false,
+                })
+                .count()
+        };
+        assert_eq!(guarded(true), 1);
+        assert_eq!(guarded(false), 1);
+    }
+
+    // The ternary and the if-then-else produce the same constraints
+    assert_eq!(constraints[0], constraints[1]);
+}
+
 #[test]
 fn test_array_dimension_calculation_within_callee() {
     let path = "./tests/sample/test_array_dimension_calculation_within_callee.circom".to_string();
@@ -2207,11 +2400,144 @@ fn test_unused_outputs() {
         progress_interval: 10000,
         template_param_names: template_param_names,
         template_param_values: template_param_values,
+        check_output_tags: false,
+        max_counterexamples: 1,
+        input_shapes: FxHashMap::default(),
+        input_spec: InputSpec::default(),
     };
 
     assert!(check_unused_outputs(&mut sexe, &verification_setting).is_some());
 }
 
+#[test]
+fn test_unused_output_patches() {
+    let path = "./tests/sample/test_unused_output_patch.circom".to_string();
+    let prime = BigInt::from_str(
+        "21888242871839275222246405745257275088548364400416034343698204186575808495617",
+    )
+    .unwrap();
+    let range = BigInt::from(100);
+
+    let (mut symbolic_library, program_archive) =
+        prepare_symbolic_library(path.clone(), prime.clone());
+    let setting = get_default_setting_for_symbolic_execution(prime.clone(), false);
+
+    let mut sexe = SymbolicExecutor::new(&mut symbolic_library, &setting);
+    execute(&mut sexe, &program_archive);
+
+    let (main_template_name, template_param_names, template_param_values) =
+        match &program_archive.initial_template_call {
+            Expression::Call { id, args, .. } => {
+                let template = &program_archive.templates[id];
+                (id, template.get_name_of_params().clone(), args.clone())
+            }
+            _ => unimplemented!(),
+        };
+
+    let verification_setting = BaseVerificationConfig {
+        target_template_name: main_template_name.to_string(),
+        prime: prime.clone(),
+        range: range.clone(),
+        quick_mode: false,
+        heuristics_mode: false,
+        progress_interval: 10000,
+        template_param_names: template_param_names,
+        template_param_values: template_param_values,
+        check_output_tags: false,
+        max_counterexamples: 1,
+        input_shapes: FxHashMap::default(),
+        input_spec: InputSpec::default(),
+    };
+
+    let ce = check_unused_outputs(&mut sexe, &verification_setting).unwrap();
+    let source = std::fs::read_to_string(&path).unwrap();
+    let patches = suggest_unused_output_patches(
+        sexe.symbolic_library,
+        &verification_setting,
+        &ce,
+        &path,
+        &source,
+        program_archive.file_id_main,
+    );
+
+    assert_eq!(patches.len(), 2);
+    assert_eq!(patches[0].output, "prod");
+    assert_eq!(patches[0].kind, OutputPatchKind::BindAssignment);
+    assert!(patches[0].diff.contains("-        prod <-- a * b;"));
+    assert!(patches[0].diff.contains("+        prod <== a * b;"));
+    assert_eq!(patches[1].output, "unused");
+    assert_eq!(patches[1].kind, OutputPatchKind::RemoveDeclaration);
+    assert!(patches[1].diff.contains("-    signal output unused;"));
+    assert!(!patches[1].diff.contains("+"));
+}
+
+#[test]
+fn test_tag_binary_output() {
+    let path = "./tests/sample/test_tag_binary_output.circom".to_string();
+    let prime = BigInt::from_str(
+        "21888242871839275222246405745257275088548364400416034343698204186575808495617",
+    )
+    .unwrap();
+
+    let (mut symbolic_library, program_archive) = prepare_symbolic_library(path, prime.clone());
+    let setting = get_default_setting_for_symbolic_execution(prime.clone(), false);
+
+    let mut sexe = SymbolicExecutor::new(&mut symbolic_library, &setting);
+    execute(&mut sexe, &program_archive);
+
+    let template = &sexe.symbolic_library.template_library
+        [&sexe.symbolic_library.name2id["TaggedOutput"]];
+    assert_eq!(
+        template.id2tags[&sexe.symbolic_library.name2id["in"]],
+        vec!["binary".to_string()]
+    );
+    assert_eq!(
+        template.id2tags[&sexe.symbolic_library.name2id["out"]],
+        vec!["binary".to_string()]
+    );
+
+    let (main_template_name, template_param_names, template_param_values) =
+        match &program_archive.initial_template_call {
+            Expression::Call { id, args, .. } => {
+                let template = &program_archive.templates[id];
+                (id, template.get_name_of_params().clone(), args.clone())
+            }
+            _ => unimplemented!(),
+        };
+
+    let verification_setting = BaseVerificationConfig {
+        target_template_name: main_template_name.to_string(),
+        prime: prime.clone(),
+        range: BigInt::from(100),
+        quick_mode: true,
+        heuristics_mode: false,
+        progress_interval: 10000,
+        template_param_names: template_param_names,
+        template_param_values: template_param_values,
+        check_output_tags: true,
+        max_counterexamples: 1,
+        input_shapes: FxHashMap::default(),
+        input_spec: InputSpec::default(),
+    };
+
+    let conc_setting = get_default_setting_for_concrete_execution(prime, false);
+    let mut conc_executor = SymbolicExecutor::new(&mut sexe.symbolic_library, &conc_setting);
+    let counter_example = brute_force_search(
+        &mut conc_executor,
+        &sexe.cur_state.symbolic_trace.clone(),
+        &sexe.cur_state.side_constraints.clone(),
+        &verification_setting,
+    );
+
+    assert!(matches!(
+        counter_example,
+        Some(CounterExample {
+            flag: VerificationResult::UnderConstrained(UnderConstrainedType::TagViolation(..)),
+            ..
+        })
+    ));
+}
+
 #[test]
 fn test_assign_nested_array() {
     let path = "./tests/sample/test_assign_nested_array.circom".to_string();
@@ -2370,3 +2696,2228 @@ fn test_var_bulk_assignment() {
     assert_eq!(*sexe.cur_state.symbolic_trace[0], first_cond);
     assert_eq!(*sexe.cur_state.side_constraints[0], first_cond);
 }
+
+#[test]
+fn test_slicing() {
+    let path = "./tests/sample/test_slicing.circom".to_string();
+    let prime = BigInt::from_str(
+        "21888242871839275222246405745257275088548364400416034343698204186575808495617",
+    )
+    .unwrap();
+
+    let (mut symbolic_library, program_archive) = prepare_symbolic_library(path, prime.clone());
+    let setting = get_default_setting_for_symbolic_execution(prime, false);
+
+    let mut sexe = SymbolicExecutor::new(&mut symbolic_library, &setting);
+    execute(&mut sexe, &program_archive);
+
+    let (sliced_trace, sliced_side_constraints) = slice_constraints_by_target_signal(
+        &sexe.cur_state.symbolic_trace,
+        &sexe.cur_state.side_constraints,
+        "main.x",
+        &sexe.symbolic_library.id2name,
+    )
+    .unwrap();
+
+    assert_eq!(sliced_trace.len(), 1);
+    assert_eq!(sliced_side_constraints.len(), 1);
+
+    let mut variables = extract_variables(&sliced_trace);
+    variables.append(&mut extract_variables(&sliced_side_constraints));
+    assert!(variables
+        .iter()
+        .all(|v| v.id != sexe.symbolic_library.name2id["b"]
+            && v.id != sexe.symbolic_library.name2id["y"]));
+
+    assert!(slice_constraints_by_target_signal(
+        &sexe.cur_state.symbolic_trace,
+        &sexe.cur_state.side_constraints,
+        "main.z",
+        &sexe.symbolic_library.id2name,
+    )
+    .is_none());
+}
+
+#[test]
+fn test_assert_reachability() {
+    let path = "./tests/sample/test_assert_reachability.circom".to_string();
+    let prime = BigInt::from_str(
+        "21888242871839275222246405745257275088548364400416034343698204186575808495617",
+    )
+    .unwrap();
+
+    let (mut symbolic_library, program_archive) = prepare_symbolic_library(path, prime.clone());
+    let setting = get_default_setting_for_symbolic_execution(prime.clone(), false);
+
+    let mut sexe = SymbolicExecutor::new(&mut symbolic_library, &setting);
+    execute(&mut sexe, &program_archive);
+
+    let assertions = gather_assertions(sexe.symbolic_library);
+    assert_eq!(assertions.len(), 1);
+    assert_eq!(assertions[0].owner_name, "Inverse");
+
+    let (main_template_name, template_param_names, template_param_values) =
+        match &program_archive.initial_template_call {
+            Expression::Call { id, args, .. } => {
+                let template = &program_archive.templates[id];
+                (id, template.get_name_of_params().clone(), args.clone())
+            }
+            _ => unimplemented!(),
+        };
+
+    let verification_setting = BaseVerificationConfig {
+        target_template_name: main_template_name.to_string(),
+        prime: prime.clone(),
+        range: BigInt::from(100),
+        quick_mode: false,
+        heuristics_mode: false,
+        progress_interval: 10000,
+        template_param_names: template_param_names,
+        template_param_values: template_param_values,
+        check_output_tags: false,
+        max_counterexamples: 1,
+        input_shapes: FxHashMap::default(),
+        input_spec: InputSpec::default(),
+    };
+
+    let conc_setting = get_default_setting_for_concrete_execution(prime.clone(), false);
+    let mut conc_executor = SymbolicExecutor::new(&mut sexe.symbolic_library, &conc_setting);
+    let counter_example = assertion_reachability_search(
+        &mut conc_executor,
+        &sexe.cur_state.symbolic_trace.clone(),
+        &sexe.cur_state.side_constraints.clone(),
+        &verification_setting,
+        &program_archive.file_library,
+        100,
+        0,
+    );
+
+    match counter_example {
+        Some(CounterExample {
+            flag: VerificationResult::AssertionFailure(location, _),
+            assignment,
+            ..
+        }) => {
+            assert!(location.starts_with("Inverse"));
+            let values: Vec<_> = assignment.values().collect();
+            assert_eq!(values.len(), 2);
+            assert_eq!(values[0], values[1]);
+        }
+        _ => panic!("the assertion in `Inverse` should be violated"),
+    }
+}
+
+struct Num2BitsRangeSemantics;
+
+impl TemplateSemantics for Num2BitsRangeSemantics {
+    fn is_applicable(&self, name: &str, template: &SymbolicTemplate) -> bool {
+        name == "Num2Bits" && template.template_parameter_names.len() == 1
+    }
+
+    fn emit_constraints(
+        &self,
+        name2id: &FxHashMap<String, usize>,
+        owner_name: Rc<Vec<OwnerName>>,
+    ) -> Vec<SymbolicValue> {
+        vec![SymbolicValue::AuxBinaryOp(
+            Rc::new(SymbolicValue::Variable(SymbolicName::new(
+                name2id["in"],
+                owner_name,
+                None,
+            ))),
+            DebuggableExpressionInfixOpcode(ExpressionInfixOpcode::Lesser),
+            Rc::new(SymbolicValue::ConstantInt(BigInt::from(16))),
+        )]
+    }
+}
+
+#[test]
+fn test_template_semantics_plugin() {
+    let path = "./tests/sample/test_lessthan.circom".to_string();
+    let prime = BigInt::from_str(
+        "21888242871839275222246405745257275088548364400416034343698204186575808495617",
+    )
+    .unwrap();
+
+    let (mut symbolic_library, program_archive) = prepare_symbolic_library(path, prime.clone());
+    symbolic_library.register_template_semantics(Rc::new(Num2BitsRangeSemantics));
+    assert_eq!(
+        symbolic_library.template_library[&symbolic_library.name2id["Num2Bits"]]
+            .semantics
+            .len(),
+        1
+    );
+    assert_eq!(
+        symbolic_library.template_library[&symbolic_library.name2id["LessThan"]]
+            .semantics
+            .len(),
+        1
+    );
+
+    let setting = get_default_setting_for_symbolic_execution(prime, false);
+    let mut sexe = SymbolicExecutor::new(&mut symbolic_library, &setting);
+    execute(&mut sexe, &program_archive);
+
+    let bound = SymbolicValue::ConstantInt(BigInt::from(16));
+    let emitted = sexe
+        .cur_state
+        .symbolic_trace
+        .iter()
+        .filter(|c| matches!(&***c, SymbolicValue::AuxBinaryOp(_, _, rhs) if **rhs == bound))
+        .count();
+    assert_eq!(emitted, 1);
+}
+
+#[test]
+fn test_build_input_json() {
+    let path = "./tests/sample/test_2d_array_component.circom".to_string();
+    let prime = BigInt::from_str(
+        "21888242871839275222246405745257275088548364400416034343698204186575808495617",
+    )
+    .unwrap();
+
+    let (mut symbolic_library, program_archive) = prepare_symbolic_library(path, prime.clone());
+    let setting = get_default_setting_for_symbolic_execution(prime.clone(), false);
+
+    let mut sexe = SymbolicExecutor::new(&mut symbolic_library, &setting);
+    execute(&mut sexe, &program_archive);
+
+    let (main_template_name, template_param_names, template_param_values) =
+        match &program_archive.initial_template_call {
+            Expression::Call { id, args, .. } => {
+                let template = &program_archive.templates[id];
+                (id, template.get_name_of_params().clone(), args.clone())
+            }
+            _ => unimplemented!(),
+        };
+
+    let verification_setting = BaseVerificationConfig {
+        target_template_name: main_template_name.to_string(),
+        prime: prime.clone(),
+        range: BigInt::from(100),
+        quick_mode: false,
+        heuristics_mode: false,
+        progress_interval: 10000,
+        template_param_names: template_param_names,
+        template_param_values: template_param_values,
+        check_output_tags: false,
+        max_counterexamples: 1,
+        input_shapes: FxHashMap::default(),
+        input_spec: InputSpec::default(),
+    };
+
+    let main_owner = Rc::new(vec![OwnerName {
+        id: sexe.symbolic_library.name2id["main"],
+        access: None,
+        counter: 0,
+    }]);
+    let input_element = |i: usize| {
+        SymbolicName::new(
+            sexe.symbolic_library.name2id["in"],
+            main_owner.clone(),
+            Some(vec![SymbolicAccess::ArrayAccess(SymbolicValue::ConstantInt(
+                BigInt::from(i),
+            ))]),
+        )
+    };
+    let mut assignment = FxHashMap::default();
+    assignment.insert(input_element(1), BigInt::from(5));
+    assignment.insert(input_element(3), BigInt::from(-1));
+    assignment.insert(
+        SymbolicName::new(sexe.symbolic_library.name2id["out"], main_owner.clone(), None),
+        BigInt::from(7),
+    );
+
+    let input_json = build_input_json(
+        &assignment,
+        sexe.symbolic_library,
+        &verification_setting,
+        &sexe.id2dimensions,
+    );
+    let minus_one = (&prime - BigInt::one()).to_string();
+    assert_eq!(
+        input_json,
+        serde_json::json!({"in": ["0", "5", "0", minus_one]})
+    );
+}
+
+#[test]
+fn test_group_assignment() {
+    let path = "./tests/sample/test_2d_array_component.circom".to_string();
+    let prime = BigInt::from_str(
+        "21888242871839275222246405745257275088548364400416034343698204186575808495617",
+    )
+    .unwrap();
+
+    let (mut symbolic_library, program_archive) = prepare_symbolic_library(path, prime.clone());
+    let setting = get_default_setting_for_symbolic_execution(prime.clone(), false);
+
+    let mut sexe = SymbolicExecutor::new(&mut symbolic_library, &setting);
+    execute(&mut sexe, &program_archive);
+
+    let main_owner = Rc::new(vec![OwnerName {
+        id: sexe.symbolic_library.name2id["main"],
+        access: None,
+        counter: 0,
+    }]);
+    let input_element = |i: usize| {
+        SymbolicName::new(
+            sexe.symbolic_library.name2id["in"],
+            main_owner.clone(),
+            Some(vec![SymbolicAccess::ArrayAccess(
+                SymbolicValue::ConstantInt(BigInt::from(i)),
+            )]),
+        )
+    };
+    let out = SymbolicName::new(
+        sexe.symbolic_library.name2id["out"],
+        main_owner.clone(),
+        None,
+    );
+    let mut assignment = FxHashMap::default();
+    assignment.insert(input_element(0), BigInt::from(258));
+    assignment.insert(input_element(2), BigInt::from(-1));
+    assignment.insert(out.clone(), BigInt::from(5));
+
+    let format = |value_format: &str| {
+        AssignmentFormat::new(
+            ValueFormat::from_str(value_format).unwrap(),
+            prime.clone(),
+            sexe.symbolic_library,
+            "Caller",
+        )
+    };
+    let minus_one = (&prime - BigInt::one()).to_string();
+    assert_eq!(
+        group_assignment(
+            &assignment,
+            &sexe.symbolic_library.id2name,
+            &format("decimal")
+        ),
+        serde_json::json!({"main.in": ["258", null, minus_one], "main.out": "5"})
+    );
+
+    let hex = format("hex");
+    assert_eq!(
+        hex.format_value(&out, &BigInt::from(255)),
+        serde_json::json!("0xff")
+    );
+
+    let mut bytes_le = vec![0u8; 32];
+    bytes_le[0] = 2;
+    bytes_le[1] = 1;
+    assert_eq!(
+        format("bytes_le").format_value(&input_element(0), &BigInt::from(258)),
+        serde_json::json!(bytes_le)
+    );
+    bytes_le.reverse();
+    assert_eq!(
+        format("bytes_be").format_value(&input_element(0), &BigInt::from(258)),
+        serde_json::json!(bytes_le)
+    );
+
+    let mut bits = vec![0u8; 254];
+    bits[0] = 1;
+    bits[2] = 1;
+    assert_eq!(
+        format("bits").format_value(&out, &BigInt::from(5)),
+        serde_json::json!(bits)
+    );
+    assert!(ValueFormat::from_str("octal").is_err());
+}
+
+#[test]
+fn test_component_boundary() {
+    let path = "./tests/sample/test_component_boundary.circom".to_string();
+    let prime = BigInt::from_str(
+        "21888242871839275222246405745257275088548364400416034343698204186575808495617",
+    )
+    .unwrap();
+
+    let (mut symbolic_library, program_archive) = prepare_symbolic_library(path, prime.clone());
+    let setting = get_default_setting_for_symbolic_execution(prime, false);
+
+    let mut sexe = SymbolicExecutor::new(&mut symbolic_library, &setting);
+    execute(&mut sexe, &program_archive);
+
+    let issues = check_component_boundaries(
+        &sexe,
+        &sexe.cur_state.symbolic_trace,
+        &sexe.cur_state.side_constraints,
+    );
+    let formatted: Vec<_> = issues
+        .iter()
+        .map(|issue| issue.lookup_fmt(&sexe.symbolic_library.id2name))
+        .collect();
+    assert_eq!(issues.len(), 2, "{:?}", formatted);
+    assert!(matches!(&issues[0], ComponentBoundaryIssue::DeadOutput(name)
+        if name.lookup_fmt(&sexe.symbolic_library.id2name) == "main.sq.y"));
+    assert!(matches!(&issues[1], ComponentBoundaryIssue::UnconstrainedAssignment(name, source)
+        if name.lookup_fmt(&sexe.symbolic_library.id2name) == "main.tmp"
+            && source.lookup_fmt(&sexe.symbolic_library.id2name) == "main.sq.x"));
+}
+
+#[test]
+fn test_symbolic_index_case_splitting() {
+    let path = "./tests/sample/test_symbolic_index.circom".to_string();
+    let prime = BigInt::from_str(
+        "21888242871839275222246405745257275088548364400416034343698204186575808495617",
+    )
+    .unwrap();
+
+    let (mut symbolic_library, program_archive) = prepare_symbolic_library(path, prime.clone());
+    let setting = get_default_setting_for_symbolic_execution(prime.clone(), false);
+
+    let mut sexe = SymbolicExecutor::new(&mut symbolic_library, &setting);
+    execute(&mut sexe, &program_archive);
+
+    let rhs = sexe
+        .cur_state
+        .symbolic_trace
+        .iter()
+        .find_map(|c| match &**c {
+            SymbolicValue::Assign(_, rhs, ..) => Some(rhs.clone()),
+            _ => None,
+        })
+        .unwrap();
+    assert!(matches!(&*rhs, SymbolicValue::Conditional(..)));
+
+    let main_owner = Rc::new(vec![OwnerName {
+        id: sexe.symbolic_library.name2id["main"],
+        access: None,
+        counter: 0,
+    }]);
+    let input = SymbolicName::new(sexe.symbolic_library.name2id["in"], main_owner, None);
+    for (index, expected) in [(0, 5), (1, 7), (2, 11)] {
+        let assignment = FxHashMap::from_iter([(input.clone(), BigInt::from(index))]);
+        assert_eq!(
+            evaluate_symbolic_value(&prime, &rhs, &assignment, &mut sexe.symbolic_library),
+            Some(SymbolicValue::ConstantInt(BigInt::from(expected)))
+        );
+    }
+
+    let mut setting = get_default_setting_for_symbolic_execution(prime.clone(), false);
+    setting.max_index_case_splits = 2;
+    let (mut symbolic_library, program_archive) = prepare_symbolic_library(
+        "./tests/sample/test_symbolic_index.circom".to_string(),
+        prime,
+    );
+    let mut sexe = SymbolicExecutor::new(&mut symbolic_library, &setting);
+    execute(&mut sexe, &program_archive);
+    assert!(!sexe
+        .cur_state
+        .symbolic_trace
+        .iter()
+        .any(|c| matches!(&**c, SymbolicValue::Assign(_, rhs, ..)
+            if matches!(&**rhs, SymbolicValue::Conditional(..)))));
+}
+
+#[test]
+fn test_division_by_zero() {
+    let path = "./tests/sample/test_division_by_zero.circom".to_string();
+    let prime = BigInt::from_str(
+        "21888242871839275222246405745257275088548364400416034343698204186575808495617",
+    )
+    .unwrap();
+
+    let (mut symbolic_library, program_archive) = prepare_symbolic_library(path, prime.clone());
+    let setting = get_default_setting_for_symbolic_execution(prime.clone(), false);
+
+    let mut sexe = SymbolicExecutor::new(&mut symbolic_library, &setting);
+    execute(&mut sexe, &program_archive);
+
+    // `6 / 3` has a nonzero constant denominator and is not reported
+    let sites = gather_division_sites(&sexe, &program_archive.file_library);
+    assert_eq!(sites.len(), 1);
+    assert_eq!(sites[0].owner_name, "Divide");
+    assert!(sites[0].location.starts_with("Divide"));
+    assert!(sites[0].denominator.contains("b"));
+
+    let (main_template_name, template_param_names, template_param_values) =
+        match &program_archive.initial_template_call {
+            Expression::Call { id, args, .. } => {
+                let template = &program_archive.templates[id];
+                (id, template.get_name_of_params().clone(), args.clone())
+            }
+            _ => unimplemented!(),
+        };
+
+    let verification_setting = BaseVerificationConfig {
+        target_template_name: main_template_name.to_string(),
+        prime: prime.clone(),
+        range: BigInt::from(100),
+        quick_mode: false,
+        heuristics_mode: false,
+        progress_interval: 10000,
+        template_param_names: template_param_names,
+        template_param_values: template_param_values,
+        check_output_tags: false,
+        max_counterexamples: 1,
+        input_shapes: FxHashMap::default(),
+        input_spec: InputSpec::default(),
+    };
+
+    let conc_setting = get_default_setting_for_concrete_execution(prime.clone(), false);
+    let mut conc_executor = SymbolicExecutor::new(&mut sexe.symbolic_library, &conc_setting);
+    let counter_example = division_by_zero_search(
+        &mut conc_executor,
+        &sexe.cur_state.symbolic_trace.clone(),
+        &sexe.cur_state.side_constraints.clone(),
+        &verification_setting,
+        &program_archive.file_library,
+        100,
+        0,
+    );
+
+    match counter_example {
+        Some(CounterExample {
+            flag: VerificationResult::DivisionByZero(location, _),
+            assignment,
+            ..
+        }) => {
+            assert!(location.starts_with("Divide"));
+            let b = assignment
+                .iter()
+                .find(|(k, _)| k.id == conc_executor.symbolic_library.name2id["b"])
+                .map(|(_, v)| v.clone())
+                .unwrap();
+            assert!((b % &prime).is_zero());
+        }
+        _ => panic!("the division by zero in `Divide` should be reachable"),
+    }
+}
+
+#[test]
+fn test_assign_oracle() {
+    let path = "./tests/sample/test_assign_oracle.circom".to_string();
+    let prime = BigInt::from_str(
+        "21888242871839275222246405745257275088548364400416034343698204186575808495617",
+    )
+    .unwrap();
+
+    let (mut symbolic_library, program_archive) = prepare_symbolic_library(path, prime.clone());
+    let setting = get_default_setting_for_symbolic_execution(prime.clone(), false);
+
+    let mut sexe = SymbolicExecutor::new(&mut symbolic_library, &setting);
+    execute(&mut sexe, &program_archive);
+
+    let symbolic_trace = sexe.cur_state.symbolic_trace.clone();
+    let side_constraints = sexe.cur_state.side_constraints.clone();
+    let pairs = pair_assignments_with_constraints(&symbolic_trace, &side_constraints);
+    assert_eq!(pairs.len(), 1);
+    assert_eq!(pairs[0].signal.id, sexe.symbolic_library.name2id["out"]);
+    assert_eq!(pairs[0].constraints.len(), 1);
+
+    let (main_template_name, template_param_names, template_param_values) =
+        match &program_archive.initial_template_call {
+            Expression::Call { id, args, .. } => {
+                let template = &program_archive.templates[id];
+                (id, template.get_name_of_params().clone(), args.clone())
+            }
+            _ => unimplemented!(),
+        };
+
+    let verification_setting = BaseVerificationConfig {
+        target_template_name: main_template_name.to_string(),
+        prime: prime.clone(),
+        range: BigInt::from(100),
+        quick_mode: false,
+        heuristics_mode: false,
+        progress_interval: 10000,
+        template_param_names: template_param_names,
+        template_param_values: template_param_values,
+        check_output_tags: false,
+        max_counterexamples: 1,
+        input_shapes: FxHashMap::default(),
+        input_spec: InputSpec::default(),
+    };
+
+    let conc_setting = get_default_setting_for_concrete_execution(prime.clone(), false);
+    let mut conc_executor = SymbolicExecutor::new(&mut sexe.symbolic_library, &conc_setting);
+    let counter_example = assignment_oracle_search(
+        &mut conc_executor,
+        &symbolic_trace,
+        &side_constraints,
+        &verification_setting,
+        100,
+        0,
+    );
+
+    match counter_example {
+        Some(CounterExample {
+            flag:
+                VerificationResult::UnderConstrained(UnderConstrainedType::NonDeterministic(
+                    signal,
+                    _,
+                    expected,
+                )),
+            assignment,
+            ..
+        }) => {
+            assert_eq!(signal.id, conc_executor.symbolic_library.name2id["out"]);
+            // The divergent witness takes the other square root, `-in * in`
+            let out = assignment[&signal].clone();
+            assert_ne!(out, expected);
+            assert!(((&out + &expected) % &prime).is_zero());
+        }
+        _ => panic!("`out` of `WeakSquare` should admit another value"),
+    }
+}
+
+#[test]
+fn test_portfolio_search() {
+    let path = "./tests/sample/test_assign_oracle.circom".to_string();
+    let prime = BigInt::from_str(
+        "21888242871839275222246405745257275088548364400416034343698204186575808495617",
+    )
+    .unwrap();
+
+    let (mut symbolic_library, program_archive) = prepare_symbolic_library(path, prime.clone());
+    let setting = get_default_setting_for_symbolic_execution(prime.clone(), false);
+
+    let mut sexe = SymbolicExecutor::new(&mut symbolic_library, &setting);
+    execute(&mut sexe, &program_archive);
+
+    let symbolic_trace = sexe.cur_state.symbolic_trace.clone();
+    let side_constraints = sexe.cur_state.side_constraints.clone();
+
+    let (main_template_name, template_param_names, template_param_values) =
+        match &program_archive.initial_template_call {
+            Expression::Call { id, args, .. } => {
+                let template = &program_archive.templates[id];
+                (id, template.get_name_of_params().clone(), args.clone())
+            }
+            _ => unimplemented!(),
+        };
+
+    let verification_setting = BaseVerificationConfig {
+        target_template_name: main_template_name.to_string(),
+        prime: prime.clone(),
+        range: BigInt::from(100),
+        quick_mode: false,
+        heuristics_mode: false,
+        progress_interval: 10000,
+        template_param_names: template_param_names,
+        template_param_values: template_param_values,
+        check_output_tags: false,
+        max_counterexamples: 1,
+        input_shapes: FxHashMap::default(),
+        input_spec: InputSpec::default(),
+    };
+
+    let conc_setting = get_default_setting_for_concrete_execution(prime.clone(), false);
+    let mut conc_executor = SymbolicExecutor::new(&mut sexe.symbolic_library, &conc_setting);
+    let result = portfolio_search(
+        &mut conc_executor,
+        &symbolic_trace,
+        &side_constraints,
+        &verification_setting,
+        Duration::from_secs(60),
+        100,
+        0,
+        |_, _, _| panic!("the genetic search should not be reached"),
+    );
+
+    // `in` and `out` take 3 values each in quick mode, but 301 each in heuristics mode
+    assert_eq!(result.features.num_inputs, 1);
+    assert_eq!(result.features.num_nonlinear_constraints, 1);
+    assert_eq!(result.features.num_checked_assignments, 1);
+    assert_eq!(result.features.quick_assignments, Some(9));
+    let plan: Vec<_> = plan_time_slices(&result.features)
+        .iter()
+        .map(|s| s.strategy)
+        .collect();
+    assert_eq!(plan, vec![Strategy::Quick, Strategy::Assign, Strategy::Ga]);
+
+    // `out = -1` satisfies `out * out === in * in * in * in` for `in = 1`
+    assert_eq!(result.winner, Some(Strategy::Quick));
+    assert_eq!(result.slices.len(), 1);
+    assert!(result.counter_example.is_some());
+}
+
+#[test]
+fn test_repair_suggestions() {
+    let path = "./tests/sample/test_assign_oracle.circom".to_string();
+    let prime = BigInt::from_str(
+        "21888242871839275222246405745257275088548364400416034343698204186575808495617",
+    )
+    .unwrap();
+
+    let (mut symbolic_library, program_archive) = prepare_symbolic_library(path, prime.clone());
+    let setting = get_default_setting_for_symbolic_execution(prime.clone(), false);
+
+    let mut sexe = SymbolicExecutor::new(&mut symbolic_library, &setting);
+    execute(&mut sexe, &program_archive);
+
+    let symbolic_trace = sexe.cur_state.symbolic_trace.clone();
+    let side_constraints = sexe.cur_state.side_constraints.clone();
+
+    let (main_template_name, template_param_names, template_param_values) =
+        match &program_archive.initial_template_call {
+            Expression::Call { id, args, .. } => {
+                let template = &program_archive.templates[id];
+                (id, template.get_name_of_params().clone(), args.clone())
+            }
+            _ => unimplemented!(),
+        };
+
+    let verification_setting = BaseVerificationConfig {
+        target_template_name: main_template_name.to_string(),
+        prime: prime.clone(),
+        range: BigInt::from(100),
+        quick_mode: false,
+        heuristics_mode: false,
+        progress_interval: 10000,
+        template_param_names: template_param_names,
+        template_param_values: template_param_values,
+        check_output_tags: false,
+        max_counterexamples: 1,
+        input_shapes: FxHashMap::default(),
+        input_spec: InputSpec::default(),
+    };
+
+    let conc_setting = get_default_setting_for_concrete_execution(prime.clone(), false);
+    let mut conc_executor = SymbolicExecutor::new(&mut sexe.symbolic_library, &conc_setting);
+    let counter_example = assignment_oracle_search(
+        &mut conc_executor,
+        &symbolic_trace,
+        &side_constraints,
+        &verification_setting,
+        100,
+        0,
+    )
+    .unwrap();
+
+    let out_id = conc_executor.symbolic_library.name2id["out"];
+    let candidates = synthesize_repair_candidates(
+        counter_example.target_output.as_ref().unwrap(),
+        &symbolic_trace,
+    );
+    assert_eq!(candidates.len(), 2 + REPAIR_RANGE_BITS.len());
+    assert!(candidates.iter().all(|c| c.signal.id == out_id));
+
+    // Only `out === in * in` rules out the other square root
+    let suggestions = suggest_repairs(
+        &mut conc_executor,
+        &symbolic_trace,
+        &side_constraints,
+        &verification_setting,
+        &counter_example,
+        100,
+        0,
+    );
+    assert_eq!(suggestions.len(), 1);
+    assert_eq!(suggestions[0].kind, RepairKind::Equality);
+}
+
+#[test]
+fn test_custom_template() {
+    let path = "./tests/sample/test_custom_template.circom".to_string();
+    let prime = BigInt::from_str(
+        "21888242871839275222246405745257275088548364400416034343698204186575808495617",
+    )
+    .unwrap();
+
+    let (mut symbolic_library, program_archive) = prepare_symbolic_library(path, prime.clone());
+    let setting = get_default_setting_for_symbolic_execution(prime.clone(), false);
+
+    let mut sexe = SymbolicExecutor::new(&mut symbolic_library, &setting);
+    execute(&mut sexe, &program_archive);
+
+    let square_id = sexe.symbolic_library.name2id["Square"];
+    assert!(sexe.symbolic_library.template_library[&square_id].is_custom_gate);
+    assert!(!sexe.symbolic_library.template_library[&sexe.symbolic_library.name2id["SumOfSquares"]]
+        .is_custom_gate);
+    assert_eq!(
+        sexe.component_templates
+            .values()
+            .filter(|template_id| **template_id == square_id)
+            .count(),
+        2
+    );
+
+    // The `<--` assignment of each gate is assumed to be enforced by the gate
+    let out_id = sexe.symbolic_library.name2id["out"];
+    let assumed_outputs: Vec<_> = sexe
+        .cur_state
+        .side_constraints
+        .iter()
+        .filter_map(|c| match &**c {
+            SymbolicValue::AssignEq(lhs, rhs) => match (&**lhs, &**rhs) {
+                (SymbolicValue::Variable(name), SymbolicValue::BinaryOp(..))
+                    if name.id == out_id && name.owner.len() == 2 =>
+                {
+                    Some(name.owner[1].id)
+                }
+                _ => None,
+            },
+            _ => None,
+        })
+        .collect();
+    assert_eq!(assumed_outputs.len(), 2);
+    assert!(assumed_outputs.contains(&sexe.symbolic_library.name2id["sa"]));
+    assert!(assumed_outputs.contains(&sexe.symbolic_library.name2id["sb"]));
+}
+
+#[test]
+fn test_gather_input_variables_with_input_shapes() {
+    let path = "./tests/sample/test_partially_used_input.circom".to_string();
+    let prime = BigInt::from_str(
+        "21888242871839275222246405745257275088548364400416034343698204186575808495617",
+    )
+    .unwrap();
+
+    let (mut symbolic_library, program_archive) = prepare_symbolic_library(path, prime.clone());
+    let setting = get_default_setting_for_symbolic_execution(prime.clone(), false);
+
+    let mut sexe = SymbolicExecutor::new(&mut symbolic_library, &setting);
+    execute(&mut sexe, &program_archive);
+
+    let (main_template_name, template_param_names, template_param_values) =
+        match &program_archive.initial_template_call {
+            Expression::Call { id, args, .. } => {
+                let template = &program_archive.templates[id];
+                (id, template.get_name_of_params().clone(), args.clone())
+            }
+            _ => unimplemented!(),
+        };
+
+    let input_shapes = gather_input_shapes(
+        sexe.symbolic_library,
+        main_template_name,
+        &sexe.id2dimensions,
+    );
+    assert_eq!(
+        input_shapes[&sexe.symbolic_library.name2id["in"]],
+        vec![3, 2]
+    );
+    assert!(input_shapes[&sexe.symbolic_library.name2id["s"]].is_empty());
+
+    let verification_setting = BaseVerificationConfig {
+        target_template_name: main_template_name.to_string(),
+        prime: prime.clone(),
+        range: prime.clone(),
+        quick_mode: false,
+        heuristics_mode: false,
+        progress_interval: 10000,
+        template_param_names: template_param_names,
+        template_param_values: template_param_values,
+        check_output_tags: false,
+        max_counterexamples: 1,
+        input_shapes: FxHashMap::default(),
+        input_spec: InputSpec::default(),
+    };
+
+    let mut variables = extract_variables(&sexe.cur_state.symbolic_trace);
+    variables.append(&mut extract_variables(&sexe.cur_state.side_constraints));
+    assert_eq!(
+        gather_input_variables(sexe.symbolic_library, &verification_setting, &variables).len(),
+        2
+    );
+
+    let verification_setting = BaseVerificationConfig {
+        input_shapes: input_shapes,
+        ..verification_setting
+    };
+    assert_eq!(
+        gather_input_variables(sexe.symbolic_library, &verification_setting, &variables).len(),
+        7
+    );
+}
+
+#[test]
+fn test_overflow_audit() {
+    let path = "./tests/sample/test_overflow_audit.circom".to_string();
+    let prime = BigInt::from_str(
+        "21888242871839275222246405745257275088548364400416034343698204186575808495617",
+    )
+    .unwrap();
+
+    let (mut symbolic_library, program_archive) = prepare_symbolic_library(path, prime.clone());
+    let setting = get_default_setting_for_symbolic_execution(prime.clone(), false);
+
+    let mut sexe = SymbolicExecutor::new(&mut symbolic_library, &setting);
+    execute(&mut sexe, &program_archive);
+
+    let mut constraints = sexe.cur_state.symbolic_trace.clone();
+    constraints.extend(sexe.cur_state.side_constraints.iter().cloned());
+    let sites = audit_integer_overflows(&constraints, &prime, &sexe.symbolic_library.id2name);
+
+    assert_eq!(sites.len(), 1);
+    assert!(sites[0].operand.contains("main.a"));
+    assert!(sites[0].operand.contains("main.b"));
+    assert!(sites[0].bound_bits > prime.bits());
+}
+
+#[test]
+fn test_concolic_execution() {
+    let path = "./tests/sample/test_concolic.circom".to_string();
+    let prime = BigInt::from_str(
+        "21888242871839275222246405745257275088548364400416034343698204186575808495617",
+    )
+    .unwrap();
+
+    let (mut symbolic_library, program_archive) = prepare_symbolic_library(path, prime.clone());
+    let setting = get_default_setting_for_symbolic_execution(prime.clone(), false);
+
+    let mut sexe = SymbolicExecutor::new(&mut symbolic_library, &setting);
+    execute(&mut sexe, &program_archive);
+    let id2dimensions = sexe.id2dimensions.clone();
+
+    let (main_template_name, template_param_names, template_param_values) =
+        match &program_archive.initial_template_call {
+            Expression::Call { id, args, .. } => {
+                let template = &program_archive.templates[id];
+                (id, template.get_name_of_params().clone(), args.clone())
+            }
+            _ => unimplemented!(),
+        };
+
+    let verification_setting = BaseVerificationConfig {
+        target_template_name: main_template_name.to_string(),
+        prime: prime.clone(),
+        range: BigInt::from(100),
+        quick_mode: false,
+        heuristics_mode: false,
+        progress_interval: 10000,
+        template_param_names: template_param_names,
+        template_param_values: template_param_values,
+        check_output_tags: false,
+        max_counterexamples: 1,
+        input_shapes: FxHashMap::default(),
+        input_spec: InputSpec::default(),
+    };
+
+    let conc_setting = get_default_setting_for_concrete_execution(prime.clone(), false);
+    let mut conc_executor = SymbolicExecutor::new(&mut symbolic_library, &conc_setting);
+
+    let seed_json = serde_json::json!({"in": "7"});
+    let seed_input = parse_input_json(
+        &seed_json,
+        conc_executor.symbolic_library,
+        &verification_setting,
+    )
+    .unwrap();
+    assert_eq!(
+        build_input_json(
+            &seed_input,
+            conc_executor.symbolic_library,
+            &verification_setting,
+            &id2dimensions,
+        ),
+        seed_json
+    );
+
+    let out_is = |value: i32| SymbolicValue::ConstantInt(BigInt::from(value));
+    let not_taken = execute_along_path(
+        &mut conc_executor,
+        &setting,
+        &verification_setting,
+        seed_input,
+    );
+    assert_eq!(not_taken.conditions.len(), 1);
+    assert!(!not_taken.conditions[0].taken);
+    assert!(not_taken
+        .symbolic_trace
+        .iter()
+        .any(|c| matches!(&**c, SymbolicValue::Assign(_, rhs, ..) if **rhs == out_is(0))));
+    assert!(!not_taken
+        .symbolic_trace
+        .iter()
+        .any(|c| matches!(&**c, SymbolicValue::Assign(_, rhs, ..) if **rhs == out_is(1))));
+
+    let taken_input = parse_input_json(
+        &serde_json::json!({"in": 2}),
+        conc_executor.symbolic_library,
+        &verification_setting,
+    )
+    .unwrap();
+    let taken = execute_along_path(
+        &mut conc_executor,
+        &setting,
+        &verification_setting,
+        taken_input,
+    );
+    assert_eq!(taken.conditions, vec![not_taken.conditions[0].negate()]);
+    assert!(taken
+        .symbolic_trace
+        .iter()
+        .any(|c| matches!(&**c, SymbolicValue::Assign(_, rhs, ..) if **rhs == out_is(1))));
+
+    assert!(parse_input_json(
+        &serde_json::json!({}),
+        conc_executor.symbolic_library,
+        &verification_setting,
+    )
+    .is_err());
+}
+
+#[test]
+fn test_dead_code() {
+    let path = "./tests/sample/test_dead_code.circom".to_string();
+    let prime = BigInt::from_str(
+        "21888242871839275222246405745257275088548364400416034343698204186575808495617",
+    )
+    .unwrap();
+
+    let (mut symbolic_library, program_archive) = prepare_symbolic_library(path, prime.clone());
+    let setting = get_default_setting_for_symbolic_execution(prime, false);
+
+    let mut sexe = SymbolicExecutor::new(&mut symbolic_library, &setting);
+    execute(&mut sexe, &program_archive);
+
+    // `n == 2` differs between `Pick(2)` and `Pick(3)`, while `n > 1` holds for both
+    let branches = gather_unreachable_branches(&sexe, &program_archive.file_library);
+    assert_eq!(branches.len(), 1);
+    assert_eq!(branches[0].owner_name, "Pick");
+    assert!(branches[0].location.starts_with("Pick"));
+    assert_eq!(branches[0].branch, "else");
+
+    assert_eq!(
+        gather_unused_definitions(&sexe, "Main"),
+        vec![
+            "function increment".to_string(),
+            "template Unused".to_string()
+        ]
+    );
+}
+
+#[test]
+fn test_function_summary() {
+    let path = "./tests/sample/test_function_summary.circom".to_string();
+    let prime = BigInt::from_str(
+        "21888242871839275222246405745257275088548364400416034343698204186575808495617",
+    )
+    .unwrap();
+
+    let (mut symbolic_library, program_archive) =
+        prepare_symbolic_library(path.clone(), prime.clone());
+    let setting = get_default_setting_for_symbolic_execution(prime.clone(), false);
+    let mut sexe = SymbolicExecutor::new(&mut symbolic_library, &setting);
+    execute(&mut sexe, &program_archive);
+
+    // `square(3)` is executed once and served from the cache in the other iterations
+    assert_eq!(sexe.function_summaries.misses, 1);
+    assert_eq!(sexe.function_summaries.hits, 3);
+    assert_eq!(sexe.function_summaries.len(), 1);
+
+    let (mut uncached_library, _) = prepare_symbolic_library(path, prime.clone());
+    let mut uncached_setting = get_default_setting_for_symbolic_execution(prime, false);
+    uncached_setting.function_summary_capacity = 0;
+    let mut uncached_sexe = SymbolicExecutor::new(&mut uncached_library, &uncached_setting);
+    execute(&mut uncached_sexe, &program_archive);
+
+    assert!(uncached_sexe.function_summaries.is_empty());
+    assert_eq!(
+        sexe.cur_state.side_constraints,
+        uncached_sexe.cur_state.side_constraints
+    );
+    assert_eq!(
+        sexe.cur_state.symbolic_trace.len(),
+        uncached_sexe.cur_state.symbolic_trace.len()
+    );
+}
+
+#[test]
+fn test_main_params() {
+    let path = "./tests/sample/test_main_params.circom".to_string();
+    let prime = BigInt::from_str(
+        "21888242871839275222246405745257275088548364400416034343698204186575808495617",
+    )
+    .unwrap();
+
+    let (mut symbolic_library, mut program_archive) =
+        prepare_symbolic_library(path.clone(), prime.clone());
+    let param_names = program_archive.templates["WeightedSum"]
+        .get_name_of_params()
+        .clone();
+
+    assert!(override_main_params(
+        &mut program_archive,
+        &param_names,
+        &serde_json::json!({"m": 3})
+    )
+    .is_err());
+    assert!(override_main_params(
+        &mut program_archive,
+        &param_names,
+        &serde_json::json!({"n": 1.5})
+    )
+    .is_err());
+
+    override_main_params(
+        &mut program_archive,
+        &param_names,
+        &serde_json::json!({"n": 3, "coeffs": [2, "0x3", -1]}),
+    )
+    .unwrap();
+    match &program_archive.initial_template_call {
+        Expression::Call { args, .. } => match &args[1] {
+            Expression::ArrayInLine { values, .. } => {
+                assert_eq!(values.len(), 3);
+                assert!(matches!(&values[1], Expression::Number(_, v) if *v == BigInt::from(3)));
+                assert!(matches!(
+                    &values[2],
+                    Expression::PrefixOp {
+                        prefix_op: ExpressionPrefixOpcode::Sub,
+                        ..
+                    }
+                ));
+            }
+            _ => panic!("`coeffs` should be an inline array"),
+        },
+        _ => panic!("Cannot Find Main Call"),
+    }
+
+    let setting = get_default_setting_for_symbolic_execution(prime.clone(), false);
+    let mut sexe = SymbolicExecutor::new(&mut symbolic_library, &setting);
+    execute(&mut sexe, &program_archive);
+
+    // `out` and `in[0..3]` instead of `in[0..2]` written in the circuit
+    assert_eq!(sexe.cur_state.side_constraints.len(), 1);
+    assert_eq!(extract_variables(&sexe.cur_state.side_constraints).len(), 4);
+
+    let (mut default_library, default_archive) = prepare_symbolic_library(path, prime.clone());
+    let default_setting = get_default_setting_for_symbolic_execution(prime, false);
+    let mut default_sexe = SymbolicExecutor::new(&mut default_library, &default_setting);
+    execute(&mut default_sexe, &default_archive);
+
+    assert_eq!(
+        extract_variables(&default_sexe.cur_state.side_constraints).len(),
+        3
+    );
+}
+
+#[test]
+fn test_underscore_substitution() {
+    let path = "./tests/sample/test_underscore_substitution.circom".to_string();
+    let prime = BigInt::from_str(
+        "21888242871839275222246405745257275088548364400416034343698204186575808495617",
+    )
+    .unwrap();
+
+    let (mut symbolic_library, program_archive) = prepare_symbolic_library(path, prime.clone());
+    let setting = get_default_setting_for_symbolic_execution(prime, false);
+
+    let mut sexe = SymbolicExecutor::new(&mut symbolic_library, &setting);
+    execute(&mut sexe, &program_archive);
+
+    // `in * (in - 1) === 0` of the discarded component is collected
+    let booleanity_constraints: Vec<_> = sexe
+        .cur_state
+        .side_constraints
+        .iter()
+        .filter(|c| {
+            matches!(
+                &***c,
+                SymbolicValue::BinaryOp(
+                    _,
+                    DebuggableExpressionInfixOpcode(ExpressionInfixOpcode::Eq),
+                    _
+                )
+            )
+        })
+        .collect();
+    assert_eq!(booleanity_constraints.len(), 1);
+    assert!(booleanity_constraints[0]
+        .lookup_fmt(&sexe.symbolic_library.id2name)
+        .contains("Boolean"));
+}
+
+#[test]
+fn test_anonymous_component_named_inputs() {
+    let path = "./tests/sample/test_anonymous_component_named.circom".to_string();
+    let prime = BigInt::from_str(
+        "21888242871839275222246405745257275088548364400416034343698204186575808495617",
+    )
+    .unwrap();
+
+    let (mut symbolic_library, program_archive) = prepare_symbolic_library(path, prime.clone());
+    let setting = get_default_setting_for_symbolic_execution(prime, false);
+
+    let mut sexe = SymbolicExecutor::new(&mut symbolic_library, &setting);
+    execute(&mut sexe, &program_archive);
+
+    let name2id = &sexe.symbolic_library.name2id;
+    let assignments: Vec<(usize, usize, usize, usize)> = sexe
+        .cur_state
+        .side_constraints
+        .iter()
+        .filter_map(|c| match &**c {
+            SymbolicValue::AssignEq(lhs, rhs) => match (&**lhs, &**rhs) {
+                (SymbolicValue::Variable(l), SymbolicValue::Variable(r)) => {
+                    Some((l.id, l.owner.len(), r.id, r.owner.len()))
+                }
+                _ => None,
+            },
+            _ => None,
+        })
+        .collect();
+
+    // `A(2)(b <== y, a <== x)` binds the inputs by name, not by position
+    assert!(assignments.contains(&(name2id["a"], 2, name2id["x"], 1)));
+    assert!(assignments.contains(&(name2id["b"], 2, name2id["y"], 1)));
+    assert!(!assignments.contains(&(name2id["a"], 2, name2id["y"], 1)));
+
+    // `(out2, _, _) <== B()(x)` only binds the first output
+    assert!(assignments.contains(&(name2id["out2"], 1, name2id["o1"], 2)));
+    assert!(!assignments
+        .iter()
+        .any(|(_, len, r, _)| *len == 1 && (*r == name2id["o2"] || *r == name2id["o3"])));
+}
+
+#[test]
+fn test_input_spec() {
+    let spec = parse_input_spec(
+        r#"
+        a = { min = 2, max = 10 }
+        op = { values = [0, 1, "2"] }
+        nonce = { fixed = "18446744073709551616" }
+        flag = "boolean"
+        "in[0]" = { min = 0, max = "340282366920938463463374607431768211455" }
+        "#,
+    )
+    .unwrap();
+    assert_eq!(
+        spec.domains["nonce"],
+        InputDomain::Values(vec![BigInt::from_str("18446744073709551616").unwrap()])
+    );
+    assert_eq!(
+        spec.domains["flag"],
+        InputDomain::Values(vec![BigInt::zero(), BigInt::one()])
+    );
+    assert_eq!(spec.domains["a"].candidates().len(), 9);
+    assert_eq!(spec.domains["in[0]"].candidates().len(), 4);
+    assert!(parse_input_spec("a = { min = 3, max = 2 }").is_err());
+    assert!(parse_input_spec("a = \"ternary\"").is_err());
+
+    let path = "./tests/sample/test_input_spec.circom".to_string();
+    let prime = BigInt::from_str(
+        "21888242871839275222246405745257275088548364400416034343698204186575808495617",
+    )
+    .unwrap();
+
+    let (mut symbolic_library, program_archive) = prepare_symbolic_library(path, prime.clone());
+    let setting = get_default_setting_for_symbolic_execution(prime.clone(), false);
+
+    let mut sexe = SymbolicExecutor::new(&mut symbolic_library, &setting);
+    execute(&mut sexe, &program_archive);
+
+    let (main_template_name, template_param_names, template_param_values) =
+        match &program_archive.initial_template_call {
+            Expression::Call { id, args, .. } => {
+                let template = &program_archive.templates[id];
+                (id, template.get_name_of_params().clone(), args.clone())
+            }
+            _ => unimplemented!(),
+        };
+    assert_eq!(
+        spec.unknown_inputs(
+            &sexe.symbolic_library.template_library[&sexe.symbolic_library.name2id["Guarded"]],
+            &sexe.symbolic_library.name2id,
+        ),
+        vec!["flag", "in[0]", "nonce", "op"]
+    );
+
+    let verification_setting = BaseVerificationConfig {
+        target_template_name: main_template_name.to_string(),
+        prime: prime.clone(),
+        range: BigInt::from(100),
+        quick_mode: true,
+        heuristics_mode: false,
+        progress_interval: 10000,
+        template_param_names: template_param_names,
+        template_param_values: template_param_values,
+        check_output_tags: false,
+        max_counterexamples: 1,
+        input_shapes: FxHashMap::default(),
+        input_spec: InputSpec::default(),
+    };
+
+    let conc_setting = get_default_setting_for_concrete_execution(prime, false);
+    let mut conc_executor = SymbolicExecutor::new(&mut sexe.symbolic_library, &conc_setting);
+    let mut search = |input_spec: InputSpec| {
+        brute_force_search(
+            &mut conc_executor,
+            &sexe.cur_state.symbolic_trace.clone(),
+            &sexe.cur_state.side_constraints.clone(),
+            &BaseVerificationConfig {
+                input_spec: input_spec,
+                ..verification_setting.clone()
+            },
+        )
+    };
+
+    // `a = 1` is found without a specification, but excluded by `a >= 2`
+    assert!(search(InputSpec::default()).is_some());
+    assert!(search(spec).is_none());
+    assert!(search(parse_input_spec("a = { fixed = 1 }").unwrap()).is_some());
+}
+
+#[test]
+fn test_recursion_limits() {
+    let prime = BigInt::from_str(
+        "21888242871839275222246405745257275088548364400416034343698204186575808495617",
+    )
+    .unwrap();
+    let mut setting = get_default_setting_for_symbolic_execution(prime.clone(), false);
+    setting.max_call_depth = 8;
+
+    // A recursion depending on a symbolic argument is stopped at the depth limit
+    let (mut symbolic_library, program_archive) = prepare_symbolic_library(
+        "./tests/sample/test_unbounded_recursion.circom".to_string(),
+        prime.clone(),
+    );
+    let mut sexe = SymbolicExecutor::new(&mut symbolic_library, &setting);
+    execute(&mut sexe, &program_archive);
+    let error = sexe.recursion_error.clone().unwrap();
+    assert_eq!(error.kind, RecursionErrorKind::DepthExceeded);
+    assert_eq!(error.call_chain, vec!["countdown".to_string(); 9]);
+    assert!(sexe.execution_failed);
+
+    // A template instantiating itself with the same parameters is a cycle
+    let (mut symbolic_library, program_archive) = prepare_symbolic_library(
+        "./tests/sample/test_cyclic_template.circom".to_string(),
+        prime.clone(),
+    );
+    let mut sexe = SymbolicExecutor::new(&mut symbolic_library, &setting);
+    execute(&mut sexe, &program_archive);
+    let error = sexe.recursion_error.clone().unwrap();
+    assert_eq!(error.kind, RecursionErrorKind::Cycle);
+    assert_eq!(
+        error.call_chain,
+        vec!["Chain".to_string(), "Chain".to_string()]
+    );
+    assert_eq!(error.message(), "cyclic call: Chain → Chain");
+
+    // Recursions with a base case are executed
+    let (mut symbolic_library, program_archive) = prepare_symbolic_library(
+        "./tests/sample/test_recursive_function.circom".to_string(),
+        prime,
+    );
+    let mut sexe = SymbolicExecutor::new(&mut symbolic_library, &setting);
+    execute(&mut sexe, &program_archive);
+    assert!(sexe.recursion_error.is_none());
+}
+
+#[test]
+fn test_signal_assignment_issues() {
+    let path = "./tests/sample/test_signal_assignment.circom".to_string();
+    let prime = BigInt::from_str(
+        "21888242871839275222246405745257275088548364400416034343698204186575808495617",
+    )
+    .unwrap();
+
+    let (mut symbolic_library, program_archive) = prepare_symbolic_library(path, prime.clone());
+    let setting = get_default_setting_for_symbolic_execution(prime, false);
+
+    let mut sexe = SymbolicExecutor::new(&mut symbolic_library, &setting);
+    execute(&mut sexe, &program_archive);
+
+    let issues = gather_signal_assignment_issues(
+        &sexe,
+        &sexe.cur_state.symbolic_trace,
+        "Main",
+        &program_archive.file_library,
+    );
+    let summary: Vec<(SignalAssignmentIssueKind, &str, usize)> = issues
+        .iter()
+        .map(|issue| (issue.kind, issue.signal.as_str(), issue.locations.len()))
+        .collect();
+    assert_eq!(
+        summary,
+        vec![
+            (SignalAssignmentIssueKind::DoubleAssignment, "main.tmp", 2),
+            (SignalAssignmentIssueKind::InputAssignment, "main.flag", 1),
+            (SignalAssignmentIssueKind::UnassignedRead, "main.mul.c", 0),
+        ]
+    );
+    assert!(issues[0].locations.iter().all(|l| l.starts_with("Main")));
+}
+
+#[test]
+fn test_signal_assignment_issues_of_partial_output_arrays() {
+    let path = "./tests/sample/test_partial_output_array.circom".to_string();
+    let prime = BigInt::from_str(
+        "21888242871839275222246405745257275088548364400416034343698204186575808495617",
+    )
+    .unwrap();
+
+    let (mut symbolic_library, program_archive) = prepare_symbolic_library(path, prime.clone());
+    let setting = get_default_setting_for_symbolic_execution(prime, false);
+
+    let mut sexe = SymbolicExecutor::new(&mut symbolic_library, &setting);
+    execute(&mut sexe, &program_archive);
+
+    let issues = gather_signal_assignment_issues(
+        &sexe,
+        &sexe.cur_state.symbolic_trace,
+        "Main",
+        &program_archive.file_library,
+    );
+    let summary: Vec<(SignalAssignmentIssueKind, &str, Vec<&str>)> = issues
+        .iter()
+        .map(|issue| {
+            (
+                issue.kind,
+                issue.signal.as_str(),
+                issue.elements.iter().map(|e| e.as_str()).collect(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        summary,
+        vec![
+            (
+                SignalAssignmentIssueKind::UnassignedOutputElements,
+                "main.out",
+                vec!["[1]"]
+            ),
+            (
+                SignalAssignmentIssueKind::UnassignedOutputElements,
+                "main.pair.out",
+                vec!["[1][0]", "[1][1]"]
+            ),
+        ]
+    );
+    assert_eq!(
+        issues[1].message(),
+        "2 element(s) of output `main.pair.out` are never assigned: [1][0], [1][1]"
+    );
+}
+
+#[test]
+fn test_witness_order_issues() {
+    let path = "./tests/sample/test_witness_order.circom".to_string();
+    let prime = BigInt::from_str(
+        "21888242871839275222246405745257275088548364400416034343698204186575808495617",
+    )
+    .unwrap();
+
+    let (mut symbolic_library, program_archive) = prepare_symbolic_library(path, prime.clone());
+    let setting = get_default_setting_for_symbolic_execution(prime, false);
+
+    let mut sexe = SymbolicExecutor::new(&mut symbolic_library, &setting);
+    execute(&mut sexe, &program_archive);
+
+    let issues = gather_witness_order_issues(
+        &sexe,
+        &sexe.cur_state.symbolic_trace,
+        &program_archive.file_library,
+    );
+    let summary: Vec<(WitnessOrderIssueKind, Vec<&str>)> = issues
+        .iter()
+        .map(|issue| (issue.kind, issue.chain.iter().map(|s| s.as_str()).collect()))
+        .collect();
+    assert_eq!(
+        summary,
+        vec![
+            (WitnessOrderIssueKind::Cycle, vec!["main.a", "main.b"]),
+            (
+                WitnessOrderIssueKind::UseBeforeAssignment,
+                vec!["main.early", "main.d.out"]
+            ),
+        ]
+    );
+    assert!(issues[0].locations.iter().all(|l| l.starts_with("Main")));
+    assert!(issues[1].locations[1].starts_with("Double"));
+    assert!(issues[0]
+        .message()
+        .contains("`main.a`, which depends on `main.b`, which depends on `main.a`"));
+}
+
+#[test]
+fn test_symbolic_ternaries() {
+    let path = "./tests/sample/test_symbolic_ternary.circom".to_string();
+    let prime = BigInt::from_str(
+        "21888242871839275222246405745257275088548364400416034343698204186575808495617",
+    )
+    .unwrap();
+
+    let (mut symbolic_library, program_archive) = prepare_symbolic_library(path, prime.clone());
+    let setting = get_default_setting_for_symbolic_execution(prime, false);
+
+    let mut sexe = SymbolicExecutor::new(&mut symbolic_library, &setting);
+    execute(&mut sexe, &program_archive);
+
+    // `n > 1 ? a : b` has a constant condition and is not reported
+    let sites = gather_symbolic_ternaries(&sexe, &program_archive.file_library);
+    assert_eq!(sites.len(), 1);
+    assert_eq!(sites[0].owner_name, "Select");
+    assert!(sites[0].location.starts_with("Select"));
+    assert_eq!(sites[0].condition, "main.s.sel");
+    assert!(sites[0]
+        .suggestion
+        .contains("main.s.sel * (main.s.sel - 1) === 0"));
+}
+
+#[test]
+fn test_parse_stages() {
+    let stages = parse_stages("propagate:10, targeted,full").unwrap();
+    let names: Vec<_> = stages.iter().map(|s| s.stage).collect();
+    assert_eq!(names, vec![Stage::Propagate, Stage::Targeted, Stage::Full]);
+    assert_eq!(stages[0].timeout, Some(Duration::from_secs(10)));
+    assert_eq!(stages[1].timeout, None);
+
+    assert!(parse_stages("full,full").is_err());
+    assert!(parse_stages("exhaustive").is_err());
+    assert!(parse_stages("full:0").is_err());
+    assert!(parse_stages("").is_err());
+}
+
+#[test]
+fn test_staged_search() {
+    let path = "./tests/sample/test_staged_search.circom".to_string();
+    let prime = BigInt::from_str(
+        "21888242871839275222246405745257275088548364400416034343698204186575808495617",
+    )
+    .unwrap();
+
+    let (mut symbolic_library, program_archive) = prepare_symbolic_library(path, prime.clone());
+    let setting = get_default_setting_for_symbolic_execution(prime.clone(), false);
+
+    let mut sexe = SymbolicExecutor::new(&mut symbolic_library, &setting);
+    execute(&mut sexe, &program_archive);
+
+    let symbolic_trace = sexe.cur_state.symbolic_trace.clone();
+    let side_constraints = sexe.cur_state.side_constraints.clone();
+
+    let (main_template_name, template_param_names, template_param_values) =
+        match &program_archive.initial_template_call {
+            Expression::Call { id, args, .. } => {
+                let template = &program_archive.templates[id];
+                (id, template.get_name_of_params().clone(), args.clone())
+            }
+            _ => unimplemented!(),
+        };
+
+    let verification_setting = BaseVerificationConfig {
+        target_template_name: main_template_name.to_string(),
+        prime: prime.clone(),
+        range: BigInt::from(5),
+        quick_mode: false,
+        heuristics_mode: true,
+        progress_interval: 10000,
+        template_param_names: template_param_names,
+        template_param_values: template_param_values,
+        check_output_tags: false,
+        max_counterexamples: 1,
+        input_shapes: FxHashMap::default(),
+        input_spec: InputSpec::default(),
+    };
+
+    let conc_setting = get_default_setting_for_concrete_execution(prime.clone(), false);
+    let mut conc_executor = SymbolicExecutor::new(&mut sexe.symbolic_library, &conc_setting);
+    let mode = conc_executor.symbolic_library.name2id["mode"];
+
+    // `mode === 3` fixes `mode`
+    let result = staged_search(
+        &mut conc_executor,
+        &symbolic_trace,
+        &side_constraints,
+        &verification_setting,
+        &parse_stages("propagate,full").unwrap(),
+        &[],
+    );
+    assert_eq!(result.winner, Some(Stage::Propagate));
+    assert_eq!(result.stages.len(), 1);
+    assert_eq!(result.stages[0].num_restricted, 1);
+    let counter_example = result.counter_example.unwrap();
+    assert!(counter_example
+        .assignment
+        .iter()
+        .any(|(k, v)| k.id == mode && *v == BigInt::from(3)));
+
+    // `out` is assigned with `<--` and checked by a side constraint with `in`, so only `mode`
+    // is left out of the targeted stage
+    let result = staged_search(
+        &mut conc_executor,
+        &symbolic_trace,
+        &side_constraints,
+        &verification_setting,
+        &parse_stages("targeted,full").unwrap(),
+        &[],
+    );
+    assert_eq!(result.winner, Some(Stage::Targeted));
+    assert_eq!(result.stages[0].status, StageStatus::Found);
+    assert_eq!(result.stages[0].num_restricted, 1);
+}
+
+#[test]
+fn test_multi_substitution_shapes() {
+    let path = "./tests/sample/test_multi_substitution_shape.circom".to_string();
+    let prime = BigInt::from_str(
+        "21888242871839275222246405745257275088548364400416034343698204186575808495617",
+    )
+    .unwrap();
+
+    let (mut symbolic_library, program_archive) = prepare_symbolic_library(path, prime.clone());
+    let setting = get_default_setting_for_symbolic_execution(prime, false);
+
+    let mut sexe = SymbolicExecutor::new(&mut symbolic_library, &setting);
+    execute(&mut sexe, &program_archive);
+
+    let name2id = &sexe.symbolic_library.name2id;
+    let assigned: Vec<(usize, usize)> = sexe
+        .cur_state
+        .side_constraints
+        .iter()
+        .filter_map(|c| match &**c {
+            SymbolicValue::AssignEq(lhs, _) => match &**lhs {
+                SymbolicValue::Variable(l) if l.owner.len() == 1 => Some((l.id, l.get_dim())),
+                _ => None,
+            },
+            _ => None,
+        })
+        .collect();
+
+    // `(arr, s) <== Pair()(x)` assigns `arr` element by element
+    assert_eq!(
+        assigned
+            .iter()
+            .filter(|(id, dim)| *id == name2id["arr"] && *dim == 1)
+            .count(),
+        2
+    );
+    assert!(assigned.iter().any(|(id, _)| *id == name2id["s"]));
+
+    // `(t, u) <== Pair()(x)` assigns `a[2]` to `t[3]` and is not assigned
+    assert!(!assigned
+        .iter()
+        .any(|(id, _)| *id == name2id["t"] || *id == name2id["u"]));
+    let sites = gather_shape_mismatches(&sexe, &program_archive.file_library);
+    assert_eq!(sites.len(), 1);
+    assert_eq!(sites[0].owner_name, "Main");
+    assert!(sites[0].location.starts_with("Main"));
+    assert_eq!(
+        sites[0].message,
+        "`main.t` of dimensions [3] is assigned a value of dimensions [2]"
+    );
+}
+
+#[test]
+fn test_path_condition_pruning() {
+    let path = "./tests/sample/test_path_condition_pruning.circom".to_string();
+    let prime = BigInt::from_str(
+        "21888242871839275222246405745257275088548364400416034343698204186575808495617",
+    )
+    .unwrap();
+
+    let (mut symbolic_library, program_archive) = prepare_symbolic_library(path, prime.clone());
+    let setting = get_default_setting_for_symbolic_execution(prime.clone(), false);
+
+    let mut sexe = SymbolicExecutor::new(&mut symbolic_library, &setting);
+    execute(&mut sexe, &program_archive);
+
+    let verification_setting = BaseVerificationConfig {
+        target_template_name: "PrunedBranch".to_string(),
+        prime: prime.clone(),
+        range: BigInt::from(100),
+        quick_mode: true,
+        heuristics_mode: false,
+        progress_interval: 10000,
+        template_param_names: Vec::new(),
+        template_param_values: Vec::new(),
+        check_output_tags: false,
+        max_counterexamples: 1,
+        input_shapes: FxHashMap::default(),
+        input_spec: InputSpec::default(),
+    };
+    let symbolic_trace = sexe.cur_state.symbolic_trace.clone();
+    let side_constraints = sexe.cur_state.side_constraints.clone();
+    let path_conditions = PathConditions::gather(
+        &symbolic_trace,
+        &side_constraints,
+        sexe.symbolic_library,
+        &verification_setting,
+    );
+    assert!(!path_conditions.is_empty());
+
+    let mut variables = extract_variables(&symbolic_trace);
+    variables.extend(extract_variables(&side_constraints));
+    let name2id = sexe.symbolic_library.name2id.clone();
+    let find = |name: &str| {
+        variables
+            .iter()
+            .find(|v| v.id == name2id[name])
+            .unwrap()
+            .clone()
+    };
+    let (s, tmp, out) = (find("s"), find("tmp"), find("out"));
+
+    // The variables of the path conditions are enumerated first
+    let mut ordered = variables.clone();
+    path_conditions.order_variables(&mut ordered);
+    assert_eq!(ordered[0], s);
+
+    // `tmp` only occurs under `s == 1`
+    let mut assignment = FxHashMap::default();
+    assert!(!path_conditions.is_infeasible(&tmp, &prime, &assignment, sexe.symbolic_library));
+    assignment.insert(s.clone(), BigInt::zero());
+    assert!(path_conditions.is_infeasible(&tmp, &prime, &assignment, sexe.symbolic_library));
+    assert!(!path_conditions.is_infeasible(&out, &prime, &assignment, sexe.symbolic_library));
+    assignment.insert(s.clone(), BigInt::one());
+    assert!(!path_conditions.is_infeasible(&tmp, &prime, &assignment, sexe.symbolic_library));
+
+    let conc_setting = get_default_setting_for_concrete_execution(prime, false);
+    let mut conc_executor = SymbolicExecutor::new(&mut sexe.symbolic_library, &conc_setting);
+    let counter_example = brute_force_search(
+        &mut conc_executor,
+        &symbolic_trace,
+        &side_constraints,
+        &verification_setting,
+    );
+    assert!(matches!(
+        counter_example,
+        Some(CounterExample {
+            flag: VerificationResult::UnderConstrained(UnderConstrainedType::NonDeterministic(..)),
+            ..
+        })
+    ));
+}
+
+#[test]
+fn test_structural_seeds() {
+    let path = "./tests/sample/test_structural_seeds.circom".to_string();
+    let prime = BigInt::from_str(
+        "21888242871839275222246405745257275088548364400416034343698204186575808495617",
+    )
+    .unwrap();
+
+    let (mut symbolic_library, program_archive) = prepare_symbolic_library(path, prime.clone());
+    let setting = get_default_setting_for_symbolic_execution(prime.clone(), false);
+
+    let mut sexe = SymbolicExecutor::new(&mut symbolic_library, &setting);
+    execute(&mut sexe, &program_archive);
+
+    let verification_setting = BaseVerificationConfig {
+        target_template_name: "MagicValue".to_string(),
+        prime: prime.clone(),
+        range: BigInt::from(100),
+        quick_mode: true,
+        heuristics_mode: false,
+        progress_interval: 10000,
+        template_param_names: Vec::new(),
+        template_param_values: Vec::new(),
+        check_output_tags: false,
+        max_counterexamples: 1,
+        input_shapes: FxHashMap::default(),
+        input_spec: InputSpec::default(),
+    };
+    let symbolic_trace = sexe.cur_state.symbolic_trace.clone();
+    let side_constraints = sexe.cur_state.side_constraints.clone();
+    let mut variables = extract_variables(&symbolic_trace);
+    variables.extend(extract_variables(&side_constraints));
+    let input_variables =
+        gather_input_variables(sexe.symbolic_library, &verification_setting, &variables);
+    let name2id = sexe.symbolic_library.name2id.clone();
+    let find = |name: &str| {
+        input_variables
+            .iter()
+            .find(|v| v.id == name2id[name])
+            .unwrap()
+            .clone()
+    };
+    let (a, b) = (find("a"), find("b"));
+
+    let seeds = StructuralSeeds::gather(
+        &symbolic_trace,
+        &side_constraints,
+        sexe.symbolic_library,
+        &verification_setting,
+        &input_variables,
+    );
+    // The constant `a` is compared against, with its neighbours
+    for value in [1233, 1234, 1235] {
+        assert!(seeds.candidates_of(&a).contains(&BigInt::from(value)));
+    }
+    // The roots of `(b - 7) * (b - 9) === 0`
+    for value in [7, 9] {
+        assert!(seeds.candidates_of(&b).contains(&BigInt::from(value)));
+    }
+    let assignments = seeds.assignments(&input_variables, 30);
+    assert!(assignments
+        .iter()
+        .any(|assignment| assignment[&a] == BigInt::from(1234)));
+
+    // The quick mode only tries `0`, `1`, and `-1` without the seeds
+    let conc_setting = get_default_setting_for_concrete_execution(prime, false);
+    let mut conc_executor = SymbolicExecutor::new(&mut sexe.symbolic_library, &conc_setting);
+    let counter_example = brute_force_search(
+        &mut conc_executor,
+        &symbolic_trace,
+        &side_constraints,
+        &verification_setting,
+    )
+    .unwrap();
+    assert!(matches!(
+        counter_example.flag,
+        VerificationResult::UnderConstrained(UnderConstrainedType::NonDeterministic(..))
+    ));
+    assert_eq!(counter_example.assignment[&a], BigInt::from(1234));
+}
+
+#[test]
+fn test_search_ranges() {
+    let path = "./tests/sample/test_lessthan.circom".to_string();
+    let prime = BigInt::from_str(
+        "21888242871839275222246405745257275088548364400416034343698204186575808495617",
+    )
+    .unwrap();
+
+    let (mut symbolic_library, program_archive) = prepare_symbolic_library(path, prime.clone());
+    let setting = get_default_setting_for_symbolic_execution(prime.clone(), false);
+
+    let mut sexe = SymbolicExecutor::new(&mut symbolic_library, &setting);
+    execute(&mut sexe, &program_archive);
+
+    let verification_setting = BaseVerificationConfig {
+        target_template_name: "VulnerableLessThan".to_string(),
+        prime: prime.clone(),
+        range: BigInt::from(3),
+        quick_mode: false,
+        heuristics_mode: true,
+        progress_interval: 10000,
+        template_param_names: Vec::new(),
+        template_param_values: Vec::new(),
+        check_output_tags: false,
+        max_counterexamples: 1,
+        input_shapes: FxHashMap::default(),
+        input_spec: InputSpec::default(),
+    };
+    let symbolic_trace = sexe.cur_state.symbolic_trace.clone();
+    let side_constraints = sexe.cur_state.side_constraints.clone();
+    let ranges = SearchRanges::infer(
+        &sexe,
+        &symbolic_trace,
+        &side_constraints,
+        &verification_setting,
+    );
+
+    let mut variables = extract_variables(&symbolic_trace);
+    variables.extend(extract_variables(&side_constraints));
+    let id2name = sexe.symbolic_library.id2name.clone();
+    let find = |name: &str| {
+        variables
+            .iter()
+            .find(|v| strip_ansi(&v.lookup_fmt(&id2name)) == name)
+            .unwrap()
+            .clone()
+    };
+    let (a, bit, n2b_in, out) = (
+        find("main.a"),
+        find("main.lt.n2b.out[0]"),
+        find("main.lt.n2b.in"),
+        find("main.out"),
+    );
+
+    // The bits of `Num2Bits(4)`, its input, and the output defined from the output of `LessThan`
+    assert_eq!(ranges.bounds.get(&bit), Some(&BigInt::from(1)));
+    assert_eq!(ranges.bounds.get(&n2b_in), Some(&BigInt::from(15)));
+    assert_eq!(ranges.bounds.get(&out), Some(&BigInt::from(1)));
+    assert!(!ranges.bounds.contains_key(&a));
+
+    // The special values first, then the seeds, then by increasing magnitude up to the range
+    let values = |values: &[i64]| values.iter().map(|v| BigInt::from(*v)).collect::<Vec<_>>();
+    assert_eq!(ranges.schedule(&bit, &[], false), values(&[0, 1]));
+    assert_eq!(
+        ranges.schedule(&n2b_in, &values(&[8, 20]), false),
+        values(&[0, 1, 15, 8, 2, 3])
+    );
+    assert_eq!(ranges.schedule(&a, &[], true), values(&[0, 1, -1]));
+    assert_eq!(
+        ranges.schedule(&a, &values(&[5]), false),
+        values(&[0, 1, -1, 5, 2, -2, 3, -3])
+    );
+    assert_eq!(
+        ranges.schedule(&a, &[&prime - BigInt::one()], true),
+        values(&[0, 1, -1])
+    );
+}
+
+#[test]
+fn test_prime_consistency() {
+    let path = "./tests/sample/test_prime_dependent_bound.circom".to_string();
+    let prime = prime_modulus("bn128").unwrap();
+
+    let (mut symbolic_library, program_archive) = prepare_symbolic_library(path, prime.clone());
+    let setting = get_default_setting_for_symbolic_execution(prime.clone(), false);
+
+    let mut sexe = SymbolicExecutor::new(&mut symbolic_library, &setting);
+    execute(&mut sexe, &program_archive);
+
+    let verification_setting = BaseVerificationConfig {
+        target_template_name: "SignedBound".to_string(),
+        prime: prime.clone(),
+        range: BigInt::from(100),
+        quick_mode: false,
+        heuristics_mode: false,
+        progress_interval: 10000,
+        template_param_names: Vec::new(),
+        template_param_values: Vec::new(),
+        check_output_tags: false,
+        max_counterexamples: 1,
+        input_shapes: FxHashMap::default(),
+        input_spec: InputSpec::default(),
+    };
+    let symbolic_trace = sexe.cur_state.symbolic_trace.clone();
+    let side_constraints = sexe.cur_state.side_constraints.clone();
+
+    assert_eq!(
+        prime_modulus("goldilocks"),
+        Some(BigInt::from(18446744069414584321u64))
+    );
+    assert_eq!(prime_modulus("101"), Some(BigInt::from(101)));
+    assert!(prime_modulus("bn254").is_none());
+
+    // Only the assignment to `out` depends on the prime, since the binarity holds in both fields
+    let prime_dependent_constraints = check_prime_consistency(
+        &symbolic_trace,
+        &side_constraints,
+        sexe.symbolic_library,
+        &verification_setting,
+        &prime_modulus("goldilocks").unwrap(),
+        100,
+        1,
+    );
+    assert_eq!(prime_dependent_constraints.len(), 1);
+    assert!(!prime_dependent_constraints[0].is_side_constraint);
+    assert!(prime_dependent_constraints[0].holds_under_base);
+
+    // `2 ** 63` is far below `p / 2` in both fields
+    assert!(check_prime_consistency(
+        &symbolic_trace,
+        &side_constraints,
+        sexe.symbolic_library,
+        &verification_setting,
+        &prime_modulus("bls12381").unwrap(),
+        100,
+        1,
+    )
+    .is_empty());
+}
+
+#[test]
+fn test_replay_logs() {
+    let path = "./tests/sample/test_log_call.circom".to_string();
+    let prime = BigInt::from_str(
+        "21888242871839275222246405745257275088548364400416034343698204186575808495617",
+    )
+    .unwrap();
+
+    let (mut symbolic_library, program_archive) = prepare_symbolic_library(path, prime.clone());
+    let setting = get_default_setting_for_symbolic_execution(prime.clone(), false);
+
+    let mut sexe = SymbolicExecutor::new(&mut symbolic_library, &setting);
+    execute(&mut sexe, &program_archive);
+
+    let verification_setting = BaseVerificationConfig {
+        target_template_name: "LoggedIsZero".to_string(),
+        prime: prime.clone(),
+        range: BigInt::from(100),
+        quick_mode: false,
+        heuristics_mode: false,
+        progress_interval: 10000,
+        template_param_names: Vec::new(),
+        template_param_values: Vec::new(),
+        check_output_tags: false,
+        max_counterexamples: 1,
+        input_shapes: FxHashMap::default(),
+        input_spec: InputSpec::default(),
+    };
+    let id2name = sexe.symbolic_library.id2name.clone();
+    let input = extract_variables(&sexe.cur_state.symbolic_trace)
+        .into_iter()
+        .find(|v| strip_ansi(&v.lookup_fmt(&id2name)) == "main.in")
+        .unwrap();
+    let counter_example = CounterExample {
+        flag: VerificationResult::UnderConstrained(UnderConstrainedType::UnusedOutput),
+        target_output: None,
+        assignment: FxHashMap::from_iter([(input, BigInt::zero())]),
+    };
+
+    let conc_setting = get_default_setting_for_concrete_execution(prime.clone(), false);
+    let lines = replay_logs(
+        sexe.symbolic_library,
+        &conc_setting,
+        &verification_setting,
+        &counter_example,
+        &program_archive.file_library,
+    );
+
+    // The lines of the template and of the called function, in execution order
+    let printed = lines
+        .iter()
+        .map(|line| (line.location.clone(), line.message.clone()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        printed,
+        vec![
+            ("LoggedIsZero (line 14)".to_string(), "in = 0".to_string()),
+            ("double (line 4)".to_string(), "double 0".to_string()),
+            (
+                "LoggedIsZero (line 19)".to_string(),
+                "d = 0 out = 1".to_string()
+            ),
+        ]
+    );
+}
+
+#[test]
+fn test_undetermined_dimension() {
+    let path = "./tests/sample/test_undetermined_dimension.circom".to_string();
+    let prime = BigInt::from_str(
+        "21888242871839275222246405745257275088548364400416034343698204186575808495617",
+    )
+    .unwrap();
+
+    let (mut symbolic_library, program_archive) = prepare_symbolic_library(path, prime.clone());
+    let setting = get_default_setting_for_symbolic_execution(prime, false);
+
+    let mut sexe = SymbolicExecutor::new(&mut symbolic_library, &setting);
+    execute(&mut sexe, &program_archive);
+
+    // The size of `arr` in `sumUpTo(4)` flows through `half` and `len`, and is resolved
+    let sites = gather_undetermined_dimensions(&sexe, &program_archive.file_library);
+    assert_eq!(sites.len(), 2);
+    assert_eq!(sites[0].owner_name, "Negative");
+    assert!(sites[0].location.starts_with("Negative"));
+    assert!(sites[0].message.ends_with("which is not a valid array size"));
+    assert_eq!(sites[1].owner_name, "identity");
+    assert!(sites[1].location.starts_with("identity"));
+    assert!(sites[1]
+        .message
+        .starts_with("`arr` is declared with the size `"));
+    assert!(sites[1].message.ends_with("which is not a constant"));
+}
+
+#[test]
+fn test_constraint_provenance() {
+    let path = "./tests/sample/test_constraint_provenance.circom".to_string();
+    let prime = BigInt::from_str(
+        "21888242871839275222246405745257275088548364400416034343698204186575808495617",
+    )
+    .unwrap();
+
+    let (mut symbolic_library, program_archive) = prepare_symbolic_library(path, prime.clone());
+    let setting = get_default_setting_for_symbolic_execution(prime, false);
+
+    let mut sexe = SymbolicExecutor::new(&mut symbolic_library, &setting);
+    execute(&mut sexe, &program_archive);
+
+    assert_eq!(
+        sexe.cur_state.trace_provenance.len(),
+        sexe.cur_state.symbolic_trace.len()
+    );
+    assert_eq!(
+        sexe.cur_state.side_constraint_provenance.len(),
+        sexe.cur_state.side_constraints.len()
+    );
+
+    let origins: Vec<_> = (0..sexe.cur_state.symbolic_trace.len())
+        .map(|i| explain_constraint(&sexe, &program_archive.file_library, i, false).unwrap())
+        .collect();
+    // The constraints of the inlined component are attributed to its statements
+    let origin = origins
+        .iter()
+        .find(|o| {
+            o.snippet
+                .as_ref()
+                .map_or(false, |s| s.contains("in * out === 0"))
+        })
+        .unwrap();
+    assert_eq!(origin.owner, "main.iz");
+    assert!(origin.location.as_ref().unwrap().starts_with("IsZero"));
+    let origin = origins
+        .iter()
+        .find(|o| {
+            o.snippet
+                .as_ref()
+                .map_or(false, |s| s.contains("iz.in <== a - b"))
+        })
+        .unwrap();
+    assert_eq!(origin.owner, "main");
+    assert!(origin.location.as_ref().unwrap().starts_with("Main"));
+    assert!(origins.iter().all(|o| o.location.is_some()));
+
+    let num_side_constraints = sexe.cur_state.side_constraints.len();
+    let origin = explain_constraint(&sexe, &program_archive.file_library, 0, true).unwrap();
+    assert!(origin.snippet.is_some());
+    assert!(explain_constraint(
+        &sexe,
+        &program_archive.file_library,
+        num_side_constraints,
+        true
+    )
+    .is_none());
+}
+
+#[test]
+fn test_function_assert() {
+    let path = "./tests/sample/test_function_assert.circom".to_string();
+    let prime = BigInt::from_str(
+        "21888242871839275222246405745257275088548364400416034343698204186575808495617",
+    )
+    .unwrap();
+
+    let (mut symbolic_library, program_archive) = prepare_symbolic_library(path, prime.clone());
+    let setting = get_default_setting_for_symbolic_execution(prime.clone(), false);
+
+    let mut sexe = SymbolicExecutor::new(&mut symbolic_library, &setting);
+    execute(&mut sexe, &program_archive);
+
+    // The assertion is a path condition attributed to the call site in `Main`
+    assert_eq!(sexe.function_assertions.len(), 1);
+    let assertion = &sexe.function_assertions[0];
+    assert_eq!(
+        sexe.symbolic_library.id2name[&assertion.function_id],
+        "checked_half"
+    );
+    assert_eq!(
+        sexe.symbolic_library.id2name[&assertion.call_site.owner_id],
+        "Main"
+    );
+    let index = sexe
+        .cur_state
+        .symbolic_trace
+        .iter()
+        .position(|c| *c == assertion.condition)
+        .unwrap();
+    assert_eq!(sexe.cur_state.trace_provenance[index], assertion.call_site);
+    let origin = explain_constraint(&sexe, &program_archive.file_library, index, false).unwrap();
+    assert_eq!(origin.owner, "main");
+    assert!(origin.snippet.unwrap().contains("checked_half(in)"));
+    assert!(origin.assertion.unwrap().starts_with("checked_half"));
+
+    let (main_template_name, template_param_names, template_param_values) =
+        match &program_archive.initial_template_call {
+            Expression::Call { id, args, .. } => {
+                let template = &program_archive.templates[id];
+                (id, template.get_name_of_params().clone(), args.clone())
+            }
+            _ => unimplemented!(),
+        };
+
+    let verification_setting = BaseVerificationConfig {
+        target_template_name: main_template_name.to_string(),
+        prime: prime.clone(),
+        range: prime.clone(),
+        quick_mode: false,
+        heuristics_mode: false,
+        progress_interval: 10000,
+        template_param_names: template_param_names,
+        template_param_values: template_param_values,
+        check_output_tags: false,
+        max_counterexamples: 1,
+        input_shapes: FxHashMap::default(),
+        input_spec: InputSpec::default(),
+    };
+
+    // `in = 200` satisfies `out * 2 === in`, but the witness generator aborts on the assertion
+    let symbolic_trace = sexe.cur_state.symbolic_trace.clone();
+    let side_constraints = sexe.cur_state.side_constraints.clone();
+    let mut variables = extract_variables(&symbolic_trace);
+    variables.append(&mut extract_variables(&side_constraints));
+    let assignment: FxHashMap<SymbolicName, BigInt> = variables
+        .into_iter()
+        .map(|v| {
+            let value = if sexe.symbolic_library.id2name[&v.id] == "in" {
+                BigInt::from(200)
+            } else {
+                BigInt::from(100)
+            };
+            (v, value)
+        })
+        .collect();
+
+    let conc_setting = get_default_setting_for_concrete_execution(prime, false);
+    let mut conc_executor = SymbolicExecutor::new(&mut sexe.symbolic_library, &conc_setting);
+    let result = verify_assignment(
+        &mut conc_executor,
+        &symbolic_trace,
+        &side_constraints,
+        &assignment,
+        &verification_setting,
+    );
+    assert!(matches!(
+        result,
+        VerificationResult::UnderConstrained(UnderConstrainedType::UnexpectedInput(..))
+    ));
+    assert!(conc_executor.cur_state.is_failed);
+    assert!(conc_executor.violated_assertion.is_some());
+}
+
+#[test]
+fn test_parse_input_json_shapes() {
+    let path = "./tests/sample/test_partially_used_input.circom".to_string();
+    let prime = BigInt::from_str(
+        "21888242871839275222246405745257275088548364400416034343698204186575808495617",
+    )
+    .unwrap();
+
+    let (mut symbolic_library, program_archive) = prepare_symbolic_library(path, prime.clone());
+    let setting = get_default_setting_for_symbolic_execution(prime.clone(), false);
+
+    let mut sexe = SymbolicExecutor::new(&mut symbolic_library, &setting);
+    execute(&mut sexe, &program_archive);
+
+    let (main_template_name, template_param_names, template_param_values) =
+        match &program_archive.initial_template_call {
+            Expression::Call { id, args, .. } => {
+                let template = &program_archive.templates[id];
+                (id, template.get_name_of_params().clone(), args.clone())
+            }
+            _ => unimplemented!(),
+        };
+
+    let verification_setting = BaseVerificationConfig {
+        target_template_name: main_template_name.to_string(),
+        prime: prime.clone(),
+        range: prime.clone(),
+        quick_mode: false,
+        heuristics_mode: false,
+        progress_interval: 10000,
+        template_param_names: template_param_names,
+        template_param_values: template_param_values,
+        check_output_tags: false,
+        max_counterexamples: 1,
+        input_shapes: gather_input_shapes(
+            sexe.symbolic_library,
+            main_template_name,
+            &sexe.id2dimensions,
+        ),
+        input_spec: InputSpec::default(),
+    };
+
+    let input = parse_input_json(
+        &serde_json::json!({"in": [[1, 2], [3, 4], [5, 6]], "s": "7"}),
+        sexe.symbolic_library,
+        &verification_setting,
+    )
+    .unwrap();
+    assert_eq!(input.len(), 7);
+
+    // Every input whose value does not match `in[3][2]` or the scalar `s` is reported
+    let e = parse_input_json(
+        &serde_json::json!({"in": [[1, 2], [3], [5, 6, 7]], "s": [1]}),
+        sexe.symbolic_library,
+        &verification_setting,
+    )
+    .unwrap_err();
+    assert_eq!(
+        e,
+        "the input `in` does not match its declared shape in[3][2]: missing in[1][1]; extra in[2][2]\n\
+         the input `s` does not match its declared shape s: missing s; extra s[0]"
+    );
+
+    let e = parse_input_json(
+        &serde_json::json!({"in": [[1, 2], [3, 4], [5, 6]], "t": 0}),
+        sexe.symbolic_library,
+        &verification_setting,
+    )
+    .unwrap_err();
+    assert_eq!(
+        e,
+        "the input `s` is missing\n`t` is not an input of `PartiallyUsedInput`"
+    );
+}
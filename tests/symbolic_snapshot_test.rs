@@ -0,0 +1,91 @@
+mod utils;
+
+use std::env;
+use std::str::FromStr;
+
+use num_bigint_dig::BigInt;
+use rustc_hash::FxHashSet;
+
+use zkfuzz::executor::symbolic_execution::SymbolicExecutor;
+use zkfuzz::executor::symbolic_setting::get_default_setting_for_symbolic_execution;
+use zkfuzz::executor::symbolic_snapshot::SymbolicSnapshot;
+use zkfuzz::executor::symbolic_value::SymbolicLibrary;
+
+use crate::utils::{execute, prepare_symbolic_library};
+
+#[test]
+fn test_symbolic_snapshot_roundtrip() {
+    let prime = BigInt::from_str(
+        "21888242871839275222246405745257275088548364400416034343698204186575808495617",
+    )
+    .unwrap();
+
+    for path in [
+        "./tests/sample/test_lessthan.circom",
+        "./tests/sample/test_division_by_zero.circom",
+    ] {
+        let (mut symbolic_library, program_archive) =
+            prepare_symbolic_library(path.to_string(), prime.clone());
+        let setting = get_default_setting_for_symbolic_execution(prime.clone(), false);
+        let mut sexe = SymbolicExecutor::new(&mut symbolic_library, &setting);
+        execute(&mut sexe, &program_archive);
+
+        let snapshot_path =
+            env::temp_dir().join(format!("zkfuzz_snapshot_test_{}.bin", std::process::id()));
+        SymbolicSnapshot::capture(&sexe, "main")
+            .save(&snapshot_path)
+            .unwrap();
+        let snapshot = SymbolicSnapshot::load(&snapshot_path).unwrap();
+        std::fs::remove_file(&snapshot_path).unwrap();
+        assert_eq!(snapshot.main_template_name, "main");
+        assert_eq!(
+            snapshot.num_constraints(),
+            (
+                sexe.cur_state.symbolic_trace.len(),
+                sexe.cur_state.side_constraints.len()
+            )
+        );
+
+        let mut restored_library = SymbolicLibrary::default();
+        let restored = snapshot
+            .restore(&mut restored_library, &FxHashSet::default())
+            .unwrap();
+        assert_eq!(restored_library.name2id, sexe.symbolic_library.name2id);
+
+        let mut restored_sexe = SymbolicExecutor::new(&mut restored_library, &setting);
+        restored.apply_to(&mut restored_sexe);
+        assert_eq!(
+            sexe.cur_state.symbolic_trace,
+            restored_sexe.cur_state.symbolic_trace
+        );
+        assert_eq!(
+            sexe.cur_state.side_constraints,
+            restored_sexe.cur_state.side_constraints
+        );
+        assert_eq!(
+            sexe.cur_state.trace_provenance,
+            restored_sexe.cur_state.trace_provenance
+        );
+        assert_eq!(
+            sexe.cur_state.side_constraint_provenance,
+            restored_sexe.cur_state.side_constraint_provenance
+        );
+        assert_eq!(
+            sexe.cur_state.symbol_binding_map,
+            restored_sexe.cur_state.symbol_binding_map
+        );
+        assert_eq!(
+            sexe.cur_state.owner_name,
+            restored_sexe.cur_state.owner_name
+        );
+        assert_eq!(sexe.id2dimensions, restored_sexe.id2dimensions);
+        assert_eq!(sexe.component_templates, restored_sexe.component_templates);
+        assert_eq!(sexe.divisions, restored_sexe.divisions);
+        assert_eq!(sexe.symbolic_ternaries, restored_sexe.symbolic_ternaries);
+        assert_eq!(sexe.shape_mismatches, restored_sexe.shape_mismatches);
+        assert_eq!(
+            sexe.undetermined_dimensions,
+            restored_sexe.undetermined_dimensions
+        );
+    }
+}
@@ -2,11 +2,19 @@ use std::rc::Rc;
 use std::str::FromStr;
 
 use num_bigint_dig::BigInt;
+use rustc_hash::{FxHashMap, FxHashSet};
 
 use program_structure::ast::ExpressionInfixOpcode;
 
 use zkfuzz::executor::debug_ast::DebuggableExpressionInfixOpcode;
-use zkfuzz::executor::symbolic_value::{enumerate_array, evaluate_binary_op, SymbolicValue};
+use zkfuzz::executor::structural_hash::{
+    structural_hash, structurally_equal, Equivalence, StructuralKey,
+};
+use zkfuzz::executor::symbolic_interner::{interned_count, reset_interner, resolve};
+use zkfuzz::executor::symbolic_state::SymbolBindingMap;
+use zkfuzz::executor::symbolic_value::{
+    enumerate_array, evaluate_binary_op, OwnerName, SymbolicAccess, SymbolicName, SymbolicValue,
+};
 
 #[test]
 fn test_arithmetic_operations() {
@@ -291,3 +299,212 @@ fn test_enumerate_empty_array() {
 
     assert_eq!(result.len(), 0);
 }
+
+#[test]
+fn test_symbolic_name_interning() {
+    let owner = Rc::new(vec![OwnerName {
+        id: 0,
+        counter: 0,
+        access: None,
+    }]);
+    let element = |i: i32| {
+        SymbolicName::new(
+            1,
+            owner.clone(),
+            Some(vec![SymbolicAccess::ArrayAccess(
+                SymbolicValue::ConstantInt(BigInt::from(i)),
+            )]),
+        )
+    };
+
+    let first = element(0);
+    let second = element(1);
+    assert_eq!(first.symbol_id(), element(0).symbol_id());
+    assert_ne!(first.symbol_id(), second.symbol_id());
+    assert_eq!(*resolve(second.symbol_id()), second);
+
+    // Modifying a name invalidates its cached id.
+    let mut modified = first.clone();
+    modified.access = None;
+    modified.update_hash();
+    assert_eq!(
+        modified.symbol_id(),
+        SymbolicName::new(1, owner.clone(), None).symbol_id()
+    );
+
+    let mut map = SymbolBindingMap::default();
+    map.insert(&first, Rc::new(SymbolicValue::ConstantInt(BigInt::from(3))));
+    assert!(map.contains_key(&element(0)));
+    assert!(!map.contains_key(&second));
+    assert_eq!(
+        *map[&element(0)],
+        SymbolicValue::ConstantInt(BigInt::from(3))
+    );
+    assert_eq!(
+        map.iter()
+            .map(|(name, _)| (*name).clone())
+            .collect::<Vec<_>>(),
+        vec![first.clone()]
+    );
+    assert_eq!(
+        map.iter_ids().map(|(id, _)| id).collect::<Vec<_>>(),
+        vec![first.symbol_id()]
+    );
+
+    // Only the entries written after the fork are logged
+    let fork = map.begin_fork();
+    let mut branch = map.clone();
+    branch.insert(
+        &second,
+        Rc::new(SymbolicValue::ConstantInt(BigInt::from(4))),
+    );
+    assert_eq!(
+        branch.written_since(fork).collect::<Vec<_>>(),
+        vec![second.symbol_id()]
+    );
+    assert_eq!(map.written_since(fork).count(), 0);
+    map.end_fork();
+    map.insert(
+        &second,
+        Rc::new(SymbolicValue::ConstantInt(BigInt::from(4))),
+    );
+    assert_eq!(map.written_since(0).count(), 0);
+    assert_eq!(map, branch);
+}
+
+#[test]
+fn test_reset_interner() {
+    let owner = Rc::new(vec![OwnerName {
+        id: 0,
+        counter: 0,
+        access: None,
+    }]);
+    let first = SymbolicName::new(1, owner.clone(), None);
+    let second = SymbolicName::new(2, owner.clone(), None);
+    first.symbol_id();
+    second.symbol_id();
+    assert!(interned_count() >= 2);
+
+    reset_interner();
+    assert_eq!(interned_count(), 0);
+
+    // The ids cached before the reset are interned again
+    assert_eq!(second.symbol_id(), 0);
+    assert_eq!(*resolve(second.symbol_id()), second);
+    assert_eq!(first.symbol_id(), 1);
+    assert_eq!(*resolve(first.symbol_id()), first);
+    assert_eq!(interned_count(), 2);
+}
+
+#[test]
+fn test_structural_equivalence() {
+    // Two libraries numbering the same symbols differently
+    let lib_a = FxHashMap::from_iter([
+        (0, "main".to_string()),
+        (1, "a".to_string()),
+        (2, "b".to_string()),
+    ]);
+    let lib_b = FxHashMap::from_iter([
+        (5, "main".to_string()),
+        (7, "b".to_string()),
+        (9, "a".to_string()),
+    ]);
+    let var = |id: usize, owner_id: usize, counter: usize| {
+        Rc::new(SymbolicValue::Variable(SymbolicName::new(
+            id,
+            Rc::new(vec![OwnerName {
+                id: owner_id,
+                counter,
+                access: None,
+            }]),
+            None,
+        )))
+    };
+    let op = |lhs, op, rhs| SymbolicValue::BinaryOp(lhs, DebuggableExpressionInfixOpcode(op), rhs);
+    let one = Rc::new(SymbolicValue::ConstantInt(BigInt::from(1)));
+
+    // `a * b + 1` in both libraries, and `1 + b * a` in the second one
+    let x = op(
+        Rc::new(op(var(1, 0, 0), ExpressionInfixOpcode::Mul, var(2, 0, 0))),
+        ExpressionInfixOpcode::Add,
+        one.clone(),
+    );
+    let same_x = op(
+        Rc::new(op(var(9, 5, 0), ExpressionInfixOpcode::Mul, var(7, 5, 0))),
+        ExpressionInfixOpcode::Add,
+        one.clone(),
+    );
+    let reordered_x = op(
+        one.clone(),
+        ExpressionInfixOpcode::Add,
+        Rc::new(op(var(7, 5, 0), ExpressionInfixOpcode::Mul, var(9, 5, 0))),
+    );
+
+    let exact = Equivalence::default();
+    let unordered = Equivalence {
+        ignore_owner_counters: false,
+        ignore_operand_order: true,
+        ignore_owners: false,
+        ignore_array_indices: false,
+    };
+    assert!(structurally_equal(&x, &lib_a, &same_x, &lib_b, exact));
+    assert_eq!(
+        structural_hash(&x, &lib_a, exact),
+        structural_hash(&same_x, &lib_b, exact)
+    );
+    assert!(!structurally_equal(&x, &lib_a, &reordered_x, &lib_b, exact));
+    assert!(structurally_equal(
+        &x,
+        &lib_a,
+        &reordered_x,
+        &lib_b,
+        unordered
+    ));
+    assert_eq!(
+        structural_hash(&x, &lib_a, unordered),
+        structural_hash(&reordered_x, &lib_b, unordered)
+    );
+
+    // `-` is not commutative
+    let a_minus_b = op(var(1, 0, 0), ExpressionInfixOpcode::Sub, var(2, 0, 0));
+    let b_minus_a = op(var(2, 0, 0), ExpressionInfixOpcode::Sub, var(1, 0, 0));
+    assert!(!structurally_equal(
+        &a_minus_b, &lib_a, &b_minus_a, &lib_a, unordered
+    ));
+
+    // The variables of two calls of the same function differ only by the counter of their owner
+    let first_call = var(1, 0, 0);
+    let second_call = var(1, 0, 1);
+    assert!(!structurally_equal(
+        &first_call,
+        &lib_a,
+        &second_call,
+        &lib_a,
+        exact
+    ));
+    let ignore_counters = Equivalence {
+        ignore_owner_counters: true,
+        ignore_operand_order: false,
+        ignore_owners: false,
+        ignore_array_indices: false,
+    };
+    assert!(structurally_equal(
+        &first_call,
+        &lib_a,
+        &second_call,
+        &lib_a,
+        ignore_counters
+    ));
+
+    // Deduplication with structural keys
+    let mut keys = FxHashSet::default();
+    let reordered_in_a = op(
+        one.clone(),
+        ExpressionInfixOpcode::Add,
+        Rc::new(op(var(2, 0, 0), ExpressionInfixOpcode::Mul, var(1, 0, 0))),
+    );
+    for value in [&x, &reordered_in_a, &a_minus_b, &b_minus_a] {
+        keys.insert(StructuralKey::new(value, &lib_a, unordered));
+    }
+    assert_eq!(keys.len(), 3);
+}
@@ -0,0 +1,127 @@
+mod utils;
+
+use std::env;
+use std::rc::Rc;
+use std::str::FromStr;
+
+use num_bigint_dig::BigInt;
+use serde_json::Value;
+
+use zkfuzz::executor::symbolic_execution::SymbolicExecutor;
+use zkfuzz::executor::symbolic_setting::get_default_setting_for_symbolic_execution;
+use zkfuzz::executor::trace_log::{read_trace_log, reconstruct_state, TraceLog};
+
+use crate::utils::{execute, prepare_symbolic_library};
+
+#[test]
+fn test_trace_jsonl() {
+    let path = "./tests/sample/test_vuln_iszero.circom";
+    let prime = BigInt::from_str(
+        "21888242871839275222246405745257275088548364400416034343698204186575808495617",
+    )
+    .unwrap();
+
+    let (mut symbolic_library, program_archive) =
+        prepare_symbolic_library(path.to_string(), prime.clone());
+    let trace_path = env::temp_dir().join(format!(
+        "zkfuzz_trace_log_test_{}.jsonl",
+        std::process::id()
+    ));
+    let mut setting = get_default_setting_for_symbolic_execution(prime, false);
+    setting.trace_log = Some(Rc::new(TraceLog::create(&trace_path).unwrap()));
+
+    let mut sexe = SymbolicExecutor::new(&mut symbolic_library, &setting);
+    execute(&mut sexe, &program_archive);
+    setting.trace_log.as_ref().unwrap().flush().unwrap();
+
+    let events: Vec<Value> = std::fs::read_to_string(&trace_path)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    std::fs::remove_file(&trace_path).unwrap();
+
+    let count = |event: &str, store: Option<&str>| {
+        events
+            .iter()
+            .filter(|e| e["event"] == event && store.map_or(true, |s| e["store"] == s))
+            .count()
+    };
+    assert!(count("statement", None) > 0);
+    assert_eq!(
+        count("constraint", Some("trace")),
+        sexe.cur_state.symbolic_trace.len()
+    );
+    assert_eq!(
+        count("constraint", Some("side")),
+        sexe.cur_state.side_constraints.len()
+    );
+
+    // The execution starts with a statement of the main template
+    assert_eq!(events[0]["event"], "statement");
+    assert_eq!(events[0]["owner"], "main");
+    assert_eq!(events[0]["template"], "VulnerableIsZero");
+    assert!(events
+        .iter()
+        .filter(|e| e["event"] == "constraint")
+        .all(|e| !e["constraint"].as_str().unwrap().contains('\x1b')));
+}
+
+#[test]
+fn test_reconstruct_state() {
+    let path = "./tests/sample/test_vuln_iszero.circom";
+    let prime = BigInt::from_str(
+        "21888242871839275222246405745257275088548364400416034343698204186575808495617",
+    )
+    .unwrap();
+
+    let (mut symbolic_library, program_archive) =
+        prepare_symbolic_library(path.to_string(), prime.clone());
+    let trace_path = env::temp_dir().join(format!(
+        "zkfuzz_trace_state_test_{}.jsonl",
+        std::process::id()
+    ));
+    let mut setting = get_default_setting_for_symbolic_execution(prime, false);
+    setting.trace_log = Some(Rc::new(TraceLog::create(&trace_path).unwrap()));
+
+    let mut sexe = SymbolicExecutor::new(&mut symbolic_library, &setting);
+    execute(&mut sexe, &program_archive);
+    sexe.log_bindings();
+    setting.trace_log.as_ref().unwrap().flush().unwrap();
+
+    let events = read_trace_log(&trace_path).unwrap();
+    std::fs::remove_file(&trace_path).unwrap();
+
+    // The steps number the events in order
+    assert!(events
+        .iter()
+        .enumerate()
+        .all(|(i, e)| e["step"].as_u64() == Some(i as u64)));
+
+    // The state before the first constraint binds `inv` but has no constraint yet
+    let first_constraint = events
+        .iter()
+        .position(|e| e["event"] == "constraint")
+        .unwrap();
+    let state = reconstruct_state(&events, first_constraint - 1);
+    assert!(state.bindings.contains_key("main.inv"));
+    assert!(state.trace_constraints.is_empty());
+    assert!(state.side_constraints.is_empty());
+    assert_eq!(state.owner_stack, vec!["main".to_string()]);
+
+    // The final state has every constraint and binding of the execution
+    let state = reconstruct_state(&events, events.len());
+    assert_eq!(
+        state.trace_constraints.len(),
+        sexe.cur_state.symbolic_trace.len()
+    );
+    assert_eq!(
+        state.side_constraints.len(),
+        sexe.cur_state.side_constraints.len()
+    );
+    assert_eq!(
+        state.bindings.len(),
+        sexe.cur_state.symbol_binding_map.len()
+    );
+    assert_eq!(state.statement.unwrap()["template"], "VulnerableIsZero");
+}
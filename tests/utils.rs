@@ -1,5 +1,7 @@
 const VERSION: &'static str = env!("CARGO_PKG_VERSION");
 
+use std::rc::Rc;
+
 use num_bigint_dig::BigInt;
 use rustc_hash::{FxHashMap, FxHashSet};
 
@@ -9,6 +11,7 @@ use program_structure::program_archive::ProgramArchive;
 
 use zkfuzz::executor::symbolic_execution::SymbolicExecutor;
 use zkfuzz::executor::symbolic_value::{OwnerName, SymbolicLibrary};
+use zkfuzz::executor::template_semantics::LessThanSemantics;
 use zkfuzz::type_analysis_user::analyse_project;
 
 pub fn parse_project(initial_file: String, prime: BigInt) -> Result<ProgramArchive, ()> {
@@ -38,7 +41,9 @@ pub fn prepare_symbolic_library(
         id2name: FxHashMap::default(),
         function_library: FxHashMap::default(),
         function_counter: FxHashMap::default(),
+        template_semantics: Vec::new(),
     };
+    symbolic_library.register_template_semantics(Rc::new(LessThanSemantics));
 
     let whitelist = FxHashSet::default();
 
@@ -49,7 +54,7 @@ pub fn prepare_symbolic_library(
             &body.clone(),
             v.get_name_of_params(),
             &whitelist,
-            false,
+            v.is_custom_gate(),
         );
 
         println!(
@@ -99,6 +104,7 @@ pub fn execute(sexe: &mut SymbolicExecutor, program_archive: &ProgramArchive) {
                 .body
                 .clone();
             sexe.execute(&body, 0);
+            sexe.record_incomplete_components();
         }
         _ => {
             panic!("Cannot Find Main Call");
@@ -0,0 +1,108 @@
+mod utils;
+
+use std::str::FromStr;
+
+use num_bigint_dig::BigInt;
+use rustc_hash::FxHashMap;
+
+use zkfuzz::executor::symbolic_execution::SymbolicExecutor;
+use zkfuzz::executor::symbolic_setting::{
+    get_default_setting_for_concrete_execution, get_default_setting_for_symbolic_execution,
+};
+use zkfuzz::mutator::input_spec::InputSpec;
+use zkfuzz::mutator::utils::BaseVerificationConfig;
+use zkfuzz::mutator::violation_density::{
+    estimate_violation_density, DensitySampling, ViolationDensity,
+};
+
+use crate::utils::{execute, prepare_symbolic_library};
+
+#[test]
+fn test_violation_density_confidence_interval() {
+    let density = ViolationDensity {
+        sampling: DensitySampling::Uniform,
+        num_samples: 1000,
+        num_accepted: 243,
+        num_violations: 117,
+    };
+    let (lower, upper) = density.confidence_interval();
+    assert!((density.density() - 0.117).abs() < 1e-12);
+    assert!((lower - 0.098528).abs() < 1e-5);
+    assert!((upper - 0.138403).abs() < 1e-5);
+
+    // The interval is not degenerate when no sample is a violation
+    let density = ViolationDensity {
+        sampling: DensitySampling::Uniform,
+        num_samples: 100,
+        num_accepted: 0,
+        num_violations: 0,
+    };
+    let (lower, upper) = density.confidence_interval();
+    assert!(lower < 1e-12);
+    assert!(upper > 0.03 && upper < 0.04);
+    assert_eq!(
+        DensitySampling::from_str("ranges"),
+        Ok(DensitySampling::Ranges)
+    );
+    assert!(DensitySampling::from_str("gaussian").is_err());
+}
+
+#[test]
+fn test_estimate_violation_density() {
+    let prime = BigInt::from_str(
+        "21888242871839275222246405745257275088548364400416034343698204186575808495617",
+    )
+    .unwrap();
+    let path = "./tests/sample/test_violation_density.circom".to_string();
+    let (mut symbolic_library, program_archive) = prepare_symbolic_library(path, prime.clone());
+    let setting = get_default_setting_for_symbolic_execution(prime.clone(), false);
+    let mut sexe = SymbolicExecutor::new(&mut symbolic_library, &setting);
+    execute(&mut sexe, &program_archive);
+
+    let base_config = BaseVerificationConfig {
+        target_template_name: "LooseIsZero".to_string(),
+        prime: prime.clone(),
+        range: prime.clone(),
+        quick_mode: false,
+        heuristics_mode: false,
+        progress_interval: 10000,
+        template_param_names: Vec::new(),
+        template_param_values: Vec::new(),
+        check_output_tags: false,
+        max_counterexamples: 1,
+        input_shapes: FxHashMap::default(),
+        input_spec: InputSpec::default(),
+    };
+    let symbolic_trace = sexe.cur_state.symbolic_trace.clone();
+    let side_constraints = sexe.cur_state.side_constraints.clone();
+    let conc_setting = get_default_setting_for_concrete_execution(prime, false);
+    let mut conc_executor = SymbolicExecutor::new(&mut sexe.symbolic_library, &conc_setting);
+
+    // `out` is a bit, so every sample is accepted and about half of them are wrong
+    let density = estimate_violation_density(
+        &mut conc_executor,
+        &symbolic_trace,
+        &side_constraints,
+        &base_config,
+        DensitySampling::Ranges,
+        200,
+        1,
+    );
+    assert_eq!(density.num_accepted, 200);
+    assert!(density.num_violations > 50 && density.num_violations < 150);
+    let (lower, upper) = density.confidence_interval();
+    assert!(lower < density.density() && density.density() < upper);
+
+    // A value of the whole field is almost never a bit
+    let density = estimate_violation_density(
+        &mut conc_executor,
+        &symbolic_trace,
+        &side_constraints,
+        &base_config,
+        DensitySampling::Uniform,
+        50,
+        1,
+    );
+    assert_eq!(density.num_accepted, 0);
+    assert_eq!(density.num_violations, 0);
+}